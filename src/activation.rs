@@ -0,0 +1,47 @@
+//! Activation sources — the ways a recording can be started and stopped
+//! from outside the window.
+//!
+//! The record button and the `record`/`stop` D-Bus actions are the
+//! controller's own entry points. Everything else (hardware buttons, the
+//! wake word, the phone remote) is an [`ActivationSource`]: it watches for
+//! its trigger on a background thread and sends an [`Activation`] down a
+//! channel. The UI polls every running source the same way and runs each
+//! activation as the `record` or `stop` action, so a new trigger only needs
+//! a source implementation and one call where it is switched on.
+
+use std::sync::mpsc;
+
+/// What a trigger asks for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    Start,
+    Stop,
+    Toggle,
+}
+
+impl Activation {
+    /// Whether to start (`Some(true)`) or stop (`Some(false)`) recording,
+    /// given whether one is running; `None` to ignore the activation.
+    pub fn resolve(self, recording: bool) -> Option<bool> {
+        match self {
+            Self::Start => (!recording).then_some(true),
+            Self::Stop => recording.then_some(false),
+            Self::Toggle => Some(!recording),
+        }
+    }
+}
+
+/// A trigger mechanism that delivers activations from the background.
+pub trait ActivationSource {
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+
+    /// Start watching for the trigger. Activations arrive on the returned
+    /// channel, which disconnects once the source stops; dropping the
+    /// source stops it.
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String>;
+
+    /// Called with the app state (`idle`, `recording`, `processing` or
+    /// `speaking`) while the source runs, for sources that show it.
+    fn state_changed(&self, _state: &'static str) {}
+}
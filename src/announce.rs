@@ -0,0 +1,69 @@
+//! Screen reader announcements — speak what was just copied or typed.
+//!
+//! The transcript is handed to speech-dispatcher with `spd-say`, the speech
+//! service Orca itself talks through, at its `message` priority so it queues
+//! behind whatever the screen reader is saying instead of cutting it off.
+//! Long transcripts are shortened to [`MAX_CHARS`] characters.
+
+/// Longest transcript read out in full; longer ones end with "and more".
+pub const MAX_CHARS: usize = 300;
+
+/// The announcement for a finished dictation: `live` dictations were typed
+/// into the focused application, the others copied to the clipboard.
+pub fn message(text: &str, live: bool) -> String {
+    let verb = if live { "Typed" } else { "Copied" };
+    let text = text.trim();
+    if text.is_empty() {
+        return format!("{verb} nothing");
+    }
+    let mut chars = text.chars();
+    let head: String = chars.by_ref().take(MAX_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{verb}: {}, and more", head.trim_end())
+    } else {
+        format!("{verb}: {head}")
+    }
+}
+
+/// Whether announcements can be spoken (`spd-say` is installed).
+#[cfg(target_os = "linux")]
+pub fn available() -> bool {
+    std::process::Command::new("spd-say")
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// speech-dispatcher is Linux-only.
+#[cfg(not(target_os = "linux"))]
+pub fn available() -> bool {
+    false
+}
+
+/// Speak `message` without waiting for it to finish.
+#[cfg(target_os = "linux")]
+pub fn announce(message: &str) -> Result<(), String> {
+    let mut child = std::process::Command::new("spd-say")
+        .args([
+            "--priority",
+            "message",
+            "--application-name",
+            "WhisperCrabs",
+        ])
+        .arg(message)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("spd-say failed: {e}"))?;
+    // Reap it in the background so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// speech-dispatcher is Linux-only.
+#[cfg(not(target_os = "linux"))]
+pub fn announce(_message: &str) -> Result<(), String> {
+    Err("Screen reader announcements need Linux".into())
+}
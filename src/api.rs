@@ -1,13 +1,158 @@
 use reqwest::multipart;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-/// Send WAV audio to an OpenAI-compatible transcription endpoint and return the text.
+use crate::local_stt::{Segment, Transcript};
+
+/// Recordings at least this long are spooled to a temporary file and
+/// streamed from disk, instead of being held (and copied for a retry) in
+/// memory during the upload.
+pub const SPOOL_MIN_SECS: f64 = 5.0 * 60.0;
+
+/// The audio of a transcription request: an in-memory WAV, or a WAV file
+/// that is streamed into the multipart body.
+pub enum AudioUpload {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+    /// A temporary copy of a long take, removed once the upload is done.
+    Spooled(SpoolFile),
+}
+
+impl AudioUpload {
+    /// Wrap a fresh recording, spooling takes of [`SPOOL_MIN_SECS`] or more to
+    /// a temporary file. Falls back to memory if the file can't be written.
+    pub fn from_recording(wav: Vec<u8>) -> Self {
+        let long = crate::audio::wav_duration_secs(&wav).is_some_and(|s| s >= SPOOL_MIN_SECS);
+        if !long {
+            return Self::Bytes(wav);
+        }
+        match SpoolFile::write(&std::env::temp_dir(), &wav) {
+            Ok(file) => Self::Spooled(file),
+            Err(e) => {
+                tracing::warn!("Spooling upload failed, sending from memory: {e}");
+                Self::Bytes(wav)
+            }
+        }
+    }
+
+    /// The file the audio is streamed from, if it is not in memory.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Bytes(_) => None,
+            Self::File(path) => Some(path),
+            Self::Spooled(file) => Some(file.path()),
+        }
+    }
+}
+
+/// Optional parts of a transcription request, filled in only for backends
+/// that take them (see [`crate::capabilities`]).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RequestOptions {
+    /// Spoken language (ISO 639-1); `None` leaves detection to the server.
+    pub language: Option<String>,
+    /// Text that steers spelling and vocabulary.
+    pub prompt: Option<String>,
+    /// Words whose recognition is boosted, one `keywords` field each.
+    pub keywords: Vec<String>,
+    /// Ask for `verbose_json`, with segments and the detected language.
+    pub timestamps: bool,
+    /// Refuse larger uploads here instead of waiting for the server's 413.
+    pub max_upload_bytes: Option<u64>,
+}
+
+/// Size of `audio` in bytes.
+fn upload_size(audio: &AudioUpload) -> Result<u64, String> {
+    match audio {
+        AudioUpload::Bytes(wav) => Ok(wav.len() as u64),
+        _ => {
+            let path = audio.path().expect("file-backed upload");
+            std::fs::metadata(path)
+                .map(|m| m.len())
+                .map_err(|e| format!("Can't read {}: {e}", path.display()))
+        }
+    }
+}
+
+/// Why an upload of `size` bytes can't be sent, if it is over `max`.
+pub fn check_upload_size(size: u64, max: Option<u64>) -> Result<(), String> {
+    const MB: u64 = 1024 * 1024;
+    match max {
+        Some(max) if size > max => Err(format!(
+            "Recording too long for this provider ({} MB, the limit is {} MB)",
+            size.div_ceil(MB),
+            max / MB
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// A temporary WAV file (owner-only on Unix), deleted when dropped.
+pub struct SpoolFile(PathBuf);
+
+impl SpoolFile {
+    pub fn write(dir: &Path, wav: &[u8]) -> Result<Self, String> {
+        use std::io::Write;
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let path = dir.join(format!(
+            "whispercrabs-upload-{}-{stamp}.wav",
+            std::process::id()
+        ));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = Self(path);
+        options
+            .open(&file.0)
+            .and_then(|mut f| f.write_all(wav))
+            .map_err(|e| format!("Can't write {}: {e}", file.0.display()))?;
+        Ok(file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Join an API base URL and endpoint path, normalising slashes on both sides.
+/// An empty path falls back to the OpenAI-compatible `/audio/transcriptions`.
+pub fn transcription_url(base_url: &str, path: &str) -> String {
+    let path = path.trim().trim_start_matches('/');
+    let path = if path.is_empty() {
+        crate::config::DEFAULT_TRANSCRIPTION_PATH.trim_start_matches('/')
+    } else {
+        path
+    };
+    format!("{}/{}", base_url.trim_end_matches('/'), path)
+}
+
+/// Send WAV audio to an OpenAI-compatible transcription endpoint and return
+/// the text, plus the spoken language when the server reports it.
+///
+/// `path` is appended to `base_url` (e.g. `/audio/transcriptions`, or
+/// `/inference` for a bare whisper.cpp server). With `options.timestamps`,
+/// `verbose_json` is requested for its segments and `language` field; models
+/// that reject it anyway (400/422) are asked again for plain `json`.
+/// File-backed `audio` is streamed from disk (re-read for the retry), so
+/// memory stays flat for long uploads.
 pub async fn transcribe(
     base_url: &str,
+    path: &str,
     api_key: &str,
     model: &str,
-    wav_data: Vec<u8>,
-) -> Result<String, String> {
+    audio: AudioUpload,
+    options: &RequestOptions,
+) -> Result<Transcript, String> {
     // Validate URL scheme — reject file://, ftp://, etc.
     if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
         return Err("Invalid API URL: only http:// and https:// are allowed".into());
@@ -19,20 +164,13 @@ pub async fn transcribe(
         && !base_url.starts_with("http://127.0.0.1")
         && !base_url.starts_with("http://[::1]")
     {
-        eprintln!("WARNING: API endpoint uses unencrypted HTTP for a remote host");
+        tracing::warn!("WARNING: API endpoint uses unencrypted HTTP for a remote host");
     }
 
-    let url = format!("{}/audio/transcriptions", base_url.trim_end_matches('/'));
+    let url = transcription_url(base_url, path);
 
-    let file_part = multipart::Part::bytes(wav_data)
-        .file_name("audio.wav")
-        .mime_str("audio/wav")
-        .map_err(|e| format!("Multipart error: {e}"))?;
-
-    let form = multipart::Form::new()
-        .text("model", model.to_string())
-        .text("response_format", "json")
-        .part("file", file_part);
+    // Local-only privacy mode: audio must never leave the machine
+    crate::policy::ensure_network_allowed(&url)?;
 
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(120))
@@ -40,13 +178,19 @@ pub async fn transcribe(
         .build()
         .map_err(|e| format!("HTTP client error: {e}"))?;
 
-    let resp = client
-        .post(&url)
-        .bearer_auth(api_key)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {e}"))?;
+    check_upload_size(upload_size(&audio)?, options.max_upload_bytes)?;
+
+    let post =
+        |format: &'static str| post_audio(&client, &url, api_key, model, &audio, options, format);
+    let mut resp = if options.timestamps {
+        post("verbose_json").await?
+    } else {
+        post("json").await?
+    };
+    if options.timestamps && matches!(resp.status().as_u16(), 400 | 422) {
+        dbg_log!("[API] verbose_json rejected, retrying with json");
+        resp = post("json").await?;
+    }
 
     if !resp.status().is_success() {
         let status = resp.status();
@@ -59,8 +203,91 @@ pub async fn transcribe(
         .await
         .map_err(|e| format!("JSON parse error: {e}"))?;
 
-    json["text"]
+    parse_response(&json)
+}
+
+async fn post_audio(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: &str,
+    model: &str,
+    audio: &AudioUpload,
+    options: &RequestOptions,
+    response_format: &str,
+) -> Result<reqwest::Response, String> {
+    let file_part = match audio {
+        AudioUpload::Bytes(wav_data) => multipart::Part::bytes(wav_data.clone()),
+        _ => {
+            let path = audio.path().expect("file-backed upload");
+            multipart::Part::file(path)
+                .await
+                .map_err(|e| format!("Can't read {}: {e}", path.display()))?
+        }
+    };
+    let file_part = file_part
+        .file_name("audio.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| format!("Multipart error: {e}"))?;
+
+    let mut form = multipart::Form::new()
+        .text("model", model.to_string())
+        .text("response_format", response_format.to_string());
+    if let Some(language) = &options.language {
+        form = form.text("language", language.clone());
+    }
+    if let Some(prompt) = &options.prompt {
+        form = form.text("prompt", prompt.clone());
+    }
+    for keyword in &options.keywords {
+        form = form.text("keywords", keyword.clone());
+    }
+    let form = form.part("file", file_part);
+
+    client
+        .post(url)
+        .bearer_auth(api_key)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {e}"))
+}
+
+/// Extract the text, timed segments and (normalised) detected language from
+/// a `json` or `verbose_json` transcription response.
+pub fn parse_response(json: &serde_json::Value) -> Result<Transcript, String> {
+    let text = json["text"]
         .as_str()
         .map(|s| s.to_string())
-        .ok_or_else(|| format!("No 'text' field in response: {json}"))
+        .ok_or_else(|| format!("No 'text' field in response: {json}"))?;
+    let language = json["language"]
+        .as_str()
+        .and_then(crate::config::normalize_language);
+    // verbose_json segments: times in seconds, confidence as a mean
+    // log-probability, discounted by the chance the segment wasn't speech
+    let segments = json["segments"]
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .filter_map(|seg| {
+                    let text = seg["text"].as_str()?.trim();
+                    (!text.is_empty()).then(|| Segment {
+                        start_ms: (seg["start"].as_f64().unwrap_or(0.0) * 1000.0) as i64,
+                        end_ms: (seg["end"].as_f64().unwrap_or(0.0) * 1000.0) as i64,
+                        text: text.to_string(),
+                        confidence: crate::confidence::with_no_speech(
+                            seg["avg_logprob"].as_f64().map(|lp| lp.exp() as f32),
+                            seg["no_speech_prob"].as_f64().map(|p| p as f32),
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Transcript {
+        text,
+        words: Vec::new(),
+        segments,
+        language,
+    })
 }
@@ -0,0 +1,338 @@
+//! Light and dark styling that follows the desktop.
+//!
+//! The XDG desktop portal publishes the preferred color scheme as
+//! `org.freedesktop.appearance` / `color-scheme`. It is read at startup and
+//! on every change: a light preference gives the overlay's status label a
+//! light look that stays readable on light wallpapers, and dialogs follow
+//! with GTK's matching theme variant. **Theme** in the menu can pin light
+//! or dark instead of following the desktop.
+//!
+//! The record button comes in three sizes, or an explicit pixel size, for
+//! screens where the default 72 px is too large or too small.
+//!
+//! An optional user style sheet (`~/.config/whispercrabs/style.css`) is
+//! loaded above the built-in one, so the button, status label and their
+//! animations can be restyled without recompiling. It is reloaded whenever
+//! the file changes.
+
+use std::path::{Path, PathBuf};
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+const PORTAL_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const NAMESPACE: &str = "org.freedesktop.appearance";
+const KEY: &str = "color-scheme";
+
+/// The desktop's preferred color scheme.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// No preference: the overlay keeps its default dark label.
+    #[default]
+    Default,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    /// From the portal value: 1 prefers dark, 2 prefers light, 0 (or
+    /// anything unknown) has no preference.
+    pub fn from_portal(value: u32) -> Self {
+        match value {
+            1 => Self::Dark,
+            2 => Self::Light,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// Settings key of the theme chosen in the menu.
+pub const THEME_SETTING: &str = "theme";
+
+/// File name of the user style sheet, in the config directory.
+pub const USER_CSS_FILE: &str = "style.css";
+
+/// Light or dark look chosen in the menu, over the desktop's preference.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "system" => Some(Self::System),
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::System => "Follow Desktop",
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+
+    /// The scheme to style with while the desktop prefers `desktop`.
+    pub fn scheme(self, desktop: ColorScheme) -> ColorScheme {
+        match self {
+            Self::System => desktop,
+            Self::Light => ColorScheme::Light,
+            Self::Dark => ColorScheme::Dark,
+        }
+    }
+}
+
+/// Settings key of the widget size chosen in the menu.
+pub const SIZE_SETTING: &str = "widget_size";
+
+/// Smallest and largest button accepted as an explicit pixel size.
+const MIN_BUTTON_PX: u32 = 40;
+const MAX_BUTTON_PX: u32 = 200;
+
+/// Size of the record button, and with it the icon and the window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WidgetSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    /// Button width and height in pixels (`WIDGET_SIZE=88`).
+    Pixels(u32),
+}
+
+impl WidgetSize {
+    /// The sizes offered in the menu.
+    pub const PRESETS: [Self; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    /// `small`, `medium`, `large`, or a button size in pixels (`88` or
+    /// `88px`) between 40 and 200.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.as_str() {
+            "small" => Some(Self::Small),
+            "medium" => Some(Self::Medium),
+            "large" => Some(Self::Large),
+            _ => s
+                .strip_suffix("px")
+                .unwrap_or(&s)
+                .trim()
+                .parse()
+                .ok()
+                .filter(|px| (MIN_BUTTON_PX..=MAX_BUTTON_PX).contains(px))
+                .map(Self::Pixels),
+        }
+    }
+
+    pub fn as_str(self) -> String {
+        match self {
+            Self::Small => "small".into(),
+            Self::Medium => "medium".into(),
+            Self::Large => "large".into(),
+            Self::Pixels(px) => px.to_string(),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Small => "Small".into(),
+            Self::Medium => "Medium".into(),
+            Self::Large => "Large".into(),
+            Self::Pixels(px) => format!("{px} px"),
+        }
+    }
+
+    /// Width and height of the record button.
+    pub fn button_px(self) -> u32 {
+        match self {
+            Self::Small => 56,
+            Self::Medium => 72,
+            Self::Large => 96,
+            Self::Pixels(px) => px,
+        }
+    }
+
+    /// The microphone icon, in the built-in proportion (32 on 72).
+    pub fn icon_px(self) -> u32 {
+        self.button_px() * 4 / 9
+    }
+
+    /// Default window size around the button and the status label.
+    pub fn window_size(self) -> (i32, i32) {
+        let button = self.button_px() as i32;
+        (button + 16, button + 28)
+    }
+
+    /// Style for the button at this size, loaded over the built-in one.
+    pub fn css(self) -> String {
+        let (button, icon) = (self.button_px(), self.icon_px());
+        format!(
+            ".mic-btn {{ min-width: {button}px; min-height: {button}px; \
+             font-size: {icon}px; -gtk-icon-size: {icon}px; }}"
+        )
+    }
+}
+
+/// Size the record button and its icon, restyling it through `provider`.
+pub fn apply_size(
+    provider: &gtk4::CssProvider,
+    button: &gtk4::Button,
+    icon: &gtk4::Image,
+    size: WidgetSize,
+) {
+    provider.load_from_data(&size.css());
+    let button_px = size.button_px() as i32;
+    button.set_size_request(button_px, button_px);
+    icon.set_pixel_size(size.icon_px() as i32);
+}
+
+/// The user style sheet: `style.css` next to `config.toml` in the user's
+/// config directory (`~/.config/whispercrabs/style.css` on Linux).
+pub fn user_css_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("whispercrabs").join(USER_CSS_FILE))
+}
+
+/// Load the style sheet at `path` over the built-in styles, and again each
+/// time it is created, changed or removed. Mistakes in it are reported on
+/// stderr with their line; the rest of the sheet still applies.
+pub fn watch_user_css(display: &gtk4::gdk::Display, path: &Path) {
+    let provider = gtk4::CssProvider::new();
+    provider.connect_parsing_error(|_, section, error| {
+        tracing::error!("style.css: {}: {error}", section.to_str());
+    });
+    gtk4::style_context_add_provider_for_display(
+        display,
+        &provider,
+        gtk4::STYLE_PROVIDER_PRIORITY_USER,
+    );
+    let load = {
+        let (provider, path) = (provider.clone(), path.to_path_buf());
+        move || {
+            // A missing file clears earlier overrides
+            let css = std::fs::read_to_string(&path).unwrap_or_default();
+            provider.load_from_data(&css);
+        }
+    };
+    load();
+    match gio::File::for_path(path)
+        .monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+    {
+        Ok(monitor) => {
+            monitor.connect_changed(move |_, _, _, event| {
+                if matches!(
+                    event,
+                    gio::FileMonitorEvent::ChangesDoneHint
+                        | gio::FileMonitorEvent::Created
+                        | gio::FileMonitorEvent::Deleted
+                ) {
+                    load();
+                }
+            });
+            // Watched for as long as the app runs
+            std::mem::forget(monitor);
+        }
+        Err(e) => dbg_log!("[appearance] can't watch {}: {e}", path.display()),
+    }
+}
+
+/// The number in a portal reply, unwrapping the variants it is boxed in
+/// (`ReadOne` boxes it once, the older `Read` twice).
+pub fn portal_value(value: &glib::Variant) -> Option<u32> {
+    let mut value = value.clone();
+    loop {
+        if let Some(number) = value.get::<u32>() {
+            return Some(number);
+        }
+        value = value.as_variant()?;
+    }
+}
+
+/// Call `apply` with the current color scheme and again whenever it
+/// changes. Without a settings portal `apply` is never called.
+pub fn watch(apply: impl Fn(ColorScheme) + 'static) {
+    let proxy = match gio::DBusProxy::for_bus_sync(
+        gio::BusType::Session,
+        gio::DBusProxyFlags::NONE,
+        None,
+        PORTAL_NAME,
+        PORTAL_PATH,
+        SETTINGS_INTERFACE,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            dbg_log!("[appearance] no settings portal: {e}");
+            return;
+        }
+    };
+    let read = |method: &str| {
+        proxy.call_sync(
+            method,
+            Some(&(NAMESPACE, KEY).to_variant()),
+            gio::DBusCallFlags::NONE,
+            1000,
+            gio::Cancellable::NONE,
+        )
+    };
+    match read("ReadOne").or_else(|_| read("Read")) {
+        Ok(reply) => {
+            if let Some(value) = portal_value(&reply.child_value(0)) {
+                apply(ColorScheme::from_portal(value));
+            }
+        }
+        Err(e) => dbg_log!("[appearance] color-scheme unavailable: {e}"),
+    }
+    proxy.connect_local("g-signal", false, move |args| {
+        let signal = args.get(2)?.get::<String>().ok()?;
+        let params = args.get(3)?.get::<glib::Variant>().ok()?;
+        if signal == "SettingChanged"
+            && let Some((namespace, key, value)) = params.get::<(String, String, glib::Variant)>()
+            && namespace == NAMESPACE
+            && key == KEY
+            && let Some(value) = portal_value(&value)
+        {
+            apply(ColorScheme::from_portal(value));
+        }
+        None
+    });
+    // Watched for as long as the app runs
+    std::mem::forget(proxy);
+}
+
+/// Style the overlay `window` and, through GTK's settings, every dialog for
+/// `scheme`.
+pub fn apply(window: &gtk4::ApplicationWindow, scheme: ColorScheme) {
+    // `.light` and `.dark` on the overlay, for the built-in and user styles
+    for (class, on) in [
+        ("light", scheme == ColorScheme::Light),
+        ("dark", scheme == ColorScheme::Dark),
+    ] {
+        if on {
+            window.add_css_class(class);
+        } else {
+            window.remove_css_class(class);
+        }
+    }
+    if let Some(settings) = gtk4::Settings::default() {
+        settings.set_gtk_application_prefer_dark_theme(scheme == ColorScheme::Dark);
+    }
+}
@@ -2,9 +2,15 @@ use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::io::Cursor;
 use std::sync::{Arc, Mutex};
 
-/// Captures audio from the default input device into an in-memory buffer.
+/// How often [`watch_input`] checks for an input device.
+pub const INPUT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Captures audio from the chosen (or default) input device into an
+/// in-memory buffer. The device is only opened when a recording starts, so
+/// the recorder can be created (and the app started) without a microphone.
 pub struct Recorder {
     samples: Arc<Mutex<Vec<f32>>>,
+    device: Option<String>,
     stream: Option<cpal::Stream>,
     sample_rate: u32,
     channels: u16,
@@ -12,12 +18,12 @@ pub struct Recorder {
 
 impl Recorder {
     pub fn new() -> Self {
-        let (sample_rate, channels) = Self::probe_input().unwrap_or((44100, 1));
         Self {
             samples: Arc::new(Mutex::new(Vec::new())),
+            device: None,
             stream: None,
-            sample_rate,
-            channels,
+            sample_rate: 44100,
+            channels: 1,
         }
     }
 
@@ -33,15 +39,34 @@ impl Recorder {
         Some((config.sample_rate().0, config.channels()))
     }
 
+    /// Record from the input device named `name`, or from the system
+    /// default with `None`.
+    pub fn set_device(&mut self, name: Option<String>) {
+        self.device = name;
+    }
+
     pub fn start(&mut self) -> Result<(), String> {
         let host = cpal::default_host();
-        let device = host
-            .default_input_device()
+        let chosen = self.device.as_deref().and_then(|name| {
+            let found = host
+                .input_devices()
+                .ok()?
+                .find(|d| d.name().is_ok_and(|n| n == name));
+            if found.is_none() {
+                tracing::warn!("Input device '{name}' not found, using the default");
+            }
+            found
+        });
+        let device = chosen
+            .or_else(|| host.default_input_device())
             .ok_or("No input device available")?;
 
         let config = device
             .default_input_config()
             .map_err(|e| format!("No input config: {e}"))?;
+        // The device may differ from the one present at the last recording
+        self.sample_rate = config.sample_rate().0;
+        self.channels = config.channels();
 
         let samples = Arc::clone(&self.samples);
         samples
@@ -49,7 +74,7 @@ impl Recorder {
             .expect("audio sample buffer poisoned")
             .clear();
 
-        let err_fn = |err| eprintln!("Audio stream error: {err}");
+        let err_fn = |err| tracing::error!("Audio stream error: {err}");
 
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => {
@@ -126,41 +151,232 @@ impl Recorder {
             return Err("No audio recorded".into());
         }
 
-        // Convert to mono if multi-channel
-        let mono: Vec<f32> = if self.channels > 1 {
-            samples
-                .chunks(self.channels as usize)
-                .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
-                .collect()
-        } else {
-            samples.clone()
+        encode_wav(&downmix(&samples, self.channels), self.sample_rate)
+    }
+
+    /// Hand over the audio captured so far as a WAV and keep recording
+    /// into an emptied buffer, without a gap (see [`crate::split`]). Not for
+    /// use alongside [`Self::live_audio`], which reads the buffer by index.
+    pub fn take_segment(&self) -> Result<Vec<u8>, String> {
+        let taken: Vec<f32> = {
+            let mut samples = self
+                .samples
+                .lock()
+                .map_err(|_| "Audio buffer lock poisoned".to_string())?;
+            // Whole frames only; a partial frame stays for the next segment
+            let end = frame_aligned(samples.len(), self.channels);
+            samples.drain(..end).collect()
         };
+        if taken.is_empty() {
+            return Err("No audio recorded".into());
+        }
+        encode_wav(&downmix(&taken, self.channels), self.sample_rate)
+    }
 
-        // Encode as WAV
-        let mut buf = Cursor::new(Vec::new());
-        let spec = hound::WavSpec {
-            channels: 1,
+    /// Shared view of the capture buffer, readable while recording.
+    pub fn live_audio(&self) -> LiveAudio {
+        LiveAudio {
+            samples: Arc::clone(&self.samples),
+            channels: self.channels,
             sample_rate: self.sample_rate,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        let mut writer =
-            hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
-
-        for &sample in &mono {
-            let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-            writer
-                .write_sample(s)
-                .map_err(|e| format!("WAV sample error: {e}"))?;
         }
-        writer
-            .finalize()
-            .map_err(|e| format!("WAV finalize error: {e}"))?;
+    }
 
-        Ok(buf.into_inner())
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
     }
+}
+
+/// The longest prefix of `len` interleaved samples that holds whole frames.
+pub fn frame_aligned(len: usize, channels: u16) -> usize {
+    let channels = channels.max(1) as usize;
+    len - len % channels
+}
+
+/// Names of the input devices present right now, for choosing one.
+pub fn input_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    let mut names: Vec<String> = host
+        .input_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default();
+    names.dedup();
+    names
+}
 
+/// Watch for the input device to disappear or come back. The returned
+/// channel receives the current availability right away and then every
+/// change, checked each [`INPUT_POLL_INTERVAL`].
+pub fn watch_input() -> std::sync::mpsc::Receiver<bool> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let available = Recorder::input_available();
+            if last != Some(available) {
+                last = Some(available);
+                if tx.send(available).is_err() {
+                    return;
+                }
+            }
+            std::thread::sleep(INPUT_POLL_INTERVAL);
+        }
+    });
+    rx
+}
+
+/// A recording in progress, for decoding audio before it has stopped.
+#[derive(Clone)]
+pub struct LiveAudio {
+    samples: Arc<Mutex<Vec<f32>>>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl LiveAudio {
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
+
+    /// Mono samples captured so far, starting at mono frame `start`.
+    pub fn mono_from(&self, start: usize) -> Vec<f32> {
+        let samples = self.samples.lock().expect("audio sample buffer poisoned");
+        let channels = self.channels.max(1) as usize;
+        let from = (start * channels).min(samples.len());
+        // Only whole frames: the capture thread may be mid-frame
+        let to = from + (samples.len() - from) / channels * channels;
+        downmix(&samples[from..to], self.channels)
+    }
+}
+
+/// Average interleaved channels into mono.
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels > 1 {
+        samples
+            .chunks(channels as usize)
+            .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+            .collect()
+    } else {
+        samples.to_vec()
+    }
+}
+
+/// Encode mono samples as a 16-bit PCM WAV buffer.
+pub fn encode_wav(mono: &[f32], sample_rate: u32) -> Result<Vec<u8>, String> {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer =
+        hound::WavWriter::new(&mut buf, spec).map_err(|e| format!("WAV write error: {e}"))?;
+
+    for &sample in mono {
+        let s = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer
+            .write_sample(s)
+            .map_err(|e| format!("WAV sample error: {e}"))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("WAV finalize error: {e}"))?;
+
+    Ok(buf.into_inner())
+}
+
+/// Read a WAV file of any sample format as mono samples, and its sample
+/// rate.
+pub fn read_wav(data: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    let mut reader =
+        hound::WavReader::new(Cursor::new(data)).map_err(|e| format!("Not a WAV file: {e}"))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| format!("WAV read error: {e}"))?;
+    if samples.is_empty() {
+        return Err("The WAV file holds no audio".into());
+    }
+    Ok((downmix(&samples, spec.channels.max(1)), spec.sample_rate))
+}
+
+/// Video containers whose sound track [`decode_file`] extracts with ffmpeg.
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "mov", "avi"];
+
+/// Whether `path` looks like a video [`decode_file`] can take the sound of.
+pub fn is_video_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)))
+}
+
+/// Read an audio file (see [`crate::decode`]) or the sound of a video as a
+/// 16 kHz mono 16-bit WAV, and that sample rate.
+pub fn decode_file(path: &std::path::Path) -> Result<(Vec<u8>, u32), String> {
+    let samples = if is_video_file(path) {
+        extract_audio(path)?
+    } else {
+        crate::decode::decode_file(path)?
+    };
+    let wav = encode_wav(&samples, crate::decode::SAMPLE_RATE)?;
+    Ok((wav, crate::decode::SAMPLE_RATE))
+}
+
+/// The sound track of the video at `path`, through an `ffmpeg` on PATH.
+/// Raw samples, since a WAV written to a pipe lacks its chunk sizes.
+fn extract_audio(path: &std::path::Path) -> Result<Vec<f32>, String> {
+    let rate = crate::decode::SAMPLE_RATE.to_string();
+    let output = std::process::Command::new("ffmpeg")
+        .args(["-nostdin", "-v", "error", "-i"])
+        .arg(path)
+        .args(["-vn", "-ac", "1", "-ar", &rate, "-f", "s16le", "-"])
+        .output()
+        .map_err(|e| format!("Videos need ffmpeg installed ({e})"))?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        return Err(match message.lines().last() {
+            Some(line) => format!("ffmpeg: {line}"),
+            None => "ffmpeg failed to read the video".to_string(),
+        });
+    }
+    let samples: Vec<f32> = output
+        .stdout
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+        .collect();
+    if samples.is_empty() {
+        return Err("The video has no sound track".into());
+    }
+    Ok(samples)
+}
+
+/// RMS level in dBFS of an in-memory 16-bit WAV buffer, see
+/// [`crate::coaching::level_db`].
+pub fn wav_level_db(wav_data: &[u8]) -> Option<f64> {
+    let mut reader = hound::WavReader::new(Cursor::new(wav_data)).ok()?;
+    let samples: Vec<f32> = reader
+        .samples::<i16>()
+        .filter_map(|s| s.ok())
+        .map(|s| s as f32 / i16::MAX as f32)
+        .collect();
+    crate::coaching::level_db(&samples)
+}
+
+/// Duration in seconds of an in-memory WAV buffer.
+pub fn wav_duration_secs(wav_data: &[u8]) -> Option<f64> {
+    let reader = hound::WavReader::new(Cursor::new(wav_data)).ok()?;
+    let rate = reader.spec().sample_rate;
+    if rate == 0 {
+        return None;
+    }
+    Some(reader.duration() as f64 / rate as f64)
 }
@@ -0,0 +1,103 @@
+//! Automatic provider selection.
+//!
+//! The `auto` transcription mode periodically probes every configured API
+//! backend and routes each dictation to the fastest healthy one. A backend
+//! that is down (connection error, timeout or 5xx) is skipped, so an outage
+//! at one provider falls through to the next.
+//!
+//! To avoid flapping between backends with similar latency, the current
+//! choice is kept until another backend is clearly faster (see [`STICKINESS`]).
+
+use std::time::Duration;
+
+/// Provider id of the automatic mode (`transcription-mode` action value).
+pub const AUTO_PROVIDER: &str = "auto";
+
+/// Seconds between probe rounds while auto mode is active.
+pub const PROBE_INTERVAL_SECS: u32 = 60;
+
+/// Probes slower than this count as unhealthy.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The current backend is kept unless it is this many times slower than the
+/// fastest healthy one.
+pub const STICKINESS: f64 = 1.5;
+
+/// A configured API backend that auto mode may route to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backend {
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub path: String,
+}
+
+/// Outcome of probing one backend; `latency` is `None` when unhealthy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeResult {
+    pub provider: String,
+    pub latency: Option<Duration>,
+}
+
+/// Measure the round-trip time of a cheap `GET {base_url}/models` request.
+///
+/// Any non-5xx response counts as healthy (a 401 still proves the endpoint
+/// is up). Returns `None` on connection errors, timeouts, server errors, or
+/// when the request is blocked by local-only mode.
+pub fn probe(base_url: &str, api_key: Option<&str>) -> Option<Duration> {
+    let url = format!("{}/models", base_url.trim_end_matches('/'));
+    crate::policy::ensure_network_allowed(&url).ok()?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .connect_timeout(PROBE_TIMEOUT)
+        .build()
+        .ok()?;
+    let mut req = client.get(&url);
+    if let Some(key) = api_key {
+        req = req.bearer_auth(key);
+    }
+    let started = std::time::Instant::now();
+    let resp = req.send().ok()?;
+    if resp.status().is_server_error() {
+        return None;
+    }
+    Some(started.elapsed())
+}
+
+/// Probe every backend in turn.
+pub fn probe_all(backends: &[Backend]) -> Vec<ProbeResult> {
+    backends
+        .iter()
+        .map(|b| {
+            let latency = probe(&b.base_url, b.api_key.as_deref());
+            dbg_log!("[AUTO] probe {} -> {latency:?}", b.provider);
+            ProbeResult {
+                provider: b.provider.clone(),
+                latency,
+            }
+        })
+        .collect()
+}
+
+/// Pick the provider to route to from a probe round.
+///
+/// Returns the fastest healthy provider, unless `current` is still healthy
+/// and within [`STICKINESS`] of it. Returns `None` when nothing is healthy.
+pub fn pick<'a>(results: &'a [ProbeResult], current: Option<&str>) -> Option<&'a str> {
+    let fastest = results
+        .iter()
+        .filter_map(|r| r.latency.map(|l| (r.provider.as_str(), l)))
+        .min_by_key(|(_, l)| *l)?;
+
+    if let Some(current) = current
+        && let Some((provider, latency)) = results
+            .iter()
+            .find(|r| r.provider == current)
+            .and_then(|r| r.latency.map(|l| (r.provider.as_str(), l)))
+        && latency.as_secs_f64() <= fastest.1.as_secs_f64() * STICKINESS
+    {
+        return Some(provider);
+    }
+    Some(fastest.0)
+}
@@ -0,0 +1,138 @@
+//! Auto-save — every transcript also written to a folder as Markdown.
+//!
+//! With a folder chosen under **Save Transcripts to Folder...**, each new
+//! History entry is also written there, either appended to one file per day
+//! (`2026-03-09.md`, a `## 14:05` heading above each transcript) or as a file
+//! of its own (`2026-03-09 14-05-31.md`). Pointed at an Obsidian or Logseq
+//! vault, that makes WhisperCrabs a voice journal. What is written follows
+//! "Store in History": a preview when only previews are kept, and nothing
+//! when it is off.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+use crate::db::Db;
+
+/// Settings key of the folder.
+pub const DIR_SETTING: &str = "autosave_dir";
+
+/// Settings key of the [`Layout`] (`daily` or `each`).
+pub const LAYOUT_SETTING: &str = "autosave_layout";
+
+/// How transcripts are spread over files.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Layout {
+    /// Appended to one Markdown file per day.
+    #[default]
+    Daily,
+    /// A Markdown file per transcript.
+    Each,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 2] = [Layout::Daily, Layout::Each];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "daily" => Some(Self::Daily),
+            "each" => Some(Self::Each),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Each => "each",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Daily => "One File per Day",
+            Self::Each => "One File per Transcript",
+        }
+    }
+}
+
+/// The folder and layout to save to, `None` when auto-save is off.
+pub fn stored(d: &Db) -> Option<(PathBuf, Layout)> {
+    let dir = d.get_setting(DIR_SETTING).ok().flatten()?;
+    let layout = d
+        .get_setting(LAYOUT_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|s| Layout::parse(&s))
+        .unwrap_or_default();
+    Some((PathBuf::from(dir), layout))
+}
+
+/// Name of the file a transcript from `time` goes to (before any `-2`
+/// suffix for a second transcript within the same second).
+pub fn file_name(layout: Layout, time: NaiveDateTime) -> String {
+    match layout {
+        Layout::Daily => format!("{}.md", time.format("%Y-%m-%d")),
+        Layout::Each => format!("{}.md", time.format("%Y-%m-%d %H-%M-%S")),
+    }
+}
+
+/// What is appended to a daily file for one transcript: a heading with the
+/// time, then the text, separated from the previous entry by a blank line.
+pub fn daily_entry(time: NaiveDateTime, text: &str, first: bool) -> String {
+    let separator = if first { "" } else { "\n" };
+    format!(
+        "{separator}## {}\n\n{}\n",
+        time.format("%H:%M"),
+        text.trim()
+    )
+}
+
+/// Write `text`, transcribed at `time`, into `dir`; the file written.
+pub fn write(
+    dir: &Path,
+    layout: Layout,
+    time: NaiveDateTime,
+    text: &str,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Transcript folder: {e}"))?;
+    let name = file_name(layout, time);
+    match layout {
+        Layout::Daily => {
+            let path = dir.join(name);
+            let first = std::fs::metadata(&path).map_or(true, |m| m.len() == 0);
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut f| f.write_all(daily_entry(time, text, first).as_bytes()))
+                .map_err(|e| format!("Writing {}: {e}", path.display()))?;
+            Ok(path)
+        }
+        Layout::Each => {
+            let stem = name.trim_end_matches(".md");
+            for n in 1.. {
+                let path = match n {
+                    1 => dir.join(&name),
+                    n => dir.join(format!("{stem}-{n}.md")),
+                };
+                let created = std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path);
+                match created {
+                    Ok(mut f) => {
+                        return f
+                            .write_all(format!("{}\n", text.trim()).as_bytes())
+                            .map(|()| path.clone())
+                            .map_err(|e| format!("Writing {}: {e}", path.display()));
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                    Err(e) => return Err(format!("Writing {}: {e}", path.display())),
+                }
+            }
+            unreachable!("file name suffixes ran out")
+        }
+    }
+}
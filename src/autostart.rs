@@ -0,0 +1,99 @@
+//! Autostart — start WhisperCrabs with the desktop session.
+//!
+//! **Start at Login** writes an XDG autostart entry,
+//! `~/.config/autostart/dev.whispercrabs.app.desktop`, which GNOME, KDE,
+//! XFCE and most other sessions run at login; unticking it deletes the file.
+//! The entry launches with `--hidden`, so the button isn't shown and
+//! WhisperCrabs waits in the tray, or for a global shortcut, without a
+//! window flashing up on login. The file is the setting: removing it by
+//! hand, or from the desktop's startup settings, turns autostart off too.
+
+use std::path::{Path, PathBuf};
+
+/// Name of the autostart entry, after the application id.
+pub const FILE_NAME: &str = "dev.whispercrabs.app.desktop";
+
+/// Launch flag that starts without showing the floating button.
+pub const HIDDEN_FLAG: &str = "--hidden";
+
+/// Where the autostart entry goes.
+pub fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("autostart").join(FILE_NAME))
+}
+
+/// Whether the autostart entry exists.
+pub fn is_enabled() -> bool {
+    path().is_some_and(|p| p.exists())
+}
+
+/// The autostart entry starting `exe` hidden.
+pub fn entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=WhisperCrabs\n\
+         Comment=Voice-to-text at a shortcut\n\
+         Exec={} {HIDDEN_FLAG}\n\
+         Icon=audio-input-microphone\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exec_arg(&exe.to_string_lossy())
+    )
+}
+
+/// `arg` quoted for an `Exec` line when it needs it (Desktop Entry
+/// Specification, "The Exec key").
+pub fn exec_arg(arg: &str) -> String {
+    const RESERVED: &str = "\"'\\><~|&;$*?#()`%";
+    let plain = !arg.is_empty()
+        && !arg
+            .chars()
+            .any(|c| c.is_whitespace() || RESERVED.contains(c));
+    if plain {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The program to autostart: the AppImage when running from one (its
+/// mount point changes every run), else this executable.
+fn executable() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| format!("Can't find the executable: {e}"))
+}
+
+/// Write the autostart entry.
+pub fn enable() -> Result<(), String> {
+    let path = path().ok_or("No config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    std::fs::write(&path, entry(&executable()?)).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Delete the autostart entry, if there is one.
+pub fn disable() -> Result<(), String> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("{}: {e}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
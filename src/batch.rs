@@ -0,0 +1,159 @@
+//! Batch transcription — many audio files in one go.
+//!
+//! **Transcribe Files...** in the menu and `whispercrabs batch` take audio
+//! files (see [`crate::decode`]) or folders of them and run each through the
+//! selected provider in turn. Every transcript is written next to its file
+//! — `talk.mp3` gets `talk.txt`, plus `talk.srt` when the provider reported
+//! timed segments — and logged into History like a dictation.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+
+use crate::config::Config;
+use crate::db::{Db, HistoryMode};
+use crate::decode::is_audio_file;
+use crate::local_stt::Segment;
+use crate::transcription::{EngineCache, Route};
+
+/// The audio files `inputs` name: files as given, folders replaced by the
+/// audio files directly inside them in name order, each file once.
+pub fn collect(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(input)
+                .map_err(|e| format!("{}: {e}", input.display()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.is_file() && is_audio_file(p))
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else if is_audio_file(input) {
+            files.push(input.clone());
+        } else {
+            return Err(format!("{}: not a supported audio file", input.display()));
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|f| seen.insert(f.clone()));
+    if files.is_empty() {
+        return Err("No audio files found".into());
+    }
+    Ok(files)
+}
+
+/// Where the transcript of `audio` is written.
+pub fn text_path(audio: &Path) -> PathBuf {
+    audio.with_extension("txt")
+}
+
+/// Where the subtitles of `audio` are written.
+pub fn subtitle_path(audio: &Path) -> PathBuf {
+    audio.with_extension("srt")
+}
+
+/// What a batch sends where.
+pub struct Job {
+    pub files: Vec<PathBuf>,
+    pub route: Route,
+    pub language: String,
+    pub translate: bool,
+    /// Custom terms steering recognition, see [`crate::vocabulary`].
+    pub vocabulary: Vec<String>,
+}
+
+/// A transcribed file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub text: String,
+    /// Whether a `.srt` was written too.
+    pub subtitles: bool,
+}
+
+/// Progress of a running batch; files are numbered from 0.
+pub enum Progress {
+    Started(usize),
+    Done(usize, Result<Outcome, String>),
+    /// All files are done, or the batch was cancelled.
+    Finished,
+}
+
+/// Transcribe file `index` of `job`, write its `.txt` (and `.srt`) and log
+/// it into History.
+pub fn transcribe_file(
+    config: &Config,
+    db: &Mutex<Db>,
+    engines: &EngineCache,
+    job: &Job,
+    index: usize,
+) -> Result<Outcome, String> {
+    let path = &job.files[index];
+    let (wav, sample_rate) = crate::audio::decode_file(path)?;
+    let transcript = crate::transcription::transcribe(
+        config,
+        &job.route,
+        engines,
+        wav,
+        sample_rate,
+        &job.language,
+        job.translate,
+        &job.vocabulary,
+    )?;
+    let d = db.lock().map_err(|e| e.to_string())?;
+    let language = transcript.language.as_deref();
+    let rules = d.accepted_replacements(language).unwrap_or_default();
+    let text = crate::corrections::apply(&transcript.text, &rules);
+    let segments: Vec<Segment> = transcript
+        .segments
+        .iter()
+        .map(|seg| Segment {
+            text: crate::corrections::apply(&seg.text, &rules),
+            ..seg.clone()
+        })
+        .collect();
+
+    let write = |target: PathBuf, contents: String| {
+        std::fs::write(&target, contents).map_err(|e| format!("{}: {e}", target.display()))
+    };
+    write(text_path(path), format!("{}\n", text.trim()))?;
+    let subtitles = !segments.is_empty();
+    if subtitles {
+        write(subtitle_path(path), crate::subtitles::to_srt(&segments))?;
+    }
+
+    let mode = crate::transcription::stored_history_mode(&d);
+    if let Some(id) = crate::transcription::store(&d, &text, language, mode)?
+        && mode == HistoryMode::Full
+        && subtitles
+        && let Err(e) = d.insert_segments(id, &segments)
+    {
+        tracing::error!("DB segments insert error: {e}");
+    }
+    Ok(Outcome { text, subtitles })
+}
+
+/// Run `job` on a worker thread, one file after the other, until done or
+/// `cancel` is set.
+pub fn spawn(
+    job: Job,
+    config: Arc<Config>,
+    db: Arc<Mutex<Db>>,
+    engines: Arc<EngineCache>,
+    cancel: Arc<AtomicBool>,
+) -> mpsc::Receiver<Progress> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for index in 0..job.files.len() {
+            if cancel.load(Ordering::Relaxed) || tx.send(Progress::Started(index)).is_err() {
+                break;
+            }
+            let result = transcribe_file(&config, &db, &engines, &job, index);
+            if tx.send(Progress::Done(index, result)).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(Progress::Finished);
+    });
+    rx
+}
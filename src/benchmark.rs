@@ -0,0 +1,321 @@
+//! Benchmark — one clip through every model and provider, side by side.
+//!
+//! **Benchmark...** in the right-click menu (or `whispercrabs benchmark`)
+//! transcribes the last kept recording, or a chosen audio file, with each
+//! downloaded local model and each configured API provider in turn. For
+//! each it reports the latency (model loading excluded), the speed relative
+//! to the clip's length and how far its transcript is from a reference: the
+//! provider currently selected in the app, unless another is picked. Word
+//! differences are listed so accuracy can be weighed against speed.
+//!
+//! API providers only get the clip when uploads to their host were allowed
+//! before, and not at all with "Local only" on.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Config};
+use crate::db::Db;
+use crate::transcription::{EngineCache, Route};
+
+/// A provider to run the clip through, or why it can't be.
+pub struct Candidate {
+    pub provider: String,
+    pub route: Result<Route, String>,
+}
+
+/// What one provider made of the clip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub provider: String,
+    /// Transcription time, without loading a local model.
+    pub latency: Duration,
+    pub text: Result<String, String>,
+}
+
+/// A word of a transcript compared with the reference.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change<'a> {
+    Same(&'a str),
+    /// In the reference only.
+    Removed(&'a str),
+    /// In the transcript only.
+    Added(&'a str),
+}
+
+/// Progress of a running benchmark, for the window.
+pub enum Progress {
+    Started(usize),
+    Done(usize, Outcome),
+    Finished,
+}
+
+/// Everything the clip can be run through: downloaded whisper and Vosk
+/// models, then configured API providers.
+pub fn candidates(d: &Db, config: &Config) -> Vec<Candidate> {
+    let mut providers: Vec<String> = config::downloaded_models(&config.models_dir)
+        .into_iter()
+        .map(|(m, _)| m.id.to_string())
+        .collect();
+    providers.extend(
+        config::downloaded_vosk_models(&config.models_dir)
+            .into_iter()
+            .map(|(m, _)| m.id.to_string()),
+    );
+    let mut candidates: Vec<Candidate> = providers
+        .into_iter()
+        .map(|provider| Candidate {
+            route: crate::transcription::route(d, config, &provider),
+            provider,
+        })
+        .collect();
+    let local_only = crate::transcription::local_only(d, config);
+    for backend in crate::transcription::api_backends(d, config) {
+        let host = crate::policy::url_host(&backend.base_url).to_string();
+        let route = if local_only && !crate::policy::is_local_url(&backend.base_url) {
+            Err("Local only is on".to_string())
+        } else if !crate::policy::is_local_url(&backend.base_url)
+            && !crate::transcription::cloud_consented(d, &host)
+        {
+            Err(format!("Uploads to {host} not allowed yet"))
+        } else {
+            Ok(Route::Api(backend.clone()))
+        };
+        candidates.push(Candidate {
+            provider: backend.provider,
+            route,
+        });
+    }
+    candidates
+}
+
+/// Keep the candidates named in `only` (all for an empty list), in the
+/// order given there.
+pub fn select(candidates: Vec<Candidate>, only: &[String]) -> Vec<Candidate> {
+    if only.is_empty() {
+        return candidates;
+    }
+    let mut candidates: Vec<Option<Candidate>> = candidates.into_iter().map(Some).collect();
+    only.iter()
+        .map(|id| {
+            candidates
+                .iter_mut()
+                .find(|c| c.as_ref().is_some_and(|c| &c.provider == id))
+                .and_then(Option::take)
+                .unwrap_or_else(|| Candidate {
+                    provider: id.clone(),
+                    route: Err("Not downloaded or not set up".into()),
+                })
+        })
+        .collect()
+}
+
+/// The newest kept recording that still exists, with its History entry id.
+pub fn last_recording(d: &Db) -> Option<(i64, PathBuf)> {
+    d.recordings()
+        .ok()?
+        .into_iter()
+        .map(|(id, path)| (id, PathBuf::from(path)))
+        .find(|(_, path)| path.is_file())
+}
+
+/// Run the clip through one candidate.
+pub fn run(
+    config: &Config,
+    engines: &EngineCache,
+    candidate: &Candidate,
+    wav: &[u8],
+    sample_rate: u32,
+    language: &str,
+    vocabulary: &[String],
+) -> Outcome {
+    let outcome = |latency, text| Outcome {
+        provider: candidate.provider.clone(),
+        latency,
+        text,
+    };
+    let route = match &candidate.route {
+        Ok(route) => route,
+        Err(e) => return outcome(Duration::ZERO, Err(e.clone())),
+    };
+    // Load the model first so its latency is the transcription's alone
+    if let Route::Local { path, .. } = route
+        && let Err(e) = engines.get(path)
+    {
+        return outcome(Duration::ZERO, Err(e));
+    }
+    let started = Instant::now();
+    let result = crate::transcription::transcribe(
+        config,
+        route,
+        engines,
+        wav.to_vec(),
+        sample_rate,
+        language,
+        false,
+        vocabulary,
+    );
+    outcome(started.elapsed(), result.map(|t| t.text.trim().to_string()))
+}
+
+/// Run the clip through `candidates` on a worker thread, one after the
+/// other.
+pub fn spawn(
+    config: std::sync::Arc<Config>,
+    candidates: Vec<Candidate>,
+    wav: Vec<u8>,
+    sample_rate: u32,
+    language: String,
+    vocabulary: Vec<String>,
+) -> mpsc::Receiver<Progress> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let engines = EngineCache::default();
+        for (index, candidate) in candidates.iter().enumerate() {
+            if tx.send(Progress::Started(index)).is_err() {
+                return;
+            }
+            let outcome = run(
+                &config,
+                &engines,
+                candidate,
+                &wav,
+                sample_rate,
+                &language,
+                &vocabulary,
+            );
+            if tx.send(Progress::Done(index, outcome)).is_err() {
+                return;
+            }
+        }
+        let _ = tx.send(Progress::Finished);
+    });
+    rx
+}
+
+/// The outcome to compare the others with: `preferred`'s when it
+/// succeeded, otherwise the first that did.
+pub fn reference<'a>(outcomes: &'a [Outcome], preferred: &str) -> Option<&'a Outcome> {
+    outcomes
+        .iter()
+        .find(|o| o.provider == preferred && o.text.is_ok())
+        .or_else(|| outcomes.iter().find(|o| o.text.is_ok()))
+}
+
+/// A word as compared: lowercase, without surrounding punctuation.
+fn normalized(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// The words of `text` against those of `reference`, in order, ignoring
+/// case and punctuation.
+pub fn word_diff<'a>(reference: &'a str, text: &'a str) -> Vec<Change<'a>> {
+    let a: Vec<&str> = reference.split_whitespace().collect();
+    let b: Vec<&str> = text.split_whitespace().collect();
+    let (na, nb): (Vec<String>, Vec<String>) = (
+        a.iter().map(|w| normalized(w)).collect(),
+        b.iter().map(|w| normalized(w)).collect(),
+    );
+    // Longest common subsequence, filled from the end
+    let mut lcs = vec![vec![0usize; nb.len() + 1]; na.len() + 1];
+    for i in (0..na.len()).rev() {
+        for j in (0..nb.len()).rev() {
+            lcs[i][j] = if na[i] == nb[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < a.len() && j < b.len() {
+        if na[i] == nb[j] {
+            changes.push(Change::Same(b[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            changes.push(Change::Removed(a[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(b[j]));
+            j += 1;
+        }
+    }
+    changes.extend(a[i..].iter().copied().map(Change::Removed));
+    changes.extend(b[j..].iter().copied().map(Change::Added));
+    changes
+}
+
+/// Word error rate of `text` against `reference`: substituted, missing
+/// and extra words over the reference's word count.
+pub fn word_error_rate(reference: &str, text: &str) -> f64 {
+    let a: Vec<String> = reference.split_whitespace().map(normalized).collect();
+    let b: Vec<String> = text.split_whitespace().map(normalized).collect();
+    if a.is_empty() {
+        return if b.is_empty() { 0.0 } else { 1.0 };
+    }
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, wa) in a.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, wb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(wa != wb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()] as f64 / a.len() as f64
+}
+
+/// The differences in plain text: `[-removed-]` and `{+added+}`.
+pub fn diff_text(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(|c| match c {
+            Change::Same(w) => w.to_string(),
+            Change::Removed(w) => format!("[-{w}-]"),
+            Change::Added(w) => format!("{{+{w}+}}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How many times faster than real time `latency` is for a clip of
+/// `audio_secs`.
+pub fn speed(audio_secs: f64, latency: Duration) -> Option<f64> {
+    let secs = latency.as_secs_f64();
+    (secs > 0.0 && audio_secs > 0.0).then(|| audio_secs / secs)
+}
+
+/// One line of the results table: provider, latency, speed and word error
+/// rate against the reference (or the error).
+pub fn summary_line(outcome: &Outcome, audio_secs: f64, reference: Option<&Outcome>) -> String {
+    let text = match &outcome.text {
+        Ok(text) => text,
+        Err(e) => return format!("{:<20} failed: {e}", outcome.provider),
+    };
+    let speed = speed(audio_secs, outcome.latency).map_or("-".to_string(), |s| format!("{s:.1}x"));
+    let wer = match reference {
+        Some(r) if r.provider == outcome.provider => "reference".to_string(),
+        Some(Outcome { text: Ok(r), .. }) => {
+            format!("{:.0}% WER", word_error_rate(r, text) * 100.0)
+        }
+        _ => "-".to_string(),
+    };
+    format!(
+        "{:<20} {:>8.2} s {:>8} {:>12}",
+        outcome.provider,
+        outcome.latency.as_secs_f64(),
+        speed,
+        wer
+    )
+}
+
+/// Where a clip named on the command line or picked in the window is
+/// decoded from.
+pub fn decode(path: &Path) -> Result<(Vec<u8>, u32), String> {
+    crate::audio::decode_file(path).map_err(|e| format!("{}: {e}", path.display()))
+}
@@ -0,0 +1,134 @@
+//! What each API backend accepts, so requests only carry what it takes.
+//!
+//! OpenAI-compatible servers differ in the optional parts of the API: some
+//! take a `language` hint, a `prompt` or keyword boosts, some return timed segments
+//! (`verbose_json`), some have the translation endpoint, and hosted ones cap
+//! the upload size. The registry below records this per provider; requests
+//! are built from it and the Preferences window disables the options a
+//! provider would ignore or reject, instead of finding out from a 400.
+//!
+//! Custom endpoints aren't known ahead of time. One on the standard
+//! `/audio/transcriptions` path is taken at its word as OpenAI-compatible;
+//! any other path (e.g. whisper.cpp's `/inference`) gets only the model and
+//! the audio.
+
+/// Version of the registry, bumped whenever an entry changes. It is logged
+/// with each request, so a server error can be traced to the table that let
+/// the parameter through.
+pub const REGISTRY_VERSION: u32 = 2;
+
+/// Upload limit of the hosted Whisper APIs (OpenAI, Groq's free tier).
+const HOSTED_MAX_UPLOAD: u64 = 25 * 1024 * 1024;
+
+/// Optional request features of one backend.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capabilities {
+    /// Takes a `language` hint (ISO 639-1) instead of detecting it.
+    pub language: bool,
+    /// Takes a `prompt` that steers spelling and vocabulary.
+    pub prompt: bool,
+    /// Takes `keywords` whose recognition is boosted (Deepgram-style), fed
+    /// from [`crate::vocabulary`].
+    pub keywords: bool,
+    /// Returns timed segments and the detected language (`verbose_json`).
+    pub timestamps: bool,
+    /// Has the `/audio/translations` endpoint (speech to English).
+    pub translation: bool,
+    /// Largest upload accepted, which caps the recording length (a minute
+    /// of 48 kHz audio is about 5.5 MB); `None` when there is no known limit.
+    pub max_upload_bytes: Option<u64>,
+}
+
+impl Capabilities {
+    /// The full OpenAI API, without an upload limit.
+    pub const OPENAI_COMPATIBLE: Self = Self {
+        language: true,
+        prompt: true,
+        keywords: false,
+        timestamps: true,
+        translation: true,
+        max_upload_bytes: None,
+    };
+
+    /// Model and audio only.
+    pub const MINIMAL: Self = Self {
+        language: false,
+        prompt: false,
+        keywords: false,
+        timestamps: false,
+        translation: false,
+        max_upload_bytes: None,
+    };
+
+    /// What the backend supports beyond plain transcription, for display.
+    pub fn summary(&self) -> String {
+        let features: Vec<&str> = [
+            (self.language, "language hint"),
+            (self.prompt, "prompt"),
+            (self.keywords, "keyword boosts"),
+            (self.timestamps, "timestamps"),
+            (self.translation, "translation"),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, name)| *name)
+        .collect();
+        let features = if features.is_empty() {
+            "Plain transcription only".to_string()
+        } else {
+            let mut text = features.join(", ");
+            text[..1].make_ascii_uppercase();
+            text
+        };
+        match self.max_upload_bytes {
+            Some(max) => format!("{features}; uploads up to {} MB", max / (1024 * 1024)),
+            None => features,
+        }
+    }
+}
+
+/// Capabilities of the built-in API presets, by preset id.
+pub const REGISTRY: &[(&str, Capabilities)] = &[
+    (
+        "groq",
+        Capabilities {
+            max_upload_bytes: Some(HOSTED_MAX_UPLOAD),
+            ..Capabilities::OPENAI_COMPATIBLE
+        },
+    ),
+    // Proxies OpenAI's whisper-1, which returns plain text only
+    (
+        "openrouter",
+        Capabilities {
+            language: true,
+            prompt: true,
+            keywords: false,
+            timestamps: false,
+            translation: false,
+            max_upload_bytes: Some(HOSTED_MAX_UPLOAD),
+        },
+    ),
+    ("ollama", Capabilities::MINIMAL),
+    ("lmstudio", Capabilities::MINIMAL),
+];
+
+/// Capabilities of API provider `provider`; `path` is the endpoint path of
+/// a custom provider (ignored for presets).
+pub fn for_provider(provider: &str, path: &str) -> Capabilities {
+    if let Some((_, caps)) = REGISTRY.iter().find(|(id, _)| *id == provider) {
+        return *caps;
+    }
+    let path = path.trim();
+    if path.is_empty() || path == crate::config::DEFAULT_TRANSCRIPTION_PATH {
+        Capabilities::OPENAI_COMPATIBLE
+    } else {
+        Capabilities::MINIMAL
+    }
+}
+
+/// The `language` to send: the chosen one when the backend takes a hint and
+/// a language is pinned (not auto-detect).
+pub fn language_hint<'a>(caps: &Capabilities, chosen: &'a str) -> Option<&'a str> {
+    (caps.language && chosen != crate::config::DEFAULT_STT_LANGUAGE && !chosen.is_empty())
+        .then_some(chosen)
+}
@@ -0,0 +1,195 @@
+//! Live captions — rolling transcriptions of whatever the computer is playing.
+//!
+//! The default sink's monitor source (everything sent to the speakers) is
+//! captured with `parec` at [`SAMPLE_RATE`]. A simple energy gate splits the
+//! stream into utterances: speech starts when a frame is louder than
+//! [`SPEECH_RMS`] and ends after [`END_SILENCE_MS`] of quiet, or once it has
+//! run for [`MAX_UTTERANCE_MS`]. Each utterance is decoded with the loaded
+//! local model on a second thread, so silence costs no decoding at all, and
+//! the text is delivered on a channel for the caption window to show.
+
+use crate::config::WhisperParams;
+use crate::local_stt::LocalEngine;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::mpsc;
+
+/// Capture rate; whisper decodes 16 kHz mono.
+pub const SAMPLE_RATE: u32 = 16_000;
+
+/// PulseAudio/PipeWire name of the default sink's monitor source.
+pub const MONITOR_SOURCE: &str = "@DEFAULT_MONITOR@";
+
+/// Frames of this length are gated as a whole.
+pub const FRAME_MS: u32 = 30;
+
+/// RMS level above which a frame counts as speech.
+pub const SPEECH_RMS: f32 = 0.01;
+
+/// Quiet this long ends an utterance.
+pub const END_SILENCE_MS: u32 = 600;
+
+/// Utterances are cut here even while speech continues, to keep captions flowing.
+pub const MAX_UTTERANCE_MS: u32 = 8_000;
+
+/// Utterances with less speech than this (clicks, short blips) are dropped.
+const MIN_SPEECH_MS: u32 = 300;
+
+/// Lines kept in the caption window.
+pub const MAX_LINES: usize = 4;
+
+/// Root-mean-square level of `samples`.
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Splits a stream of frames into speech utterances by energy.
+#[derive(Debug, Default)]
+pub struct SpeechGate {
+    utterance: Vec<f32>,
+    speech_ms: u32,
+    silent_ms: u32,
+}
+
+impl SpeechGate {
+    /// Feed one frame; returns a finished utterance when one just ended.
+    pub fn push(&mut self, frame: &[f32]) -> Option<Vec<f32>> {
+        let speech = rms(frame) >= SPEECH_RMS;
+        if self.utterance.is_empty() && !speech {
+            return None;
+        }
+        self.utterance.extend_from_slice(frame);
+        if speech {
+            self.speech_ms += ms_of(frame.len());
+            self.silent_ms = 0;
+        } else {
+            self.silent_ms += ms_of(frame.len());
+        }
+        if self.silent_ms >= END_SILENCE_MS || ms_of(self.utterance.len()) >= MAX_UTTERANCE_MS {
+            return self.take();
+        }
+        None
+    }
+
+    /// End the current utterance, e.g. when the stream closes.
+    pub fn flush(&mut self) -> Option<Vec<f32>> {
+        self.take()
+    }
+
+    fn take(&mut self) -> Option<Vec<f32>> {
+        let speech_ms = std::mem::take(&mut self.speech_ms);
+        self.silent_ms = 0;
+        let utterance = std::mem::take(&mut self.utterance);
+        (speech_ms >= MIN_SPEECH_MS).then_some(utterance)
+    }
+}
+
+fn ms_of(samples: usize) -> u32 {
+    (samples as u64 * 1000 / SAMPLE_RATE as u64) as u32
+}
+
+/// The last few caption lines, oldest first.
+#[derive(Debug, Default)]
+pub struct CaptionLines {
+    lines: std::collections::VecDeque<String>,
+}
+
+impl CaptionLines {
+    /// Add a decoded utterance; blank ones are ignored.
+    pub fn push(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(text.to_string());
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.iter().cloned().collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// A running caption capture. Dropping it stops `parec`, which ends both threads.
+pub struct CaptionSession {
+    child: Child,
+}
+
+impl CaptionSession {
+    /// Start capturing the monitor source and decoding it with `engine`.
+    /// Each caption is delivered on the returned channel, which disconnects
+    /// once the capture stops.
+    pub fn start(
+        engine: Arc<LocalEngine>,
+        language: String,
+        params: WhisperParams,
+    ) -> Result<(Self, mpsc::Receiver<String>), String> {
+        let mut child = Command::new("parec")
+            .arg(format!("--device={MONITOR_SOURCE}"))
+            .args(["--format=s16le", "--channels=1", "--raw"])
+            .arg(format!("--rate={SAMPLE_RATE}"))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("spawn parec: {e}"))?;
+        let mut stdout = child.stdout.take().ok_or("parec has no output")?;
+
+        let (utterance_tx, utterance_rx) = mpsc::channel::<Vec<f32>>();
+        let (tx, rx) = mpsc::channel();
+
+        // Reading never waits on decoding, so the pipe keeps draining during a decode
+        std::thread::spawn(move || {
+            let mut gate = SpeechGate::default();
+            let mut buf = vec![0u8; (SAMPLE_RATE * FRAME_MS / 1000) as usize * 2];
+            while stdout.read_exact(&mut buf).is_ok() {
+                let frame: Vec<f32> = buf
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+                    .collect();
+                if let Some(utterance) = gate.push(&frame)
+                    && utterance_tx.send(utterance).is_err()
+                {
+                    return;
+                }
+            }
+            if let Some(utterance) = gate.flush() {
+                let _ = utterance_tx.send(utterance);
+            }
+            dbg_log!("[CAPTIONS] capture stopped");
+        });
+
+        std::thread::spawn(move || {
+            for utterance in utterance_rx {
+                let text = crate::audio::encode_wav(&utterance, SAMPLE_RATE).and_then(|wav| {
+                    engine.transcribe_with_segments(&wav, SAMPLE_RATE, &language, &params, |_| {})
+                });
+                match text {
+                    Ok(t) => {
+                        dbg_log!("[CAPTIONS] {} ms -> {:?}", ms_of(utterance.len()), t.text);
+                        if tx.send(t.text).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => tracing::warn!("Caption decode failed: {e}"),
+                }
+            }
+        });
+
+        dbg_log!("[CAPTIONS] capturing {MONITOR_SOURCE}");
+        Ok((Self { child }, rx))
+    }
+}
+
+impl Drop for CaptionSession {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
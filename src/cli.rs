@@ -0,0 +1,515 @@
+//! Command-line mode — transcription and History without a window.
+//!
+//! `whispercrabs <command>` runs one command and exits without starting GTK,
+//! for scripts, SSH sessions and machines without a desktop:
+//!
+//! ```text
+//! whispercrabs transcribe meeting.wav --provider groq
+//! whispercrabs record --duration 10 --language de
+//! whispercrabs batch ~/Recordings --provider local-base
+//! whispercrabs history --limit 50 --json
+//! whispercrabs history --language de
+//! whispercrabs benchmark --providers local-base,groq
+//! ```
+//!
+//! Transcripts go to stdout and errors to stderr (exit code 1). Commands
+//! use the providers, keys, models and language set up in the app, through
+//! the same routing as the floating button (see [`crate::transcription`]).
+//! Only `batch` writes History (see [`crate::batch`]); `benchmark` compares
+//! providers on one clip (see [`crate::benchmark`]).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{self, Config};
+
+/// History entries `history` lists unless `--limit` says otherwise.
+pub const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Longest recording `record` makes.
+pub const MAX_RECORD_SECS: f64 = 60.0 * 60.0;
+
+pub const USAGE: &str = "\
+Usage: whispercrabs [command]
+
+Without a command, the floating button starts.
+
+Commands:
+  transcribe <file>       Transcribe an audio file and print the text
+  record --duration <s>   Record from the microphone, then print the text
+  batch <file|folder>...  Transcribe audio files into .txt/.srt files beside
+                          them and log them into History
+  history                 Print the newest History entries
+  benchmark [file]        Transcribe a clip (default: the last kept
+                          recording) with every downloaded model and
+                          configured provider, and compare the results
+  help                    Show this help
+
+Options of transcribe, record and batch:
+  --provider <id>         Provider to use, e.g. groq or local-base
+                          (default: the one selected in the app)
+  --language <code>       Spoken language, e.g. en, de or auto
+  --translate             Translate the speech into English
+
+Options of history:
+  --limit <n>             Number of entries (default: 20)
+  --json                  Print JSON instead of one line per entry
+  --language <code>       Only entries spoken in this language, e.g. de
+
+Options of benchmark:
+  --providers <ids>       Only these, separated by commas
+  --reference <id>        Compare with this one (default: the one
+                          selected in the app)
+  --language <code>       Spoken language, e.g. en, de or auto
+";
+
+/// How `transcribe`, `record` and `batch` transcribe.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SttOptions {
+    /// Provider id; the app's current provider for `None`.
+    pub provider: Option<String>,
+    /// Language code or `auto`; the app's language for `None`.
+    pub language: Option<String>,
+    pub translate: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Transcribe {
+        file: PathBuf,
+        options: SttOptions,
+    },
+    Record {
+        duration: Duration,
+        options: SttOptions,
+    },
+    Batch {
+        inputs: Vec<PathBuf>,
+        options: SttOptions,
+    },
+    History {
+        limit: usize,
+        json: bool,
+        /// Only entries tagged with this language (ISO 639-1).
+        language: Option<String>,
+    },
+    Benchmark {
+        /// Clip to transcribe; the last kept recording for `None`.
+        file: Option<PathBuf>,
+        /// Providers to compare; all available for an empty list.
+        providers: Vec<String>,
+        /// Provider the others are compared with; the app's for `None`.
+        reference: Option<String>,
+        /// Language code or `auto`; the app's language for `None`.
+        language: Option<String>,
+    },
+    Help,
+}
+
+/// The command `args` (without the program name) ask for: `None` when they
+/// don't start with a command, so the app starts as usual.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (name, rest) = args.split_first()?;
+    let command = match name.as_str() {
+        "transcribe" => parse_transcribe(rest),
+        "record" => parse_record(rest),
+        "batch" => parse_batch(rest),
+        "history" => parse_history(rest),
+        "benchmark" => parse_benchmark(rest),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn parse_transcribe(args: &[String]) -> Result<Command, String> {
+    let mut file = None;
+    let mut options = SttOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !stt_option(arg, &mut args, &mut options)? {
+            if arg.starts_with("--") || file.is_some() {
+                return Err(format!("transcribe: unexpected argument \"{arg}\""));
+            }
+            file = Some(PathBuf::from(arg));
+        }
+    }
+    let file = file.ok_or("transcribe: which file? See whispercrabs help")?;
+    Ok(Command::Transcribe { file, options })
+}
+
+fn parse_record(args: &[String]) -> Result<Command, String> {
+    let mut duration = None;
+    let mut options = SttOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if stt_option(arg, &mut args, &mut options)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--duration" => {
+                let value = value_of(arg, &mut args)?;
+                let secs = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|s| *s > 0.0 && *s <= MAX_RECORD_SECS)
+                    .ok_or_else(|| {
+                        format!("record: --duration takes seconds, up to {MAX_RECORD_SECS}")
+                    })?;
+                duration = Some(Duration::from_secs_f64(secs));
+            }
+            _ => return Err(format!("record: unexpected argument \"{arg}\"")),
+        }
+    }
+    let duration = duration.ok_or("record: how long? Add --duration <seconds>")?;
+    Ok(Command::Record { duration, options })
+}
+
+fn parse_batch(args: &[String]) -> Result<Command, String> {
+    let mut inputs = Vec::new();
+    let mut options = SttOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !stt_option(arg, &mut args, &mut options)? {
+            if arg.starts_with("--") {
+                return Err(format!("batch: unexpected argument \"{arg}\""));
+            }
+            inputs.push(PathBuf::from(arg));
+        }
+    }
+    if inputs.is_empty() {
+        return Err("batch: which files or folders? See whispercrabs help".into());
+    }
+    Ok(Command::Batch { inputs, options })
+}
+
+fn parse_history(args: &[String]) -> Result<Command, String> {
+    let mut limit = DEFAULT_HISTORY_LIMIT;
+    let mut json = false;
+    let mut language = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--limit" => {
+                limit = value_of(arg, &mut args)?
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or("history: --limit takes a number of entries")?;
+            }
+            "--json" => json = true,
+            "--language" => {
+                let value = value_of(arg, &mut args)?;
+                language = Some(config::normalize_language(value).ok_or_else(|| {
+                    format!("history: unknown language \"{value}\", try e.g. en or de")
+                })?);
+            }
+            _ => return Err(format!("history: unexpected argument \"{arg}\"")),
+        }
+    }
+    Ok(Command::History {
+        limit,
+        json,
+        language,
+    })
+}
+
+fn parse_benchmark(args: &[String]) -> Result<Command, String> {
+    let mut file = None;
+    let mut providers = Vec::new();
+    let mut reference = None;
+    let mut options = SttOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--providers" => {
+                providers = value_of(arg, &mut args)?
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "--reference" => reference = Some(value_of(arg, &mut args)?.trim().to_string()),
+            "--language" => {
+                stt_option(arg, &mut args, &mut options)?;
+            }
+            _ if arg.starts_with("--") || file.is_some() => {
+                return Err(format!("benchmark: unexpected argument \"{arg}\""));
+            }
+            _ => file = Some(PathBuf::from(arg)),
+        }
+    }
+    Ok(Command::Benchmark {
+        file,
+        providers,
+        reference,
+        language: options.language,
+    })
+}
+
+/// Take `arg` into `options` if it is one of theirs, with its value from
+/// `rest`; `false` if it isn't.
+fn stt_option<'a>(
+    arg: &str,
+    rest: &mut impl Iterator<Item = &'a String>,
+    options: &mut SttOptions,
+) -> Result<bool, String> {
+    match arg {
+        "--provider" => options.provider = Some(value_of(arg, rest)?.trim().to_string()),
+        "--language" => {
+            let value = value_of(arg, rest)?;
+            let language = if value
+                .trim()
+                .eq_ignore_ascii_case(config::DEFAULT_STT_LANGUAGE)
+            {
+                Some(config::DEFAULT_STT_LANGUAGE.to_string())
+            } else {
+                config::normalize_language(value)
+            };
+            options.language = Some(
+                language
+                    .ok_or_else(|| format!("Unknown language \"{value}\", try e.g. en or auto"))?,
+            );
+        }
+        "--translate" => options.translate = true,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn value_of<'a>(
+    option: &str,
+    rest: &mut impl Iterator<Item = &'a String>,
+) -> Result<&'a String, String> {
+    rest.next()
+        .filter(|v| !v.starts_with("--"))
+        .ok_or_else(|| format!("{option} needs a value"))
+}
+
+/// Run `command`; what it prints goes to stdout.
+pub fn run(command: Command, config: &Config) -> Result<(), String> {
+    match command {
+        Command::Help => print!("{USAGE}"),
+        Command::Transcribe { file, options } => {
+            let (wav, sample_rate) =
+                crate::audio::decode_file(&file).map_err(|e| format!("{}: {e}", file.display()))?;
+            println!("{}", transcribe(config, wav, sample_rate, &options)?);
+        }
+        Command::Record { duration, options } => {
+            let (wav, sample_rate) = record(config, duration)?;
+            println!("{}", transcribe(config, wav, sample_rate, &options)?);
+        }
+        Command::Batch { inputs, options } => batch(config, &inputs, &options)?,
+        Command::Benchmark {
+            file,
+            providers,
+            reference,
+            language,
+        } => benchmark(config, file, &providers, reference, language)?,
+        Command::History {
+            limit,
+            json,
+            language,
+        } => {
+            let entries = crate::transcription::open_history(config)?
+                .recent_in_language(limit, language.as_deref())
+                .map_err(|e| format!("Can't read History: {e}"))?;
+            if json {
+                println!(
+                    "{}",
+                    crate::export::render(&entries, crate::export::ExportFormat::Json)
+                );
+            } else {
+                for entry in entries {
+                    match &entry.language {
+                        Some(language) => println!(
+                            "{}  [{language}]  {}",
+                            entry.created_at,
+                            history_line(&entry.text)
+                        ),
+                        None => println!("{}  {}", entry.created_at, history_line(&entry.text)),
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A History entry on one line.
+pub fn history_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Record `duration` from the input device chosen in the app.
+fn record(config: &Config, duration: Duration) -> Result<(Vec<u8>, u32), String> {
+    let device = crate::transcription::open_history(config)?
+        .get_setting("audio_device")
+        .ok()
+        .flatten()
+        .filter(|d| !d.is_empty());
+    let mut recorder = crate::audio::Recorder::new();
+    recorder.set_device(device);
+    recorder.start()?;
+    eprintln!("Recording for {:.0} s...", duration.as_secs_f64());
+    std::thread::sleep(duration);
+    let wav = recorder.stop()?;
+    Ok((wav, recorder.sample_rate()))
+}
+
+/// The route and language `options` ask for, with the app's settings
+/// filling in the rest.
+fn resolve(
+    db: &crate::db::Db,
+    config: &Config,
+    options: &SttOptions,
+) -> Result<(crate::transcription::Route, String), String> {
+    crate::policy::set_local_only(crate::transcription::local_only(db, config));
+    let provider = options
+        .provider
+        .clone()
+        .unwrap_or_else(|| crate::transcription::stored_provider(db, config));
+    let language = options
+        .language
+        .clone()
+        .unwrap_or_else(|| crate::transcription::stored_language(db));
+    let route = crate::transcription::route(db, config, &provider)?;
+    // Naming a provider counts as choosing it; the stored one needs consent
+    if options.provider.is_none() {
+        crate::transcription::ensure_consent(db, &route)?;
+    }
+    eprintln!("Transcribing with {provider}...");
+    Ok((route, language))
+}
+
+/// Transcribe `wav` as `options` ask and apply the personal dictionary.
+fn transcribe(
+    config: &Config,
+    wav: Vec<u8>,
+    sample_rate: u32,
+    options: &SttOptions,
+) -> Result<String, String> {
+    let db = crate::transcription::open_history(config)?;
+    let (route, language) = resolve(&db, config, options)?;
+    let transcript = crate::transcription::transcribe(
+        config,
+        &route,
+        &crate::transcription::EngineCache::default(),
+        wav,
+        sample_rate,
+        &language,
+        options.translate,
+        &crate::vocabulary::stored(&db),
+    )?;
+    let rules = db
+        .accepted_replacements(transcript.language.as_deref())
+        .unwrap_or_default();
+    let text = crate::corrections::apply(&transcript.text, &rules);
+    Ok(match crate::dictation_commands::stored(&db) {
+        Some(commands) => crate::dictation_commands::apply(&text, &commands),
+        None => text,
+    })
+}
+
+/// Run a clip through the providers and print how they compare: a table
+/// of latency, speed and word error rate, then each transcript's words
+/// against the reference.
+fn benchmark(
+    config: &Config,
+    file: Option<PathBuf>,
+    providers: &[String],
+    reference: Option<String>,
+    language: Option<String>,
+) -> Result<(), String> {
+    let db = crate::transcription::open_history(config)?;
+    crate::policy::set_local_only(crate::transcription::local_only(&db, config));
+    let file = match file {
+        Some(file) => file,
+        None => crate::benchmark::last_recording(&db)
+            .map(|(_, path)| path)
+            .ok_or("benchmark: no kept recording, pass an audio file")?,
+    };
+    let (wav, sample_rate) = crate::benchmark::decode(&file)?;
+    let audio_secs = crate::audio::wav_duration_secs(&wav).unwrap_or(0.0);
+    let candidates = crate::benchmark::select(crate::benchmark::candidates(&db, config), providers);
+    if candidates.is_empty() {
+        return Err("benchmark: no downloaded models or configured providers".into());
+    }
+    let language = language.unwrap_or_else(|| crate::transcription::stored_language(&db));
+    let vocabulary = crate::vocabulary::stored(&db);
+    let reference = reference.unwrap_or_else(|| crate::transcription::stored_provider(&db, config));
+    eprintln!("Clip: {} ({audio_secs:.1} s)", file.display());
+    let engines = crate::transcription::EngineCache::default();
+    let mut outcomes = Vec::new();
+    for candidate in &candidates {
+        eprintln!("Transcribing with {}...", candidate.provider);
+        outcomes.push(crate::benchmark::run(
+            config,
+            &engines,
+            candidate,
+            &wav,
+            sample_rate,
+            &language,
+            &vocabulary,
+        ));
+    }
+    let reference = crate::benchmark::reference(&outcomes, &reference);
+    for outcome in &outcomes {
+        println!(
+            "{}",
+            crate::benchmark::summary_line(outcome, audio_secs, reference)
+        );
+    }
+    for outcome in &outcomes {
+        let Ok(text) = &outcome.text else {
+            continue;
+        };
+        println!("\n{}:", outcome.provider);
+        let compared = reference
+            .filter(|r| r.provider != outcome.provider)
+            .and_then(|r| r.text.as_ref().ok());
+        match compared {
+            Some(r) => println!(
+                "{}",
+                crate::benchmark::diff_text(&crate::benchmark::word_diff(r, text))
+            ),
+            None => println!("{text}"),
+        }
+    }
+    Ok(())
+}
+
+/// Transcribe the audio files `inputs` name one by one, reporting each on
+/// stderr; fails at the end if any of them failed.
+fn batch(config: &Config, inputs: &[PathBuf], options: &SttOptions) -> Result<(), String> {
+    let files = crate::batch::collect(inputs)?;
+    let db = crate::transcription::open_history(config)?;
+    let (route, language) = resolve(&db, config, options)?;
+    let job = crate::batch::Job {
+        files,
+        route,
+        language,
+        translate: options.translate,
+        vocabulary: crate::vocabulary::stored(&db),
+    };
+    let db = std::sync::Mutex::new(db);
+    let engines = crate::transcription::EngineCache::default();
+    let total = job.files.len();
+    let mut failed = 0;
+    for (index, file) in job.files.iter().enumerate() {
+        eprint!("[{}/{total}] {} ... ", index + 1, file.display());
+        match crate::batch::transcribe_file(config, &db, &engines, &job, index) {
+            Ok(outcome) if outcome.subtitles => eprintln!("done (.txt, .srt)"),
+            Ok(_) => eprintln!("done (.txt)"),
+            Err(e) => {
+                failed += 1;
+                eprintln!("failed: {e}");
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {total} files failed"));
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Dictation coaching — speaking rate and loudness over recent dictations.
+//!
+//! Every dictation records its word count, audio length and loudness (RMS
+//! level in dBFS) with its usage row. From those the Stats window shows the
+//! speaking rate in words per minute, and, when tips are on, a gentle hint
+//! appears after a dictation if the recent ones were mostly too quiet or too
+//! fast for good accuracy. One odd dictation never triggers a hint.
+
+use std::time::Duration;
+
+/// Dictations looked at for a hint, newest first.
+pub const RECENT: usize = 10;
+
+/// Dictations needed before any hint is given.
+pub const MIN_SAMPLES: usize = 5;
+
+/// Above this rate, words start to run together for most models.
+pub const FAST_WPM: f64 = 180.0;
+
+/// Below this RMS level the voice is close to the noise floor.
+pub const QUIET_DB: f64 = -42.0;
+
+/// Shortest time between two hints.
+pub const HINT_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Dictations shorter than this say little about a speaking rate.
+const MIN_RATE_SECS: f64 = 3.0;
+
+/// What was measured for one dictation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpeechSample {
+    pub words: i64,
+    pub audio_secs: f64,
+    pub level_db: Option<f64>,
+}
+
+impl SpeechSample {
+    /// Speaking rate of this dictation, `None` when it is too short to tell.
+    pub fn words_per_minute(&self) -> Option<f64> {
+        words_per_minute(self.words, self.audio_secs)
+    }
+}
+
+/// Words per minute over `audio_secs` of audio, `None` below a few seconds
+/// or without words.
+pub fn words_per_minute(words: i64, audio_secs: f64) -> Option<f64> {
+    (audio_secs >= MIN_RATE_SECS && words > 0).then(|| words as f64 * 60.0 / audio_secs)
+}
+
+/// RMS level of `samples` in dBFS (0 for full scale), `None` for silence.
+pub fn level_db(samples: &[f32]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mean_square = samples
+        .iter()
+        .map(|s| (*s as f64) * (*s as f64))
+        .sum::<f64>()
+        / samples.len() as f64;
+    (mean_square > 0.0).then(|| 10.0 * mean_square.log10())
+}
+
+/// A suggestion for better accuracy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hint {
+    TooQuiet,
+    TooFast,
+}
+
+impl Hint {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::TooQuiet => "Tip: speak up or move closer to the mic",
+            Self::TooFast => "Tip: slowing down a little helps accuracy",
+        }
+    }
+}
+
+/// The hint for `recent` dictations (newest first), if most of the last
+/// [`RECENT`] with a measurement were too quiet or too fast. Quiet comes
+/// first: a voice lost in noise is the bigger problem.
+pub fn hint(recent: &[SpeechSample]) -> Option<Hint> {
+    let recent = &recent[..recent.len().min(RECENT)];
+    let mostly = |flags: Vec<bool>| {
+        flags.len() >= MIN_SAMPLES && flags.iter().filter(|f| **f).count() * 3 >= flags.len() * 2
+    };
+    let quiet = recent
+        .iter()
+        .filter_map(|s| s.level_db)
+        .map(|db| db < QUIET_DB)
+        .collect();
+    if mostly(quiet) {
+        return Some(Hint::TooQuiet);
+    }
+    let fast = recent
+        .iter()
+        .filter_map(SpeechSample::words_per_minute)
+        .map(|wpm| wpm > FAST_WPM)
+        .collect();
+    mostly(fast).then_some(Hint::TooFast)
+}
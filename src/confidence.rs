@@ -0,0 +1,75 @@
+//! Confidence — how sure the speech model was of a transcript.
+//!
+//! Local whisper models report a probability for every token and API
+//! providers with `verbose_json` a mean log-probability per segment (see
+//! [`crate::local_stt::Segment::confidence`]); both are discounted by the
+//! segment's no-speech probability where the engine reports one. The
+//! transcript's score is the mean over its segments, weighted by their
+//! length, and is stored with its History entry.
+//!
+//! A score under the threshold (60 % by default, `confidence_threshold`)
+//! shows as "Copied — low confidence"; with **Review Low Confidence** on,
+//! such transcripts open in the review window (see [`crate::review`]) even
+//! when reviewing is off.
+
+use crate::db::Db;
+use crate::local_stt::Segment;
+
+/// Setting of the threshold, in percent.
+pub const THRESHOLD_SETTING: &str = "confidence_threshold";
+
+/// Setting of the "review low-confidence transcripts" toggle.
+pub const REVIEW_SETTING: &str = "confidence_review";
+
+/// Threshold used without a setting.
+pub const DEFAULT_THRESHOLD: f32 = 0.6;
+
+/// The score of a transcript made of `segments`: their confidences
+/// averaged by text length, `None` when no segment has one.
+pub fn score(segments: &[Segment]) -> Option<f32> {
+    let (sum, weight) = segments
+        .iter()
+        .filter_map(|seg| {
+            let weight = seg.text.chars().count().max(1) as f32;
+            seg.confidence.map(|c| (c.clamp(0.0, 1.0) * weight, weight))
+        })
+        .fold((0.0, 0.0), |(sum, total), (c, w)| (sum + c, total + w));
+    (weight > 0.0).then(|| sum / weight)
+}
+
+/// A segment confidence discounted by the chance it wasn't speech at all.
+pub fn with_no_speech(confidence: Option<f32>, no_speech_prob: Option<f32>) -> Option<f32> {
+    confidence.map(|c| c * (1.0 - no_speech_prob.unwrap_or(0.0).clamp(0.0, 1.0)))
+}
+
+/// Whether `score` is under `threshold`; an unknown score never is.
+pub fn is_low(score: Option<f32>, threshold: f32) -> bool {
+    score.is_some_and(|s| s < threshold)
+}
+
+/// The threshold in use: the setting (a percentage) or the default.
+pub fn threshold(d: &Db) -> f32 {
+    d.get_setting(THRESHOLD_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().trim_end_matches('%').parse::<f32>().ok())
+        .filter(|p| (0.0..=100.0).contains(p))
+        .map_or(DEFAULT_THRESHOLD, |p| p / 100.0)
+}
+
+/// Whether low-confidence transcripts are held for review.
+pub fn review_low(d: &Db) -> bool {
+    d.get_setting(REVIEW_SETTING)
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// The status shown once a transcript is copied (or typed).
+pub fn status(delivered: &str, low: bool) -> String {
+    if low {
+        format!("{delivered} \u{2014} low confidence")
+    } else {
+        format!("{delivered}!")
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// Active transcription backend.
@@ -7,6 +8,15 @@ pub enum TranscriptionService {
     Local,
 }
 
+/// How hardware (HID) trigger buttons drive recording.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HidMode {
+    /// Press once to start, press again to stop.
+    Toggle,
+    /// Record while the button is held down.
+    PushToTalk,
+}
+
 /// Built-in API provider configuration.
 pub struct ApiPreset {
     pub id: &'static str,
@@ -14,6 +24,8 @@ pub struct ApiPreset {
     pub base_url: &'static str,
     pub default_model: &'static str,
     pub needs_key: bool,
+    /// Published price in USD per audio minute (0.0 for self-hosted).
+    pub usd_per_minute: f64,
 }
 
 /// Pre-configured API providers (Groq, Ollama, OpenRouter, LM Studio).
@@ -24,6 +36,7 @@ pub const API_PRESETS: &[ApiPreset] = &[
         base_url: "https://api.groq.com/openai/v1",
         default_model: "whisper-large-v3-turbo",
         needs_key: true,
+        usd_per_minute: 0.000667,
     },
     ApiPreset {
         id: "ollama",
@@ -31,6 +44,7 @@ pub const API_PRESETS: &[ApiPreset] = &[
         base_url: "http://localhost:11434/v1",
         default_model: "whisper",
         needs_key: false,
+        usd_per_minute: 0.0,
     },
     ApiPreset {
         id: "openrouter",
@@ -38,6 +52,7 @@ pub const API_PRESETS: &[ApiPreset] = &[
         base_url: "https://openrouter.ai/api/v1",
         default_model: "openai/whisper-1",
         needs_key: true,
+        usd_per_minute: 0.006,
     },
     ApiPreset {
         id: "lmstudio",
@@ -45,9 +60,24 @@ pub const API_PRESETS: &[ApiPreset] = &[
         base_url: "http://localhost:1234/v1",
         default_model: "whisper-1",
         needs_key: false,
+        usd_per_minute: 0.0,
     },
 ];
 
+/// Default transcription endpoint path, appended to the API base URL.
+pub const DEFAULT_TRANSCRIPTION_PATH: &str = "/audio/transcriptions";
+
+/// OpenAI-compatible endpoint that translates speech to English.
+pub const TRANSLATION_PATH: &str = "/audio/translations";
+
+/// Estimated cost in USD for `minutes` of audio on a provider.
+/// Local models and unknown/custom providers are counted as free.
+pub fn estimated_cost(provider_id: &str, minutes: f64) -> f64 {
+    find_preset(provider_id)
+        .map(|p| p.usd_per_minute * minutes)
+        .unwrap_or(0.0)
+}
+
 /// Look up an API preset by its short identifier.
 pub fn find_preset(id: &str) -> Option<&'static ApiPreset> {
     API_PRESETS.iter().find(|p| p.id == id)
@@ -59,33 +89,168 @@ pub struct LocalModelPreset {
     pub label: &'static str,
     pub file_name: &'static str,
     pub size_label: &'static str,
+    /// Multilingual models can transcribe (and auto-detect) any Whisper language;
+    /// `.en` models are English-only.
+    pub multilingual: bool,
+    /// ggml quantization (`q5_0`, `q5_1`, `q8_0`), `None` for full precision.
+    pub quantization: Option<&'static str>,
 }
 
-/// Available local whisper model sizes (Tiny through Medium).
+/// Available local whisper models: English-only (Tiny through Medium) and
+/// multilingual (Tiny through Large v3), plus quantized variants that trade a
+/// little accuracy for much smaller files and faster inference.
 pub const LOCAL_MODEL_PRESETS: &[LocalModelPreset] = &[
     LocalModelPreset {
         id: "local-tiny",
         label: "Tiny",
         file_name: "ggml-tiny.en.bin",
         size_label: "~75 MB",
+        multilingual: false,
+        quantization: None,
     },
     LocalModelPreset {
         id: "local-base",
         label: "Base",
         file_name: "ggml-base.en.bin",
         size_label: "~142 MB",
+        multilingual: false,
+        quantization: None,
     },
     LocalModelPreset {
         id: "local-small",
         label: "Small",
         file_name: "ggml-small.en.bin",
         size_label: "~466 MB",
+        multilingual: false,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-small-tdrz",
+        label: "Small (speaker turns)",
+        file_name: TDRZ_MODEL_FILE,
+        size_label: "~466 MB",
+        multilingual: false,
+        quantization: None,
     },
     LocalModelPreset {
         id: "local-medium",
         label: "Medium",
         file_name: "ggml-medium.en.bin",
         size_label: "~1.5 GB",
+        multilingual: false,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-tiny-multi",
+        label: "Tiny",
+        file_name: "ggml-tiny.bin",
+        size_label: "~75 MB",
+        multilingual: true,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-base-multi",
+        label: "Base",
+        file_name: "ggml-base.bin",
+        size_label: "~142 MB",
+        multilingual: true,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-small-multi",
+        label: "Small",
+        file_name: "ggml-small.bin",
+        size_label: "~466 MB",
+        multilingual: true,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-medium-multi",
+        label: "Medium",
+        file_name: "ggml-medium.bin",
+        size_label: "~1.5 GB",
+        multilingual: true,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-large-v3",
+        label: "Large v3",
+        file_name: "ggml-large-v3.bin",
+        size_label: "~3.1 GB",
+        multilingual: true,
+        quantization: None,
+    },
+    LocalModelPreset {
+        id: "local-tiny-q8",
+        label: "Tiny Q8",
+        file_name: "ggml-tiny.en-q8_0.bin",
+        size_label: "~44 MB",
+        multilingual: false,
+        quantization: Some("q8_0"),
+    },
+    LocalModelPreset {
+        id: "local-base-q5",
+        label: "Base Q5",
+        file_name: "ggml-base.en-q5_1.bin",
+        size_label: "~60 MB",
+        multilingual: false,
+        quantization: Some("q5_1"),
+    },
+    LocalModelPreset {
+        id: "local-small-q5",
+        label: "Small Q5",
+        file_name: "ggml-small.en-q5_1.bin",
+        size_label: "~181 MB",
+        multilingual: false,
+        quantization: Some("q5_1"),
+    },
+    LocalModelPreset {
+        id: "local-small-q8",
+        label: "Small Q8",
+        file_name: "ggml-small.en-q8_0.bin",
+        size_label: "~252 MB",
+        multilingual: false,
+        quantization: Some("q8_0"),
+    },
+    LocalModelPreset {
+        id: "local-medium-q5",
+        label: "Medium Q5",
+        file_name: "ggml-medium.en-q5_0.bin",
+        size_label: "~514 MB",
+        multilingual: false,
+        quantization: Some("q5_0"),
+    },
+    LocalModelPreset {
+        id: "local-small-multi-q5",
+        label: "Small Q5",
+        file_name: "ggml-small-q5_1.bin",
+        size_label: "~181 MB",
+        multilingual: true,
+        quantization: Some("q5_1"),
+    },
+    LocalModelPreset {
+        id: "local-medium-multi-q5",
+        label: "Medium Q5",
+        file_name: "ggml-medium-q5_0.bin",
+        size_label: "~514 MB",
+        multilingual: true,
+        quantization: Some("q5_0"),
+    },
+    LocalModelPreset {
+        id: "local-large-v3-turbo-q5",
+        label: "Large v3 Turbo Q5",
+        file_name: "ggml-large-v3-turbo-q5_0.bin",
+        size_label: "~547 MB",
+        multilingual: true,
+        quantization: Some("q5_0"),
+    },
+    LocalModelPreset {
+        id: "local-large-v3-q5",
+        label: "Large v3 Q5",
+        file_name: "ggml-large-v3-q5_0.bin",
+        size_label: "~1.1 GB",
+        multilingual: true,
+        quantization: Some("q5_0"),
     },
 ];
 
@@ -97,12 +262,351 @@ pub fn find_local_model(id: &str) -> Option<&'static LocalModelPreset> {
     LOCAL_MODEL_PRESETS.iter().find(|m| m.id == id)
 }
 
+/// A Vosk model: a zip archive that unpacks to a directory of the same name.
+pub struct VoskModelPreset {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// Archive (without `.zip`) and directory name.
+    pub name: &'static str,
+    pub size_label: &'static str,
+    /// ISO 639-1 code of the single language the model recognizes.
+    pub language: &'static str,
+}
+
+/// Where Vosk model archives are downloaded from.
+pub const VOSK_MODEL_BASE_URL: &str = "https://alphacephei.com/vosk/models";
+
+/// Small Vosk models for fast, lower-accuracy offline transcription.
+pub const VOSK_MODEL_PRESETS: &[VoskModelPreset] = &[
+    VoskModelPreset {
+        id: "vosk-small-en",
+        label: "Vosk Small English",
+        name: "vosk-model-small-en-us-0.15",
+        size_label: "~40 MB",
+        language: "en",
+    },
+    VoskModelPreset {
+        id: "vosk-small-de",
+        label: "Vosk Small German",
+        name: "vosk-model-small-de-0.15",
+        size_label: "~45 MB",
+        language: "de",
+    },
+    VoskModelPreset {
+        id: "vosk-small-fr",
+        label: "Vosk Small French",
+        name: "vosk-model-small-fr-0.22",
+        size_label: "~41 MB",
+        language: "fr",
+    },
+    VoskModelPreset {
+        id: "vosk-small-es",
+        label: "Vosk Small Spanish",
+        name: "vosk-model-small-es-0.42",
+        size_label: "~39 MB",
+        language: "es",
+    },
+];
+
+/// Look up a Vosk model preset by its short identifier.
+pub fn find_vosk_model(id: &str) -> Option<&'static VoskModelPreset> {
+    VOSK_MODEL_PRESETS.iter().find(|m| m.id == id)
+}
+
+/// Download URL of a Vosk model archive.
+pub fn vosk_model_url(model: &VoskModelPreset) -> String {
+    format!("{VOSK_MODEL_BASE_URL}/{}.zip", model.name)
+}
+
+/// Directory an unpacked Vosk model lives in.
+pub fn vosk_model_dir(models_dir: &std::path::Path, model: &VoskModelPreset) -> PathBuf {
+    models_dir.join("vosk").join(model.name)
+}
+
+/// On-disk location of a local provider's model: a whisper file, a Vosk
+/// directory, or the user's custom model. `None` for non-local ids.
+pub fn local_model_path(
+    models_dir: &std::path::Path,
+    id: &str,
+    custom_model: Option<&std::path::Path>,
+) -> Option<PathBuf> {
+    if id == CUSTOM_LOCAL_MODEL {
+        return custom_model.map(|p| p.to_path_buf());
+    }
+    if let Some(m) = find_local_model(id) {
+        return Some(models_dir.join(m.file_name));
+    }
+    find_vosk_model(id).map(|m| vosk_model_dir(models_dir, m))
+}
+
+/// Whether a models directory can be read right now. A removable or network
+/// drive that is unmounted (or whose mount point is gone) is not.
+pub fn models_dir_available(dir: &std::path::Path) -> bool {
+    std::fs::read_dir(dir).is_ok()
+}
+
+/// Where a local model can be loaded from, see [`locate_model`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModelLocation {
+    /// The model is on disk at this path.
+    Found(PathBuf),
+    /// Not downloaded yet; it belongs at this path.
+    Missing(PathBuf),
+    /// The directory holding the model can't be reached (e.g. an unmounted
+    /// drive). `local_path` is where a fresh copy could be downloaded on this
+    /// machine, `None` for a custom model file.
+    Unavailable {
+        dir: PathBuf,
+        local_path: Option<PathBuf>,
+    },
+}
+
+/// Find a local provider's model in `models_dir`, falling back to a copy in
+/// `local_models_dir` when the former is missing the model or is offline.
+/// `None` for non-local ids (and a custom model that was never picked).
+pub fn locate_model(
+    models_dir: &std::path::Path,
+    local_models_dir: &std::path::Path,
+    id: &str,
+    custom_model: Option<&std::path::Path>,
+) -> Option<ModelLocation> {
+    let path = local_model_path(models_dir, id, custom_model)?;
+    if id == CUSTOM_LOCAL_MODEL {
+        let dir = path.parent().unwrap_or(&path).to_path_buf();
+        return Some(if path.exists() {
+            ModelLocation::Found(path)
+        } else if models_dir_available(&dir) {
+            ModelLocation::Missing(path)
+        } else {
+            ModelLocation::Unavailable {
+                dir,
+                local_path: None,
+            }
+        });
+    }
+
+    let local = local_model_path(local_models_dir, id, None)?;
+    let available = models_dir_available(models_dir);
+    Some(if available && path.exists() {
+        ModelLocation::Found(path)
+    } else if local.exists() {
+        ModelLocation::Found(local)
+    } else if available {
+        ModelLocation::Missing(path)
+    } else {
+        ModelLocation::Unavailable {
+            dir: models_dir.to_path_buf(),
+            local_path: Some(local),
+        }
+    })
+}
+
+/// Total size of the files below `path`.
+fn dir_size(path: &std::path::Path) -> u64 {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| match e.file_type() {
+                    Ok(t) if t.is_dir() => dir_size(&e.path()),
+                    _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Vosk models unpacked in `models_dir`, with their size in bytes.
+pub fn downloaded_vosk_models(
+    models_dir: &std::path::Path,
+) -> Vec<(&'static VoskModelPreset, u64)> {
+    VOSK_MODEL_PRESETS
+        .iter()
+        .filter_map(|m| {
+            let dir = vosk_model_dir(models_dir, m);
+            dir.is_dir().then(|| (m, dir_size(&dir)))
+        })
+        .collect()
+}
+
+/// WhisperCrabs' data folder: History, models, recordings and logs.
+pub fn data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("whispercrabs")
+}
+
+/// Where the retained recording of a history entry lives.
+pub fn recording_path(audio_dir: &std::path::Path, transcription_id: i64) -> PathBuf {
+    audio_dir.join(format!("{transcription_id}.wav"))
+}
+
+/// Preset models present in `models_dir`, with their file size in bytes.
+pub fn downloaded_models(models_dir: &std::path::Path) -> Vec<(&'static LocalModelPreset, u64)> {
+    LOCAL_MODEL_PRESETS
+        .iter()
+        .filter_map(|m| {
+            let meta = std::fs::metadata(models_dir.join(m.file_name)).ok()?;
+            meta.is_file().then_some((m, meta.len()))
+        })
+        .collect()
+}
+
+/// Human-readable size, e.g. `142 MB` or `1.5 GB`.
+pub fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{mb:.0} MB")
+    }
+}
+
+/// Provider id for a user-provided model file (path stored in the
+/// `local_custom_model` setting).
+pub const CUSTOM_LOCAL_MODEL: &str = "local-custom";
+
+/// Whether a provider id runs on a local model (whisper preset, custom file or Vosk).
+pub fn is_local_provider(id: &str) -> bool {
+    id == CUSTOM_LOCAL_MODEL || find_local_model(id).is_some() || find_vosk_model(id).is_some()
+}
+
+/// Check that a user-chosen model file exists and looks like a ggml/gguf model.
+pub fn validate_custom_model(path: &std::path::Path) -> Result<(), String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    if !matches!(ext.as_deref(), Some("bin" | "gguf")) {
+        return Err("Model must be a .bin (ggml) or .gguf file".into());
+    }
+    if !path.is_file() {
+        return Err(format!("Model file not found: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Spoken languages offered in the menu for multilingual models (ISO 639-1 code, label).
+/// `auto` lets whisper detect the language.
+pub const STT_LANGUAGES: &[(&str, &str)] = &[
+    ("auto", "Auto-detect"),
+    ("en", "English"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("ru", "Russian"),
+    ("uk", "Ukrainian"),
+    ("tr", "Turkish"),
+    ("ar", "Arabic"),
+    ("hi", "Hindi"),
+    ("zh", "Chinese"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+];
+
+/// Default spoken language for multilingual models.
+pub const DEFAULT_STT_LANGUAGE: &str = "auto";
+
+/// Whether a language code is offered in [`STT_LANGUAGES`].
+pub fn is_known_language(code: &str) -> bool {
+    STT_LANGUAGES.iter().any(|(c, _)| *c == code)
+}
+
+/// Normalise a detected language to an ISO 639-1 code. Providers report
+/// either a code (`en`) or an English name (`english`, from OpenAI's
+/// verbose_json); names are resolved via [`STT_LANGUAGES`].
+pub fn normalize_language(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_lowercase();
+    if raw.is_empty() || raw == "auto" {
+        return None;
+    }
+    if (2..=3).contains(&raw.len()) && raw.chars().all(|c| c.is_ascii_lowercase()) {
+        return Some(raw);
+    }
+    STT_LANGUAGES
+        .iter()
+        .find(|(code, label)| *code != "auto" && label.eq_ignore_ascii_case(&raw))
+        .map(|(code, _)| code.to_string())
+}
+
+/// Display name of a language code (the code itself if it isn't in the menu).
+pub fn language_label(code: &str) -> &str {
+    STT_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(code, |(_, label)| label)
+}
+
+/// Language to request from whisper for a model: English-only models are
+/// always pinned to English, multilingual ones use the user's choice.
+pub fn effective_language<'a>(model: &LocalModelPreset, chosen: &'a str) -> &'a str {
+    if model.multilingual { chosen } else { "en" }
+}
+
+/// Temporary path used while a model file is downloading.
+///
+/// Appends `.part` to the full file name rather than swapping the extension,
+/// so quantized names like `ggml-small.en-q5_1.bin` map cleanly.
+pub fn model_part_path(model_path: &std::path::Path) -> PathBuf {
+    let mut name = model_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    model_path.with_file_name(name)
+}
+
+/// First byte of a `Content-Range: bytes START-END/TOTAL` response header,
+/// used to confirm a resumed download continues where the `.part` file ends.
+pub fn content_range_start(header: &str) -> Option<u64> {
+    header
+        .trim()
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Default repository whisper models are downloaded from.
+pub const DEFAULT_MODEL_BASE_URL: &str =
+    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
 /// Build the HuggingFace download URL for a whisper model file.
 pub fn model_url(file_name: &str) -> String {
-    format!(
-        "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}",
-        file_name
-    )
+    model_url_in(DEFAULT_MODEL_BASE_URL, file_name)
+}
+
+/// Download URL for a whisper model file in a specific repository
+/// (`WHISPER_MODEL_BASE_URL`, e.g. a gated or mirrored repo).
+pub fn model_url_in(base_url: &str, file_name: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), file_name)
+}
+
+/// tinydiarize fine-tune of Small (English) that marks speaker turns; it is
+/// hosted outside the main whisper.cpp repo.
+pub const TDRZ_MODEL_FILE: &str = "ggml-small.en-tdrz.bin";
+
+const TDRZ_MODEL_BASE_URL: &str =
+    "https://huggingface.co/akashmjn/tinydiarize-whisper.cpp/resolve/main";
+
+/// Download URL of a preset: `base_url` for regular models, the
+/// tinydiarize repo for the speaker-turn model.
+pub fn preset_model_url(base_url: &str, model: &LocalModelPreset) -> String {
+    if model.file_name == TDRZ_MODEL_FILE {
+        model_url_in(TDRZ_MODEL_BASE_URL, model.file_name)
+    } else {
+        model_url_in(base_url, model.file_name)
+    }
+}
+
+/// Whether a URL is served by Hugging Face, i.e. may receive the HF token.
+pub fn is_huggingface_url(url: &str) -> bool {
+    let host = crate::policy::url_host(url);
+    url.starts_with("https://") && (host == "huggingface.co" || host.ends_with(".huggingface.co"))
 }
 
 // ── TTS (text-to-speech) ────────────────────────────────────────────────────
@@ -202,24 +706,240 @@ pub fn piper_voice_exists(piper_dir: &std::path::Path, voice_id: &str) -> bool {
         && piper_dir.join(format!("{voice_id}.onnx.json")).exists()
 }
 
-/// Application configuration loaded from environment and `.env` file.
+/// Default minimum recording length; shorter clips are treated as accidental taps.
+pub const DEFAULT_MIN_RECORDING_MS: u64 = 300;
+
+/// whisper.cpp inference settings for local models. The defaults favour
+/// latency (greedy decoding); beam search trades speed for accuracy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhisperParams {
+    /// CPU threads; 0 lets whisper.cpp decide. On big.LITTLE CPUs, setting
+    /// this to the number of performance cores is often much faster.
+    pub threads: u32,
+    /// Beam search width; 0 or 1 uses greedy decoding.
+    pub beam_size: u32,
+    /// Candidates sampled per step with greedy decoding.
+    pub best_of: u32,
+    /// Sampling temperature (0.0 = deterministic).
+    pub temperature: f32,
+    /// Segments whose no-speech probability exceeds this are dropped.
+    pub no_speech_threshold: f32,
+    /// Suppress non-speech tokens such as `[MUSIC]`, `(applause)` or speaker tags.
+    pub suppress_non_speech: bool,
+    /// Translate to English instead of transcribing (set per dictation by
+    /// record profiles; multilingual models only).
+    pub translate: bool,
+    /// Label speaker turns (set per dictation from the "Label speakers"
+    /// toggle; needs a tinydiarize model).
+    pub diarize: bool,
+    /// Text decoding starts from, steering spelling towards its words (set
+    /// per dictation from [`crate::vocabulary`]).
+    pub initial_prompt: Option<String>,
+}
+
+impl Default for WhisperParams {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            beam_size: 1,
+            best_of: 1,
+            temperature: 0.0,
+            no_speech_threshold: 0.6,
+            suppress_non_speech: false,
+            translate: false,
+            diarize: false,
+            initial_prompt: None,
+        }
+    }
+}
+
+impl WhisperParams {
+    /// Read `WHISPER_THREADS`, `WHISPER_BEAM_SIZE`, `WHISPER_BEST_OF`,
+    /// `WHISPER_TEMPERATURE`, `WHISPER_NO_SPEECH_THRESHOLD` and
+    /// `WHISPER_SUPPRESS_NON_SPEECH` through `get` (see [`Config::load`]).
+    /// Unset or invalid values keep the default.
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let d = Self::default();
+        let num = |key: &str| get(key).and_then(|v| v.trim().parse::<u32>().ok());
+        let float = |key: &str| {
+            get(key)
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .filter(|v| v.is_finite())
+        };
+        Self {
+            threads: num("WHISPER_THREADS").unwrap_or(d.threads).min(64),
+            beam_size: num("WHISPER_BEAM_SIZE").unwrap_or(d.beam_size).min(16),
+            best_of: num("WHISPER_BEST_OF").unwrap_or(d.best_of).clamp(1, 16),
+            temperature: float("WHISPER_TEMPERATURE")
+                .unwrap_or(d.temperature)
+                .clamp(0.0, 1.0),
+            no_speech_threshold: float("WHISPER_NO_SPEECH_THRESHOLD")
+                .unwrap_or(d.no_speech_threshold)
+                .clamp(0.0, 1.0),
+            suppress_non_speech: get("WHISPER_SUPPRESS_NON_SPEECH")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(d.suppress_non_speech),
+            translate: d.translate,
+            diarize: d.diarize,
+            initial_prompt: d.initial_prompt,
+        }
+    }
+}
+
+/// Application configuration loaded from the environment, `.env` and
+/// `config.toml`, see [`Config::load`].
 pub struct Config {
     pub transcription_service: TranscriptionService,
     pub api_base_url: String,
     pub api_key: Option<String>,
     pub api_model: String,
+    pub api_path: String,
+    /// Text sent as `prompt` to API providers that take one (`API_PROMPT`),
+    /// e.g. names and jargon the model should spell right.
+    pub api_prompt: Option<String>,
     pub db_path: PathBuf,
+    /// Where STT models are stored; `WHISPER_MODELS_DIR` may put this on a
+    /// removable or network drive.
     pub models_dir: PathBuf,
-    pub sound_notification: bool,
+    /// Models directory on this machine. Piper voices live here, and it holds
+    /// the fallback copy of STT models when `models_dir` is offline.
+    pub local_models_dir: PathBuf,
+    /// Retained recordings, one `<transcription id>.wav` per history entry.
+    pub audio_dir: PathBuf,
+    /// Repository whisper models are downloaded from.
+    pub model_base_url: String,
+    /// Sound and volume per event. `SOUND_NOTIFICATION_ON_COMPLETION` alone
+    /// still turns on the completion chime.
+    pub sounds: crate::sounds::SoundScheme,
+    /// Default quiet hours (`QUIET_HOURS=22:00-08:00`), until set in the menu.
+    pub quiet_hours: Option<crate::quiet::Schedule>,
+    /// Default size of the record button (`WIDGET_SIZE=small` or pixels),
+    /// until set in the menu.
+    pub widget_size: Option<crate::appearance::WidgetSize>,
+    /// Default screen corner of the window on Wayland (`SCREEN_CORNER`),
+    /// until set in the menu.
+    pub screen_corner: Option<crate::stacking::Corner>,
+    /// Horizontal and vertical distance of that corner (`SCREEN_MARGIN`).
+    pub screen_margin: (i32, i32),
+    /// Recordings shorter than this are discarded without transcription.
+    pub min_recording_ms: u64,
+    /// When and how fast auto-paste presses Ctrl+V, per window class.
+    pub paste: crate::paste::PasteConfig,
+    pub hid_trigger: bool,
+    pub hid_keys: Vec<u16>,
+    pub hid_mode: HidMode,
+    /// rustpotter wake-word file for the optional "hey whisper" listener.
+    pub wake_word_model: PathBuf,
+    /// LAN port of the remote control server (`REMOTE_PORT`).
+    pub remote_port: u16,
+    /// Run without the floating window (tiling WMs): state is published on
+    /// D-Bus and as desktop notifications, control is by actions only.
+    pub no_window: bool,
+    /// Started with `--safe-mode`: stored settings and replacements are
+    /// ignored for this session, see [`crate::db::Db::enter_safe_mode`].
+    pub safe_mode: bool,
+    /// Started with `--hidden` (autostart): the floating button isn't shown
+    /// until asked for from the tray or the `show-button` action.
+    pub hidden: bool,
+    pub whisper: WhisperParams,
+    pub policy: crate::policy::Policy,
+}
+
+/// Environment variable naming another config file than the default.
+pub const CONFIG_FILE_VAR: &str = "WHISPERCRABS_CONFIG";
+
+/// `config.toml` in the user's config directory
+/// (`~/.config/whispercrabs/config.toml` on Linux), or the file named by
+/// `WHISPERCRABS_CONFIG`.
+pub fn config_file_path() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_FILE_VAR)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::config_dir().map(|d| d.join("whispercrabs").join("config.toml")))
+}
+
+/// Settings from a config file: top-level keys named like the environment
+/// variables, in any case (`api_key = "..."`, `min_recording_ms = 500`).
+/// Numbers and booleans become their text; an array of values becomes a
+/// comma-separated list (`hid_keys = ["KEY_F20", "KEY_MICMUTE"]`).
+pub fn parse_config_file(text: &str) -> Result<HashMap<String, String>, String> {
+    let table: BTreeMap<String, toml::Value> =
+        toml::from_str(text).map_err(|e| format!("parse config: {e}"))?;
+    let scalar = |key: &str, value: &toml::Value| match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        _ => Err(format!(
+            "config: `{key}` must be a string, number or boolean"
+        )),
+    };
+    table
+        .iter()
+        .map(|(key, value)| {
+            let text = match value {
+                toml::Value::Array(items) => items
+                    .iter()
+                    .map(|item| scalar(key, item))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(","),
+                other => scalar(key, other)?,
+            };
+            Ok((key.to_ascii_uppercase(), text))
+        })
+        .collect()
+}
+
+/// Look `key` up in order of precedence: the environment (a `.env` file in
+/// the working directory is part of it), then the config file. Settings
+/// saved from the menus are applied later, over both.
+pub fn layered_lookup(
+    env: impl Fn(&str) -> Option<String>,
+    file: &HashMap<String, String>,
+) -> impl Fn(&str) -> Option<String> {
+    move |key| env(key).or_else(|| file.get(key).cloned())
 }
 
 impl Config {
+    /// Load the configuration. Precedence, highest first:
+    ///
+    /// 1. settings saved from the menus and Preferences (History database)
+    /// 2. environment variables
+    /// 3. `.env` in the working directory (never overrides the environment)
+    /// 4. `config.toml`, see [`config_file_path`]
+    /// 5. built-in defaults
+    ///
+    /// A config file that can't be read or parsed is reported and skipped.
     pub fn load() -> Self {
         // Try loading .env from current dir, ignore if missing
         let _ = dotenvy::dotenv();
 
-        let transcription_service = match std::env::var("PRIMARY_TRANSCRIPTION_SERVICE")
-            .unwrap_or_else(|_| "api".into())
+        let file = match config_file_path() {
+            Some(path) if path.exists() => std::fs::read_to_string(&path)
+                .map_err(|e| format!("read {}: {e}", path.display()))
+                .and_then(|text| parse_config_file(&text))
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Ignoring config file: {e}");
+                    HashMap::new()
+                }),
+            _ => HashMap::new(),
+        };
+        Self::from_lookup(layered_lookup(|key| std::env::var(key).ok(), &file))
+    }
+
+    /// Build the configuration from an arbitrary key lookup (used by
+    /// [`Self::load`]).
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let flag = |key: &str| {
+            get(key)
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false)
+        };
+        // Unset and empty mean the same, as for `.env` entries left blank
+        let path = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+
+        let transcription_service = match get("PRIMARY_TRANSCRIPTION_SERVICE")
+            .unwrap_or_else(|| "api".into())
             .to_lowercase()
             .as_str()
         {
@@ -229,22 +949,25 @@ impl Config {
         };
 
         // API_BASE_URL with default pointing to Groq (backwards compatible)
-        let api_base_url = std::env::var("API_BASE_URL")
-            .unwrap_or_else(|_| "https://api.groq.com/openai/v1".into());
+        let api_base_url =
+            get("API_BASE_URL").unwrap_or_else(|| "https://api.groq.com/openai/v1".into());
 
         // API_KEY with GROQ_API_KEY as legacy fallback
-        let api_key = std::env::var("API_KEY")
-            .or_else(|_| std::env::var("GROQ_API_KEY"))
-            .ok();
+        let api_key = get("API_KEY").or_else(|| get("GROQ_API_KEY"));
 
         // API_MODEL with GROQ_STT_MODEL as legacy fallback
-        let api_model = std::env::var("API_MODEL")
-            .or_else(|_| std::env::var("GROQ_STT_MODEL"))
-            .unwrap_or_else(|_| "whisper-large-v3-turbo".into());
+        let api_model = get("API_MODEL")
+            .or_else(|| get("GROQ_STT_MODEL"))
+            .unwrap_or_else(|| "whisper-large-v3-turbo".into());
+
+        // Endpoint path for servers that don't use /audio/transcriptions
+        let api_path =
+            get("API_TRANSCRIPTION_PATH").unwrap_or_else(|| DEFAULT_TRANSCRIPTION_PATH.into());
+
+        // Vocabulary hint, for providers whose capabilities include a prompt
+        let api_prompt = path("API_PROMPT");
 
-        let data_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("whispercrabs");
+        let data_dir = data_dir();
         std::fs::create_dir_all(&data_dir).ok();
 
         // Restrict directory permissions on Unix (owner-only: rwx------)
@@ -256,21 +979,109 @@ impl Config {
 
         let db_path = data_dir.join("history.db");
 
-        let models_dir = data_dir.join("models");
-        std::fs::create_dir_all(&models_dir).ok();
+        let local_models_dir = data_dir.join("models");
+        std::fs::create_dir_all(&local_models_dir).ok();
+
+        // Models on an external drive: never created here, so an unmounted
+        // drive isn't shadowed by an empty directory at its mount point
+        let models_dir = path("WHISPER_MODELS_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| local_models_dir.clone());
+
+        let audio_dir = data_dir.join("audio");
+
+        // Alternative (e.g. gated) model repo; the HF token is sent to huggingface.co only
+        let model_base_url =
+            path("WHISPER_MODEL_BASE_URL").unwrap_or_else(|| DEFAULT_MODEL_BASE_URL.into());
+
+        let sounds =
+            crate::sounds::SoundScheme::from_lookup(flag("SOUND_NOTIFICATION_ON_COMPLETION"), &get);
+        let quiet_hours = get("QUIET_HOURS").and_then(|v| crate::quiet::parse_schedule(&v));
+        let widget_size = get("WIDGET_SIZE").and_then(|v| crate::appearance::WidgetSize::parse(&v));
+        let screen_corner = get("SCREEN_CORNER").and_then(|v| crate::stacking::Corner::parse(&v));
+        let screen_margin = get("SCREEN_MARGIN")
+            .and_then(|v| crate::stacking::parse_margin(&v))
+            .unwrap_or((
+                crate::stacking::DEFAULT_MARGIN,
+                crate::stacking::DEFAULT_MARGIN,
+            ));
+
+        // Ignore accidental taps (0 disables the filter)
+        let min_recording_ms = get("MIN_RECORDING_MS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MIN_RECORDING_MS);
 
-        let sound_notification = std::env::var("SOUND_NOTIFICATION_ON_COMPLETION")
-            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
-            .unwrap_or(false);
+        let paste = crate::paste::PasteConfig::from_lookup(&get);
+
+        let hid_trigger = flag("HID_TRIGGER");
+
+        // Comma-separated evdev key names or codes, e.g. "KEY_MICMUTE,KEY_F20"
+        let hid_keys: Vec<u16> = match get("HID_KEYS") {
+            Some(v) => v
+                .split(',')
+                .filter_map(crate::hid::parse_key_name)
+                .collect(),
+            None => crate::hid::DEFAULT_HID_KEYS
+                .iter()
+                .filter_map(|k| crate::hid::parse_key_name(k))
+                .collect(),
+        };
+
+        let hid_mode = match get("HID_MODE").unwrap_or_default().to_lowercase().as_str() {
+            "ptt" | "push-to-talk" | "hold" => HidMode::PushToTalk,
+            _ => HidMode::Toggle,
+        };
+
+        let wake_word_model = path("WAKE_WORD_MODEL")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                data_dir
+                    .join("wakeword")
+                    .join(crate::wakeword::DEFAULT_MODEL_FILE)
+            });
+
+        let remote_port = get("REMOTE_PORT")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(crate::remote::DEFAULT_PORT);
+
+        let no_window = flag("NO_WINDOW");
 
         Self {
             transcription_service,
             api_base_url,
             api_key,
             api_model,
+            api_path,
+            api_prompt,
             db_path,
             models_dir,
-            sound_notification,
+            local_models_dir,
+            audio_dir,
+            model_base_url,
+            sounds,
+            quiet_hours,
+            widget_size,
+            screen_corner,
+            screen_margin,
+            min_recording_ms,
+            paste,
+            hid_trigger,
+            hid_keys,
+            hid_mode,
+            wake_word_model,
+            remote_port,
+            no_window,
+            safe_mode: false,
+            hidden: false,
+            whisper: WhisperParams::from_lookup(&get),
+            policy: crate::policy::Policy::load(),
         }
     }
+
+    /// The `API_KEY` from the environment, for the endpoint `API_BASE_URL`
+    /// names only: a key meant for one provider never goes to another.
+    pub fn env_key_for(&self, base_url: &str) -> Option<String> {
+        let same = self.api_base_url.trim_end_matches('/') == base_url.trim_end_matches('/');
+        self.api_key.clone().filter(|_| same)
+    }
 }
@@ -0,0 +1,102 @@
+//! Correction learning and the replacement dictionary.
+//!
+//! When a transcript is edited in History, the changed phrases are turned
+//! into replacement suggestions (`original → corrected`). Accepted
+//! suggestions are applied to every new transcript, so recurring
+//! mis-hearings (names, jargon) fix themselves over time.
+
+/// Longest phrase (in words) that becomes a suggestion; larger edits are
+/// rewrites rather than corrections.
+pub const MAX_PHRASE_WORDS: usize = 6;
+
+/// Word-level diff of an edit, returning `(original, corrected)` phrase
+/// pairs for every replaced span. Pure insertions and deletions are skipped
+/// since a replacement rule needs something to match.
+pub fn suggestions(original: &str, corrected: &str) -> Vec<(String, String)> {
+    let a: Vec<&str> = original.split_whitespace().collect();
+    let b: Vec<&str> = corrected.split_whitespace().collect();
+
+    // Longest common subsequence table over words
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_a, mut hunk_b): (Vec<&str>, Vec<&str>) = (Vec::new(), Vec::new());
+    loop {
+        let at_end = i == a.len() && j == b.len();
+        if at_end || (i < a.len() && j < b.len() && a[i] == b[j]) {
+            push_hunk(&mut out, &hunk_a, &hunk_b);
+            hunk_a.clear();
+            hunk_b.clear();
+            if at_end {
+                break;
+            }
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            hunk_b.push(b[j]);
+            j += 1;
+        } else {
+            hunk_a.push(a[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+fn push_hunk(out: &mut Vec<(String, String)>, a: &[&str], b: &[&str]) {
+    if a.is_empty() || b.is_empty() || a.len() > MAX_PHRASE_WORDS || b.len() > MAX_PHRASE_WORDS {
+        return;
+    }
+    let (from, to) = strip_shared_punctuation(a.join(" "), b.join(" "));
+    if !from.is_empty() && !to.is_empty() && from != to {
+        out.push((from, to));
+    }
+}
+
+/// Drop trailing punctuation both sides share, so `teh,` → `the,` learns `teh` → `the`.
+fn strip_shared_punctuation(mut a: String, mut b: String) -> (String, String) {
+    while let (Some(ca), Some(cb)) = (a.chars().last(), b.chars().last()) {
+        if ca == cb && ca.is_ascii_punctuation() {
+            a.pop();
+            b.pop();
+        } else {
+            break;
+        }
+    }
+    (a, b)
+}
+
+/// Apply `(original, replacement)` rules to `text`, matching whole words only.
+pub fn apply(text: &str, rules: &[(String, String)]) -> String {
+    let mut text = text.to_string();
+    for (from, to) in rules {
+        if from.is_empty() {
+            continue;
+        }
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(pos) = rest.find(from.as_str()) {
+            result.push_str(&rest[..pos]);
+            // What precedes the match in the text, even a skipped match
+            let before = result.chars().next_back();
+            let after = rest[pos + from.len()..].chars().next();
+            let boundary = !before.is_some_and(char::is_alphanumeric)
+                && !after.is_some_and(char::is_alphanumeric);
+            result.push_str(if boundary { to } else { from });
+            rest = &rest[pos + from.len()..];
+        }
+        result.push_str(rest);
+        text = result;
+    }
+    text
+}
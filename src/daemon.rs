@@ -0,0 +1,451 @@
+//! Daemon mode — dictation without GTK.
+//!
+//! `whispercrabs --daemon` runs the recorder, the transcription pipeline,
+//! History and both D-Bus interfaces in a plain GIO application: no GTK and
+//! no window. It suits window-manager setups driven entirely from
+//! keybindings — bind the `record`/`stop` actions or the
+//! `org.whispercrabs.Control` methods — and delivers each transcript to the
+//! clipboard and as a desktop notification.
+//!
+//! The daemon holds the application id, so it doesn't run alongside the
+//! floating button. Providers, keys, models, language, History choice and
+//! the input device come from the settings the app saved.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use crate::activation::ActivationSource;
+use crate::audio::Recorder;
+use crate::config::Config;
+use crate::db::{Db, HistoryMode};
+use crate::local_stt::Transcript;
+use crate::transcription::EngineCache;
+
+/// Launch flag of daemon mode.
+pub const FLAG: &str = "--daemon";
+
+const APP_ID: &str = "dev.whispercrabs.app";
+
+/// Notification id of the status text.
+const STATUS_ID: &str = "status";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Idle,
+    Recording,
+    Processing,
+}
+
+impl State {
+    /// Name as `GetState` and `StateChanged` report it.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Recording => "recording",
+            Self::Processing => "processing",
+        }
+    }
+}
+
+struct Daemon {
+    app: gio::Application,
+    config: Arc<Config>,
+    db: Arc<Mutex<Db>>,
+    engines: Arc<EngineCache>,
+    recorder: RefCell<Recorder>,
+    state: Cell<State>,
+    /// The last delivered transcript.
+    last: RefCell<String>,
+    /// Media players paused for the running recording.
+    paused_media: RefCell<Option<crate::media::PausedPlayers>>,
+    /// Desktop notifications silenced for the running recording.
+    silenced: RefCell<Option<crate::silence::Silenced>>,
+}
+
+/// Run the daemon until it is quit.
+pub fn run(config: Arc<Config>) -> Result<(), String> {
+    let db = crate::transcription::open_history(&config)?;
+    crate::silence::restore_after_crash(&db);
+    crate::policy::set_local_only(crate::transcription::local_only(&db, &config));
+    // Quiet hours hold back notifications as in the app
+    crate::quiet::set_schedule(match db.get_setting("quiet_hours").ok().flatten() {
+        Some(value) => crate::quiet::parse_schedule(&value),
+        None => config.quiet_hours,
+    });
+    crate::quiet::set_dnd(
+        db.get_setting("dnd")
+            .ok()
+            .flatten()
+            .and_then(|v| crate::quiet::Dnd::parse(&v))
+            .unwrap_or_default(),
+    );
+
+    let app = gio::Application::new(Some(APP_ID), gio::ApplicationFlags::empty());
+    app.register(gio::Cancellable::NONE)
+        .map_err(|e| format!("Can't register on the session bus: {e}"))?;
+    if app.is_remote() {
+        return Err("WhisperCrabs is already running".into());
+    }
+    // Launching again while running just activates the daemon
+    app.connect_activate(|_| {});
+
+    let daemon = Rc::new(Daemon {
+        app: app.clone(),
+        config,
+        db: Arc::new(Mutex::new(db)),
+        engines: Arc::new(EngineCache::default()),
+        recorder: RefCell::new(Recorder::new()),
+        state: Cell::new(State::Idle),
+        last: RefCell::new(String::new()),
+        paused_media: RefCell::new(None),
+        silenced: RefCell::new(None),
+    });
+    add_actions(&daemon);
+    let control = start_control(&daemon)?;
+
+    let _hold = app.hold();
+    tracing::info!("WhisperCrabs daemon running; control it with D-Bus");
+    app.run_with_args(&[] as &[&str]);
+    drop(control);
+    Ok(())
+}
+
+/// The app actions, named as in the window so the same `gdbus` calls work.
+fn add_actions(daemon: &Rc<Daemon>) {
+    let app = &daemon.app;
+    let action = |name: &str, run: fn(&Rc<Daemon>)| {
+        let action = gio::SimpleAction::new(name, None);
+        let daemon = Rc::clone(daemon);
+        action.connect_activate(move |_, _| run(&daemon));
+        app.add_action(&action);
+    };
+    action("record", Daemon::start_recording);
+    action("stop", Daemon::stop_recording);
+    action("toggle-recording", |d| match d.state.get() {
+        State::Idle => d.start_recording(),
+        State::Recording => d.stop_recording(),
+        State::Processing => {}
+    });
+    action("quit", |d| d.app.quit());
+
+    let status = gio::SimpleAction::new_stateful(STATUS_ID, None, &"".to_variant());
+    app.add_action(&status);
+
+    // Notification button: put a transcript back on the clipboard
+    let copy = gio::SimpleAction::new("copy-transcript", Some(glib::VariantTy::STRING));
+    copy.connect_activate(|_, param| {
+        if let Some(text) = param.and_then(|p| p.get::<String>())
+            && let Err(e) = crate::input::copy_to_clipboard(&text)
+        {
+            tracing::warn!("{e}");
+        }
+    });
+    app.add_action(&copy);
+
+    let provider = daemon
+        .db
+        .lock()
+        .map(|d| crate::transcription::stored_provider(&d, &daemon.config))
+        .unwrap_or_default();
+    let mode = gio::SimpleAction::new_stateful(
+        "transcription-mode",
+        Some(glib::VariantTy::STRING),
+        &provider.to_variant(),
+    );
+    let daemon_mode = Rc::clone(daemon);
+    mode.connect_activate(move |action, param| {
+        let Some(provider) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        match daemon_mode.set_provider(&provider) {
+            Ok(()) => action.set_state(&provider.to_variant()),
+            Err(e) => daemon_mode.flash_status(&e),
+        }
+    });
+    app.add_action(&mode);
+}
+
+/// Serve `org.whispercrabs.Control` and run its activations.
+fn start_control(daemon: &Rc<Daemon>) -> Result<Rc<crate::dbus::ControlSource>, String> {
+    let handler_daemon = Rc::clone(daemon);
+    let handler: crate::dbus::Handler = Rc::new(move |request| {
+        let d = &handler_daemon;
+        match request {
+            crate::dbus::Request::LastTranscript => {
+                Ok(Some((d.last.borrow().clone(),).to_variant()))
+            }
+            crate::dbus::Request::State => Ok(Some((d.state.get().name(),).to_variant())),
+            crate::dbus::Request::SetProvider(provider) => {
+                d.app
+                    .activate_action("transcription-mode", Some(&provider.to_variant()));
+                let current = d
+                    .app
+                    .action_state("transcription-mode")
+                    .and_then(|s| s.get::<String>());
+                if current.as_deref() == Some(provider.as_str()) {
+                    Ok(None)
+                } else {
+                    Err(format!("Can't switch to \"{provider}\""))
+                }
+            }
+            crate::dbus::Request::Activate(_) => Ok(None),
+        }
+    });
+    let mut source = crate::dbus::ControlSource::new(handler);
+    let rx = source.start()?;
+    let source = Rc::new(source);
+    let (weak, daemon) = (Rc::downgrade(&source), Rc::clone(daemon));
+    glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
+        if let Some(source) = weak.upgrade() {
+            source.state_changed(daemon.state.get().name());
+        }
+        loop {
+            let activation = match rx.try_recv() {
+                Ok(activation) => activation,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    return glib::ControlFlow::Continue;
+                }
+                Err(_) => return glib::ControlFlow::Break,
+            };
+            let recording = match daemon.state.get() {
+                State::Idle => false,
+                State::Recording => true,
+                State::Processing => continue,
+            };
+            match activation.resolve(recording) {
+                Some(true) => daemon.start_recording(),
+                Some(false) => daemon.stop_recording(),
+                None => {}
+            }
+        }
+    });
+    Ok(source)
+}
+
+impl Daemon {
+    fn setting(&self, key: &str) -> Option<String> {
+        self.db
+            .lock()
+            .ok()
+            .and_then(|d| d.get_setting(key).ok().flatten())
+    }
+
+    fn start_recording(self: &Rc<Self>) {
+        if self.state.get() != State::Idle {
+            return;
+        }
+        let device = self.setting("audio_device").filter(|d| !d.is_empty());
+        let started = {
+            let mut recorder = self.recorder.borrow_mut();
+            recorder.set_device(device);
+            recorder.start()
+        };
+        match started {
+            Ok(()) => {
+                self.state.set(State::Recording);
+                self.show_status("Recording...");
+                let (allowlist, silence) = self
+                    .db
+                    .lock()
+                    .ok()
+                    .map(|d| (crate::media::stored(&d), crate::silence::enabled(&d)))
+                    .unwrap_or_default();
+                if let Some(allowlist) = allowlist {
+                    *self.paused_media.borrow_mut() = Some(crate::media::pause_playing(&allowlist));
+                }
+                if silence {
+                    *self.silenced.borrow_mut() = Some(crate::silence::silence(&self.db));
+                }
+            }
+            Err(e) => self.flash_status(&e),
+        }
+    }
+
+    fn stop_recording(self: &Rc<Self>) {
+        if self.state.get() != State::Recording {
+            return;
+        }
+        // Resumes the players paused for this recording, and notifications
+        self.paused_media.borrow_mut().take();
+        self.silenced.borrow_mut().take();
+        let (wav, sample_rate) = {
+            let mut recorder = self.recorder.borrow_mut();
+            (recorder.stop(), recorder.sample_rate())
+        };
+        let wav = match wav {
+            Ok(wav) => wav,
+            Err(e) => return self.fail(&e),
+        };
+        let secs = crate::audio::wav_duration_secs(&wav).unwrap_or_default();
+        if secs * 1000.0 < self.config.min_recording_ms as f64 {
+            return self.fail("Too short");
+        }
+
+        let (provider, language, vocabulary, route, post_process) = {
+            let Ok(d) = self.db.lock() else {
+                return self.fail("Database unavailable");
+            };
+            let provider = crate::transcription::stored_provider(&d, &self.config);
+            let language = crate::transcription::stored_language(&d);
+            let vocabulary = crate::vocabulary::stored(&d);
+            let post_process = crate::postprocess::job(&d, &self.config).unwrap_or_else(|e| {
+                tracing::warn!("Post-processing skipped: {e}");
+                None
+            });
+            // No window to ask for upload consent; the app must have had it
+            match crate::transcription::route(&d, &self.config, &provider).and_then(|route| {
+                crate::transcription::ensure_consent(&d, &route)?;
+                Ok(route)
+            }) {
+                Ok(route) => (provider, language, vocabulary, route, post_process),
+                Err(e) => return self.fail(&e),
+            }
+        };
+        self.state.set(State::Processing);
+        self.show_status("Transcribing...");
+
+        let (config, engines) = (Arc::clone(&self.config), Arc::clone(&self.engines));
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = crate::transcription::transcribe(
+                &config,
+                &route,
+                &engines,
+                wav,
+                sample_rate,
+                &language,
+                false,
+                &vocabulary,
+            )
+            .map(|mut transcript| {
+                let raw = post_process
+                    .as_ref()
+                    .and_then(|job| crate::postprocess::rewrite(job, &mut transcript));
+                (transcript, raw)
+            });
+            let _ = tx.send(result);
+        });
+        let daemon = Rc::clone(self);
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok(result) => daemon.deliver(result, &provider),
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    return glib::ControlFlow::Continue;
+                }
+                Err(_) => daemon.fail("Transcription stopped"),
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Copy, store and announce a finished transcription; `raw` is its text
+    /// before post-processing, if it was rewritten.
+    fn deliver(&self, result: Result<(Transcript, Option<String>), String>, provider: &str) {
+        let (transcript, raw) = match result {
+            Ok(done) => done,
+            Err(e) => return self.fail(&crate::errors::summary(&e)),
+        };
+        let language = transcript.language.as_deref();
+        let text = {
+            let Ok(d) = self.db.lock() else {
+                return self.fail("Database unavailable");
+            };
+            let rules = d.accepted_replacements(language).unwrap_or_default();
+            let mut text = crate::corrections::apply(&transcript.text, &rules);
+            if let Some(commands) = crate::dictation_commands::stored(&d) {
+                text = crate::dictation_commands::apply(&text, &commands);
+            }
+            let mode = crate::transcription::stored_history_mode(&d);
+            if !text.trim().is_empty() {
+                match crate::transcription::store(&d, &text, language, mode) {
+                    Ok(Some(id)) if mode == HistoryMode::Full => {
+                        if let Some(raw) = raw
+                            && let Err(e) = d.set_raw_text(id, &raw)
+                        {
+                            tracing::error!("DB raw text update error: {e}");
+                        }
+                        if let Some(confidence) = crate::confidence::score(&transcript.segments)
+                            && let Err(e) = d.set_confidence(id, confidence)
+                        {
+                            tracing::error!("DB confidence update error: {e}");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("DB insert error: {e}"),
+                }
+            }
+            text
+        };
+        if text.trim().is_empty() {
+            return self.fail("No speech detected");
+        }
+        if let Err(e) = crate::input::copy_to_clipboard(&text) {
+            return self.fail(&e);
+        }
+        self.state.set(State::Idle);
+        self.show_status("");
+        crate::notify::send(&text, false);
+        crate::dbus::transcription_done(&text, provider);
+        if let Ok(d) = self.db.lock() {
+            crate::hooks::fire(
+                crate::hooks::stored(&d),
+                crate::hooks::Event::new(chrono::Local::now(), &text, provider),
+            );
+        }
+        *self.last.borrow_mut() = text;
+    }
+
+    /// Switch the provider dictations use, if it is set up.
+    fn set_provider(&self, provider: &str) -> Result<(), String> {
+        if self.state.get() != State::Idle {
+            return Err("Busy, try again when idle".into());
+        }
+        let d = self.db.lock().map_err(|e| e.to_string())?;
+        crate::transcription::route(&d, &self.config, provider)?;
+        d.set_setting("transcription_mode", provider)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Back to idle, showing `error` for a moment.
+    fn fail(&self, error: &str) {
+        tracing::error!("{error}");
+        self.state.set(State::Idle);
+        self.flash_status(error);
+    }
+
+    /// Publish `text` as the status, cleared after 3 s unless it has
+    /// changed by then.
+    fn flash_status(&self, text: &str) {
+        self.show_status(text);
+        let (app, text) = (self.app.clone(), text.to_string());
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            let current = app.action_state(STATUS_ID).and_then(|s| s.get::<String>());
+            if current.as_deref() == Some(text.as_str()) {
+                publish_status(&app, "");
+            }
+        });
+    }
+
+    fn show_status(&self, text: &str) {
+        publish_status(&self.app, text);
+    }
+}
+
+/// Mirror `text` into the `status` action and a desktop notification that
+/// is replaced in place and withdrawn when the status clears.
+fn publish_status(app: &gio::Application, text: &str) {
+    dbg_log!("[STATUS] {text}");
+    app.change_action_state(STATUS_ID, &text.to_variant());
+    if text.is_empty() {
+        app.withdraw_notification(STATUS_ID);
+    } else if !crate::quiet::is_quiet() {
+        let notification = gio::Notification::new("WhisperCrabs");
+        notification.set_body(Some(text));
+        app.send_notification(Some(STATUS_ID), &notification);
+    }
+}
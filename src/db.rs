@@ -1,5 +1,9 @@
+use crate::local_stt::{Segment, Transcript, Word};
+use crate::sync::{SyncEntry, content_hash};
 use rusqlite::{Connection, Result, params};
-use std::path::Path;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Allowed setting keys (prevents arbitrary key/value injection).
 /// Allowed setting key prefixes (prevents arbitrary key/value injection).
@@ -10,49 +14,889 @@ const VALID_SETTING_PREFIXES: &[&str] = &[
     "api_custom_url",
     "api_custom_key",
     "api_custom_model",
+    "api_custom_path",
     "api_key_",
     "tts_provider",
     "tts_voice",
+    "profile_rules",
+    "profile_rule_base",
+    "local_only",
+    "cloud_consent_",
+    "stt_language",
+    "local_custom_model",
+    "record_profiles",
+    "learn_corrections",
+    "history_mode",
+    "history_lock",
+    "label_speakers",
+    "wake_word",
+    "live_dictation",
+    "dictation_commands",
+    "monitor_dots",
+    "text_retention_days",
+    "audio_retention_days",
+    "history_paste",
+    "store_audio",
+    "sound_",
+    "quiet_hours",
+    "dnd",
+    "remote_control",
+    "sync_dir",
+    "autosave_",
+    "hook_",
+    "pause_media",
+    "silence_notifications",
+    "review_transcripts",
+    "confidence_",
+    "announce_transcripts",
+    "api_model_",
+    "audio_device",
+    "auto_paste",
+    "coaching_hints",
+    "auto_split_minutes",
+    "meeting_",
+    "theme",
+    "widget_size",
+    "keep_above",
+    "all_workspaces",
+    "screen_corner",
+    "window_corner",
+    "window_offset",
+    "window_monitor",
+    "tray_icon",
+    "hide_button",
+    "notify_transcripts",
+    "portal_shortcuts",
+    "shortcut_trigger_",
+    "keybinding_",
+    "postprocess_",
+    "vocabulary",
 ];
 
+/// One step of the History schema (see [`SCHEMA_MIGRATIONS`]).
+pub struct SchemaMigration {
+    pub description: &'static str,
+    pub apply: fn(&Connection) -> Result<()>,
+}
+
+/// Schema migrations, oldest first. A database records how many it has
+/// applied in `PRAGMA user_version`; each step runs in its own transaction
+/// together with the version bump, so an interrupted upgrade leaves the
+/// last complete version behind. Only append: the position of an entry is
+/// its version. The first nine steps predate versioning and are idempotent,
+/// since histories from then start at version 0 with any of them applied.
+pub const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        description: "tables",
+        apply: create_tables,
+    },
+    SchemaMigration {
+        description: "transcriptions.language",
+        apply: |c| add_column_if_missing(c, "transcriptions", "language", "TEXT"),
+    },
+    SchemaMigration {
+        description: "replacements.language",
+        apply: |c| add_column_if_missing(c, "replacements", "language", "TEXT"),
+    },
+    SchemaMigration {
+        description: "transcriptions.audio_path",
+        apply: |c| add_column_if_missing(c, "transcriptions", "audio_path", "TEXT"),
+    },
+    SchemaMigration {
+        description: "transcriptions.revision_of",
+        apply: |c| add_column_if_missing(c, "transcriptions", "revision_of", "INTEGER"),
+    },
+    SchemaMigration {
+        description: "transcriptions.provider",
+        apply: |c| add_column_if_missing(c, "transcriptions", "provider", "TEXT"),
+    },
+    SchemaMigration {
+        description: "transcriptions.note",
+        apply: |c| add_column_if_missing(c, "transcriptions", "note", "TEXT"),
+    },
+    SchemaMigration {
+        description: "transcriptions.pinned",
+        apply: |c| {
+            add_column_if_missing(c, "transcriptions", "pinned", "INTEGER NOT NULL DEFAULT 0")
+        },
+    },
+    SchemaMigration {
+        description: "usage.words",
+        apply: |c| add_column_if_missing(c, "usage", "words", "INTEGER"),
+    },
+    SchemaMigration {
+        description: "usage.level_db",
+        apply: |c| add_column_if_missing(c, "usage", "level_db", "REAL"),
+    },
+    SchemaMigration {
+        description: "transcriptions.raw_text",
+        apply: |c| add_column_if_missing(c, "transcriptions", "raw_text", "TEXT"),
+    },
+    SchemaMigration {
+        description: "transcriptions.confidence",
+        apply: |c| add_column_if_missing(c, "transcriptions", "confidence", "REAL"),
+    },
+];
+
+/// Start of the error message for a History written by a newer release.
+const NEWER_SCHEMA: &str = "History schema is newer than this version of WhisperCrabs";
+
+/// A setting renamed, or given a new spelling of its value, between
+/// releases (see [`SETTING_MIGRATIONS`]).
+pub struct SettingMigration {
+    /// Key the value was stored under.
+    pub from_key: &'static str,
+    /// Key it is stored under now; the same key when only the value changed.
+    pub to_key: &'static str,
+    /// An old value and its replacement; `None` carries any value over.
+    pub value: Option<(&'static str, &'static str)>,
+}
+
+/// Settings migrations, oldest first, each applied once at startup (tracked
+/// by the `settings_version` setting). Renames go here instead of legacy
+/// handling where settings are read, so an old value is never mistaken for
+/// an unknown one. Only append: the position of an entry is its version.
+pub const SETTING_MIGRATIONS: &[SettingMigration] = &[
+    // Before local model presets, "local" meant the default local model
+    SettingMigration {
+        from_key: "transcription_mode",
+        to_key: "transcription_mode",
+        value: Some(("local", crate::config::DEFAULT_LOCAL_MODEL)),
+    },
+];
+
+/// Settings key counting the [`SETTING_MIGRATIONS`] already applied.
+const SETTINGS_VERSION: &str = "settings_version";
+
+/// Characters of a transcript kept in [`HistoryMode::Preview`].
+pub const HISTORY_PREVIEW_CHARS: usize = 40;
+
+/// Longest setting value stored; longer ones are cut at a character boundary.
+pub const MAX_SETTING_BYTES: usize = 4096;
+
+/// What a finished dictation leaves behind in History.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryMode {
+    /// The full transcript (and its word timings).
+    #[default]
+    Full,
+    /// Only the first [`HISTORY_PREVIEW_CHARS`] characters.
+    Preview,
+    /// No history row at all.
+    Off,
+}
+
+impl HistoryMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "full" => Some(Self::Full),
+            "preview" => Some(Self::Preview),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Preview => "preview",
+            Self::Off => "off",
+        }
+    }
+
+    /// The text to store for a transcript, or `None` to store nothing.
+    pub fn stored_text(self, text: &str) -> Option<String> {
+        match self {
+            Self::Full => Some(text.to_string()),
+            Self::Preview => {
+                let mut chars = text.chars();
+                let preview: String = chars.by_ref().take(HISTORY_PREVIEW_CHARS).collect();
+                Some(if chars.next().is_some() {
+                    format!("{}\u{2026}", preview.trim_end())
+                } else {
+                    preview
+                })
+            }
+            Self::Off => None,
+        }
+    }
+}
+
 /// SQLite database for transcription history and settings.
 pub struct Db {
     conn: Connection,
+    /// Settings of a safe-mode session, see [`Self::enter_safe_mode`].
+    safe_settings: Option<RefCell<BTreeMap<String, String>>>,
+}
+
+/// Aggregated usage for one provider in one month.
+pub struct UsageSummary {
+    pub month: String, // "YYYY-MM"
+    pub provider: String,
+    pub transcriptions: i64,
+    pub audio_secs: f64,
+    pub avg_latency_ms: f64,
+}
+
+/// How [`Db::stats`] groups dictations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl StatsPeriod {
+    pub const ALL: [Self; 3] = [Self::Day, Self::Week, Self::Month];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Day => "Per day",
+            Self::Week => "Per week",
+            Self::Month => "Per month",
+        }
+    }
+
+    /// SQLite `strftime` format naming the period, e.g. `2026-03-09`,
+    /// `2026-W10` or `2026-03`.
+    fn strftime(self) -> &'static str {
+        match self {
+            Self::Day => "%Y-%m-%d",
+            Self::Week => "%Y-W%W",
+            Self::Month => "%Y-%m",
+        }
+    }
+}
+
+/// Dictation statistics for one provider in one period.
+pub struct DictationStats {
+    pub period: String,
+    pub provider: String,
+    pub sessions: i64,
+    pub words: i64,
+    pub audio_secs: f64,
+    /// Audio of the sessions with a word count, for the speaking rate.
+    pub speech_secs: f64,
+    pub avg_latency_ms: f64,
+}
+
+impl DictationStats {
+    /// Average speaking rate, `None` without enough counted speech.
+    pub fn words_per_minute(&self) -> Option<f64> {
+        crate::coaching::words_per_minute(self.words, self.speech_secs)
+    }
+}
+
+/// A learned replacement rule (`original` → `replacement`).
+pub struct Replacement {
+    pub id: i64,
+    pub original: String,
+    pub replacement: String,
+    pub accepted: bool,
+    /// Language of the entry the rule was learned from (`None`: all languages).
+    pub language: Option<String>,
 }
 
 /// A single transcription record.
+#[derive(Clone)]
 pub struct Transcription {
-    pub _id: i64,
+    pub id: i64,
+    pub text: String,
+    pub created_at: String,
+    /// Spoken language (ISO 639-1), when the engine reported one.
+    pub language: Option<String>,
+    /// The recording this transcript came from, when audio is kept.
+    pub audio_path: Option<String>,
+}
+
+/// A re-transcription of a History entry's recording with another provider.
+pub struct Revision {
+    pub id: i64,
     pub text: String,
     pub created_at: String,
+    /// Provider id that produced it (API preset, `custom` or a local model).
+    pub provider: Option<String>,
 }
 
+/// What [`Db::import`] did with the entries it was given.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Entries already in History (or empty).
+    pub skipped: usize,
+}
+
+/// How long to wait for another process holding the database lock.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 impl Db {
     pub fn open(path: &Path) -> Result<Self> {
         let conn = Connection::open(path)?;
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS transcriptions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                text TEXT NOT NULL,
-                created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Self::init(conn)
+    }
+
+    /// Open a database encrypted with `key` (see [`Self::set_encryption`]).
+    /// A wrong key fails with "file is not a database", see
+    /// [`is_wrong_key_error`].
+    pub fn open_encrypted(path: &Path, key: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "key", key)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Self::init(conn)
+    }
+
+    /// Whether this database is an encrypted file on disk.
+    pub fn is_encrypted(&self) -> bool {
+        self.file().is_some_and(|path| is_encrypted(&path))
+    }
+
+    /// Rewrite the database encrypted with `key`, or as plain SQLite with
+    /// `None`, and carry on with the rewritten file. Needs a build with the
+    /// `encryption` feature (SQLCipher).
+    pub fn set_encryption(&mut self, key: Option<&str>) -> std::result::Result<(), String> {
+        if !encryption_available() {
+            return Err(
+                "This build can't encrypt History (build with --features encryption)".into(),
             );
-            CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            );",
-        )?;
-        Ok(Self { conn })
+        }
+        let path = self
+            .file()
+            .ok_or("History isn't saved to disk this session")?;
+        let rewritten = path.with_extension("db.rekey");
+        let _ = std::fs::remove_file(&rewritten);
+        let export = || -> Result<()> {
+            let version: usize = self
+                .conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            self.conn.execute(
+                "ATTACH DATABASE ?1 AS rekeyed KEY ?2",
+                params![rewritten.to_string_lossy(), key.unwrap_or("")],
+            )?;
+            let exported = self
+                .conn
+                .query_row("SELECT sqlcipher_export('rekeyed')", [], |_| Ok(()))
+                // The export doesn't carry the schema version over
+                .and_then(|()| {
+                    self.conn
+                        .execute_batch(&format!("PRAGMA rekeyed.user_version = {version}"))
+                });
+            self.conn.execute("DETACH DATABASE rekeyed", [])?;
+            exported
+        };
+        if let Err(e) = export() {
+            let _ = std::fs::remove_file(&rewritten);
+            return Err(format!("Rewriting History failed: {e}"));
+        }
+        // Close the old file before the rewritten one takes its place
+        self.conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        std::fs::rename(&rewritten, &path).map_err(|e| format!("Replacing History failed: {e}"))?;
+        let marker = encryption_marker(&path);
+        if key.is_some() {
+            if let Err(e) = std::fs::write(&marker, b"") {
+                tracing::warn!("Can't write {}: {e}", marker.display());
+            }
+        } else {
+            let _ = std::fs::remove_file(&marker);
+        }
+        let safe_settings = self.safe_settings.take();
+        *self = match key {
+            Some(key) => Self::open_encrypted(&path, key),
+            None => Self::open(&path),
+        }
+        .map_err(|e| e.to_string())?;
+        self.safe_settings = safe_settings;
+        Ok(())
+    }
+
+    /// The file behind this database, `None` in memory.
+    fn file(&self) -> Option<PathBuf> {
+        self.conn
+            .path()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// A database that lives only for this session, used when the file on
+    /// disk can't be opened.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// Bring the schema up to date, see [`SCHEMA_MIGRATIONS`].
+    fn init(conn: Connection) -> Result<Self> {
+        migrate_schema(&conn, SCHEMA_MIGRATIONS)?;
+        let db = Self {
+            conn,
+            safe_settings: None,
+        };
+        db.migrate_settings(SETTING_MIGRATIONS)?;
+        Ok(db)
     }
 
     pub fn insert(&self, text: &str) -> Result<i64> {
+        self.insert_with_language(text, None)
+    }
+
+    /// Insert a transcription tagged with its spoken language (ISO 639-1).
+    pub fn insert_with_language(&self, text: &str, language: Option<&str>) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO transcriptions (text) VALUES (?1)",
-            params![text],
+            "INSERT INTO transcriptions (text, language) VALUES (?1, ?2)",
+            params![text, language],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Replace the text of a transcription (edited in History).
+    pub fn update_text(&self, id: i64, text: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET text = ?1 WHERE id = ?2",
+            params![text, id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a transcription with its word timings, segments and tags. Its usage
+    /// record is kept for the statistics but no longer points at it.
+    pub fn delete(&self, id: i64) -> Result<()> {
+        self.delete_many(&[id])
+    }
+
+    /// Delete several transcriptions in one transaction, see [`Self::delete`].
+    /// Revisions of a deleted entry go with it.
+    pub fn delete_many(&self, ids: &[i64]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut all = ids.to_vec();
+        {
+            let mut stmt = tx.prepare("SELECT id FROM transcriptions WHERE revision_of = ?1")?;
+            for id in ids {
+                for revision in stmt.query_map(params![id], |row| row.get(0))? {
+                    all.push(revision?);
+                }
+            }
+        }
+        for id in &all {
+            tx.execute("DELETE FROM words WHERE transcription_id = ?1", params![id])?;
+            tx.execute(
+                "DELETE FROM segments WHERE transcription_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM tags WHERE transcription_id = ?1", params![id])?;
+            tx.execute(
+                "UPDATE usage SET transcription_id = NULL WHERE transcription_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM transcriptions WHERE id = ?1", params![id])?;
+        }
+        tx.commit()
+    }
+
+    /// Ids of transcriptions created more than `days` days ago.
+    pub fn ids_older_than(&self, days: u32) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM transcriptions
+             WHERE created_at < datetime('now', 'localtime', ?1)
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![format!("-{days} days")], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Delete every transcription (see [`Self::delete`]); settings, usage
+    /// statistics and learned replacements stay.
+    pub fn clear(&self) -> Result<()> {
+        self.conn.execute_batch(
+            "BEGIN;
+             DELETE FROM words;
+             DELETE FROM segments;
+             DELETE FROM tags;
+             UPDATE usage SET transcription_id = NULL;
+             DELETE FROM transcriptions;
+             COMMIT;",
+        )
+    }
+
+    /// Record where the recording of a transcription is kept.
+    pub fn set_audio_path(&self, id: i64, path: &Path) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET audio_path = ?1 WHERE id = ?2",
+            params![path.to_string_lossy(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Kept recordings as `(id, audio_path)`, newest first.
+    pub fn recordings(&self) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, audio_path FROM transcriptions
+             WHERE audio_path IS NOT NULL ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Replace a transcript with a new transcription of the same recording:
+    /// text, language, word timings and segments.
+    pub fn replace_transcript(
+        &self,
+        id: i64,
+        text: &str,
+        language: Option<&str>,
+        words: &[Word],
+        segments: &[Segment],
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET text = ?1, language = ?2 WHERE id = ?3",
+            params![text, language, id],
+        )?;
+        self.conn
+            .execute("DELETE FROM words WHERE transcription_id = ?1", params![id])?;
+        self.conn.execute(
+            "DELETE FROM segments WHERE transcription_id = ?1",
+            params![id],
+        )?;
+        self.insert_words(id, words)?;
+        self.insert_segments(id, segments)
+    }
+
+    /// Store a new transcription of `original`'s recording, made with
+    /// `provider`, as a revision linked to it. The revision shares the
+    /// original's recording. Returns the revision's id.
+    pub fn insert_revision(
+        &self,
+        original: i64,
+        provider: &str,
+        transcript: &Transcript,
+    ) -> Result<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+        let inserted = tx.execute(
+            "INSERT INTO transcriptions (text, language, audio_path, revision_of, provider)
+             SELECT ?1, ?2, audio_path, id, ?3 FROM transcriptions WHERE id = ?4",
+            params![transcript.text, transcript.language, provider, original],
+        )?;
+        if inserted == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        let id = self.conn.last_insert_rowid();
+        self.insert_words(id, &transcript.words)?;
+        self.insert_segments(id, &transcript.segments)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Revisions of `original`, oldest first.
+    pub fn revisions(&self, original: i64) -> Result<Vec<Revision>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, created_at, provider FROM transcriptions
+             WHERE revision_of = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![original], |row| {
+            Ok(Revision {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                created_at: row.get(2)?,
+                provider: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Store a learned correction as a suggestion. An existing suggestion for
+    /// the same phrase is updated; accepted rules are left alone. `language`
+    /// is that of the edited entry: the rule then only applies to transcripts
+    /// in that language (`None` applies everywhere).
+    pub fn suggest_replacement(
+        &self,
+        original: &str,
+        replacement: &str,
+        language: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO replacements (original, replacement, language) VALUES (?1, ?2, ?3)
+             ON CONFLICT(original) DO UPDATE SET replacement = excluded.replacement,
+                                                 language = excluded.language
+             WHERE accepted = 0",
+            params![original, replacement, language],
+        )?;
+        Ok(())
+    }
+
+    /// All replacement rules, pending suggestions first.
+    pub fn replacements(&self) -> Result<Vec<Replacement>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, original, replacement, accepted, language FROM replacements
+             ORDER BY accepted, id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Replacement {
+                id: row.get(0)?,
+                original: row.get(1)?,
+                replacement: row.get(2)?,
+                accepted: row.get(3)?,
+                language: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Accepted rules for a transcript in `language` as `(original,
+    /// replacement)` pairs, oldest first. Rules without a language always
+    /// apply; with an unknown `language`, every rule does. None apply in
+    /// safe mode.
+    pub fn accepted_replacements(&self, language: Option<&str>) -> Result<Vec<(String, String)>> {
+        if self.is_safe_mode() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT original, replacement FROM replacements
+             WHERE accepted = 1 AND (?1 IS NULL OR language IS NULL OR language = ?1)
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![language], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    pub fn accept_replacement(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE replacements SET accepted = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_replacement(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM replacements WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Store the word timings of a transcription (local models only).
+    pub fn insert_words(&self, transcription_id: i64, words: &[Word]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, w) in words.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO words (transcription_id, position, word, start_ms, end_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    transcription_id,
+                    position as i64,
+                    w.text,
+                    w.start_ms,
+                    w.end_ms
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Word timings of a transcription in spoken order (empty if none were stored).
+    pub fn words(&self, transcription_id: i64) -> Result<Vec<Word>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT word, start_ms, end_ms FROM words WHERE transcription_id = ?1
+             ORDER BY position",
+        )?;
+        let rows = stmt.query_map(params![transcription_id], |row| {
+            Ok(Word {
+                text: row.get(0)?,
+                start_ms: row.get(1)?,
+                end_ms: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Store the timed segments of a transcription. The flat `text` of the
+    /// transcription stays as the copyable form of the whole dictation.
+    pub fn insert_segments(&self, transcription_id: i64, segments: &[Segment]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (position, seg) in segments.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO segments
+                 (transcription_id, position, start_ms, end_ms, text, confidence)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    transcription_id,
+                    position as i64,
+                    seg.start_ms,
+                    seg.end_ms,
+                    seg.text,
+                    seg.confidence
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Segments of a transcription in spoken order (empty if none were stored).
+    pub fn segments(&self, transcription_id: i64) -> Result<Vec<Segment>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT start_ms, end_ms, text, confidence FROM segments
+             WHERE transcription_id = ?1 ORDER BY position",
+        )?;
+        let rows = stmt.query_map(params![transcription_id], |row| {
+            Ok(Segment {
+                start_ms: row.get(0)?,
+                end_ms: row.get(1)?,
+                text: row.get(2)?,
+                confidence: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Record provider, model, audio duration, latency, the number of
+    /// words dictated and the recording's loudness for one transcription.
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_usage(
+        &self,
+        transcription_id: Option<i64>,
+        provider: &str,
+        model: &str,
+        audio_secs: f64,
+        latency_ms: u64,
+        words: usize,
+        level_db: Option<f64>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO usage (transcription_id, provider, model, audio_secs, latency_ms, words, level_db)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                transcription_id,
+                provider,
+                model,
+                audio_secs,
+                latency_ms as i64,
+                words as i64,
+                level_db
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Word count, length and loudness of the last `limit` dictations that
+    /// have a word count, newest first.
+    pub fn speech_samples(&self, limit: usize) -> Result<Vec<crate::coaching::SpeechSample>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT words, audio_secs, level_db FROM usage
+             WHERE words IS NOT NULL
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(crate::coaching::SpeechSample {
+                words: row.get(0)?,
+                audio_secs: row.get(1)?,
+                level_db: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Dictations grouped by `period` and provider, newest period first.
+    /// Sessions recorded before word counts were kept count as no words.
+    pub fn stats(&self, period: StatsPeriod) -> Result<Vec<DictationStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT strftime(?1, created_at) AS period, provider,
+                    COUNT(*), COALESCE(SUM(words), 0), SUM(audio_secs),
+                    COALESCE(SUM(CASE WHEN words IS NOT NULL THEN audio_secs END), 0),
+                    AVG(latency_ms)
+             FROM usage
+             GROUP BY period, provider
+             ORDER BY period DESC, provider",
+        )?;
+        let rows = stmt.query_map(params![period.strftime()], |row| {
+            Ok(DictationStats {
+                period: row.get(0)?,
+                provider: row.get(1)?,
+                sessions: row.get(2)?,
+                words: row.get(3)?,
+                audio_secs: row.get(4)?,
+                speech_secs: row.get(5)?,
+                avg_latency_ms: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Usage grouped by month and provider, newest month first.
+    pub fn usage_by_month(&self) -> Result<Vec<UsageSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT strftime('%Y-%m', created_at) AS month, provider,
+                    COUNT(*), SUM(audio_secs), AVG(latency_ms)
+             FROM usage
+             GROUP BY month, provider
+             ORDER BY month DESC, provider",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(UsageSummary {
+                month: row.get(0)?,
+                provider: row.get(1)?,
+                transcriptions: row.get(2)?,
+                audio_secs: row.get(3)?,
+                avg_latency_ms: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Apply the `migrations` this database hasn't seen yet, in one
+    /// transaction, and record how many it has seen.
+    pub fn migrate_settings(&self, migrations: &[SettingMigration]) -> Result<()> {
+        let done = self
+            .get_setting(SETTINGS_VERSION)?
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        if done >= migrations.len() {
+            return Ok(());
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        for migration in &migrations[done..] {
+            if let Some((old, new)) = migration.value {
+                tx.execute(
+                    "UPDATE settings SET value = ?3 WHERE key = ?1 AND value = ?2",
+                    params![migration.from_key, old, new],
+                )?;
+            }
+            if migration.from_key != migration.to_key {
+                // A value already saved under the new key wins
+                tx.execute(
+                    "INSERT OR IGNORE INTO settings (key, value)
+                     SELECT ?2, value FROM settings WHERE key = ?1",
+                    params![migration.from_key, migration.to_key],
+                )?;
+                tx.execute(
+                    "DELETE FROM settings WHERE key = ?1",
+                    params![migration.from_key],
+                )?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![SETTINGS_VERSION, migrations.len().to_string()],
+        )?;
+        tx.commit()
+    }
+
+    /// Start a safe-mode session (`--safe-mode`): stored settings other than
+    /// API keys read as unset and accepted replacements don't apply, so
+    /// everything runs with its defaults. Settings changed meanwhile last until the app quits;
+    /// the stored ones are left as they are.
+    pub fn enter_safe_mode(&mut self) {
+        self.safe_settings = Some(RefCell::default());
+    }
+
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_settings.is_some()
+    }
+
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        if let Some(settings) = &self.safe_settings {
+            let value = settings.borrow().get(key).cloned();
+            // API keys aren't configuration; the default provider needs its key
+            if value.is_some() || !crate::settings_file::is_secret(key) {
+                return Ok(value);
+            }
+        }
         let mut stmt = self
             .conn
             .prepare("SELECT value FROM settings WHERE key = ?1")?;
@@ -73,8 +917,16 @@ impl Db {
                 "unknown setting key: {key}"
             )));
         }
-        // Cap value length to prevent abuse
-        let value = &value[..value.len().min(4096)];
+        // Cap value length to prevent abuse, without splitting a character
+        let mut end = value.len().min(MAX_SETTING_BYTES);
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        let value = &value[..end];
+        if let Some(settings) = &self.safe_settings {
+            settings.borrow_mut().insert(key.into(), value.into());
+            return Ok(());
+        }
         self.conn.execute(
             "INSERT INTO settings (key, value) VALUES (?1, ?2)
              ON CONFLICT(key) DO UPDATE SET value = excluded.value",
@@ -83,17 +935,536 @@ impl Db {
         Ok(())
     }
 
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        if let Some(settings) = &self.safe_settings {
+            settings.borrow_mut().remove(key);
+            return Ok(());
+        }
+        self.conn
+            .execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    /// Every stored setting, ordered by key.
+    pub fn all_settings(&self) -> Result<Vec<(String, String)>> {
+        if let Some(settings) = &self.safe_settings {
+            let settings = settings.borrow();
+            return Ok(settings
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect());
+        }
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM settings ORDER BY key")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Store `settings` from a settings export, in one transaction. Keys this
+    /// version doesn't know are skipped rather than failing the import.
+    pub fn import_settings(&self, settings: &[(String, String)]) -> Result<ImportSummary> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut summary = ImportSummary::default();
+        for (key, value) in settings {
+            match self.set_setting(key, value) {
+                Ok(()) => summary.added += 1,
+                Err(rusqlite::Error::InvalidParameterName(_)) => summary.skipped += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
+
     pub fn recent(&self, limit: usize) -> Result<Vec<Transcription>> {
+        self.recent_in_language(limit, None)
+    }
+
+    /// Most recent transcriptions, only those in `language` when given.
+    /// Revisions are left out; they are listed with their original, see
+    /// [`Self::revisions`].
+    pub fn recent_in_language(
+        &self,
+        limit: usize,
+        language: Option<&str>,
+    ) -> Result<Vec<Transcription>> {
+        self.recent_filtered(limit, language, None)
+    }
+
+    /// Most recent transcriptions, narrowed to `language` and/or `tag` when
+    /// given, see [`Self::recent_in_language`].
+    pub fn recent_filtered(
+        &self,
+        limit: usize,
+        language: Option<&str>,
+        tag: Option<&str>,
+    ) -> Result<Vec<Transcription>> {
+        self.page_filtered(0, limit, language, tag)
+    }
+
+    /// `limit` transcriptions after skipping the `offset` most recent ones,
+    /// newest first. History loads these a page at a time as it scrolls.
+    pub fn page(&self, offset: usize, limit: usize) -> Result<Vec<Transcription>> {
+        self.page_filtered(offset, limit, None, None)
+    }
+
+    /// Like [`Self::page`], narrowed to `language` and/or `tag` when given.
+    pub fn page_filtered(
+        &self,
+        offset: usize,
+        limit: usize,
+        language: Option<&str>,
+        tag: Option<&str>,
+    ) -> Result<Vec<Transcription>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, created_at, language, audio_path FROM transcriptions
+             WHERE (?3 IS NULL OR language = ?3) AND revision_of IS NULL
+               AND (?4 IS NULL OR id IN (SELECT transcription_id FROM tags WHERE tag = ?4))
+             ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+        )?;
+        let rows = stmt.query_map(params![limit as i64, offset as i64, language, tag], |row| {
+            Ok(Transcription {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                created_at: row.get(2)?,
+                language: row.get(3)?,
+                audio_path: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// All transcriptions created on or after `since` and on or before
+    /// `until` (inclusive `YYYY-MM-DD` dates, `None` for no limit), oldest
+    /// first. Used for exporting History.
+    pub fn entries_between(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<Transcription>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, created_at, language, audio_path FROM transcriptions
+             WHERE (?1 IS NULL OR date(created_at) >= ?1)
+               AND (?2 IS NULL OR date(created_at) <= ?2)
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![since, until], |row| {
+            Ok(Transcription {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                created_at: row.get(2)?,
+                language: row.get(3)?,
+                audio_path: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Merge `entries` from another machine into History, in one transaction.
+    /// Entries with the timestamp and text hash of one already here (or
+    /// earlier in `entries`) are skipped, so importing twice adds nothing.
+    pub fn import(&self, entries: &[SyncEntry]) -> Result<ImportSummary> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut summary = ImportSummary::default();
+        {
+            let mut same_time =
+                tx.prepare("SELECT text FROM transcriptions WHERE created_at = ?1")?;
+            let mut insert = tx.prepare(
+                "INSERT INTO transcriptions (text, created_at, language) VALUES (?1, ?2, ?3)",
+            )?;
+            for entry in entries {
+                let hash = content_hash(&entry.text);
+                let texts: Vec<String> = same_time
+                    .query_map(params![entry.created_at], |row| row.get(0))?
+                    .collect::<Result<_>>()?;
+                if entry.text.trim().is_empty() || texts.iter().any(|t| content_hash(t) == hash) {
+                    summary.skipped += 1;
+                    continue;
+                }
+                insert.execute(params![entry.text, entry.created_at, entry.language])?;
+                summary.added += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    /// When a transcription was created, `YYYY-MM-DD HH:MM:SS` local time.
+    pub fn created_at(&self, id: i64) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT created_at FROM transcriptions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })
+    }
+
+    /// Replace the tags of a transcription (see [`parse_tags`]).
+    pub fn set_tags(&self, id: i64, tags: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM tags WHERE transcription_id = ?1", params![id])?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (transcription_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Tags of a transcription, alphabetically.
+    pub fn tags(&self, id: i64) -> Result<Vec<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id, text, created_at FROM transcriptions ORDER BY id DESC LIMIT ?1")?;
-        let rows = stmt.query_map(params![limit as i64], |row| {
+            .prepare("SELECT tag FROM tags WHERE transcription_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map(params![id], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Every tag in use, most used first.
+    pub fn all_tags(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM tags GROUP BY tag ORDER BY COUNT(*) DESC, tag")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Pin or unpin a transcription.
+    pub fn set_pinned(&self, id: i64, pinned: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET pinned = ?1 WHERE id = ?2",
+            params![pinned, id],
+        )?;
+        Ok(())
+    }
+
+    /// Pinned transcriptions, most recently created first.
+    pub fn pinned(&self) -> Result<Vec<Transcription>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, text, created_at, language, audio_path FROM transcriptions
+             WHERE pinned = 1 ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
             Ok(Transcription {
-                _id: row.get(0)?,
+                id: row.get(0)?,
                 text: row.get(1)?,
                 created_at: row.get(2)?,
+                language: row.get(3)?,
+                audio_path: row.get(4)?,
             })
         })?;
         rows.collect()
     }
+
+    /// Whether a transcription is pinned.
+    pub fn is_pinned(&self, id: i64) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT pinned FROM transcriptions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(false),
+                e => Err(e),
+            })
+    }
+
+    /// Set or clear (`None` or blank) the free-text note of a transcription.
+    pub fn set_note(&self, id: i64, note: Option<&str>) -> Result<()> {
+        let note = note.map(str::trim).filter(|n| !n.is_empty());
+        self.conn.execute(
+            "UPDATE transcriptions SET note = ?1 WHERE id = ?2",
+            params![note, id],
+        )?;
+        Ok(())
+    }
+
+    /// The note of a transcription, if it has one.
+    pub fn note(&self, id: i64) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT note FROM transcriptions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .map(Option::flatten)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })
+    }
+
+    /// Keep the transcript as it was before post-processing rewrote it (see
+    /// [`crate::postprocess`]).
+    pub fn set_raw_text(&self, id: i64, raw: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET raw_text = ?1 WHERE id = ?2",
+            params![raw, id],
+        )?;
+        Ok(())
+    }
+
+    /// The transcript before post-processing, if it was rewritten.
+    pub fn raw_text(&self, id: i64) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT raw_text FROM transcriptions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .map(Option::flatten)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })
+    }
+
+    /// Store how confident the speech model was of a transcript (0–1, see
+    /// [`crate::confidence`]).
+    pub fn set_confidence(&self, id: i64, confidence: f32) -> Result<()> {
+        self.conn.execute(
+            "UPDATE transcriptions SET confidence = ?1 WHERE id = ?2",
+            params![confidence, id],
+        )?;
+        Ok(())
+    }
+
+    /// The confidence stored with a transcript, if the engine reported one.
+    pub fn confidence(&self, id: i64) -> Result<Option<f32>> {
+        self.conn
+            .query_row(
+                "SELECT confidence FROM transcriptions WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .map(Option::flatten)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })
+    }
+
+    /// Languages that occur in History, most frequent first.
+    pub fn languages(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT language FROM transcriptions WHERE language IS NOT NULL
+             GROUP BY language ORDER BY COUNT(*) DESC, language",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+}
+
+/// Parse tags typed as `work, ideas` or `#work #ideas`: lowercased, without
+/// `#`, deduplicated and sorted.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|t| t.trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Whether an open error means another process holds the database, rather
+/// than a damaged file.
+pub fn is_locked_error(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Shortest accepted passphrase for an encrypted History.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Whether this build can encrypt History (the `encryption` feature).
+pub fn encryption_available() -> bool {
+    cfg!(feature = "encryption")
+}
+
+/// Whether `path` holds an encrypted database: an existing file without the
+/// plain SQLite header.
+pub fn is_encrypted(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|_| &header != b"SQLite format 3\0")
+}
+
+/// The file next to `path` that says it was encrypted here, e.g.
+/// `history.db.encrypted`.
+pub fn encryption_marker(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".encrypted");
+    PathBuf::from(name)
+}
+
+/// Whether to ask for the passphrase of `path` rather than treat it as
+/// damaged: its header isn't plain SQLite, this build can decrypt, and
+/// either the keyring holds a key (`keyring_key`) or the marker left when
+/// it was encrypted is there.
+pub fn opens_encrypted(path: &Path, keyring_key: bool) -> bool {
+    encryption_available()
+        && is_encrypted(path)
+        && (keyring_key || encryption_marker(path).exists())
+}
+
+/// Whether opening an encrypted database failed because of the key.
+pub fn is_wrong_key_error(e: &rusqlite::Error) -> bool {
+    e.sqlite_error_code() == Some(rusqlite::ErrorCode::NotADatabase)
+}
+
+/// Check a new passphrase (and its confirmation) before encrypting with it.
+pub fn validate_passphrase(passphrase: &str, confirm: &str) -> std::result::Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Use at least {MIN_PASSPHRASE_LEN} characters"));
+    }
+    if passphrase != confirm {
+        return Err("The entries don't match".into());
+    }
+    Ok(())
+}
+
+/// A random key for keeping in the keyring: 32 bytes, hex encoded.
+pub fn generate_key() -> std::result::Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("random key: {e}"))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Move a database that can't be opened (and its journal files) aside to
+/// `<name>.broken-<timestamp>`, so a fresh one can be created in its place.
+/// Returns the backup path of the main file.
+pub fn back_up_broken(path: &Path) -> std::result::Result<PathBuf, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let name = path
+        .file_name()
+        .ok_or("Invalid database path")?
+        .to_string_lossy()
+        .to_string();
+    let backup = path.with_file_name(format!("{name}.broken-{stamp}"));
+    if path.exists() {
+        std::fs::rename(path, &backup).map_err(|e| format!("Back up database: {e}"))?;
+    }
+    for suffix in ["-wal", "-shm", "-journal", ".encrypted"] {
+        let side = path.with_file_name(format!("{name}{suffix}"));
+        if side.exists() {
+            let _ = std::fs::rename(
+                &side,
+                path.with_file_name(format!("{name}.broken-{stamp}{suffix}")),
+            );
+        }
+    }
+    Ok(backup)
+}
+
+/// The tables of the first release (and of later ones, as they were added
+/// before schema versions were kept).
+fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS transcriptions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            transcription_id INTEGER,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            audio_secs REAL NOT NULL,
+            latency_ms INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE TABLE IF NOT EXISTS replacements (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            original TEXT NOT NULL UNIQUE,
+            replacement TEXT NOT NULL,
+            accepted INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+        );
+        CREATE TABLE IF NOT EXISTS words (
+            transcription_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            word TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            PRIMARY KEY (transcription_id, position)
+        );
+        CREATE TABLE IF NOT EXISTS segments (
+            transcription_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            confidence REAL,
+            PRIMARY KEY (transcription_id, position)
+        );
+        CREATE TABLE IF NOT EXISTS tags (
+            transcription_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (transcription_id, tag)
+        );",
+    )
+}
+
+/// Apply the `migrations` past the database's `user_version`, each in a
+/// transaction with its version bump. A database at a version beyond
+/// `migrations` was written by a newer release and is refused rather than
+/// used with a schema this release doesn't know, see
+/// [`is_newer_schema_error`].
+pub fn migrate_schema(conn: &Connection, migrations: &[SchemaMigration]) -> Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > migrations.len() {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+            Some(format!(
+                "{NEWER_SCHEMA} (version {version}, this release knows {}); update WhisperCrabs",
+                migrations.len()
+            )),
+        ));
+    }
+    for (i, migration) in migrations.iter().enumerate().skip(version) {
+        dbg_log!("[DB] schema migration {}: {}", i + 1, migration.description);
+        let tx = conn.unchecked_transaction()?;
+        (migration.apply)(&tx)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// Whether an open error means the file was written by a newer release.
+pub fn is_newer_schema_error(e: &rusqlite::Error) -> bool {
+    matches!(e, rusqlite::Error::SqliteFailure(_, Some(msg)) if msg.starts_with(NEWER_SCHEMA))
+}
+
+/// Add a column to a table created by an older version of the app.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{table}') WHERE name = ?1"
+        ))?
+        .exists(params![column])?;
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))?;
+    }
+    Ok(())
 }
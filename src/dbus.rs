@@ -0,0 +1,192 @@
+//! The `org.whispercrabs.Control` D-Bus interface.
+//!
+//! GApplication already exports every app action over `org.gtk.Actions`,
+//! but calling those means wrapping arguments in variant arrays and getting
+//! nothing back. This interface, on the app's object path, offers plain
+//! methods and signals for scripts, keyboard daemons and other apps:
+//!
+//! | Member | Kind | |
+//! |---|---|---|
+//! | `StartRecording()` | method | |
+//! | `StopAndTranscribe()` | method | |
+//! | `ToggleRecording()` | method | |
+//! | `GetLastTranscript() → s` | method | `""` before the first one |
+//! | `GetState() → s` | method | `idle`, `recording`, `processing` or `speaking` |
+//! | `SetProvider(s)` | method | a `transcription-mode` id, e.g. `groq` |
+//! | `TranscriptionDone(s text, s provider)` | signal | each delivered transcript |
+//! | `StateChanged(s state)` | signal | |
+//!
+//! It is served on the main loop through GIO's own D-Bus connection and is
+//! an [`ActivationSource`]: the three recording methods are activations,
+//! run like those of any other trigger.
+
+use crate::activation::{Activation, ActivationSource};
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc;
+
+/// Interface name.
+pub const INTERFACE: &str = "org.whispercrabs.Control";
+
+/// Object path, the application's own.
+pub const OBJECT_PATH: &str = crate::preferences::DBUS_PATH;
+
+/// Prefix of the errors methods return.
+const ERROR_PREFIX: &str = "org.whispercrabs.Control.Error";
+
+pub const INTROSPECTION: &str = r#"<node>
+  <interface name="org.whispercrabs.Control">
+    <method name="StartRecording"/>
+    <method name="StopAndTranscribe"/>
+    <method name="ToggleRecording"/>
+    <method name="GetLastTranscript">
+      <arg name="text" type="s" direction="out"/>
+    </method>
+    <method name="GetState">
+      <arg name="state" type="s" direction="out"/>
+    </method>
+    <method name="SetProvider">
+      <arg name="provider" type="s" direction="in"/>
+    </method>
+    <signal name="TranscriptionDone">
+      <arg name="text" type="s"/>
+      <arg name="provider" type="s"/>
+    </signal>
+    <signal name="StateChanged">
+      <arg name="state" type="s"/>
+    </signal>
+  </interface>
+</node>"#;
+
+/// A method call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Request {
+    Activate(Activation),
+    LastTranscript,
+    State,
+    SetProvider(String),
+}
+
+/// The request for `method` called with `params`; `None` for an unknown
+/// method or wrong arguments.
+pub fn parse_request(method: &str, params: &glib::Variant) -> Option<Request> {
+    Some(match method {
+        "StartRecording" => Request::Activate(Activation::Start),
+        "StopAndTranscribe" => Request::Activate(Activation::Stop),
+        "ToggleRecording" => Request::Activate(Activation::Toggle),
+        "GetLastTranscript" => Request::LastTranscript,
+        "GetState" => Request::State,
+        "SetProvider" => {
+            let (provider,) = params.get::<(String,)>()?;
+            Request::SetProvider(provider.trim().to_string())
+        }
+        _ => return None,
+    })
+}
+
+/// Answers the requests that aren't activations: the reply's out
+/// arguments as a tuple (`None` for none), or an error message.
+pub type Handler = Rc<dyn Fn(Request) -> Result<Option<glib::Variant>, String>>;
+
+/// The interface as an activation source.
+pub struct ControlSource {
+    handler: Handler,
+    registration: RefCell<Option<(gio::DBusConnection, gio::RegistrationId)>>,
+    state: Cell<&'static str>,
+}
+
+impl ControlSource {
+    pub fn new(handler: Handler) -> Self {
+        Self {
+            handler,
+            registration: RefCell::new(None),
+            state: Cell::new("idle"),
+        }
+    }
+}
+
+impl ActivationSource for ControlSource {
+    fn name(&self) -> &'static str {
+        "D-Bus interface"
+    }
+
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        let connection = gio::Application::default()
+            .and_then(|app| app.dbus_connection())
+            .ok_or("not on the session bus")?;
+        let interface = gio::DBusNodeInfo::for_xml(INTROSPECTION)
+            .map_err(|e| e.to_string())?
+            .lookup_interface(INTERFACE)
+            .ok_or("interface missing from its introspection data")?;
+        let (tx, rx) = mpsc::channel();
+        let handler = Rc::clone(&self.handler);
+        let id = connection
+            .register_object(OBJECT_PATH, &interface)
+            .method_call(
+                move |_, _sender, _path, _interface, method, params, invocation| {
+                    answer(&tx, &handler, method, &params, invocation)
+                },
+            )
+            .build()
+            .map_err(|e| e.to_string())?;
+        if let Some((connection, old)) = self.registration.replace(Some((connection, id))) {
+            let _ = connection.unregister_object(old);
+        }
+        Ok(rx)
+    }
+
+    fn state_changed(&self, state: &'static str) {
+        if self.state.replace(state) != state {
+            emit("StateChanged", &(state,).to_variant());
+        }
+    }
+}
+
+impl Drop for ControlSource {
+    fn drop(&mut self) {
+        if let Some((connection, id)) = self.registration.take() {
+            let _ = connection.unregister_object(id);
+        }
+    }
+}
+
+/// Reply to a call of `method`.
+fn answer(
+    tx: &mpsc::Sender<Activation>,
+    handler: &Handler,
+    method: &str,
+    params: &glib::Variant,
+    invocation: gio::DBusMethodInvocation,
+) {
+    match parse_request(method, params) {
+        Some(Request::Activate(activation)) => {
+            let _ = tx.send(activation);
+            invocation.return_value(None);
+        }
+        Some(request) => match handler(request) {
+            Ok(reply) => invocation.return_value(reply.as_ref()),
+            Err(e) => invocation.return_dbus_error(&format!("{ERROR_PREFIX}.Failed"), &e),
+        },
+        None => invocation.return_dbus_error(
+            &format!("{ERROR_PREFIX}.InvalidArgs"),
+            &format!("Unknown method or arguments: {method}"),
+        ),
+    }
+}
+
+/// Broadcast the `TranscriptionDone` signal.
+pub fn transcription_done(text: &str, provider: &str) {
+    emit("TranscriptionDone", &(text, provider).to_variant());
+}
+
+fn emit(signal: &str, args: &glib::Variant) {
+    let Some(connection) = gio::Application::default().and_then(|app| app.dbus_connection()) else {
+        return;
+    };
+    if let Err(e) = connection.emit_signal(None, OBJECT_PATH, INTERFACE, signal, Some(args)) {
+        dbg_log!("[DBUS] {signal} not sent: {e}");
+    }
+}
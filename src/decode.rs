@@ -0,0 +1,131 @@
+//! Audio file decoding for file-based transcription.
+//!
+//! Recordings arrive as the in-memory WAV [`crate::audio::Recorder`] makes;
+//! files (the `transcribe` and `batch` commands, **Transcribe Files...**,
+//! drops on the button) come in whatever format they were saved in. This
+//! turns WAV, MP3, Ogg Vorbis, M4A (AAC) and FLAC into the 16 kHz mono
+//! samples the speech models work on. WAV is read with hound, everything
+//! else with symphonia.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Sample rate of decoded audio, what whisper and Vosk models take.
+pub const SAMPLE_RATE: u32 = crate::local_stt::WHISPER_SAMPLE_RATE;
+
+/// File extensions of the formats [`decode`] reads.
+pub const EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "m4a", "flac"];
+
+/// Whether `path` has one of the [`EXTENSIONS`].
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.iter().any(|x| x.eq_ignore_ascii_case(e)))
+}
+
+/// Mono audio at its own sample rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decoded {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+/// Decode the file at `path` into [`SAMPLE_RATE`] mono samples.
+pub fn decode_file(path: &Path) -> Result<Vec<f32>, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let extension = path.extension().and_then(|e| e.to_str());
+    to_speech_rate(decode(data, extension)?)
+}
+
+/// Decode `data`, a whole audio file; `extension` helps tell its format.
+pub fn decode(data: Vec<u8>, extension: Option<&str>) -> Result<Decoded, String> {
+    let decoded = if hound::WavReader::new(Cursor::new(&data)).is_ok() {
+        let (samples, sample_rate) = crate::audio::read_wav(&data)?;
+        Decoded {
+            samples,
+            sample_rate,
+        }
+    } else {
+        decode_compressed(data, extension)?
+    };
+    if decoded.samples.is_empty() || decoded.sample_rate == 0 {
+        return Err("The file holds no audio".into());
+    }
+    Ok(decoded)
+}
+
+/// `decoded` resampled to [`SAMPLE_RATE`].
+pub fn to_speech_rate(decoded: Decoded) -> Result<Vec<f32>, String> {
+    if decoded.sample_rate == SAMPLE_RATE {
+        return Ok(decoded.samples);
+    }
+    crate::local_stt::resample(&decoded.samples, decoded.sample_rate, SAMPLE_RATE)
+}
+
+/// The first audio track of `data` through symphonia, downmixed to mono.
+/// Damaged packets are skipped, as players do.
+fn decode_compressed(data: Vec<u8>, extension: Option<&str>) -> Result<Decoded, String> {
+    let unsupported = |e: Error| format!("Not a WAV, MP3, Ogg, M4A or FLAC file: {e}");
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(unsupported)?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("The file has no audio track")?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(unsupported)?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Audio read error: {e}")),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let buffer = match decoder.decode(&packet) {
+            Ok(buffer) => buffer,
+            Err(Error::DecodeError(e)) => {
+                dbg_log!("[DECODE] skipped a damaged packet: {e}");
+                continue;
+            }
+            Err(e) => return Err(format!("Audio decode error: {e}")),
+        };
+        let spec = *buffer.spec();
+        sample_rate = spec.rate;
+        let mut interleaved = SampleBuffer::<f32>::new(buffer.capacity() as u64, spec);
+        interleaved.copy_interleaved_ref(buffer);
+        let channels = spec.channels.count().max(1) as u16;
+        samples.extend(crate::audio::downmix(interleaved.samples(), channels));
+    }
+    Ok(Decoded {
+        samples,
+        sample_rate,
+    })
+}
@@ -0,0 +1,253 @@
+//! Real-time dictation — words appear in the focused application while speaking.
+//!
+//! While a recording runs, the audio captured since the last *committed*
+//! word is re-decoded every [`STEP`] with the loaded local model. Words that
+//! two consecutive decodes agree on are committed and never change again;
+//! the rest is a tentative tail that is typed right away and corrected
+//! (backspaced and retyped) when the next decode revises it. Committing moves
+//! the decode window forward, so each pass only covers the last few seconds.
+
+use crate::audio::LiveAudio;
+use crate::config::WhisperParams;
+use crate::local_stt::{LocalEngine, Transcript, Word};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Time between decodes of the live window.
+pub const STEP: Duration = Duration::from_millis(1500);
+
+/// A window this long is committed up to its last word even without agreement.
+pub const MAX_WINDOW_MS: i64 = 20_000;
+
+/// Windows shorter than this aren't worth decoding yet.
+const MIN_WINDOW_MS: i64 = 500;
+
+/// Keystrokes that turn the text on screen into the new text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Edit {
+    pub backspaces: usize,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn is_empty(&self) -> bool {
+        self.backspaces == 0 && self.text.is_empty()
+    }
+}
+
+/// Minimal edit from `shown` to `target`: erase back to the common prefix, type the rest.
+pub fn edit_between(shown: &str, target: &str) -> Edit {
+    let common = shown
+        .chars()
+        .zip(target.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    Edit {
+        backspaces: shown.chars().count() - common,
+        text: target.chars().skip(common).collect(),
+    }
+}
+
+/// Words compare equal across decodes regardless of case and punctuation,
+/// which whisper revises as more context arrives.
+fn same_word(a: &str, b: &str) -> bool {
+    let norm = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    norm(a) == norm(b)
+}
+
+/// Committed words, the tentative tail, and what has been typed so far.
+#[derive(Default)]
+pub struct LiveDictation {
+    /// Personal dictionary rules applied to the typed text.
+    rules: Vec<(String, String)>,
+    /// Committed words with times relative to the start of the recording.
+    committed: Vec<Word>,
+    /// Uncommitted words of the latest decode.
+    tentative: Vec<String>,
+    /// Where the current decode window starts in the recording.
+    window_start_ms: i64,
+    /// Text typed into the focused application.
+    shown: String,
+}
+
+impl LiveDictation {
+    pub fn new(rules: Vec<(String, String)>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn window_start_ms(&self) -> i64 {
+        self.window_start_ms
+    }
+
+    /// Take a decode of the current window (`window_ms` long, word times
+    /// relative to its start) and return the keystrokes to apply.
+    pub fn update(&mut self, words: &[Word], window_ms: i64) -> Edit {
+        let agreed = words
+            .iter()
+            .zip(&self.tentative)
+            .take_while(|(w, prev)| same_word(&w.text, prev))
+            .count();
+        // A window that keeps growing without agreement is committed all but its last word
+        let commit = if window_ms >= MAX_WINDOW_MS {
+            agreed.max(words.len().saturating_sub(1))
+        } else {
+            agreed
+        };
+        self.commit(&words[..commit]);
+        self.tentative = words[commit..].iter().map(|w| w.text.clone()).collect();
+        self.retype()
+    }
+
+    /// Take the final decode after recording stopped: everything is committed.
+    pub fn finish(&mut self, words: &[Word]) -> Edit {
+        self.commit(words);
+        self.tentative.clear();
+        self.retype()
+    }
+
+    /// The full text: committed words, then the tentative tail.
+    pub fn text(&self) -> String {
+        let words: Vec<&str> = self
+            .committed
+            .iter()
+            .map(|w| w.text.as_str())
+            .chain(self.tentative.iter().map(String::as_str))
+            .collect();
+        crate::corrections::apply(&words.join(" "), &self.rules)
+    }
+
+    /// Committed words with their times in the recording.
+    pub fn words(&self) -> &[Word] {
+        &self.committed
+    }
+
+    fn commit(&mut self, words: &[Word]) {
+        let Some(last) = words.last() else {
+            return;
+        };
+        let offset = self.window_start_ms;
+        self.committed.extend(words.iter().map(|w| Word {
+            text: w.text.clone(),
+            start_ms: w.start_ms + offset,
+            end_ms: w.end_ms + offset,
+        }));
+        self.window_start_ms = offset + last.end_ms;
+    }
+
+    fn retype(&mut self) -> Edit {
+        let target = self.text();
+        let edit = edit_between(&self.shown, &target);
+        self.shown = target;
+        edit
+    }
+}
+
+/// A live dictation running alongside a recording. Dropping it without
+/// [`LiveSession::finish`] stops the decode thread after its final pass.
+pub struct LiveSession {
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<Result<Transcript, String>>>,
+}
+
+impl LiveSession {
+    /// Start decoding `audio` with `engine` on a background thread.
+    pub fn start(
+        engine: Arc<LocalEngine>,
+        audio: LiveAudio,
+        language: String,
+        params: WhisperParams,
+        rules: Vec<(String, String)>,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_c = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            decode_loop(&engine, &audio, &language, &params, rules, &stop_c)
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Decode the rest of the recording (call after the recorder stopped) and
+    /// return the complete transcript.
+    pub fn finish(mut self) -> Result<Transcript, String> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .take()
+            .ok_or("Live dictation already finished")?
+            .join()
+            .map_err(|_| "Live dictation thread panicked".to_string())?
+    }
+}
+
+impl Drop for LiveSession {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn decode_loop(
+    engine: &LocalEngine,
+    audio: &LiveAudio,
+    language: &str,
+    params: &WhisperParams,
+    rules: Vec<(String, String)>,
+    stop: &AtomicBool,
+) -> Result<Transcript, String> {
+    let rate = audio.sample_rate();
+    let mut live = LiveDictation::new(rules);
+    let mut detected = None;
+    loop {
+        // Read before taking the audio, so the last pass sees everything recorded
+        let stopping = stop.load(Ordering::Relaxed);
+        let start = (live.window_start_ms() * rate as i64 / 1000) as usize;
+        let mono = audio.mono_from(start);
+        let window_ms = mono.len() as i64 * 1000 / rate as i64;
+
+        if stopping || window_ms >= MIN_WINDOW_MS {
+            let decoded = if window_ms >= MIN_WINDOW_MS {
+                let wav = crate::audio::encode_wav(&mono, rate)?;
+                engine.transcribe_with_segments(&wav, rate, language, params, |_| {})?
+            } else {
+                Transcript::default()
+            };
+            detected = decoded.language.or(detected);
+            let edit = if stopping {
+                live.finish(&decoded.words)
+            } else {
+                live.update(&decoded.words, window_ms)
+            };
+            dbg_log!("[LIVE] {window_ms} ms window -> {edit:?}");
+            if !edit.is_empty()
+                && let Err(e) = crate::input::type_edit(edit.backspaces, &edit.text)
+            {
+                tracing::warn!("Live typing failed: {e}");
+            }
+        }
+
+        if stopping {
+            return Ok(Transcript {
+                text: live.text(),
+                words: live.words().to_vec(),
+                segments: Vec::new(),
+                language: detected,
+            });
+        }
+        // Sleep in short slices so stopping doesn't wait a whole step
+        let mut slept = Duration::ZERO;
+        while slept < STEP && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+            slept += Duration::from_millis(100);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Spoken dictation commands — "new line", "comma", "scratch that".
+//!
+//! With **Dictation Commands** on, command phrases spoken in a dictation are
+//! turned into the characters they stand for before the transcript is
+//! delivered: "dear Sam comma new line" becomes `dear Sam,` and a line
+//! break. "Scratch that" drops what was said since the last sentence end or
+//! line break. Phrases match whole words, ignoring case and the punctuation
+//! the speech model put around them.
+//!
+//! The built-in table can be extended as a JSON array in the
+//! `dictation_commands_table` setting. An entry with a built-in phrase
+//! replaces it, and one with empty `text` turns it off:
+//!
+//! ```json
+//! [
+//!   {"phrase": "smiley", "text": ":-)"},
+//!   {"phrase": "bullet", "text": "\n- ", "spacing": "join"},
+//!   {"phrase": "undo", "scratch": true},
+//!   {"phrase": "period", "text": ""}
+//! ]
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+
+/// `true` when spoken commands are turned into text; plain words otherwise.
+pub const ENABLED_SETTING: &str = "dictation_commands";
+
+/// Setting of the user's command table, see the module docs.
+pub const TABLE_SETTING: &str = "dictation_commands_table";
+
+/// How inserted text joins the words around it.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Spacing {
+    /// A word of its own, spaced from both neighbours.
+    #[default]
+    Word,
+    /// Attached to the word before: `,` `.` a closing quote.
+    Attach,
+    /// Attached to the word after: an opening quote or parenthesis.
+    Open,
+    /// Attached on both sides: line breaks.
+    Join,
+}
+
+/// A spoken phrase and what it turns into.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Command {
+    pub phrase: String,
+    /// Text inserted in place of the phrase; empty removes a built-in command.
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub spacing: Spacing,
+    /// Delete the previous segment instead of inserting text.
+    #[serde(default)]
+    pub scratch: bool,
+}
+
+/// Commands known without any setup.
+pub const BUILT_IN: &[(&str, &str, Spacing)] = &[
+    ("new line", "\n", Spacing::Join),
+    ("new paragraph", "\n\n", Spacing::Join),
+    ("comma", ",", Spacing::Attach),
+    ("period", ".", Spacing::Attach),
+    ("full stop", ".", Spacing::Attach),
+    ("question mark", "?", Spacing::Attach),
+    ("exclamation mark", "!", Spacing::Attach),
+    ("exclamation point", "!", Spacing::Attach),
+    ("colon", ":", Spacing::Attach),
+    ("semicolon", ";", Spacing::Attach),
+    ("open quote", "\u{201c}", Spacing::Open),
+    ("close quote", "\u{201d}", Spacing::Attach),
+    ("end quote", "\u{201d}", Spacing::Attach),
+    ("open parenthesis", "(", Spacing::Open),
+    ("close parenthesis", ")", Spacing::Attach),
+];
+
+/// The built-in phrase that deletes the previous segment.
+pub const SCRATCH_PHRASE: &str = "scratch that";
+
+/// Punctuation a spoken mark replaces rather than follows, so a comma the
+/// model already wrote before "period" doesn't stay.
+const MARKS: &[char] = &[',', '.', '?', '!', ':', ';'];
+
+/// Ends of the segment "scratch that" deletes.
+const SEGMENT_ENDS: &[char] = &['.', '?', '!', '\n'];
+
+/// Parse and validate the JSON command table stored in settings.
+pub fn parse_table(json: &str) -> Result<Vec<Command>, String> {
+    let commands: Vec<Command> =
+        serde_json::from_str(json).map_err(|e| format!("invalid commands JSON: {e}"))?;
+    for (i, c) in commands.iter().enumerate() {
+        if words(&c.phrase).is_empty() {
+            return Err("command phrases need at least one word".into());
+        }
+        if commands[..i]
+            .iter()
+            .any(|d| words(&d.phrase) == words(&c.phrase))
+        {
+            return Err(format!("duplicate command phrase '{}'", c.phrase));
+        }
+    }
+    Ok(commands)
+}
+
+/// The built-in commands with `custom` ones added or replacing them.
+pub fn table(custom: &[Command]) -> Vec<Command> {
+    let built_in = BUILT_IN
+        .iter()
+        .map(|&(phrase, text, spacing)| Command {
+            phrase: phrase.to_string(),
+            text: text.to_string(),
+            spacing,
+            scratch: false,
+        })
+        .chain(std::iter::once(Command {
+            phrase: SCRATCH_PHRASE.to_string(),
+            text: String::new(),
+            spacing: Spacing::Word,
+            scratch: true,
+        }));
+    built_in
+        .filter(|b| !custom.iter().any(|c| words(&c.phrase) == words(&b.phrase)))
+        .chain(custom.iter().cloned())
+        .filter(|c| c.scratch || !c.text.is_empty())
+        .collect()
+}
+
+/// The commands to apply to the next transcript, or `None` when they are
+/// off. A broken table falls back to the built-in commands.
+pub fn stored(d: &Db) -> Option<Vec<Command>> {
+    let enabled = d.get_setting(ENABLED_SETTING).ok().flatten();
+    if enabled.as_deref() != Some("true") {
+        return None;
+    }
+    let custom = d
+        .get_setting(TABLE_SETTING)
+        .ok()
+        .flatten()
+        .map(|json| parse_table(&json))
+        .unwrap_or_else(|| Ok(Vec::new()))
+        .unwrap_or_else(|e| {
+            tracing::warn!("Dictation commands: {e}");
+            Vec::new()
+        });
+    Some(table(&custom))
+}
+
+/// Replace the command phrases in `text` with what they stand for.
+pub fn apply(text: &str, commands: &[Command]) -> String {
+    let phrases: Vec<Vec<String>> = commands.iter().map(|c| words(&c.phrase)).collect();
+    let spoken: Vec<&str> = text.split_whitespace().collect();
+    let mut out = String::with_capacity(text.len());
+    // The next word attaches without a space: at the start, after `Open` or `Join`
+    let mut glued = true;
+    let mut i = 0;
+    while i < spoken.len() {
+        let found = phrases
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| matches_at(&spoken[i..], p))
+            .max_by_key(|(_, p)| p.len());
+        let Some((n, phrase)) = found else {
+            if !glued {
+                out.push(' ');
+            }
+            out.push_str(spoken[i]);
+            glued = false;
+            i += 1;
+            continue;
+        };
+        let command = &commands[n];
+        if command.scratch {
+            scratch(&mut out);
+            glued = out.is_empty() || out.ends_with('\n');
+        } else {
+            match command.spacing {
+                Spacing::Attach | Spacing::Join => {
+                    out.truncate(out.trim_end_matches(' ').len());
+                    if command.text.chars().all(|c| MARKS.contains(&c)) {
+                        out.truncate(out.trim_end_matches(MARKS).len());
+                    }
+                }
+                Spacing::Word | Spacing::Open if !glued => out.push(' '),
+                Spacing::Word | Spacing::Open => {}
+            }
+            out.push_str(&command.text);
+            glued = matches!(command.spacing, Spacing::Open | Spacing::Join);
+        }
+        i += phrase.len();
+    }
+    out
+}
+
+/// Drop the segment at the end of `out`: back to the previous sentence end
+/// or line break, or the line break itself when nothing follows it.
+fn scratch(out: &mut String) {
+    let trimmed = out.trim_end_matches(' ');
+    if trimmed.ends_with('\n') {
+        let kept = trimmed.trim_end_matches('\n').len();
+        out.truncate(kept);
+        return;
+    }
+    let body = trimmed.trim_end_matches(['.', '?', '!']);
+    let kept = body.rfind(SEGMENT_ENDS).map_or(0, |i| i + 1);
+    out.truncate(kept);
+}
+
+/// Lowercase words of `text` without the punctuation around them.
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn matches_at(spoken: &[&str], phrase: &[String]) -> bool {
+    !phrase.is_empty()
+        && spoken.len() >= phrase.len()
+        && spoken.iter().zip(phrase).all(|(s, p)| {
+            s.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+                == *p
+        })
+}
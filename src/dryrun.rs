@@ -0,0 +1,97 @@
+//! Dry run — the whole dictation pipeline without its effects.
+//!
+//! With dry run on, a dictation is recorded, transcribed and run through the
+//! accepted replacements as usual, but nothing leaves the app: the clipboard
+//! is left alone, nothing is typed, pasted or announced, nothing is
+//! written to History, the sync folder or the auto-save folder, and the
+//! hooks don't run. A preview lists what would have happened instead, which makes it safe to try out replacement rules and
+//! output settings on real dictations. Usage is still counted, since the
+//! transcription itself did run.
+
+/// Everything a dictation would have been delivered to.
+pub struct Outputs<'a> {
+    /// The final text, after replacements.
+    pub text: &'a str,
+    /// What History would store (full text or a preview), `None` for nothing.
+    pub stored: Option<&'a str>,
+    /// The sync folder the entry would be written to.
+    pub sync_dir: Option<&'a str>,
+    /// The auto-save folder the transcript would be written to.
+    pub autosave_dir: Option<&'a str>,
+    /// Typed into the focused app while speaking (Type as I Speak).
+    pub typed: bool,
+    pub paste: bool,
+    pub announce: bool,
+    /// The recording would be kept with its History entry.
+    pub keep_audio: bool,
+    /// The transcript hooks, with the event they would be sent.
+    pub hooks: Option<(&'a crate::hooks::Hooks, &'a crate::hooks::Event)>,
+}
+
+/// One action a dictation would have taken.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Planned {
+    pub action: &'static str,
+    pub detail: String,
+}
+
+impl Planned {
+    fn new(action: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            action,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// The actions `outputs` describe, in the order they would run.
+pub fn plan(outputs: &Outputs) -> Vec<Planned> {
+    let mut plan = Vec::new();
+    if outputs.typed {
+        plan.push(Planned::new("Type into the focused app", outputs.text));
+    } else {
+        plan.push(Planned::new("Copy to clipboard", outputs.text));
+        if outputs.paste {
+            plan.push(Planned::new("Paste into the focused app", "Ctrl+V"));
+        }
+    }
+    if outputs.announce {
+        plan.push(Planned::new(
+            "Announce to screen reader",
+            crate::announce::message(outputs.text, outputs.typed),
+        ));
+    }
+    match outputs.stored {
+        Some(stored) => {
+            plan.push(Planned::new("Save to History", stored));
+            if outputs.keep_audio {
+                plan.push(Planned::new("Keep the recording", "WAV next to the entry"));
+            }
+            if let Some(dir) = outputs.sync_dir {
+                plan.push(Planned::new("Write to sync folder", dir));
+            }
+            if let Some(dir) = outputs.autosave_dir {
+                plan.push(Planned::new("Write to auto-save folder", dir));
+            }
+        }
+        None => plan.push(Planned::new(
+            "Save to History",
+            "nothing (Store in History is off)",
+        )),
+    }
+    if let Some((hooks, event)) = outputs.hooks
+        && !event.text.trim().is_empty()
+    {
+        if let Some(command) = &hooks.command {
+            plan.push(Planned::new(
+                "Run command hook",
+                format!("{command} (transcript on stdin)"),
+            ));
+        }
+        if let Some(url) = &hooks.url {
+            let body = serde_json::to_string(event).unwrap_or_default();
+            plan.push(Planned::new("Post to webhook", format!("{url} {body}")));
+        }
+    }
+    plan
+}
@@ -0,0 +1,173 @@
+//! Errors — what went wrong with a dictation, in words anyone can act on.
+//!
+//! Providers, the network and local models fail with messages written for
+//! a log: `API error 401 Unauthorized: {"error":{...}}`, `Request failed:
+//! error sending request ... operation timed out`. [`classify`] sorts them
+//! into a few [`Kind`]s — key rejected, rate limited, offline, model
+//! missing — each with a short status for the floating button, a title and
+//! a hint for the popover that opens on a failure. That popover shows the
+//! provider's own message too ([`detail`]) and, unless nothing was
+//! recorded, offers **Retry with same audio**, which transcribes the
+//! captured recording again with the provider now selected.
+
+/// A failed dictation, by cause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// The provider refused the API key (401 or 403).
+    KeyInvalid,
+    /// Too many requests, or the account's quota is used up (429).
+    RateLimited,
+    /// The provider couldn't be reached.
+    NetworkDown,
+    /// The request or the provider's gateway timed out.
+    Timeout,
+    /// The provider failed on its side (5xx).
+    Server,
+    /// The selected local model isn't downloaded or doesn't load.
+    ModelMissing,
+    /// The recording held no audio.
+    EmptyRecording,
+    /// The recording is larger than the provider accepts.
+    TooLong,
+    /// Local-only mode or an organization policy kept the audio here.
+    Blocked,
+    /// Anything else.
+    Other,
+}
+
+/// The HTTP status of an `API error <status>: ...` message.
+fn api_status(message: &str) -> Option<u16> {
+    let rest = message.split("API error ").nth(1)?;
+    rest.get(..3)?.parse().ok()
+}
+
+/// The kind of failure `message` describes.
+pub fn classify(message: &str) -> Kind {
+    if let Some(status) = api_status(message) {
+        return match status {
+            401 | 403 => Kind::KeyInvalid,
+            429 => Kind::RateLimited,
+            408 | 504 => Kind::Timeout,
+            413 => Kind::TooLong,
+            500..=599 => Kind::Server,
+            _ => Kind::Other,
+        };
+    }
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+    if has(&["timed out", "timeout"]) {
+        Kind::Timeout
+    } else if has(&["no audio recorded", "no audio samples"]) {
+        Kind::EmptyRecording
+    } else if has(&["recording too long"]) {
+        Kind::TooLong
+    } else if has(&["local-only mode", "blocked by policy"]) {
+        Kind::Blocked
+    } else if has(&[
+        "model not downloaded",
+        "model not loaded",
+        "failed to load whisper model",
+        "failed to load vosk model",
+        "models drive not available",
+    ]) {
+        Kind::ModelMissing
+    } else if has(&[
+        "request failed",
+        "error trying to connect",
+        "dns error",
+        "connection refused",
+        "network is unreachable",
+    ]) {
+        Kind::NetworkDown
+    } else {
+        Kind::Other
+    }
+}
+
+impl Kind {
+    /// A few words for the status label under the button.
+    pub fn status(self) -> &'static str {
+        match self {
+            Kind::KeyInvalid => "Key rejected",
+            Kind::RateLimited => "Rate limited",
+            Kind::NetworkDown => "Offline",
+            Kind::Timeout => "Timed out",
+            Kind::Server => "Provider error",
+            Kind::ModelMissing => "Model missing",
+            Kind::EmptyRecording => "Nothing recorded",
+            Kind::TooLong => "Too long",
+            Kind::Blocked => "Blocked",
+            Kind::Other => "Failed",
+        }
+    }
+
+    /// Heading of the failure popover.
+    pub fn title(self) -> &'static str {
+        match self {
+            Kind::KeyInvalid => "The API key was rejected",
+            Kind::RateLimited => "The provider is rate limiting you",
+            Kind::NetworkDown => "The provider can't be reached",
+            Kind::Timeout => "The provider took too long",
+            Kind::Server => "The provider had an internal error",
+            Kind::ModelMissing => "The local model is missing",
+            Kind::EmptyRecording => "Nothing was recorded",
+            Kind::TooLong => "The recording is too long for this provider",
+            Kind::Blocked => "The audio wasn't allowed to leave this computer",
+            Kind::Other => "Transcription failed",
+        }
+    }
+
+    /// What to do about it.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Kind::KeyInvalid => "Change API Key... in Preferences, then retry.",
+            Kind::RateLimited => "Wait a moment or check the account's quota, then retry.",
+            Kind::NetworkDown => "Check the connection, or switch to a local model, then retry.",
+            Kind::Timeout => "The connection may be slow; retry, or pick a faster provider.",
+            Kind::Server => "This is usually brief; retry in a moment.",
+            Kind::ModelMissing => "Download it with Manage Models..., or pick another, then retry.",
+            Kind::EmptyRecording => "Check the Microphone in Preferences.",
+            Kind::TooLong => "Pick a local model, or one of the providers without a limit.",
+            Kind::Blocked => "Pick a local model, or turn Local only off, then retry.",
+            Kind::Other => "Show Logs in the menu has the details.",
+        }
+    }
+
+    /// Whether transcribing the same audio again can help. An empty
+    /// recording has nothing to retry.
+    pub fn retryable(self) -> bool {
+        self != Kind::EmptyRecording
+    }
+}
+
+/// Longest provider message shown in the popover.
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// The provider's own words for the failure: the `message` of a JSON error
+/// body when there is one, otherwise `message` itself, shortened.
+pub fn detail(message: &str) -> String {
+    let from_body = message
+        .find('{')
+        .and_then(|i| serde_json::from_str::<serde_json::Value>(&message[i..]).ok())
+        .and_then(|json| {
+            json["error"]["message"]
+                .as_str()
+                .or_else(|| json["error"].as_str())
+                .or_else(|| json["message"].as_str())
+                .or_else(|| json["detail"].as_str())
+                .map(str::to_string)
+        });
+    let text = from_body.unwrap_or_else(|| message.trim().to_string());
+    if text.chars().count() > MAX_DETAIL_CHARS {
+        let cut: String = text.chars().take(MAX_DETAIL_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        text
+    }
+}
+
+/// One line for places without a popover, e.g. the daemon's status:
+/// `Key rejected: Invalid API key`.
+pub fn summary(message: &str) -> String {
+    format!("{}: {}", classify(message).status(), detail(message))
+}
@@ -0,0 +1,107 @@
+//! History export — dump transcriptions as JSON, CSV or Markdown for use in
+//! other tools (note systems, spreadsheets) without opening `history.db`.
+
+use crate::db::Transcription;
+
+/// File formats History can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// All formats, in the order the export dialog offers them.
+    pub const ALL: [Self; 3] = [Self::Json, Self::Csv, Self::Markdown];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// Parse an optional `YYYY-MM-DD` date filter; blank means no limit.
+pub fn parse_date(input: &str) -> Result<Option<String>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map(|d| Some(d.format("%Y-%m-%d").to_string()))
+        .map_err(|_| format!("Invalid date '{input}', use YYYY-MM-DD"))
+}
+
+/// Render `entries` in `format`.
+pub fn render(entries: &[Transcription], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Json => to_json(entries),
+        ExportFormat::Csv => to_csv(entries),
+        ExportFormat::Markdown => to_markdown(entries),
+    }
+}
+
+fn to_json(entries: &[Transcription]) -> String {
+    let list: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "id": e.id,
+                "created_at": e.created_at,
+                "language": e.language,
+                "text": e.text,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&list).unwrap_or_else(|_| "[]".into())
+}
+
+fn to_csv(entries: &[Transcription]) -> String {
+    let mut out = String::from("id,created_at,language,text\r\n");
+    for e in entries {
+        out.push_str(&format!(
+            "{},{},{},{}\r\n",
+            e.id,
+            csv_field(&e.created_at),
+            csv_field(e.language.as_deref().unwrap_or("")),
+            csv_field(&e.text)
+        ));
+    }
+    out
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_markdown(entries: &[Transcription]) -> String {
+    let mut out = String::from("# WhisperCrabs History\n");
+    for e in entries {
+        let heading = match e.language.as_deref() {
+            Some(code) => format!(
+                "{} \u{00b7} {}",
+                e.created_at,
+                crate::config::language_label(code)
+            ),
+            None => e.created_at.clone(),
+        };
+        out.push_str(&format!("\n## {heading}\n\n{}\n", e.text.trim()));
+    }
+    out
+}
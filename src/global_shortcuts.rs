@@ -0,0 +1,262 @@
+//! Global shortcuts through the XDG desktop portal.
+//!
+//! Setting up a custom shortcut that runs `gdbus call` by hand works
+//! everywhere but takes some doing. Desktops with the GlobalShortcuts portal
+//! (`org.freedesktop.portal.GlobalShortcuts`: GNOME 48+, KDE Plasma 6,
+//! Hyprland, and others through their portal backends) let the app register
+//! its own: WhisperCrabs binds Record, Stop and Toggle, each with a preferred
+//! key combination, and the desktop asks the user to confirm or change them.
+//! Presses arrive as D-Bus signals.
+//!
+//! The source runs the portal session on a background thread (`ashpd` on a
+//! small tokio runtime) and is an [`ActivationSource`], so a shortcut press
+//! is run like any other trigger. Which keys were finally bound is reported
+//! by [`status`], for the Shortcuts tab of Preferences.
+
+use crate::activation::{Activation, ActivationSource};
+use std::sync::{Mutex, mpsc};
+
+/// Settings key of the option.
+pub const SETTING: &str = "portal_shortcuts";
+
+/// A shortcut the app registers.
+pub struct Shortcut {
+    /// Portal id, stable across sessions.
+    pub id: &'static str,
+    /// Shown in the desktop's shortcut settings.
+    pub description: &'static str,
+    /// Suggested keys, in the XDG shortcuts format.
+    pub default_trigger: Option<&'static str>,
+    pub activation: Activation,
+}
+
+pub const SHORTCUTS: &[Shortcut] = &[
+    Shortcut {
+        id: "toggle",
+        description: "Start or stop recording",
+        default_trigger: Some("CTRL+ALT+space"),
+        activation: Activation::Toggle,
+    },
+    Shortcut {
+        id: "record",
+        description: "Start recording",
+        default_trigger: None,
+        activation: Activation::Start,
+    },
+    Shortcut {
+        id: "stop",
+        description: "Stop recording",
+        default_trigger: None,
+        activation: Activation::Stop,
+    },
+];
+
+/// Settings key of the keys chosen for shortcut `id`.
+pub fn trigger_setting(id: &str) -> String {
+    format!("shortcut_trigger_{id}")
+}
+
+/// The activation of the shortcut with portal id `id`.
+pub fn activation_for(id: &str) -> Option<Activation> {
+    SHORTCUTS.iter().find(|s| s.id == id).map(|s| s.activation)
+}
+
+/// Keys as typed (`Ctrl+Alt+Space`, `<Super>r`, `super + shift + d`) in the
+/// XDG shortcuts format the portal takes (`CTRL+ALT+space`, `LOGO+r`,
+/// `LOGO+SHIFT+d`): modifiers in capitals and in a fixed order, then one key.
+/// `None` without a key or with an unknown modifier.
+pub fn normalize_trigger(input: &str) -> Option<String> {
+    // "<Control><Alt>r" → "Control+Alt+r"
+    let input = input.replace('>', "+").replace('<', "");
+    let mut parts: Vec<&str> = input
+        .split('+')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    let key = parts.pop().filter(|k| modifier(k).is_none())?;
+    let mut modifiers = Vec::new();
+    for part in parts {
+        let modifier = modifier(part)?;
+        if !modifiers.contains(&modifier) {
+            modifiers.push(modifier);
+        }
+    }
+    modifiers.sort_by_key(|m| ["CTRL", "ALT", "SHIFT", "LOGO"].iter().position(|o| o == m));
+    // Keysym names: single characters lowercase, names as in xkb
+    let key = if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        match key.to_ascii_lowercase().as_str() {
+            "space" => "space".to_string(),
+            "enter" | "return" => "Return".to_string(),
+            "esc" | "escape" => "Escape".to_string(),
+            "tab" => "Tab".to_string(),
+            _ => key.to_string(),
+        }
+    };
+    modifiers.push(&key);
+    Some(modifiers.join("+"))
+}
+
+/// The XDG name of modifier `name`, in any common spelling.
+fn modifier(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" | "primary" => Some("CTRL"),
+        "alt" | "option" => Some("ALT"),
+        "shift" => Some("SHIFT"),
+        "logo" | "super" | "meta" | "win" | "cmd" => Some("LOGO"),
+        _ => None,
+    }
+}
+
+/// Where the portal session stands.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Status {
+    #[default]
+    Off,
+    /// Waiting for the desktop (and the user) to confirm the shortcuts.
+    Binding,
+    /// Portal id and the desktop's description of the keys bound to it.
+    Bound(Vec<(String, String)>),
+    Failed(String),
+}
+
+static STATUS: Mutex<Status> = Mutex::new(Status::Off);
+
+fn set_status(status: Status) {
+    *STATUS.lock().unwrap_or_else(|e| e.into_inner()) = status;
+}
+
+/// The state of the portal session, for display.
+pub fn status() -> Status {
+    STATUS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Portal shortcuts as an activation source.
+pub struct PortalShortcutsSource {
+    /// Preferred keys per shortcut id.
+    triggers: Vec<(&'static str, Option<String>)>,
+    #[cfg(target_os = "linux")]
+    stop: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl PortalShortcutsSource {
+    /// A source suggesting the keys `trigger(id)` returns for each
+    /// shortcut, or its default when that is `None`.
+    pub fn new(trigger: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            triggers: SHORTCUTS
+                .iter()
+                .map(|s| {
+                    let keys = trigger(s.id)
+                        .and_then(|t| normalize_trigger(&t))
+                        .or(s.default_trigger.map(str::to_string));
+                    (s.id, keys)
+                })
+                .collect(),
+            #[cfg(target_os = "linux")]
+            stop: None,
+        }
+    }
+}
+
+impl ActivationSource for PortalShortcutsSource {
+    fn name(&self) -> &'static str {
+        "portal shortcuts"
+    }
+
+    #[cfg(target_os = "linux")]
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let (tx, rx) = mpsc::channel();
+        let (stop, stopped) = tokio::sync::oneshot::channel();
+        // Replacing a running session closes it
+        self.stop = Some(stop);
+        let triggers = self.triggers.clone();
+        set_status(Status::Binding);
+        std::thread::spawn(move || {
+            if let Err(e) = runtime.block_on(run(triggers, tx, stopped)) {
+                tracing::warn!("Portal shortcuts failed: {e}");
+                set_status(Status::Failed(e));
+            }
+        });
+        Ok(rx)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        Err("Portal shortcuts are only supported on Linux".into())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for PortalShortcutsSource {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        set_status(Status::Off);
+    }
+}
+
+/// Bind the shortcuts and forward presses until `stopped` fires.
+#[cfg(target_os = "linux")]
+async fn run(
+    triggers: Vec<(&'static str, Option<String>)>,
+    tx: mpsc::Sender<Activation>,
+    mut stopped: tokio::sync::oneshot::Receiver<()>,
+) -> Result<(), String> {
+    use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+    use futures_util::StreamExt;
+
+    let err = |e: ashpd::Error| e.to_string();
+    let portal = GlobalShortcuts::new().await.map_err(err)?;
+    let session = portal.create_session().await.map_err(err)?;
+    let shortcuts: Vec<NewShortcut> = SHORTCUTS
+        .iter()
+        .map(|s| {
+            let keys = triggers
+                .iter()
+                .find(|(id, _)| *id == s.id)
+                .and_then(|(_, keys)| keys.as_deref());
+            NewShortcut::new(s.id, s.description).preferred_trigger(keys)
+        })
+        .collect();
+    let bound = portal
+        .bind_shortcuts(&session, &shortcuts, None)
+        .await
+        .map_err(err)?
+        .response()
+        .map_err(err)?;
+    set_status(Status::Bound(
+        bound
+            .shortcuts()
+            .iter()
+            .map(|s| (s.id().to_string(), s.trigger_description().to_string()))
+            .collect(),
+    ));
+    dbg_log!(
+        "[SHORTCUTS] bound {} portal shortcuts",
+        bound.shortcuts().len()
+    );
+
+    let mut activated = portal.receive_activated().await.map_err(err)?;
+    loop {
+        tokio::select! {
+            _ = &mut stopped => break,
+            event = activated.next() => {
+                let Some(event) = event else { break };
+                if let Some(activation) = activation_for(event.shortcut_id())
+                    && tx.send(activation).is_err()
+                {
+                    break;
+                }
+            }
+        }
+    }
+    let _ = session.close().await;
+    Ok(())
+}
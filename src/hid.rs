@@ -0,0 +1,187 @@
+//! Hardware trigger support — headset buttons, mute keys, and PTT pedals.
+//!
+//! Reads key events straight from `/dev/input/event*` via evdev, so it works
+//! regardless of window focus or display server. Reading input devices needs
+//! permission: add your user to the `input` group
+//! (`sudo usermod -aG input $USER`, then log out and back in) or install a
+//! udev rule granting access to the specific device.
+
+use crate::activation::{Activation, ActivationSource};
+use crate::config::HidMode;
+use std::sync::mpsc;
+
+/// A trigger event from a hardware button.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HidEvent {
+    Pressed,
+    Released,
+}
+
+/// Known key names accepted in `HID_KEYS` (evdev key codes).
+const KEY_NAMES: &[(&str, u16)] = &[
+    ("KEY_MUTE", 113),
+    ("KEY_PHONE", 169),
+    ("KEY_RECORD", 167),
+    ("KEY_PLAYPAUSE", 164),
+    ("KEY_MEDIA", 226),
+    ("KEY_VOICECOMMAND", 582),
+    ("KEY_MICMUTE", 248),
+    ("KEY_F13", 183),
+    ("KEY_F14", 184),
+    ("KEY_F15", 185),
+    ("KEY_F16", 186),
+    ("KEY_F17", 187),
+    ("KEY_F18", 188),
+    ("KEY_F19", 189),
+    ("KEY_F20", 190),
+    ("KEY_F21", 191),
+    ("KEY_F22", 192),
+    ("KEY_F23", 193),
+    ("KEY_F24", 194),
+    ("BTN_0", 0x100),
+    ("BTN_1", 0x101),
+    ("BTN_2", 0x102),
+];
+
+/// Default trigger keys when `HID_KEYS` is not set.
+pub const DEFAULT_HID_KEYS: &[&str] = &["KEY_MICMUTE", "KEY_PHONE", "KEY_VOICECOMMAND"];
+
+/// Resolve a key name (e.g. `KEY_MICMUTE`) or raw numeric code to an evdev key code.
+pub fn parse_key_name(name: &str) -> Option<u16> {
+    let name = name.trim();
+    if let Ok(code) = name.parse::<u16>() {
+        return Some(code);
+    }
+    let upper = name.to_ascii_uppercase();
+    KEY_NAMES
+        .iter()
+        .find(|(n, _)| *n == upper)
+        .map(|(_, code)| *code)
+}
+
+/// Map a raw key event value (1 = press, 0 = release, 2 = autorepeat) to a trigger event.
+pub fn event_from_value(value: i32) -> Option<HidEvent> {
+    match value {
+        1 => Some(HidEvent::Pressed),
+        0 => Some(HidEvent::Released),
+        _ => None,
+    }
+}
+
+/// The activation a trigger event stands for in `mode`, or `None` to ignore
+/// it. Whether it starts or stops a recording is decided by
+/// [`Activation::resolve`].
+pub fn activation_for(mode: HidMode, event: HidEvent) -> Option<Activation> {
+    match (mode, event) {
+        (HidMode::Toggle, HidEvent::Pressed) => Some(Activation::Toggle),
+        (HidMode::Toggle, HidEvent::Released) => None,
+        (HidMode::PushToTalk, HidEvent::Pressed) => Some(Activation::Start),
+        (HidMode::PushToTalk, HidEvent::Released) => Some(Activation::Stop),
+    }
+}
+
+/// Hardware buttons as an activation source.
+pub struct HidSource {
+    keys: Vec<u16>,
+    mode: HidMode,
+}
+
+impl HidSource {
+    pub fn new(keys: &[u16], mode: HidMode) -> Self {
+        Self {
+            keys: keys.to_vec(),
+            mode,
+        }
+    }
+}
+
+impl ActivationSource for HidSource {
+    fn name(&self) -> &'static str {
+        "hid"
+    }
+
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        let events = spawn_listener(&self.keys)?;
+        let (tx, rx) = mpsc::channel();
+        let mode = self.mode;
+        std::thread::spawn(move || {
+            for event in events {
+                dbg_log!("[HID] {event:?}");
+                if let Some(activation) = activation_for(mode, event)
+                    && tx.send(activation).is_err()
+                {
+                    return;
+                }
+            }
+        });
+        Ok(rx)
+    }
+}
+
+/// Start listening on every input device that reports one of `keys`.
+///
+/// Each matching device gets its own reader thread; events are delivered on
+/// the returned channel. Returns an error (with permissions guidance) if no
+/// readable device supports any of the requested keys.
+#[cfg(target_os = "linux")]
+pub fn spawn_listener(keys: &[u16]) -> Result<mpsc::Receiver<HidEvent>, String> {
+    let (tx, rx) = mpsc::channel();
+    let mut found = 0usize;
+
+    for (path, mut device) in evdev::enumerate() {
+        let Some(supported) = device.supported_keys() else {
+            continue;
+        };
+        let wanted: Vec<evdev::Key> = keys
+            .iter()
+            .map(|&k| evdev::Key::new(k))
+            .filter(|k| supported.contains(*k))
+            .collect();
+        if wanted.is_empty() {
+            continue;
+        }
+
+        dbg_log!(
+            "[HID] listening on {} ({})",
+            path.display(),
+            device.name().unwrap_or("unknown")
+        );
+        found += 1;
+
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            loop {
+                let events = match device.fetch_events() {
+                    Ok(events) => events,
+                    Err(e) => {
+                        dbg_log!("[HID] device {} closed: {e}", path.display());
+                        return;
+                    }
+                };
+                for ev in events {
+                    if let evdev::InputEventKind::Key(key) = ev.kind()
+                        && wanted.contains(&key)
+                        && let Some(event) = event_from_value(ev.value())
+                        && tx.send(event).is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    if found == 0 {
+        return Err("No readable input device reports the configured HID keys. \
+             Add your user to the 'input' group (sudo usermod -aG input $USER) \
+             or add a udev rule for the device, then log in again."
+            .into());
+    }
+    Ok(rx)
+}
+
+/// Hardware triggers are only supported on Linux (evdev).
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_listener(_keys: &[u16]) -> Result<mpsc::Receiver<HidEvent>, String> {
+    Err("HID triggers are only supported on Linux".into())
+}
@@ -0,0 +1,206 @@
+//! Hooks — a shell command and a webhook run after every transcription.
+//!
+//! Set under **Hooks...** in the right-click menu (or the `set-hooks` D-Bus
+//! action), they pass each delivered transcript on to other tools without a
+//! bespoke integration:
+//!
+//! - the **command** runs through `sh -c` (`cmd /C` on Windows) with the
+//!   text on stdin, and `WHISPERCRABS_PROVIDER` and `WHISPERCRABS_TIMESTAMP`
+//!   in its environment, e.g. `todo.sh add "$(cat)"`;
+//! - the **webhook** gets a POST of `{"text", "timestamp", "provider"}` as
+//!   JSON, e.g. a Home Assistant or n8n webhook URL.
+//!
+//! Both run in the background after the text is copied and are given
+//! [`TIMEOUT`] to finish; a failing hook is logged and never holds up the
+//! next dictation. The webhook respects
+//! "Local only" and the organization policy like every other request.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::db::Db;
+
+/// Settings key of the shell command.
+pub const COMMAND_SETTING: &str = "hook_command";
+
+/// Settings key of the webhook URL.
+pub const URL_SETTING: &str = "hook_url";
+
+/// How long the command or the webhook may take.
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The configured hooks; both empty means none.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Hooks {
+    /// Trim both fields, dropping empty ones, and check the URL.
+    pub fn validated(self) -> Result<Self, String> {
+        let clean = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        let hooks = Self {
+            command: clean(self.command),
+            url: clean(self.url),
+        };
+        if let Some(url) = &hooks.url
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            return Err("Webhook URL must use http:// or https://".into());
+        }
+        Ok(hooks)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.command.is_none() && self.url.is_none()
+    }
+}
+
+/// What the hooks are told about a transcript.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct Event {
+    pub text: String,
+    /// RFC 3339, local time with offset.
+    pub timestamp: String,
+    /// Provider that transcribed it, e.g. `groq` or `local-base`.
+    pub provider: String,
+}
+
+impl Event {
+    pub fn new(at: chrono::DateTime<chrono::Local>, text: &str, provider: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            timestamp: at.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            provider: provider.to_string(),
+        }
+    }
+}
+
+/// Parse hooks sent as JSON over D-Bus.
+pub fn parse(json: &str) -> Result<Hooks, String> {
+    serde_json::from_str::<Hooks>(json)
+        .map_err(|e| format!("invalid hooks JSON: {e}"))?
+        .validated()
+}
+
+/// The saved hooks.
+pub fn stored(d: &Db) -> Hooks {
+    let get = |key| d.get_setting(key).ok().flatten();
+    Hooks {
+        command: get(COMMAND_SETTING),
+        url: get(URL_SETTING),
+    }
+}
+
+/// Save `hooks`; empty fields clear their setting.
+pub fn save(d: &Db, hooks: &Hooks) -> rusqlite::Result<()> {
+    for (key, value) in [(COMMAND_SETTING, &hooks.command), (URL_SETTING, &hooks.url)] {
+        match value {
+            Some(value) => d.set_setting(key, value)?,
+            None => d.delete_setting(key)?,
+        }
+    }
+    Ok(())
+}
+
+/// Run the hooks for `event` on a background thread.
+pub fn fire(hooks: Hooks, event: Event) {
+    if hooks.is_empty() || event.text.trim().is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Some(command) = &hooks.command
+            && let Err(e) = run_command(command, &event)
+        {
+            tracing::warn!("Command hook failed: {e}");
+        }
+        if let Some(url) = &hooks.url
+            && let Err(e) = post(url, &event)
+        {
+            tracing::warn!("Webhook failed: {e}");
+        }
+    });
+}
+
+/// Run `command` with the transcript on stdin and wait for it.
+pub fn run_command(command: &str, event: &Event) -> Result<(), String> {
+    run_command_within(command, event, TIMEOUT)
+}
+
+/// [`run_command`], killing the command once it has run for `timeout`.
+pub fn run_command_within(command: &str, event: &Event, timeout: Duration) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    let mut child = cmd
+        .env("WHISPERCRABS_PROVIDER", &event.provider)
+        .env("WHISPERCRABS_TIMESTAMP", &event.timestamp)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("starting '{command}': {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Written from its own thread so a command that never reads its input
+        // can't block past the timeout; one that closes the pipe early is fine
+        let text = event.text.clone();
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
+    }
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "'{command}' was stopped after {} seconds",
+                timeout.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    if !status.success() {
+        return Err(format!("'{command}' exited with {status}"));
+    }
+    Ok(())
+}
+
+/// POST `event` as JSON to `url`.
+pub fn post(url: &str, event: &Event) -> Result<(), String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Invalid webhook URL: only http:// and https:// are allowed".into());
+    }
+    crate::policy::ensure_network_allowed(url)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))?;
+    let resp = client
+        .post(url)
+        .json(event)
+        .send()
+        .map_err(|e| format!("Webhook request failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Webhook returned {}", resp.status()));
+    }
+    Ok(())
+}
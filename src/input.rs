@@ -8,3 +8,141 @@ pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
         .map_err(|e| format!("Failed to copy to clipboard: {e}"))?;
     Ok(())
 }
+
+/// Tool that types into the focused window: `wtype` on Wayland, `xdotool` on X11.
+#[cfg(target_os = "linux")]
+fn typing_tool() -> &'static str {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        "wtype"
+    } else {
+        "xdotool"
+    }
+}
+
+/// Whether keystrokes can be sent to the focused application.
+#[cfg(target_os = "linux")]
+pub fn typing_available() -> bool {
+    std::process::Command::new(typing_tool())
+        .arg("-h")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// Typing into other applications is only supported on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn typing_available() -> bool {
+    false
+}
+
+/// Erase `backspaces` characters before the cursor of the focused
+/// application, then type `text`.
+#[cfg(target_os = "linux")]
+pub fn type_edit(backspaces: usize, text: &str) -> Result<(), String> {
+    let tool = typing_tool();
+    let run = |args: Vec<String>| {
+        let status = std::process::Command::new(tool)
+            .args(&args)
+            .status()
+            .map_err(|e| format!("{tool} failed: {e}"))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{tool} exited with {status}"))
+        }
+    };
+    if backspaces > 0 {
+        run(if tool == "wtype" {
+            (0..backspaces)
+                .flat_map(|_| ["-k".to_string(), "BackSpace".to_string()])
+                .collect()
+        } else {
+            vec![
+                "key".into(),
+                "--repeat".into(),
+                backspaces.to_string(),
+                "BackSpace".into(),
+            ]
+        })?;
+    }
+    if !text.is_empty() {
+        run(if tool == "wtype" {
+            vec!["--".into(), text.to_string()]
+        } else {
+            vec!["type".into(), "--".into(), text.to_string()]
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn type_edit(_backspaces: usize, _text: &str) -> Result<(), String> {
+    Err("Typing into other applications is only supported on Linux".into())
+}
+
+/// Whether the clipboard holds `text`.
+pub fn clipboard_holds(text: &str) -> bool {
+    Clipboard::new()
+        .and_then(|mut c| c.get_text())
+        .is_ok_and(|current| current == text)
+}
+
+/// Window class of the focused application (e.g. `code`, `kitty`), from
+/// Hyprland on Wayland or `xdotool` on X11 and XWayland.
+#[cfg(target_os = "linux")]
+pub fn focused_app_class() -> Option<String> {
+    let output = |program: &str, args: &[&str]| {
+        std::process::Command::new(program)
+            .args(args)
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+    };
+    let class = if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let json = output("hyprctl", &["activewindow", "-j"])?;
+        serde_json::from_str::<serde_json::Value>(&json)
+            .ok()?
+            .get("class")?
+            .as_str()?
+            .to_string()
+    } else {
+        output("xdotool", &["getactivewindow", "getwindowclassname"])?
+    };
+    let class = class.trim();
+    (!class.is_empty()).then(|| class.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn focused_app_class() -> Option<String> {
+    None
+}
+
+/// Press Ctrl+V in the focused application, pasting the clipboard, with
+/// `key_delay_ms` between the key events.
+#[cfg(target_os = "linux")]
+pub fn paste(key_delay_ms: u64) -> Result<(), String> {
+    let tool = typing_tool();
+    let delay = key_delay_ms.to_string();
+    let args: Vec<&str> = if tool == "wtype" {
+        vec!["-d", &delay, "-M", "ctrl", "v", "-m", "ctrl"]
+    } else {
+        vec!["key", "--delay", &delay, "--clearmodifiers", "ctrl+v"]
+    };
+    let status = std::process::Command::new(tool)
+        .args(&args)
+        .status()
+        .map_err(|e| format!("{tool} failed: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{tool} exited with {status}"))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn paste(_key_delay_ms: u64) -> Result<(), String> {
+    Err("Pasting into other applications is only supported on Linux".into())
+}
@@ -0,0 +1,133 @@
+//! In-app keys — shortcuts that work while the WhisperCrabs window has focus.
+//!
+//! Each binding runs an app action, so a key does exactly what the menu or a
+//! D-Bus call would. The keys are GTK accelerators (`<Control>h`, `Escape`)
+//! stored per binding in settings, and editable on the Keys page of
+//! Preferences, where `Ctrl+H` style spellings are accepted as well. A
+//! binding set to `none` has no key. Global shortcuts, which work from any
+//! application, are separate (see [`crate::global_shortcuts`]).
+
+/// A remappable key.
+pub struct Binding {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// The app action it runs, `app.` prefixed.
+    pub action: &'static str,
+    /// GTK accelerator used until another is set.
+    pub default: &'static str,
+}
+
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        id: "toggle",
+        label: "Start or stop recording",
+        action: "app.toggle-recording",
+        default: "<Control>r",
+    },
+    Binding {
+        id: "stop",
+        label: "Stop recording",
+        action: "app.stop",
+        default: "Escape",
+    },
+    Binding {
+        id: "discard",
+        label: "Discard recording",
+        action: "app.discard-recording",
+        default: "<Shift>Escape",
+    },
+    Binding {
+        id: "history",
+        label: "Open History",
+        action: "app.show-history",
+        default: "<Control>h",
+    },
+    Binding {
+        id: "cycle-mode",
+        label: "Next transcription mode",
+        action: "app.cycle-mode",
+        default: "<Control>m",
+    },
+];
+
+/// Stored value of a binding without a key.
+pub const NONE: &str = "none";
+
+/// Settings key of binding `id`.
+pub fn setting(id: &str) -> String {
+    format!("keybinding_{id}")
+}
+
+/// The key of `binding` given its stored value: the default when nothing
+/// is stored, `None` when it is switched off.
+pub fn effective<'a>(binding: &'a Binding, stored: Option<&'a str>) -> Option<&'a str> {
+    match stored.map(str::trim) {
+        None | Some("") => Some(binding.default),
+        Some(NONE) => None,
+        Some(keys) => Some(keys),
+    }
+}
+
+/// Keys as typed — a GTK accelerator (`<Control><Shift>h`) or `Ctrl+Shift+H`
+/// — as a GTK accelerator, modifiers in a fixed order. `Some("none")` for
+/// `none`/`off`; `None` if it isn't a key combination.
+pub fn parse(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case(NONE) || input.eq_ignore_ascii_case("off") {
+        return Some(NONE.to_string());
+    }
+    // "<Control><Shift>h" → "Control+Shift+h"
+    let input = input.replace('>', "+").replace('<', "");
+    let mut parts: Vec<&str> = input
+        .split('+')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    let key = parts.pop().filter(|k| modifier(k).is_none())?;
+    let mut modifiers = Vec::new();
+    for part in parts {
+        let modifier = modifier(part)?;
+        if !modifiers.contains(&modifier) {
+            modifiers.push(modifier);
+        }
+    }
+    modifiers.sort_by_key(|m| ORDER.iter().position(|o| o == m));
+    let key = if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        match key.to_ascii_lowercase().as_str() {
+            "space" => "space".to_string(),
+            "enter" | "return" => "Return".to_string(),
+            "esc" | "escape" => "Escape".to_string(),
+            "tab" => "Tab".to_string(),
+            "del" | "delete" => "Delete".to_string(),
+            "backspace" => "BackSpace".to_string(),
+            _ => key.to_string(),
+        }
+    };
+    let modifiers: String = modifiers.iter().map(|m| format!("<{m}>")).collect();
+    Some(modifiers + &key)
+}
+
+const ORDER: [&str; 4] = ["Control", "Alt", "Shift", "Super"];
+
+/// The GTK name of modifier `name`, in any common spelling.
+fn modifier(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" | "primary" => Some("Control"),
+        "alt" | "option" => Some("Alt"),
+        "shift" => Some("Shift"),
+        "super" | "logo" | "meta" | "win" | "cmd" => Some("Super"),
+        _ => None,
+    }
+}
+
+/// The choice after `current` in `choices`, wrapping around; the first one
+/// when `current` isn't among them.
+pub fn next_choice<'a>(choices: &'a [String], current: &str) -> Option<&'a str> {
+    let next = choices
+        .iter()
+        .position(|c| c == current)
+        .map_or(0, |i| (i + 1) % choices.len());
+    choices.get(next).map(String::as_str)
+}
@@ -2,14 +2,186 @@ use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
 };
 use std::io::Cursor;
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::Mutex;
 
-const WHISPER_SAMPLE_RATE: u32 = 16000;
+use crate::config::WhisperParams;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Idle whisper states kept per loaded model: one for the regular
+/// transcription plus one for a concurrent job (type-as-I-speak decoding,
+/// a History re-transcription).
+const STATE_POOL_SIZE: usize = 2;
+
+/// A transcribed word and where it was spoken in the clip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// A decoded stretch of speech (a whisper segment, a Vosk utterance or an
+/// API segment) with its time span in the clip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    /// Mean probability (0–1) of the decoded tokens, when the engine reports one.
+    pub confidence: Option<f32>,
+}
+
+/// Result of a transcription: the text plus per-word timings (local models
+/// only), timed segments and the spoken language, when the engine reported one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    pub words: Vec<Word>,
+    pub segments: Vec<Segment>,
+    /// ISO 639-1 code, see [`crate::config::normalize_language`].
+    pub language: Option<String>,
+}
+
+/// A loaded local engine: a whisper.cpp model file or a Vosk model directory.
+pub enum LocalEngine {
+    Whisper(LocalWhisper),
+    Vosk(crate::vosk_stt::VoskEngine),
+}
+
+impl LocalEngine {
+    /// Load the model at `path`; directories are Vosk models.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if path.is_dir() {
+            crate::vosk_stt::VoskEngine::new(path).map(Self::Vosk)
+        } else {
+            LocalWhisper::new(path).map(Self::Whisper)
+        }
+    }
+
+    /// Transcribe with whichever engine is loaded. Vosk models have a fixed
+    /// language and ignore `language` and `options`.
+    pub fn transcribe_with_segments(
+        &self,
+        wav_data: &[u8],
+        device_sample_rate: u32,
+        language: &str,
+        options: &WhisperParams,
+        on_segment: impl FnMut(String) + 'static,
+    ) -> Result<Transcript, String> {
+        match self {
+            Self::Whisper(w) => w.transcribe_with_segments(
+                wav_data,
+                device_sample_rate,
+                language,
+                options,
+                on_segment,
+            ),
+            Self::Vosk(v) => {
+                crate::vosk_stt::transcribe(v, wav_data, device_sample_rate, on_segment)
+            }
+        }
+    }
+}
+
+/// A small pool of reusable values that are expensive to set up. [`take`]
+/// hands out an idle one (or creates one when all are in use, so concurrent
+/// jobs never wait on each other); it returns to the pool when dropped,
+/// keeping at most `capacity` idle.
+///
+/// [`take`]: StatePool::take
+pub struct StatePool<T> {
+    idle: Mutex<Vec<T>>,
+    capacity: usize,
+}
+
+impl<T> StatePool<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            capacity,
+        }
+    }
+
+    /// Borrow an idle value, or build a new one with `create`.
+    pub fn take(
+        &self,
+        create: impl FnOnce() -> Result<T, String>,
+    ) -> Result<Pooled<'_, T>, String> {
+        let idle = self.lock().pop();
+        let value = match idle {
+            Some(value) => value,
+            None => create()?,
+        };
+        Ok(Pooled {
+            pool: self,
+            value: Some(value),
+        })
+    }
+
+    /// Hand `value` to the pool without borrowing it first (pre-warming).
+    pub fn put(&self, value: T) {
+        let mut idle = self.lock();
+        if idle.len() < self.capacity {
+            idle.push(value);
+        }
+    }
+
+    /// Number of values currently waiting in the pool.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<T>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A value borrowed from a [`StatePool`]; goes back to the pool on drop.
+pub struct Pooled<'a, T> {
+    pool: &'a StatePool<T>,
+    value: Option<T>,
+}
+
+impl<T> Deref for Pooled<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+            .as_ref()
+            .expect("pooled value present until drop")
+    }
+}
+
+impl<T> DerefMut for Pooled<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+            .as_mut()
+            .expect("pooled value present until drop")
+    }
+}
+
+impl<T> Drop for Pooled<'_, T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.pool.put(value);
+        }
+    }
+}
 
 /// Local speech-to-text engine using whisper.cpp.
+///
+/// The model context is loaded once, and decoding states (several MB of
+/// KV cache and compute buffers each) are pooled across transcriptions, so
+/// short dictations pay only for inference.
 pub struct LocalWhisper {
     ctx: WhisperContext,
+    states: StatePool<WhisperState>,
 }
 
 impl LocalWhisper {
@@ -17,10 +189,46 @@ impl LocalWhisper {
         let path_str = model_path.to_str().ok_or("Model path is not valid UTF-8")?;
         let ctx = WhisperContext::new_with_params(path_str, WhisperContextParameters::default())
             .map_err(|e| format!("Failed to load whisper model: {e}"))?;
-        Ok(Self { ctx })
+        let whisper = Self {
+            ctx,
+            states: StatePool::new(STATE_POOL_SIZE),
+        };
+        // Allocate the first state now, while the model is loading anyway
+        whisper.states.put(whisper.create_state()?);
+        Ok(whisper)
     }
 
-    pub fn transcribe(&self, wav_data: &[u8], device_sample_rate: u32) -> Result<String, String> {
+    fn create_state(&self) -> Result<WhisperState, String> {
+        self.ctx
+            .create_state()
+            .map_err(|e| format!("Failed to create whisper state: {e}"))
+    }
+
+    /// Transcribe a WAV clip. `language` is an ISO 639-1 code, or `"auto"`
+    /// to let whisper detect it (multilingual models only).
+    pub fn transcribe(
+        &self,
+        wav_data: &[u8],
+        device_sample_rate: u32,
+        language: &str,
+        options: &WhisperParams,
+    ) -> Result<String, String> {
+        self.transcribe_with_segments(wav_data, device_sample_rate, language, options, |_| {})
+            .map(|t| t.text)
+    }
+
+    /// Like [`Self::transcribe`], but calls `on_segment` with the text of each
+    /// segment as soon as whisper.cpp has decoded it, so long dictations can
+    /// show progress before the whole clip is done. Also returns word
+    /// timestamps for click-to-play in History.
+    pub fn transcribe_with_segments(
+        &self,
+        wav_data: &[u8],
+        device_sample_rate: u32,
+        language: &str,
+        options: &WhisperParams,
+        mut on_segment: impl FnMut(String) + 'static,
+    ) -> Result<Transcript, String> {
         // Parse WAV to f32 samples
         let cursor = Cursor::new(wav_data);
         let mut reader =
@@ -42,33 +250,163 @@ impl LocalWhisper {
         };
 
         // Run whisper inference
-        let mut state = self
-            .ctx
-            .create_state()
-            .map_err(|e| format!("Failed to create whisper state: {e}"))?;
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let mut state = self.states.take(|| self.create_state())?;
+        let strategy = if options.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: options.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy {
+                best_of: options.best_of.max(1) as i32,
+            }
+        };
+        let mut params = FullParams::new(strategy);
         params.set_print_special(false);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        params.set_language(Some(language));
+        if options.threads > 0 {
+            params.set_n_threads(options.threads as i32);
+        }
+        params.set_temperature(options.temperature);
+        params.set_no_speech_thold(options.no_speech_threshold);
+        params.set_suppress_nst(options.suppress_non_speech);
+        params.set_translate(options.translate);
+        params.set_token_timestamps(true);
+        params.set_tdrz_enable(options.diarize);
+        if let Some(prompt) = &options.initial_prompt {
+            params.set_initial_prompt(prompt);
+        }
+        params.set_segment_callback_safe_lossy(move |data: whisper_rs::SegmentCallbackData| {
+            on_segment(data.text);
+        });
 
         state
             .full(params, &audio_16k)
             .map_err(|e| format!("Whisper inference failed: {e}"))?;
 
-        // Collect transcription text
+        // Collect transcription text, speaker turns, timed segments and token timings
         let mut text = String::new();
+        let mut turns = Vec::new();
+        let mut timed = Vec::new();
+        let mut tokens = Vec::new();
         for segment in state.as_iter() {
+            let mut probs = Vec::new();
+            for i in 0..segment.n_tokens() {
+                if let Some(token) = segment.get_token(i)
+                    && let Ok(t) = token.to_str()
+                {
+                    let data = token.token_data();
+                    if !is_special_token(t) {
+                        probs.push(data.p);
+                    }
+                    tokens.push((t.to_string(), data.t0, data.t1));
+                }
+            }
             if let Ok(s) = segment.to_str() {
                 text.push_str(s);
+                turns.push((s.to_string(), segment.next_segment_speaker_turn()));
+                if !s.trim().is_empty() {
+                    // Segment timestamps are in whisper's 10 ms units
+                    timed.push(Segment {
+                        start_ms: segment.start_timestamp() * 10,
+                        end_ms: segment.end_timestamp() * 10,
+                        text: s.trim().to_string(),
+                        confidence: crate::confidence::with_no_speech(
+                            mean_confidence(&probs),
+                            Some(segment.no_speech_probability()),
+                        ),
+                    });
+                }
+            }
+        }
+
+        // The language whisper decoded in: the requested one, or the detected one for "auto"
+        let language = state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .and_then(crate::config::normalize_language);
+
+        let labelled = options.diarize.then(|| label_speakers(&turns)).flatten();
+        Ok(Transcript {
+            text: labelled.unwrap_or_else(|| text.trim().to_string()),
+            words: words_from_tokens(&tokens),
+            segments: timed,
+            language,
+        })
+    }
+}
+
+/// Prefix each speaker's run of segments with `Speaker 1:` / `Speaker 2:`,
+/// alternating at every turn tinydiarize marked after a segment. Returns
+/// `None` when no turn was detected (a single speaker, or a model without
+/// speaker-turn support).
+pub fn label_speakers(segments: &[(String, bool)]) -> Option<String> {
+    if !segments.iter().any(|(_, turn)| *turn) {
+        return None;
+    }
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut speaker = 1;
+    for (i, (text, turn)) in segments.iter().enumerate() {
+        current.push_str(text);
+        if *turn || i == segments.len() - 1 {
+            let spoken = current.trim();
+            if !spoken.is_empty() {
+                lines.push(format!("Speaker {speaker}: {spoken}"));
+            }
+            current.clear();
+            if *turn {
+                speaker = if speaker == 1 { 2 } else { 1 };
             }
         }
+    }
+    Some(lines.join("\n"))
+}
+
+/// Merge whisper tokens `(text, t0, t1)` into words. Timestamps are in
+/// whisper's 10 ms units; a token starting with a space begins a new word,
+/// and special tokens (`[_BEG_]`, `<|en|>`, ...) are dropped.
+pub fn words_from_tokens(tokens: &[(String, i64, i64)]) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    for (text, t0, t1) in tokens {
+        if is_special_token(text) || text.trim().is_empty() {
+            continue;
+        }
+        let (start_ms, end_ms) = (t0 * 10, t1 * 10);
+        match words.last_mut() {
+            Some(word) if !text.starts_with(char::is_whitespace) => {
+                word.text.push_str(text);
+                word.end_ms = word.end_ms.max(end_ms);
+            }
+            _ => words.push(Word {
+                text: text.trim().to_string(),
+                start_ms,
+                end_ms,
+            }),
+        }
+    }
+    words
+}
+
+/// Whisper's control tokens (`[_BEG_]`, `<|en|>`, ...) carry no speech.
+fn is_special_token(text: &str) -> bool {
+    text.starts_with("[_") || text.starts_with("<|")
+}
 
-        Ok(text.trim().to_string())
+/// Mean of per-token (or per-word) probabilities, `None` without any.
+pub fn mean_confidence(probs: &[f32]) -> Option<f32> {
+    if probs.is_empty() {
+        return None;
     }
+    Some(probs.iter().sum::<f32>() / probs.len() as f32)
 }
 
-fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
+/// `input` at `from_rate` converted to `to_rate`.
+pub fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
     let params = SincInterpolationParameters {
         sinc_len: 256,
         f_cutoff: 0.95,
@@ -0,0 +1,46 @@
+//! Optional PIN/passphrase gate for the History window.
+//!
+//! Transcripts can contain sensitive content and the floating button is
+//! often left running on shared machines, so History can be locked behind a
+//! secret. Only an Argon2id hash (PHC string) is stored, in the
+//! `history_lock` setting; removing the setting removes the lock.
+
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+
+/// Shortest accepted PIN/passphrase.
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// Settings key holding the hash of the History lock secret.
+pub const HISTORY_LOCK_SETTING: &str = "history_lock";
+
+/// Check a new secret (and its confirmation) before it is hashed.
+pub fn validate_secret(secret: &str, confirm: &str) -> Result<(), String> {
+    if secret.chars().count() < MIN_SECRET_LEN {
+        return Err(format!("Use at least {MIN_SECRET_LEN} characters"));
+    }
+    if secret != confirm {
+        return Err("The entries don't match".into());
+    }
+    Ok(())
+}
+
+/// Hash a secret with a fresh random salt.
+pub fn hash_secret(secret: &str) -> Result<String, String> {
+    let mut salt = [0u8; 16];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("random salt: {e}"))?;
+    let salt = SaltString::encode_b64(&salt).map_err(|e| format!("encode salt: {e}"))?;
+    Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| format!("hash secret: {e}"))
+}
+
+/// Whether `secret` matches a stored hash. A malformed hash never matches.
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|parsed| {
+        Argon2::default()
+            .verify_password(secret.as_bytes(), &parsed)
+            .is_ok()
+    })
+}
@@ -1,51 +1,246 @@
-//! Simple file-based debug logger.
+//! Logging — every message to a rotating file, and to the terminal.
 //!
-//! When `--debug` is passed, all `log!()` calls write timestamped lines
-//! to `~/whispercrabs-debug.log`. When debug is off, `log!()` is a no-op.
+//! Messages go through [`tracing`]: `tracing::error!`, `warn!` and `info!`
+//! for what anyone may need when something goes wrong, and [`dbg_log!`]
+//! for detail. Each is written to `whispercrabs.log` in the `logs` folder of
+//! the data directory (`~/.local/share/whispercrabs/logs/` on Linux) and
+//! echoed to stderr. A file reaching [`MAX_FILE_BYTES`] becomes
+//! `whispercrabs.log.1`, the older ones move up, and the oldest beyond
+//! [`KEEP_FILES`] is removed. **Show Logs** in the right-click menu opens
+//! the current file, so a failed transcription can be looked into without
+//! starting WhisperCrabs from a terminal.
+//!
+//! The level is `info` unless [`LEVEL_ENV`] says otherwise (`error`, `warn`,
+//! `info`, `debug` or `trace`); `--debug` means `debug`. Other crates only
+//! get their warnings and errors through.
 
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-static ENABLED: AtomicBool = AtomicBool::new(false);
-static LOG_FILE: Mutex<Option<std::fs::File>> = Mutex::new(None);
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, span};
 
-/// Initialize the logger. Call once at startup.
-pub fn init(debug: bool) {
-    if !debug {
-        return;
+/// Environment variable that sets the level.
+pub const LEVEL_ENV: &str = "WHISPERCRABS_LOG";
+
+/// Name of the current log file.
+pub const FILE_NAME: &str = "whispercrabs.log";
+
+/// Size at which the log file is rotated.
+pub const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the current one.
+pub const KEEP_FILES: u32 = 3;
+
+/// Crate whose messages are logged at every enabled level.
+const OWN_TARGET: &str = "whispercrabs";
+
+/// A level by name, as in [`LEVEL_ENV`].
+pub fn parse_level(name: &str) -> Option<Level> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "error" => Some(Level::ERROR),
+        "warn" | "warning" => Some(Level::WARN),
+        "info" => Some(Level::INFO),
+        "debug" => Some(Level::DEBUG),
+        "trace" => Some(Level::TRACE),
+        _ => None,
     }
-    ENABLED.store(true, Ordering::Relaxed);
+}
 
-    let path = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("whispercrabs-debug.log");
+/// The level to log at: `env` (the value of [`LEVEL_ENV`]) when it names
+/// one, otherwise `debug` with `--debug` and `info` without.
+pub fn level(env: Option<&str>, debug: bool) -> Level {
+    env.and_then(parse_level)
+        .unwrap_or(if debug { Level::DEBUG } else { Level::INFO })
+}
 
-    if let Ok(file) = OpenOptions::new().create(true).append(true).open(&path) {
-        *LOG_FILE.lock().unwrap() = Some(file);
-        eprintln!("Debug logging to {}", path.display());
+/// Whether a message from `target` at `level` is logged under `max`.
+pub fn enabled(max: Level, target: &str, level: Level) -> bool {
+    let own = target == OWN_TARGET || target.starts_with("whispercrabs::");
+    level <= max && (own || level <= Level::WARN)
+}
+
+/// Folder of the log files.
+pub fn log_dir() -> PathBuf {
+    crate::config::data_dir().join("logs")
+}
+
+/// The current log file.
+pub fn log_path() -> PathBuf {
+    log_dir().join(FILE_NAME)
+}
+
+/// Rotated file `n` of `path`: `whispercrabs.log.1` for 1.
+pub fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+/// Move `path` to `.1`, `.1` to `.2` and so on, dropping the one beyond
+/// `keep`.
+pub fn rotate(path: &Path, keep: u32) -> std::io::Result<()> {
+    let _ = std::fs::remove_file(rotated_path(path, keep));
+    for n in (1..keep).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    if path.exists() {
+        std::fs::rename(path, rotated_path(path, 1))?;
+    }
+    Ok(())
+}
+
+/// One line of the log: time, level, module and message.
+pub fn format_line(time: &str, level: Level, target: &str, message: &str) -> String {
+    let module = target.strip_prefix("whispercrabs::").unwrap_or(target);
+    format!("{time} {level:<5} {module}: {message}")
+}
+
+/// The last `max` lines of `text`.
+pub fn tail(text: &str, max: usize) -> &str {
+    if max == 0 {
+        return "";
+    }
+    match text
+        .trim_end_matches('\n')
+        .rmatch_indices('\n')
+        .nth(max - 1)
+    {
+        Some((i, _)) => &text[i + 1..],
+        None => text,
+    }
+}
+
+/// The log file, rotated when it grows past [`MAX_FILE_BYTES`].
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size + line.len() as u64 + 1 > MAX_FILE_BYTES && self.size > 0 {
+            let reopened = rotate(&self.path, KEEP_FILES).and_then(|()| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+            });
+            if let Ok(file) = reopened {
+                self.file = file;
+                self.size = 0;
+            }
+        }
+        if writeln!(self.file, "{line}").is_ok() {
+            self.size += line.len() as u64 + 1;
+        }
     }
 }
 
-/// Write a debug log line (with timestamp). No-op if `--debug` was not passed.
-pub fn debug(msg: &str) {
-    if !ENABLED.load(Ordering::Relaxed) {
-        return;
+/// Collects an event's message and fields into one string.
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        use std::fmt::Write;
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// The subscriber behind every `tracing` macro.
+struct Logger {
+    max: Level,
+    file: Mutex<Option<RotatingFile>>,
+    next_span: AtomicU64,
+}
+
+impl tracing::Subscriber for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        enabled(self.max, metadata.target(), *metadata.level())
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(tracing::level_filters::LevelFilter::from_level(self.max))
     }
-    if let Ok(mut guard) = LOG_FILE.lock() {
-        if let Some(ref mut f) = *guard {
-            let now = chrono::Local::now().format("%H:%M:%S%.3f");
-            let _ = writeln!(f, "[{now}] {msg}");
-            let _ = f.flush();
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        let metadata = event.metadata();
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+        let line = format_line(
+            &time.to_string(),
+            *metadata.level(),
+            metadata.target(),
+            &message.0,
+        );
+        eprintln!("{line}");
+        if let Ok(mut file) = self.file.lock()
+            && let Some(file) = file.as_mut()
+        {
+            file.write_line(&line);
         }
     }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Start logging. Call once at startup.
+pub fn init(debug: bool) {
+    let max = level(std::env::var(LEVEL_ENV).ok().as_deref(), debug);
+    let path = log_path();
+    let file = match RotatingFile::open(path.clone()) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("Can't write the log file {}: {e}", path.display());
+            None
+        }
+    };
+    let logger = Logger {
+        max,
+        file: Mutex::new(file),
+        // Span ids must not be 0
+        next_span: AtomicU64::new(1),
+    };
+    if tracing::subscriber::set_global_default(logger).is_ok() && debug {
+        eprintln!("Debug logging to {}", path.display());
+    }
 }
 
 /// Convenience macro for formatted debug logging.
 #[macro_export]
 macro_rules! dbg_log {
     ($($arg:tt)*) => {
-        $crate::log::debug(&format!($($arg)*))
+        tracing::debug!($($arg)*)
     };
 }
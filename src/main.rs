@@ -16,20 +16,80 @@
 //! - TTS — Local via Piper, 6 built-in voices, optional
 //! - One-click STT/TTS switching via right-click menu
 //! - Global keyboard shortcuts via D-Bus
+//! - Headset / PTT pedal triggers via evdev (Linux)
+//! - Optional "hey whisper" wake word
 //! - AI Agent-Ready: full D-Bus control
 
 #[macro_use]
 mod log;
+mod activation;
+mod announce;
 mod api;
+mod appearance;
 mod audio;
+mod auto;
+mod autosave;
+mod autostart;
+mod batch;
+mod benchmark;
+mod capabilities;
+mod captions;
+mod cli;
+mod coaching;
+mod confidence;
 mod config;
+mod corrections;
+mod daemon;
 mod db;
+mod dbus;
+mod decode;
+mod dictation;
+mod dictation_commands;
+mod dryrun;
+mod errors;
+mod export;
+mod global_shortcuts;
+mod hid;
+mod hooks;
 mod input;
+mod keybindings;
 mod local_stt;
+mod lock;
+mod media;
+mod meeting;
+mod notify;
+mod paste;
+mod policy;
+mod postprocess;
+mod preferences;
+mod profiles;
+mod provider_file;
+mod quiet;
+mod remote;
+mod retention;
+mod review;
+mod rules;
+mod secrets;
+mod session_log;
+mod settings_file;
+mod silence;
+mod sounds;
+mod split;
+mod stacking;
+mod subtitles;
+mod sync;
+mod template;
 #[cfg(test)]
 mod tests;
+mod transcription;
+mod tray;
 mod tts;
 mod ui;
+mod vocabulary;
+mod vosk_stt;
+mod wakeword;
+#[cfg(target_os = "linux")]
+mod x11;
 
 use gtk4::prelude::*;
 use std::sync::Arc;
@@ -39,7 +99,34 @@ fn main() {
     let debug = args.iter().any(|a| a == "--debug");
     log::init(debug);
 
-    let config = Arc::new(config::Config::load());
+    // Command-line mode: run one command and exit without starting GTK
+    let cli_args: Vec<String> = args[1..]
+        .iter()
+        .filter(|a| *a != "--debug")
+        .cloned()
+        .collect();
+    if let Some(command) = cli::parse(&cli_args) {
+        if let Err(e) = command.and_then(|c| cli::run(c, &config::Config::load())) {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut config = config::Config::load();
+    config.no_window |= args.iter().any(|a| a == "--no-window");
+    config.safe_mode = args.iter().any(|a| a == "--safe-mode");
+    config.hidden = args.iter().any(|a| a == autostart::HIDDEN_FLAG);
+    let config = Arc::new(config);
+
+    // Daemon mode: no GTK at all
+    if args.iter().any(|a| a == daemon::FLAG) {
+        if let Err(e) = daemon::run(config) {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
 
     let app = gtk4::Application::builder()
         .application_id("dev.whispercrabs.app")
@@ -50,8 +137,19 @@ fn main() {
         ui::build_ui(app, Arc::clone(&config_c));
     });
 
-    // Filter out --debug so GTK4 doesn't reject it as unknown option
-    let gtk_args: Vec<String> = args.into_iter().filter(|a| a != "--debug").collect();
+    // Filter out our flags so GTK4 doesn't reject them as unknown options
+    let gtk_args: Vec<String> = args
+        .into_iter()
+        .filter(|a| {
+            ![
+                "--debug",
+                "--no-window",
+                "--safe-mode",
+                autostart::HIDDEN_FLAG,
+            ]
+            .contains(&a.as_str())
+        })
+        .collect();
     let gtk_args_ref: Vec<&str> = gtk_args.iter().map(|s| s.as_str()).collect();
     app.run_with_args(&gtk_args_ref);
 }
@@ -0,0 +1,188 @@
+//! Pausing media players while recording.
+//!
+//! Music bleeding into the microphone ruins a transcript. With **Pause Media
+//! While Recording** on, every MPRIS player (Spotify, VLC, browsers, ...)
+//! that is playing when a recording starts is sent `Pause`, and the same
+//! players are sent `Play` when it stops. Players that were already paused
+//! are left alone. An allowlist in the `pause_media_players` setting limits
+//! it to the players named there, e.g. `spotify, vlc`; empty means all.
+//!
+//! Players are found on the session bus, so this does nothing on systems
+//! without one.
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use crate::db::Db;
+
+/// Whether players are paused for a recording; unset means they keep playing.
+pub const ENABLED_SETTING: &str = "pause_media";
+
+/// Setting of the allowlist, player ids separated by commas.
+pub const PLAYERS_SETTING: &str = "pause_media_players";
+
+/// Bus name prefix every MPRIS player owns a name under.
+pub const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// How long a player may take to answer, in milliseconds; recording
+/// doesn't wait for a hung one.
+const CALL_TIMEOUT_MS: i32 = 300;
+
+/// The player id of an MPRIS bus name: `spotify` for
+/// `org.mpris.MediaPlayer2.spotify`, `firefox` for
+/// `org.mpris.MediaPlayer2.firefox.instance_1_84`.
+pub fn player_id(bus_name: &str) -> Option<String> {
+    let rest = bus_name.strip_prefix(MPRIS_PREFIX)?;
+    let id = rest.split('.').next().unwrap_or_default();
+    (!id.is_empty()).then(|| id.to_lowercase())
+}
+
+/// The player ids of an allowlist: separated by commas or whitespace,
+/// lowercased, without repeats.
+pub fn parse_players(text: &str) -> Vec<String> {
+    let mut players: Vec<String> = Vec::new();
+    for id in text.split([',', ' ', '\n']).map(str::trim) {
+        let id = id.to_lowercase();
+        if !id.is_empty() && !players.contains(&id) {
+            players.push(id);
+        }
+    }
+    players
+}
+
+/// Whether the player owning `bus_name` may be paused under `allowlist`.
+pub fn allowed(allowlist: &[String], bus_name: &str) -> bool {
+    player_id(bus_name).is_some_and(|id| allowlist.is_empty() || allowlist.contains(&id))
+}
+
+/// The allowlist to pause with, or `None` when pausing is off.
+pub fn stored(d: &Db) -> Option<Vec<String>> {
+    let enabled = d.get_setting(ENABLED_SETTING).ok().flatten();
+    if enabled.as_deref() != Some("true") {
+        return None;
+    }
+    Some(
+        d.get_setting(PLAYERS_SETTING)
+            .ok()
+            .flatten()
+            .map(|text| parse_players(&text))
+            .unwrap_or_default(),
+    )
+}
+
+/// Players paused for a recording; dropping it resumes them.
+pub struct PausedPlayers {
+    connection: Option<gio::DBusConnection>,
+    names: Vec<String>,
+}
+
+impl PausedPlayers {
+    /// Bus names of the players that were paused.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+impl Drop for PausedPlayers {
+    fn drop(&mut self) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        for name in &self.names {
+            if let Err(e) = call(connection, name, PLAYER_INTERFACE, "Play", None) {
+                dbg_log!("[MEDIA] resuming {name} failed: {e}");
+            }
+        }
+    }
+}
+
+/// Pause the players allowed by `allowlist` that are playing right now.
+pub fn pause_playing(allowlist: &[String]) -> PausedPlayers {
+    let connection = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            dbg_log!("[MEDIA] no session bus: {e}");
+            return PausedPlayers {
+                connection: None,
+                names: Vec::new(),
+            };
+        }
+    };
+    let mut names = Vec::new();
+    for name in player_names(&connection) {
+        if !allowed(allowlist, &name) || !is_playing(&connection, &name) {
+            continue;
+        }
+        match call(&connection, &name, PLAYER_INTERFACE, "Pause", None) {
+            Ok(_) => names.push(name),
+            Err(e) => dbg_log!("[MEDIA] pausing {name} failed: {e}"),
+        }
+    }
+    PausedPlayers {
+        connection: Some(connection),
+        names,
+    }
+}
+
+/// Bus names of the MPRIS players on the bus.
+fn player_names(connection: &gio::DBusConnection) -> Vec<String> {
+    let reply = connection.call_sync(
+        Some("org.freedesktop.DBus"),
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus",
+        "ListNames",
+        None,
+        Some(glib::VariantTy::new("(as)").expect("valid type")),
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    );
+    reply
+        .ok()
+        .and_then(|r| r.get::<(Vec<String>,)>())
+        .map(|(names,)| {
+            names
+                .into_iter()
+                .filter(|n| n.starts_with(MPRIS_PREFIX))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_playing(connection: &gio::DBusConnection, name: &str) -> bool {
+    call(
+        connection,
+        name,
+        "org.freedesktop.DBus.Properties",
+        "Get",
+        Some(&(PLAYER_INTERFACE, "PlaybackStatus").to_variant()),
+    )
+    .ok()
+    .and_then(|reply| reply.child_value(0).as_variant())
+    .and_then(|status| status.get::<String>())
+    .is_some_and(|status| status == "Playing")
+}
+
+fn call(
+    connection: &gio::DBusConnection,
+    name: &str,
+    interface: &str,
+    method: &str,
+    params: Option<&glib::Variant>,
+) -> Result<glib::Variant, glib::Error> {
+    connection.call_sync(
+        Some(name),
+        MPRIS_PATH,
+        interface,
+        method,
+        params,
+        None,
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    )
+}
@@ -0,0 +1,268 @@
+//! Meeting mode — an hour-long recording transcribed while it runs.
+//!
+//! **Start Meeting...** opens a window that records until **Stop**, handing
+//! the audio to the transcriber every 30–60 seconds (the `meeting_segment_secs`
+//! setting) without a gap, like [`crate::split`] does with minutes. Each
+//! segment is appended to a live transcript in the window as soon as it is
+//! transcribed, with its offset from the start, e.g. `[12:30]`. Segments may
+//! finish out of order; they are shown in recording order.
+//!
+//! When the meeting stops and its last segment is in, the whole transcript
+//! becomes one History entry, with each segment's timings shifted to the
+//! meeting's clock so subtitles and the segment view line up. A segment
+//! whose transcription fails keeps its audio in the parts folder and is
+//! marked in the transcript.
+
+use chrono::{DateTime, Local};
+
+use crate::db::Db;
+use crate::local_stt::Segment;
+
+/// Settings key of the segment length in seconds.
+pub const SEGMENT_SETTING: &str = "meeting_segment_secs";
+
+/// Segment lengths offered, in seconds.
+pub const CHOICES: &[u32] = &[30, 45, 60];
+
+/// Segment length unless the setting says otherwise.
+pub const DEFAULT_SEGMENT_SECS: u32 = 45;
+
+/// The segment length stored in settings or sent over D-Bus, when it is
+/// between 30 and 60 seconds.
+pub fn parse_secs(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|s| (CHOICES[0]..=CHOICES[CHOICES.len() - 1]).contains(s))
+}
+
+/// The saved segment length.
+pub fn segment_secs(d: &Db) -> u32 {
+    d.get_setting(SEGMENT_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|v| parse_secs(&v))
+        .unwrap_or(DEFAULT_SEGMENT_SECS)
+}
+
+/// An offset from the start of the meeting: `MM:SS`, or `H:MM:SS` from the
+/// first hour on.
+pub fn offset_label(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// Where a segment of the meeting stands.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Part {
+    /// Handed to the transcriber, no result yet.
+    Pending,
+    /// Transcribed: its text and timed segments, relative to the meeting.
+    Done {
+        text: String,
+        segments: Vec<Segment>,
+        language: Option<String>,
+    },
+    /// Transcription failed with this message.
+    Failed(String),
+}
+
+/// A segment of the meeting: where it starts and ends, and its transcript.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chunk {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub part: Part,
+}
+
+/// The transcript of a running meeting, segment by segment.
+#[derive(Clone, Debug)]
+pub struct Meeting {
+    pub started: DateTime<Local>,
+    chunks: Vec<Chunk>,
+}
+
+impl Meeting {
+    pub fn new(started: DateTime<Local>) -> Self {
+        Self {
+            started,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Milliseconds recorded so far in handed-off segments.
+    pub fn recorded_ms(&self) -> i64 {
+        self.chunks.last().map_or(0, |c| c.end_ms)
+    }
+
+    /// Add a segment of `duration_ms` that went to the transcriber; its
+    /// index for [`Self::finish`] and [`Self::fail`].
+    pub fn push(&mut self, duration_ms: i64) -> usize {
+        let start_ms = self.recorded_ms();
+        self.chunks.push(Chunk {
+            start_ms,
+            end_ms: start_ms + duration_ms.max(0),
+            part: Part::Pending,
+        });
+        self.chunks.len() - 1
+    }
+
+    /// Record the transcript of segment `index`. `segments` are relative to
+    /// the segment and are shifted to the meeting's clock; without any, the
+    /// text becomes one segment spanning it.
+    pub fn finish(
+        &mut self,
+        index: usize,
+        text: &str,
+        segments: &[Segment],
+        language: Option<String>,
+    ) {
+        let Some(chunk) = self.chunks.get_mut(index) else {
+            return;
+        };
+        let text = text.trim().to_string();
+        let segments = if segments.is_empty() {
+            if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![Segment {
+                    start_ms: chunk.start_ms,
+                    end_ms: chunk.end_ms,
+                    text: text.clone(),
+                    confidence: None,
+                }]
+            }
+        } else {
+            segments
+                .iter()
+                .map(|s| Segment {
+                    start_ms: s.start_ms + chunk.start_ms,
+                    end_ms: s.end_ms + chunk.start_ms,
+                    ..s.clone()
+                })
+                .collect()
+        };
+        chunk.part = Part::Done {
+            text,
+            segments,
+            language,
+        };
+    }
+
+    /// Mark segment `index` as failed.
+    pub fn fail(&mut self, index: usize, message: &str) {
+        if let Some(chunk) = self.chunks.get_mut(index) {
+            chunk.part = Part::Failed(message.to_string());
+        }
+    }
+
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    /// Segments still waiting for their transcript.
+    pub fn pending(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|c| c.part == Part::Pending)
+            .count()
+    }
+
+    /// Segments transcribed so far.
+    pub fn transcribed(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|c| matches!(c.part, Part::Done { .. }))
+            .count()
+    }
+
+    /// The live transcript shown in the window: a paragraph per segment
+    /// with its offset, in recording order. Silent segments are left out.
+    pub fn document(&self) -> String {
+        self.chunks
+            .iter()
+            .filter_map(|c| {
+                let body = match &c.part {
+                    Part::Pending => "…".to_string(),
+                    Part::Done { text, .. } if text.is_empty() => return None,
+                    Part::Done { text, .. } => text.clone(),
+                    Part::Failed(e) => format!("(not transcribed: {e})"),
+                };
+                Some(format!("[{}] {body}", offset_label(c.start_ms)))
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// The text saved to History: the transcribed segments, in order, a
+    /// paragraph each.
+    pub fn text(&self) -> String {
+        self.chunks
+            .iter()
+            .filter_map(|c| match &c.part {
+                Part::Done { text, .. } if !text.is_empty() => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Timed segments of the whole meeting, on its clock.
+    pub fn segments(&self) -> Vec<Segment> {
+        self.chunks
+            .iter()
+            .flat_map(|c| match &c.part {
+                Part::Done { segments, .. } => segments.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The language most segments were spoken in, when any reported one.
+    pub fn language(&self) -> Option<String> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for chunk in &self.chunks {
+            if let Part::Done {
+                language: Some(language),
+                ..
+            } = &chunk.part
+            {
+                match counts.iter_mut().find(|(l, _)| l == language) {
+                    Some((_, n)) => *n += 1,
+                    None => counts.push((language, 1)),
+                }
+            }
+        }
+        // First seen wins a tie
+        let mut best: Option<(&str, usize)> = None;
+        for (language, n) in counts {
+            if best.is_none_or(|(_, b)| n > b) {
+                best = Some((language, n));
+            }
+        }
+        best.map(|(l, _)| l.to_string())
+    }
+
+    /// Status line of the window.
+    pub fn status(&self, recording: bool) -> String {
+        let elapsed = offset_label(
+            Local::now()
+                .signed_duration_since(self.started)
+                .num_milliseconds(),
+        );
+        match (recording, self.pending()) {
+            (true, _) => format!(
+                "Recording {elapsed} · {} segments transcribed",
+                self.transcribed()
+            ),
+            (false, 0) => "Stopped".to_string(),
+            (false, n) => format!("Stopped · transcribing {n} more segments..."),
+        }
+    }
+}
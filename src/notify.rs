@@ -0,0 +1,64 @@
+//! Transcript notifications — a desktop notification for each result.
+//!
+//! The status label next to the button is easy to miss with the widget in
+//! a screen corner. With notifications on, every delivered transcript also
+//! raises a desktop notification (through `GNotification`, so the portal on
+//! Flatpak and `org.freedesktop.Notifications` elsewhere) with the start of
+//! the text and two buttons: **Copy Again**, which puts the transcript back
+//! on the clipboard, and **Open History**. Quiet hours hold them back.
+
+use gtk4::prelude::*;
+
+/// Settings key of the option.
+pub const SETTING: &str = "notify_transcripts";
+
+/// Characters of the transcript shown in the notification body.
+pub const PREVIEW_CHARS: usize = 120;
+
+/// Notification id; each transcript replaces the previous one.
+const ID: &str = "transcript";
+
+/// The notification body: the transcript on one line, shortened to
+/// [`PREVIEW_CHARS`] characters with an ellipsis.
+pub fn preview(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut chars = line.chars();
+    let head: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{}\u{2026}", head.trim_end())
+    } else {
+        head
+    }
+}
+
+/// The notification title: `live` dictations were typed, the others copied.
+pub fn title(live: bool) -> &'static str {
+    if live {
+        "Typed into the focused app"
+    } else {
+        "Copied to the clipboard"
+    }
+}
+
+/// Show the notification for `text`, unless it's quiet hours.
+pub fn send(text: &str, live: bool) {
+    let Some(app) = gtk4::gio::Application::default() else {
+        return;
+    };
+    if crate::quiet::is_quiet() {
+        return;
+    }
+    let notification = gtk4::gio::Notification::new(title(live));
+    notification.set_body(Some(&preview(text)));
+    notification.add_button_with_target_value(
+        "Copy Again",
+        "app.copy-transcript",
+        Some(&text.to_variant()),
+    );
+    // Daemon mode has no History window
+    if app.lookup_action("show-history").is_some() {
+        notification.add_button("Open History", "app.show-history");
+        notification.set_default_action("app.show-history");
+    }
+    app.send_notification(Some(ID), &notification);
+}
@@ -0,0 +1,151 @@
+//! Paste timing — when and how fast auto-paste presses Ctrl+V.
+//!
+//! Right after a transcript is copied, the clipboard owner needs a moment
+//! before another application can read it; how long differs between apps
+//! (an Electron editor wants more than a terminal). The wait, the delay
+//! between key events, and an optional check that the clipboard still holds
+//! the transcript are set once in `.env` and can be overridden per window
+//! class:
+//!
+//! ```env
+//! PASTE_DELAY_MS=150
+//! PASTE_KEY_DELAY_MS=12
+//! PASTE_VERIFY=false
+//! PASTE_APPS=code:400, jetbrains-idea:500:20:verify, kitty:50
+//! ```
+//!
+//! Each `PASTE_APPS` entry is `class:delay[:key delay][:verify]`; the class
+//! is matched case-insensitively against the focused window's class (see
+//! [`crate::input::focused_app_class`]).
+
+use std::sync::Mutex;
+
+/// Wait before pressing Ctrl+V when nothing else is configured.
+pub const DEFAULT_DELAY_MS: u64 = 150;
+
+/// Delay between key events, the default of `xdotool key`.
+pub const DEFAULT_KEY_DELAY_MS: u64 = 12;
+
+/// Longest wait or key delay accepted.
+const MAX_DELAY_MS: u64 = 5000;
+
+/// How to paste into one application.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PasteTiming {
+    /// Wait after copying before the key press.
+    pub delay_ms: u64,
+    /// Delay between the key events of Ctrl+V.
+    pub key_delay_ms: u64,
+    /// Check the transcript is still on the clipboard before and after the
+    /// key press, copying it again if a clipboard manager replaced it.
+    pub verify: bool,
+}
+
+impl PasteTiming {
+    pub const DEFAULT: Self = Self {
+        delay_ms: DEFAULT_DELAY_MS,
+        key_delay_ms: DEFAULT_KEY_DELAY_MS,
+        verify: false,
+    };
+}
+
+impl Default for PasteTiming {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Paste timing for all applications, with overrides by window class.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PasteConfig {
+    pub default: PasteTiming,
+    /// Lowercase window class and its timing, in `PASTE_APPS` order.
+    pub apps: Vec<(String, PasteTiming)>,
+}
+
+impl PasteConfig {
+    const DEFAULT: Self = Self {
+        default: PasteTiming::DEFAULT,
+        apps: Vec::new(),
+    };
+
+    /// Read `PASTE_DELAY_MS`, `PASTE_KEY_DELAY_MS`, `PASTE_VERIFY` and
+    /// `PASTE_APPS`; invalid values keep the defaults.
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let mut default = PasteTiming::DEFAULT;
+        if let Some(ms) = get("PASTE_DELAY_MS").as_deref().and_then(parse_ms) {
+            default.delay_ms = ms;
+        }
+        if let Some(ms) = get("PASTE_KEY_DELAY_MS").as_deref().and_then(parse_ms) {
+            default.key_delay_ms = ms;
+        }
+        if let Some(verify) = get("PASTE_VERIFY") {
+            default.verify = matches!(verify.trim(), "true" | "1" | "yes");
+        }
+        let apps = get("PASTE_APPS")
+            .map(|v| parse_apps(&v, default))
+            .unwrap_or_default();
+        Self { default, apps }
+    }
+
+    /// Timing for the window class `class`, or the default one.
+    pub fn for_class(&self, class: Option<&str>) -> PasteTiming {
+        let Some(class) = class.map(|c| c.trim().to_lowercase()) else {
+            return self.default;
+        };
+        self.apps
+            .iter()
+            .find(|(app, _)| *app == class)
+            .map_or(self.default, |(_, timing)| *timing)
+    }
+}
+
+/// The `PASTE_APPS` entries, comma separated; malformed ones are skipped.
+/// Fields an entry leaves out come from `default`.
+pub fn parse_apps(value: &str, default: PasteTiming) -> Vec<(String, PasteTiming)> {
+    value
+        .split(',')
+        .filter_map(|entry| parse_entry(entry, default))
+        .collect()
+}
+
+fn parse_entry(entry: &str, default: PasteTiming) -> Option<(String, PasteTiming)> {
+    let mut fields = entry.split(':').map(str::trim);
+    let class = fields.next().filter(|c| !c.is_empty())?.to_lowercase();
+    let mut timing = PasteTiming {
+        delay_ms: parse_ms(fields.next()?)?,
+        ..default
+    };
+    for field in fields {
+        match field {
+            "verify" => timing.verify = true,
+            "noverify" => timing.verify = false,
+            ms => timing.key_delay_ms = parse_ms(ms)?,
+        }
+    }
+    Some((class, timing))
+}
+
+fn parse_ms(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .trim_end_matches("ms")
+        .parse()
+        .ok()
+        .filter(|ms| *ms <= MAX_DELAY_MS)
+}
+
+static CONFIG: Mutex<PasteConfig> = Mutex::new(PasteConfig::DEFAULT);
+
+/// Set the paste timing used from now on.
+pub fn set_config(config: PasteConfig) {
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Timing for the window class `class`.
+pub fn timing_for(class: Option<&str>) -> PasteTiming {
+    CONFIG
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .for_class(class)
+}
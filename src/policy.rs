@@ -0,0 +1,182 @@
+//! Organization-managed configuration.
+//!
+//! Administrators can drop a read-only TOML file at
+//! `/etc/whisperclip/config.toml` (or `/etc/whispercrabs/config.toml`, read
+//! when the first is absent) to restrict which transcription providers users
+//! may pick. Locked choices are greyed out in the right-click menu and
+//! rejected when requested over D-Bus.
+//!
+//! ```toml
+//! [policy]
+//! # Only these provider ids may be selected (omit to allow all)
+//! allowed_providers = ["local-base", "local-small", "ollama"]
+//! # Block API providers that aren't on localhost
+//! disable_cloud = true
+//! # Only local whisper models may be used
+//! local_only = false
+//! # Pin a single provider and lock the menu
+//! force_provider = "local-base"
+//! ```
+//!
+//! A policy file that exists but cannot be parsed fails closed (local only).
+
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Runtime "Local only" privacy switch. Every network code path checks this
+/// through [`ensure_network_allowed`] before opening a connection.
+static LOCAL_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// System-wide policy file locations; the first that exists is used.
+pub const SYSTEM_POLICY_PATHS: &[&str] = &[
+    "/etc/whisperclip/config.toml",
+    "/etc/whispercrabs/config.toml",
+];
+
+/// Admin-enforced restrictions. The default policy allows everything.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub allowed_providers: Option<Vec<String>>,
+    #[serde(default)]
+    pub disable_cloud: bool,
+    #[serde(default)]
+    pub local_only: bool,
+    #[serde(default)]
+    pub force_provider: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PolicyFile {
+    #[serde(default)]
+    policy: Policy,
+}
+
+impl Policy {
+    /// Load the system policy, or the permissive default if no file exists.
+    pub fn load() -> Self {
+        let Some(path) = SYSTEM_POLICY_PATHS
+            .iter()
+            .map(Path::new)
+            .find(|p| p.exists())
+        else {
+            return Self::default();
+        };
+        match Self::load_from(path) {
+            Ok(p) => {
+                tracing::info!("Organization policy loaded from {}", path.display());
+                p
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Invalid policy file {} ({e}); enforcing local only",
+                    path.display()
+                );
+                Self {
+                    local_only: true,
+                    ..Self::default()
+                }
+            }
+        }
+    }
+
+    /// Load a policy from a specific TOML file.
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("read policy: {e}"))?;
+        Self::parse(&text)
+    }
+
+    /// Parse policy TOML text.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: PolicyFile = toml::from_str(text).map_err(|e| format!("parse policy: {e}"))?;
+        Ok(file.policy)
+    }
+
+    /// Whether any restriction is in effect.
+    pub fn is_managed(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Whether the provider menu is pinned to a single choice.
+    pub fn is_locked(&self) -> bool {
+        self.force_provider.is_some()
+    }
+
+    /// Whether a provider id may be selected. `base_url` is the endpoint the
+    /// provider would use (only consulted for API providers).
+    pub fn allows(&self, provider_id: &str, base_url: Option<&str>) -> bool {
+        if let Some(ref forced) = self.force_provider {
+            return forced == provider_id;
+        }
+        if let Some(ref allowed) = self.allowed_providers
+            && !allowed.iter().any(|p| p == provider_id)
+        {
+            return false;
+        }
+        let is_local_model = crate::config::is_local_provider(provider_id);
+        if self.local_only && !is_local_model {
+            return false;
+        }
+        if self.disable_cloud && !is_local_model {
+            let url =
+                base_url.or_else(|| crate::config::find_preset(provider_id).map(|p| p.base_url));
+            if !url.is_some_and(is_local_url) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The provider to fall back to when the requested one is not allowed.
+    pub fn fallback_provider(&self) -> String {
+        if let Some(ref forced) = self.force_provider {
+            return forced.clone();
+        }
+        if let Some(ref allowed) = self.allowed_providers
+            && let Some(first) = allowed.iter().find(|p| self.allows(p, None))
+        {
+            return first.clone();
+        }
+        crate::config::DEFAULT_LOCAL_MODEL.to_string()
+    }
+}
+
+/// Host part of an http(s) URL (empty for other schemes).
+pub fn url_host(url: &str) -> &str {
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .unwrap_or("");
+    if let Some(v6) = rest.strip_prefix('[') {
+        v6.split(']').next().unwrap_or("")
+    } else {
+        rest.split(['/', ':']).next().unwrap_or("")
+    }
+}
+
+/// Whether a URL points at the local machine (no data leaves the host).
+pub fn is_local_url(url: &str) -> bool {
+    matches!(url_host(url), "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Turn the runtime "Local only" privacy switch on or off.
+pub fn set_local_only(enabled: bool) {
+    LOCAL_ONLY.store(enabled, Ordering::SeqCst);
+}
+
+/// Whether the "Local only" privacy switch is on.
+pub fn local_only() -> bool {
+    LOCAL_ONLY.load(Ordering::SeqCst)
+}
+
+/// Gate for all outgoing network requests. In local-only mode only loopback
+/// URLs (e.g. a local Ollama server) are permitted.
+pub fn ensure_network_allowed(url: &str) -> Result<(), String> {
+    if local_only() && !is_local_url(url) {
+        dbg_log!("[PRIVACY] blocked network request to {url}");
+        return Err("Local-only mode is on: network access blocked".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+//! Post-processing — a chat model rewrites each transcript.
+//!
+//! With a mode picked under **Post-Processing** in the menu, the transcript
+//! goes to an OpenAI-compatible `/chat/completions` endpoint with the mode's
+//! instructions as the system prompt, and the reply is what gets copied.
+//! The endpoint is one of the API providers set up for transcription (its
+//! base URL and key) with a chat model of its own. History keeps the text
+//! before the rewrite next to the result.
+
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::db::Db;
+use crate::local_stt::Transcript;
+
+pub const MODE_SETTING: &str = "postprocess_mode";
+pub const PROVIDER_SETTING: &str = "postprocess_provider";
+pub const MODEL_SETTING: &str = "postprocess_model";
+/// The system prompt of the [`CUSTOM`] mode.
+pub const PROMPT_SETTING: &str = "postprocess_prompt";
+
+/// Longest custom prompt, in bytes: what a setting can hold.
+pub const MAX_PROMPT_BYTES: usize = crate::db::MAX_SETTING_BYTES;
+
+/// A custom prompt that fits in [`PROMPT_SETTING`], or why it doesn't.
+pub fn check_prompt(prompt: &str) -> Result<(), String> {
+    if prompt.len() > MAX_PROMPT_BYTES {
+        return Err(format!(
+            "The prompt is too long ({} bytes, the limit is {MAX_PROMPT_BYTES})",
+            prompt.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Mode id of no post-processing, the default.
+pub const OFF: &str = "off";
+
+/// Mode id of the user's own prompt.
+pub const CUSTOM: &str = "custom";
+
+/// How long a rewrite may take before the transcript is used as it is.
+pub const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A built-in rewrite.
+pub struct Mode {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub prompt: &'static str,
+}
+
+pub const MODES: &[Mode] = &[
+    Mode {
+        id: "punctuation",
+        label: "Fix Punctuation",
+        prompt: "Fix the punctuation, capitalization and obvious transcription errors of \
+                 the dictated text the user sends. Keep its wording and language. Reply with \
+                 the corrected text only.",
+    },
+    Mode {
+        id: "email",
+        label: "Format as Email",
+        prompt: "Rewrite the dictated text the user sends as a short, polite email with a \
+                 greeting and a sign-off, in the language it was spoken in. Reply with the \
+                 email only.",
+    },
+    Mode {
+        id: "translate-de",
+        label: "Translate to German",
+        prompt: "Translate the dictated text the user sends into German. Reply with the \
+                 translation only.",
+    },
+];
+
+/// Chat models used when none is set, per API preset. Custom endpoints
+/// need one set.
+pub const DEFAULT_MODELS: &[(&str, &str)] = &[
+    ("groq", "llama-3.1-8b-instant"),
+    ("ollama", "llama3.2"),
+    ("openrouter", "openai/gpt-4o-mini"),
+    ("lmstudio", "local-model"),
+];
+
+pub fn find_mode(id: &str) -> Option<&'static Mode> {
+    MODES.iter().find(|m| m.id == id)
+}
+
+/// The chat model of API preset `provider` when none is set.
+pub fn default_model(provider: &str) -> Option<&'static str> {
+    DEFAULT_MODELS
+        .iter()
+        .find(|(id, _)| *id == provider)
+        .map(|(_, model)| *model)
+}
+
+/// Where and how a transcript is rewritten.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Job {
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub prompt: String,
+}
+
+/// The post-processing the settings ask for: `Ok(None)` when it is off, an
+/// error when the chosen mode can't run.
+pub fn job(d: &Db, config: &Config) -> Result<Option<Job>, String> {
+    let setting = |key| d.get_setting(key).ok().flatten().filter(|v| !v.is_empty());
+    let mode = setting(MODE_SETTING).unwrap_or_else(|| OFF.to_string());
+    let prompt = match mode.as_str() {
+        OFF => return Ok(None),
+        CUSTOM => {
+            setting(PROMPT_SETTING).ok_or("Write a prompt for custom post-processing first")?
+        }
+        id => find_mode(id)
+            .ok_or_else(|| format!("Unknown post-processing mode \"{id}\""))?
+            .prompt
+            .to_string(),
+    };
+    let mut backends = crate::transcription::api_backends(d, config).into_iter();
+    let backend = match setting(PROVIDER_SETTING) {
+        Some(provider) => backends.find(|b| b.provider == provider),
+        None => backends.next(),
+    }
+    .ok_or("No API provider set up for post-processing")?;
+    let model = setting(MODEL_SETTING)
+        .or_else(|| default_model(&backend.provider).map(str::to_string))
+        .ok_or("Choose a chat model for post-processing")?;
+    Ok(Some(Job {
+        url: chat_url(&backend.base_url),
+        api_key: backend.api_key,
+        model,
+        prompt,
+    }))
+}
+
+/// The chat completions endpoint of an API base URL.
+pub fn chat_url(base_url: &str) -> String {
+    format!("{}/chat/completions", base_url.trim_end_matches('/'))
+}
+
+/// The request rewriting `text` as `job` says.
+pub fn request_body(job: &Job, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "model": job.model,
+        "temperature": 0.2,
+        "messages": [
+            { "role": "system", "content": job.prompt },
+            { "role": "user", "content": text },
+        ],
+    })
+}
+
+/// The rewritten text of a chat completion response.
+pub fn parse_reply(json: &serde_json::Value) -> Result<String, String> {
+    json["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("No reply in post-processing response: {json}"))
+}
+
+/// Rewrite `transcript` as `job` says, returning the text before; a failed
+/// rewrite leaves the transcript as it is.
+pub fn rewrite(job: &Job, transcript: &mut Transcript) -> Option<String> {
+    if transcript.text.trim().is_empty() {
+        return None;
+    }
+    match run(job, &transcript.text) {
+        Ok(text) => Some(std::mem::replace(&mut transcript.text, text)),
+        Err(e) => {
+            tracing::warn!("Post-processing failed, keeping the transcript: {e}");
+            None
+        }
+    }
+}
+
+/// Rewrite `text` as `job` says and wait for the result.
+pub fn run(job: &Job, text: &str) -> Result<String, String> {
+    if !job.url.starts_with("http://") && !job.url.starts_with("https://") {
+        return Err("Invalid API URL: only http:// and https:// are allowed".into());
+    }
+    crate::policy::ensure_network_allowed(&job.url)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("HTTP client error: {e}"))?;
+    let mut request = client.post(&job.url).json(&request_body(job, text));
+    if let Some(key) = &job.api_key {
+        request = request.bearer_auth(key);
+    }
+    let resp = request
+        .send()
+        .map_err(|e| format!("Post-processing request failed: {e}"))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().unwrap_or_default();
+        return Err(format!("Post-processing error {status}: {body}"));
+    }
+    let json: serde_json::Value = resp.json().map_err(|e| format!("JSON parse error: {e}"))?;
+    parse_reply(&json)
+}
@@ -0,0 +1,51 @@
+//! Preferences window helpers — the parts of the window that aren't widgets.
+//!
+//! The window itself (in `ui.rs`) drives the same stateful actions as the
+//! right-click menu, so a choice made in either place is persisted by one
+//! handler and shows up in the other. Settings that had no menu entry yet —
+//! the input device, auto-paste and per-provider model names — get actions
+//! of their own.
+
+/// D-Bus name and object path of the running app, for shortcut commands.
+pub const DBUS_DEST: &str = "dev.whispercrabs.app";
+pub const DBUS_PATH: &str = "/dev/whispercrabs/app";
+
+/// Actions worth a global shortcut, with the name shown for each.
+pub const SHORTCUT_ACTIONS: &[(&str, &str)] = &[
+    ("Record", "record"),
+    ("Stop", "stop"),
+    ("Read Clipboard", "read-clipboard"),
+    ("History", "show-history"),
+];
+
+/// Settings key of the API model chosen for preset `provider`.
+pub fn model_setting(provider: &str) -> String {
+    format!("api_model_{provider}")
+}
+
+/// The model to send to a preset: the stored choice, or the preset's
+/// `default` when none (or only whitespace) is stored.
+pub fn effective_model(stored: Option<&str>, default: &str) -> String {
+    stored
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .unwrap_or(default)
+        .to_string()
+}
+
+/// The command that activates `action` in the running app, to bind to a
+/// shortcut in the desktop's keyboard settings.
+pub fn shortcut_command(action: &str) -> String {
+    format!(
+        "gdbus call --session --dest={DBUS_DEST} --object-path={DBUS_PATH} \
+         --method=org.gtk.Actions.Activate {action} [] {{}}"
+    )
+}
+
+/// Position of `value` among `(value, label)` choices.
+pub fn choice_index(choices: &[(String, String)], value: &str) -> Option<u32> {
+    choices
+        .iter()
+        .position(|(v, _)| v == value)
+        .map(|i| i as u32)
+}
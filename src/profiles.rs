@@ -0,0 +1,91 @@
+//! Record profiles — named one-shot configurations for per-profile hotkeys.
+//!
+//! Each profile can override the provider, the spoken language, whether
+//! whisper translates to English, what is kept in History and how the copied
+//! text is wrapped (see [`crate::template`]). Binding `record-profile` with
+//! a profile name to a global shortcut records a single dictation with that
+//! configuration; the normal settings are untouched afterwards.
+//!
+//! Profiles are stored as a JSON array in the `record_profiles` setting:
+//!
+//! ```json
+//! [
+//!   {"name": "dictate-en", "language": "en"},
+//!   {"name": "translate", "provider": "local-small-multi", "translate": true},
+//!   {"name": "fast", "provider": "groq"},
+//!   {"name": "secrets", "history": "off"},
+//!   {"name": "journal", "template": "- [{timestamp}] {text}"}
+//! ]
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// A named one-shot recording configuration. Unset fields keep the current setting.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Identifier passed to the `record-profile` action.
+    pub name: String,
+    /// Provider id (API preset, `custom`, or a local model) for this dictation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// ISO 639-1 code or `auto` (multilingual models and APIs).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Translate speech to English instead of transcribing it.
+    #[serde(default)]
+    pub translate: bool,
+    /// What to keep in History for this dictation (`full`, `preview` or `off`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<crate::db::HistoryMode>,
+    /// What is copied, with the transcript as `{text}` (see [`crate::template`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// Parse and validate the JSON profile list stored in settings.
+pub fn parse_profiles(json: &str) -> Result<Vec<Profile>, String> {
+    let profiles: Vec<Profile> =
+        serde_json::from_str(json).map_err(|e| format!("invalid profiles JSON: {e}"))?;
+    for (i, p) in profiles.iter().enumerate() {
+        if p.name.is_empty()
+            || !p
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "invalid profile name '{}': use letters, digits, '-' or '_'",
+                p.name
+            ));
+        }
+        if profiles[..i].iter().any(|q| q.name == p.name) {
+            return Err(format!("duplicate profile name '{}'", p.name));
+        }
+        if let Some(ref provider) = p.provider
+            && !is_known_provider(provider)
+        {
+            return Err(format!("unknown provider '{provider}'"));
+        }
+        if let Some(ref lang) = p.language
+            && !crate::config::is_known_language(lang)
+        {
+            return Err(format!("unknown language '{lang}'"));
+        }
+        if let Some(ref template) = p.template {
+            crate::template::validate(template)?;
+        }
+    }
+    Ok(profiles)
+}
+
+/// Look up a profile by name.
+pub fn find<'a>(profiles: &'a [Profile], name: &str) -> Option<&'a Profile> {
+    profiles.iter().find(|p| p.name == name)
+}
+
+fn is_known_provider(id: &str) -> bool {
+    id == "custom"
+        || crate::config::find_preset(id).is_some()
+        || crate::config::is_local_provider(id)
+}
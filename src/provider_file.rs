@@ -0,0 +1,111 @@
+//! Shareable provider definitions (`.whispercrabs-provider` files).
+//!
+//! The Custom API dialog can export the endpoint it is configured with as a
+//! small JSON file, minus the API key, so a team can hand out one file for
+//! their self-hosted server. Dropping such a file onto the button opens the
+//! Custom API dialog pre-filled with it; the key is entered locally.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "name": "Team Whisper",
+//!   "base_url": "https://whisper.example.com/v1",
+//!   "model": "large-v3",
+//!   "path": "/inference"
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// File name extension, without the leading dot.
+pub const EXTENSION: &str = "whispercrabs-provider";
+
+/// Format version written by this build.
+pub const VERSION: u32 = 1;
+
+/// Largest file accepted on import.
+pub const MAX_FILE_BYTES: u64 = 4096;
+
+/// A provider endpoint without credentials.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderFile {
+    pub version: u32,
+    /// Display name, also used for the exported file name.
+    pub name: String,
+    pub base_url: String,
+    pub model: String,
+    /// Transcription endpoint path; omitted for the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl ProviderFile {
+    /// Parse and validate an imported file. Files carrying an API key are
+    /// rejected (unknown field) so secrets are never imported by accident.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: Self =
+            serde_json::from_str(text).map_err(|e| format!("invalid provider file: {e}"))?;
+        if file.version != VERSION {
+            return Err(format!(
+                "unsupported provider file version {}",
+                file.version
+            ));
+        }
+        if !(file.base_url.starts_with("http://") || file.base_url.starts_with("https://")) {
+            return Err("base_url must be an http(s) URL".into());
+        }
+        if file.model.trim().is_empty() {
+            return Err("model is empty".into());
+        }
+        if let Some(ref path) = file.path
+            && !path.starts_with('/')
+        {
+            return Err("path must start with '/'".into());
+        }
+        Ok(file)
+    }
+
+    /// Pretty-printed JSON for export.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Suggested file name, e.g. `team-whisper.whispercrabs-provider`.
+    pub fn file_name(&self) -> String {
+        let slug: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let slug = slug
+            .split('-')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let slug = if slug.is_empty() {
+            "provider".into()
+        } else {
+            slug
+        };
+        format!("{slug}.{EXTENSION}")
+    }
+}
+
+/// Read and parse a provider file from disk.
+pub fn load(path: &std::path::Path) -> Result<ProviderFile, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("read provider file: {e}"))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err("provider file too large".into());
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("read provider file: {e}"))?;
+    ProviderFile::parse(&text)
+}
@@ -0,0 +1,93 @@
+//! Quiet hours and Do Not Disturb.
+//!
+//! During the quiet hours schedule (e.g. `22:00-08:00`) notification sounds
+//! and desktop notifications are suppressed. The **Do Not Disturb** toggle
+//! overrides the schedule either way: `on` keeps everything quiet, `off`
+//! plays sounds even inside the window, `auto` follows the schedule.
+//!
+//! Like the "Local only" switch in [`crate::policy`], the current state is
+//! process-wide so every sound and notification path can check [`is_quiet`].
+
+use chrono::{NaiveTime, Timelike};
+use std::sync::Mutex;
+
+/// Quiet hours offered in the menu.
+pub const SCHEDULE_CHOICES: &[&str] = &["22:00-08:00", "23:00-07:00", "21:00-09:00"];
+
+/// Do Not Disturb override of the quiet hours schedule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dnd {
+    /// Quiet only during the schedule.
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl Dnd {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "auto" => Some(Self::Auto),
+            "on" => Some(Self::On),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::On => "on",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// A quiet hours window `[start, end)`, wrapping past midnight when needed.
+pub type Schedule = (NaiveTime, NaiveTime);
+
+static STATE: Mutex<(Dnd, Option<Schedule>)> = Mutex::new((Dnd::Auto, None));
+
+/// Parse a stored schedule: `HH:MM-HH:MM`, or `off` for none.
+pub fn parse_schedule(value: &str) -> Option<Schedule> {
+    crate::rules::parse_hours(value).filter(|(start, end)| start != end)
+}
+
+/// The stored form of `schedule`, `off` when there is none.
+pub fn format_schedule(schedule: Option<Schedule>) -> String {
+    match schedule {
+        Some((start, end)) => format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+        None => "off".into(),
+    }
+}
+
+/// Whether sounds and notifications are suppressed at `time`.
+pub fn is_quiet_at(dnd: Dnd, schedule: Option<Schedule>, time: NaiveTime) -> bool {
+    match dnd {
+        Dnd::On => true,
+        Dnd::Off => false,
+        Dnd::Auto => schedule.is_some_and(|(start, end)| crate::rules::in_window(time, start, end)),
+    }
+}
+
+/// Set the Do Not Disturb override.
+pub fn set_dnd(dnd: Dnd) {
+    lock().0 = dnd;
+}
+
+/// Set (or clear) the quiet hours schedule.
+pub fn set_schedule(schedule: Option<Schedule>) {
+    lock().1 = schedule;
+}
+
+/// Whether sounds and notifications are suppressed right now.
+pub fn is_quiet() -> bool {
+    let (dnd, schedule) = *lock();
+    let now = chrono::Local::now();
+    let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default();
+    is_quiet_at(dnd, schedule, time)
+}
+
+fn lock() -> std::sync::MutexGuard<'static, (Dnd, Option<Schedule>)> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,358 @@
+//! Remote control — a phone on the same network as a record button.
+//!
+//! When pairing is on, a tiny HTTP server listens on the LAN (port
+//! [`DEFAULT_PORT`], `REMOTE_PORT` to change it). Every request must carry
+//! the pairing token, either as `?token=` (the paired URL shown as a QR code)
+//! or as `Authorization: Bearer <token>`. `GET /` serves a one-button page
+//! that polls `GET /status` and posts to `/start`, `/stop` or `/toggle`;
+//! the server is an [`ActivationSource`], so commands are run like those of
+//! any other trigger. The token lives in the OS keyring, so a paired
+//! phone keeps working across restarts until a new token is made.
+
+use crate::activation::{Activation, ActivationSource};
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+/// Port the remote server listens on unless `REMOTE_PORT` is set.
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Requests with a larger head than this are refused.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// An HTTP response to write back.
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn new(status: u16, content_type: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        Self::new(status, "application/json", body.to_string())
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Bad Request",
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+/// Compare tokens without stopping at the first difference.
+pub fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// The token a request carries: the `token` query parameter, else a bearer
+/// `Authorization` header.
+fn request_token<'a>(query: &'a str, headers: &[(&str, &'a str)]) -> Option<&'a str> {
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .or_else(|| {
+            headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+                .and_then(|(_, value)| value.strip_prefix("Bearer "))
+        })
+}
+
+/// Answer one request head (request line and headers) given the pairing
+/// `token` and the app's current `state`; also returns the command to run.
+pub fn respond(request: &str, token: &str, state: &str) -> (Response, Option<Activation>) {
+    let mut lines = request.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return (Response::new(400, "text/plain", "Bad request"), None);
+    };
+    let headers: Vec<(&str, &str)> = lines
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    if !request_token(query, &headers).is_some_and(|t| token_matches(t, token)) {
+        return (Response::new(401, "text/plain", "Not paired"), None);
+    }
+
+    let command = match path {
+        "/" | "/status" if method != "GET" => {
+            return (Response::new(405, "text/plain", "Use GET"), None);
+        }
+        "/" => return (Response::new(200, "text/html", PAGE), None),
+        "/status" => {
+            return (
+                Response::json(200, serde_json::json!({ "state": state })),
+                None,
+            );
+        }
+        "/start" => Activation::Start,
+        "/stop" => Activation::Stop,
+        "/toggle" => Activation::Toggle,
+        _ => return (Response::new(404, "text/plain", "Not found"), None),
+    };
+    if method != "POST" {
+        return (Response::new(405, "text/plain", "Use POST"), None);
+    }
+    (
+        Response::json(200, serde_json::json!({ "ok": true, "state": state })),
+        Some(command),
+    )
+}
+
+/// The URL to pair a phone with: open it (or scan it as a QR code) on the phone.
+pub fn pairing_url(ip: IpAddr, port: u16, token: &str) -> String {
+    match ip {
+        IpAddr::V6(ip) => format!("http://[{ip}]:{port}/?token={token}"),
+        IpAddr::V4(ip) => format!("http://{ip}:{port}/?token={token}"),
+    }
+}
+
+/// This machine's address on the LAN: the source address of the default
+/// route. Connecting a UDP socket sends nothing.
+pub fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// The QR code of `text` as a square of dark (`true`) and light modules, row
+/// by row, with its side length.
+pub fn qr_modules(text: &str) -> Result<(usize, Vec<bool>), String> {
+    let code = qrcode::QrCode::new(text.as_bytes()).map_err(|e| format!("QR code: {e}"))?;
+    let modules = code
+        .to_colors()
+        .into_iter()
+        .map(|c| c == qrcode::Color::Dark)
+        .collect();
+    Ok((code.width(), modules))
+}
+
+/// A running remote server. Dropping it closes the port.
+pub struct RemoteServer {
+    stop: Arc<AtomicBool>,
+    state: Arc<Mutex<String>>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl RemoteServer {
+    /// Listen on all interfaces at `port` for requests carrying `token`.
+    /// Each command is delivered on the returned channel, which disconnects
+    /// once the server stops.
+    pub fn start(port: u16, token: String) -> Result<(Self, mpsc::Receiver<Activation>), String> {
+        let listener =
+            TcpListener::bind(("0.0.0.0", port)).map_err(|e| format!("listen on {port}: {e}"))?;
+        // Non-blocking accept lets the loop notice `stop`
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("listen on {port}: {e}"))?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(Mutex::new("idle".to_string()));
+        let (tx, rx) = mpsc::channel();
+
+        let (stop_c, state_c) = (Arc::clone(&stop), Arc::clone(&state));
+        let handle = std::thread::spawn(move || {
+            while !stop_c.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, peer)) => {
+                        let state = state_c.lock().map(|s| s.clone()).unwrap_or_default();
+                        match serve(stream, &token, &state) {
+                            Ok(Some(command)) => {
+                                dbg_log!("[REMOTE] {command:?} from {peer}");
+                                if tx.send(command).is_err() {
+                                    return;
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => dbg_log!("[REMOTE] {peer}: {e}"),
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Remote control accept failed: {e}");
+                        std::thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            }
+            dbg_log!("[REMOTE] server stopped");
+        });
+
+        dbg_log!("[REMOTE] listening on port {port}");
+        Ok((
+            Self {
+                stop,
+                state,
+                handle: Some(handle),
+            },
+            rx,
+        ))
+    }
+
+    /// Publish the app state (`idle`, `recording`, ...) for `/status`.
+    pub fn set_state(&self, state: &str) {
+        if let Ok(mut s) = self.state.lock()
+            && *s != state
+        {
+            *s = state.to_string();
+        }
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wait for the listener to close, so the port can be bound again right away
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// The phone remote as an activation source.
+pub struct RemoteSource {
+    port: u16,
+    token: String,
+    server: Option<RemoteServer>,
+}
+
+impl RemoteSource {
+    pub fn new(port: u16, token: String) -> Self {
+        Self {
+            port,
+            token,
+            server: None,
+        }
+    }
+}
+
+impl ActivationSource for RemoteSource {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        // Close a running server first so its port is free again
+        self.server = None;
+        let (server, rx) = RemoteServer::start(self.port, self.token.clone())?;
+        self.server = Some(server);
+        Ok(rx)
+    }
+
+    fn state_changed(&self, state: &'static str) {
+        if let Some(server) = &self.server {
+            server.set_state(state);
+        }
+    }
+}
+
+/// Read one request from `stream`, answer it and return its command.
+fn serve(mut stream: TcpStream, token: &str, state: &str) -> Result<Option<Activation>, String> {
+    stream
+        .set_nonblocking(false)
+        .and_then(|()| stream.set_read_timeout(Some(Duration::from_secs(2))))
+        .map_err(|e| e.to_string())?;
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_BYTES {
+            return Err("request too large".into());
+        }
+        let n = stream.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let (response, command) = respond(&String::from_utf8_lossy(&head), token, state);
+    stream
+        .write_all(&response.to_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(command)
+}
+
+/// The remote page: one big button that follows the app state. The token
+/// comes from the page's own URL.
+const PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>WhisperCrabs Remote</title>
+<style>
+  body { margin: 0; height: 100vh; display: flex; flex-direction: column; align-items: center;
+         justify-content: center; background: #0f172a; color: #e2e8f0; font-family: sans-serif; }
+  button { width: 60vmin; height: 60vmin; border-radius: 50%; border: none; font-size: 7vmin;
+           font-weight: 600; color: #fff; background: #2563eb; }
+  button.recording { background: #dc2626; }
+  p { margin-top: 6vmin; font-size: 5vmin; opacity: 0.8; }
+</style>
+</head>
+<body>
+<button id="rec">Record</button>
+<p id="state">Connecting...</p>
+<script>
+  const token = new URLSearchParams(location.search).get("token") || "";
+  const auth = { headers: { Authorization: "Bearer " + token } };
+  const button = document.getElementById("rec");
+  const label = document.getElementById("state");
+  function show(state) {
+    const recording = state === "recording";
+    button.classList.toggle("recording", recording);
+    button.textContent = recording ? "Stop" : "Record";
+    label.textContent = state;
+  }
+  async function refresh() {
+    try {
+      const r = await fetch("/status", auth);
+      show(r.ok ? (await r.json()).state : "not paired");
+    } catch (e) {
+      label.textContent = "offline";
+    }
+  }
+  button.onclick = async () => {
+    const r = await fetch("/toggle", { method: "POST", ...auth });
+    if (r.ok) show((await r.json()).state === "recording" ? "processing" : "recording");
+  };
+  refresh();
+  setInterval(refresh, 1000);
+</script>
+</body>
+</html>
+"#;
@@ -0,0 +1,117 @@
+//! Retention windows for History — how long transcript text and retained
+//! recordings are kept.
+//!
+//! Text and audio have separate windows, so recordings (large, and the more
+//! sensitive part) can be dropped after a few days while the text stays for
+//! search and export. A recording never outlives its text. The maintenance
+//! job ([`run`]) enforces both at startup and then hourly.
+
+use crate::db::Db;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Audio windows offered in the menu, in days.
+pub const AUDIO_CHOICES: &[u32] = &[1, 7, 30];
+
+/// Text windows offered in the menu, in days.
+pub const TEXT_CHOICES: &[u32] = &[30, 90, 365];
+
+/// How often the maintenance job runs.
+pub const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Retention windows in days; `None` keeps forever.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Retention {
+    pub text_days: Option<u32>,
+    pub audio_days: Option<u32>,
+}
+
+impl Retention {
+    /// Read the `text_retention_days` / `audio_retention_days` settings.
+    pub fn load(db: &Db) -> Self {
+        let days = |key: &str| {
+            db.get_setting(key)
+                .ok()
+                .flatten()
+                .and_then(|v| parse_days(&v))
+        };
+        Self {
+            text_days: days("text_retention_days"),
+            audio_days: days("audio_retention_days"),
+        }
+    }
+
+    /// The audio window actually applied: never longer than the text window.
+    pub fn effective_audio_days(&self) -> Option<u32> {
+        match (self.text_days, self.audio_days) {
+            (Some(t), Some(a)) => Some(t.min(a)),
+            (t, a) => a.or(t),
+        }
+    }
+}
+
+/// Parse a stored window: a positive number of days, or `forever`.
+pub fn parse_days(value: &str) -> Option<u32> {
+    value.trim().parse().ok().filter(|d| *d > 0)
+}
+
+/// Whether something `age` old falls outside a `days` window.
+pub fn is_expired(age: Duration, days: Option<u32>) -> bool {
+    days.is_some_and(|d| age >= DAY * d)
+}
+
+/// Delete recordings in `audio_dir` last written longer than `days` ago.
+/// Returns how many were removed.
+pub fn purge_audio(audio_dir: &Path, days: Option<u32>, now: SystemTime) -> usize {
+    if days.is_none() {
+        return 0;
+    }
+    let Ok(files) = std::fs::read_dir(audio_dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for file in files.flatten() {
+        let path = file.path();
+        if path.extension().is_none_or(|e| e != "wav") {
+            continue;
+        }
+        let age = file
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok());
+        if age.is_some_and(|age| is_expired(age, days)) && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Enforce `retention`: delete expired History entries (with their
+/// recordings), then recordings past the audio window. Returns the number of
+/// entries and recordings removed.
+pub fn run(db: &Db, audio_dir: &Path, retention: Retention) -> Result<(usize, usize), String> {
+    let mut recordings = 0;
+    let mut entries = 0;
+    if let Some(days) = retention.text_days {
+        let ids = db
+            .ids_older_than(days)
+            .map_err(|e| format!("Retention query failed: {e}"))?;
+        db.delete_many(&ids)
+            .map_err(|e| format!("Retention delete failed: {e}"))?;
+        for id in &ids {
+            if std::fs::remove_file(crate::config::recording_path(audio_dir, *id)).is_ok() {
+                recordings += 1;
+            }
+        }
+        entries = ids.len();
+    }
+    recordings += purge_audio(
+        audio_dir,
+        retention.effective_audio_days(),
+        SystemTime::now(),
+    );
+    Ok((entries, recordings))
+}
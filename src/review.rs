@@ -0,0 +1,33 @@
+//! Review before copying — a transcript waits in an editable window.
+//!
+//! With **Review Before Copying** on, a finished dictation doesn't go to the
+//! clipboard right away. It opens in a small window where a word or two can
+//! be fixed first, then **Copy**, **Copy & Paste** or **Discard** decides
+//! where it goes. The transcript is in History either way; a fix made here
+//! is saved to its History entry and, with "Learn corrections from edits"
+//! on, suggested as a replacement like an edit in History. Live dictation
+//! types as it goes and is never held back.
+
+/// `true` to hold each transcript in the review window instead of copying it.
+pub const SETTING: &str = "review_transcripts";
+
+/// Where a reviewed transcript goes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Choice {
+    Copy,
+    CopyAndPaste,
+    Discard,
+}
+
+/// The text to deliver from the review window, or `None` when it was
+/// emptied (nothing to copy).
+pub fn final_text(edited: &str) -> Option<String> {
+    let text = edited.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// The corrected text to save back to History, when `edited` differs from
+/// the `original` transcript.
+pub fn correction(original: &str, edited: &str) -> Option<String> {
+    final_text(edited).filter(|text| text != original.trim())
+}
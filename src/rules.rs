@@ -0,0 +1,213 @@
+//! Profile auto-switch rules.
+//!
+//! A small rules engine evaluated at recording start. Each rule names a
+//! provider (any `transcription-mode` id, e.g. `local-base` or `groq`) and a
+//! set of conditions that must all hold. The first matching rule wins; when
+//! no rule matches, the manually chosen provider is used.
+//!
+//! Rules are stored as a JSON array in the `profile_rules` setting:
+//!
+//! ```json
+//! [
+//!   {"provider": "local-base", "ssid_not_in": ["HomeNet", "OfficeNet"]},
+//!   {"provider": "local-tiny", "hours": "18:00-08:00"},
+//!   {"provider": "local-base", "days": [6, 7]}
+//! ]
+//! ```
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// A single auto-switch rule. Empty conditions always match.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Rule {
+    /// Provider id to switch to when the rule matches.
+    pub provider: String,
+    /// Match only when connected to one of these Wi-Fi networks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssid_in: Vec<String>,
+    /// Match when connected to a Wi-Fi network *not* in this list
+    /// (i.e. an untrusted network). Never matches without a Wi-Fi connection,
+    /// but does when the network isn't known.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssid_not_in: Vec<String>,
+    /// Local time window `HH:MM-HH:MM`; may wrap past midnight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hours: Option<String>,
+    /// ISO weekdays (1 = Monday … 7 = Sunday).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<u8>,
+}
+
+/// Environment snapshot the rules are evaluated against.
+pub struct Context {
+    pub ssid: Option<String>,
+    /// Whether `ssid` was read recently. An unknown network counts as
+    /// untrusted: `ssid_in` rules don't match it and `ssid_not_in` rules do.
+    pub ssid_known: bool,
+    pub time: NaiveTime,
+    /// ISO weekday (1 = Monday … 7 = Sunday).
+    pub weekday: u8,
+}
+
+impl Context {
+    /// Capture the current local time and the last Wi-Fi SSID read. Never
+    /// waits for NetworkManager: the SSID is re-read in the background for
+    /// the next evaluation, and one older than [`SSID_MAX_AGE`] is unknown.
+    pub fn current() -> Self {
+        let now = chrono::Local::now();
+        let cached = cached_ssid();
+        refresh_ssid();
+        Self {
+            ssid_known: cached.is_some(),
+            ssid: cached.flatten(),
+            time: NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default(),
+            weekday: now.weekday().number_from_monday() as u8,
+        }
+    }
+}
+
+/// Parse the JSON rule list stored in settings.
+pub fn parse_rules(json: &str) -> Result<Vec<Rule>, String> {
+    let rules: Vec<Rule> =
+        serde_json::from_str(json).map_err(|e| format!("invalid rules JSON: {e}"))?;
+    for rule in &rules {
+        if rule.provider.trim().is_empty() {
+            return Err("rule is missing a provider".into());
+        }
+        if let Some(ref h) = rule.hours
+            && parse_hours(h).is_none()
+        {
+            return Err(format!("invalid hours '{h}', expected HH:MM-HH:MM"));
+        }
+        if rule.days.iter().any(|d| !(1..=7).contains(d)) {
+            return Err("days must be between 1 (Mon) and 7 (Sun)".into());
+        }
+    }
+    Ok(rules)
+}
+
+/// Return the provider of the first rule matching `ctx`.
+pub fn evaluate<'a>(rules: &'a [Rule], ctx: &Context) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|r| matches(r, ctx))
+        .map(|r| r.provider.as_str())
+}
+
+fn matches(rule: &Rule, ctx: &Context) -> bool {
+    if !rule.ssid_in.is_empty() {
+        match ctx.ssid {
+            Some(ref s) if ctx.ssid_known && rule.ssid_in.iter().any(|x| x == s) => {}
+            _ => return false,
+        }
+    }
+    if !rule.ssid_not_in.is_empty() && ctx.ssid_known {
+        match ctx.ssid {
+            Some(ref s) if !rule.ssid_not_in.iter().any(|x| x == s) => {}
+            _ => return false,
+        }
+    }
+    if let Some(ref h) = rule.hours {
+        match parse_hours(h) {
+            Some((start, end)) if in_window(ctx.time, start, end) => {}
+            _ => return false,
+        }
+    }
+    if !rule.days.is_empty() && !rule.days.contains(&ctx.weekday) {
+        return false;
+    }
+    true
+}
+
+/// Parse a local time window `HH:MM-HH:MM` into its start and end.
+pub fn parse_hours(s: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (a, b) = s.split_once('-')?;
+    let start = NaiveTime::parse_from_str(a.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(b.trim(), "%H:%M").ok()?;
+    Some((start, end))
+}
+
+/// Whether `t` falls in `[start, end)`, wrapping past midnight when `end <= start`.
+pub fn in_window(t: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start < end {
+        t >= start && t < end
+    } else {
+        t >= start || t < end
+    }
+}
+
+/// Seconds between background SSID reads while rules are set.
+pub const SSID_REFRESH_SECS: u32 = 5;
+
+/// How long a read SSID is trusted; a few missed refreshes make it unknown.
+pub const SSID_MAX_AGE: Duration = Duration::from_secs(15);
+
+/// The SSID last read by [`refresh_ssid`], and when.
+static SSID: Mutex<Option<(Instant, Option<String>)>> = Mutex::new(None);
+
+/// Set while a worker thread is reading the SSID.
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+/// The Wi-Fi SSID as of the last [`refresh_ssid`], without running anything:
+/// `Some(None)` without Wi-Fi, `None` when not read within [`SSID_MAX_AGE`].
+pub fn cached_ssid() -> Option<Option<String>> {
+    let cached = SSID.lock().ok()?;
+    let (read_at, ssid) = cached.as_ref()?;
+    (read_at.elapsed() <= SSID_MAX_AGE).then(|| ssid.clone())
+}
+
+/// Re-read the Wi-Fi SSID on a worker thread, unless a read is under way.
+/// `nmcli` can take seconds when NetworkManager is busy.
+pub fn refresh_ssid() {
+    if REFRESHING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let ssid = current_ssid();
+        if let Ok(mut cached) = SSID.lock() {
+            *cached = Some((Instant::now(), ssid));
+        }
+        REFRESHING.store(false, Ordering::Release);
+    });
+}
+
+/// The active SSID in `nmcli -t -f active,ssid dev wifi` output, with the
+/// terse format's `\:` and `\\` escapes undone.
+pub fn parse_nmcli_ssid(text: &str) -> Option<String> {
+    let escaped = text.lines().find_map(|l| l.strip_prefix("yes:"))?;
+    let mut ssid = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => ssid.extend(chars.next()),
+            c => ssid.push(c),
+        }
+    }
+    (!ssid.is_empty()).then_some(ssid)
+}
+
+/// Currently connected Wi-Fi SSID (via NetworkManager, falling back to `iwgetid`).
+fn current_ssid() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(output) = std::process::Command::new("nmcli")
+            .args(["-t", "-f", "active,ssid", "dev", "wifi"])
+            .output()
+            && let Some(ssid) = parse_nmcli_ssid(&String::from_utf8_lossy(&output.stdout))
+        {
+            return Some(ssid);
+        }
+        if let Ok(output) = std::process::Command::new("iwgetid").arg("-r").output() {
+            let ssid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !ssid.is_empty() {
+                return Some(ssid);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,48 @@
+//! Credentials kept in the OS keyring (Secret Service, macOS Keychain,
+//! Windows Credential Manager) rather than in the SQLite settings.
+
+use keyring::Entry;
+
+/// Keyring service name all entries are stored under.
+const SERVICE: &str = "whispercrabs";
+
+/// Hugging Face access token for gated or private model repos.
+pub const HF_TOKEN: &str = "huggingface-token";
+
+/// Key of an encrypted History that unlocks without a passphrase.
+pub const HISTORY_KEY: &str = "history-key";
+
+/// Pairing token of the remote control server.
+pub const REMOTE_TOKEN: &str = "remote-token";
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, name).map_err(|e| format!("keyring: {e}"))
+}
+
+/// Read a secret; `None` if unset or the keyring is unavailable.
+pub fn get(name: &str) -> Option<String> {
+    entry(name).ok()?.get_password().ok()
+}
+
+/// Store a secret, replacing any previous value.
+pub fn set(name: &str, value: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("keyring: {e}"))
+}
+
+/// Remove a secret. Removing a missing entry is not an error.
+pub fn delete(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("keyring: {e}")),
+    }
+}
+
+/// The Hugging Face token: the keyring entry, else the `HF_TOKEN` environment
+/// variable used by the Hugging Face tooling.
+pub fn hf_token() -> Option<String> {
+    get(HF_TOKEN)
+        .or_else(|| std::env::var("HF_TOKEN").ok())
+        .filter(|t| !t.trim().is_empty())
+}
@@ -0,0 +1,55 @@
+//! Session log — this session's transcripts in a small window.
+//!
+//! Between the status label, which shows only the last result for a moment,
+//! and History, which holds everything stored, the session log lists what
+//! was dictated since WhisperCrabs started, each with a copy button. It is
+//! kept in memory only and is gone when the app quits; with "Store in
+//! History" off, this is the only place a transcript can be copied again.
+
+use chrono::{DateTime, Local};
+
+/// Entries kept; the oldest are dropped beyond this.
+pub const MAX_ENTRIES: u32 = 500;
+
+/// One transcript of this session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// When it was delivered, `HH:MM:SS`.
+    pub time: String,
+    pub text: String,
+    /// Provider that transcribed it.
+    pub provider: String,
+}
+
+impl Entry {
+    pub fn new(at: DateTime<Local>, text: &str, provider: &str) -> Self {
+        Self {
+            time: at.format("%H:%M:%S").to_string(),
+            text: text.to_string(),
+            provider: provider.to_string(),
+        }
+    }
+
+    /// The line above the text: time and provider.
+    pub fn heading(&self) -> String {
+        if self.provider.is_empty() {
+            self.time.clone()
+        } else {
+            format!("{} \u{00b7} {}", self.time, self.provider)
+        }
+    }
+}
+
+/// How many of the oldest entries to drop so `len` fits [`MAX_ENTRIES`].
+pub fn excess(len: u32) -> u32 {
+    len.saturating_sub(MAX_ENTRIES)
+}
+
+/// All texts for "Copy All", oldest first, separated by blank lines.
+pub fn joined<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> String {
+    entries
+        .into_iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
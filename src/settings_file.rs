@@ -0,0 +1,244 @@
+//! Settings export and import — set up a new machine in one step.
+//!
+//! An export is a small JSON file with every portable setting. API keys are
+//! left out unless asked for, and then only travel sealed: encrypted with
+//! ChaCha20-Poly1305 under a key derived from a passphrase with Argon2id.
+//! Only settings listed as portable are exported or imported; the rest
+//! (window position, input device, the History lock, upload consent, Local
+//! only, remote control, transcript hooks) belong to the machine that set
+//! them, so a hand-edited file can't smuggle them in.
+
+use std::collections::BTreeMap;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+
+/// The `format` field of a settings export.
+pub const FORMAT: &str = "whispercrabs-settings";
+
+/// The newest export version this build reads and the one it writes.
+pub const VERSION: u32 = 1;
+
+/// Shortest accepted passphrase for sealing API keys.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Settings that travel with an export: exact keys, or prefixes ending in
+/// `_`. Everything else stays on the machine that set it — window
+/// position, input device, the History lock, upload consent per host, the
+/// **Local only** switch, remote control and the transcript hooks — so an
+/// imported file can't loosen privacy or run anything, and a setting added
+/// later is local until it is listed here.
+const PORTABLE_KEYS: &[&str] = &[
+    "transcription_mode",
+    "api_custom_url",
+    "api_custom_key",
+    "api_custom_model",
+    "api_custom_path",
+    "api_key_",
+    "api_model_",
+    "tts_provider",
+    "tts_voice",
+    "profile_rules",
+    "stt_language",
+    "local_custom_model",
+    "record_profiles",
+    "learn_corrections",
+    "history_mode",
+    "label_speakers",
+    "wake_word",
+    "live_dictation",
+    "dictation_commands",
+    "dictation_commands_table",
+    "monitor_dots",
+    "text_retention_days",
+    "audio_retention_days",
+    "history_paste",
+    "store_audio",
+    "sound_",
+    "quiet_hours",
+    "dnd",
+    "sync_dir",
+    "autosave_",
+    "pause_media",
+    "pause_media_players",
+    "silence_notifications",
+    "review_transcripts",
+    "confidence_",
+    "announce_transcripts",
+    "auto_paste",
+    "coaching_hints",
+    "auto_split_minutes",
+    "meeting_",
+    "theme",
+    "widget_size",
+    "keep_above",
+    "all_workspaces",
+    "screen_corner",
+    "tray_icon",
+    "hide_button",
+    "notify_transcripts",
+    "portal_shortcuts",
+    "shortcut_trigger_",
+    "keybinding_",
+    "postprocess_",
+    "vocabulary",
+];
+
+/// Whether `key` holds a credential.
+pub fn is_secret(key: &str) -> bool {
+    key.starts_with("api_key_") || key == "api_custom_key"
+}
+
+/// Whether `key` belongs in an export (secrets aside).
+pub fn is_portable(key: &str) -> bool {
+    PORTABLE_KEYS
+        .iter()
+        .any(|p| key == *p || (p.ends_with('_') && key.starts_with(p)))
+}
+
+/// A settings export as stored on disk.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SettingsFile {
+    pub format: String,
+    pub version: u32,
+    pub settings: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SealedSecrets>,
+}
+
+/// API keys encrypted with a passphrase; all fields hex encoded.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SealedSecrets {
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl SettingsFile {
+    /// Whether the file carries sealed API keys.
+    pub fn has_secrets(&self) -> bool {
+        self.secrets.is_some()
+    }
+
+    /// The settings to apply: the portable ones, plus the API keys when
+    /// `passphrase` is given. A wrong passphrase is an error rather than a
+    /// silent skip, so a typo doesn't leave the keys behind unnoticed.
+    pub fn into_settings(self, passphrase: Option<&str>) -> Result<Vec<(String, String)>, String> {
+        let mut settings: Vec<(String, String)> = self
+            .settings
+            .into_iter()
+            .filter(|(k, _)| is_portable(k) && !is_secret(k))
+            .collect();
+        if let (Some(sealed), Some(passphrase)) = (self.secrets, passphrase) {
+            let secrets = open(&sealed, passphrase)?;
+            settings.extend(secrets.into_iter().filter(|(k, _)| is_secret(k)));
+        }
+        Ok(settings)
+    }
+}
+
+/// Check a new passphrase (and its confirmation) before sealing with it.
+pub fn validate_passphrase(passphrase: &str, confirm: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Use at least {MIN_PASSPHRASE_LEN} characters"));
+    }
+    if passphrase != confirm {
+        return Err("The entries don't match".into());
+    }
+    Ok(())
+}
+
+/// The export of `settings` as pretty JSON. API keys are included, sealed,
+/// only with a `passphrase`.
+pub fn export(settings: &[(String, String)], passphrase: Option<&str>) -> Result<String, String> {
+    let portable = settings.iter().filter(|(k, _)| is_portable(k));
+    let (secrets, plain): (Vec<_>, Vec<_>) = portable.partition(|(k, _)| is_secret(k));
+    let secrets = match passphrase {
+        Some(passphrase) if !secrets.is_empty() => {
+            let secrets: BTreeMap<&str, &str> = secrets
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect();
+            Some(seal(&secrets, passphrase)?)
+        }
+        _ => None,
+    };
+    let file = SettingsFile {
+        format: FORMAT.into(),
+        version: VERSION,
+        settings: plain.into_iter().cloned().collect(),
+        secrets,
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+}
+
+/// Read a settings export, refusing other files and newer versions.
+pub fn parse(json: &str) -> Result<SettingsFile, String> {
+    let file: SettingsFile =
+        serde_json::from_str(json).map_err(|e| format!("Not a settings export: {e}"))?;
+    if file.format != FORMAT {
+        return Err("Not a settings export".into());
+    }
+    if file.version > VERSION {
+        return Err(format!(
+            "This export is from a newer version (format {}); update WhisperCrabs first",
+            file.version
+        ));
+    }
+    Ok(file)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("derive key: {e}"))?;
+    Ok(key)
+}
+
+fn seal(secrets: &BTreeMap<&str, &str>, passphrase: &str) -> Result<SealedSecrets, String> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    getrandom::getrandom(&mut salt).map_err(|e| format!("random salt: {e}"))?;
+    getrandom::getrandom(&mut nonce).map_err(|e| format!("random nonce: {e}"))?;
+    let key = derive_key(passphrase, &salt)?;
+    let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| "Encrypting API keys failed".to_string())?;
+    Ok(SealedSecrets {
+        salt: to_hex(&salt),
+        nonce: to_hex(&nonce),
+        ciphertext: to_hex(&ciphertext),
+    })
+}
+
+fn open(sealed: &SealedSecrets, passphrase: &str) -> Result<BTreeMap<String, String>, String> {
+    let malformed = || "The API keys in this export are damaged".to_string();
+    let salt = from_hex(&sealed.salt).ok_or_else(malformed)?;
+    let nonce = from_hex(&sealed.nonce).ok_or_else(malformed)?;
+    let ciphertext = from_hex(&sealed.ciphertext).ok_or_else(malformed)?;
+    if nonce.len() != 12 {
+        return Err(malformed());
+    }
+    let key = derive_key(passphrase, &salt)?;
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "Wrong passphrase".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|_| malformed())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.is_ascii() || hex.len() % 2 == 1 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
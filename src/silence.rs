@@ -0,0 +1,195 @@
+//! Silencing desktop notifications while recording.
+//!
+//! A notification popping up mid-dictation interrupts it, and its sound ends
+//! up in the transcript. With **Silence Notifications While Recording** on,
+//! the desktop's own do-not-disturb is switched on when a recording starts
+//! and back to what it was when it stops:
+//!
+//! - on GNOME, by turning off `show-banners` in
+//!   `org.gnome.desktop.notifications` (only if it was on);
+//! - on KDE Plasma and other servers that support it, through the `Inhibit`
+//!   call of `org.freedesktop.Notifications`, which the server lifts again
+//!   on `UnInhibit` or when WhisperCrabs quits.
+//!
+//! The GNOME setting outlives the app, so turning it off is recorded in
+//! [`RESTORE_SETTING`] and undone by [`restore_after_crash`] at the next
+//! start if WhisperCrabs didn't get to it.
+//!
+//! This is separate from the app's own **Do Not Disturb**, which only holds
+//! back WhisperCrabs' sounds and notifications.
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use crate::db::Db;
+
+/// `true` once **Silence Notifications While Recording** is ticked.
+pub const ENABLED_SETTING: &str = "silence_notifications";
+
+/// Set while GNOME banners are turned off by us, cleared once restored.
+pub const RESTORE_SETTING: &str = "silence_notifications_restore";
+
+const GNOME_SCHEMA: &str = "org.gnome.desktop.notifications";
+const GNOME_KEY: &str = "show-banners";
+
+const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// How long the notification server may take to answer, in milliseconds.
+const CALL_TIMEOUT_MS: i32 = 500;
+
+/// How do-not-disturb is switched on, by desktop.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// The `show-banners` GSettings key.
+    GnomeBanners,
+    /// `org.freedesktop.Notifications.Inhibit`.
+    Inhibit,
+}
+
+/// The method for the desktop named in `XDG_CURRENT_DESKTOP` (a
+/// colon-separated list such as `ubuntu:GNOME`).
+pub fn method_for(current_desktop: &str) -> Method {
+    let gnome = current_desktop.split(':').any(|d| {
+        matches!(
+            d.to_ascii_lowercase().as_str(),
+            "gnome" | "gnome-classic" | "unity" | "budgie"
+        )
+    });
+    if gnome {
+        Method::GnomeBanners
+    } else {
+        Method::Inhibit
+    }
+}
+
+/// Whether silencing is turned on.
+pub fn enabled(d: &Db) -> bool {
+    d.get_setting(ENABLED_SETTING)
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// Notifications silenced for a recording; dropping it restores them.
+pub struct Silenced {
+    /// GNOME settings whose banners were turned off, and the database to
+    /// clear the crash marker in.
+    banners: Option<(gio::Settings, std::sync::Arc<std::sync::Mutex<Db>>)>,
+    /// Connection and cookie of an active inhibition.
+    inhibition: Option<(gio::DBusConnection, u32)>,
+}
+
+impl Silenced {
+    pub fn is_active(&self) -> bool {
+        self.banners.is_some() || self.inhibition.is_some()
+    }
+}
+
+impl Drop for Silenced {
+    fn drop(&mut self) {
+        if let Some((settings, db)) = self.banners.take() {
+            if let Err(e) = settings.set_boolean(GNOME_KEY, true) {
+                tracing::warn!("Restoring notification banners failed: {e}");
+            }
+            gio::Settings::sync();
+            if let Ok(d) = db.lock() {
+                let _ = d.delete_setting(RESTORE_SETTING);
+            }
+        }
+        if let Some((connection, cookie)) = self.inhibition.take()
+            && let Err(e) = connection.call_sync(
+                Some(NOTIFICATIONS_NAME),
+                NOTIFICATIONS_PATH,
+                NOTIFICATIONS_NAME,
+                "UnInhibit",
+                Some(&(cookie,).to_variant()),
+                None,
+                gio::DBusCallFlags::NONE,
+                CALL_TIMEOUT_MS,
+                gio::Cancellable::NONE,
+            )
+        {
+            dbg_log!("[SILENCE] UnInhibit failed: {e}");
+        }
+    }
+}
+
+/// Switch on the desktop's do-not-disturb for a recording.
+pub fn silence(db: &std::sync::Arc<std::sync::Mutex<Db>>) -> Silenced {
+    let mut silenced = Silenced {
+        banners: None,
+        inhibition: None,
+    };
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    match method_for(&desktop) {
+        Method::GnomeBanners => {
+            let Some(settings) = gnome_settings() else {
+                return silenced;
+            };
+            // Already off (the user's own do-not-disturb): nothing to undo later
+            if !settings.boolean(GNOME_KEY) {
+                return silenced;
+            }
+            if let Ok(d) = db.lock() {
+                let _ = d.set_setting(RESTORE_SETTING, "true");
+            }
+            match settings.set_boolean(GNOME_KEY, false) {
+                Ok(()) => silenced.banners = Some((settings, std::sync::Arc::clone(db))),
+                Err(e) => tracing::warn!("Silencing notifications failed: {e}"),
+            }
+        }
+        Method::Inhibit => match inhibit() {
+            Ok(inhibition) => silenced.inhibition = Some(inhibition),
+            Err(e) => dbg_log!("[SILENCE] Inhibit unavailable: {e}"),
+        },
+    }
+    silenced
+}
+
+/// Turn GNOME banners back on if a previous run silenced them and didn't
+/// get to restore them.
+pub fn restore_after_crash(d: &Db) {
+    if d.get_setting(RESTORE_SETTING).ok().flatten().is_none() {
+        return;
+    }
+    if let Some(settings) = gnome_settings()
+        && let Err(e) = settings.set_boolean(GNOME_KEY, true)
+    {
+        tracing::warn!("Restoring notification banners failed: {e}");
+    }
+    gio::Settings::sync();
+    let _ = d.delete_setting(RESTORE_SETTING);
+}
+
+/// The GNOME notification settings, if the schema is installed.
+fn gnome_settings() -> Option<gio::Settings> {
+    gio::SettingsSchemaSource::default()?.lookup(GNOME_SCHEMA, true)?;
+    Some(gio::Settings::new(GNOME_SCHEMA))
+}
+
+fn inhibit() -> Result<(gio::DBusConnection, u32), glib::Error> {
+    let connection = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE)?;
+    let hints = glib::VariantDict::new(None).end();
+    let reply = connection.call_sync(
+        Some(NOTIFICATIONS_NAME),
+        NOTIFICATIONS_PATH,
+        NOTIFICATIONS_NAME,
+        "Inhibit",
+        Some(
+            &(
+                crate::preferences::DBUS_DEST,
+                "Recording a dictation",
+                hints,
+            )
+                .to_variant(),
+        ),
+        Some(glib::VariantTy::new("(u)").expect("valid type")),
+        gio::DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    )?;
+    let (cookie,) = reply.get::<(u32,)>().unwrap_or_default();
+    Ok((connection, cookie))
+}
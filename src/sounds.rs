@@ -0,0 +1,236 @@
+//! Notification sounds per event — recording start and stop, a finished
+//! transcription and an error — each with its own sound (or none) and volume.
+//!
+//! Sounds come from `.env` (`SOUND_<EVENT>` / `SOUND_<EVENT>_VOLUME`) and can
+//! be changed from the **Sounds** menu, which stores them as `sound_<event>`
+//! / `sound_<event>_volume` settings.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+const CHIME: &[u8] = include_bytes!("audio/notification.wav");
+
+/// Volumes offered in the menu, in percent.
+pub const VOLUME_CHOICES: &[u8] = &[25, 50, 75, 100];
+
+/// Something that can play a sound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundEvent {
+    RecordStart,
+    RecordStop,
+    Success,
+    Error,
+}
+
+impl SoundEvent {
+    pub const ALL: [Self; 4] = [
+        Self::RecordStart,
+        Self::RecordStop,
+        Self::Success,
+        Self::Error,
+    ];
+
+    /// Key used in setting names and action names (`-` instead of `_`).
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::RecordStart => "record_start",
+            Self::RecordStop => "record_stop",
+            Self::Success => "success",
+            Self::Error => "error",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RecordStart => "Recording Started",
+            Self::RecordStop => "Recording Stopped",
+            Self::Success => "Transcription Done",
+            Self::Error => "Error",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// What to play for an event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sound {
+    Off,
+    /// The bundled completion chime.
+    Chime,
+    /// A short high beep.
+    High,
+    /// A short low beep.
+    Low,
+    /// A WAV file of your own.
+    File(PathBuf),
+}
+
+impl Sound {
+    /// Built-in choices offered in the menu.
+    pub const BUILT_IN: [Self; 4] = [Self::Off, Self::Chime, Self::High, Self::Low];
+
+    /// Parse `off`, `chime`, `high`, `low` or a path to a WAV file.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "" => None,
+            "off" | "none" | "false" | "0" => Some(Self::Off),
+            "chime" | "true" | "1" => Some(Self::Chime),
+            "high" => Some(Self::High),
+            "low" => Some(Self::Low),
+            _ => Some(Self::File(PathBuf::from(value))),
+        }
+    }
+
+    /// The stored (and action state) form, see [`Self::parse`].
+    pub fn as_setting(&self) -> String {
+        match self {
+            Self::Off => "off".into(),
+            Self::Chime => "chime".into(),
+            Self::High => "high".into(),
+            Self::Low => "low".into(),
+            Self::File(path) => path.to_string_lossy().into_owned(),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Chime => "Chime",
+            Self::High => "High Beep",
+            Self::Low => "Low Beep",
+            Self::File(_) => "Custom File",
+        }
+    }
+}
+
+/// Parse a volume in percent (0–100).
+pub fn parse_volume(value: &str) -> Option<u8> {
+    value
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .ok()
+        .filter(|v| *v <= 100)
+}
+
+/// The sound and volume (percent) of every event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoundScheme {
+    sounds: [(Sound, u8); 4],
+}
+
+impl SoundScheme {
+    /// Sounds from the `SOUND_<EVENT>` settings read through `lookup` (see
+    /// [`crate::config::Config::load`]). Without any, only the completion
+    /// chime plays, when `SOUND_NOTIFICATION_ON_COMPLETION` is set.
+    pub fn from_lookup(completion_chime: bool, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut scheme = Self::default();
+        if completion_chime {
+            scheme.set_sound(SoundEvent::Success, Sound::Chime);
+        }
+        for event in SoundEvent::ALL {
+            let var = format!("SOUND_{}", event.id().to_ascii_uppercase());
+            if let Some(sound) = lookup(&var).as_deref().and_then(Sound::parse) {
+                scheme.set_sound(event, sound);
+            }
+            if let Some(volume) = lookup(&format!("{var}_VOLUME"))
+                .as_deref()
+                .and_then(parse_volume)
+            {
+                scheme.set_volume(event, volume);
+            }
+        }
+        scheme
+    }
+
+    /// `self` with the choices made in the **Sounds** menu applied on top.
+    pub fn load(mut self, db: &crate::db::Db) -> Self {
+        let setting = |key: &str| db.get_setting(key).ok().flatten();
+        for event in SoundEvent::ALL {
+            let key = format!("sound_{}", event.id());
+            if let Some(sound) = setting(&key).as_deref().and_then(Sound::parse) {
+                self.set_sound(event, sound);
+            }
+            if let Some(volume) = setting(&format!("{key}_volume"))
+                .as_deref()
+                .and_then(parse_volume)
+            {
+                self.set_volume(event, volume);
+            }
+        }
+        self
+    }
+
+    pub fn sound(&self, event: SoundEvent) -> &Sound {
+        &self.sounds[event.index()].0
+    }
+
+    pub fn volume(&self, event: SoundEvent) -> u8 {
+        self.sounds[event.index()].1
+    }
+
+    pub fn set_sound(&mut self, event: SoundEvent, sound: Sound) {
+        self.sounds[event.index()].0 = sound;
+    }
+
+    pub fn set_volume(&mut self, event: SoundEvent, volume: u8) {
+        self.sounds[event.index()].1 = volume.min(100);
+    }
+
+    /// Play the sound of `event` in the background, if it has one and it
+    /// isn't quiet time (see [`crate::quiet`]).
+    pub fn play(&self, event: SoundEvent) {
+        let sound = self.sound(event).clone();
+        if sound == Sound::Off || crate::quiet::is_quiet() {
+            return;
+        }
+        let volume = self.volume(event) as f32 / 100.0;
+        std::thread::spawn(move || {
+            if let Err(e) = play_sound(&sound, volume) {
+                tracing::warn!("Notification sound failed: {e}");
+            }
+        });
+    }
+}
+
+impl Default for SoundScheme {
+    fn default() -> Self {
+        Self {
+            sounds: std::array::from_fn(|_| (Sound::Off, 100)),
+        }
+    }
+}
+
+/// Play `sound` at `volume` (0.0–1.0) and wait for it to finish.
+fn play_sound(sound: &Sound, volume: f32) -> Result<(), String> {
+    use rodio::{Decoder, OutputStream, Sink, Source};
+    use std::io::Cursor;
+
+    let (_stream, handle) = OutputStream::try_default().map_err(|e| e.to_string())?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    sink.set_volume(volume);
+    let beep = |freq: f32| {
+        rodio::source::SineWave::new(freq)
+            .take_duration(Duration::from_millis(120))
+            .amplify(0.3)
+    };
+    match sound {
+        Sound::Off => return Ok(()),
+        Sound::Chime => {
+            sink.append(Decoder::new(Cursor::new(CHIME)).map_err(|e| e.to_string())?);
+        }
+        Sound::High => sink.append(beep(880.0)),
+        Sound::Low => sink.append(beep(440.0)),
+        Sound::File(path) => {
+            let file = std::fs::File::open(path)
+                .map_err(|e| format!("Can't open {}: {e}", path.display()))?;
+            sink.append(Decoder::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?);
+        }
+    }
+    sink.sleep_until_end();
+    Ok(())
+}
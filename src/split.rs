@@ -0,0 +1,58 @@
+//! Auto-split — long recordings transcribed in parts while they run.
+//!
+//! With a split interval set, a recording hands its audio so far to the
+//! transcriber every N minutes and keeps recording without a gap: the
+//! capture buffer is drained, not restarted. Each part becomes its own
+//! History entry as soon as it is transcribed, so a two-hour session yields
+//! a stream of entries instead of one upload that may fail at the end. The
+//! last part (from the final split to Stop) is delivered like any dictation.
+//!
+//! A part is written to [`parts_dir`] before it is transcribed and removed
+//! once it is in History; one whose transcription fails stays there, so no
+//! audio is lost.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Settings key of the split interval in minutes (absent for off).
+pub const SPLIT_SETTING: &str = "auto_split_minutes";
+
+/// Intervals offered in the menu, in minutes.
+pub const CHOICES: &[u32] = &[5, 10, 15, 30];
+
+/// Longest interval accepted from settings or D-Bus.
+const MAX_MINUTES: u32 = 120;
+
+/// The interval stored in settings or sent over D-Bus: minutes, or `off`.
+pub fn parse_minutes(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|m| (1..=MAX_MINUTES).contains(m))
+}
+
+/// Action state for an interval: the minutes, or `off`.
+pub fn state(minutes: Option<u32>) -> String {
+    minutes.map_or_else(|| "off".to_string(), |m| m.to_string())
+}
+
+pub fn interval(minutes: u32) -> Duration {
+    Duration::from_secs(minutes as u64 * 60)
+}
+
+/// Where parts wait for their transcription.
+pub fn parts_dir(audio_dir: &Path) -> PathBuf {
+    audio_dir.join("parts")
+}
+
+/// File of part `part` of the recording started at `started`
+/// (`YYYYMMDD-HHMMSS`).
+pub fn part_path(audio_dir: &Path, started: &str, part: u32) -> PathBuf {
+    parts_dir(audio_dir).join(format!("{started}-part{part:03}.wav"))
+}
+
+/// Status shown while recording once part `part` is handed off.
+pub fn part_status(part: u32) -> String {
+    format!("Recording... (part {part} sent)")
+}
@@ -0,0 +1,351 @@
+//! Where the floating window sits: stacking, workspaces and placement.
+//!
+//! GTK 4 dropped the keep-above and sticky window hints. On X11 they are
+//! asked of the window manager with `wmctrl` (`_NET_WM_STATE_ABOVE` and
+//! `_NET_WM_STATE_STICKY`). The window's position is remembered as its
+//! nearest monitor corner and the distance from it ([`Placement`]), read and
+//! restored through the X protocol (see [`crate::x11`]).
+//!
+//! A Wayland client can neither ask for those hints nor place its windows,
+//! so the button would open wherever the compositor puts it. Built with the
+//! `layer-shell` feature, the window is a layer-shell surface on Wayland
+//! instead (wlroots compositors, KDE, GNOME with a layer-shell extension):
+//! anchored to a screen corner chosen in the menu, with margins from
+//! `SCREEN_MARGIN`. A layer surface is shown on every workspace; it sits on
+//! the top layer, over maximized windows, and with keep-above on the overlay
+//! layer, over fullscreen ones too. It can't be dragged.
+
+/// Settings keys of the two options.
+pub const KEEP_ABOVE_SETTING: &str = "keep_above";
+pub const ALL_WORKSPACES_SETTING: &str = "all_workspaces";
+
+/// Settings key of the corner a layer-shell window is anchored to.
+pub const CORNER_SETTING: &str = "screen_corner";
+
+/// Distance from the screen edges when `SCREEN_MARGIN` isn't set.
+pub const DEFAULT_MARGIN: i32 = 24;
+
+/// Largest margin accepted.
+const MAX_MARGIN: i32 = 2000;
+
+/// Screen corner of a layer-shell window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Self; 4] = [
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s.trim().to_ascii_lowercase())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TopLeft => "Top Left",
+            Self::TopRight => "Top Right",
+            Self::BottomLeft => "Bottom Left",
+            Self::BottomRight => "Bottom Right",
+        }
+    }
+
+    pub fn is_top(self) -> bool {
+        matches!(self, Self::TopLeft | Self::TopRight)
+    }
+
+    pub fn is_left(self) -> bool {
+        matches!(self, Self::TopLeft | Self::BottomLeft)
+    }
+}
+
+/// Horizontal and vertical distance from the screen edges:
+/// `SCREEN_MARGIN=24` for both, or `SCREEN_MARGIN=24,48`.
+pub fn parse_margin(value: &str) -> Option<(i32, i32)> {
+    let number = |v: &str| {
+        v.trim()
+            .trim_end_matches("px")
+            .parse()
+            .ok()
+            .filter(|m| (0..=MAX_MARGIN).contains(m))
+    };
+    match value.split_once(',') {
+        Some((x, y)) => Some((number(x)?, number(y)?)),
+        None => number(value).map(|m| (m, m)),
+    }
+}
+
+/// Settings keys of the remembered X11 position: the nearest corner, the
+/// distance from it and the monitor (see [`monitor_id`]), which a
+/// layer-shell window is shown on too.
+pub const POSITION_CORNER_SETTING: &str = "window_corner";
+pub const POSITION_OFFSET_SETTING: &str = "window_offset";
+pub const POSITION_MONITOR_SETTING: &str = "window_monitor";
+
+/// Position and size of a window or monitor: x, y, width, height.
+pub type Rect = (i32, i32, i32, i32);
+
+/// Where the window sits on its monitor: a corner and the distance of the
+/// window's edges from it. Kept relative to the corner, a position survives
+/// a change of resolution or widget size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub corner: Corner,
+    /// Horizontal and vertical distance from the corner's edges.
+    pub offset: (i32, i32),
+}
+
+impl Placement {
+    /// Near the bottom-right corner, where the window opens the first time.
+    pub const DEFAULT: Self = Self {
+        corner: Corner::BottomRight,
+        offset: (12, 40),
+    };
+
+    /// The placement of `window` on `monitor`, measured from the corner
+    /// nearest to the window's center.
+    pub fn from_geometry(window: Rect, monitor: Rect) -> Self {
+        let (x, y, w, h) = window;
+        let (mx, my, mw, mh) = monitor;
+        let left = x + w / 2 < mx + mw / 2;
+        let top = y + h / 2 < my + mh / 2;
+        let corner = match (top, left) {
+            (true, true) => Corner::TopLeft,
+            (true, false) => Corner::TopRight,
+            (false, true) => Corner::BottomLeft,
+            (false, false) => Corner::BottomRight,
+        };
+        let dx = if left { x - mx } else { mx + mw - x - w };
+        let dy = if top { y - my } else { my + mh - y - h };
+        Self {
+            corner,
+            offset: (dx.max(0), dy.max(0)),
+        }
+    }
+
+    /// Top-left position of a window of `size` on `monitor`, kept inside it.
+    pub fn position(self, size: (i32, i32), monitor: Rect) -> (i32, i32) {
+        let (w, h) = size;
+        let (mx, my, mw, mh) = monitor;
+        let (dx, dy) = self.offset;
+        let x = if self.corner.is_left() {
+            mx + dx
+        } else {
+            mx + mw - w - dx
+        };
+        let y = if self.corner.is_top() {
+            my + dy
+        } else {
+            my + mh - h - dy
+        };
+        (
+            x.clamp(mx, (mx + mw - w).max(mx)),
+            y.clamp(my, (my + mh - h).max(my)),
+        )
+    }
+
+    /// Read back the stored corner and `"x,y"` offset.
+    pub fn parse(corner: &str, offset: &str) -> Option<Self> {
+        let (dx, dy) = offset.split_once(',')?;
+        let number = |v: &str| v.trim().parse().ok().filter(|n: &i32| *n >= 0);
+        Some(Self {
+            corner: Corner::parse(corner)?,
+            offset: (number(dx)?, number(dy)?),
+        })
+    }
+
+    /// The offset as stored, `"x,y"`.
+    pub fn offset_str(self) -> String {
+        format!("{},{}", self.offset.0, self.offset.1)
+    }
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How a monitor is remembered: its connector name (`DP-1`, `HDMI-A-1`),
+/// which stays the same across hot-plugs, or `#n` by position when the
+/// backend doesn't name connectors.
+pub fn monitor_id(index: usize, connector: Option<&str>) -> String {
+    match connector.filter(|c| !c.is_empty()) {
+        Some(connector) => connector.to_string(),
+        None => format!("#{}", index + 1),
+    }
+}
+
+/// Menu label of a monitor: its model and connector, or a number.
+pub fn monitor_label(index: usize, connector: Option<&str>, model: Option<&str>) -> String {
+    let number = format!("Monitor {}", index + 1);
+    match (
+        model.filter(|m| !m.is_empty()),
+        connector.filter(|c| !c.is_empty()),
+    ) {
+        (Some(model), Some(connector)) => format!("{model} ({connector})"),
+        (Some(name), None) | (None, Some(name)) => format!("{number}: {name}"),
+        (None, None) => number,
+    }
+}
+
+/// A window manager state toggled from the menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hint {
+    /// Above other windows, maximized ones included.
+    Above,
+    /// On every workspace.
+    Sticky,
+}
+
+impl Hint {
+    /// The `_NET_WM_STATE` name `wmctrl -b` takes.
+    pub fn wm_state(self) -> &'static str {
+        match self {
+            Self::Above => "above",
+            Self::Sticky => "sticky",
+        }
+    }
+}
+
+/// `wmctrl` arguments adding or removing `hint` on the window titled
+/// exactly `title`.
+pub fn wmctrl_args(title: &str, hint: Hint, on: bool) -> Vec<String> {
+    let change = if on { "add" } else { "remove" };
+    vec![
+        "-F".into(),
+        "-r".into(),
+        title.into(),
+        "-b".into(),
+        format!("{change},{}", hint.wm_state()),
+    ]
+}
+
+/// Whether GTK runs on Wayland (not X11 or XWayland).
+pub fn on_wayland() -> bool {
+    gtk4::gdk::Display::default().is_some_and(|d| d.type_().name() == "GdkWaylandDisplay")
+}
+
+/// Set or clear `hint` on the X11 window titled `title`.
+#[cfg(target_os = "linux")]
+pub fn set_hint(title: &str, hint: Hint, on: bool) -> Result<(), String> {
+    let status = std::process::Command::new("wmctrl")
+        .args(wmctrl_args(title, hint, on))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| format!("wmctrl failed: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("wmctrl exited with {status}"))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_hint(_title: &str, _hint: Hint, _on: bool) -> Result<(), String> {
+    Err("Window hints are only supported on Linux".into())
+}
+
+/// Make `window`, not yet shown, a layer-shell surface at `corner` (see
+/// [`place`]), on the overlay layer with `keep_above`. Returns false when
+/// the compositor lacks layer-shell.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn init_layer_shell(
+    window: &gtk4::ApplicationWindow,
+    keep_above: bool,
+    corner: Corner,
+    margin: (i32, i32),
+) -> bool {
+    use gtk4_layer_shell::{KeyboardMode, LayerShell};
+
+    if !gtk4_layer_shell::is_supported() {
+        return false;
+    }
+    window.init_layer_shell();
+    window.set_namespace("whispercrabs");
+    // Keyboard focus only when clicked, so Esc and the menu still work
+    window.set_keyboard_mode(KeyboardMode::OnDemand);
+    set_keep_above(window, keep_above);
+    place(window, corner, margin);
+    true
+}
+
+/// Whether `window` is a layer-shell surface.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn is_layer_window(window: &gtk4::ApplicationWindow) -> bool {
+    use gtk4_layer_shell::LayerShell;
+    window.is_layer_window()
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+pub fn is_layer_window(_window: &gtk4::ApplicationWindow) -> bool {
+    false
+}
+
+/// Anchor the layer-shell `window` to `corner`, `margin` (horizontal,
+/// vertical) away from its edges; applies right away.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn place(window: &gtk4::ApplicationWindow, corner: Corner, margin: (i32, i32)) {
+    use gtk4_layer_shell::{Edge, LayerShell};
+
+    let (x, y) = margin;
+    for (edge, on, margin) in [
+        (Edge::Top, corner.is_top(), y),
+        (Edge::Bottom, !corner.is_top(), y),
+        (Edge::Left, corner.is_left(), x),
+        (Edge::Right, !corner.is_left(), x),
+    ] {
+        window.set_anchor(edge, on);
+        window.set_margin(edge, if on { margin } else { 0 });
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+pub fn place(_window: &gtk4::ApplicationWindow, _corner: Corner, _margin: (i32, i32)) {}
+
+/// Show the layer-shell `window` on `monitor`, or where the compositor
+/// chooses with `None`; applies right away.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn set_monitor(window: &gtk4::ApplicationWindow, monitor: Option<&gtk4::gdk::Monitor>) {
+    use gtk4_layer_shell::LayerShell;
+    window.set_monitor(monitor);
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+pub fn set_monitor(_window: &gtk4::ApplicationWindow, _monitor: Option<&gtk4::gdk::Monitor>) {}
+
+/// Put the layer-shell `window` on the overlay layer (above fullscreen
+/// windows) or the top layer; applies right away.
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub fn set_keep_above(window: &gtk4::ApplicationWindow, keep_above: bool) {
+    use gtk4_layer_shell::{Layer, LayerShell};
+    window.set_layer(if keep_above {
+        Layer::Overlay
+    } else {
+        Layer::Top
+    });
+}
+
+#[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+pub fn set_keep_above(_window: &gtk4::ApplicationWindow, _keep_above: bool) {}
@@ -0,0 +1,34 @@
+//! Subtitle export of timed transcript segments.
+//!
+//! History entries with stored segments can be saved as SubRip (`.srt`):
+//! one numbered cue per segment, timed relative to the start of the
+//! recording.
+
+use crate::local_stt::Segment;
+
+/// `HH:MM:SS,mmm`, the SubRip timestamp format.
+pub fn srt_timestamp(ms: i64) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Render `segments` as a SubRip document.
+pub fn to_srt(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for (i, seg) in segments.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            srt_timestamp(seg.start_ms),
+            srt_timestamp(seg.end_ms.max(seg.start_ms)),
+            seg.text.trim()
+        ));
+    }
+    out
+}
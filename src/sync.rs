@@ -0,0 +1,105 @@
+//! History sync between machines — merge imports and a shared sync folder.
+//!
+//! An entry is identified by its timestamp and a hash of its text, so the
+//! same dictation imported twice (or read back from the sync folder by the
+//! machine that wrote it) is recognised and skipped, see [`crate::db::Db::import`].
+//!
+//! With a sync folder set, every new History entry is also written there as
+//! one small JSON file named after its timestamp and hash. Files are never
+//! rewritten, which is what folder-sync tools like Syncthing or Nextcloud
+//! handle best; each machine imports the files the others wrote at startup
+//! and on **Sync Now**.
+
+use std::path::{Path, PathBuf};
+
+/// Settings key of the sync folder.
+pub const SYNC_DIR_SETTING: &str = "sync_dir";
+
+/// A History entry as exported, imported or synced.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct SyncEntry {
+    /// `YYYY-MM-DD HH:MM:SS`, local time of the machine that dictated it.
+    pub created_at: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    pub text: String,
+}
+
+/// A stable hash of `text` (64-bit FNV-1a, hex), the same on every machine.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Entries from a History export (a JSON array) or a single sync file.
+/// Fields other than those of [`SyncEntry`], like `id`, are ignored.
+pub fn parse_entries(json: &str) -> Result<Vec<SyncEntry>, String> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        Many(Vec<SyncEntry>),
+        One(SyncEntry),
+    }
+    match serde_json::from_str(json).map_err(|e| format!("Not a History export: {e}"))? {
+        OneOrMany::Many(entries) => Ok(entries),
+        OneOrMany::One(entry) => Ok(vec![entry]),
+    }
+}
+
+/// File name of `entry` in the sync folder: `<YYYYMMDD-HHMMSS>-<hash>.json`.
+pub fn file_name(entry: &SyncEntry) -> String {
+    let stamp: String = entry
+        .created_at
+        .chars()
+        .filter_map(|c| match c {
+            '0'..='9' => Some(c),
+            ' ' | 'T' => Some('-'),
+            _ => None,
+        })
+        .collect();
+    format!("{stamp}-{}.json", content_hash(&entry.text))
+}
+
+/// Write `entry` into the sync folder `dir`. The file appears complete or
+/// not at all: it is written under a hidden name first and then renamed.
+pub fn write_entry(dir: &Path, entry: &SyncEntry) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Sync folder: {e}"))?;
+    let name = file_name(entry);
+    let path = dir.join(&name);
+    let partial = dir.join(format!(".{name}.part"));
+    let json = serde_json::to_string_pretty(entry).map_err(|e| e.to_string())?;
+    std::fs::write(&partial, json)
+        .and_then(|()| std::fs::rename(&partial, &path))
+        .map_err(|e| format!("Writing {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Every entry in the sync folder `dir`. Files that can't be read (still
+/// syncing, or not ours) are skipped.
+pub fn read_dir(dir: &Path) -> Result<Vec<SyncEntry>, String> {
+    let listing = std::fs::read_dir(dir).map_err(|e| format!("Sync folder: {e}"))?;
+    let mut paths: Vec<PathBuf> = listing
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension().is_some_and(|ext| ext == "json")
+                && !p
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    // Oldest first, so imported entries keep their order in History
+    paths.sort();
+    let mut entries = Vec::new();
+    for path in paths {
+        match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| parse_entries(&json))
+        {
+            Ok(mut found) => entries.append(&mut found),
+            Err(e) => tracing::warn!("Skipping sync file {}: {e}", path.display()),
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,131 @@
+//! Output templates — the copied text wrapped per record profile.
+//!
+//! A profile's `template` shapes what lands on the clipboard, e.g.
+//! `- [{timestamp}] {text}` for a journal or `> {text}` for quoted meeting
+//! notes. History keeps the plain transcript. Variables are written in
+//! braces; `{{` and `}}` stand for literal braces:
+//!
+//! | Variable | Value |
+//! |---|---|
+//! | `{text}` | the transcript |
+//! | `{date}` | `2026-03-09` |
+//! | `{time}` | `14:05` |
+//! | `{timestamp}` | `2026-03-09 14:05` |
+//! | `{provider}` | `groq`, `local-base`, ... |
+//! | `{language}` | detected or chosen language code, empty if unknown |
+//!
+//! On a line that starts with `>` (a Markdown quote), every line of a
+//! multi-line transcript is quoted, not just the first.
+
+use chrono::NaiveDateTime;
+
+/// Variables a template may use.
+pub const VARIABLES: &[&str] = &["text", "date", "time", "timestamp", "provider", "language"];
+
+/// What a template is filled in with.
+pub struct Vars<'a> {
+    pub text: &'a str,
+    pub provider: &'a str,
+    pub language: Option<&'a str>,
+    /// When the transcript was delivered, local time.
+    pub time: NaiveDateTime,
+}
+
+/// One piece of a parsed template.
+enum Part<'a> {
+    Literal(&'a str),
+    Brace(char),
+    Variable(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        if pos > 0 {
+            parts.push(Part::Literal(&rest[..pos]));
+        }
+        let brace = rest.as_bytes()[pos] as char;
+        let after = &rest[pos + 1..];
+        if after.starts_with(brace) {
+            parts.push(Part::Brace(brace));
+            rest = &after[1..];
+            continue;
+        }
+        if brace == '}' {
+            return Err("unmatched '}' in template (write '}}' for a brace)".into());
+        }
+        let end = after
+            .find('}')
+            .ok_or("unclosed '{' in template (write '{{' for a brace)")?;
+        let name = &after[..end];
+        if !VARIABLES.contains(&name) {
+            return Err(format!(
+                "unknown template variable '{{{name}}}' (use {})",
+                VARIABLES
+                    .iter()
+                    .map(|v| format!("{{{v}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        parts.push(Part::Variable(name));
+        rest = &after[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+    Ok(parts)
+}
+
+/// Check `template` for unknown variables and stray braces.
+pub fn validate(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// Fill in `template`; an invalid one leaves the transcript as it is.
+pub fn render(template: &str, vars: &Vars) -> String {
+    let Ok(parts) = parse(template) else {
+        return vars.text.to_string();
+    };
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            Part::Literal(text) => out.push_str(text),
+            Part::Brace(brace) => out.push(brace),
+            Part::Variable("text") => {
+                // Continue a Markdown quote over every line of the transcript
+                let line = &out[out.rfind('\n').map_or(0, |i| i + 1)..];
+                let quote: String = line
+                    .chars()
+                    .take_while(|c| *c == '>' || *c == ' ')
+                    .collect();
+                if quote.contains('>') {
+                    for (i, text_line) in vars.text.split('\n').enumerate() {
+                        if i > 0 {
+                            out.push('\n');
+                            // Blank lines get a bare ">"
+                            out.push_str(if text_line.is_empty() {
+                                quote.trim_end()
+                            } else {
+                                &quote
+                            });
+                        }
+                        out.push_str(text_line);
+                    }
+                } else {
+                    out.push_str(vars.text);
+                }
+            }
+            Part::Variable("date") => out.push_str(&vars.time.format("%Y-%m-%d").to_string()),
+            Part::Variable("time") => out.push_str(&vars.time.format("%H:%M").to_string()),
+            Part::Variable("timestamp") => {
+                out.push_str(&vars.time.format("%Y-%m-%d %H:%M").to_string())
+            }
+            Part::Variable("provider") => out.push_str(vars.provider),
+            Part::Variable("language") => out.push_str(vars.language.unwrap_or_default()),
+            Part::Variable(name) => unreachable!("unknown variable {name} passed parse"),
+        }
+    }
+    out
+}
@@ -0,0 +1,46 @@
+use crate::activation::{Activation, ActivationSource};
+use std::sync::mpsc;
+
+#[test]
+fn start_and_stop_only_change_the_matching_state() {
+    assert_eq!(Activation::Start.resolve(false), Some(true));
+    assert_eq!(Activation::Start.resolve(true), None);
+    assert_eq!(Activation::Stop.resolve(true), Some(false));
+    assert_eq!(Activation::Stop.resolve(false), None);
+}
+
+#[test]
+fn toggle_flips_the_recording() {
+    assert_eq!(Activation::Toggle.resolve(false), Some(true));
+    assert_eq!(Activation::Toggle.resolve(true), Some(false));
+}
+
+/// A source that fires a fixed list of activations, as a new trigger would.
+struct Scripted(Vec<Activation>);
+
+impl ActivationSource for Scripted {
+    fn name(&self) -> &'static str {
+        "scripted"
+    }
+
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        let (tx, rx) = mpsc::channel();
+        for activation in self.0.drain(..) {
+            tx.send(activation).map_err(|e| e.to_string())?;
+        }
+        Ok(rx)
+    }
+}
+
+#[test]
+fn sources_deliver_activations_in_order() {
+    let mut source: Box<dyn ActivationSource> =
+        Box::new(Scripted(vec![Activation::Toggle, Activation::Stop]));
+    let rx = source.start().unwrap();
+    // Default hook: sources that don't show the state ignore it
+    source.state_changed("recording");
+    assert_eq!(
+        rx.iter().collect::<Vec<_>>(),
+        vec![Activation::Toggle, Activation::Stop]
+    );
+}
@@ -0,0 +1,22 @@
+use crate::announce;
+
+#[test]
+fn message_says_where_the_text_went() {
+    assert_eq!(
+        announce::message(" hello world ", false),
+        "Copied: hello world"
+    );
+    assert_eq!(announce::message("hello", true), "Typed: hello");
+    assert_eq!(announce::message("  ", false), "Copied nothing");
+}
+
+#[test]
+fn long_transcripts_are_shortened() {
+    let long = "word ".repeat(200);
+    let message = announce::message(&long, false);
+    assert!(message.ends_with(", and more"));
+    assert!(message.chars().count() <= "Copied: ".len() + announce::MAX_CHARS + ", and more".len());
+
+    let exact = "é".repeat(announce::MAX_CHARS);
+    assert_eq!(announce::message(&exact, false), format!("Copied: {exact}"));
+}
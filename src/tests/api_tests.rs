@@ -2,23 +2,40 @@ use crate::api;
 
 #[test]
 fn transcribe_url_strips_trailing_slash() {
-    // We can't call transcribe without a server, but we can verify the URL logic
-    // by testing the format pattern used in api.rs
     let base = "https://api.groq.com/openai/v1/";
-    let url = format!("{}/audio/transcriptions", base.trim_end_matches('/'));
+    let url = api::transcription_url(base, "/audio/transcriptions");
     assert_eq!(url, "https://api.groq.com/openai/v1/audio/transcriptions");
 }
 
 #[test]
 fn transcribe_url_no_trailing_slash() {
     let base = "https://api.groq.com/openai/v1";
-    let url = format!("{}/audio/transcriptions", base.trim_end_matches('/'));
+    let url = api::transcription_url(base, "/audio/transcriptions");
     assert_eq!(url, "https://api.groq.com/openai/v1/audio/transcriptions");
 }
 
+#[test]
+fn transcribe_url_custom_path() {
+    let url = api::transcription_url("http://localhost:8080/", "inference");
+    assert_eq!(url, "http://localhost:8080/inference");
+}
+
+#[test]
+fn transcribe_url_empty_path_uses_default() {
+    let url = api::transcription_url("http://localhost:8000/v1", "  ");
+    assert_eq!(url, "http://localhost:8000/v1/audio/transcriptions");
+}
+
 #[tokio::test]
 async fn transcribe_rejects_invalid_url() {
-    let result = api::transcribe("http://127.0.0.1:1", "fake-key", "model", vec![0u8; 44]).await;
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "/audio/transcriptions",
+        "fake-key",
+        "model",
+        api::AudioUpload::Bytes(vec![0u8; 44]),
+    )
+    .await;
     assert!(result.is_err());
     let err = result.unwrap_err();
     assert!(
@@ -30,20 +47,147 @@ async fn transcribe_rejects_invalid_url() {
 #[tokio::test]
 async fn transcribe_rejects_empty_wav() {
     // Even with a valid-looking URL, an empty body should fail at the multipart level or server
-    let result = api::transcribe("http://127.0.0.1:1", "key", "model", vec![]).await;
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "",
+        "key",
+        "model",
+        api::AudioUpload::Bytes(vec![]),
+    )
+    .await;
     assert!(result.is_err());
 }
 
 #[tokio::test]
 async fn transcribe_rejects_file_url() {
-    let result = api::transcribe("file:///etc/passwd", "key", "model", vec![1]).await;
+    let result = api::transcribe(
+        "file:///etc/passwd",
+        "",
+        "key",
+        "model",
+        api::AudioUpload::Bytes(vec![1]),
+    )
+    .await;
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("only http:// and https://"));
 }
 
 #[tokio::test]
 async fn transcribe_rejects_ftp_url() {
-    let result = api::transcribe("ftp://evil.com", "key", "model", vec![1]).await;
+    let result = api::transcribe(
+        "ftp://evil.com",
+        "",
+        "key",
+        "model",
+        api::AudioUpload::Bytes(vec![1]),
+    )
+    .await;
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("only http:// and https://"));
 }
+
+#[test]
+fn parse_response_reads_verbose_json_language() {
+    let json = serde_json::json!({"text": "Hallo Welt", "language": "german", "duration": 1.2});
+    let t = api::parse_response(&json).unwrap();
+    assert_eq!(t.text, "Hallo Welt");
+    assert_eq!(t.language.as_deref(), Some("de"));
+
+    let json = serde_json::json!({"text": "hi", "language": "en"});
+    assert_eq!(
+        api::parse_response(&json).unwrap().language.as_deref(),
+        Some("en")
+    );
+}
+
+#[test]
+fn parse_response_plain_json_has_no_language() {
+    let json = serde_json::json!({"text": "hi"});
+    assert_eq!(api::parse_response(&json).unwrap().language, None);
+    assert!(api::parse_response(&serde_json::json!({"error": "x"})).is_err());
+}
+
+#[test]
+fn parse_response_reads_verbose_json_segments() {
+    let json = serde_json::json!({
+        "text": "One. Two.",
+        "segments": [
+            {"start": 0.0, "end": 1.5, "text": " One.", "avg_logprob": 0.0},
+            {"start": 1.5, "end": 2.25, "text": " Two."},
+            {"start": 2.25, "end": 3.0, "text": " "}
+        ]
+    });
+    let t = api::parse_response(&json).unwrap();
+    assert_eq!(t.segments.len(), 2);
+    assert_eq!(t.segments[0].text, "One.");
+    assert_eq!(t.segments[0].confidence, Some(1.0));
+    assert_eq!((t.segments[1].start_ms, t.segments[1].end_ms), (1500, 2250));
+    assert_eq!(t.segments[1].confidence, None);
+
+    let plain = serde_json::json!({"text": "hi"});
+    assert!(api::parse_response(&plain).unwrap().segments.is_empty());
+}
+
+#[test]
+fn short_recordings_upload_from_memory() {
+    let wav = crate::audio::encode_wav(&[0.0; 1600], 16000).unwrap();
+    let upload = api::AudioUpload::from_recording(wav);
+    assert!(matches!(upload, api::AudioUpload::Bytes(_)));
+    assert!(upload.path().is_none());
+}
+
+#[test]
+fn long_recordings_are_spooled_and_removed_after_upload() {
+    // A low sample rate keeps the five-minute clip small
+    let rate = 100;
+    let secs = api::SPOOL_MIN_SECS as usize + 1;
+    let wav = crate::audio::encode_wav(&vec![0.0; rate * secs], rate as u32).unwrap();
+    let upload = api::AudioUpload::from_recording(wav.clone());
+    let path = upload.path().expect("spooled to disk").to_path_buf();
+    assert_eq!(std::fs::read(&path).unwrap(), wav);
+    drop(upload);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn transcribe_reports_missing_upload_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("gone.wav");
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "",
+        "key",
+        "model",
+        api::AudioUpload::File(missing),
+        &api::RequestOptions::default(),
+    )
+    .await;
+    assert!(result.unwrap_err().contains("Can't read"));
+}
+
+#[test]
+fn uploads_over_the_limit_are_refused() {
+    assert!(api::check_upload_size(10, None).is_ok());
+    assert!(api::check_upload_size(25 * 1024 * 1024, Some(25 * 1024 * 1024)).is_ok());
+    let err = api::check_upload_size(30 * 1024 * 1024, Some(25 * 1024 * 1024)).unwrap_err();
+    assert!(err.contains("30 MB"));
+    assert!(err.contains("limit is 25 MB"));
+}
+
+#[tokio::test]
+async fn transcribe_checks_the_limit_before_sending() {
+    let options = api::RequestOptions {
+        max_upload_bytes: Some(4),
+        ..Default::default()
+    };
+    let result = api::transcribe(
+        "http://127.0.0.1:1",
+        "",
+        "key",
+        "model",
+        api::AudioUpload::Bytes(vec![0; 8]),
+        &options,
+    )
+    .await;
+    assert!(result.unwrap_err().contains("too long"));
+}
@@ -0,0 +1,76 @@
+use crate::appearance::{ColorScheme, Theme, WidgetSize, portal_value};
+use gtk4::glib::{self, prelude::*};
+
+#[test]
+fn portal_values_map_to_schemes() {
+    assert_eq!(ColorScheme::from_portal(0), ColorScheme::Default);
+    assert_eq!(ColorScheme::from_portal(1), ColorScheme::Dark);
+    assert_eq!(ColorScheme::from_portal(2), ColorScheme::Light);
+    assert_eq!(ColorScheme::from_portal(7), ColorScheme::Default);
+}
+
+#[test]
+fn portal_value_unwraps_boxed_variants() {
+    let plain = 1u32.to_variant();
+    let read_one = glib::Variant::from_variant(&plain);
+    let read = glib::Variant::from_variant(&read_one);
+    assert_eq!(portal_value(&plain), Some(1));
+    assert_eq!(portal_value(&read_one), Some(1));
+    assert_eq!(portal_value(&read), Some(1));
+    assert_eq!(portal_value(&"dark".to_variant()), None);
+}
+
+#[test]
+fn theme_round_trips_through_settings() {
+    for theme in Theme::ALL {
+        assert_eq!(Theme::parse(theme.as_str()), Some(theme));
+    }
+    assert_eq!(Theme::parse("sepia"), None);
+    assert_eq!(Theme::default(), Theme::System);
+}
+
+#[test]
+fn pinned_theme_overrides_the_desktop() {
+    assert_eq!(Theme::System.scheme(ColorScheme::Light), ColorScheme::Light);
+    assert_eq!(
+        Theme::System.scheme(ColorScheme::Default),
+        ColorScheme::Default
+    );
+    assert_eq!(Theme::Dark.scheme(ColorScheme::Light), ColorScheme::Dark);
+    assert_eq!(Theme::Light.scheme(ColorScheme::Dark), ColorScheme::Light);
+}
+
+#[test]
+fn user_css_sits_in_the_config_dir() {
+    if let Some(path) = crate::appearance::user_css_path() {
+        assert!(path.ends_with("whispercrabs/style.css"));
+    }
+}
+
+#[test]
+fn widget_size_parses_presets_and_pixels() {
+    for size in WidgetSize::PRESETS {
+        assert_eq!(WidgetSize::parse(&size.as_str()), Some(size));
+    }
+    assert_eq!(WidgetSize::parse(" 88px "), Some(WidgetSize::Pixels(88)));
+    assert_eq!(WidgetSize::parse("120"), Some(WidgetSize::Pixels(120)));
+    assert_eq!(WidgetSize::parse("10"), None);
+    assert_eq!(WidgetSize::parse("1000"), None);
+    assert_eq!(WidgetSize::default(), WidgetSize::Medium);
+}
+
+#[test]
+fn medium_size_matches_the_built_in_style() {
+    let size = WidgetSize::Medium;
+    assert_eq!((size.button_px(), size.icon_px()), (72, 32));
+    assert_eq!(size.window_size(), (88, 100));
+    assert!(size.css().contains("min-width: 72px"));
+    assert!(size.css().contains("-gtk-icon-size: 32px"));
+}
+
+#[test]
+fn sizes_scale_the_icon_with_the_button() {
+    assert!(WidgetSize::Small.icon_px() < WidgetSize::Medium.icon_px());
+    assert!(WidgetSize::Large.icon_px() > WidgetSize::Medium.icon_px());
+    assert_eq!(WidgetSize::Pixels(90).icon_px(), 40);
+}
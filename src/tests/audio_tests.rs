@@ -127,3 +127,90 @@ fn u16_to_f32_conversion() {
     assert!((floats[1] - 1.0).abs() < 0.001);
     assert!(floats[2].abs() < 0.01); // midpoint ~0
 }
+
+#[test]
+fn wav_duration_from_buffer() {
+    let samples = vec![0.0f32; 32000];
+    let wav = encode_wav(&samples, 16000, 1);
+    let secs = crate::audio::wav_duration_secs(&wav).unwrap();
+    assert!((secs - 2.0).abs() < 1e-9);
+    assert!(crate::audio::wav_duration_secs(b"not a wav").is_none());
+}
+
+#[test]
+fn recorder_new_opens_no_device() {
+    // Creating the recorder must not need a microphone; the device is opened on start
+    let recorder = crate::audio::Recorder::new();
+    assert_eq!(recorder.sample_rate(), 44100);
+}
+
+#[test]
+fn frame_aligned_keeps_whole_frames() {
+    assert_eq!(crate::audio::frame_aligned(10, 1), 10);
+    assert_eq!(crate::audio::frame_aligned(11, 2), 10);
+    assert_eq!(crate::audio::frame_aligned(5, 6), 0);
+    assert_eq!(crate::audio::frame_aligned(7, 0), 7);
+}
+
+#[test]
+fn read_wav_downmixes_to_mono() {
+    let stereo = encode_wav(&[0.5, -0.5, 0.25, 0.25], 48000, 2);
+    let (samples, rate) = crate::audio::read_wav(&stereo).unwrap();
+    assert_eq!(rate, 48000);
+    assert_eq!(samples.len(), 2);
+    assert!(samples[0].abs() < 0.001);
+    assert!((samples[1] - 0.25).abs() < 0.001);
+}
+
+#[test]
+fn read_wav_reads_float_samples() {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::new(&mut buf, spec).unwrap();
+    for s in [0.0f32, 0.5, -0.5] {
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+    let (samples, rate) = crate::audio::read_wav(&buf.into_inner()).unwrap();
+    assert_eq!(rate, 16000);
+    assert_eq!(samples, [0.0, 0.5, -0.5]);
+}
+
+#[test]
+fn read_wav_rejects_other_files() {
+    assert!(crate::audio::read_wav(b"ID3 not a wav").is_err());
+    assert!(crate::audio::read_wav(&encode_wav(&[], 16000, 1)).is_err());
+}
+
+#[test]
+fn decode_file_gives_16k_wav() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("a.wav");
+    std::fs::write(&path, encode_wav(&[0.1; 16000], 16000, 2)).unwrap();
+    let (wav, rate) = crate::audio::decode_file(&path).unwrap();
+    assert_eq!(rate, 16000);
+    assert_eq!(crate::audio::wav_duration_secs(&wav), Some(0.5));
+}
+
+#[test]
+fn decode_file_rejects_other_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("a.mp3");
+    std::fs::write(&path, b"not audio").unwrap();
+    assert!(crate::audio::decode_file(&path).is_err());
+    assert!(crate::audio::decode_file(&dir.path().join("missing.ogg")).is_err());
+}
+
+#[test]
+fn videos_are_known_by_extension() {
+    use std::path::Path;
+    assert!(crate::audio::is_video_file(Path::new("call.mp4")));
+    assert!(crate::audio::is_video_file(Path::new("talk.MKV")));
+    assert!(!crate::audio::is_video_file(Path::new("talk.mp3")));
+    assert!(!crate::audio::is_video_file(Path::new("mp4")));
+}
@@ -0,0 +1,50 @@
+use crate::auto::{self, ProbeResult};
+use std::time::Duration;
+
+fn result(provider: &str, ms: Option<u64>) -> ProbeResult {
+    ProbeResult {
+        provider: provider.to_string(),
+        latency: ms.map(Duration::from_millis),
+    }
+}
+
+#[test]
+fn pick_fastest_healthy() {
+    let results = vec![
+        result("groq", Some(300)),
+        result("openrouter", Some(120)),
+        result("ollama", None),
+    ];
+    assert_eq!(auto::pick(&results, None), Some("openrouter"));
+}
+
+#[test]
+fn pick_none_when_all_down() {
+    let results = vec![result("groq", None), result("openrouter", None)];
+    assert_eq!(auto::pick(&results, None), None);
+    assert_eq!(auto::pick(&[], Some("groq")), None);
+}
+
+#[test]
+fn pick_sticks_with_current_when_close() {
+    let results = vec![result("groq", Some(140)), result("openrouter", Some(100))];
+    assert_eq!(auto::pick(&results, Some("groq")), Some("groq"));
+}
+
+#[test]
+fn pick_switches_when_current_much_slower() {
+    let results = vec![result("groq", Some(400)), result("openrouter", Some(100))];
+    assert_eq!(auto::pick(&results, Some("groq")), Some("openrouter"));
+}
+
+#[test]
+fn pick_leaves_unhealthy_current() {
+    let results = vec![result("groq", None), result("openrouter", Some(900))];
+    assert_eq!(auto::pick(&results, Some("groq")), Some("openrouter"));
+}
+
+#[test]
+fn probe_unreachable_backend_is_unhealthy() {
+    // Port 9 (discard) on loopback is closed on test machines
+    assert_eq!(auto::probe("http://127.0.0.1:9/v1", None), None);
+}
@@ -0,0 +1,95 @@
+use crate::autosave::{self, Layout};
+use crate::db::Db;
+use chrono::NaiveDateTime;
+
+fn at(s: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+}
+
+#[test]
+fn layout_roundtrips_through_settings() {
+    for layout in Layout::ALL {
+        assert_eq!(Layout::parse(layout.as_str()), Some(layout));
+    }
+    assert_eq!(Layout::parse("weekly"), None);
+}
+
+#[test]
+fn file_names_follow_the_layout() {
+    let time = at("2026-03-09 14:05:31");
+    assert_eq!(autosave::file_name(Layout::Daily, time), "2026-03-09.md");
+    assert_eq!(
+        autosave::file_name(Layout::Each, time),
+        "2026-03-09 14-05-31.md"
+    );
+}
+
+#[test]
+fn stored_needs_a_folder() {
+    let db = Db::open_in_memory().unwrap();
+    assert_eq!(autosave::stored(&db), None);
+    db.set_setting(autosave::DIR_SETTING, "/home/me/Notes")
+        .unwrap();
+    assert_eq!(
+        autosave::stored(&db),
+        Some(("/home/me/Notes".into(), Layout::Daily))
+    );
+    db.set_setting(autosave::LAYOUT_SETTING, "each").unwrap();
+    assert_eq!(autosave::stored(&db).unwrap().1, Layout::Each);
+}
+
+#[test]
+fn daily_file_collects_the_days_transcripts() {
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("Journal");
+    let path = autosave::write(
+        &notes,
+        Layout::Daily,
+        at("2026-03-09 09:12:00"),
+        "Buy milk.",
+    )
+    .unwrap();
+    autosave::write(
+        &notes,
+        Layout::Daily,
+        at("2026-03-09 14:05:31"),
+        " Call Sam. \n",
+    )
+    .unwrap();
+    autosave::write(&notes, Layout::Daily, at("2026-03-10 08:00:00"), "New day.").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "## 09:12\n\nBuy milk.\n\n## 14:05\n\nCall Sam.\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(notes.join("2026-03-10.md")).unwrap(),
+        "## 08:00\n\nNew day.\n"
+    );
+}
+
+#[test]
+fn each_transcript_gets_its_own_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let time = at("2026-03-09 14:05:31");
+    let first = autosave::write(dir.path(), Layout::Each, time, "First").unwrap();
+    let second = autosave::write(dir.path(), Layout::Each, time, "Second").unwrap();
+    assert_eq!(first.file_name().unwrap(), "2026-03-09 14-05-31.md");
+    assert_eq!(second.file_name().unwrap(), "2026-03-09 14-05-31-2.md");
+    assert_eq!(std::fs::read_to_string(&first).unwrap(), "First\n");
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), "Second\n");
+}
+
+#[test]
+fn store_writes_what_history_keeps() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting(autosave::DIR_SETTING, &dir.path().to_string_lossy())
+        .unwrap();
+    db.set_setting(autosave::LAYOUT_SETTING, "each").unwrap();
+    crate::transcription::store(&db, "kept", None, crate::db::HistoryMode::Full).unwrap();
+    crate::transcription::store(&db, "secret", None, crate::db::HistoryMode::Off).unwrap();
+    let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(files.len(), 1);
+    let path = files[0].as_ref().unwrap().path();
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "kept\n");
+}
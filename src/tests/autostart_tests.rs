@@ -0,0 +1,39 @@
+use crate::autostart::{FILE_NAME, HIDDEN_FLAG, entry, exec_arg};
+use std::path::Path;
+
+#[test]
+fn entry_starts_hidden() {
+    let text = entry(Path::new("/usr/bin/whispercrabs"));
+    assert!(text.starts_with("[Desktop Entry]\n"));
+    assert!(text.contains("\nType=Application\n"));
+    assert!(text.contains("\nExec=/usr/bin/whispercrabs --hidden\n"));
+    assert_eq!(HIDDEN_FLAG, "--hidden");
+}
+
+#[test]
+fn entry_is_named_after_the_app_id() {
+    assert_eq!(FILE_NAME, "dev.whispercrabs.app.desktop");
+}
+
+#[test]
+fn plain_paths_are_not_quoted() {
+    assert_eq!(
+        exec_arg("/home/me/.cargo/bin/whispercrabs"),
+        "/home/me/.cargo/bin/whispercrabs"
+    );
+}
+
+#[test]
+fn paths_with_spaces_are_quoted() {
+    assert_eq!(
+        exec_arg("/home/me/My Apps/whispercrabs"),
+        "\"/home/me/My Apps/whispercrabs\""
+    );
+}
+
+#[test]
+fn reserved_characters_are_escaped() {
+    assert_eq!(exec_arg("/opt/a$b"), "\"/opt/a\\$b\"");
+    assert_eq!(exec_arg("/opt/50%"), "\"/opt/50%%\"");
+    assert_eq!(exec_arg("/opt/\"x\""), "\"/opt/\\\"x\\\"\"");
+}
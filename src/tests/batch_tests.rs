@@ -0,0 +1,46 @@
+use crate::batch::{collect, subtitle_path, text_path};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn outputs_sit_beside_the_audio() {
+    assert_eq!(
+        text_path(Path::new("a/talk.mp3")),
+        PathBuf::from("a/talk.txt")
+    );
+    assert_eq!(
+        subtitle_path(Path::new("a/talk.ogg")),
+        PathBuf::from("a/talk.srt")
+    );
+}
+
+#[test]
+fn folders_expand_to_their_audio_files_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["b.mp3", "a.wav", "notes.txt", "c.ogg"] {
+        std::fs::write(dir.path().join(name), b"").unwrap();
+    }
+    std::fs::create_dir(dir.path().join("sub.wav")).unwrap();
+    let files = collect(&[dir.path().to_path_buf()]).unwrap();
+    let names: Vec<_> = files
+        .iter()
+        .map(|f| f.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["a.wav", "b.mp3", "c.ogg"]);
+}
+
+#[test]
+fn files_are_taken_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("a.wav");
+    std::fs::write(&file, b"").unwrap();
+    let files = collect(&[file.clone(), dir.path().to_path_buf()]).unwrap();
+    assert_eq!(files, [file]);
+}
+
+#[test]
+fn other_files_and_empty_folders_are_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(collect(&[dir.path().to_path_buf()]).is_err());
+    assert!(collect(&[dir.path().join("notes.txt")]).is_err());
+    assert!(collect(&[]).is_err());
+}
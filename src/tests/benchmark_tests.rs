@@ -0,0 +1,127 @@
+use crate::benchmark::{self, Candidate, Change, Outcome};
+use std::time::Duration;
+
+fn outcome(provider: &str, secs: f64, text: Result<&str, &str>) -> Outcome {
+    Outcome {
+        provider: provider.into(),
+        latency: Duration::from_secs_f64(secs),
+        text: text.map(str::to_string).map_err(str::to_string),
+    }
+}
+
+fn candidate(provider: &str) -> Candidate {
+    Candidate {
+        provider: provider.into(),
+        route: Err("unused".into()),
+    }
+}
+
+#[test]
+fn word_diff_ignores_case_and_punctuation() {
+    assert_eq!(
+        benchmark::word_diff("Hello, world.", "hello world"),
+        vec![Change::Same("hello"), Change::Same("world")]
+    );
+}
+
+#[test]
+fn word_diff_marks_missing_and_extra_words() {
+    assert_eq!(
+        benchmark::word_diff("send the report today", "send report to day"),
+        vec![
+            Change::Same("send"),
+            Change::Removed("the"),
+            Change::Same("report"),
+            Change::Removed("today"),
+            Change::Added("to"),
+            Change::Added("day"),
+        ]
+    );
+    assert_eq!(
+        benchmark::diff_text(&benchmark::word_diff("a b", "a c")),
+        "a [-b-] {+c+}"
+    );
+}
+
+#[test]
+fn word_error_rate_counts_edits_per_reference_word() {
+    assert_eq!(
+        benchmark::word_error_rate("one two three four", "one two three four"),
+        0.0
+    );
+    assert_eq!(
+        benchmark::word_error_rate("one two three four", "one too three four"),
+        0.25
+    );
+    assert_eq!(
+        benchmark::word_error_rate("one two", "one two three four"),
+        1.0
+    );
+    assert_eq!(benchmark::word_error_rate("one two", ""), 1.0);
+    assert_eq!(benchmark::word_error_rate("", ""), 0.0);
+    assert_eq!(benchmark::word_error_rate("", "noise"), 1.0);
+}
+
+#[test]
+fn reference_prefers_the_selected_provider() {
+    let outcomes = vec![
+        outcome("local-tiny", 0.5, Ok("a")),
+        outcome("groq", 0.8, Ok("b")),
+        outcome("openai", 1.0, Err("timeout")),
+    ];
+    assert_eq!(
+        benchmark::reference(&outcomes, "groq").map(|o| o.provider.as_str()),
+        Some("groq")
+    );
+    // A failed or missing preference falls back to the first success
+    assert_eq!(
+        benchmark::reference(&outcomes, "openai").map(|o| o.provider.as_str()),
+        Some("local-tiny")
+    );
+    assert!(benchmark::reference(&outcomes[2..], "openai").is_none());
+}
+
+#[test]
+fn select_keeps_the_named_providers_in_order() {
+    let all = vec![
+        candidate("local-base"),
+        candidate("groq"),
+        candidate("openai"),
+    ];
+    let names = |c: Vec<Candidate>| c.into_iter().map(|c| c.provider).collect::<Vec<_>>();
+    assert_eq!(
+        names(benchmark::select(
+            all,
+            &["openai".into(), "local-base".into()]
+        )),
+        vec!["openai", "local-base"]
+    );
+    let all = vec![candidate("local-base")];
+    let picked = benchmark::select(all, &["local-large".into()]);
+    assert_eq!(picked[0].provider, "local-large");
+    assert!(picked[0].route.is_err());
+    assert_eq!(
+        names(benchmark::select(vec![candidate("groq")], &[])),
+        vec!["groq"]
+    );
+}
+
+#[test]
+fn speed_is_relative_to_real_time() {
+    assert_eq!(benchmark::speed(10.0, Duration::from_secs(2)), Some(5.0));
+    assert_eq!(benchmark::speed(10.0, Duration::ZERO), None);
+}
+
+#[test]
+fn summary_lines_show_the_comparison() {
+    let reference = outcome("groq", 1.0, Ok("one two three four"));
+    let other = outcome("local-base", 2.0, Ok("one too three four"));
+    let line = benchmark::summary_line(&other, 10.0, Some(&reference));
+    assert!(line.starts_with("local-base"));
+    assert!(line.contains("2.00 s"));
+    assert!(line.contains("5.0x"));
+    assert!(line.ends_with("25% WER"));
+    assert!(benchmark::summary_line(&reference, 10.0, Some(&reference)).ends_with("reference"));
+    let failed = outcome("openai", 0.0, Err("timeout"));
+    assert!(benchmark::summary_line(&failed, 10.0, Some(&reference)).ends_with("failed: timeout"));
+}
@@ -0,0 +1,61 @@
+use crate::capabilities::{self, Capabilities};
+
+#[test]
+fn every_api_preset_is_registered() {
+    for preset in crate::config::API_PRESETS {
+        assert!(
+            capabilities::REGISTRY
+                .iter()
+                .any(|(id, _)| *id == preset.id),
+            "{} has no capabilities",
+            preset.id
+        );
+    }
+}
+
+#[test]
+fn presets_use_their_registry_entry() {
+    let groq = capabilities::for_provider("groq", "/inference");
+    assert!(groq.language && groq.translation);
+    assert_eq!(groq.max_upload_bytes, Some(25 * 1024 * 1024));
+    assert_eq!(
+        capabilities::for_provider("ollama", ""),
+        Capabilities::MINIMAL
+    );
+}
+
+#[test]
+fn custom_providers_follow_their_path() {
+    assert_eq!(
+        capabilities::for_provider("custom", "/audio/transcriptions"),
+        Capabilities::OPENAI_COMPATIBLE
+    );
+    assert_eq!(
+        capabilities::for_provider("custom", ""),
+        Capabilities::OPENAI_COMPATIBLE
+    );
+    assert_eq!(
+        capabilities::for_provider("custom", "/inference"),
+        Capabilities::MINIMAL
+    );
+}
+
+#[test]
+fn language_hint_only_for_pinned_languages() {
+    let caps = Capabilities::OPENAI_COMPATIBLE;
+    assert_eq!(capabilities::language_hint(&caps, "de"), Some("de"));
+    assert_eq!(capabilities::language_hint(&caps, "auto"), None);
+    assert_eq!(
+        capabilities::language_hint(&Capabilities::MINIMAL, "de"),
+        None
+    );
+}
+
+#[test]
+fn summary_lists_features_and_limit() {
+    assert_eq!(
+        capabilities::for_provider("groq", "").summary(),
+        "Language hint, prompt, timestamps, translation; uploads up to 25 MB"
+    );
+    assert_eq!(Capabilities::MINIMAL.summary(), "Plain transcription only");
+}
@@ -0,0 +1,69 @@
+use crate::captions::{self, CaptionLines, SpeechGate};
+
+fn frame(level: f32) -> Vec<f32> {
+    vec![level; (captions::SAMPLE_RATE * captions::FRAME_MS / 1000) as usize]
+}
+
+fn frames_for(ms: u32) -> u32 {
+    ms.div_ceil(captions::FRAME_MS)
+}
+
+#[test]
+fn rms_of_constant_signal_is_its_level() {
+    assert_eq!(captions::rms(&[]), 0.0);
+    assert!((captions::rms(&[0.5, -0.5, 0.5]) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn silence_never_opens_an_utterance() {
+    let mut gate = SpeechGate::default();
+    for _ in 0..100 {
+        assert!(gate.push(&frame(0.0)).is_none());
+    }
+    assert!(gate.flush().is_none());
+}
+
+#[test]
+fn utterance_ends_after_trailing_silence() {
+    let mut gate = SpeechGate::default();
+    for _ in 0..frames_for(1000) {
+        assert!(gate.push(&frame(0.2)).is_none());
+    }
+    let mut finished = None;
+    for _ in 0..frames_for(captions::END_SILENCE_MS) {
+        finished = finished.or(gate.push(&frame(0.0)));
+    }
+    let utterance = finished.expect("silence must end the utterance");
+    assert!(utterance.len() >= captions::SAMPLE_RATE as usize);
+}
+
+#[test]
+fn long_speech_is_cut_at_max_utterance() {
+    let mut gate = SpeechGate::default();
+    let cut = (0..frames_for(captions::MAX_UTTERANCE_MS))
+        .find_map(|_| gate.push(&frame(0.2)))
+        .expect("continuous speech must be cut");
+    assert!(cut.len() as u32 >= captions::MAX_UTTERANCE_MS * captions::SAMPLE_RATE / 1000);
+}
+
+#[test]
+fn short_blips_are_dropped() {
+    let mut gate = SpeechGate::default();
+    gate.push(&frame(0.5));
+    let ended = (0..frames_for(captions::END_SILENCE_MS)).find_map(|_| gate.push(&frame(0.0)));
+    assert!(ended.is_none());
+}
+
+#[test]
+fn caption_lines_keep_the_latest() {
+    let mut lines = CaptionLines::default();
+    lines.push("  ");
+    assert_eq!(lines.text(), "");
+    for i in 0..captions::MAX_LINES + 2 {
+        lines.push(&format!(" line {i} "));
+    }
+    let text = lines.text();
+    assert_eq!(text.lines().count(), captions::MAX_LINES);
+    assert!(text.starts_with("line 2"));
+    assert!(text.ends_with(&format!("line {}", captions::MAX_LINES + 1)));
+}
@@ -0,0 +1,203 @@
+use crate::cli::{Command, DEFAULT_HISTORY_LIMIT, SttOptions, history_line, parse};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn no_command_starts_the_app() {
+    assert_eq!(parse(&[]), None);
+    assert_eq!(parse(&args("--no-window")), None);
+    assert_eq!(parse(&args("--hidden --safe-mode")), None);
+}
+
+#[test]
+fn transcribe_takes_a_file_and_options() {
+    assert_eq!(
+        parse(&args(
+            "transcribe call.wav --provider groq --language German --translate"
+        )),
+        Some(Ok(Command::Transcribe {
+            file: PathBuf::from("call.wav"),
+            options: SttOptions {
+                provider: Some("groq".into()),
+                language: Some("de".into()),
+                translate: true,
+            },
+        }))
+    );
+    assert_eq!(
+        parse(&args("transcribe --language auto call.wav")),
+        Some(Ok(Command::Transcribe {
+            file: PathBuf::from("call.wav"),
+            options: SttOptions {
+                language: Some("auto".into()),
+                ..SttOptions::default()
+            },
+        }))
+    );
+}
+
+#[test]
+fn transcribe_needs_exactly_one_file() {
+    assert!(matches!(parse(&args("transcribe")), Some(Err(_))));
+    assert!(matches!(
+        parse(&args("transcribe a.wav b.wav")),
+        Some(Err(_))
+    ));
+    assert!(matches!(
+        parse(&args("transcribe a.wav --fast")),
+        Some(Err(_))
+    ));
+}
+
+#[test]
+fn options_need_a_value() {
+    assert!(matches!(
+        parse(&args("transcribe a.wav --provider")),
+        Some(Err(_))
+    ));
+    assert!(matches!(
+        parse(&args("transcribe a.wav --provider --translate")),
+        Some(Err(_))
+    ));
+    assert!(matches!(
+        parse(&args("transcribe a.wav --language klingon")),
+        Some(Err(_))
+    ));
+}
+
+#[test]
+fn record_needs_a_duration() {
+    assert_eq!(
+        parse(&args("record --duration 10")),
+        Some(Ok(Command::Record {
+            duration: Duration::from_secs(10),
+            options: SttOptions::default(),
+        }))
+    );
+    assert_eq!(
+        parse(&args("record --duration 2.5 --provider local-base")),
+        Some(Ok(Command::Record {
+            duration: Duration::from_millis(2500),
+            options: SttOptions {
+                provider: Some("local-base".into()),
+                ..SttOptions::default()
+            },
+        }))
+    );
+    assert!(matches!(parse(&args("record")), Some(Err(_))));
+    assert!(matches!(parse(&args("record --duration 0")), Some(Err(_))));
+    assert!(matches!(parse(&args("record --duration -3")), Some(Err(_))));
+    assert!(matches!(
+        parse(&args("record --duration 7200")),
+        Some(Err(_))
+    ));
+    assert!(matches!(
+        parse(&args("record --duration ten")),
+        Some(Err(_))
+    ));
+}
+
+#[test]
+fn batch_takes_files_and_folders() {
+    assert_eq!(
+        parse(&args("batch a.mp3 recordings --provider local-base")),
+        Some(Ok(Command::Batch {
+            inputs: vec![PathBuf::from("a.mp3"), PathBuf::from("recordings")],
+            options: SttOptions {
+                provider: Some("local-base".into()),
+                ..SttOptions::default()
+            },
+        }))
+    );
+    assert!(matches!(parse(&args("batch")), Some(Err(_))));
+    assert!(matches!(parse(&args("batch a.mp3 --fast")), Some(Err(_))));
+}
+
+#[test]
+fn history_has_a_default_limit() {
+    assert_eq!(
+        parse(&args("history")),
+        Some(Ok(Command::History {
+            limit: DEFAULT_HISTORY_LIMIT,
+            json: false,
+            language: None,
+        }))
+    );
+    assert_eq!(
+        parse(&args("history --limit 50 --json")),
+        Some(Ok(Command::History {
+            limit: 50,
+            json: true,
+            language: None,
+        }))
+    );
+    assert!(matches!(parse(&args("history --limit 0")), Some(Err(_))));
+    assert!(matches!(parse(&args("history --translate")), Some(Err(_))));
+}
+
+#[test]
+fn history_filters_by_language() {
+    assert_eq!(
+        parse(&args("history --language German")),
+        Some(Ok(Command::History {
+            limit: DEFAULT_HISTORY_LIMIT,
+            json: false,
+            language: Some("de".into()),
+        }))
+    );
+    assert!(matches!(
+        parse(&args("history --language auto")),
+        Some(Err(_))
+    ));
+    assert!(matches!(parse(&args("history --language")), Some(Err(_))));
+}
+
+#[test]
+fn benchmark_takes_an_optional_clip() {
+    assert_eq!(
+        parse(&args("benchmark")),
+        Some(Ok(Command::Benchmark {
+            file: None,
+            providers: vec![],
+            reference: None,
+            language: None,
+        }))
+    );
+    assert_eq!(
+        parse(&args(
+            "benchmark call.wav --providers local-base,groq --reference groq --language de"
+        )),
+        Some(Ok(Command::Benchmark {
+            file: Some(PathBuf::from("call.wav")),
+            providers: vec!["local-base".into(), "groq".into()],
+            reference: Some("groq".into()),
+            language: Some("de".into()),
+        }))
+    );
+    assert!(matches!(
+        parse(&args("benchmark a.wav b.wav")),
+        Some(Err(_))
+    ));
+    assert!(matches!(
+        parse(&args("benchmark --translate")),
+        Some(Err(_))
+    ));
+}
+
+#[test]
+fn help_is_a_command() {
+    assert_eq!(parse(&args("help")), Some(Ok(Command::Help)));
+    assert_eq!(parse(&args("--help")), Some(Ok(Command::Help)));
+}
+
+#[test]
+fn history_lines_are_single_lines() {
+    assert_eq!(
+        history_line("Dear team,\n\n  see below."),
+        "Dear team, see below."
+    );
+}
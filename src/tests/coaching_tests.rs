@@ -0,0 +1,60 @@
+use crate::coaching::{self, Hint, SpeechSample};
+
+fn sample(words: i64, audio_secs: f64, level_db: Option<f64>) -> SpeechSample {
+    SpeechSample {
+        words,
+        audio_secs,
+        level_db,
+    }
+}
+
+#[test]
+fn words_per_minute_needs_a_few_seconds() {
+    assert_eq!(coaching::words_per_minute(30, 12.0), Some(150.0));
+    assert_eq!(coaching::words_per_minute(5, 1.5), None);
+    assert_eq!(coaching::words_per_minute(0, 30.0), None);
+}
+
+#[test]
+fn level_is_rms_in_dbfs() {
+    let full = coaching::level_db(&[1.0, -1.0, 1.0, -1.0]).unwrap();
+    assert!(full.abs() < 1e-9);
+    let tenth = coaching::level_db(&[0.1, -0.1]).unwrap();
+    assert!((tenth + 20.0).abs() < 1e-6);
+    assert_eq!(coaching::level_db(&[0.0; 8]), None);
+    assert_eq!(coaching::level_db(&[]), None);
+}
+
+#[test]
+fn no_hint_without_enough_dictations() {
+    let quiet = vec![sample(20, 10.0, Some(-55.0)); coaching::MIN_SAMPLES - 1];
+    assert_eq!(coaching::hint(&quiet), None);
+}
+
+#[test]
+fn mostly_quiet_dictations_get_a_hint() {
+    let mut recent = vec![sample(20, 10.0, Some(-55.0)); 4];
+    recent.push(sample(20, 10.0, Some(-20.0)));
+    assert_eq!(coaching::hint(&recent), Some(Hint::TooQuiet));
+
+    // One quiet dictation among normal ones says nothing
+    let mut mixed = vec![sample(20, 10.0, Some(-20.0)); 5];
+    mixed[0].level_db = Some(-60.0);
+    assert_eq!(coaching::hint(&mixed), None);
+}
+
+#[test]
+fn mostly_fast_dictations_get_a_hint() {
+    // 40 words in 10 s is 240 wpm
+    let fast = vec![sample(40, 10.0, Some(-20.0)); 6];
+    assert_eq!(coaching::hint(&fast), Some(Hint::TooFast));
+    let calm = vec![sample(25, 10.0, Some(-20.0)); 6];
+    assert_eq!(coaching::hint(&calm), None);
+}
+
+#[test]
+fn only_the_latest_dictations_count() {
+    let mut recent = vec![sample(25, 10.0, Some(-20.0)); coaching::RECENT];
+    recent.extend(vec![sample(25, 10.0, Some(-60.0)); 20]);
+    assert_eq!(coaching::hint(&recent), None);
+}
@@ -0,0 +1,67 @@
+use crate::confidence;
+use crate::db::Db;
+use crate::local_stt::Segment;
+
+fn segment(text: &str, confidence: Option<f32>) -> Segment {
+    Segment {
+        start_ms: 0,
+        end_ms: 1000,
+        text: text.to_string(),
+        confidence,
+    }
+}
+
+#[test]
+fn score_weights_segments_by_length() {
+    let segments = [
+        segment("A long and clear sentence.", Some(0.9)),
+        segment("Hm?", Some(0.1)),
+    ];
+    let score = confidence::score(&segments).unwrap();
+    assert!(score > 0.8 && score < 0.9, "{score}");
+}
+
+#[test]
+fn score_needs_a_confidence() {
+    assert_eq!(confidence::score(&[]), None);
+    assert_eq!(confidence::score(&[segment("Hello.", None)]), None);
+    assert_eq!(
+        confidence::score(&[segment("Hello.", None), segment("There.", Some(0.5))]),
+        Some(0.5)
+    );
+}
+
+#[test]
+fn no_speech_discounts_confidence() {
+    assert_eq!(confidence::with_no_speech(Some(0.8), Some(0.5)), Some(0.4));
+    assert_eq!(confidence::with_no_speech(Some(0.8), None), Some(0.8));
+    assert_eq!(confidence::with_no_speech(None, Some(0.5)), None);
+}
+
+#[test]
+fn low_needs_a_known_score() {
+    assert!(confidence::is_low(Some(0.3), 0.6));
+    assert!(!confidence::is_low(Some(0.7), 0.6));
+    assert!(!confidence::is_low(None, 0.6));
+}
+
+#[test]
+fn threshold_is_a_percentage_setting() {
+    let db = Db::open_in_memory().unwrap();
+    assert_eq!(confidence::threshold(&db), confidence::DEFAULT_THRESHOLD);
+    db.set_setting(confidence::THRESHOLD_SETTING, "45%")
+        .unwrap();
+    assert_eq!(confidence::threshold(&db), 0.45);
+    db.set_setting(confidence::THRESHOLD_SETTING, "250")
+        .unwrap();
+    assert_eq!(confidence::threshold(&db), confidence::DEFAULT_THRESHOLD);
+}
+
+#[test]
+fn status_flags_low_confidence() {
+    assert_eq!(confidence::status("Copied", false), "Copied!");
+    assert_eq!(
+        confidence::status("Copied", true),
+        "Copied \u{2014} low confidence"
+    );
+}
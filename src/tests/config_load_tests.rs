@@ -31,3 +31,84 @@ fn config_api_model_is_not_empty() {
     let config = Config::load();
     assert!(!config.api_model.is_empty());
 }
+
+#[test]
+fn config_min_recording_defaults_when_unset() {
+    if std::env::var("MIN_RECORDING_MS").is_err() {
+        let config = Config::load();
+        assert_eq!(
+            config.min_recording_ms,
+            crate::config::DEFAULT_MIN_RECORDING_MS
+        );
+    }
+}
+
+#[test]
+fn config_file_keys_are_env_names() {
+    let values = crate::config::parse_config_file(
+        r#"
+api_key = "gsk_file"
+MIN_RECORDING_MS = 500
+no_window = true
+whisper_temperature = 0.2
+hid_keys = ["KEY_F20", "KEY_MICMUTE"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(values["API_KEY"], "gsk_file");
+    assert_eq!(values["MIN_RECORDING_MS"], "500");
+    assert_eq!(values["NO_WINDOW"], "true");
+    assert_eq!(values["WHISPER_TEMPERATURE"], "0.2");
+    assert_eq!(values["HID_KEYS"], "KEY_F20,KEY_MICMUTE");
+}
+
+#[test]
+fn config_file_rejects_sections_and_bad_syntax() {
+    assert!(crate::config::parse_config_file("[api]\nkey = \"x\"").is_err());
+    assert!(crate::config::parse_config_file("api_key = ").is_err());
+    assert!(crate::config::parse_config_file("hid_keys = [[1]]").is_err());
+    assert!(crate::config::parse_config_file("").unwrap().is_empty());
+}
+
+#[test]
+fn environment_overrides_config_file() {
+    let file = crate::config::parse_config_file(
+        "api_key = \"from-file\"\napi_model = \"file-model\"\nmin_recording_ms = 0",
+    )
+    .unwrap();
+    let env = |key: &str| (key == "API_KEY").then(|| "from-env".to_string());
+    let config = Config::from_lookup(crate::config::layered_lookup(env, &file));
+    assert_eq!(config.api_key.as_deref(), Some("from-env"));
+    assert_eq!(config.api_model, "file-model");
+    assert_eq!(config.min_recording_ms, 0);
+    // Not set anywhere: the built-in default
+    assert_eq!(config.api_base_url, "https://api.groq.com/openai/v1");
+    assert_eq!(config.remote_port, crate::remote::DEFAULT_PORT);
+}
+
+#[test]
+fn blank_paths_fall_back_to_defaults() {
+    let config = Config::from_lookup(|key| (key == "WHISPER_MODELS_DIR").then(String::new));
+    assert_eq!(config.models_dir, config.local_models_dir);
+}
+
+#[test]
+fn config_api_prompt_ignores_blank_values() {
+    let config = Config::from_lookup(|key| (key == "API_PROMPT").then(|| " ".to_string()));
+    assert_eq!(config.api_prompt, None);
+    let config =
+        Config::from_lookup(|key| (key == "API_PROMPT").then(|| "WhisperCrabs, Groq".to_string()));
+    assert_eq!(config.api_prompt.as_deref(), Some("WhisperCrabs, Groq"));
+}
+
+#[test]
+fn config_widget_size_accepts_names_and_pixels() {
+    use crate::appearance::WidgetSize;
+    let size = |value: &'static str| {
+        Config::from_lookup(move |key| (key == "WIDGET_SIZE").then(|| value.to_string()))
+            .widget_size
+    };
+    assert_eq!(size("Large"), Some(WidgetSize::Large));
+    assert_eq!(size("88px"), Some(WidgetSize::Pixels(88)));
+    assert_eq!(size("huge"), None);
+}
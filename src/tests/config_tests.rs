@@ -78,3 +78,344 @@ fn model_url_produces_valid_huggingface_url() {
     assert!(url.starts_with("https://huggingface.co/"));
     assert!(url.ends_with("ggml-tiny.en.bin"));
 }
+
+#[test]
+fn estimated_cost_uses_preset_prices() {
+    let groq = config::find_preset("groq").unwrap();
+    assert!((config::estimated_cost("groq", 10.0) - groq.usd_per_minute * 10.0).abs() < 1e-9);
+    assert_eq!(config::estimated_cost("ollama", 10.0), 0.0);
+    assert_eq!(config::estimated_cost("local-base", 10.0), 0.0);
+    assert_eq!(config::estimated_cost("custom", 10.0), 0.0);
+}
+
+#[test]
+fn multilingual_presets_are_not_english_only_files() {
+    let multi: Vec<_> = config::LOCAL_MODEL_PRESETS
+        .iter()
+        .filter(|m| m.multilingual)
+        .collect();
+    assert!(!multi.is_empty());
+    for m in multi {
+        assert!(!m.file_name.contains(".en"), "{} is multilingual", m.id);
+    }
+    for m in config::LOCAL_MODEL_PRESETS
+        .iter()
+        .filter(|m| !m.multilingual)
+    {
+        assert!(m.file_name.contains(".en"), "{} is English-only", m.id);
+    }
+}
+
+#[test]
+fn local_model_ids_are_unique() {
+    let mut ids: Vec<_> = config::LOCAL_MODEL_PRESETS.iter().map(|m| m.id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), config::LOCAL_MODEL_PRESETS.len());
+}
+
+#[test]
+fn effective_language_pins_english_only_models() {
+    let en = config::find_local_model("local-base").unwrap();
+    let multi = config::find_local_model("local-base-multi").unwrap();
+    assert_eq!(config::effective_language(en, "de"), "en");
+    assert_eq!(config::effective_language(multi, "de"), "de");
+    assert_eq!(config::effective_language(multi, "auto"), "auto");
+}
+
+#[test]
+fn default_language_is_known() {
+    assert!(config::is_known_language(config::DEFAULT_STT_LANGUAGE));
+    assert!(!config::is_known_language("xx"));
+}
+
+#[test]
+fn quantized_presets_name_their_quantization() {
+    let quantized: Vec<_> = config::LOCAL_MODEL_PRESETS
+        .iter()
+        .filter(|m| m.quantization.is_some())
+        .collect();
+    assert!(!quantized.is_empty());
+    for m in quantized {
+        let q = m.quantization.unwrap();
+        assert!(
+            m.file_name.ends_with(&format!("-{q}.bin")),
+            "{} file name should end with -{q}.bin",
+            m.id
+        );
+    }
+}
+
+#[test]
+fn model_part_path_appends_suffix() {
+    let path = std::path::Path::new("/tmp/models/ggml-small.en-q5_1.bin");
+    assert_eq!(
+        config::model_part_path(path),
+        std::path::PathBuf::from("/tmp/models/ggml-small.en-q5_1.bin.part")
+    );
+}
+
+#[test]
+fn content_range_start_parses_header() {
+    assert_eq!(
+        config::content_range_start("bytes 1048576-1533042/1533043"),
+        Some(1_048_576)
+    );
+    assert_eq!(config::content_range_start("bytes 0-99/*"), Some(0));
+    assert_eq!(config::content_range_start("bytes */1533043"), None);
+    assert_eq!(config::content_range_start("items 0-9/10"), None);
+}
+
+#[test]
+fn recording_path_is_named_after_transcription() {
+    assert_eq!(
+        config::recording_path(std::path::Path::new("/tmp/audio"), 42),
+        std::path::PathBuf::from("/tmp/audio/42.wav")
+    );
+}
+
+#[test]
+fn custom_local_model_is_local_provider() {
+    assert!(config::is_local_provider(config::CUSTOM_LOCAL_MODEL));
+    assert!(config::is_local_provider("local-base"));
+    assert!(!config::is_local_provider("groq"));
+}
+
+#[test]
+fn validate_custom_model_checks_extension_and_existence() {
+    let dir = tempfile::tempdir().unwrap();
+    let bin = dir.path().join("my-finetune.bin");
+    let gguf = dir.path().join("my-finetune.GGUF");
+    let txt = dir.path().join("notes.txt");
+    for p in [&bin, &gguf, &txt] {
+        std::fs::write(p, b"x").unwrap();
+    }
+    assert!(config::validate_custom_model(&bin).is_ok());
+    assert!(config::validate_custom_model(&gguf).is_ok());
+    assert!(config::validate_custom_model(&txt).is_err());
+    assert!(config::validate_custom_model(&dir.path().join("missing.bin")).is_err());
+}
+
+#[test]
+fn whisper_params_default_is_greedy() {
+    let p = config::WhisperParams::default();
+    assert_eq!(p.threads, 0);
+    assert!(p.beam_size <= 1);
+    assert_eq!(p.best_of, 1);
+    assert_eq!(p.temperature, 0.0);
+}
+
+#[test]
+fn whisper_params_from_lookup_parses_values() {
+    let p = config::WhisperParams::from_lookup(|key| match key {
+        "WHISPER_THREADS" => Some("4".into()),
+        "WHISPER_BEAM_SIZE" => Some("5".into()),
+        "WHISPER_TEMPERATURE" => Some("0.2".into()),
+        "WHISPER_NO_SPEECH_THRESHOLD" => Some("0.4".into()),
+        "WHISPER_SUPPRESS_NON_SPEECH" => Some("true".into()),
+        _ => None,
+    });
+    assert_eq!(p.threads, 4);
+    assert_eq!(p.beam_size, 5);
+    assert_eq!(p.best_of, 1);
+    assert!((p.temperature - 0.2).abs() < f32::EPSILON);
+    assert!((p.no_speech_threshold - 0.4).abs() < f32::EPSILON);
+    assert!(p.suppress_non_speech);
+}
+
+#[test]
+fn whisper_params_from_lookup_rejects_out_of_range() {
+    let p = config::WhisperParams::from_lookup(|key| match key {
+        "WHISPER_THREADS" => Some("lots".into()),
+        "WHISPER_BEST_OF" => Some("0".into()),
+        "WHISPER_TEMPERATURE" => Some("7".into()),
+        "WHISPER_NO_SPEECH_THRESHOLD" => Some("NaN".into()),
+        _ => None,
+    });
+    let d = config::WhisperParams::default();
+    assert_eq!(p.threads, d.threads);
+    assert_eq!(p.best_of, 1);
+    assert_eq!(p.temperature, 1.0);
+    assert_eq!(p.no_speech_threshold, d.no_speech_threshold);
+}
+
+#[test]
+fn downloaded_models_lists_existing_files() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(config::downloaded_models(dir.path()).is_empty());
+    std::fs::write(dir.path().join("ggml-base.en.bin"), vec![0u8; 2048]).unwrap();
+    std::fs::write(dir.path().join("unrelated.bin"), b"x").unwrap();
+    let models = config::downloaded_models(dir.path());
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].0.id, "local-base");
+    assert_eq!(models[0].1, 2048);
+}
+
+#[test]
+fn format_size_units() {
+    assert_eq!(config::format_size(142 * 1024 * 1024), "142 MB");
+    assert_eq!(config::format_size(1536 * 1024 * 1024), "1.5 GB");
+}
+
+#[test]
+fn model_url_in_custom_repo() {
+    assert_eq!(
+        config::model_url("ggml-base.en.bin"),
+        config::model_url_in(config::DEFAULT_MODEL_BASE_URL, "ggml-base.en.bin")
+    );
+    assert_eq!(
+        config::model_url_in("https://huggingface.co/org/repo/resolve/main/", "m.bin"),
+        "https://huggingface.co/org/repo/resolve/main/m.bin"
+    );
+}
+
+#[test]
+fn huggingface_url_detection() {
+    assert!(config::is_huggingface_url(&config::model_url(
+        "ggml-base.en.bin"
+    )));
+    assert!(config::is_huggingface_url(
+        "https://cdn-lfs.huggingface.co/x"
+    ));
+    assert!(!config::is_huggingface_url("http://huggingface.co/x"));
+    assert!(!config::is_huggingface_url(
+        "https://huggingface.co.evil.com/x"
+    ));
+    assert!(!config::is_huggingface_url(
+        "https://example.com/huggingface.co"
+    ));
+}
+
+#[test]
+fn vosk_presets_are_local_and_unique() {
+    for m in config::VOSK_MODEL_PRESETS {
+        assert!(config::is_local_provider(m.id));
+        assert!(config::find_local_model(m.id).is_none());
+        assert!(config::is_known_language(m.language));
+        assert!(config::vosk_model_url(m).ends_with(&format!("/{}.zip", m.name)));
+        assert_eq!(
+            config::VOSK_MODEL_PRESETS
+                .iter()
+                .filter(|o| o.id == m.id)
+                .count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn local_model_path_by_kind() {
+    let dir = std::path::Path::new("/tmp/models");
+    assert_eq!(
+        config::local_model_path(dir, "local-base", None),
+        Some(dir.join("ggml-base.en.bin"))
+    );
+    assert_eq!(
+        config::local_model_path(dir, "vosk-small-en", None),
+        Some(dir.join("vosk").join("vosk-model-small-en-us-0.15"))
+    );
+    let custom = std::path::Path::new("/opt/ft.gguf");
+    assert_eq!(
+        config::local_model_path(dir, config::CUSTOM_LOCAL_MODEL, Some(custom)),
+        Some(custom.to_path_buf())
+    );
+    assert_eq!(
+        config::local_model_path(dir, config::CUSTOM_LOCAL_MODEL, None),
+        None
+    );
+    assert_eq!(config::local_model_path(dir, "groq", None), None);
+}
+
+#[test]
+fn downloaded_vosk_models_sums_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let model = config::find_vosk_model("vosk-small-en").unwrap();
+    let model_dir = config::vosk_model_dir(dir.path(), model);
+    std::fs::create_dir_all(model_dir.join("am")).unwrap();
+    std::fs::write(model_dir.join("am").join("final.mdl"), vec![0u8; 300]).unwrap();
+    std::fs::write(model_dir.join("README"), vec![0u8; 20]).unwrap();
+
+    let found = config::downloaded_vosk_models(dir.path());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0.id, "vosk-small-en");
+    assert_eq!(found[0].1, 320);
+}
+
+#[test]
+fn locate_model_prefers_models_dir_then_local_copy() {
+    use config::ModelLocation;
+    let external = tempfile::tempdir().unwrap();
+    let local = tempfile::tempdir().unwrap();
+    let (ext, loc) = (external.path(), local.path());
+
+    assert_eq!(
+        config::locate_model(ext, loc, "local-base", None),
+        Some(ModelLocation::Missing(ext.join("ggml-base.en.bin")))
+    );
+
+    std::fs::write(loc.join("ggml-base.en.bin"), b"local").unwrap();
+    assert_eq!(
+        config::locate_model(ext, loc, "local-base", None),
+        Some(ModelLocation::Found(loc.join("ggml-base.en.bin")))
+    );
+
+    std::fs::write(ext.join("ggml-base.en.bin"), b"external").unwrap();
+    assert_eq!(
+        config::locate_model(ext, loc, "local-base", None),
+        Some(ModelLocation::Found(ext.join("ggml-base.en.bin")))
+    );
+    assert_eq!(config::locate_model(ext, loc, "groq", None), None);
+}
+
+#[test]
+fn locate_model_reports_unmounted_drive() {
+    use config::ModelLocation;
+    let local = tempfile::tempdir().unwrap();
+    let unmounted = local.path().join("media").join("usb").join("models");
+    assert!(!config::models_dir_available(&unmounted));
+
+    assert_eq!(
+        config::locate_model(&unmounted, local.path(), "vosk-small-en", None),
+        Some(ModelLocation::Unavailable {
+            dir: unmounted.clone(),
+            local_path: Some(
+                local
+                    .path()
+                    .join("vosk")
+                    .join("vosk-model-small-en-us-0.15")
+            ),
+        })
+    );
+
+    let custom = unmounted.join("ft.gguf");
+    assert_eq!(
+        config::locate_model(
+            local.path(),
+            local.path(),
+            config::CUSTOM_LOCAL_MODEL,
+            Some(&custom)
+        ),
+        Some(ModelLocation::Unavailable {
+            dir: unmounted,
+            local_path: None,
+        })
+    );
+}
+
+#[test]
+fn normalize_language_accepts_codes_and_names() {
+    assert_eq!(config::normalize_language("en").as_deref(), Some("en"));
+    assert_eq!(config::normalize_language(" DE ").as_deref(), Some("de"));
+    assert_eq!(config::normalize_language("english").as_deref(), Some("en"));
+    assert_eq!(
+        config::normalize_language("Japanese").as_deref(),
+        Some("ja")
+    );
+    // Codes whisper knows but the menu doesn't offer are kept
+    assert_eq!(config::normalize_language("sw").as_deref(), Some("sw"));
+    assert_eq!(config::normalize_language("auto"), None);
+    assert_eq!(config::normalize_language(""), None);
+    assert_eq!(config::normalize_language("klingon"), None);
+    assert_eq!(config::language_label("de"), "German");
+    assert_eq!(config::language_label("sw"), "sw");
+}
@@ -0,0 +1,67 @@
+use crate::corrections;
+
+#[test]
+fn suggestions_single_word_fix() {
+    assert_eq!(
+        corrections::suggestions("I saw teh cat", "I saw the cat"),
+        vec![("teh".to_string(), "the".to_string())]
+    );
+}
+
+#[test]
+fn suggestions_phrase_merge_and_punctuation() {
+    assert_eq!(
+        corrections::suggestions("try whisper crabs, it works", "try WhisperCrabs, it works"),
+        vec![("whisper crabs".to_string(), "WhisperCrabs".to_string())]
+    );
+}
+
+#[test]
+fn suggestions_multiple_hunks() {
+    let s = corrections::suggestions(
+        "call jon about the cubernetes demo",
+        "call John about the Kubernetes demo",
+    );
+    assert_eq!(
+        s,
+        vec![
+            ("jon".to_string(), "John".to_string()),
+            ("cubernetes".to_string(), "Kubernetes".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn suggestions_skip_insertions_deletions_and_rewrites() {
+    assert!(corrections::suggestions("hello world", "hello big world").is_empty());
+    assert!(corrections::suggestions("hello big world", "hello world").is_empty());
+    assert!(corrections::suggestions("same", "same").is_empty());
+    assert!(
+        corrections::suggestions("one two three four five six seven", "a b c d e f g").is_empty()
+    );
+}
+
+#[test]
+fn apply_matches_whole_words_only() {
+    let rules = vec![("teh".to_string(), "the".to_string())];
+    assert_eq!(
+        corrections::apply("teh tehran, teh.", &rules),
+        "the tehran, the."
+    );
+}
+
+#[test]
+fn apply_skips_matches_glued_to_a_skipped_match() {
+    let rules = vec![("teh".to_string(), "the".to_string())];
+    assert_eq!(corrections::apply("tehteh", &rules), "tehteh");
+    assert_eq!(corrections::apply("tehtehteh teh", &rules), "tehtehteh the");
+}
+
+#[test]
+fn apply_multiword_rule() {
+    let rules = vec![("whisper crabs".to_string(), "WhisperCrabs".to_string())];
+    assert_eq!(
+        corrections::apply("I use whisper crabs daily", &rules),
+        "I use WhisperCrabs daily"
+    );
+}
@@ -1,4 +1,4 @@
-use crate::db::Db;
+use crate::db::{Db, StatsPeriod, parse_tags};
 use std::path::PathBuf;
 
 fn temp_db() -> (Db, tempfile::TempDir) {
@@ -35,6 +35,38 @@ fn recent_respects_limit() {
     assert_eq!(recent.len(), 3);
 }
 
+#[test]
+fn pages_walk_back_through_history() {
+    let (db, _dir) = temp_db();
+    for i in 0..5 {
+        db.insert(&format!("entry {i}")).unwrap();
+    }
+    let texts = |offset| -> Vec<String> {
+        db.page(offset, 2)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.text)
+            .collect()
+    };
+    assert_eq!(texts(0), ["entry 4", "entry 3"]);
+    assert_eq!(texts(2), ["entry 2", "entry 1"]);
+    assert_eq!(texts(4), ["entry 0"]);
+    assert!(texts(6).is_empty());
+}
+
+#[test]
+fn filtered_pages_skip_other_languages() {
+    let (db, _dir) = temp_db();
+    for i in 0..4 {
+        let language = if i % 2 == 0 { "de" } else { "en" };
+        db.insert_with_language(&format!("entry {i}"), Some(language))
+            .unwrap();
+    }
+    let page = db.page_filtered(1, 10, Some("de"), None).unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].text, "entry 0");
+}
+
 #[test]
 fn settings_roundtrip() {
     let (db, _dir) = temp_db();
@@ -62,6 +94,67 @@ fn settings_rejects_unknown_key() {
     assert!(result.is_err());
 }
 
+#[test]
+fn import_settings_skips_unknown_keys() {
+    let (db, _dir) = temp_db();
+    let settings = vec![
+        ("stt_language".to_string(), "de".to_string()),
+        ("from_a_newer_version".to_string(), "on".to_string()),
+    ];
+    let summary = db.import_settings(&settings).unwrap();
+    assert_eq!((summary.added, summary.skipped), (1, 1));
+    assert_eq!(
+        db.all_settings().unwrap(),
+        vec![("stt_language".to_string(), "de".to_string())]
+    );
+}
+
+#[test]
+fn safe_mode_ignores_stored_settings_and_keeps_them() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.db");
+    {
+        let db = Db::open(&path).unwrap();
+        db.set_setting("auto_paste", "true").unwrap();
+        db.set_setting("api_key_groq", "gsk_test").unwrap();
+    }
+    let mut db = Db::open(&path).unwrap();
+    db.enter_safe_mode();
+    assert!(db.is_safe_mode());
+    assert_eq!(db.get_setting("auto_paste").unwrap(), None);
+    // API keys stay readable so the default provider works
+    assert_eq!(
+        db.get_setting("api_key_groq").unwrap().as_deref(),
+        Some("gsk_test")
+    );
+    db.set_setting("auto_paste", "false").unwrap();
+    assert_eq!(
+        db.get_setting("auto_paste").unwrap().as_deref(),
+        Some("false")
+    );
+    assert!(db.set_setting("not_a_setting", "x").is_err());
+    drop(db);
+
+    let db = Db::open(&path).unwrap();
+    assert_eq!(
+        db.get_setting("auto_paste").unwrap().as_deref(),
+        Some("true")
+    );
+}
+
+#[test]
+fn safe_mode_applies_no_replacements() {
+    let (mut db, _dir) = temp_db();
+    db.suggest_replacement("teh", "the", None).unwrap();
+    let id = db.replacements().unwrap()[0].id;
+    db.accept_replacement(id).unwrap();
+    assert_eq!(db.accepted_replacements(None).unwrap().len(), 1);
+    db.enter_safe_mode();
+    assert!(db.accepted_replacements(None).unwrap().is_empty());
+    // Still listed, so a broken rule can be found and deleted
+    assert_eq!(db.replacements().unwrap().len(), 1);
+}
+
 #[test]
 fn open_at_nonexistent_path_creates_file() {
     let dir = tempfile::tempdir().unwrap();
@@ -71,3 +164,898 @@ fn open_at_nonexistent_path_creates_file() {
     // This tests that we get a proper error rather than a panic
     assert!(result.is_err() || PathBuf::from(&path).exists());
 }
+
+#[test]
+fn usage_aggregates_by_provider() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("hello").unwrap();
+    db.insert_usage(
+        Some(id),
+        "groq",
+        "whisper-large-v3-turbo",
+        30.0,
+        400,
+        1,
+        None,
+    )
+    .unwrap();
+    db.insert_usage(None, "groq", "whisper-large-v3-turbo", 90.0, 600, 12, None)
+        .unwrap();
+    db.insert_usage(None, "local-base", "ggml-base.en.bin", 60.0, 2000, 8, None)
+        .unwrap();
+
+    let usage = db.usage_by_month().unwrap();
+    assert_eq!(usage.len(), 2);
+    let groq = usage.iter().find(|u| u.provider == "groq").unwrap();
+    assert_eq!(groq.transcriptions, 2);
+    assert!((groq.audio_secs - 120.0).abs() < f64::EPSILON);
+    assert!((groq.avg_latency_ms - 500.0).abs() < f64::EPSILON);
+    assert_eq!(groq.month.len(), 7); // YYYY-MM
+}
+
+#[test]
+fn stats_group_words_by_period_and_provider() {
+    let (db, _dir) = temp_db();
+    db.insert_usage(None, "groq", "whisper-large-v3-turbo", 30.0, 400, 5, None)
+        .unwrap();
+    db.insert_usage(None, "groq", "whisper-large-v3-turbo", 90.0, 600, 15, None)
+        .unwrap();
+    db.insert_usage(None, "local-base", "ggml-base.en.bin", 60.0, 2000, 8, None)
+        .unwrap();
+
+    for period in StatsPeriod::ALL {
+        let stats = db.stats(period).unwrap();
+        assert_eq!(stats.len(), 2);
+        let groq = stats.iter().find(|s| s.provider == "groq").unwrap();
+        assert_eq!(groq.sessions, 2);
+        assert_eq!(groq.words, 20);
+        assert!((groq.audio_secs - 120.0).abs() < f64::EPSILON);
+        assert!((groq.avg_latency_ms - 500.0).abs() < f64::EPSILON);
+    }
+    assert_eq!(db.stats(StatsPeriod::Day).unwrap()[0].period.len(), 10); // YYYY-MM-DD
+    assert!(
+        db.stats(StatsPeriod::Week).unwrap()[0]
+            .period
+            .contains("-W")
+    );
+}
+
+#[test]
+fn speech_samples_are_newest_first() {
+    let (db, _dir) = temp_db();
+    db.insert_usage(
+        None,
+        "groq",
+        "whisper-large-v3-turbo",
+        30.0,
+        400,
+        60,
+        Some(-25.0),
+    )
+    .unwrap();
+    db.insert_usage(None, "groq", "whisper-large-v3-turbo", 10.0, 400, 40, None)
+        .unwrap();
+
+    let samples = db.speech_samples(10).unwrap();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[0].words, 40);
+    assert_eq!(samples[0].level_db, None);
+    assert_eq!(samples[1].level_db, Some(-25.0));
+    assert_eq!(samples[1].words_per_minute(), Some(120.0));
+    assert_eq!(db.speech_samples(1).unwrap().len(), 1);
+
+    let stats = db.stats(StatsPeriod::Month).unwrap();
+    assert!((stats[0].speech_secs - 40.0).abs() < f64::EPSILON);
+    assert_eq!(stats[0].words_per_minute(), Some(150.0));
+}
+
+#[test]
+fn usage_empty_by_default() {
+    let (db, _dir) = temp_db();
+    assert!(db.usage_by_month().unwrap().is_empty());
+}
+
+#[test]
+fn update_text_edits_transcription() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("teh original").unwrap();
+    db.update_text(id, "the original").unwrap();
+    let recent = db.recent(1).unwrap();
+    assert_eq!(recent[0].id, id);
+    assert_eq!(recent[0].text, "the original");
+}
+
+#[test]
+fn replacement_suggestions_accept_and_delete() {
+    let (db, _dir) = temp_db();
+    db.suggest_replacement("whisper crabs", "WhisperCrabs", None)
+        .unwrap();
+    db.suggest_replacement("teh", "the", None).unwrap();
+    assert!(db.accepted_replacements(None).unwrap().is_empty());
+
+    let all = db.replacements().unwrap();
+    assert_eq!(all.len(), 2);
+    let teh = all.iter().find(|r| r.original == "teh").unwrap();
+    db.accept_replacement(teh.id).unwrap();
+    assert_eq!(
+        db.accepted_replacements(None).unwrap(),
+        vec![("teh".to_string(), "the".to_string())]
+    );
+
+    // Re-suggesting an accepted phrase doesn't overwrite it
+    db.suggest_replacement("teh", "tea", None).unwrap();
+    assert_eq!(db.accepted_replacements(None).unwrap()[0].1, "the");
+
+    db.delete_replacement(teh.id).unwrap();
+    assert!(db.accepted_replacements(None).unwrap().is_empty());
+    assert_eq!(db.replacements().unwrap().len(), 1);
+}
+
+#[test]
+fn words_roundtrip_in_order() {
+    use crate::local_stt::Word;
+    let (db, _dir) = temp_db();
+    let id = db.insert("hello world").unwrap();
+    assert!(db.words(id).unwrap().is_empty());
+
+    let words = vec![
+        Word {
+            text: "hello".into(),
+            start_ms: 0,
+            end_ms: 400,
+        },
+        Word {
+            text: "world".into(),
+            start_ms: 450,
+            end_ms: 900,
+        },
+    ];
+    db.insert_words(id, &words).unwrap();
+    assert_eq!(db.words(id).unwrap(), words);
+
+    let other = db.insert("other").unwrap();
+    assert!(db.words(other).unwrap().is_empty());
+}
+
+#[test]
+fn segments_roundtrip() {
+    use crate::local_stt::Segment;
+    let (db, _dir) = temp_db();
+    let id = db.insert("Hello there. General Kenobi.").unwrap();
+    let segments = vec![
+        Segment {
+            start_ms: 0,
+            end_ms: 1200,
+            text: "Hello there.".into(),
+            confidence: Some(0.5),
+        },
+        Segment {
+            start_ms: 1200,
+            end_ms: 2500,
+            text: "General Kenobi.".into(),
+            confidence: None,
+        },
+    ];
+    db.insert_segments(id, &segments).unwrap();
+    assert_eq!(db.segments(id).unwrap(), segments);
+    // The flat text is still there for copying the whole entry
+    assert_eq!(
+        db.recent(1).unwrap()[0].text,
+        "Hello there. General Kenobi."
+    );
+
+    let other = db.insert("other").unwrap();
+    assert!(db.segments(other).unwrap().is_empty());
+}
+
+#[test]
+fn history_mode_parse_roundtrip() {
+    use crate::db::HistoryMode;
+    for mode in [HistoryMode::Full, HistoryMode::Preview, HistoryMode::Off] {
+        assert_eq!(HistoryMode::parse(mode.as_str()), Some(mode));
+    }
+    assert_eq!(HistoryMode::parse("nothing"), None);
+    assert_eq!(HistoryMode::default(), HistoryMode::Full);
+}
+
+#[test]
+fn history_mode_stored_text() {
+    use crate::db::{HISTORY_PREVIEW_CHARS, HistoryMode};
+    let long = "word ".repeat(20);
+    assert_eq!(
+        HistoryMode::Full.stored_text(&long).as_deref(),
+        Some(long.as_str())
+    );
+    let preview = HistoryMode::Preview.stored_text(&long).unwrap();
+    assert!(preview.ends_with('\u{2026}'));
+    assert!(preview.chars().count() <= HISTORY_PREVIEW_CHARS + 1);
+    assert_eq!(
+        HistoryMode::Preview.stored_text("short").as_deref(),
+        Some("short")
+    );
+    assert_eq!(HistoryMode::Off.stored_text(&long), None);
+}
+
+#[test]
+fn history_filters_by_language() {
+    let (db, _dir) = temp_db();
+    db.insert_with_language("hello", Some("en")).unwrap();
+    db.insert_with_language("hallo", Some("de")).unwrap();
+    db.insert_with_language("guten tag", Some("de")).unwrap();
+    db.insert("untagged").unwrap();
+
+    assert_eq!(db.languages().unwrap(), vec!["de", "en"]);
+    let german = db.recent_in_language(10, Some("de")).unwrap();
+    assert_eq!(german.len(), 2);
+    assert!(german.iter().all(|t| t.language.as_deref() == Some("de")));
+    assert_eq!(db.recent(10).unwrap().len(), 4);
+    assert_eq!(db.recent(1).unwrap()[0].language, None);
+}
+
+#[test]
+fn replacements_scoped_to_language() {
+    let (db, _dir) = temp_db();
+    db.suggest_replacement("grüß", "Grüß", Some("de")).unwrap();
+    db.suggest_replacement("teh", "the", None).unwrap();
+    for r in db.replacements().unwrap() {
+        db.accept_replacement(r.id).unwrap();
+    }
+
+    assert_eq!(db.accepted_replacements(Some("en")).unwrap().len(), 1);
+    assert_eq!(db.accepted_replacements(Some("de")).unwrap().len(), 2);
+    // Unknown language: every rule applies
+    assert_eq!(db.accepted_replacements(None).unwrap().len(), 2);
+}
+
+#[test]
+fn open_adds_language_column_to_old_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.db");
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transcriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now','localtime'))
+            );
+            INSERT INTO transcriptions (text) VALUES ('from v0.1');",
+        )
+        .unwrap();
+    }
+    let db = Db::open(&path).unwrap();
+    assert_eq!(db.recent(1).unwrap()[0].language, None);
+    db.insert_with_language("bonjour", Some("fr")).unwrap();
+    // Reopening doesn't try to add the column again
+    drop(db);
+    let db = Db::open(&path).unwrap();
+    assert_eq!(db.languages().unwrap(), vec!["fr"]);
+}
+
+#[test]
+fn entries_between_filters_by_date() {
+    let (db, _dir) = temp_db();
+    db.insert("first").unwrap();
+    db.insert_with_language("second", Some("de")).unwrap();
+
+    let all = db.entries_between(None, None).unwrap();
+    let texts: Vec<&str> = all.iter().map(|e| e.text.as_str()).collect();
+    assert_eq!(texts, vec!["first", "second"]);
+    assert_eq!(all[1].language.as_deref(), Some("de"));
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    assert_eq!(
+        db.entries_between(Some(&today), Some(&today))
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(
+        db.entries_between(None, Some("2000-12-31"))
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        db.entries_between(Some("2999-01-01"), None)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn delete_removes_entry_and_its_timings() {
+    use crate::local_stt::Word;
+    let (db, _dir) = temp_db();
+    let keep = db.insert("keep me").unwrap();
+    let gone = db.insert("secret").unwrap();
+    db.insert_words(
+        gone,
+        &[Word {
+            text: "secret".into(),
+            start_ms: 0,
+            end_ms: 500,
+        }],
+    )
+    .unwrap();
+    db.insert_usage(
+        Some(gone),
+        "groq",
+        "whisper-large-v3-turbo",
+        30.0,
+        400,
+        2,
+        None,
+    )
+    .unwrap();
+
+    db.delete(gone).unwrap();
+    let texts: Vec<String> = db.recent(10).unwrap().into_iter().map(|e| e.text).collect();
+    assert_eq!(texts, vec!["keep me"]);
+    assert!(db.words(gone).unwrap().is_empty());
+    // Usage statistics survive the deletion
+    assert_eq!(db.usage_by_month().unwrap()[0].transcriptions, 1);
+    assert_eq!(db.recent(10).unwrap()[0].id, keep);
+}
+
+#[test]
+fn delete_many_and_clear() {
+    let (db, _dir) = temp_db();
+    let a = db.insert("a").unwrap();
+    let b = db.insert("b").unwrap();
+    db.insert("c").unwrap();
+    db.delete_many(&[a, b]).unwrap();
+    assert_eq!(db.recent(10).unwrap().len(), 1);
+
+    db.set_setting("history_mode", "full").unwrap();
+    db.clear().unwrap();
+    assert!(db.recent(10).unwrap().is_empty());
+    // Settings are untouched
+    assert_eq!(
+        db.get_setting("history_mode").unwrap().as_deref(),
+        Some("full")
+    );
+}
+
+#[test]
+fn open_in_memory_works_like_a_file() {
+    let db = Db::open_in_memory().unwrap();
+    db.insert("session only").unwrap();
+    assert_eq!(db.recent(1).unwrap()[0].text, "session only");
+}
+
+#[test]
+fn corrupt_file_fails_to_open_and_can_be_backed_up() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.db");
+    std::fs::write(&path, b"definitely not an sqlite database, just some bytes").unwrap();
+    std::fs::write(dir.path().join("history.db-journal"), b"junk").unwrap();
+
+    let err = Db::open(&path).err().expect("corrupt file should not open");
+    assert!(!crate::db::is_locked_error(&err));
+
+    let backup = crate::db::back_up_broken(&path).unwrap();
+    assert!(backup.exists());
+    assert!(!path.exists());
+    assert!(!dir.path().join("history.db-journal").exists());
+    let db = Db::open(&path).unwrap();
+    assert!(db.recent(1).unwrap().is_empty());
+}
+
+#[test]
+fn recordings_are_listed_newest_first() {
+    let (db, _dir) = temp_db();
+    let first = db.insert("one").unwrap();
+    db.insert("no recording").unwrap();
+    let third = db.insert("three").unwrap();
+    db.set_audio_path(first, std::path::Path::new("/tmp/audio/1.wav"))
+        .unwrap();
+    db.set_audio_path(third, std::path::Path::new("/tmp/audio/3.wav"))
+        .unwrap();
+    assert_eq!(
+        db.recordings().unwrap(),
+        vec![
+            (third, "/tmp/audio/3.wav".to_string()),
+            (first, "/tmp/audio/1.wav".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn audio_path_and_replace_transcript() {
+    use crate::local_stt::{Segment, Word};
+    let (db, _dir) = temp_db();
+    let id = db.insert("helo wrld").unwrap();
+    assert_eq!(db.recent(1).unwrap()[0].audio_path, None);
+    db.set_audio_path(id, std::path::Path::new("/tmp/audio/1.wav"))
+        .unwrap();
+    assert_eq!(
+        db.recent(1).unwrap()[0].audio_path.as_deref(),
+        Some("/tmp/audio/1.wav")
+    );
+
+    let old_words = vec![
+        Word {
+            text: "helo".into(),
+            start_ms: 0,
+            end_ms: 300,
+        },
+        Word {
+            text: "wrld".into(),
+            start_ms: 300,
+            end_ms: 600,
+        },
+    ];
+    db.insert_words(id, &old_words).unwrap();
+    let words = vec![Word {
+        text: "hello".into(),
+        start_ms: 0,
+        end_ms: 600,
+    }];
+    let segments = vec![Segment {
+        start_ms: 0,
+        end_ms: 600,
+        text: "hello".into(),
+        confidence: Some(0.9),
+    }];
+    db.replace_transcript(id, "hello", Some("en"), &words, &segments)
+        .unwrap();
+    let entry = &db.recent(1).unwrap()[0];
+    assert_eq!(entry.text, "hello");
+    assert_eq!(entry.language.as_deref(), Some("en"));
+    // Timings of the old transcript are gone, not mixed in
+    assert_eq!(db.words(id).unwrap(), words);
+    assert_eq!(db.segments(id).unwrap(), segments);
+}
+
+#[test]
+fn revisions_link_to_their_original() {
+    use crate::local_stt::{Segment, Transcript};
+    let (db, _dir) = temp_db();
+    let original = db.insert("helo wrld").unwrap();
+    db.set_audio_path(original, std::path::Path::new("/tmp/audio/1.wav"))
+        .unwrap();
+    let transcript = Transcript {
+        text: "hello world".into(),
+        language: Some("en".into()),
+        segments: vec![Segment {
+            start_ms: 0,
+            end_ms: 900,
+            text: "hello world".into(),
+            confidence: None,
+        }],
+        ..Default::default()
+    };
+    let revision = db.insert_revision(original, "groq", &transcript).unwrap();
+
+    // Listed under the original, not as an entry of its own
+    let recent = db.recent(10).unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].text, "helo wrld");
+    let revisions = db.revisions(original).unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].id, revision);
+    assert_eq!(revisions[0].text, "hello world");
+    assert_eq!(revisions[0].provider.as_deref(), Some("groq"));
+    assert_eq!(db.segments(revision).unwrap(), transcript.segments);
+
+    // Deleting the original takes its revisions along
+    db.delete(original).unwrap();
+    assert!(db.revisions(original).unwrap().is_empty());
+    assert!(db.segments(revision).unwrap().is_empty());
+    assert!(db.entries_between(None, None).unwrap().is_empty());
+}
+
+#[test]
+fn revision_of_missing_entry_fails() {
+    let (db, _dir) = temp_db();
+    let transcript = crate::local_stt::Transcript::default();
+    assert!(db.insert_revision(42, "groq", &transcript).is_err());
+}
+
+#[test]
+fn parse_tags_normalizes_input() {
+    assert_eq!(parse_tags("Work, #ideas  work"), vec!["ideas", "work"]);
+    assert!(parse_tags(" , # ").is_empty());
+}
+
+#[test]
+fn tags_filter_history() {
+    let (db, _dir) = temp_db();
+    let a = db.insert("standup notes").unwrap();
+    let b = db.insert("dear diary").unwrap();
+    db.insert("untagged").unwrap();
+    db.set_tags(a, &parse_tags("work ideas")).unwrap();
+    db.set_tags(b, &parse_tags("journal")).unwrap();
+    db.set_tags(b, &parse_tags("journal ideas")).unwrap();
+
+    assert_eq!(db.tags(b).unwrap(), vec!["ideas", "journal"]);
+    assert_eq!(db.all_tags().unwrap(), vec!["ideas", "journal", "work"]);
+    let texts = |tag| -> Vec<String> {
+        db.recent_filtered(10, None, Some(tag))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.text)
+            .collect()
+    };
+    assert_eq!(texts("ideas"), vec!["dear diary", "standup notes"]);
+    assert_eq!(texts("work"), vec!["standup notes"]);
+    assert!(texts("nothing").is_empty());
+    assert_eq!(db.recent_filtered(10, None, None).unwrap().len(), 3);
+
+    db.delete(a).unwrap();
+    assert_eq!(db.all_tags().unwrap(), vec!["ideas", "journal"]);
+    db.clear().unwrap();
+    assert!(db.all_tags().unwrap().is_empty());
+}
+
+#[test]
+fn notes_are_set_and_cleared() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("call the plumber").unwrap();
+    assert_eq!(db.note(id).unwrap(), None);
+    db.set_note(id, Some("  before Friday ")).unwrap();
+    assert_eq!(db.note(id).unwrap().as_deref(), Some("before Friday"));
+    db.set_note(id, Some("")).unwrap();
+    assert_eq!(db.note(id).unwrap(), None);
+    assert_eq!(db.note(999).unwrap(), None);
+}
+
+#[test]
+fn pinned_entries() {
+    let (db, _dir) = temp_db();
+    let address = db.insert("221B Baker Street").unwrap();
+    let reply = db.insert("Thanks, I'll get back to you").unwrap();
+    db.insert("one-off").unwrap();
+    assert!(db.pinned().unwrap().is_empty());
+
+    db.set_pinned(address, true).unwrap();
+    db.set_pinned(reply, true).unwrap();
+    let pinned: Vec<i64> = db.pinned().unwrap().into_iter().map(|e| e.id).collect();
+    assert_eq!(pinned, vec![reply, address]);
+    assert!(db.is_pinned(address).unwrap());
+
+    db.set_pinned(address, false).unwrap();
+    assert!(!db.is_pinned(address).unwrap());
+    assert!(!db.is_pinned(999).unwrap());
+    db.delete(reply).unwrap();
+    assert!(db.pinned().unwrap().is_empty());
+}
+
+#[test]
+fn plain_databases_are_not_encrypted() {
+    let (db, dir) = temp_db();
+    db.insert("hello").unwrap();
+    let path = dir.path().join("test.db");
+    assert!(!crate::db::is_encrypted(&path));
+    assert!(!db.is_encrypted());
+    assert!(!crate::db::is_encrypted(&dir.path().join("missing.db")));
+
+    let scrambled = dir.path().join("scrambled.db");
+    std::fs::write(&scrambled, [0x5a; 64]).unwrap();
+    assert!(crate::db::is_encrypted(&scrambled));
+}
+
+#[test]
+fn damaged_headers_are_not_taken_for_encryption() {
+    let (_db, dir) = temp_db();
+    let scrambled = dir.path().join("scrambled.db");
+    std::fs::write(&scrambled, [0x5a; 64]).unwrap();
+    // Without a key or marker it's damage, the recovery dialog's case
+    assert!(!crate::db::opens_encrypted(&scrambled, false));
+    std::fs::write(crate::db::encryption_marker(&scrambled), b"").unwrap();
+    assert_eq!(
+        crate::db::opens_encrypted(&scrambled, false),
+        crate::db::encryption_available()
+    );
+    assert_eq!(
+        crate::db::opens_encrypted(&scrambled, true),
+        crate::db::encryption_available()
+    );
+    assert!(!crate::db::opens_encrypted(
+        &dir.path().join("test.db"),
+        true
+    ));
+}
+
+#[test]
+fn passphrases_are_checked() {
+    assert!(crate::db::validate_passphrase("short", "short").is_err());
+    assert!(crate::db::validate_passphrase("long enough", "long enougH").is_err());
+    assert!(crate::db::validate_passphrase("long enough", "long enough").is_ok());
+}
+
+#[test]
+fn generated_keys_are_random_hex() {
+    let a = crate::db::generate_key().unwrap();
+    let b = crate::db::generate_key().unwrap();
+    assert_eq!(a.len(), 64);
+    assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(a, b);
+}
+
+#[test]
+fn in_memory_history_cant_be_encrypted() {
+    let mut db = Db::open_in_memory().unwrap();
+    assert!(db.set_encryption(Some("passphrase")).is_err());
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn encryption_round_trip() {
+    let (mut db, dir) = temp_db();
+    let path = dir.path().join("test.db");
+    db.insert("confidential").unwrap();
+
+    db.set_encryption(Some("correct horse")).unwrap();
+    assert!(db.is_encrypted());
+    assert!(crate::db::opens_encrypted(&path, false));
+    assert_eq!(db.recent(1).unwrap()[0].text, "confidential");
+    drop(db);
+
+    let wrong = Db::open_encrypted(&path, "wrong horse").err().unwrap();
+    assert!(crate::db::is_wrong_key_error(&wrong));
+    let mut db = Db::open_encrypted(&path, "correct horse").unwrap();
+    assert_eq!(db.recent(1).unwrap()[0].text, "confidential");
+
+    db.set_encryption(None).unwrap();
+    assert!(!crate::db::is_encrypted(&path));
+    assert!(!crate::db::encryption_marker(&path).exists());
+    drop(db);
+    assert_eq!(
+        Db::open(&path).unwrap().recent(1).unwrap()[0].text,
+        "confidential"
+    );
+}
+
+#[test]
+fn legacy_local_mode_is_migrated() {
+    let (db, dir) = temp_db();
+    db.set_setting("transcription_mode", "local").unwrap();
+    // Pretend the database predates the migration table
+    db.delete_setting("settings_version").unwrap();
+    drop(db);
+
+    let db = Db::open(&dir.path().join("test.db")).unwrap();
+    assert_eq!(
+        db.get_setting("transcription_mode").unwrap().as_deref(),
+        Some(crate::config::DEFAULT_LOCAL_MODEL)
+    );
+    assert_eq!(
+        db.get_setting("settings_version").unwrap(),
+        Some(crate::db::SETTING_MIGRATIONS.len().to_string())
+    );
+}
+
+#[test]
+fn setting_migrations_run_once_in_order() {
+    use crate::db::SettingMigration;
+
+    let (db, _dir) = temp_db();
+    db.delete_setting("settings_version").unwrap();
+    db.set_setting("api_custom_url", "http://old").unwrap();
+    db.set_setting("api_custom_model", "old-model").unwrap();
+    db.set_setting("tts_voice", "kept").unwrap();
+    let migrations = [
+        SettingMigration {
+            from_key: "api_custom_url",
+            to_key: "api_custom_path",
+            value: None,
+        },
+        SettingMigration {
+            from_key: "api_custom_model",
+            to_key: "tts_voice",
+            value: Some(("old-model", "new-model")),
+        },
+    ];
+    db.migrate_settings(&migrations).unwrap();
+    assert_eq!(db.get_setting("api_custom_url").unwrap(), None);
+    assert_eq!(
+        db.get_setting("api_custom_path").unwrap().as_deref(),
+        Some("http://old")
+    );
+    // An existing value under the new key is kept
+    assert_eq!(
+        db.get_setting("tts_voice").unwrap().as_deref(),
+        Some("kept")
+    );
+    assert_eq!(db.get_setting("api_custom_model").unwrap(), None);
+
+    // Already applied: a second run changes nothing
+    db.set_setting("api_custom_url", "http://again").unwrap();
+    db.migrate_settings(&migrations).unwrap();
+    assert_eq!(
+        db.get_setting("api_custom_url").unwrap().as_deref(),
+        Some("http://again")
+    );
+}
+
+fn user_version(path: &std::path::Path) -> usize {
+    rusqlite::Connection::open(path)
+        .unwrap()
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap()
+}
+
+#[test]
+fn new_database_is_at_the_latest_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("new.db");
+    drop(Db::open(&path).unwrap());
+    assert_eq!(user_version(&path), crate::db::SCHEMA_MIGRATIONS.len());
+}
+
+#[test]
+fn unversioned_database_is_adopted_without_losing_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.db");
+    {
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transcriptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now','localtime')),
+                language TEXT,
+                audio_path TEXT
+            );
+            INSERT INTO transcriptions (text, language) VALUES ('kept', 'en');",
+        )
+        .unwrap();
+    }
+    let db = Db::open(&path).unwrap();
+    let entries = db.recent(10).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].text, "kept");
+    assert_eq!(entries[0].language.as_deref(), Some("en"));
+    drop(db);
+    assert_eq!(user_version(&path), crate::db::SCHEMA_MIGRATIONS.len());
+}
+
+#[test]
+fn schema_migrations_run_once_in_order() {
+    use crate::db::{SchemaMigration, migrate_schema};
+
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    let migrations = [
+        SchemaMigration {
+            description: "log table",
+            apply: |c| c.execute_batch("CREATE TABLE log (step INTEGER)"),
+        },
+        SchemaMigration {
+            description: "second step",
+            apply: |c| c.execute_batch("INSERT INTO log VALUES (2)"),
+        },
+    ];
+    migrate_schema(&conn, &migrations[..1]).unwrap();
+    migrate_schema(&conn, &migrations).unwrap();
+    // Already at the latest version: nothing runs again
+    migrate_schema(&conn, &migrations).unwrap();
+    let rows: i64 = conn
+        .query_row("SELECT COUNT(*) FROM log", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(rows, 1);
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn failed_schema_migration_keeps_the_last_complete_version() {
+    use crate::db::{SchemaMigration, migrate_schema};
+
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    let migrations = [
+        SchemaMigration {
+            description: "table",
+            apply: |c| c.execute_batch("CREATE TABLE t (a INTEGER)"),
+        },
+        SchemaMigration {
+            description: "half done, then broken",
+            apply: |c| c.execute_batch("ALTER TABLE t ADD COLUMN b INTEGER; NOT SQL"),
+        },
+    ];
+    assert!(migrate_schema(&conn, &migrations).is_err());
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(version, 1);
+    // The half-applied step was rolled back
+    let has_b = conn
+        .prepare("SELECT 1 FROM pragma_table_info('t') WHERE name = 'b'")
+        .unwrap()
+        .exists([])
+        .unwrap();
+    assert!(!has_b);
+}
+
+#[test]
+fn newer_schema_refuses_to_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("future.db");
+    drop(Db::open(&path).unwrap());
+    let future = crate::db::SCHEMA_MIGRATIONS.len() + 1;
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .pragma_update(None, "user_version", future)
+        .unwrap();
+
+    let err = Db::open(&path).err().expect("a newer schema must not open");
+    assert!(crate::db::is_newer_schema_error(&err), "{err}");
+    assert!(!crate::db::is_locked_error(&err));
+    // Nothing was changed
+    assert_eq!(user_version(&path), future);
+}
+
+#[test]
+fn import_skips_entries_already_in_history() {
+    use crate::sync::SyncEntry;
+
+    let (db, _dir) = temp_db();
+    let id = db.insert("already here").unwrap();
+    let created_at = db.created_at(id).unwrap().unwrap();
+    let entries = vec![
+        SyncEntry {
+            created_at: created_at.clone(),
+            language: None,
+            text: "already here".into(),
+        },
+        SyncEntry {
+            created_at: "2026-03-09 14:05:07".into(),
+            language: Some("de".into()),
+            text: "guten Morgen".into(),
+        },
+        // Same text at another time is another dictation
+        SyncEntry {
+            created_at: "2026-03-10 09:00:00".into(),
+            language: None,
+            text: "already here".into(),
+        },
+    ];
+
+    let summary = db.import(&entries).unwrap();
+    assert_eq!((summary.added, summary.skipped), (2, 1));
+    let imported = db
+        .entries_between(Some("2026-03-09"), Some("2026-03-09"))
+        .unwrap();
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].created_at, "2026-03-09 14:05:07");
+    assert_eq!(imported[0].language.as_deref(), Some("de"));
+
+    // A second import adds nothing
+    let again = db.import(&entries).unwrap();
+    assert_eq!((again.added, again.skipped), (0, 3));
+    assert_eq!(db.created_at(9999).unwrap(), None);
+}
+
+#[test]
+fn raw_text_is_kept_beside_the_rewrite() {
+    let (db, _dir) = temp_db();
+    let id = db.insert("Dear Sam, see you at noon.").unwrap();
+    assert_eq!(db.raw_text(id).unwrap(), None);
+    db.set_raw_text(id, "see you at noon").unwrap();
+    assert_eq!(db.raw_text(id).unwrap().as_deref(), Some("see you at noon"));
+    assert_eq!(db.raw_text(999).unwrap(), None);
+}
+
+#[test]
+fn confidence_is_stored_with_the_entry() {
+    let db = Db::open_in_memory().unwrap();
+    let id = db.insert("maybe this").unwrap();
+    assert_eq!(db.confidence(id).unwrap(), None);
+    db.set_confidence(id, 0.42).unwrap();
+    assert_eq!(db.confidence(id).unwrap(), Some(0.42));
+    assert_eq!(db.confidence(id + 1).unwrap(), None);
+}
+
+#[test]
+fn long_settings_are_cut_at_a_character_boundary() {
+    use crate::db::MAX_SETTING_BYTES;
+    let db = Db::open_in_memory().unwrap();
+    // "é" is two bytes, so the limit falls inside one
+    let value = format!("a{}", "é".repeat(MAX_SETTING_BYTES));
+    db.set_setting("postprocess_prompt", &value).unwrap();
+    let stored = db.get_setting("postprocess_prompt").unwrap().unwrap();
+    assert_eq!(stored.len(), MAX_SETTING_BYTES - 1);
+    assert!(value.starts_with(&stored));
+}
@@ -0,0 +1,75 @@
+use crate::activation::Activation;
+use crate::dbus::{INTERFACE, INTROSPECTION, Request, parse_request};
+use gtk4::glib;
+use gtk4::prelude::*;
+
+fn no_args() -> glib::Variant {
+    ().to_variant()
+}
+
+#[test]
+fn recording_methods_are_activations() {
+    assert_eq!(
+        parse_request("StartRecording", &no_args()),
+        Some(Request::Activate(Activation::Start))
+    );
+    assert_eq!(
+        parse_request("StopAndTranscribe", &no_args()),
+        Some(Request::Activate(Activation::Stop))
+    );
+    assert_eq!(
+        parse_request("ToggleRecording", &no_args()),
+        Some(Request::Activate(Activation::Toggle))
+    );
+}
+
+#[test]
+fn queries_are_parsed() {
+    assert_eq!(
+        parse_request("GetLastTranscript", &no_args()),
+        Some(Request::LastTranscript)
+    );
+    assert_eq!(parse_request("GetState", &no_args()), Some(Request::State));
+}
+
+#[test]
+fn set_provider_takes_a_trimmed_id() {
+    assert_eq!(
+        parse_request("SetProvider", &(" groq ",).to_variant()),
+        Some(Request::SetProvider("groq".into()))
+    );
+}
+
+#[test]
+fn set_provider_without_a_string_is_rejected() {
+    assert_eq!(parse_request("SetProvider", &no_args()), None);
+    assert_eq!(parse_request("SetProvider", &(7u32,).to_variant()), None);
+}
+
+#[test]
+fn unknown_methods_are_rejected() {
+    assert_eq!(parse_request("Shutdown", &no_args()), None);
+}
+
+#[test]
+fn introspection_declares_every_method() {
+    assert_eq!(INTERFACE, "org.whispercrabs.Control");
+    assert!(INTROSPECTION.contains(&format!("<interface name=\"{INTERFACE}\">")));
+    for method in [
+        "StartRecording",
+        "StopAndTranscribe",
+        "ToggleRecording",
+        "GetLastTranscript",
+        "GetState",
+        "SetProvider",
+    ] {
+        assert!(
+            INTROSPECTION.contains(&format!("<method name=\"{method}\"")),
+            "{method}"
+        );
+        assert!(parse_request(method, &(String::new(),).to_variant()).is_some());
+    }
+    for signal in ["TranscriptionDone", "StateChanged"] {
+        assert!(INTROSPECTION.contains(&format!("<signal name=\"{signal}\">")));
+    }
+}
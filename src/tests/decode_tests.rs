@@ -0,0 +1,65 @@
+use crate::decode::{Decoded, SAMPLE_RATE, decode, is_audio_file, to_speech_rate};
+use std::io::Cursor;
+use std::path::Path;
+
+fn wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::new(&mut buf, spec).unwrap();
+    for &s in samples {
+        writer.write_sample((s * i16::MAX as f32) as i16).unwrap();
+    }
+    writer.finalize().unwrap();
+    buf.into_inner()
+}
+
+#[test]
+fn audio_files_are_known_by_extension() {
+    assert!(is_audio_file(Path::new("talk.wav")));
+    assert!(is_audio_file(Path::new("talk.MP3")));
+    assert!(is_audio_file(Path::new("dir/talk.ogg")));
+    assert!(is_audio_file(Path::new("memo.m4a")));
+    assert!(is_audio_file(Path::new("album.flac")));
+    assert!(!is_audio_file(Path::new("talk.txt")));
+    assert!(!is_audio_file(Path::new("talk")));
+}
+
+#[test]
+fn wav_files_keep_their_rate_until_resampled() {
+    let decoded = decode(wav(&[0.5; 800], 8000), Some("wav")).unwrap();
+    assert_eq!(decoded.sample_rate, 8000);
+    assert_eq!(decoded.samples.len(), 800);
+    assert!((decoded.samples[0] - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn speech_rate_audio_passes_through() {
+    let decoded = Decoded {
+        samples: vec![0.25; 160],
+        sample_rate: SAMPLE_RATE,
+    };
+    assert_eq!(to_speech_rate(decoded).unwrap(), vec![0.25; 160]);
+}
+
+#[test]
+fn other_rates_are_resampled_to_speech_rate() {
+    let decoded = Decoded {
+        samples: vec![0.0; 48000],
+        sample_rate: 48000,
+    };
+    let samples = to_speech_rate(decoded).unwrap();
+    let expected = SAMPLE_RATE as f64;
+    assert!((samples.len() as f64 - expected).abs() < expected * 0.05);
+}
+
+#[test]
+fn unknown_data_and_silence_are_errors() {
+    assert!(decode(b"not audio at all".to_vec(), Some("mp3")).is_err());
+    assert!(decode(b"not audio at all".to_vec(), None).is_err());
+    assert!(decode(wav(&[], 16000), Some("wav")).is_err());
+}
@@ -0,0 +1,90 @@
+use crate::db::Db;
+use crate::dictation_commands::{
+    ENABLED_SETTING, TABLE_SETTING, apply, parse_table, stored, table,
+};
+
+fn built_in(text: &str) -> String {
+    apply(text, &table(&[]))
+}
+
+#[test]
+fn punctuation_attaches_to_the_word_before() {
+    assert_eq!(
+        built_in("Dear Sam comma how are you question mark"),
+        "Dear Sam, how are you?"
+    );
+    // Marks the model wrote itself give way to the spoken one
+    assert_eq!(built_in("That's all, period."), "That's all.");
+}
+
+#[test]
+fn line_breaks_join_both_sides() {
+    assert_eq!(
+        built_in("Dear Sam, New line. Thanks for the notes. New paragraph. Bye"),
+        "Dear Sam,\nThanks for the notes.\n\nBye"
+    );
+}
+
+#[test]
+fn quotes_open_and_close() {
+    assert_eq!(
+        built_in("she said open quote hello close quote and left"),
+        "she said \u{201c}hello\u{201d} and left"
+    );
+}
+
+#[test]
+fn scratch_that_drops_the_last_segment() {
+    assert_eq!(
+        built_in("Call Anna. Tell her no. Scratch that. Tell her yes."),
+        "Call Anna. Tell her yes."
+    );
+    assert_eq!(
+        built_in("Buy milk new line eggs scratch that bread"),
+        "Buy milk\nbread"
+    );
+    assert_eq!(built_in("Hello new line scratch that world"), "Hello world");
+    assert_eq!(built_in("Scratch that. Start over"), "Start over");
+}
+
+#[test]
+fn phrases_match_whole_words() {
+    assert_eq!(built_in("commas and colons"), "commas and colons");
+    assert_eq!(built_in("a new liner"), "a new liner");
+}
+
+#[test]
+fn custom_commands_extend_and_override() {
+    let custom = parse_table(
+        r#"[{"phrase": "smiley", "text": ":-)"},
+            {"phrase": "Period", "text": ""},
+            {"phrase": "undo", "scratch": true}]"#,
+    )
+    .unwrap();
+    let commands = table(&custom);
+    assert_eq!(apply("see you smiley", &commands), "see you :-)");
+    assert_eq!(apply("Done. Not quite undo", &commands), "Done.");
+    assert_eq!(apply("the trial period", &commands), "the trial period");
+}
+
+#[test]
+fn invalid_tables_are_rejected() {
+    assert!(parse_table("not json").is_err());
+    assert!(parse_table(r#"[{"phrase": " ", "text": "x"}]"#).is_err());
+    assert!(
+        parse_table(r#"[{"phrase": "a b", "text": "x"}, {"phrase": "A  b", "text": "y"}]"#)
+            .is_err()
+    );
+    assert!(parse_table(r#"[{"phrase": "a", "txt": "x"}]"#).is_err());
+}
+
+#[test]
+fn off_unless_enabled() {
+    let db = Db::open_in_memory().unwrap();
+    assert!(stored(&db).is_none());
+    db.set_setting(ENABLED_SETTING, "true").unwrap();
+    assert_eq!(stored(&db).unwrap(), table(&[]));
+    // A broken table keeps the built-in commands working
+    db.set_setting(TABLE_SETTING, "[oops").unwrap();
+    assert_eq!(stored(&db).unwrap(), table(&[]));
+}
@@ -0,0 +1,106 @@
+use crate::dictation::{self, Edit, LiveDictation};
+use crate::local_stt::Word;
+
+fn words(spec: &[(&str, i64, i64)]) -> Vec<Word> {
+    spec.iter()
+        .map(|&(text, start_ms, end_ms)| Word {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+        })
+        .collect()
+}
+
+#[test]
+fn edit_between_keeps_common_prefix() {
+    assert_eq!(
+        dictation::edit_between("hello wor", "hello world"),
+        Edit {
+            backspaces: 0,
+            text: "ld".into()
+        }
+    );
+    assert_eq!(
+        dictation::edit_between("hello word", "hello world"),
+        Edit {
+            backspaces: 1,
+            text: "ld".into()
+        }
+    );
+    assert!(dictation::edit_between("same", "same").is_empty());
+    // Counts characters, not bytes
+    assert_eq!(dictation::edit_between("café", "cafe").backspaces, 1);
+}
+
+#[test]
+fn tentative_tail_is_typed_then_corrected() {
+    let mut live = LiveDictation::default();
+    let edit = live.update(&words(&[("Hello", 0, 400), ("word", 400, 900)]), 1500);
+    assert_eq!(edit.text, "Hello word");
+    assert_eq!(live.window_start_ms(), 0);
+
+    // Next decode agrees on "Hello" and revises the tail
+    let edit = live.update(
+        &words(&[
+            ("Hello,", 0, 400),
+            ("world", 400, 900),
+            ("again", 900, 1400),
+        ]),
+        3000,
+    );
+    assert_eq!(
+        edit,
+        Edit {
+            backspaces: 5,
+            text: ", world again".into()
+        }
+    );
+    // "Hello," is committed and the window moves past it
+    assert_eq!(live.window_start_ms(), 400);
+    assert_eq!(live.words()[0].text, "Hello,");
+}
+
+#[test]
+fn committed_words_get_recording_times() {
+    let mut live = LiveDictation::default();
+    live.update(&words(&[("one", 0, 300), ("two", 300, 600)]), 1000);
+    live.update(&words(&[("one", 0, 300), ("two", 300, 600)]), 1500);
+    assert_eq!(live.window_start_ms(), 600);
+
+    // Times of the next window are relative to its start
+    live.finish(&words(&[("three", 100, 500)]));
+    let committed = live.words();
+    assert_eq!(committed.len(), 3);
+    assert_eq!((committed[2].start_ms, committed[2].end_ms), (700, 1100));
+    assert_eq!(live.text(), "one two three");
+}
+
+#[test]
+fn long_window_commits_without_agreement() {
+    let mut live = LiveDictation::default();
+    live.update(&words(&[("a", 0, 100)]), 1000);
+    live.update(
+        &words(&[("b", 0, 100), ("c", 100, 200), ("d", 200, 300)]),
+        dictation::MAX_WINDOW_MS,
+    );
+    // Everything but the last word is committed
+    assert_eq!(live.words().len(), 2);
+    assert_eq!(live.window_start_ms(), 200);
+    assert_eq!(live.text(), "b c d");
+}
+
+#[test]
+fn finish_with_nothing_new_erases_stale_tail() {
+    let mut live = LiveDictation::default();
+    live.update(&words(&[("uh", 0, 200)]), 800);
+    let edit = live.finish(&[]);
+    assert_eq!(edit.backspaces, 2);
+    assert_eq!(live.text(), "");
+}
+
+#[test]
+fn dictionary_rules_apply_to_typed_text() {
+    let mut live = LiveDictation::new(vec![("whisper crabs".into(), "WhisperCrabs".into())]);
+    let edit = live.finish(&words(&[("whisper", 0, 300), ("crabs", 300, 600)]));
+    assert_eq!(edit.text, "WhisperCrabs");
+}
@@ -0,0 +1,117 @@
+use crate::dryrun::{self, Outputs};
+use crate::hooks::{Event, Hooks};
+
+fn outputs(text: &str) -> Outputs<'_> {
+    Outputs {
+        text,
+        stored: Some(text),
+        sync_dir: None,
+        autosave_dir: None,
+        typed: false,
+        paste: false,
+        announce: false,
+        keep_audio: false,
+        hooks: None,
+    }
+}
+
+fn actions(outputs: &Outputs) -> Vec<&'static str> {
+    dryrun::plan(outputs).iter().map(|p| p.action).collect()
+}
+
+#[test]
+fn plain_dictation_copies_and_saves() {
+    let plan = dryrun::plan(&outputs("hello world"));
+    assert_eq!(plan.len(), 2);
+    assert_eq!(plan[0].action, "Copy to clipboard");
+    assert_eq!(plan[0].detail, "hello world");
+    assert_eq!(plan[1].action, "Save to History");
+}
+
+#[test]
+fn every_enabled_output_is_listed_in_order() {
+    let outputs = Outputs {
+        paste: true,
+        announce: true,
+        keep_audio: true,
+        sync_dir: Some("/home/me/Sync/whispercrabs"),
+        autosave_dir: Some("/home/me/Notes/Journal"),
+        ..outputs("hello")
+    };
+    assert_eq!(
+        actions(&outputs),
+        [
+            "Copy to clipboard",
+            "Paste into the focused app",
+            "Announce to screen reader",
+            "Save to History",
+            "Keep the recording",
+            "Write to sync folder",
+            "Write to auto-save folder",
+        ]
+    );
+    let plan = dryrun::plan(&outputs);
+    assert_eq!(plan[2].detail, "Copied: hello");
+    assert_eq!(plan[5].detail, "/home/me/Sync/whispercrabs");
+    assert_eq!(plan[6].detail, "/home/me/Notes/Journal");
+}
+
+#[test]
+fn typed_dictation_skips_the_clipboard() {
+    let outputs = Outputs {
+        typed: true,
+        paste: true,
+        ..outputs("hello")
+    };
+    assert_eq!(
+        actions(&outputs),
+        ["Type into the focused app", "Save to History"]
+    );
+}
+
+#[test]
+fn history_off_stores_and_syncs_nothing() {
+    let outputs = Outputs {
+        stored: None,
+        sync_dir: Some("/tmp/sync"),
+        autosave_dir: Some("/tmp/notes"),
+        keep_audio: true,
+        ..outputs("secret")
+    };
+    let plan = dryrun::plan(&outputs);
+    assert_eq!(plan.len(), 2);
+    assert!(plan[1].detail.starts_with("nothing"));
+}
+
+#[test]
+fn hooks_are_listed_with_what_they_would_get() {
+    let hooks = Hooks {
+        command: Some("cat >> ~/inbox.md".into()),
+        url: Some("http://localhost:8123/api/webhook/dictation".into()),
+    };
+    let at = chrono::DateTime::parse_from_rfc3339("2026-03-09T14:05:31+01:00")
+        .unwrap()
+        .with_timezone(&chrono::Local);
+    let event = Event::new(at, "hello", "groq");
+    let outputs = Outputs {
+        hooks: Some((&hooks, &event)),
+        ..outputs("hello")
+    };
+    let plan = dryrun::plan(&outputs);
+    assert_eq!(
+        actions(&outputs),
+        [
+            "Copy to clipboard",
+            "Save to History",
+            "Run command hook",
+            "Post to webhook",
+        ]
+    );
+    assert_eq!(plan[2].detail, "cat >> ~/inbox.md (transcript on stdin)");
+    assert!(
+        plan[3]
+            .detail
+            .starts_with("http://localhost:8123/api/webhook/dictation {\"text\":\"hello\"")
+    );
+    assert!(plan[3].detail.contains("\"provider\":\"groq\""));
+}
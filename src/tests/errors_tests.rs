@@ -0,0 +1,101 @@
+use crate::errors::{self, Kind};
+
+#[test]
+fn api_errors_are_classified_by_status() {
+    let cases = [
+        ("API error 401 Unauthorized: bad key", Kind::KeyInvalid),
+        ("API error 403 Forbidden: ", Kind::KeyInvalid),
+        (
+            "API error 429 Too Many Requests: slow down",
+            Kind::RateLimited,
+        ),
+        ("API error 504 Gateway Timeout: ", Kind::Timeout),
+        ("API error 413 Payload Too Large: ", Kind::TooLong),
+        ("API error 503 Service Unavailable: ", Kind::Server),
+        ("API error 400 Bad Request: unsupported", Kind::Other),
+    ];
+    for (message, kind) in cases {
+        assert_eq!(errors::classify(message), kind, "{message}");
+    }
+}
+
+#[test]
+fn local_and_network_errors_are_classified_by_message() {
+    let cases = [
+        (
+            "Request failed: error sending request for url (https://api.groq.com/): operation timed out",
+            Kind::Timeout,
+        ),
+        (
+            "Request failed: error sending request for url (https://api.openai.com/): error trying to connect: dns error",
+            Kind::NetworkDown,
+        ),
+        ("Model not downloaded", Kind::ModelMissing),
+        ("Local model not loaded", Kind::ModelMissing),
+        (
+            "Failed to load whisper model: bad magic",
+            Kind::ModelMissing,
+        ),
+        ("No audio recorded", Kind::EmptyRecording),
+        (
+            "Recording too long for this provider (30 MB, the limit is 25 MB)",
+            Kind::TooLong,
+        ),
+        (
+            "Local-only mode is on: network access blocked",
+            Kind::Blocked,
+        ),
+        ("JSON parse error: EOF", Kind::Other),
+    ];
+    for (message, kind) in cases {
+        assert_eq!(errors::classify(message), kind, "{message}");
+    }
+}
+
+#[test]
+fn only_an_empty_recording_is_not_retried() {
+    assert!(!Kind::EmptyRecording.retryable());
+    assert!(Kind::KeyInvalid.retryable());
+    assert!(Kind::NetworkDown.retryable());
+    assert!(Kind::Other.retryable());
+}
+
+#[test]
+fn detail_prefers_the_providers_message() {
+    assert_eq!(
+        errors::detail(
+            r#"API error 401 Unauthorized: {"error":{"message":"Invalid API Key","type":"invalid_request_error"}}"#
+        ),
+        "Invalid API Key"
+    );
+    assert_eq!(
+        errors::detail(r#"API error 422 Unprocessable Entity: {"detail":"Bad audio"}"#),
+        "Bad audio"
+    );
+    assert_eq!(
+        errors::detail("API error 502 Bad Gateway: <html>oops</html>"),
+        "API error 502 Bad Gateway: <html>oops</html>"
+    );
+}
+
+#[test]
+fn long_details_are_shortened() {
+    let long = "x".repeat(1000);
+    let detail = errors::detail(&long);
+    assert_eq!(detail.chars().count(), 301);
+    assert!(detail.ends_with('…'));
+}
+
+#[test]
+fn summary_leads_with_the_kind() {
+    assert_eq!(
+        errors::summary(
+            r#"API error 429 Too Many Requests: {"error":{"message":"Rate limit reached"}}"#
+        ),
+        "Rate limited: Rate limit reached"
+    );
+    assert_eq!(
+        errors::summary("Model not downloaded"),
+        "Model missing: Model not downloaded"
+    );
+}
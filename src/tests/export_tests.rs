@@ -0,0 +1,57 @@
+use crate::db::Transcription;
+use crate::export::{ExportFormat, parse_date, render};
+
+fn entries() -> Vec<Transcription> {
+    vec![
+        Transcription {
+            id: 1,
+            text: "Hello, \"world\"".into(),
+            created_at: "2026-03-01 10:12:00".into(),
+            language: None,
+            audio_path: None,
+        },
+        Transcription {
+            id: 2,
+            text: "Hallo Welt".into(),
+            created_at: "2026-03-02 08:00:00".into(),
+            language: Some("de".into()),
+            audio_path: None,
+        },
+    ]
+}
+
+#[test]
+fn json_export_lists_entries() {
+    let json: serde_json::Value =
+        serde_json::from_str(&render(&entries(), ExportFormat::Json)).unwrap();
+    assert_eq!(json[0]["text"], "Hello, \"world\"");
+    assert_eq!(json[0]["language"], serde_json::Value::Null);
+    assert_eq!(json[1]["id"], 2);
+    assert_eq!(json[1]["language"], "de");
+}
+
+#[test]
+fn csv_export_quotes_fields() {
+    assert_eq!(
+        render(&entries(), ExportFormat::Csv),
+        "id,created_at,language,text\r\n\
+         1,2026-03-01 10:12:00,,\"Hello, \"\"world\"\"\"\r\n\
+         2,2026-03-02 08:00:00,de,Hallo Welt\r\n"
+    );
+}
+
+#[test]
+fn markdown_export_has_a_section_per_entry() {
+    let md = render(&entries(), ExportFormat::Markdown);
+    assert!(md.starts_with("# WhisperCrabs History\n"));
+    assert!(md.contains("\n## 2026-03-01 10:12:00\n\nHello, \"world\"\n"));
+    assert!(md.contains("\n## 2026-03-02 08:00:00 \u{00b7} German\n\nHallo Welt\n"));
+}
+
+#[test]
+fn parse_date_accepts_blank_and_iso_dates() {
+    assert_eq!(parse_date("  "), Ok(None));
+    assert_eq!(parse_date("2026-03-01"), Ok(Some("2026-03-01".into())));
+    assert!(parse_date("01/03/2026").is_err());
+    assert!(parse_date("2026-02-30").is_err());
+}
@@ -0,0 +1,60 @@
+use crate::activation::Activation;
+use crate::global_shortcuts::{SHORTCUTS, activation_for, normalize_trigger, trigger_setting};
+
+#[test]
+fn typed_keys_become_the_portal_format() {
+    assert_eq!(
+        normalize_trigger("Ctrl+Alt+Space").as_deref(),
+        Some("CTRL+ALT+space")
+    );
+    assert_eq!(
+        normalize_trigger(" super + shift + D ").as_deref(),
+        Some("SHIFT+LOGO+d")
+    );
+    assert_eq!(
+        normalize_trigger("<Control><Alt>r").as_deref(),
+        Some("CTRL+ALT+r")
+    );
+    assert_eq!(
+        normalize_trigger("alt+ctrl+enter").as_deref(),
+        Some("CTRL+ALT+Return")
+    );
+    assert_eq!(normalize_trigger("F9").as_deref(), Some("F9"));
+}
+
+#[test]
+fn repeated_modifiers_are_kept_once() {
+    assert_eq!(
+        normalize_trigger("ctrl+Control+x").as_deref(),
+        Some("CTRL+x")
+    );
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    assert_eq!(normalize_trigger(""), None);
+    assert_eq!(normalize_trigger("ctrl+"), None);
+    assert_eq!(normalize_trigger("hyper+x"), None);
+}
+
+#[test]
+fn shortcuts_map_to_activations() {
+    assert_eq!(activation_for("toggle"), Some(Activation::Toggle));
+    assert_eq!(activation_for("record"), Some(Activation::Start));
+    assert_eq!(activation_for("stop"), Some(Activation::Stop));
+    assert_eq!(activation_for("other"), None);
+}
+
+#[test]
+fn default_triggers_are_in_the_portal_format() {
+    for shortcut in SHORTCUTS {
+        if let Some(keys) = shortcut.default_trigger {
+            assert_eq!(normalize_trigger(keys).as_deref(), Some(keys));
+        }
+    }
+}
+
+#[test]
+fn trigger_settings_are_per_shortcut() {
+    assert_eq!(trigger_setting("toggle"), "shortcut_trigger_toggle");
+}
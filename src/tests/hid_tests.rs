@@ -0,0 +1,70 @@
+use crate::config::HidMode;
+use crate::hid::{self, HidEvent};
+
+#[test]
+fn parse_key_name_known_names() {
+    assert_eq!(hid::parse_key_name("KEY_MICMUTE"), Some(248));
+    assert_eq!(hid::parse_key_name("key_phone"), Some(169));
+    assert_eq!(hid::parse_key_name(" KEY_F20 "), Some(190));
+}
+
+#[test]
+fn parse_key_name_accepts_raw_codes() {
+    assert_eq!(hid::parse_key_name("256"), Some(256));
+}
+
+#[test]
+fn parse_key_name_rejects_unknown() {
+    assert!(hid::parse_key_name("KEY_NOPE").is_none());
+    assert!(hid::parse_key_name("").is_none());
+}
+
+#[test]
+fn default_keys_all_resolve() {
+    for key in hid::DEFAULT_HID_KEYS {
+        assert!(hid::parse_key_name(key).is_some(), "{key} should resolve");
+    }
+}
+
+#[test]
+fn event_from_value_ignores_autorepeat() {
+    assert_eq!(hid::event_from_value(1), Some(HidEvent::Pressed));
+    assert_eq!(hid::event_from_value(0), Some(HidEvent::Released));
+    assert_eq!(hid::event_from_value(2), None);
+}
+
+#[test]
+fn toggle_mode_flips_on_press_only() {
+    assert_eq!(
+        hid::activation_for(HidMode::Toggle, HidEvent::Pressed).and_then(|a| a.resolve(false)),
+        Some(true)
+    );
+    assert_eq!(
+        hid::activation_for(HidMode::Toggle, HidEvent::Pressed).and_then(|a| a.resolve(true)),
+        Some(false)
+    );
+    assert_eq!(
+        hid::activation_for(HidMode::Toggle, HidEvent::Released).and_then(|a| a.resolve(true)),
+        None
+    );
+}
+
+#[test]
+fn push_to_talk_records_while_held() {
+    assert_eq!(
+        hid::activation_for(HidMode::PushToTalk, HidEvent::Pressed).and_then(|a| a.resolve(false)),
+        Some(true)
+    );
+    assert_eq!(
+        hid::activation_for(HidMode::PushToTalk, HidEvent::Released).and_then(|a| a.resolve(true)),
+        Some(false)
+    );
+    assert_eq!(
+        hid::activation_for(HidMode::PushToTalk, HidEvent::Pressed).and_then(|a| a.resolve(true)),
+        None
+    );
+    assert_eq!(
+        hid::activation_for(HidMode::PushToTalk, HidEvent::Released).and_then(|a| a.resolve(false)),
+        None
+    );
+}
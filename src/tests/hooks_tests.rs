@@ -0,0 +1,91 @@
+use crate::db::Db;
+use crate::hooks::{self, Event, Hooks};
+use chrono::TimeZone;
+
+fn event(text: &str) -> Event {
+    let at = chrono::Local
+        .with_ymd_and_hms(2026, 3, 9, 14, 5, 31)
+        .unwrap();
+    Event::new(at, text, "groq")
+}
+
+#[test]
+fn parse_trims_and_drops_empty_fields() {
+    let hooks = hooks::parse(r#"{"command": "  notify-send hi ", "url": ""}"#).unwrap();
+    assert_eq!(hooks.command.as_deref(), Some("notify-send hi"));
+    assert_eq!(hooks.url, None);
+    assert!(hooks::parse("{}").unwrap().is_empty());
+}
+
+#[test]
+fn parse_rejects_bad_urls_and_fields() {
+    assert!(hooks::parse(r#"{"url": "ftp://example.com"}"#).is_err());
+    assert!(hooks::parse(r#"{"script": "x"}"#).is_err());
+    assert!(hooks::parse("not json").is_err());
+}
+
+#[test]
+fn save_and_clear_roundtrip() {
+    let db = Db::open_in_memory().unwrap();
+    assert!(hooks::stored(&db).is_empty());
+    let hooks = Hooks {
+        command: Some("cat >> ~/inbox.txt".into()),
+        url: Some("http://localhost:8123/api/webhook/dictation".into()),
+    };
+    hooks::save(&db, &hooks).unwrap();
+    assert_eq!(hooks::stored(&db), hooks);
+    hooks::save(&db, &Hooks::default()).unwrap();
+    assert!(hooks::stored(&db).is_empty());
+}
+
+#[test]
+fn event_serializes_text_timestamp_and_provider() {
+    let json = serde_json::to_value(event("Buy milk")).unwrap();
+    assert_eq!(json["text"], "Buy milk");
+    assert_eq!(json["provider"], "groq");
+    assert!(
+        json["timestamp"]
+            .as_str()
+            .unwrap()
+            .starts_with("2026-03-09T14:05:31")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn command_gets_text_on_stdin_and_provider_in_env() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.txt");
+    let command = format!(
+        "cat > '{}'; echo \" $WHISPERCRABS_PROVIDER\" >> '{}'",
+        out.display(),
+        out.display()
+    );
+    hooks::run_command(&command, &event("Call Sam")).unwrap();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "Call Sam groq\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_command_is_an_error() {
+    assert!(hooks::run_command("exit 3", &event("x")).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn slow_command_is_stopped() {
+    let started = std::time::Instant::now();
+    let err = hooks::run_command_within(
+        "sleep 30",
+        &event("x"),
+        std::time::Duration::from_millis(200),
+    )
+    .unwrap_err();
+    assert!(err.contains("stopped"));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[test]
+fn webhook_needs_http() {
+    assert!(hooks::post("file:///etc/passwd", &event("x")).is_err());
+}
@@ -0,0 +1,57 @@
+use crate::keybindings::{BINDINGS, NONE, effective, next_choice, parse, setting};
+
+#[test]
+fn typed_keys_become_gtk_accelerators() {
+    assert_eq!(parse("Ctrl+H").as_deref(), Some("<Control>h"));
+    assert_eq!(
+        parse("shift + ctrl + space").as_deref(),
+        Some("<Control><Shift>space")
+    );
+    assert_eq!(parse("<Control>m").as_deref(), Some("<Control>m"));
+    assert_eq!(parse("esc").as_deref(), Some("Escape"));
+    assert_eq!(parse("Super+Return").as_deref(), Some("<Super>Return"));
+}
+
+#[test]
+fn none_switches_a_key_off() {
+    assert_eq!(parse("none").as_deref(), Some(NONE));
+    assert_eq!(parse(" Off ").as_deref(), Some(NONE));
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    assert_eq!(parse(""), None);
+    assert_eq!(parse("ctrl+"), None);
+    assert_eq!(parse("hyper+x"), None);
+}
+
+#[test]
+fn stored_keys_override_the_default() {
+    let stop = BINDINGS.iter().find(|b| b.id == "stop").unwrap();
+    assert_eq!(effective(stop, None), Some("Escape"));
+    assert_eq!(effective(stop, Some("")), Some("Escape"));
+    assert_eq!(effective(stop, Some("<Control>q")), Some("<Control>q"));
+    assert_eq!(effective(stop, Some(NONE)), None);
+}
+
+#[test]
+fn defaults_are_already_normalized() {
+    for binding in BINDINGS {
+        assert_eq!(parse(binding.default).as_deref(), Some(binding.default));
+        assert!(binding.action.starts_with("app."));
+    }
+}
+
+#[test]
+fn settings_are_per_binding() {
+    assert_eq!(setting("history"), "keybinding_history");
+}
+
+#[test]
+fn modes_cycle_and_wrap() {
+    let choices: Vec<String> = ["groq", "local", "base"].map(String::from).to_vec();
+    assert_eq!(next_choice(&choices, "groq"), Some("local"));
+    assert_eq!(next_choice(&choices, "base"), Some("groq"));
+    assert_eq!(next_choice(&choices, "unknown"), Some("groq"));
+    assert_eq!(next_choice(&[], "groq"), None);
+}
@@ -0,0 +1,113 @@
+use crate::local_stt::{StatePool, Word, mean_confidence, words_from_tokens};
+
+fn tokens(list: &[(&str, i64, i64)]) -> Vec<(String, i64, i64)> {
+    list.iter()
+        .map(|(t, a, b)| (t.to_string(), *a, *b))
+        .collect()
+}
+
+#[test]
+fn words_from_tokens_merges_subwords() {
+    let words = words_from_tokens(&tokens(&[
+        ("[_BEG_]", 0, 0),
+        (" Hello", 0, 40),
+        (" Whis", 45, 60),
+        ("per", 60, 80),
+        ("Crabs", 80, 110),
+        (".", 110, 112),
+        ("[_TT_150]", 150, 150),
+    ]));
+    assert_eq!(
+        words,
+        vec![
+            Word {
+                text: "Hello".into(),
+                start_ms: 0,
+                end_ms: 400,
+            },
+            Word {
+                text: "WhisperCrabs.".into(),
+                start_ms: 450,
+                end_ms: 1120,
+            },
+        ]
+    );
+}
+
+#[test]
+fn words_from_tokens_skips_special_and_blank() {
+    let words = words_from_tokens(&tokens(&[("<|en|>", 0, 0), (" ", 0, 5), (" ok", 5, 20)]));
+    assert_eq!(words.len(), 1);
+    assert_eq!(words[0].text, "ok");
+    assert_eq!(words[0].start_ms, 50);
+}
+
+#[test]
+fn words_from_tokens_empty() {
+    assert!(words_from_tokens(&[]).is_empty());
+}
+
+#[test]
+fn label_speakers_alternates_at_turns() {
+    let segments = vec![
+        (" Hi, how are you?".to_string(), true),
+        (" Good, thanks.".to_string(), false),
+        (" And you?".to_string(), true),
+        (" Fine.".to_string(), false),
+    ];
+    assert_eq!(
+        crate::local_stt::label_speakers(&segments).unwrap(),
+        "Speaker 1: Hi, how are you?\nSpeaker 2: Good, thanks. And you?\nSpeaker 1: Fine."
+    );
+}
+
+#[test]
+fn label_speakers_none_without_turns() {
+    let segments = vec![(" Just me.".to_string(), false)];
+    assert_eq!(crate::local_stt::label_speakers(&segments), None);
+    assert_eq!(crate::local_stt::label_speakers(&[]), None);
+}
+
+#[test]
+fn mean_confidence_averages() {
+    assert_eq!(mean_confidence(&[]), None);
+    assert_eq!(mean_confidence(&[0.5, 1.0]), Some(0.75));
+}
+
+#[test]
+fn state_pool_reuses_returned_values() {
+    let pool = StatePool::new(2);
+    let mut created = 0;
+    {
+        let first = pool
+            .take(|| {
+                created += 1;
+                Ok(created)
+            })
+            .unwrap();
+        assert_eq!(*first, 1);
+    }
+    assert_eq!(pool.idle(), 1);
+    let again = pool.take(|| Err("should reuse".into())).unwrap();
+    assert_eq!(*again, 1);
+    assert_eq!(pool.idle(), 0);
+}
+
+#[test]
+fn state_pool_creates_for_concurrent_jobs_and_caps_idle() {
+    let pool = StatePool::new(2);
+    pool.put(0);
+    let a = pool.take(|| Ok(1)).unwrap();
+    let b = pool.take(|| Ok(2)).unwrap();
+    let c = pool.take(|| Ok(3)).unwrap();
+    assert_eq!((*a, *b, *c), (0, 2, 3));
+    drop((a, b, c));
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn state_pool_propagates_create_errors() {
+    let pool: StatePool<u8> = StatePool::new(1);
+    assert!(pool.take(|| Err("no memory".into())).is_err());
+    assert_eq!(pool.idle(), 0);
+}
@@ -0,0 +1,31 @@
+use crate::lock;
+
+#[test]
+fn hash_and_verify_secret() {
+    let hash = lock::hash_secret("1234").unwrap();
+    assert!(hash.starts_with("$argon2id$"));
+    assert!(lock::verify_secret("1234", &hash));
+    assert!(!lock::verify_secret("4321", &hash));
+    assert!(!lock::verify_secret("", &hash));
+}
+
+#[test]
+fn hash_uses_fresh_salt() {
+    assert_ne!(
+        lock::hash_secret("correct horse").unwrap(),
+        lock::hash_secret("correct horse").unwrap()
+    );
+}
+
+#[test]
+fn verify_rejects_malformed_hash() {
+    assert!(!lock::verify_secret("1234", ""));
+    assert!(!lock::verify_secret("1234", "1234"));
+}
+
+#[test]
+fn validate_secret_rules() {
+    assert!(lock::validate_secret("1234", "1234").is_ok());
+    assert!(lock::validate_secret("123", "123").is_err());
+    assert!(lock::validate_secret("1234", "1235").is_err());
+}
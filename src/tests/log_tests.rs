@@ -0,0 +1,69 @@
+use crate::log;
+use tracing::Level;
+
+#[test]
+fn levels_parse_by_name() {
+    assert_eq!(log::parse_level("debug"), Some(Level::DEBUG));
+    assert_eq!(log::parse_level(" WARN "), Some(Level::WARN));
+    assert_eq!(log::parse_level("warning"), Some(Level::WARN));
+    assert_eq!(log::parse_level("verbose"), None);
+}
+
+#[test]
+fn environment_wins_over_the_debug_flag() {
+    assert_eq!(log::level(None, false), Level::INFO);
+    assert_eq!(log::level(None, true), Level::DEBUG);
+    assert_eq!(log::level(Some("error"), true), Level::ERROR);
+    assert_eq!(log::level(Some("trace"), false), Level::TRACE);
+    assert_eq!(log::level(Some("nonsense"), false), Level::INFO);
+}
+
+#[test]
+fn other_crates_only_log_warnings() {
+    assert!(log::enabled(Level::INFO, "whispercrabs::ui", Level::INFO));
+    assert!(!log::enabled(Level::INFO, "whispercrabs::ui", Level::DEBUG));
+    assert!(log::enabled(Level::DEBUG, "whispercrabs", Level::DEBUG));
+    assert!(!log::enabled(Level::TRACE, "hyper::proto", Level::DEBUG));
+    assert!(log::enabled(Level::TRACE, "hyper::proto", Level::WARN));
+    assert!(!log::enabled(Level::ERROR, "hyper::proto", Level::WARN));
+}
+
+#[test]
+fn lines_name_the_module() {
+    assert_eq!(
+        log::format_line("12:00:00", Level::WARN, "whispercrabs::api", "timeout"),
+        "12:00:00 WARN  api: timeout"
+    );
+    assert_eq!(
+        log::format_line("12:00:00", Level::ERROR, "h2", "reset"),
+        "12:00:00 ERROR h2: reset"
+    );
+}
+
+#[test]
+fn rotation_shifts_and_drops_the_oldest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(log::FILE_NAME);
+    assert_eq!(
+        log::rotated_path(&path, 2),
+        dir.path().join("whispercrabs.log.2")
+    );
+    for round in 1..=4 {
+        std::fs::write(&path, format!("round {round}")).unwrap();
+        log::rotate(&path, 3).unwrap();
+    }
+    assert!(!path.exists());
+    let read = |n| std::fs::read_to_string(log::rotated_path(&path, n)).unwrap();
+    assert_eq!(read(1), "round 4");
+    assert_eq!(read(2), "round 3");
+    assert_eq!(read(3), "round 2");
+    assert!(!log::rotated_path(&path, 4).exists());
+}
+
+#[test]
+fn tail_keeps_the_last_lines() {
+    assert_eq!(log::tail("a\nb\nc\n", 2), "b\nc\n");
+    assert_eq!(log::tail("a\nb\nc", 2), "b\nc");
+    assert_eq!(log::tail("a\nb", 5), "a\nb");
+    assert_eq!(log::tail("a\nb", 0), "");
+}
@@ -0,0 +1,50 @@
+use crate::db::Db;
+use crate::media;
+
+#[test]
+fn player_id_drops_prefix_and_instance() {
+    assert_eq!(
+        media::player_id("org.mpris.MediaPlayer2.spotify").as_deref(),
+        Some("spotify")
+    );
+    assert_eq!(
+        media::player_id("org.mpris.MediaPlayer2.firefox.instance_1_84").as_deref(),
+        Some("firefox")
+    );
+    assert_eq!(
+        media::player_id("org.mpris.MediaPlayer2.VLC").as_deref(),
+        Some("vlc")
+    );
+    assert_eq!(media::player_id("org.freedesktop.Notifications"), None);
+    assert_eq!(media::player_id("org.mpris.MediaPlayer2."), None);
+}
+
+#[test]
+fn parse_players_splits_and_dedupes() {
+    assert_eq!(
+        media::parse_players("Spotify, vlc  spotify,,\nmpv"),
+        ["spotify", "vlc", "mpv"]
+    );
+    assert!(media::parse_players(" , ").is_empty());
+}
+
+#[test]
+fn allowlist_limits_players() {
+    let all: Vec<String> = Vec::new();
+    let some = media::parse_players("spotify");
+    assert!(media::allowed(&all, "org.mpris.MediaPlayer2.vlc"));
+    assert!(media::allowed(&some, "org.mpris.MediaPlayer2.spotify"));
+    assert!(!media::allowed(&some, "org.mpris.MediaPlayer2.vlc"));
+    assert!(!media::allowed(&all, "org.gnome.Shell"));
+}
+
+#[test]
+fn stored_is_off_until_enabled() {
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting(media::PLAYERS_SETTING, "spotify").unwrap();
+    assert_eq!(media::stored(&db), None);
+    db.set_setting(media::ENABLED_SETTING, "true").unwrap();
+    assert_eq!(media::stored(&db), Some(vec!["spotify".to_string()]));
+    db.delete_setting(media::PLAYERS_SETTING).unwrap();
+    assert_eq!(media::stored(&db), Some(Vec::new()));
+}
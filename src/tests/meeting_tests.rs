@@ -0,0 +1,126 @@
+use crate::local_stt::Segment;
+use crate::meeting::{self, Meeting, Part};
+
+fn segment(start_ms: i64, end_ms: i64, text: &str) -> Segment {
+    Segment {
+        start_ms,
+        end_ms,
+        text: text.into(),
+        confidence: None,
+    }
+}
+
+fn meeting() -> Meeting {
+    Meeting::new(chrono::Local::now())
+}
+
+#[test]
+fn segment_length_is_30_to_60_seconds() {
+    assert_eq!(meeting::parse_secs("45"), Some(45));
+    assert_eq!(meeting::parse_secs(" 30 "), Some(30));
+    assert_eq!(meeting::parse_secs("60"), Some(60));
+    assert_eq!(meeting::parse_secs("29"), None);
+    assert_eq!(meeting::parse_secs("61"), None);
+    assert_eq!(meeting::parse_secs("off"), None);
+    for secs in meeting::CHOICES {
+        assert_eq!(meeting::parse_secs(&secs.to_string()), Some(*secs));
+    }
+}
+
+#[test]
+fn offsets_gain_hours_after_the_first() {
+    assert_eq!(meeting::offset_label(0), "00:00");
+    assert_eq!(meeting::offset_label(754_900), "12:34");
+    assert_eq!(meeting::offset_label(3_723_000), "1:02:03");
+    assert_eq!(meeting::offset_label(-5), "00:00");
+}
+
+#[test]
+fn segments_follow_each_other() {
+    let mut m = meeting();
+    assert_eq!(m.push(45_000), 0);
+    assert_eq!(m.push(45_000), 1);
+    assert_eq!(m.chunks()[1].start_ms, 45_000);
+    assert_eq!(m.recorded_ms(), 90_000);
+    assert_eq!(m.pending(), 2);
+}
+
+#[test]
+fn out_of_order_results_keep_recording_order() {
+    let mut m = meeting();
+    let first = m.push(30_000);
+    let second = m.push(30_000);
+    m.finish(second, "Second part.", &[], None);
+    assert_eq!(m.document(), "[00:00] …\n\n[00:30] Second part.");
+    m.finish(first, " First part. ", &[], None);
+    assert_eq!(m.document(), "[00:00] First part.\n\n[00:30] Second part.");
+    assert_eq!(m.text(), "First part.\n\nSecond part.");
+    assert_eq!(m.pending(), 0);
+    assert_eq!(m.transcribed(), 2);
+}
+
+#[test]
+fn segment_timings_move_to_the_meeting_clock() {
+    let mut m = meeting();
+    m.push(30_000);
+    let i = m.push(30_000);
+    m.finish(
+        i,
+        "Hello there",
+        &[segment(1_000, 4_000, "Hello there")],
+        None,
+    );
+    assert_eq!(m.segments(), vec![segment(31_000, 34_000, "Hello there")]);
+}
+
+#[test]
+fn a_segment_without_timings_spans_its_chunk() {
+    let mut m = meeting();
+    m.push(30_000);
+    let i = m.push(45_000);
+    m.finish(i, "Plain text", &[], None);
+    assert_eq!(m.segments(), vec![segment(30_000, 75_000, "Plain text")]);
+}
+
+#[test]
+fn silent_and_failed_segments_stay_out_of_the_text() {
+    let mut m = meeting();
+    let silent = m.push(30_000);
+    let failed = m.push(30_000);
+    let spoken = m.push(30_000);
+    m.finish(silent, "  ", &[], None);
+    m.fail(failed, "timeout");
+    m.finish(spoken, "Done.", &[], None);
+    assert_eq!(
+        m.document(),
+        "[00:30] (not transcribed: timeout)\n\n[01:00] Done."
+    );
+    assert_eq!(m.text(), "Done.");
+    assert_eq!(m.segments().len(), 1);
+    assert_eq!(m.chunks()[1].part, Part::Failed("timeout".into()));
+}
+
+#[test]
+fn language_is_the_most_common_one() {
+    let mut m = meeting();
+    assert_eq!(m.language(), None);
+    for language in ["de", "en", "en", "de"] {
+        let i = m.push(30_000);
+        m.finish(i, "text", &[], Some(language.into()));
+    }
+    // A tie goes to the first language heard
+    assert_eq!(m.language().as_deref(), Some("de"));
+    let i = m.push(30_000);
+    m.finish(i, "text", &[], Some("en".into()));
+    assert_eq!(m.language().as_deref(), Some("en"));
+}
+
+#[test]
+fn status_counts_what_is_left() {
+    let mut m = meeting();
+    let i = m.push(30_000);
+    m.push(30_000);
+    m.finish(i, "text", &[], None);
+    assert!(m.status(true).ends_with("1 segments transcribed"));
+    assert_eq!(m.status(false), "Stopped · transcribing 1 more segments...");
+}
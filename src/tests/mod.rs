@@ -1,6 +1,61 @@
+mod activation_tests;
+mod announce_tests;
 mod api_tests;
+mod appearance_tests;
 mod audio_tests;
+mod auto_tests;
+mod autosave_tests;
+mod autostart_tests;
+mod batch_tests;
+mod benchmark_tests;
+mod capabilities_tests;
+mod captions_tests;
+mod cli_tests;
+mod coaching_tests;
+mod confidence_tests;
 mod config_load_tests;
 mod config_tests;
+mod corrections_tests;
 mod db_tests;
+mod dbus_tests;
+mod decode_tests;
+mod dictation_commands_tests;
+mod dictation_tests;
+mod dryrun_tests;
+mod errors_tests;
+mod export_tests;
+mod global_shortcuts_tests;
+mod hid_tests;
+mod hooks_tests;
+mod keybindings_tests;
+mod local_stt_tests;
+mod lock_tests;
+mod log_tests;
+mod media_tests;
+mod meeting_tests;
 mod model_download_tests;
+mod notify_tests;
+mod paste_tests;
+mod policy_tests;
+mod postprocess_tests;
+mod preferences_tests;
+mod profiles_tests;
+mod provider_file_tests;
+mod quiet_tests;
+mod remote_tests;
+mod retention_tests;
+mod review_tests;
+mod rules_tests;
+mod session_log_tests;
+mod settings_file_tests;
+mod silence_tests;
+mod sounds_tests;
+mod split_tests;
+mod stacking_tests;
+mod subtitles_tests;
+mod sync_tests;
+mod template_tests;
+mod transcription_tests;
+mod tray_tests;
+mod vocabulary_tests;
+mod wakeword_tests;
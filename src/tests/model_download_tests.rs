@@ -9,7 +9,7 @@ fn model_urls_are_reachable() {
         .unwrap();
 
     for model in config::LOCAL_MODEL_PRESETS {
-        let url = config::model_url(model.file_name);
+        let url = config::preset_model_url(config::DEFAULT_MODEL_BASE_URL, model);
         let resp = client.head(&url).send();
         match resp {
             Ok(r) => {
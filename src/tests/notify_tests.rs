@@ -0,0 +1,35 @@
+use crate::notify::{PREVIEW_CHARS, preview, title};
+
+#[test]
+fn short_transcripts_are_shown_whole() {
+    assert_eq!(preview("Buy milk."), "Buy milk.");
+}
+
+#[test]
+fn preview_is_one_line() {
+    assert_eq!(
+        preview("  First line\nsecond\tline "),
+        "First line second line"
+    );
+}
+
+#[test]
+fn long_transcripts_are_cut_with_an_ellipsis() {
+    let text = "word ".repeat(60);
+    let body = preview(&text);
+    assert!(body.ends_with('\u{2026}'));
+    assert!(body.chars().count() <= PREVIEW_CHARS + 1);
+    assert!(!body.contains(" \u{2026}"));
+}
+
+#[test]
+fn exactly_the_limit_has_no_ellipsis() {
+    let text = "x".repeat(PREVIEW_CHARS);
+    assert_eq!(preview(&text), text);
+}
+
+#[test]
+fn title_says_what_happened() {
+    assert_eq!(title(false), "Copied to the clipboard");
+    assert_eq!(title(true), "Typed into the focused app");
+}
@@ -0,0 +1,89 @@
+use crate::paste::{PasteConfig, PasteTiming, parse_apps};
+
+fn lookup(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |key| {
+        pairs
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    }
+}
+
+#[test]
+fn defaults_keep_the_old_paste_delay() {
+    let config = PasteConfig::from_lookup(|_| None);
+    assert_eq!(config.default, PasteTiming::DEFAULT);
+    assert_eq!(config.default.delay_ms, 150);
+    assert!(!config.default.verify);
+    assert!(config.apps.is_empty());
+}
+
+#[test]
+fn global_values_apply_to_every_app() {
+    let config = PasteConfig::from_lookup(lookup(&[
+        ("PASTE_DELAY_MS", "250"),
+        ("PASTE_KEY_DELAY_MS", "30ms"),
+        ("PASTE_VERIFY", "true"),
+    ]));
+    let expected = PasteTiming {
+        delay_ms: 250,
+        key_delay_ms: 30,
+        verify: true,
+    };
+    assert_eq!(config.default, expected);
+    assert_eq!(config.for_class(Some("firefox")), expected);
+    assert_eq!(config.for_class(None), expected);
+}
+
+#[test]
+fn app_entries_override_fields_they_set() {
+    let default = PasteTiming::DEFAULT;
+    let apps = parse_apps("Code:400, jetbrains-idea:500:20:verify ,kitty:50", default);
+    assert_eq!(apps.len(), 3);
+    assert_eq!(
+        apps[0],
+        (
+            "code".to_string(),
+            PasteTiming {
+                delay_ms: 400,
+                ..default
+            }
+        )
+    );
+    assert_eq!(
+        apps[1].1,
+        PasteTiming {
+            delay_ms: 500,
+            key_delay_ms: 20,
+            verify: true,
+        }
+    );
+    assert_eq!(apps[2].1.delay_ms, 50);
+}
+
+#[test]
+fn malformed_app_entries_are_skipped() {
+    let apps = parse_apps(
+        "code, :100, kitty:fast, term:99999, xterm:80",
+        PasteTiming::DEFAULT,
+    );
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].0, "xterm");
+}
+
+#[test]
+fn window_class_matches_case_insensitively() {
+    let config = PasteConfig::from_lookup(lookup(&[("PASTE_APPS", "code:400")]));
+    assert_eq!(config.for_class(Some("Code")).delay_ms, 400);
+    assert_eq!(config.for_class(Some("kitty")), PasteTiming::DEFAULT);
+}
+
+#[test]
+fn app_entries_inherit_global_values() {
+    let config = PasteConfig::from_lookup(lookup(&[
+        ("PASTE_VERIFY", "true"),
+        ("PASTE_APPS", "code:400:noverify, kitty:50"),
+    ]));
+    assert!(!config.for_class(Some("code")).verify);
+    assert!(config.for_class(Some("kitty")).verify);
+}
@@ -0,0 +1,117 @@
+use crate::policy::{self, Policy};
+
+#[test]
+fn default_policy_allows_everything() {
+    let p = Policy::default();
+    assert!(!p.is_managed());
+    assert!(p.allows("groq", None));
+    assert!(p.allows("local-base", None));
+    assert!(p.allows("custom", Some("https://api.example.com/v1")));
+}
+
+#[test]
+fn parse_policy_section() {
+    let p = Policy::parse(
+        r#"
+        [policy]
+        allowed_providers = ["local-base", "ollama"]
+        disable_cloud = true
+        "#,
+    )
+    .unwrap();
+    assert!(p.is_managed());
+    assert!(p.disable_cloud);
+    assert!(p.allows("local-base", None));
+    assert!(p.allows("ollama", None));
+    assert!(!p.allows("groq", None));
+    assert!(!p.allows("local-tiny", None));
+}
+
+#[test]
+fn parse_policy_rejects_unknown_keys() {
+    assert!(Policy::parse("[policy]\nallow_everything = true\n").is_err());
+}
+
+#[test]
+fn missing_policy_section_is_permissive() {
+    let p = Policy::parse("").unwrap();
+    assert_eq!(p, Policy::default());
+}
+
+#[test]
+fn disable_cloud_blocks_remote_endpoints() {
+    let p = Policy {
+        disable_cloud: true,
+        ..Policy::default()
+    };
+    assert!(!p.allows("groq", None));
+    assert!(!p.allows("openrouter", None));
+    assert!(p.allows("ollama", None));
+    assert!(p.allows("custom", Some("http://127.0.0.1:8080")));
+    assert!(!p.allows("custom", Some("https://api.example.com/v1")));
+    assert!(p.allows("local-small", None));
+}
+
+#[test]
+fn local_only_blocks_all_api_providers() {
+    let p = Policy {
+        local_only: true,
+        ..Policy::default()
+    };
+    assert!(!p.allows("ollama", None));
+    assert!(!p.allows("custom", Some("http://localhost:1234/v1")));
+    assert!(p.allows("local-tiny", None));
+}
+
+#[test]
+fn force_provider_locks_choice() {
+    let p = Policy {
+        force_provider: Some("local-base".into()),
+        ..Policy::default()
+    };
+    assert!(p.is_locked());
+    assert!(p.allows("local-base", None));
+    assert!(!p.allows("local-tiny", None));
+    assert_eq!(p.fallback_provider(), "local-base");
+}
+
+#[test]
+fn fallback_skips_disallowed_entries() {
+    let p = Policy {
+        allowed_providers: Some(vec!["groq".into(), "local-small".into()]),
+        disable_cloud: true,
+        ..Policy::default()
+    };
+    assert_eq!(p.fallback_provider(), "local-small");
+}
+
+#[test]
+fn is_local_url_detects_loopback_hosts() {
+    assert!(policy::is_local_url("http://localhost:11434/v1"));
+    assert!(policy::is_local_url("http://127.0.0.1/v1"));
+    assert!(policy::is_local_url("http://[::1]:8080"));
+    assert!(!policy::is_local_url("https://api.groq.com/openai/v1"));
+    assert!(!policy::is_local_url("http://localhost.evil.com/v1"));
+    assert!(!policy::is_local_url("file:///etc/passwd"));
+}
+
+#[test]
+fn local_only_switch_gates_remote_urls() {
+    policy::set_local_only(true);
+    assert!(policy::local_only());
+    assert!(policy::ensure_network_allowed("https://huggingface.co/model.bin").is_err());
+    assert!(policy::ensure_network_allowed("http://localhost:11434/v1").is_ok());
+    policy::set_local_only(false);
+    assert!(policy::ensure_network_allowed("https://huggingface.co/model.bin").is_ok());
+}
+
+#[test]
+fn url_host_extracts_host() {
+    assert_eq!(
+        policy::url_host("https://api.groq.com/openai/v1"),
+        "api.groq.com"
+    );
+    assert_eq!(policy::url_host("http://localhost:11434/v1"), "localhost");
+    assert_eq!(policy::url_host("http://[::1]:8080/x"), "::1");
+    assert_eq!(policy::url_host("ftp://example.com"), "");
+}
@@ -0,0 +1,117 @@
+use crate::config::Config;
+use crate::db::Db;
+use crate::postprocess::{
+    CUSTOM, Job, MODE_SETTING, MODEL_SETTING, MODES, OFF, PROMPT_SETTING, PROVIDER_SETTING,
+    chat_url, default_model, find_mode, job, parse_reply, request_body,
+};
+
+fn config() -> Config {
+    Config::from_lookup(|_| None)
+}
+
+#[test]
+fn off_by_default() {
+    let db = Db::open_in_memory().unwrap();
+    assert_eq!(job(&db, &config()), Ok(None));
+    db.set_setting(MODE_SETTING, OFF).unwrap();
+    assert_eq!(job(&db, &config()), Ok(None));
+}
+
+#[test]
+fn built_in_modes_use_the_first_api_provider() {
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting(MODE_SETTING, "punctuation").unwrap();
+    // Ollama needs no key, so it is always set up
+    let job = job(&db, &config()).unwrap().unwrap();
+    assert!(job.url.ends_with("/chat/completions"));
+    assert_eq!(job.model, default_model("ollama").unwrap());
+    assert_eq!(job.prompt, find_mode("punctuation").unwrap().prompt);
+}
+
+#[test]
+fn chosen_provider_and_model_are_used() {
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting(MODE_SETTING, "email").unwrap();
+    db.set_setting("api_key_groq", "gsk-test").unwrap();
+    db.set_setting(PROVIDER_SETTING, "groq").unwrap();
+    db.set_setting(MODEL_SETTING, "llama-3.3-70b-versatile")
+        .unwrap();
+    let job = job(&db, &config()).unwrap().unwrap();
+    assert_eq!(job.api_key.as_deref(), Some("gsk-test"));
+    assert_eq!(job.model, "llama-3.3-70b-versatile");
+    assert!(job.url.starts_with("https://api.groq.com/"));
+}
+
+#[test]
+fn unusable_modes_are_errors() {
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting(MODE_SETTING, CUSTOM).unwrap();
+    assert!(job(&db, &config()).is_err());
+    db.set_setting(PROMPT_SETTING, "Answer like a pirate.")
+        .unwrap();
+    assert_eq!(
+        job(&db, &config()).unwrap().unwrap().prompt,
+        "Answer like a pirate."
+    );
+    db.set_setting(MODE_SETTING, "sonnet").unwrap();
+    assert!(job(&db, &config()).is_err());
+    db.set_setting(MODE_SETTING, "email").unwrap();
+    db.set_setting(PROVIDER_SETTING, "groq").unwrap();
+    assert!(job(&db, &config()).is_err(), "groq has no key");
+}
+
+#[test]
+fn mode_ids_are_unique() {
+    for (i, mode) in MODES.iter().enumerate() {
+        assert!(MODES[i + 1..].iter().all(|m| m.id != mode.id));
+        assert_ne!(mode.id, OFF);
+        assert_ne!(mode.id, CUSTOM);
+    }
+}
+
+#[test]
+fn chat_url_joins_the_base_url() {
+    assert_eq!(
+        chat_url("https://api.groq.com/openai/v1/"),
+        "https://api.groq.com/openai/v1/chat/completions"
+    );
+    assert_eq!(
+        chat_url("http://localhost:11434/v1"),
+        "http://localhost:11434/v1/chat/completions"
+    );
+}
+
+#[test]
+fn request_carries_prompt_and_transcript() {
+    let job = Job {
+        url: chat_url("http://localhost:11434/v1"),
+        api_key: None,
+        model: "llama3.2".into(),
+        prompt: "Fix it.".into(),
+    };
+    let body = request_body(&job, "hello world");
+    assert_eq!(body["model"], "llama3.2");
+    assert_eq!(body["messages"][0]["role"], "system");
+    assert_eq!(body["messages"][0]["content"], "Fix it.");
+    assert_eq!(body["messages"][1]["role"], "user");
+    assert_eq!(body["messages"][1]["content"], "hello world");
+}
+
+#[test]
+fn replies_are_trimmed() {
+    let json = serde_json::json!({
+        "choices": [{ "message": { "role": "assistant", "content": " Hello, world.\n" } }]
+    });
+    assert_eq!(parse_reply(&json).unwrap(), "Hello, world.");
+    assert!(parse_reply(&serde_json::json!({ "choices": [] })).is_err());
+    let empty = serde_json::json!({ "choices": [{ "message": { "content": "  " } }] });
+    assert!(parse_reply(&empty).is_err());
+}
+
+#[test]
+fn custom_prompts_must_fit_in_a_setting() {
+    use crate::postprocess::{MAX_PROMPT_BYTES, check_prompt};
+    assert_eq!(check_prompt(&"a".repeat(MAX_PROMPT_BYTES)), Ok(()));
+    let err = check_prompt(&"ü".repeat(MAX_PROMPT_BYTES)).unwrap_err();
+    assert!(err.contains("8192 bytes"));
+}
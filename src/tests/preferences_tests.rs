@@ -0,0 +1,47 @@
+use crate::preferences;
+
+#[test]
+fn stored_model_overrides_the_default() {
+    assert_eq!(preferences::model_setting("groq"), "api_model_groq");
+    assert_eq!(
+        preferences::effective_model(Some(" whisper-large-v3 "), "whisper-large-v3-turbo"),
+        "whisper-large-v3"
+    );
+    assert_eq!(
+        preferences::effective_model(Some("  "), "whisper-large-v3-turbo"),
+        "whisper-large-v3-turbo"
+    );
+    assert_eq!(preferences::effective_model(None, "whisper-1"), "whisper-1");
+}
+
+#[test]
+fn shortcut_commands_activate_the_action() {
+    let command = preferences::shortcut_command("record");
+    assert!(command.starts_with("gdbus call --session --dest=dev.whispercrabs.app "));
+    assert!(command.contains("--object-path=/dev/whispercrabs/app"));
+    assert!(command.ends_with("org.gtk.Actions.Activate record [] {}"));
+    assert!(!command.contains("  "));
+}
+
+#[test]
+fn choice_index_finds_values() {
+    let choices = vec![
+        ("forever".to_string(), "Forever".to_string()),
+        ("30".to_string(), "30 days".to_string()),
+    ];
+    assert_eq!(preferences::choice_index(&choices, "30"), Some(1));
+    assert_eq!(preferences::choice_index(&choices, "7"), None);
+}
+
+#[test]
+fn model_settings_are_accepted() {
+    let db = crate::db::Db::open_in_memory().unwrap();
+    db.set_setting(&preferences::model_setting("openai"), "gpt-4o-transcribe")
+        .unwrap();
+    db.set_setting("audio_device", "USB Microphone").unwrap();
+    db.set_setting("auto_paste", "true").unwrap();
+    assert_eq!(
+        db.get_setting("api_model_openai").unwrap().as_deref(),
+        Some("gpt-4o-transcribe")
+    );
+}
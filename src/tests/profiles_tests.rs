@@ -0,0 +1,65 @@
+use crate::profiles;
+
+#[test]
+fn parse_profiles_accepts_valid_list() {
+    let json = r#"[
+        {"name": "dictate-en", "language": "en"},
+        {"name": "translate", "provider": "local-small-multi", "translate": true},
+        {"name": "fast", "provider": "groq"}
+    ]"#;
+    let list = profiles::parse_profiles(json).unwrap();
+    assert_eq!(list.len(), 3);
+    assert!(list[1].translate);
+    assert_eq!(list[2].provider.as_deref(), Some("groq"));
+    assert!(!list[0].translate);
+}
+
+#[test]
+fn parse_profiles_rejects_bad_names() {
+    assert!(profiles::parse_profiles(r#"[{"name": ""}]"#).is_err());
+    assert!(profiles::parse_profiles(r#"[{"name": "has space"}]"#).is_err());
+    assert!(profiles::parse_profiles(r#"[{"name": "a"}, {"name": "a"}]"#).is_err());
+}
+
+#[test]
+fn parse_profiles_rejects_unknown_provider_and_language() {
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "provider": "nope"}]"#).is_err());
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "language": "xx"}]"#).is_err());
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "summarize": true}]"#).is_err());
+}
+
+#[test]
+fn parse_profiles_accepts_custom_providers() {
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "provider": "custom"}]"#).is_ok());
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "provider": "local-custom"}]"#).is_ok());
+}
+
+#[test]
+fn find_profile_by_name() {
+    let list = profiles::parse_profiles(r#"[{"name": "a"}, {"name": "b"}]"#).unwrap();
+    assert_eq!(
+        profiles::find(&list, "b").map(|p| p.name.as_str()),
+        Some("b")
+    );
+    assert!(profiles::find(&list, "c").is_none());
+}
+
+#[test]
+fn parse_profiles_history_mode() {
+    use crate::db::HistoryMode;
+    let list =
+        profiles::parse_profiles(r#"[{"name": "secrets", "history": "off"}, {"name": "plain"}]"#)
+            .unwrap();
+    assert_eq!(list[0].history, Some(HistoryMode::Off));
+    assert_eq!(list[1].history, None);
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "history": "some"}]"#).is_err());
+}
+
+#[test]
+fn parse_profiles_checks_templates() {
+    let list =
+        profiles::parse_profiles(r#"[{"name": "journal", "template": "- [{time}] {text}"}]"#)
+            .unwrap();
+    assert_eq!(list[0].template.as_deref(), Some("- [{time}] {text}"));
+    assert!(profiles::parse_profiles(r#"[{"name": "x", "template": "{body}"}]"#).is_err());
+}
@@ -0,0 +1,84 @@
+use crate::provider_file::{self, ProviderFile};
+
+fn sample() -> ProviderFile {
+    ProviderFile {
+        version: provider_file::VERSION,
+        name: "Team Whisper".into(),
+        base_url: "https://whisper.example.com/v1".into(),
+        model: "large-v3".into(),
+        path: Some("/inference".into()),
+    }
+}
+
+#[test]
+fn roundtrip_json() {
+    let file = sample();
+    assert_eq!(ProviderFile::parse(&file.to_json()).unwrap(), file);
+}
+
+#[test]
+fn export_never_contains_key() {
+    let json = sample().to_json();
+    assert!(!json.contains("key"));
+}
+
+#[test]
+fn parse_rejects_api_key_and_bad_fields() {
+    assert!(
+        ProviderFile::parse(
+            r#"{"version":1,"name":"x","base_url":"https://a/v1","model":"m","api_key":"sk"}"#
+        )
+        .is_err()
+    );
+    assert!(
+        ProviderFile::parse(r#"{"version":2,"name":"x","base_url":"https://a/v1","model":"m"}"#)
+            .is_err()
+    );
+    assert!(
+        ProviderFile::parse(r#"{"version":1,"name":"x","base_url":"ftp://a","model":"m"}"#)
+            .is_err()
+    );
+    assert!(
+        ProviderFile::parse(r#"{"version":1,"name":"x","base_url":"https://a/v1","model":" "}"#)
+            .is_err()
+    );
+    assert!(
+        ProviderFile::parse(
+            r#"{"version":1,"name":"x","base_url":"https://a/v1","model":"m","path":"inference"}"#
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn path_is_optional() {
+    let file = ProviderFile::parse(
+        r#"{"version":1,"name":"x","base_url":"http://localhost:8080/v1","model":"m"}"#,
+    )
+    .unwrap();
+    assert_eq!(file.path, None);
+}
+
+#[test]
+fn file_name_is_slugged() {
+    assert_eq!(sample().file_name(), "team-whisper.whispercrabs-provider");
+    let mut odd = sample();
+    odd.name = "  ".into();
+    assert_eq!(odd.file_name(), "provider.whispercrabs-provider");
+}
+
+#[test]
+fn load_rejects_large_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("big.whispercrabs-provider");
+    std::fs::write(
+        &path,
+        " ".repeat(provider_file::MAX_FILE_BYTES as usize + 1),
+    )
+    .unwrap();
+    assert!(provider_file::load(&path).is_err());
+
+    let ok = dir.path().join("ok.whispercrabs-provider");
+    std::fs::write(&ok, sample().to_json()).unwrap();
+    assert_eq!(provider_file::load(&ok).unwrap(), sample());
+}
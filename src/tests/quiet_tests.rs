@@ -0,0 +1,50 @@
+use crate::quiet::{Dnd, format_schedule, is_quiet_at, parse_schedule};
+use chrono::NaiveTime;
+
+fn t(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+#[test]
+fn parse_and_format_schedules() {
+    let night = parse_schedule("22:00-08:00");
+    assert_eq!(night, Some((t(22, 0), t(8, 0))));
+    assert_eq!(format_schedule(night), "22:00-08:00");
+    assert_eq!(parse_schedule("off"), None);
+    assert_eq!(parse_schedule("25:00-08:00"), None);
+    // An empty window is no schedule
+    assert_eq!(parse_schedule("08:00-08:00"), None);
+    assert_eq!(format_schedule(None), "off");
+}
+
+#[test]
+fn schedule_wraps_past_midnight() {
+    let night = parse_schedule("22:00-08:00");
+    assert!(is_quiet_at(Dnd::Auto, night, t(23, 30)));
+    assert!(is_quiet_at(Dnd::Auto, night, t(2, 0)));
+    assert!(is_quiet_at(Dnd::Auto, night, t(22, 0)));
+    assert!(!is_quiet_at(Dnd::Auto, night, t(8, 0)));
+    assert!(!is_quiet_at(Dnd::Auto, night, t(12, 0)));
+
+    let afternoon = parse_schedule("13:00-15:00");
+    assert!(is_quiet_at(Dnd::Auto, afternoon, t(14, 0)));
+    assert!(!is_quiet_at(Dnd::Auto, afternoon, t(16, 0)));
+}
+
+#[test]
+fn dnd_overrides_schedule() {
+    let night = parse_schedule("22:00-08:00");
+    assert!(is_quiet_at(Dnd::On, night, t(12, 0)));
+    assert!(is_quiet_at(Dnd::On, None, t(12, 0)));
+    assert!(!is_quiet_at(Dnd::Off, night, t(23, 0)));
+    assert!(!is_quiet_at(Dnd::Auto, None, t(23, 0)));
+}
+
+#[test]
+fn dnd_round_trips() {
+    for dnd in [Dnd::Auto, Dnd::On, Dnd::Off] {
+        assert_eq!(Dnd::parse(dnd.as_str()), Some(dnd));
+    }
+    assert_eq!(Dnd::parse("maybe"), None);
+    assert_eq!(Dnd::default(), Dnd::Auto);
+}
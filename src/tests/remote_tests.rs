@@ -0,0 +1,101 @@
+use crate::activation::Activation;
+use crate::remote;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const TOKEN: &str = "0123456789abcdef";
+
+#[test]
+fn token_comparison() {
+    assert!(remote::token_matches(TOKEN, TOKEN));
+    assert!(!remote::token_matches("0123456789abcdeF", TOKEN));
+    assert!(!remote::token_matches("0123", TOKEN));
+    assert!(!remote::token_matches("", TOKEN));
+}
+
+#[test]
+fn requests_without_the_token_are_refused() {
+    for request in [
+        "POST /toggle HTTP/1.1\r\nHost: x\r\n\r\n",
+        "POST /toggle?token=wrong HTTP/1.1\r\n\r\n",
+        "GET /status HTTP/1.1\r\nAuthorization: Bearer wrong\r\n\r\n",
+    ] {
+        let (response, command) = remote::respond(request, TOKEN, "idle");
+        assert_eq!(response.status, 401, "{request:?}");
+        assert_eq!(command, None);
+    }
+}
+
+#[test]
+fn commands_need_post_and_the_token() {
+    let (response, command) = remote::respond(
+        &format!("POST /start?token={TOKEN} HTTP/1.1\r\n\r\n"),
+        TOKEN,
+        "idle",
+    );
+    assert_eq!(response.status, 200);
+    assert_eq!(command, Some(Activation::Start));
+
+    let (_, command) = remote::respond(
+        &format!("POST /toggle HTTP/1.1\r\nauthorization: Bearer {TOKEN}\r\n\r\n"),
+        TOKEN,
+        "recording",
+    );
+    assert_eq!(command, Some(Activation::Toggle));
+
+    let (response, command) = remote::respond(
+        &format!("GET /stop?token={TOKEN} HTTP/1.1\r\n\r\n"),
+        TOKEN,
+        "recording",
+    );
+    assert_eq!(response.status, 405);
+    assert_eq!(command, None);
+}
+
+#[test]
+fn status_and_page() {
+    let (response, command) = remote::respond(
+        &format!("GET /status?token={TOKEN} HTTP/1.1\r\n\r\n"),
+        TOKEN,
+        "recording",
+    );
+    assert_eq!(command, None);
+    assert_eq!(response.content_type, "application/json");
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["state"], "recording");
+
+    let (page, _) = remote::respond(
+        &format!("GET /?token={TOKEN} HTTP/1.1\r\n\r\n"),
+        TOKEN,
+        "idle",
+    );
+    assert_eq!(page.status, 200);
+    assert!(page.body.contains("<button"));
+
+    let (missing, _) = remote::respond(
+        &format!("GET /nope?token={TOKEN} HTTP/1.1\r\n\r\n"),
+        TOKEN,
+        "idle",
+    );
+    assert_eq!(missing.status, 404);
+    assert_eq!(remote::respond("garbage", TOKEN, "idle").0.status, 400);
+}
+
+#[test]
+fn pairing_url_carries_the_token() {
+    assert_eq!(
+        remote::pairing_url(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)), 8765, TOKEN),
+        format!("http://192.168.1.20:8765/?token={TOKEN}")
+    );
+    assert_eq!(
+        remote::pairing_url(IpAddr::V6(Ipv6Addr::LOCALHOST), 9000, TOKEN),
+        format!("http://[::1]:9000/?token={TOKEN}")
+    );
+}
+
+#[test]
+fn qr_code_is_square() {
+    let (size, modules) = remote::qr_modules("http://192.168.1.20:8765/?token=abc").unwrap();
+    assert!(size >= 21);
+    assert_eq!(modules.len(), size * size);
+    assert!(modules.iter().any(|m| *m) && modules.iter().any(|m| !*m));
+}
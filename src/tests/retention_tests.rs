@@ -0,0 +1,70 @@
+use crate::retention::{Retention, is_expired, parse_days, purge_audio};
+use std::time::{Duration, SystemTime};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[test]
+fn parse_days_accepts_positive_numbers() {
+    assert_eq!(parse_days("7"), Some(7));
+    assert_eq!(parse_days(" 30 "), Some(30));
+    assert_eq!(parse_days("0"), None);
+    assert_eq!(parse_days("forever"), None);
+}
+
+#[test]
+fn audio_never_outlives_text() {
+    let r = |text_days, audio_days| Retention {
+        text_days,
+        audio_days,
+    };
+    assert_eq!(r(None, None).effective_audio_days(), None);
+    assert_eq!(r(None, Some(7)).effective_audio_days(), Some(7));
+    assert_eq!(r(Some(30), None).effective_audio_days(), Some(30));
+    assert_eq!(r(Some(30), Some(7)).effective_audio_days(), Some(7));
+    assert_eq!(r(Some(1), Some(7)).effective_audio_days(), Some(1));
+}
+
+#[test]
+fn is_expired_respects_window() {
+    assert!(!is_expired(DAY * 100, None));
+    assert!(!is_expired(DAY * 6, Some(7)));
+    assert!(is_expired(DAY * 7, Some(7)));
+}
+
+#[test]
+fn purge_audio_removes_old_recordings_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let now = SystemTime::now();
+    let write = |name: &str, age: Duration| {
+        let path = dir.path().join(name);
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_modified(now - age).unwrap();
+        path
+    };
+    let old = write("1.wav", DAY * 10);
+    let fresh = write("2.wav", DAY);
+    let other = write("notes.txt", DAY * 10);
+
+    assert_eq!(purge_audio(dir.path(), None, now), 0);
+    assert_eq!(purge_audio(dir.path(), Some(7), now), 1);
+    assert!(!old.exists());
+    assert!(fresh.exists());
+    assert!(other.exists());
+}
+
+#[test]
+fn run_keeps_recent_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = crate::db::Db::open(&dir.path().join("test.db")).unwrap();
+    db.insert("today").unwrap();
+    let retention = Retention {
+        text_days: Some(1),
+        audio_days: Some(1),
+    };
+    assert_eq!(
+        crate::retention::run(&db, dir.path(), retention),
+        Ok((0, 0))
+    );
+    assert_eq!(db.recent(10).unwrap().len(), 1);
+    assert!(db.ids_older_than(1).unwrap().is_empty());
+}
@@ -0,0 +1,23 @@
+use crate::review;
+
+#[test]
+fn final_text_is_trimmed_and_never_empty() {
+    assert_eq!(
+        review::final_text("  Meet at noon.\n").as_deref(),
+        Some("Meet at noon.")
+    );
+    assert_eq!(review::final_text(" \n "), None);
+}
+
+#[test]
+fn correction_only_when_the_text_changed() {
+    assert_eq!(
+        review::correction("Meet at noon.", " Meet at noon.\n"),
+        None
+    );
+    assert_eq!(
+        review::correction("Meet at new.", "Meet at noon.").as_deref(),
+        Some("Meet at noon.")
+    );
+    assert_eq!(review::correction("Meet at noon.", ""), None);
+}
@@ -0,0 +1,101 @@
+use crate::rules::{self, Context, Rule};
+use chrono::NaiveTime;
+
+fn ctx(ssid: Option<&str>, h: u32, m: u32, weekday: u8) -> Context {
+    Context {
+        ssid: ssid.map(String::from),
+        ssid_known: true,
+        time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+        weekday,
+    }
+}
+
+fn rule(provider: &str) -> Rule {
+    Rule {
+        provider: provider.into(),
+        ssid_in: vec![],
+        ssid_not_in: vec![],
+        hours: None,
+        days: vec![],
+    }
+}
+
+#[test]
+fn parse_rules_roundtrip() {
+    let json = r#"[{"provider":"local-base","ssid_not_in":["Home"]},{"provider":"groq","hours":"09:00-17:00","days":[1,2,3,4,5]}]"#;
+    let parsed = rules::parse_rules(json).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[0].ssid_not_in, vec!["Home".to_string()]);
+    assert_eq!(parsed[1].days, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn parse_rules_rejects_bad_input() {
+    assert!(rules::parse_rules("not json").is_err());
+    assert!(rules::parse_rules(r#"[{"provider":""}]"#).is_err());
+    assert!(rules::parse_rules(r#"[{"provider":"groq","hours":"9-5"}]"#).is_err());
+    assert!(rules::parse_rules(r#"[{"provider":"groq","days":[0]}]"#).is_err());
+}
+
+#[test]
+fn untrusted_ssid_matches_only_on_unknown_wifi() {
+    let mut r = rule("local-base");
+    r.ssid_not_in = vec!["Home".into()];
+    let rules = [r];
+    assert_eq!(
+        rules::evaluate(&rules, &ctx(Some("Cafe"), 12, 0, 1)),
+        Some("local-base")
+    );
+    assert_eq!(rules::evaluate(&rules, &ctx(Some("Home"), 12, 0, 1)), None);
+    assert_eq!(rules::evaluate(&rules, &ctx(None, 12, 0, 1)), None);
+}
+
+#[test]
+fn unknown_network_counts_as_untrusted() {
+    let mut untrusted = rule("local-base");
+    untrusted.ssid_not_in = vec!["Home".into()];
+    let mut trusted = rule("groq");
+    trusted.ssid_in = vec!["Home".into()];
+    let rules = [trusted, untrusted];
+    let mut stale = ctx(Some("Home"), 12, 0, 1);
+    stale.ssid_known = false;
+    assert_eq!(rules::evaluate(&rules, &stale), Some("local-base"));
+    assert_eq!(
+        rules::evaluate(&rules, &ctx(Some("Home"), 12, 0, 1)),
+        Some("groq")
+    );
+}
+
+#[test]
+fn hours_window_wraps_midnight() {
+    let mut r = rule("local-tiny");
+    r.hours = Some("18:00-08:00".into());
+    let rules = [r];
+    assert!(rules::evaluate(&rules, &ctx(None, 23, 30, 3)).is_some());
+    assert!(rules::evaluate(&rules, &ctx(None, 7, 59, 3)).is_some());
+    assert!(rules::evaluate(&rules, &ctx(None, 8, 0, 3)).is_none());
+    assert!(rules::evaluate(&rules, &ctx(None, 12, 0, 3)).is_none());
+}
+
+#[test]
+fn first_matching_rule_wins() {
+    let mut weekend = rule("local-base");
+    weekend.days = vec![6, 7];
+    let rules = [weekend, rule("groq")];
+    assert_eq!(
+        rules::evaluate(&rules, &ctx(None, 10, 0, 6)),
+        Some("local-base")
+    );
+    assert_eq!(rules::evaluate(&rules, &ctx(None, 10, 0, 2)), Some("groq"));
+}
+
+#[test]
+fn nmcli_ssid_escapes_are_undone() {
+    let out = "no:Neighbour\nyes:Cafe\\: Guest\\\\5G\n";
+    assert_eq!(
+        rules::parse_nmcli_ssid(out).as_deref(),
+        Some("Cafe: Guest\\5G")
+    );
+    assert_eq!(rules::parse_nmcli_ssid("no:Other\n"), None);
+    assert_eq!(rules::parse_nmcli_ssid("yes:\n"), None);
+}
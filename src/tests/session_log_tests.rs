@@ -0,0 +1,31 @@
+use crate::session_log::{Entry, MAX_ENTRIES, excess, joined};
+use chrono::{Local, TimeZone};
+
+fn at(h: u32, m: u32, s: u32) -> chrono::DateTime<Local> {
+    Local.with_ymd_and_hms(2026, 3, 14, h, m, s).unwrap()
+}
+
+#[test]
+fn entry_heading_shows_time_and_provider() {
+    let entry = Entry::new(at(9, 5, 7), "Hello there", "groq");
+    assert_eq!(entry.time, "09:05:07");
+    assert_eq!(entry.heading(), "09:05:07 \u{00b7} groq");
+    assert_eq!(Entry::new(at(9, 5, 7), "Hi", "").heading(), "09:05:07");
+}
+
+#[test]
+fn excess_keeps_the_newest_entries() {
+    assert_eq!(excess(0), 0);
+    assert_eq!(excess(MAX_ENTRIES), 0);
+    assert_eq!(excess(MAX_ENTRIES + 3), 3);
+}
+
+#[test]
+fn copy_all_joins_texts_oldest_first() {
+    let entries = [
+        Entry::new(at(9, 0, 0), "First", "groq"),
+        Entry::new(at(9, 1, 0), "Second", "local-base"),
+    ];
+    assert_eq!(joined(&entries), "First\n\nSecond");
+    assert_eq!(joined(&[]), "");
+}
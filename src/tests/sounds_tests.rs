@@ -0,0 +1,70 @@
+use crate::db::Db;
+use crate::sounds::{Sound, SoundEvent, SoundScheme, parse_volume};
+use std::path::PathBuf;
+
+#[test]
+fn parse_sounds() {
+    assert_eq!(Sound::parse("off"), Some(Sound::Off));
+    assert_eq!(Sound::parse(" Chime "), Some(Sound::Chime));
+    assert_eq!(Sound::parse("HIGH"), Some(Sound::High));
+    assert_eq!(Sound::parse("low"), Some(Sound::Low));
+    assert_eq!(
+        Sound::parse("/home/me/ding.wav"),
+        Some(Sound::File(PathBuf::from("/home/me/ding.wav")))
+    );
+    assert_eq!(Sound::parse("  "), None);
+    for sound in Sound::BUILT_IN {
+        assert_eq!(Sound::parse(&sound.as_setting()), Some(sound));
+    }
+}
+
+#[test]
+fn parse_volumes() {
+    assert_eq!(parse_volume("50"), Some(50));
+    assert_eq!(parse_volume("75%"), Some(75));
+    assert_eq!(parse_volume("0"), Some(0));
+    assert_eq!(parse_volume("150"), None);
+    assert_eq!(parse_volume("loud"), None);
+}
+
+#[test]
+fn legacy_flag_only_turns_on_the_completion_chime() {
+    let scheme = SoundScheme::from_lookup(true, |_| None);
+    assert_eq!(scheme.sound(SoundEvent::Success), &Sound::Chime);
+    assert_eq!(scheme.sound(SoundEvent::RecordStart), &Sound::Off);
+    assert_eq!(scheme.sound(SoundEvent::Error), &Sound::Off);
+    assert_eq!(
+        SoundScheme::from_lookup(false, |_| None),
+        SoundScheme::default()
+    );
+}
+
+#[test]
+fn env_sets_sound_and_volume_per_event() {
+    let scheme = SoundScheme::from_lookup(true, |key| match key {
+        "SOUND_RECORD_START" => Some("high".into()),
+        "SOUND_RECORD_START_VOLUME" => Some("25".into()),
+        "SOUND_SUCCESS" => Some("off".into()),
+        "SOUND_ERROR" => Some("/tmp/error.wav".into()),
+        _ => None,
+    });
+    assert_eq!(scheme.sound(SoundEvent::RecordStart), &Sound::High);
+    assert_eq!(scheme.volume(SoundEvent::RecordStart), 25);
+    assert_eq!(scheme.sound(SoundEvent::Success), &Sound::Off);
+    assert_eq!(
+        scheme.sound(SoundEvent::Error),
+        &Sound::File(PathBuf::from("/tmp/error.wav"))
+    );
+    assert_eq!(scheme.volume(SoundEvent::Error), 100);
+}
+
+#[test]
+fn saved_settings_override_env() {
+    let db = Db::open_in_memory().unwrap();
+    db.set_setting("sound_record_stop", "low").unwrap();
+    db.set_setting("sound_record_stop_volume", "50").unwrap();
+    let scheme = SoundScheme::from_lookup(true, |_| None).load(&db);
+    assert_eq!(scheme.sound(SoundEvent::RecordStop), &Sound::Low);
+    assert_eq!(scheme.volume(SoundEvent::RecordStop), 50);
+    assert_eq!(scheme.sound(SoundEvent::Success), &Sound::Chime);
+}
@@ -0,0 +1,42 @@
+use crate::split;
+use std::path::Path;
+use std::time::Duration;
+
+#[test]
+fn minutes_parse_within_range() {
+    assert_eq!(split::parse_minutes("10"), Some(10));
+    assert_eq!(split::parse_minutes(" 5 "), Some(5));
+    assert_eq!(split::parse_minutes("off"), None);
+    assert_eq!(split::parse_minutes("0"), None);
+    assert_eq!(split::parse_minutes("121"), None);
+}
+
+#[test]
+fn state_round_trips_through_parse() {
+    assert_eq!(split::state(None), "off");
+    for minutes in split::CHOICES {
+        assert_eq!(
+            split::parse_minutes(&split::state(Some(*minutes))),
+            Some(*minutes)
+        );
+    }
+}
+
+#[test]
+fn interval_is_in_minutes() {
+    assert_eq!(split::interval(15), Duration::from_secs(900));
+}
+
+#[test]
+fn parts_sort_in_recording_order() {
+    let dir = Path::new("/data/audio");
+    let second = split::part_path(dir, "20260301-091500", 2);
+    let tenth = split::part_path(dir, "20260301-091500", 10);
+    assert_eq!(second.parent(), Some(split::parts_dir(dir).as_path()));
+    assert!(
+        second
+            .to_string_lossy()
+            .ends_with("20260301-091500-part002.wav")
+    );
+    assert!(second < tenth);
+}
@@ -0,0 +1,123 @@
+use crate::stacking::{
+    Corner, DEFAULT_MARGIN, Hint, Placement, monitor_id, monitor_label, parse_margin, wmctrl_args,
+};
+
+#[test]
+fn wmctrl_targets_the_exact_title() {
+    assert_eq!(
+        wmctrl_args("WhisperCrabs", Hint::Above, true),
+        ["-F", "-r", "WhisperCrabs", "-b", "add,above"]
+    );
+    assert_eq!(
+        wmctrl_args("WhisperCrabs", Hint::Sticky, false),
+        ["-F", "-r", "WhisperCrabs", "-b", "remove,sticky"]
+    );
+}
+
+#[test]
+fn corners_round_trip_through_settings() {
+    for corner in Corner::ALL {
+        assert_eq!(Corner::parse(corner.as_str()), Some(corner));
+    }
+    assert_eq!(Corner::parse(" Top-Left "), Some(Corner::TopLeft));
+    assert_eq!(Corner::parse("middle"), None);
+    assert_eq!(Corner::default(), Corner::BottomRight);
+}
+
+#[test]
+fn corners_name_their_edges() {
+    assert!(Corner::TopLeft.is_top() && Corner::TopLeft.is_left());
+    assert!(Corner::TopRight.is_top() && !Corner::TopRight.is_left());
+    assert!(!Corner::BottomLeft.is_top() && Corner::BottomLeft.is_left());
+    assert!(!Corner::BottomRight.is_top() && !Corner::BottomRight.is_left());
+}
+
+#[test]
+fn margins_take_one_or_two_values() {
+    assert_eq!(parse_margin("24"), Some((24, 24)));
+    assert_eq!(parse_margin("16, 48px"), Some((16, 48)));
+    assert_eq!(parse_margin("-5"), None);
+    assert_eq!(parse_margin("10,x"), None);
+    assert_eq!(parse_margin("99999"), None);
+}
+
+#[test]
+fn config_defaults_to_the_bottom_right_corner() {
+    let config = crate::config::Config::from_lookup(|_| None);
+    assert_eq!(config.screen_corner, None);
+    assert_eq!(config.screen_margin, (DEFAULT_MARGIN, DEFAULT_MARGIN));
+}
+
+const MONITOR: (i32, i32, i32, i32) = (1920, 0, 1920, 1080);
+
+#[test]
+fn placement_measures_from_the_nearest_corner() {
+    let top_left = Placement::from_geometry((1950, 60, 88, 100), MONITOR);
+    assert_eq!(top_left.corner, Corner::TopLeft);
+    assert_eq!(top_left.offset, (30, 60));
+
+    let bottom_right = Placement::from_geometry((3720, 940, 88, 100), MONITOR);
+    assert_eq!(bottom_right.corner, Corner::BottomRight);
+    assert_eq!(bottom_right.offset, (32, 40));
+}
+
+#[test]
+fn placement_round_trips_to_the_same_position() {
+    for window in [
+        (1950, 60, 88, 100),
+        (3720, 940, 88, 100),
+        (2000, 900, 88, 100),
+    ] {
+        let (x, y, w, h) = window;
+        let placement = Placement::from_geometry(window, MONITOR);
+        assert_eq!(placement.position((w, h), MONITOR), (x, y));
+    }
+}
+
+#[test]
+fn placement_keeps_the_window_on_a_smaller_monitor() {
+    let far = Placement {
+        corner: Corner::TopLeft,
+        offset: (3000, 2000),
+    };
+    assert_eq!(far.position((88, 100), (0, 0, 1280, 720)), (1192, 620));
+}
+
+#[test]
+fn placement_follows_its_corner_when_the_window_grows() {
+    let placement = Placement::DEFAULT;
+    let (x, y) = placement.position((120, 136), MONITOR);
+    assert_eq!((x + 120, y + 136), (1920 + 1920 - 12, 1080 - 40));
+}
+
+#[test]
+fn placement_round_trips_through_settings() {
+    let placement = Placement {
+        corner: Corner::BottomLeft,
+        offset: (16, 48),
+    };
+    assert_eq!(
+        Placement::parse(placement.corner.as_str(), &placement.offset_str()),
+        Some(placement)
+    );
+    assert_eq!(Placement::parse("bottom-left", "16"), None);
+    assert_eq!(Placement::parse("bottom-left", "-5,10"), None);
+    assert_eq!(Placement::parse("middle", "0,0"), None);
+}
+
+#[test]
+fn monitors_are_remembered_by_connector() {
+    assert_eq!(monitor_id(1, Some("HDMI-A-1")), "HDMI-A-1");
+    assert_eq!(monitor_id(1, None), "#2");
+    assert_eq!(monitor_id(0, Some("")), "#1");
+}
+
+#[test]
+fn monitor_labels_name_model_and_connector() {
+    assert_eq!(
+        monitor_label(0, Some("DP-1"), Some("DELL U2720Q")),
+        "DELL U2720Q (DP-1)"
+    );
+    assert_eq!(monitor_label(1, Some("eDP-1"), None), "Monitor 2: eDP-1");
+    assert_eq!(monitor_label(2, None, None), "Monitor 3");
+}
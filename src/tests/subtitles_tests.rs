@@ -0,0 +1,33 @@
+use crate::local_stt::Segment;
+use crate::subtitles::{srt_timestamp, to_srt};
+
+#[test]
+fn srt_timestamp_format() {
+    assert_eq!(srt_timestamp(0), "00:00:00,000");
+    assert_eq!(srt_timestamp(3_723_045), "01:02:03,045");
+    assert_eq!(srt_timestamp(-5), "00:00:00,000");
+}
+
+#[test]
+fn to_srt_numbers_cues() {
+    let segments = vec![
+        Segment {
+            start_ms: 0,
+            end_ms: 1500,
+            text: " Hello.".into(),
+            confidence: None,
+        },
+        Segment {
+            start_ms: 1500,
+            end_ms: 61_000,
+            text: "Bye.".into(),
+            confidence: Some(0.9),
+        },
+    ];
+    assert_eq!(
+        to_srt(&segments),
+        "1\n00:00:00,000 --> 00:00:01,500\nHello.\n\n\
+         2\n00:00:01,500 --> 00:01:01,000\nBye.\n\n"
+    );
+    assert_eq!(to_srt(&[]), "");
+}
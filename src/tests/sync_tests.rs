@@ -0,0 +1,56 @@
+use crate::sync::{self, SyncEntry};
+
+fn entry(created_at: &str, text: &str) -> SyncEntry {
+    SyncEntry {
+        created_at: created_at.into(),
+        language: Some("en".into()),
+        text: text.into(),
+    }
+}
+
+#[test]
+fn content_hash_is_stable() {
+    // FNV-1a test vectors: the hash must not change between releases or machines
+    assert_eq!(sync::content_hash(""), "cbf29ce484222325");
+    assert_eq!(sync::content_hash("a"), "af63dc4c8601ec8c");
+    assert_ne!(sync::content_hash("hello"), sync::content_hash("hello "));
+}
+
+#[test]
+fn file_name_is_timestamp_and_hash() {
+    let e = entry("2026-03-09 14:05:07", "hello");
+    assert_eq!(
+        sync::file_name(&e),
+        format!("20260309-140507-{}.json", sync::content_hash("hello"))
+    );
+}
+
+#[test]
+fn parses_exports_and_single_entries() {
+    let export = r#"[
+        {"id": 3, "created_at": "2026-03-09 14:05:07", "language": "en", "text": "one"},
+        {"id": 4, "created_at": "2026-03-09 14:06:00", "language": null, "text": "two"}
+    ]"#;
+    let entries = sync::parse_entries(export).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].language, None);
+
+    let single = r#"{"created_at": "2026-03-09 14:05:07", "text": "one"}"#;
+    assert_eq!(sync::parse_entries(single).unwrap()[0].text, "one");
+
+    assert!(sync::parse_entries("id,text").is_err());
+}
+
+#[test]
+fn folder_roundtrip_skips_partial_and_foreign_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let second = entry("2026-03-09 14:06:00", "second");
+    let first = entry("2026-03-09 14:05:07", "first");
+    sync::write_entry(dir.path(), &second).unwrap();
+    sync::write_entry(dir.path(), &first).unwrap();
+    std::fs::write(dir.path().join(".20260309-150000-x.json.part"), "{").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "hi").unwrap();
+    std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+
+    assert_eq!(sync::read_dir(dir.path()).unwrap(), vec![first, second]);
+}
@@ -0,0 +1,63 @@
+use crate::template::{Vars, render, validate};
+use chrono::NaiveDate;
+
+fn vars(text: &str) -> Vars<'_> {
+    Vars {
+        text,
+        provider: "groq",
+        language: Some("de"),
+        time: NaiveDate::from_ymd_opt(2026, 3, 9)
+            .unwrap()
+            .and_hms_opt(14, 5, 30)
+            .unwrap(),
+    }
+}
+
+#[test]
+fn variables_are_filled_in() {
+    assert_eq!(
+        render("- [{timestamp}] {text}", &vars("Call Anna")),
+        "- [2026-03-09 14:05] Call Anna"
+    );
+    assert_eq!(
+        render(
+            "{date} {time} via {provider} ({language}): {text}",
+            &vars("Hallo")
+        ),
+        "2026-03-09 14:05 via groq (de): Hallo"
+    );
+    let untagged = Vars {
+        language: None,
+        ..vars("hi")
+    };
+    assert_eq!(render("[{language}] {text}", &untagged), "[] hi");
+}
+
+#[test]
+fn doubled_braces_are_literal() {
+    assert_eq!(render("{{{text}}}", &vars("x")), "{x}");
+    assert_eq!(render("}} {{", &vars("x")), "} {");
+}
+
+#[test]
+fn quotes_cover_every_line() {
+    assert_eq!(
+        render("> {text}\n", &vars("First point\nSecond point\n\nThird")),
+        "> First point\n> Second point\n>\n> Third\n"
+    );
+    assert_eq!(
+        render("Notes:\n> {text}", &vars("a\nb")),
+        "Notes:\n> a\n> b"
+    );
+    assert_eq!(render("- {text}", &vars("a\nb")), "- a\nb");
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    assert!(validate("- {text}").is_ok());
+    assert!(validate("{txt}").unwrap_err().contains("unknown"));
+    assert!(validate("{text").is_err());
+    assert!(validate("text}").is_err());
+    // Delivered unwrapped rather than half rendered
+    assert_eq!(render("{nope} {text}", &vars("plain")), "plain");
+}
@@ -0,0 +1,76 @@
+use crate::db::Db;
+use crate::transcription::{local_language, stored_language};
+
+#[test]
+fn english_only_models_stay_in_english() {
+    assert_eq!(local_language("local-base", false, "de"), "en");
+    assert_eq!(local_language("local-base-multi", true, "de"), "de");
+}
+
+#[test]
+fn custom_models_follow_the_chosen_language() {
+    assert_eq!(
+        local_language(crate::config::CUSTOM_LOCAL_MODEL, true, "fr"),
+        "fr"
+    );
+    assert_eq!(local_language("vosk-small-en", false, "fr"), "en");
+}
+
+#[test]
+fn stored_language_falls_back_to_auto() {
+    let db = Db::open_in_memory().unwrap();
+    assert_eq!(stored_language(&db), crate::config::DEFAULT_STT_LANGUAGE);
+    db.set_setting("stt_language", "xx-unknown").unwrap();
+    assert_eq!(stored_language(&db), crate::config::DEFAULT_STT_LANGUAGE);
+    db.set_setting("stt_language", "de").unwrap();
+    assert_eq!(stored_language(&db), "de");
+}
+
+#[test]
+fn env_key_only_goes_to_its_own_endpoint() {
+    let config = crate::config::Config::from_lookup(|key| match key {
+        "API_BASE_URL" => Some("https://openrouter.ai/api/v1/".into()),
+        "API_KEY" => Some("sk-env".into()),
+        _ => None,
+    });
+    let db = Db::open_in_memory().unwrap();
+    let key = |db: &Db, id: &str| {
+        crate::transcription::api_backends(db, &config)
+            .into_iter()
+            .find(|b| b.provider == id)
+            .map(|b| b.api_key)
+    };
+    assert_eq!(key(&db, "openrouter"), Some(Some("sk-env".into())));
+    // Without a stored key of its own, another preset isn't offered at all
+    assert_eq!(key(&db, "groq"), None);
+    db.set_setting("api_key_groq", "gsk-stored").unwrap();
+    assert_eq!(key(&db, "groq"), Some(Some("gsk-stored".into())));
+}
+
+#[test]
+fn cloud_uploads_need_consent() {
+    use crate::transcription::{Route, ensure_consent};
+    let db = Db::open_in_memory().unwrap();
+    let backend = |url: &str| {
+        Route::Api(crate::auto::Backend {
+            provider: "custom".into(),
+            base_url: url.into(),
+            api_key: None,
+            model: "whisper-1".into(),
+            path: crate::config::DEFAULT_TRANSCRIPTION_PATH.into(),
+        })
+    };
+    let cloud = backend("https://api.groq.com/openai/v1");
+    assert_eq!(
+        ensure_consent(&db, &cloud),
+        Err("Cloud upload not yet approved; open the app once to allow api.groq.com".into())
+    );
+    db.set_setting("cloud_consent_api.groq.com", "allow")
+        .unwrap();
+    assert_eq!(ensure_consent(&db, &cloud), Ok(()));
+    // Loopback servers never needed consent
+    assert_eq!(
+        ensure_consent(&db, &backend("http://localhost:11434/v1")),
+        Ok(())
+    );
+}
@@ -0,0 +1,28 @@
+use crate::activation::ActivationSource;
+use crate::tray::{TraySource, icon_name, status_text};
+
+#[test]
+fn icon_follows_the_app_state() {
+    assert_eq!(icon_name("idle"), "audio-input-microphone");
+    assert_eq!(icon_name("recording"), "media-record");
+    assert_eq!(icon_name("processing"), "view-refresh");
+    assert_eq!(icon_name("speaking"), "audio-volume-high");
+    assert_eq!(icon_name("unknown"), "audio-input-microphone");
+}
+
+#[test]
+fn tooltip_names_the_app_state() {
+    assert_eq!(status_text("idle"), "Ready");
+    assert_eq!(status_text("recording"), "Recording");
+    assert_eq!(status_text("processing"), "Transcribing");
+}
+
+#[test]
+fn menu_actions_are_only_available_once_started() {
+    let source = TraySource::new(true);
+    assert_eq!(source.name(), "tray");
+    assert!(source.take_actions().is_none());
+    // State changes before start are kept for the icon
+    source.state_changed("recording");
+    source.set_button_visible(false);
+}
@@ -0,0 +1,70 @@
+use crate::config::Config;
+use crate::db::Db;
+use crate::transcription::api_request_options;
+use crate::vocabulary::{MAX_PROMPT_LEN, MAX_TERMS, parse, prompt, save, stored};
+
+fn terms(list: &[&str]) -> Vec<String> {
+    list.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn terms_are_split_trimmed_and_deduplicated() {
+    assert_eq!(
+        parse("Kubernetes\n  WhisperCrabs , evdev\n\nkubernetes\nPTT"),
+        terms(&["Kubernetes", "WhisperCrabs", "evdev", "PTT"])
+    );
+    assert!(parse(" \n,\n").is_empty());
+    assert_eq!(parse("bad\u{0}term\nok"), terms(&["ok"]));
+}
+
+#[test]
+fn term_count_is_capped() {
+    let text: Vec<String> = (0..MAX_TERMS + 20).map(|i| format!("term{i}")).collect();
+    assert_eq!(parse(&text.join("\n")).len(), MAX_TERMS);
+}
+
+#[test]
+fn prompt_lists_terms_after_the_configured_prompt() {
+    let list = terms(&["Kubernetes", "evdev"]);
+    assert_eq!(prompt(None, &list).as_deref(), Some("Kubernetes, evdev."));
+    assert_eq!(
+        prompt(Some("Meeting notes."), &list).as_deref(),
+        Some("Meeting notes. Kubernetes, evdev.")
+    );
+    assert_eq!(
+        prompt(Some("Meeting notes."), &[]).as_deref(),
+        Some("Meeting notes.")
+    );
+    assert_eq!(prompt(Some("  "), &[]), None);
+    assert_eq!(prompt(None, &[]), None);
+}
+
+#[test]
+fn prompt_stays_within_the_limit() {
+    let list: Vec<String> = (0..MAX_TERMS).map(|i| format!("longer-term-{i}")).collect();
+    let text = prompt(None, &list).unwrap();
+    assert!(text.len() <= MAX_PROMPT_LEN);
+    assert!(text.starts_with("longer-term-0, longer-term-1,"));
+    assert!(text.ends_with('.'));
+}
+
+#[test]
+fn saved_terms_round_trip() {
+    let db = Db::open_in_memory().unwrap();
+    assert!(stored(&db).is_empty());
+    save(&db, &terms(&["Kubernetes", "evdev"])).unwrap();
+    assert_eq!(stored(&db), terms(&["Kubernetes", "evdev"]));
+    save(&db, &[]).unwrap();
+    assert!(stored(&db).is_empty());
+}
+
+#[test]
+fn vocabulary_goes_to_providers_that_take_a_prompt() {
+    let config = Config::from_lookup(|_| None);
+    let list = terms(&["Kubernetes"]);
+    let groq = api_request_options(&config, "groq", "", "auto", false, &list).unwrap();
+    assert_eq!(groq.prompt.as_deref(), Some("Kubernetes."));
+    assert!(groq.keywords.is_empty());
+    let ollama = api_request_options(&config, "ollama", "", "auto", false, &list).unwrap();
+    assert_eq!(ollama.prompt, None);
+}
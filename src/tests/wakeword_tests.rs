@@ -0,0 +1,30 @@
+use crate::wakeword;
+use std::time::{Duration, Instant};
+
+#[test]
+fn first_detection_triggers() {
+    assert!(wakeword::should_trigger(None, Instant::now()));
+}
+
+#[test]
+fn detections_within_cooldown_are_one_utterance() {
+    let first = Instant::now();
+    assert!(!wakeword::should_trigger(
+        Some(first),
+        first + Duration::from_millis(300)
+    ));
+    assert!(wakeword::should_trigger(
+        Some(first),
+        first + wakeword::COOLDOWN
+    ));
+}
+
+#[test]
+fn start_without_model_file_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join(wakeword::DEFAULT_MODEL_FILE);
+    let err = wakeword::WakeWordListener::start(&missing)
+        .err()
+        .expect("listener must not start without a model");
+    assert!(err.contains("not found"), "unexpected error: {err}");
+}
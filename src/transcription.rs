@@ -0,0 +1,421 @@
+//! Transcription routing, shared by the window and the command line.
+//!
+//! Which backend a dictation goes to and what is sent with it: the API
+//! backends set up in settings, local models on disk, and each provider's
+//! request options. Nothing here touches GTK, so `whispercrabs transcribe`
+//! (see [`crate::cli`]) takes the same paths as the floating button.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::api::{AudioUpload, RequestOptions};
+use crate::auto::Backend;
+use crate::config::{self, Config, TranscriptionService};
+use crate::db::{Db, HistoryMode};
+use crate::local_stt::{LocalEngine, Transcript};
+
+/// Where a dictation is sent when it doesn't use the loaded provider.
+pub enum Route {
+    Api(Backend),
+    Local {
+        provider: String,
+        path: PathBuf,
+        multilingual: bool,
+    },
+}
+
+/// The provider chosen in settings, or the one the environment sets up.
+pub fn stored_provider(d: &Db, config: &Config) -> String {
+    d.get_setting("transcription_mode")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            if config.transcription_service == TranscriptionService::Local {
+                config::DEFAULT_LOCAL_MODEL.to_string()
+            } else {
+                "groq".to_string()
+            }
+        })
+}
+
+/// The dictation language chosen in settings.
+pub fn stored_language(d: &Db) -> String {
+    d.get_setting("stt_language")
+        .ok()
+        .flatten()
+        .filter(|l| config::is_known_language(l))
+        .unwrap_or_else(|| config::DEFAULT_STT_LANGUAGE.to_string())
+}
+
+/// What finished dictations leave in History, as chosen in settings.
+pub fn stored_history_mode(d: &Db) -> HistoryMode {
+    d.get_setting("history_mode")
+        .ok()
+        .flatten()
+        .and_then(|m| HistoryMode::parse(&m))
+        .unwrap_or_default()
+}
+
+/// Whether **Local only** is on, by policy or in settings.
+pub fn local_only(d: &Db, config: &Config) -> bool {
+    config.policy.local_only
+        || d.get_setting("local_only").ok().flatten().as_deref() == Some("true")
+}
+
+/// API backends a dictation may go to: presets with a usable key plus the
+/// saved custom endpoint, filtered by organization policy.
+pub fn api_backends(d: &Db, config: &Config) -> Vec<Backend> {
+    let mut backends = Vec::new();
+    for preset in config::API_PRESETS {
+        if !config.policy.allows(preset.id, None) {
+            continue;
+        }
+        let key = if preset.needs_key {
+            d.get_setting(&format!("api_key_{}", preset.id))
+                .ok()
+                .flatten()
+                .or_else(|| config.env_key_for(preset.base_url))
+        } else {
+            None
+        };
+        if preset.needs_key && key.is_none() {
+            continue;
+        }
+        backends.push(Backend {
+            provider: preset.id.to_string(),
+            base_url: preset.base_url.to_string(),
+            api_key: key,
+            model: crate::preferences::effective_model(
+                d.get_setting(&crate::preferences::model_setting(preset.id))
+                    .ok()
+                    .flatten()
+                    .as_deref(),
+                preset.default_model,
+            ),
+            path: config::DEFAULT_TRANSCRIPTION_PATH.to_string(),
+        });
+    }
+    if let Some(url) = d.get_setting("api_custom_url").ok().flatten()
+        && config.policy.allows("custom", Some(&url))
+    {
+        backends.push(Backend {
+            provider: "custom".to_string(),
+            api_key: d.get_setting("api_custom_key").ok().flatten(),
+            model: d
+                .get_setting("api_custom_model")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| config.api_model.clone()),
+            path: d
+                .get_setting("api_custom_path")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| config.api_path.clone()),
+            base_url: url,
+        });
+    }
+    backends
+}
+
+/// The route to `provider`, looked up in settings. `auto` takes the first
+/// API backend, without probing.
+pub fn route(d: &Db, config: &Config, provider: &str) -> Result<Route, String> {
+    if config::is_local_provider(provider) {
+        let custom = d
+            .get_setting("local_custom_model")
+            .ok()
+            .flatten()
+            .map(PathBuf::from);
+        return local_route(config, provider, custom.as_deref());
+    }
+    if config::find_preset(provider).is_some() && !config.policy.allows(provider, None) {
+        return Err("Blocked by policy".into());
+    }
+    let mut backends = api_backends(d, config).into_iter();
+    let backend = if provider == crate::auto::AUTO_PROVIDER {
+        backends.next()
+    } else {
+        backends.find(|b| b.provider == provider)
+    }
+    .ok_or_else(|| format!("Provider \"{provider}\" is not set up"))?;
+    crate::policy::ensure_network_allowed(&backend.base_url)?;
+    Ok(Route::Api(backend))
+}
+
+/// Whether uploads to `host` were allowed in the one-time consent dialog.
+pub fn cloud_consented(d: &Db, host: &str) -> bool {
+    d.get_setting(&format!("cloud_consent_{host}"))
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "allow")
+}
+
+/// Refuse a route that would upload audio to a cloud host nobody allowed
+/// yet. Only the window can ask, so paths without one (the daemon, the
+/// command line) fail here instead.
+pub fn ensure_consent(d: &Db, route: &Route) -> Result<(), String> {
+    match route {
+        Route::Api(backend) if !crate::policy::is_local_url(&backend.base_url) => {
+            let host = crate::policy::url_host(&backend.base_url);
+            if cloud_consented(d, host) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Cloud upload not yet approved; open the app once to allow {host}"
+                ))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The route to local model `provider`, with `custom` the user's own model
+/// file; an error when policy blocks it or the model isn't on disk.
+pub fn local_route(
+    config: &Config,
+    provider: &str,
+    custom: Option<&Path>,
+) -> Result<Route, String> {
+    if !config.policy.allows(provider, None) {
+        return Err("Blocked by policy".into());
+    }
+    let location = config::locate_model(
+        &config.models_dir,
+        &config.local_models_dir,
+        provider,
+        custom,
+    );
+    // Custom models may be multilingual; Vosk models have a fixed language
+    let multilingual = match config::find_local_model(provider) {
+        Some(m) => m.multilingual,
+        None => provider == config::CUSTOM_LOCAL_MODEL,
+    };
+    let path = match location {
+        Some(config::ModelLocation::Found(path)) => path,
+        Some(config::ModelLocation::Unavailable { .. }) => {
+            return Err("Models drive not available".into());
+        }
+        _ => return Err("Model not downloaded".into()),
+    };
+    Ok(Route::Local {
+        provider: provider.to_string(),
+        path,
+        multilingual,
+    })
+}
+
+/// The language to run local model `provider` in when `chosen` is picked:
+/// English-only models stay in English.
+pub fn local_language(provider: &str, multilingual: bool, chosen: &str) -> String {
+    match config::find_local_model(provider) {
+        Some(m) => config::effective_language(m, chosen).to_string(),
+        None if multilingual => chosen.to_string(),
+        None => "en".to_string(),
+    }
+}
+
+/// Endpoint path for an API dictation; translation swaps the standard
+/// transcription endpoint for the translation one.
+pub fn api_path_for(path: &str, translate: bool) -> String {
+    if translate && (path.is_empty() || path == config::DEFAULT_TRANSCRIPTION_PATH) {
+        config::TRANSLATION_PATH.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// What to send API provider `provider` (custom ones at endpoint `path`)
+/// besides the audio, from its [`crate::capabilities`]. `vocabulary` goes
+/// into the prompt and keyword boosts. Translation is refused up front for
+/// providers without the endpoint.
+pub fn api_request_options(
+    config: &Config,
+    provider: &str,
+    path: &str,
+    language: &str,
+    translate: bool,
+    vocabulary: &[String],
+) -> Result<RequestOptions, String> {
+    let caps = crate::capabilities::for_provider(provider, path);
+    dbg_log!(
+        "[API] {provider}: capabilities v{} {caps:?}",
+        crate::capabilities::REGISTRY_VERSION
+    );
+    if translate && !caps.translation {
+        let label = config::find_preset(provider).map_or("This provider", |p| p.label);
+        return Err(format!(
+            "{label} can't translate; use a multilingual local model"
+        ));
+    }
+    Ok(RequestOptions {
+        language: crate::capabilities::language_hint(&caps, language).map(str::to_string),
+        prompt: crate::vocabulary::prompt(config.api_prompt.as_deref(), vocabulary)
+            .filter(|_| caps.prompt),
+        keywords: if caps.keywords {
+            vocabulary.to_vec()
+        } else {
+            Vec::new()
+        },
+        timestamps: caps.timestamps,
+        max_upload_bytes: caps.max_upload_bytes,
+    })
+}
+
+/// Send audio to an OpenAI-compatible endpoint and wait for the result.
+pub fn transcribe_api(
+    base_url: &str,
+    path: &str,
+    api_key: &str,
+    model: &str,
+    audio: AudioUpload,
+    options: &RequestOptions,
+) -> Result<Transcript, String> {
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Async runtime error: {e}"))?;
+    rt.block_on(crate::api::transcribe(
+        base_url, path, api_key, model, audio, options,
+    ))
+}
+
+/// The local model last used, kept loaded for the next transcription.
+#[derive(Default)]
+pub struct EngineCache(Mutex<Option<(PathBuf, Arc<LocalEngine>)>>);
+
+impl EngineCache {
+    /// A cache holding `engine`, already loaded from `path`.
+    pub fn with_engine(path: &Path, engine: Arc<LocalEngine>) -> Self {
+        Self(Mutex::new(Some((path.to_path_buf(), engine))))
+    }
+
+    /// The engine of the model at `path`, loaded in place of the previous
+    /// one unless it is that one.
+    pub fn get(&self, path: &Path) -> Result<Arc<LocalEngine>, String> {
+        let mut slot = self
+            .0
+            .lock()
+            .map_err(|_| "Engine cache lock poisoned".to_string())?;
+        if let Some((loaded, engine)) = slot.as_ref()
+            && loaded == path
+        {
+            return Ok(Arc::clone(engine));
+        }
+        // Free the previous model before loading the next
+        *slot = None;
+        let engine = Arc::new(LocalEngine::load(path)?);
+        *slot = Some((path.to_path_buf(), Arc::clone(&engine)));
+        Ok(engine)
+    }
+}
+
+/// Transcribe `wav`, captured at `sample_rate`, through `route` in
+/// `language` (into English with `translate`) and wait for the result.
+/// Local models come from `engines`; `vocabulary` steers both kinds.
+#[allow(clippy::too_many_arguments)]
+pub fn transcribe(
+    config: &Config,
+    route: &Route,
+    engines: &EngineCache,
+    wav: Vec<u8>,
+    sample_rate: u32,
+    language: &str,
+    translate: bool,
+    vocabulary: &[String],
+) -> Result<Transcript, String> {
+    match route {
+        Route::Api(b) => {
+            let options = api_request_options(
+                config,
+                &b.provider,
+                &b.path,
+                language,
+                translate,
+                vocabulary,
+            )?;
+            transcribe_api(
+                &b.base_url,
+                &api_path_for(&b.path, translate),
+                b.api_key.as_deref().unwrap_or_default(),
+                &b.model,
+                AudioUpload::from_recording(wav),
+                &options,
+            )
+        }
+        Route::Local {
+            provider,
+            path,
+            multilingual,
+        } => {
+            let language = local_language(provider, *multilingual, language);
+            let mut params = config.whisper.clone();
+            params.translate = translate;
+            params.initial_prompt = crate::vocabulary::prompt(None, vocabulary);
+            engines.get(path)?.transcribe_with_segments(
+                &wav,
+                sample_rate,
+                &language,
+                &params,
+                |_| {},
+            )
+        }
+    }
+}
+
+/// Add `text` to History as `mode` allows, copying it to the sync folder
+/// and the auto-save folder when set; the new entry's id.
+pub fn store(
+    d: &Db,
+    text: &str,
+    language: Option<&str>,
+    mode: HistoryMode,
+) -> Result<Option<i64>, String> {
+    let Some(stored) = mode.stored_text(text) else {
+        return Ok(None);
+    };
+    let id = d
+        .insert_with_language(&stored, language)
+        .map_err(|e| e.to_string())?;
+    sync_new_entry(d, id, &stored, language);
+    autosave_new_entry(d, &stored);
+    Ok(Some(id))
+}
+
+/// Write a new History entry to the auto-save folder, when one is set.
+fn autosave_new_entry(db: &Db, text: &str) {
+    let Some((dir, layout)) = crate::autosave::stored(db) else {
+        return;
+    };
+    let now = chrono::Local::now().naive_local();
+    if let Err(e) = crate::autosave::write(&dir, layout, now, text) {
+        tracing::warn!("Auto-save failed: {e}");
+    }
+}
+
+/// Copy a new History entry into the sync folder, when one is set.
+fn sync_new_entry(db: &Db, id: i64, text: &str, language: Option<&str>) {
+    let Some(dir) = db.get_setting(crate::sync::SYNC_DIR_SETTING).ok().flatten() else {
+        return;
+    };
+    let Some(created_at) = db.created_at(id).ok().flatten() else {
+        return;
+    };
+    let entry = crate::sync::SyncEntry {
+        created_at,
+        language: language.map(str::to_string),
+        text: text.to_string(),
+    };
+    if let Err(e) = crate::sync::write_entry(Path::new(&dir), &entry) {
+        tracing::warn!("History sync failed: {e}");
+    }
+}
+
+/// The History database, unlocked with the keyring key when encrypted, for
+/// the frontends that can't ask for a passphrase.
+pub fn open_history(config: &Config) -> Result<Db, String> {
+    let key = crate::secrets::get(crate::secrets::HISTORY_KEY);
+    let result = if crate::db::opens_encrypted(&config.db_path, key.is_some()) {
+        let key = key.ok_or("History is encrypted and its key isn't in the keyring")?;
+        Db::open_encrypted(&config.db_path, &key)
+    } else {
+        Db::open(&config.db_path)
+    };
+    result.map_err(|e| format!("Can't open History: {e}"))
+}
@@ -0,0 +1,224 @@
+//! Tray icon — WhisperCrabs in the panel's system tray.
+//!
+//! A StatusNotifierItem (through `ksni`, pure D-Bus), shown by KDE, most
+//! wlroots bars, XFCE, and GNOME with the AppIndicator extension. Its menu
+//! records, stops, opens History, shows or hides the floating button, and
+//! quits; clicking the icon toggles recording. The icon shows the app state,
+//! so with the floating button hidden the tray alone tells you it's
+//! listening.
+//!
+//! The tray is an [`ActivationSource`]: Record and Stop arrive as
+//! activations like any other trigger's. Other items name an app action to
+//! run, delivered on the channel from [`TraySource::take_actions`].
+
+use crate::activation::{Activation, ActivationSource};
+use std::cell::{Cell, RefCell};
+use std::sync::mpsc;
+
+/// Settings keys of the tray icon and of hiding the floating button.
+pub const TRAY_SETTING: &str = "tray_icon";
+pub const HIDE_BUTTON_SETTING: &str = "hide_button";
+
+/// Freedesktop icon name for an app state (see [`ActivationSource::state_changed`]).
+pub fn icon_name(state: &str) -> &'static str {
+    match state {
+        "recording" => "media-record",
+        "processing" => "view-refresh",
+        "speaking" => "audio-volume-high",
+        _ => "audio-input-microphone",
+    }
+}
+
+/// Tooltip line for an app state.
+pub fn status_text(state: &str) -> &'static str {
+    match state {
+        "recording" => "Recording",
+        "processing" => "Transcribing",
+        "speaking" => "Speaking",
+        _ => "Ready",
+    }
+}
+
+/// The tray icon as an activation source.
+pub struct TraySource {
+    button_visible: Cell<bool>,
+    state: Cell<&'static str>,
+    actions: RefCell<Option<mpsc::Receiver<&'static str>>>,
+    #[cfg(target_os = "linux")]
+    handle: Option<ksni::Handle<Tray>>,
+}
+
+impl TraySource {
+    /// A tray whose "Show Floating Button" starts as `button_visible`.
+    pub fn new(button_visible: bool) -> Self {
+        Self {
+            button_visible: Cell::new(button_visible),
+            state: Cell::new("idle"),
+            actions: RefCell::new(None),
+            #[cfg(target_os = "linux")]
+            handle: None,
+        }
+    }
+
+    /// Names of the app actions picked in the tray menu, once after
+    /// [`ActivationSource::start`].
+    pub fn take_actions(&self) -> Option<mpsc::Receiver<&'static str>> {
+        self.actions.borrow_mut().take()
+    }
+
+    /// Tick or untick "Show Floating Button".
+    pub fn set_button_visible(&self, visible: bool) {
+        self.button_visible.set(visible);
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = &self.handle {
+            handle.update(|tray| tray.button_visible = visible);
+        }
+    }
+}
+
+impl ActivationSource for TraySource {
+    fn name(&self) -> &'static str {
+        "tray"
+    }
+
+    #[cfg(target_os = "linux")]
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        if let Some(handle) = self.handle.take() {
+            handle.shutdown();
+        }
+        let (activations, rx) = mpsc::channel();
+        let (actions, actions_rx) = mpsc::channel();
+        let service = ksni::TrayService::new(Tray {
+            state: self.state.get(),
+            button_visible: self.button_visible.get(),
+            activations,
+            actions,
+        });
+        self.handle = Some(service.handle());
+        service.spawn();
+        *self.actions.borrow_mut() = Some(actions_rx);
+        Ok(rx)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        Err("The tray icon is only supported on Linux".into())
+    }
+
+    fn state_changed(&self, state: &'static str) {
+        if self.state.replace(state) == state {
+            return;
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(handle) = &self.handle {
+            handle.update(|tray| tray.state = state);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for TraySource {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.shutdown();
+        }
+    }
+}
+
+/// What the tray service thread shows.
+#[cfg(target_os = "linux")]
+struct Tray {
+    state: &'static str,
+    button_visible: bool,
+    activations: mpsc::Sender<Activation>,
+    actions: mpsc::Sender<&'static str>,
+}
+
+#[cfg(target_os = "linux")]
+impl ksni::Tray for Tray {
+    fn id(&self) -> String {
+        "whispercrabs".into()
+    }
+
+    fn title(&self) -> String {
+        "WhisperCrabs".into()
+    }
+
+    fn icon_name(&self) -> String {
+        icon_name(self.state).into()
+    }
+
+    fn status(&self) -> ksni::Status {
+        if self.state == "recording" {
+            ksni::Status::NeedsAttention
+        } else {
+            ksni::Status::Active
+        }
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: "WhisperCrabs".into(),
+            description: status_text(self.state).into(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        let _ = self.activations.send(Activation::Toggle);
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        use ksni::menu::{CheckmarkItem, StandardItem};
+
+        let idle = self.state == "idle";
+        let recording = self.state == "recording";
+        vec![
+            StandardItem {
+                label: "Record".into(),
+                enabled: idle,
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.activations.send(Activation::Start);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Stop".into(),
+                enabled: recording,
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.activations.send(Activation::Stop);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            ksni::MenuItem::Separator,
+            StandardItem {
+                label: "History".into(),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.actions.send("show-history");
+                }),
+                ..Default::default()
+            }
+            .into(),
+            CheckmarkItem {
+                label: "Show Floating Button".into(),
+                checked: self.button_visible,
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.actions.send("show-button");
+                }),
+                ..Default::default()
+            }
+            .into(),
+            ksni::MenuItem::Separator,
+            StandardItem {
+                label: "Quit".into(),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.actions.send("quit");
+                }),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
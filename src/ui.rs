@@ -1,33 +1,21 @@
 use gtk4::gdk;
 use gtk4::glib;
 use gtk4::prelude::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
+use crate::api::AudioUpload;
 use crate::audio::Recorder;
 use crate::config::{self, Config, TranscriptionService, TtsProvider};
-use crate::db::Db;
-use crate::local_stt::LocalWhisper;
+use crate::db::{Db, Transcription};
+use crate::local_stt::LocalEngine;
+use crate::sounds::SoundEvent;
+use crate::transcription::{Route, api_path_for, api_request_options};
 use crate::tts::PiperTts;
 
 const MIC_SVG: &[u8] = include_bytes!("icons/microphone.svg");
-const NOTIFICATION_SOUND: &[u8] = include_bytes!("audio/notification.wav");
-
-fn play_notification() {
-    std::thread::spawn(|| {
-        use rodio::{Decoder, OutputStream, Sink};
-        use std::io::Cursor;
-        if let Ok((_stream, handle)) = OutputStream::try_default()
-            && let Ok(sink) = Sink::try_new(&handle)
-            && let Ok(source) = Decoder::new(Cursor::new(NOTIFICATION_SOUND))
-        {
-            sink.append(source);
-            sink.sleep_until_end();
-        }
-    });
-}
-
 const CSS: &str = r#"
     window.main-window {
         background-color: transparent;
@@ -72,6 +60,12 @@ const CSS: &str = r#"
         box-shadow: none;
         animation: pulse 1s ease-in-out infinite;
     }
+    .mic-btn.no-input,
+    .mic-btn.no-input:hover {
+        background-image: none;
+        background-color: #475569;
+        box-shadow: none;
+    }
     .mic-btn.processing,
     .mic-btn.processing:hover {
         background-image: none;
@@ -109,6 +103,52 @@ const CSS: &str = r#"
         letter-spacing: 1px;
         margin-top: 4px;
     }
+    .local-badge {
+        color: #bbf7d0;
+        background-color: rgba(21, 128, 61, 0.85);
+        font-size: 9px;
+        font-weight: 700;
+        letter-spacing: 1px;
+        border-radius: 4px;
+        padding: 1px 5px;
+    }
+    .wake-badge {
+        color: #bfdbfe;
+        background-color: rgba(29, 78, 216, 0.85);
+        font-size: 9px;
+        font-weight: 700;
+        letter-spacing: 1px;
+        border-radius: 4px;
+        padding: 1px 5px;
+    }
+    .dry-run-badge {
+        color: #fde68a;
+        background-color: rgba(180, 83, 9, 0.85);
+        font-size: 9px;
+        font-weight: 700;
+        letter-spacing: 1px;
+        border-radius: 4px;
+        padding: 1px 5px;
+    }
+    .safe-mode-badge {
+        color: #fecaca;
+        background-color: rgba(185, 28, 28, 0.85);
+        font-size: 9px;
+        font-weight: 700;
+        letter-spacing: 1px;
+        border-radius: 4px;
+        padding: 1px 5px;
+    }
+    window.recording-dot {
+        background-color: transparent;
+    }
+    .recording-dot-mark {
+        min-width: 18px;
+        min-height: 18px;
+        border-radius: 9999px;
+        background-color: #16a34a;
+        animation: pulse 1s ease-in-out infinite;
+    }
     .status-label {
         color: #e2e8f0;
         font-size: 12px;
@@ -117,6 +157,22 @@ const CSS: &str = r#"
         border-radius: 6px;
         padding: 3px 8px;
     }
+    window.main-window.light .status-label {
+        color: #0f172a;
+        background-color: rgba(248, 250, 252, 0.85);
+    }
+    window.main-window.light .brand-label {
+        color: rgba(15, 23, 42, 0.55);
+    }
+    window.live-captions {
+        background-color: rgba(0, 0, 0, 0.85);
+    }
+    .caption-text {
+        color: #ffffff;
+        font-size: 22px;
+        font-weight: 500;
+        padding: 8px 14px;
+    }
 "#;
 
 /// Show a status message inline. On macOS, also shows a dialog for errors.
@@ -124,6 +180,7 @@ fn show_status(label: &gtk4::Label, text: &str) {
     dbg_log!("[STATUS] {text}");
     label.set_label(text);
     label.set_opacity(1.0);
+    publish_status(label, text);
 
     #[cfg(target_os = "macos")]
     {
@@ -155,6 +212,45 @@ fn show_status(label: &gtk4::Label, text: &str) {
 /// Hide the status label
 fn hide_status(label: &gtk4::Label) {
     label.set_opacity(0.0);
+    publish_status(label, "");
+}
+
+/// Show a status message for a few seconds.
+fn flash_status(label: &gtk4::Label, text: &str) {
+    show_status(label, text);
+    let label = label.clone();
+    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+        hide_status(&label);
+    });
+}
+
+/// Mirror the status text into the `status` action's state (readable over
+/// D-Bus with `org.gtk.Actions.Describe`, announced by `Changed`). Without a
+/// visible window it is also shown as a desktop notification (outside quiet
+/// hours), replaced in place and withdrawn when the status clears.
+fn publish_status(label: &gtk4::Label, text: &str) {
+    let Some(app) = gtk4::gio::Application::default() else {
+        return;
+    };
+    if let Some(action) = app
+        .lookup_action("status")
+        .and_downcast::<gtk4::gio::SimpleAction>()
+    {
+        action.set_state(&text.to_variant());
+    }
+    let windowless = label
+        .root()
+        .and_downcast::<gtk4::Window>()
+        .is_some_and(|w| !w.is_visible());
+    if windowless {
+        if text.is_empty() {
+            app.withdraw_notification("status");
+        } else if !crate::quiet::is_quiet() {
+            let notification = gtk4::gio::Notification::new("WhisperCrabs");
+            notification.set_body(Some(text));
+            app.send_notification(Some("status"), &notification);
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -172,24 +268,376 @@ struct RuntimeState {
     api_base_url: String,    // active API base URL
     api_key: Option<String>, // active API key
     api_model: String,       // active API model
-    local_whisper: Option<Arc<LocalWhisper>>,
+    api_path: String,        // active transcription endpoint path
+    local_engine: Option<Arc<LocalEngine>>,
     downloading: bool,
+    download_cancel: Arc<std::sync::atomic::AtomicBool>, // set to abort the running model download
     tts_provider: TtsProvider,
     tts_voice: String,
     tts_engine: Option<Arc<PiperTts>>,
     tts_downloading: bool,
     tts_stop: Arc<std::sync::atomic::AtomicBool>,
+    rule_switching: bool,    // true while a profile rule drives the mode action
+    stt_language: String,    // ISO 639-1 code or "auto" (multilingual models)
+    vocabulary: Vec<String>, // custom terms steering recognition (vocabulary.rs)
+    auto_mode: bool,         // "auto" provider: route each dictation to the fastest backend
+    auto_route: Option<crate::auto::Backend>, // backend picked by the last probe round
+    auto_probing: bool,
+    custom_model: Option<std::path::PathBuf>, // user-provided model file ("local-custom")
+    pending_profile: Option<crate::profiles::Profile>, // one-shot config for the next dictation
+    profile_route: Option<Route>,             // provider override of the pending profile
+    history_mode: crate::db::HistoryMode,     // what finished dictations leave in History
+    label_speakers: bool,                     // tag speaker turns (tinydiarize models)
+    live_dictation: bool,                     // type words into the focused app while speaking
+    announce: bool,                           // speak each transcript to the screen reader
+    notify: bool,                             // desktop notification for each transcript
+    discard_recording: bool,                  // drop the running recording when it stops
+    dropped_audio: Option<(Vec<u8>, u32)>,    // a dropped file's WAV, taken in place of a recording
+    failed_audio: Option<(Vec<u8>, u32)>,     // WAV of the last failed dictation, for a retry
+    auto_paste: bool,                         // paste each transcript into the focused app
+    coaching: bool,                           // hint when dictations are too quiet or fast
+    last_tip: Option<std::time::Instant>,     // when the last coaching hint was shown
+    dry_run: bool,                            // preview outputs instead of delivering them
+    auto_split: Option<u32>,                  // minutes between parts of a long recording
+    split_session: u64,                       // bumped per recording; stale split timers stop
+    live_session: Option<crate::dictation::LiveSession>, // decoder of the running live dictation
+    monitor_dots: bool, // mirror the recording state as a dot on every monitor
+    store_audio: bool,  // keep each recording next to its History entry
+    sounds: crate::sounds::SoundScheme, // sound and volume per event
+    recording_dots: Option<RecordingDots>, // dots shown for the running recording
+    pause_media: bool,  // pause playing media players while recording
+    paused_media: Option<crate::media::PausedPlayers>, // players to resume when it stops
+    silence_notifications: bool, // desktop do-not-disturb while recording
+    review: bool,       // hold transcripts in an editable window before copying
+    silenced: Option<crate::silence::Silenced>, // lifted when the recording stops
+    meeting_window: Option<gtk4::Window>, // window of the running meeting
+    session_log: gtk4::gio::ListStore, // this session's transcripts (session_log::Entry)
+}
+
+/// Messages from a transcription worker thread.
+enum SttMsg {
+    /// A decoded segment (local models only), sent while decoding continues.
+    Segment(String),
+    /// The final transcript (or error) and the transcription latency.
+    Done(
+        Result<crate::local_stt::Transcript, String>,
+        std::time::Duration,
+    ),
+    /// The transcript went to post-processing (see [`post_process_relay`]).
+    PostProcessing,
+    /// Post-processing rewrote the transcript; this is the text before.
+    Raw(String),
+}
+
+/// Longest tail of in-progress text shown in the status label.
+const STATUS_PREVIEW_CHARS: usize = 40;
+
+/// Tail of the partial transcript, trimmed to fit the status label.
+fn status_preview(text: &str) -> String {
+    let text = text.trim();
+    let count = text.chars().count();
+    if count <= STATUS_PREVIEW_CHARS {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - STATUS_PREVIEW_CHARS).collect();
+    format!("\u{2026}{}", tail.trim_start())
 }
 
 pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
+    // Encrypted History opens with the keyring key, else asks for its passphrase
+    // A header that isn't plain SQLite is only taken for encryption when this
+    // build can decrypt and there's a key or marker; otherwise it's damage
+    let key = crate::secrets::get(crate::secrets::HISTORY_KEY);
+    if crate::db::opens_encrypted(&config.db_path, key.is_some()) {
+        match key.map(|key| Db::open_encrypted(&config.db_path, &key)) {
+            Some(Ok(db)) => build_main_window(app, config, db),
+            Some(Err(e)) if !crate::db::is_wrong_key_error(&e) => {
+                tracing::error!("Failed to open database: {e}");
+                show_db_error_dialog(app, config, &e);
+            }
+            _ => show_db_unlock_dialog(app, config),
+        }
+        return;
+    }
+    match Db::open(&config.db_path) {
+        Ok(db) => build_main_window(app, config, db),
+        Err(e) => {
+            tracing::error!("Failed to open database: {e}");
+            show_db_error_dialog(app, config, &e);
+        }
+    }
+}
+
+/// Run this session on an in-memory database instead of the one on disk,
+/// closing `dialog` once the main window is up; errors go to `failure`.
+fn continue_without_history(
+    app: &gtk4::Application,
+    config: &Arc<Config>,
+    dialog: &gtk4::Window,
+    failure: &gtk4::Label,
+) {
+    match Db::open_in_memory() {
+        Ok(db) => {
+            tracing::warn!("Running with an in-memory database; History won't be saved");
+            // Open the main window first so the application never runs out of windows
+            build_main_window(app, Arc::clone(config), db);
+            dialog.close();
+        }
+        Err(e) => {
+            failure.set_label(&format!("In-memory database failed: {e}"));
+            failure.set_visible(true);
+        }
+    }
+}
+
+/// Ask for the passphrase of an encrypted History at startup, or run this
+/// session without it.
+fn show_db_unlock_dialog(app: &gtk4::Application, config: Arc<Config>) {
+    let dialog = gtk4::Window::builder()
+        .application(app)
+        .title("WhisperCrabs")
+        .default_width(380)
+        .resizable(false)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let heading = gtk4::Label::new(Some("Unlock History"));
+    heading.add_css_class("heading");
+    heading.set_halign(gtk4::Align::Start);
+    vbox.append(&heading);
+
+    let body = gtk4::Label::new(Some(
+        "History is encrypted. Enter its passphrase, or continue without History \
+         for this session. If the passphrase is lost or the file is damaged, back \
+         it up and start with an empty History.",
+    ));
+    body.set_wrap(true);
+    body.set_halign(gtk4::Align::Start);
+    vbox.append(&body);
+
+    let passphrase = gtk4::PasswordEntry::new();
+    passphrase.set_show_peek_icon(true);
+    vbox.append(&passphrase);
+
+    let failure = gtk4::Label::new(None);
+    failure.add_css_class("error");
+    failure.set_wrap(true);
+    failure.set_visible(false);
+    vbox.append(&failure);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let quit_btn = gtk4::Button::with_label("Quit");
+    let memory_btn = gtk4::Button::with_label("Continue Without History");
+    let fresh_btn = gtk4::Button::with_label("Back Up and Start Fresh");
+    let unlock_btn = gtk4::Button::with_label("Unlock");
+    unlock_btn.add_css_class("suggested-action");
+    buttons.append(&quit_btn);
+    buttons.append(&memory_btn);
+    buttons.append(&fresh_btn);
+    buttons.append(&unlock_btn);
+    vbox.append(&buttons);
+
+    let dialog_quit = dialog.clone();
+    quit_btn.connect_clicked(move |_| dialog_quit.close());
+
+    let (app_mem, config_mem, dialog_mem, failure_mem) = (
+        app.clone(),
+        Arc::clone(&config),
+        dialog.clone(),
+        failure.clone(),
+    );
+    memory_btn.connect_clicked(move |_| {
+        continue_without_history(&app_mem, &config_mem, &dialog_mem, &failure_mem);
+    });
+
+    // The encrypted file stays next to the new one, as with a damaged one
+    let (app_fresh, config_fresh, dialog_fresh, failure_fresh) = (
+        app.clone(),
+        Arc::clone(&config),
+        dialog.clone(),
+        failure.clone(),
+    );
+    fresh_btn.connect_clicked(move |_| {
+        let reopened = crate::db::back_up_broken(&config_fresh.db_path).and_then(|backup| {
+            tracing::warn!("Moved the encrypted database to {}", backup.display());
+            Db::open(&config_fresh.db_path).map_err(|e| e.to_string())
+        });
+        match reopened {
+            Ok(db) => {
+                build_main_window(&app_fresh, Arc::clone(&config_fresh), db);
+                dialog_fresh.close();
+            }
+            Err(e) => {
+                failure_fresh.set_label(&format!("Still can't open the database: {e}"));
+                failure_fresh.set_visible(true);
+            }
+        }
+    });
+
+    let (app_unlock, dialog_unlock, entry_unlock) =
+        (app.clone(), dialog.clone(), passphrase.clone());
+    let unlock = move || match Db::open_encrypted(&config.db_path, &entry_unlock.text()) {
+        Ok(db) => {
+            build_main_window(&app_unlock, Arc::clone(&config), db);
+            dialog_unlock.close();
+        }
+        Err(e) => {
+            failure.set_label(&if crate::db::is_wrong_key_error(&e) {
+                "Wrong passphrase".to_string()
+            } else {
+                format!("Can't open the database: {e}")
+            });
+            failure.set_visible(true);
+            entry_unlock.set_text("");
+        }
+    };
+    let unlock = Rc::new(unlock);
+    let unlock_btn_c = Rc::clone(&unlock);
+    unlock_btn.connect_clicked(move |_| unlock_btn_c());
+    passphrase.connect_activate(move |_| unlock());
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+    passphrase.grab_focus();
+}
+
+/// Explain why History couldn't be opened and let the user back it up and
+/// start over, run this session without saving anything, or quit.
+fn show_db_error_dialog(app: &gtk4::Application, config: Arc<Config>, error: &rusqlite::Error) {
+    let locked = crate::db::is_locked_error(error);
+    let newer = crate::db::is_newer_schema_error(error);
+    let dialog = gtk4::Window::builder()
+        .application(app)
+        .title("WhisperCrabs")
+        .default_width(420)
+        .resizable(false)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let heading = gtk4::Label::new(Some("History database can't be opened"));
+    heading.add_css_class("heading");
+    heading.set_halign(gtk4::Align::Start);
+    vbox.append(&heading);
+
+    let explanation = if newer {
+        "History was last used by a newer version of WhisperCrabs and has changes \
+         this version doesn't know about. Update WhisperCrabs to keep using it, or \
+         continue without saving History for now. Backing it up keeps the file \
+         untouched next to a new, empty History."
+    } else if locked {
+        "Another process is using the database — most likely a WhisperCrabs that is \
+         still running or didn't exit cleanly. Close it and try again, or continue \
+         without saving History for now."
+    } else {
+        "The file looks damaged. It can be moved aside (kept as a backup next to it) \
+         and replaced by an empty database, or WhisperCrabs can run without saving \
+         History for this session."
+    };
+    let body = gtk4::Label::new(Some(&format!(
+        "{explanation}\n\n{}\n{error}",
+        config.db_path.display()
+    )));
+    body.set_wrap(true);
+    body.set_halign(gtk4::Align::Start);
+    body.set_selectable(true);
+    vbox.append(&body);
+
+    let failure = gtk4::Label::new(None);
+    failure.add_css_class("error");
+    failure.set_wrap(true);
+    failure.set_visible(false);
+    vbox.append(&failure);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let quit_btn = gtk4::Button::with_label("Quit");
+    let memory_btn = gtk4::Button::with_label("Continue Without History");
+    let recover_btn = gtk4::Button::with_label(if locked {
+        "Try Again"
+    } else {
+        "Back Up and Recreate"
+    });
+    recover_btn.add_css_class("suggested-action");
+    buttons.append(&quit_btn);
+    buttons.append(&memory_btn);
+    buttons.append(&recover_btn);
+    vbox.append(&buttons);
+
+    let dialog_quit = dialog.clone();
+    quit_btn.connect_clicked(move |_| dialog_quit.close());
+
+    let (app_mem, config_mem, dialog_mem, failure_mem) = (
+        app.clone(),
+        Arc::clone(&config),
+        dialog.clone(),
+        failure.clone(),
+    );
+    memory_btn.connect_clicked(move |_| {
+        continue_without_history(&app_mem, &config_mem, &dialog_mem, &failure_mem);
+    });
+
+    let (app_rec, dialog_rec) = (app.clone(), dialog.clone());
+    recover_btn.connect_clicked(move |_| {
+        let reopened = if locked {
+            Db::open(&config.db_path).map_err(|e| e.to_string())
+        } else {
+            crate::db::back_up_broken(&config.db_path).and_then(|backup| {
+                tracing::warn!("Moved the damaged database to {}", backup.display());
+                Db::open(&config.db_path).map_err(|e| e.to_string())
+            })
+        };
+        match reopened {
+            Ok(db) => {
+                build_main_window(&app_rec, Arc::clone(&config), db);
+                dialog_rec.close();
+            }
+            Err(e) => {
+                failure.set_label(&format!("Still can't open the database: {e}"));
+                failure.set_visible(true);
+            }
+        }
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+fn build_main_window(app: &gtk4::Application, config: Arc<Config>, mut db: Db) {
+    if config.safe_mode {
+        db.enter_safe_mode();
+        tracing::info!("Safe mode: stored settings and replacements are ignored this session");
+    }
     // Load CSS
+    let display = gdk::Display::default().expect("no default display");
     let provider = gtk4::CssProvider::new();
     provider.load_from_data(CSS);
     gtk4::style_context_add_provider_for_display(
-        &gdk::Display::default().expect("no default display"),
+        &display,
         &provider,
         gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
     );
+    // Button size, over the built-in style and under the user's
+    let size_provider = gtk4::CssProvider::new();
+    gtk4::style_context_add_provider_for_display(
+        &display,
+        &size_provider,
+        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+    );
+    // The user's own styles go on top
+    if let Some(path) = crate::appearance::user_css_path() {
+        crate::appearance::watch_user_css(&display, &path);
+    }
 
     let window = gtk4::ApplicationWindow::builder()
         .application(app)
@@ -204,6 +652,63 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     #[cfg(target_os = "macos")]
     window.add_css_class("macos-bg");
 
+    // Above other windows and on every workspace, as chosen in the menu
+    let setting_on = |key: &str| {
+        db.get_setting(key)
+            .ok()
+            .flatten()
+            .is_some_and(|v| v == "true")
+    };
+    let keep_above = setting_on(crate::stacking::KEEP_ABOVE_SETTING);
+    let all_workspaces = setting_on(crate::stacking::ALL_WORKSPACES_SETTING);
+    // On Wayland, a layer-shell surface in a screen corner (menu over .env)
+    let corner = db
+        .get_setting(crate::stacking::CORNER_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|v| crate::stacking::Corner::parse(&v))
+        .or(config.screen_corner)
+        .unwrap_or_default();
+    // Monitor picked under "Move to Monitor"
+    let chosen_monitor = db
+        .get_setting(crate::stacking::POSITION_MONITOR_SETTING)
+        .ok()
+        .flatten();
+    #[cfg(all(target_os = "linux", feature = "layer-shell"))]
+    if crate::stacking::on_wayland() {
+        if crate::stacking::init_layer_shell(&window, keep_above, corner, config.screen_margin) {
+            let monitor = chosen_monitor.as_deref().and_then(monitor_by_id);
+            crate::stacking::set_monitor(&window, monitor.as_ref());
+        } else {
+            tracing::info!("The compositor has no layer-shell; it places the window itself");
+        }
+    }
+
+    // Light or dark label and dialogs, following the desktop's color scheme
+    // unless a theme is pinned in the menu
+    let theme = Rc::new(std::cell::Cell::new(
+        db.get_setting(crate::appearance::THEME_SETTING)
+            .ok()
+            .flatten()
+            .and_then(|v| crate::appearance::Theme::parse(&v))
+            .unwrap_or_default(),
+    ));
+    let desktop_scheme = Rc::new(std::cell::Cell::new(
+        crate::appearance::ColorScheme::default(),
+    ));
+    if theme.get() != crate::appearance::Theme::System {
+        crate::appearance::apply(&window, theme.get().scheme(desktop_scheme.get()));
+    }
+    let (window_scheme, theme_scheme, desktop_watch) = (
+        window.clone(),
+        Rc::clone(&theme),
+        Rc::clone(&desktop_scheme),
+    );
+    crate::appearance::watch(move |scheme| {
+        desktop_watch.set(scheme);
+        crate::appearance::apply(&window_scheme, theme_scheme.get().scheme(scheme));
+    });
+
     // Layout
     let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
     vbox.set_halign(gtk4::Align::Center);
@@ -212,7 +717,6 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     // The mic button (no keyboard activation to prevent accidental recordings)
     // Try system icon first (works on Linux), fall back to SVG pixbuf
     let icon = gtk4::Image::from_icon_name("audio-input-microphone-symbolic");
-    icon.set_pixel_size(32);
 
     // Check if icon resolved; if not, load bundled SVG
     #[cfg(target_os = "macos")]
@@ -229,7 +733,18 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let button = gtk4::Button::new();
     button.set_child(Some(&icon));
     button.add_css_class("mic-btn");
-    button.set_size_request(72, 72);
+    // Small, medium (72 px) or large, or a pixel size; menu choice over .env
+    let widget_size = Rc::new(std::cell::Cell::new(
+        db.get_setting(crate::appearance::SIZE_SETTING)
+            .ok()
+            .flatten()
+            .and_then(|v| crate::appearance::WidgetSize::parse(&v))
+            .or(config.widget_size)
+            .unwrap_or_default(),
+    ));
+    crate::appearance::apply_size(&size_provider, &button, &icon, widget_size.get());
+    let (width, height) = widget_size.get().window_size();
+    window.set_default_size(width, height);
     button.set_halign(gtk4::Align::Center);
     button.set_focusable(false);
 
@@ -239,16 +754,55 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
 
     vbox.append(&button);
 
+    // "Local only" privacy badge, shown while network access is disabled
+    let local_badge = gtk4::Label::new(Some("LOCAL ONLY"));
+    local_badge.add_css_class("local-badge");
+    local_badge.set_halign(gtk4::Align::Center);
+    local_badge.set_visible(false);
+    vbox.append(&local_badge);
+
+    // Wake-word badge: the microphone is being listened to in the background
+    let wake_badge = gtk4::Label::new(Some("WAKE WORD"));
+    wake_badge.add_css_class("wake-badge");
+    wake_badge.set_halign(gtk4::Align::Center);
+    wake_badge.set_tooltip_text(Some(&format!(
+        "Listening for \"{}\"",
+        crate::wakeword::WAKE_PHRASE
+    )));
+    wake_badge.set_visible(false);
+    vbox.append(&wake_badge);
+
+    // Dry-run badge: dictations are previewed, not delivered
+    let dry_run_badge = gtk4::Label::new(Some("DRY RUN"));
+    dry_run_badge.add_css_class("dry-run-badge");
+    dry_run_badge.set_halign(gtk4::Align::Center);
+    dry_run_badge.set_tooltip_text(Some("Dictations are previewed, not copied or saved"));
+    dry_run_badge.set_visible(false);
+    vbox.append(&dry_run_badge);
+
+    // Safe-mode badge: this session runs on defaults (`--safe-mode`)
+    let safe_mode_badge = gtk4::Label::new(Some("SAFE MODE"));
+    safe_mode_badge.add_css_class("safe-mode-badge");
+    safe_mode_badge.set_halign(gtk4::Align::Center);
+    safe_mode_badge.set_tooltip_text(Some(
+        "Started with --safe-mode: saved settings and replacements are ignored, changes aren't saved",
+    ));
+    safe_mode_badge.set_visible(config.safe_mode);
+    vbox.append(&safe_mode_badge);
+
     // On macOS there's no transparent window, so show branding
     #[cfg(target_os = "macos")]
     {
-        icon.set_pixel_size(36);
-        button.set_size_request(68, 68);
+        if widget_size.get() == crate::appearance::WidgetSize::Medium {
+            icon.set_pixel_size(36);
+            button.set_size_request(68, 68);
+        }
         let brand = gtk4::Label::new(Some("WHISPER\nCRABS"));
         brand.set_justify(gtk4::Justification::Center);
         brand.add_css_class("brand-label");
         vbox.append(&brand);
-        window.set_default_size(96, 110);
+        let (width, height) = widget_size.get().window_size();
+        window.set_default_size(width + 8, height + 10);
     }
 
     // On macOS, status floats as overlay so it doesn't affect window layout.
@@ -276,25 +830,44 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
 
     window.set_child(Some(&handle));
 
-    // Open DB
-    let db = Arc::new(Mutex::new(
-        Db::open(&config.db_path).expect("Failed to open database"),
-    ));
+    let db = Arc::new(Mutex::new(db));
+
+    // Pick up entries other machines wrote to the sync folder meanwhile
+    let syncing = db.lock().ok().is_some_and(|d| {
+        d.get_setting(crate::sync::SYNC_DIR_SETTING)
+            .ok()
+            .flatten()
+            .is_some()
+    });
+    if syncing {
+        match sync_from_folder(&db) {
+            Ok(s) => dbg_log!("[SYNC] {} added, {} already present", s.added, s.skipped),
+            Err(e) => tracing::warn!("History sync failed: {e}"),
+        }
+    }
 
     // Determine initial provider: DB setting overrides env var
-    let (initial_service, initial_provider, initial_base_url, initial_api_key, initial_api_model) = {
+    let (
+        initial_service,
+        initial_provider,
+        initial_base_url,
+        initial_api_key,
+        initial_api_model,
+        initial_api_path,
+    ) = {
         let db_provider = db
             .lock()
             .ok()
             .and_then(|d| d.get_setting("transcription_mode").ok().flatten());
         match db_provider.as_deref() {
-            // Legacy "local" maps to default local model
-            Some("local") => (
-                TranscriptionService::Local,
-                config::DEFAULT_LOCAL_MODEL.to_string(),
+            // Automatic routing; the backend is resolved per dictation
+            Some(crate::auto::AUTO_PROVIDER) => (
+                TranscriptionService::Api,
+                crate::auto::AUTO_PROVIDER.to_string(),
                 config.api_base_url.clone(),
                 config.api_key.clone(),
                 config.api_model.clone(),
+                config.api_path.clone(),
             ),
             Some("custom") => {
                 let d = db.lock().expect("db lock poisoned");
@@ -307,18 +880,24 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                     .get_setting("api_custom_key")
                     .ok()
                     .flatten()
-                    .or_else(|| config.api_key.clone());
+                    .or_else(|| config.env_key_for(&url));
                 let model = d
                     .get_setting("api_custom_model")
                     .ok()
                     .flatten()
                     .unwrap_or_else(|| config.api_model.clone());
+                let path = d
+                    .get_setting("api_custom_path")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| config.api_path.clone());
                 (
                     TranscriptionService::Api,
                     "custom".to_string(),
                     url,
                     key,
                     model,
+                    path,
                 )
             }
             Some(provider_id) => {
@@ -332,7 +911,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                                     .ok()
                                     .flatten()
                             })
-                            .or_else(|| config.api_key.clone())
+                            .or_else(|| config.env_key_for(preset.base_url))
                     } else {
                         None
                     };
@@ -341,25 +920,29 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                         provider_id.to_string(),
                         preset.base_url.to_string(),
                         key,
-                        preset.default_model.to_string(),
+                        preset_model(&db, preset),
+                        config::DEFAULT_TRANSCRIPTION_PATH.to_string(),
                     )
-                } else if config::find_local_model(provider_id).is_some() {
-                    // Local model preset (e.g. "local-base", "local-small")
+                } else if config::is_local_provider(provider_id) {
+                    // Local model preset (e.g. "local-base"), Vosk model or custom model file
                     (
                         TranscriptionService::Local,
                         provider_id.to_string(),
                         config.api_base_url.clone(),
                         config.api_key.clone(),
                         config.api_model.clone(),
+                        config.api_path.clone(),
                     )
                 } else {
                     // Unknown provider in DB, fall back to env var config
+                    tracing::warn!("Unknown provider '{provider_id}' in settings, using Groq");
                     (
                         config.transcription_service,
                         "groq".to_string(),
                         config.api_base_url.clone(),
                         config.api_key.clone(),
                         config.api_model.clone(),
+                        config.api_path.clone(),
                     )
                 }
             }
@@ -376,34 +959,115 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                     config.api_base_url.clone(),
                     config.api_key.clone(),
                     config.api_model.clone(),
+                    config.api_path.clone(),
+                )
+            }
+        }
+    };
+
+    // Organization policy: replace a disallowed provider with the admin's choice
+    let (
+        initial_service,
+        initial_provider,
+        initial_base_url,
+        initial_api_key,
+        initial_api_model,
+        initial_api_path,
+    ) = {
+        let url =
+            (initial_service == TranscriptionService::Api).then_some(initial_base_url.as_str());
+        if config.policy.allows(&initial_provider, url) {
+            (
+                initial_service,
+                initial_provider,
+                initial_base_url,
+                initial_api_key,
+                initial_api_model,
+                initial_api_path,
+            )
+        } else {
+            let fallback = config.policy.fallback_provider();
+            tracing::warn!("Provider '{initial_provider}' blocked by policy, using '{fallback}'");
+            if let Some(preset) = config::find_preset(&fallback) {
+                let key = db
+                    .lock()
+                    .ok()
+                    .and_then(|d| {
+                        d.get_setting(&format!("api_key_{}", preset.id))
+                            .ok()
+                            .flatten()
+                    })
+                    .or(initial_api_key);
+                (
+                    TranscriptionService::Api,
+                    fallback,
+                    preset.base_url.to_string(),
+                    key,
+                    preset_model(&db, preset),
+                    config::DEFAULT_TRANSCRIPTION_PATH.to_string(),
+                )
+            } else {
+                (
+                    TranscriptionService::Local,
+                    fallback,
+                    initial_base_url,
+                    initial_api_key,
+                    initial_api_model,
+                    initial_api_path,
                 )
             }
         }
     };
 
-    // Init local whisper only if Local mode AND the selected model file exists
-    let initial_whisper: Option<Arc<LocalWhisper>> =
-        if initial_service == TranscriptionService::Local {
-            let lm = config::find_local_model(&initial_provider)
-                .unwrap_or(&config::LOCAL_MODEL_PRESETS[0]); // default to "tiny"
-            let model_path = config.models_dir.join(lm.file_name);
-            if model_path.exists() {
-                match LocalWhisper::new(&model_path) {
+    let initial_custom_model = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("local_custom_model").ok().flatten())
+        .map(std::path::PathBuf::from);
+
+    // Init the local engine only if Local mode AND the selected model exists.
+    // A models drive that isn't mounted is reported once the window is up.
+    let mut startup_models_unavailable = None;
+    let initial_engine: Option<Arc<LocalEngine>> = if initial_service == TranscriptionService::Local
+    {
+        let location = config::locate_model(
+            &config.models_dir,
+            &config.local_models_dir,
+            &initial_provider,
+            initial_custom_model.as_deref(),
+        )
+        .or_else(|| {
+            // default to "tiny"
+            config::locate_model(
+                &config.models_dir,
+                &config.local_models_dir,
+                config::LOCAL_MODEL_PRESETS[0].id,
+                None,
+            )
+        });
+        match location {
+            Some(config::ModelLocation::Found(model_path)) => {
+                match LocalEngine::load(&model_path) {
                     Ok(w) => Some(Arc::new(w)),
                     Err(e) => {
-                        eprintln!("Failed to load whisper model: {e}");
+                        tracing::error!("Failed to load local model: {e}");
                         None
                     }
                 }
-            } else {
+            }
+            Some(config::ModelLocation::Unavailable { dir, local_path }) => {
+                tracing::warn!("Models directory {} is not available", dir.display());
+                startup_models_unavailable = Some((dir, local_path));
                 None
             }
-        } else {
-            None
-        };
+            _ => None,
+        }
+    } else {
+        None
+    };
 
     // Load TTS state from DB
-    let piper_dir = config.models_dir.join("piper");
+    let piper_dir = config.local_models_dir.join("piper");
     let initial_tts_voice = db
         .lock()
         .ok()
@@ -421,7 +1085,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             match PiperTts::new(&piper_dir, &initial_tts_voice) {
                 Ok(engine) => (TtsProvider::Piper, Some(Arc::new(engine))),
                 Err(e) => {
-                    eprintln!("Failed to load Piper TTS: {e}");
+                    tracing::error!("Failed to load Piper TTS: {e}");
                     (TtsProvider::None, None)
                 }
             }
@@ -430,6 +1094,124 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         }
     };
 
+    let initial_stt_language = db
+        .lock()
+        .map(|d| crate::transcription::stored_language(&d))
+        .unwrap_or_else(|_| config::DEFAULT_STT_LANGUAGE.to_string());
+
+    let initial_vocabulary = db
+        .lock()
+        .map(|d| crate::vocabulary::stored(&d))
+        .unwrap_or_default();
+
+    let initial_history_mode = db
+        .lock()
+        .map(|d| crate::transcription::stored_history_mode(&d))
+        .unwrap_or_default();
+
+    let initial_label_speakers = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("label_speakers").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_live_dictation = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("live_dictation").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_dictation_commands = db
+        .lock()
+        .ok()
+        .and_then(|d| {
+            d.get_setting(crate::dictation_commands::ENABLED_SETTING)
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|v| v == "true");
+
+    let initial_announce = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("announce_transcripts").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_notify = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::notify::SETTING).ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_auto_paste = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("auto_paste").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_coaching = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("coaching_hints").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_auto_split = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::split::SPLIT_SETTING).ok().flatten())
+        .and_then(|v| crate::split::parse_minutes(&v));
+
+    let initial_store_audio = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("store_audio").ok().flatten())
+        .is_some_and(|v| v == "true");
+
+    let initial_sounds = match db.lock() {
+        Ok(d) => config.sounds.clone().load(&d),
+        Err(_) => config.sounds.clone(),
+    };
+
+    // Quiet hours: the menu choice wins over QUIET_HOURS from .env
+    let initial_quiet = match db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("quiet_hours").ok().flatten())
+    {
+        Some(value) => crate::quiet::parse_schedule(&value),
+        None => config.quiet_hours,
+    };
+    let initial_dnd = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("dnd").ok().flatten())
+        .and_then(|v| crate::quiet::Dnd::parse(&v))
+        .unwrap_or_default();
+    crate::quiet::set_schedule(initial_quiet);
+    crate::quiet::set_dnd(initial_dnd);
+    crate::paste::set_config(config.paste.clone());
+
+    let initial_monitor_dots = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("monitor_dots").ok().flatten())
+        .is_some_and(|v| v == "true");
+    let initial_pause_media = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::media::ENABLED_SETTING).ok().flatten())
+        .is_some_and(|v| v == "true");
+    let initial_review = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::review::SETTING).ok().flatten())
+        .is_some_and(|v| v == "true");
+    let initial_silence = db.lock().ok().is_some_and(|d| {
+        // Banners a crashed run left off come back first
+        crate::silence::restore_after_crash(&d);
+        crate::silence::enabled(&d)
+    });
+
     // Runtime state (UI-thread only)
     let runtime = Rc::new(RefCell::new(RuntimeState {
         active_service: initial_service,
@@ -437,18 +1219,97 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         api_base_url: initial_base_url,
         api_key: initial_api_key,
         api_model: initial_api_model,
-        local_whisper: initial_whisper,
+        api_path: initial_api_path,
+        local_engine: initial_engine,
         downloading: false,
+        download_cancel: Arc::new(std::sync::atomic::AtomicBool::new(false)),
         tts_provider: initial_tts_provider,
         tts_voice: initial_tts_voice,
         tts_engine: initial_tts_engine,
         tts_downloading: false,
         tts_stop: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        rule_switching: false,
+        stt_language: initial_stt_language,
+        vocabulary: initial_vocabulary,
+        auto_mode: initial_provider == crate::auto::AUTO_PROVIDER,
+        auto_route: None,
+        auto_probing: false,
+        custom_model: initial_custom_model,
+        pending_profile: None,
+        profile_route: None,
+        history_mode: initial_history_mode,
+        label_speakers: initial_label_speakers,
+        live_dictation: initial_live_dictation,
+        announce: initial_announce,
+        notify: initial_notify,
+        discard_recording: false,
+        dropped_audio: None,
+        failed_audio: None,
+        auto_paste: initial_auto_paste,
+        coaching: initial_coaching,
+        last_tip: None,
+        dry_run: false,
+        auto_split: initial_auto_split,
+        split_session: 0,
+        live_session: None,
+        monitor_dots: initial_monitor_dots,
+        store_audio: initial_store_audio,
+        sounds: initial_sounds.clone(),
+        recording_dots: None,
+        pause_media: initial_pause_media,
+        paused_media: None,
+        silence_notifications: initial_silence,
+        review: initial_review,
+        silenced: None,
+        meeting_window: None,
+        session_log: gtk4::gio::ListStore::new::<glib::BoxedAnyObject>(),
     }));
 
     // Shared state
     let state = Rc::new(RefCell::new(State::Idle));
     let recorder = Rc::new(RefCell::new(Recorder::new()));
+    let initial_audio_device = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("audio_device").ok().flatten())
+        .unwrap_or_default();
+    if !initial_audio_device.is_empty() {
+        recorder
+            .borrow_mut()
+            .set_device(Some(initial_audio_device.clone()));
+    }
+
+    // Input device watcher: grey "no microphone" button until one appears
+    let input_rx = crate::audio::watch_input();
+    let (btn_input, st_input) = (button.clone(), status.clone());
+    let mut input_seen: Option<bool> = None;
+    glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+        loop {
+            match input_rx.try_recv() {
+                Ok(available) => {
+                    if available {
+                        btn_input.remove_css_class("no-input");
+                        btn_input.set_tooltip_text(None);
+                        // Announce a device that came back, not the one present at startup
+                        if input_seen == Some(false) {
+                            show_status(&st_input, "Microphone connected");
+                            let st = st_input.clone();
+                            glib::timeout_add_local_once(
+                                std::time::Duration::from_secs(2),
+                                move || hide_status(&st),
+                            );
+                        }
+                    } else {
+                        btn_input.add_css_class("no-input");
+                        btn_input.set_tooltip_text(Some("No microphone found"));
+                    }
+                    input_seen = Some(available);
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => return glib::ControlFlow::Break,
+            }
+        }
+    });
 
     // --- Left-click handler (on the Button) ---
     let btn = button.clone();
@@ -458,6 +1319,8 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let config_c = Arc::clone(&config);
     let db_c = Arc::clone(&db);
     let runtime_c = Rc::clone(&runtime);
+    let app_c = app.clone();
+    let win_c = window.clone();
 
     button.connect_clicked(move |_| {
         let current = *state_c.borrow();
@@ -465,58 +1328,228 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
             State::Idle => {
                 // Guard: block recording during model download
                 if runtime_c.borrow().downloading {
-                    show_status(&st, "Downloading model...");
-                    return;
-                }
-
-                // Guard: Local mode without loaded model
-                let rt = runtime_c.borrow();
-                if rt.active_service == TranscriptionService::Local && rt.local_whisper.is_none() {
-                    drop(rt);
-                    show_status(&st, "No local model loaded");
+                    show_status(&st, "Downloading model... (right-click to cancel)");
                     return;
                 }
 
-                // Guard: API mode — check if provider needs key and none is set
-                if rt.active_service == TranscriptionService::Api {
-                    let needs_key = config::find_preset(&rt.active_provider)
-                        .map(|p| p.needs_key)
-                        .unwrap_or(true); // custom defaults to needing a key check
-                    if needs_key && rt.api_key.is_none() {
+                // Profile rules: switch provider based on network / time of day
+                if let Some(target) = profile_rule_target(&runtime_c, &db_c) {
+                    dbg_log!("[RULES] switching to {target}");
+                    runtime_c.borrow_mut().rule_switching = true;
+                    app_c.activate_action("transcription-mode", Some(&target.to_variant()));
+                    runtime_c.borrow_mut().rule_switching = false;
+                    let rt = runtime_c.borrow();
+                    if rt.active_provider != target {
+                        // Switch needs user input (e.g. API key dialog)
+                        return;
+                    }
+                    if rt.active_service == TranscriptionService::Local && rt.local_engine.is_none()
+                    {
                         drop(rt);
-                        show_status(&st, "No API key set");
+                        show_status(&st, "Profile switched, loading model...");
                         return;
                     }
                 }
-                drop(rt);
 
-                if !Recorder::input_available() {
-                    show_status(&st, "No microphone found");
+                // Auto mode: route to the fastest healthy backend from the last probe
+                if runtime_c.borrow().auto_mode {
+                    let route = runtime_c.borrow().auto_route.clone();
+                    let Some(backend) = route else {
+                        start_auto_probe(&runtime_c, &db_c, &config_c);
+                        show_status(&st, "No provider reachable, probing...");
+                        return;
+                    };
+                    dbg_log!("[AUTO] routing to {}", backend.provider);
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.active_service = TranscriptionService::Api;
+                    rt.active_provider = backend.provider;
+                    rt.api_base_url = backend.base_url;
+                    rt.api_key = backend.api_key;
+                    rt.api_model = backend.model;
+                    rt.api_path = backend.path;
+                }
+
+                // One-shot record profile: resolve and validate its provider override
+                let profile = runtime_c.borrow().pending_profile.clone();
+                let profile_route = match profile {
+                    Some(ref p) => match resolve_profile_route(&runtime_c, &db_c, &config_c, p) {
+                        Ok(route) => route,
+                        Err(e) => {
+                            show_status(&st, &e);
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                let profile_routed = profile_route.is_some();
+                runtime_c.borrow_mut().profile_route = profile_route;
+
+                // Provider guards (a profile route was validated when it was resolved)
+                if !profile_routed {
+                    // Guard: Local mode without loaded model
+                    let rt = runtime_c.borrow();
+                    if rt.active_service == TranscriptionService::Local && rt.local_engine.is_none()
+                    {
+                        drop(rt);
+                        show_status(&st, "No local model loaded");
+                        return;
+                    }
+
+                    // Guard: organization policy (defense in depth for the network path)
+                    let url = (rt.active_service == TranscriptionService::Api)
+                        .then_some(rt.api_base_url.as_str());
+                    if !config_c.policy.allows(&rt.active_provider, url) {
+                        drop(rt);
+                        show_status(&st, "Blocked by policy");
+                        return;
+                    }
+
+                    // Guard: local-only privacy mode blocks remote endpoints
+                    if rt.active_service == TranscriptionService::Api
+                        && crate::policy::ensure_network_allowed(&rt.api_base_url).is_err()
+                    {
+                        drop(rt);
+                        show_status(&st, "Local only: pick a local model");
+                        return;
+                    }
+
+                    // Guard: API mode — check if provider needs key and none is set
+                    if rt.active_service == TranscriptionService::Api {
+                        let needs_key = config::find_preset(&rt.active_provider)
+                            .map(|p| p.needs_key)
+                            .unwrap_or(true); // custom defaults to needing a key check
+                        if needs_key && rt.api_key.is_none() {
+                            drop(rt);
+                            show_status(&st, "No API key set");
+                            return;
+                        }
+                    }
+
+                    // Guard: one-time consent before audio first goes to a cloud provider
+                    if rt.active_service == TranscriptionService::Api
+                        && !crate::policy::is_local_url(&rt.api_base_url)
+                    {
+                        let host = crate::policy::url_host(&rt.api_base_url).to_string();
+                        if !cloud_consented(&db_c, &host) {
+                            let label = config::find_preset(&rt.active_provider)
+                                .map(|p| p.label)
+                                .unwrap_or("Custom API")
+                                .to_string();
+                            drop(rt);
+                            show_cloud_consent_dialog(&win_c, &btn, &app_c, &db_c, &label, &host);
+                            return;
+                        }
+                    }
+                    drop(rt);
+                }
+
+                if !Recorder::input_available() {
+                    show_status(&st, "No microphone found");
                     return;
                 }
 
                 if let Err(e) = rec_c.borrow_mut().start() {
-                    eprintln!("Record start error: {e}");
+                    tracing::error!("Record start error: {e}");
+                    runtime_c.borrow().sounds.play(SoundEvent::Error);
                     show_status(&st, &format!("Err: {e}"));
                     return;
                 }
+                runtime_c.borrow().sounds.play(SoundEvent::RecordStart);
                 *state_c.borrow_mut() = State::Recording;
+                if runtime_c.borrow().pause_media {
+                    let allowlist = db_c
+                        .lock()
+                        .ok()
+                        .and_then(|d| crate::media::stored(&d))
+                        .unwrap_or_default();
+                    let paused = crate::media::pause_playing(&allowlist);
+                    dbg_log!("[MEDIA] paused {:?}", paused.names());
+                    runtime_c.borrow_mut().paused_media = Some(paused);
+                }
+                if runtime_c.borrow().silence_notifications {
+                    let silenced = crate::silence::silence(&db_c);
+                    dbg_log!("[SILENCE] notifications silenced: {}", silenced.is_active());
+                    runtime_c.borrow_mut().silenced = Some(silenced);
+                }
                 btn.add_css_class("recording");
                 btn.remove_css_class("done");
 
-                show_status(&st, "Recording...");
+                // Real-time dictation: decode while recording and type into the focused app
+                let live = start_live_dictation(&runtime_c, &db_c, &config_c, &rec_c.borrow());
+                // Long sessions: transcribe into History part by part
+                start_auto_split(
+                    &runtime_c,
+                    &config_c,
+                    &db_c,
+                    &rec_c,
+                    &state_c,
+                    &st,
+                    profile.as_ref(),
+                );
+
+                if runtime_c.borrow().monitor_dots {
+                    let dots = RecordingDots::show(&app_c, &win_c);
+                    runtime_c.borrow_mut().recording_dots = Some(dots);
+                }
+
+                match profile {
+                    Some(p) => show_status(&st, &format!("Recording ({})...", p.name)),
+                    None if live => show_status(&st, "Dictating..."),
+                    None => show_status(&st, "Recording..."),
+                }
             }
             State::Recording => {
+                let (profile, profile_route, live_session, dropped) = {
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.recording_dots = None;
+                    // Resumes the players paused for this recording
+                    rt.paused_media = None;
+                    rt.silenced = None;
+                    (
+                        rt.pending_profile.take(),
+                        rt.profile_route.take(),
+                        rt.live_session.take(),
+                        rt.dropped_audio.take(),
+                    )
+                };
+                let live = live_session.is_some();
+                let (announce, notify, auto_paste, review, dry_run, would_type) = {
+                    let rt = runtime_c.borrow();
+                    (
+                        rt.announce,
+                        rt.notify,
+                        rt.auto_paste,
+                        rt.review,
+                        rt.dry_run,
+                        // What live dictation would have done outside a dry run
+                        rt.dry_run
+                            && rt.live_dictation
+                            && profile.is_none()
+                            && rt.active_service == TranscriptionService::Local,
+                    )
+                };
                 *state_c.borrow_mut() = State::Processing;
                 btn.remove_css_class("recording");
                 btn.add_css_class("processing");
 
+                if dropped.is_none() {
+                    runtime_c.borrow().sounds.play(SoundEvent::RecordStop);
+                }
                 show_status(&st, "Transcribing...");
 
-                let wav = match rec_c.borrow_mut().stop() {
-                    Ok(w) => w,
+                // A dropped file stands in for the recording
+                let stopped = match dropped {
+                    Some(audio) => Ok(audio),
+                    None => {
+                        let mut rec = rec_c.borrow_mut();
+                        rec.stop().map(|wav| (wav, rec.sample_rate()))
+                    }
+                };
+                let (wav, sample_rate) = match stopped {
+                    Ok(audio) => audio,
                     Err(e) => {
-                        eprintln!("Record stop error: {e}");
+                        tracing::error!("Record stop error: {e}");
+                        runtime_c.borrow().sounds.play(SoundEvent::Error);
                         show_status(&st, &format!("Err: {e}"));
                         *state_c.borrow_mut() = State::Idle;
                         btn.remove_css_class("processing");
@@ -525,32 +1558,201 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                 };
 
                 let db_inner = Arc::clone(&db_c);
-                let sample_rate = rec_c.borrow().sample_rate();
+                let audio_secs = crate::audio::wav_duration_secs(&wav).unwrap_or(0.0);
+                let level_db = crate::audio::wav_level_db(&wav);
+
+                // Discarded on purpose, or an accidental tap: skip the API
+                // call and the history row
+                let discarded = std::mem::take(&mut runtime_c.borrow_mut().discard_recording);
+                if discarded || audio_secs * 1000.0 < config_c.min_recording_ms as f64 {
+                    dbg_log!("[REC] discarded {audio_secs:.2}s recording");
+                    *state_c.borrow_mut() = State::Idle;
+                    btn.remove_css_class("processing");
+                    show_status(
+                        &st,
+                        if discarded {
+                            "Discarded"
+                        } else {
+                            "Too short, discarded"
+                        },
+                    );
+                    let st2 = st.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                        hide_status(&st2);
+                    });
+                    return;
+                }
 
-                let (tx, rx) = std::sync::mpsc::channel::<Result<String, String>>();
+                let (tx, rx) = std::sync::mpsc::channel::<SttMsg>();
+                // Kept for "Retry with same audio" should the transcription fail
+                let mut retry_audio = Some((wav.clone(), sample_rate));
 
                 let rt = runtime_c.borrow();
-                match rt.active_service {
-                    TranscriptionService::Api => {
-                        let base_url = rt.api_base_url.clone();
-                        let api_key = rt.api_key.clone().unwrap_or_default();
-                        let model = rt.api_model.clone();
+                let auto_mode = rt.auto_mode;
+                let language_choice = profile
+                    .as_ref()
+                    .and_then(|p| p.language.clone())
+                    .unwrap_or_else(|| rt.stt_language.clone());
+                let history_mode = profile
+                    .as_ref()
+                    .and_then(|p| p.history)
+                    .unwrap_or(rt.history_mode);
+                let template = profile.as_ref().and_then(|p| p.template.clone());
+                // Keep the recording next to its History entry (full history only)
+                let mut kept_wav = (rt.store_audio && history_mode == crate::db::HistoryMode::Full)
+                    .then(|| wav.clone());
+                let mut whisper_params = config_c.whisper.clone();
+                whisper_params.translate = profile.as_ref().is_some_and(|p| p.translate);
+                whisper_params.diarize = rt.label_speakers;
+                whisper_params.initial_prompt = crate::vocabulary::prompt(None, &rt.vocabulary);
+                let (usage_provider, usage_model) = match profile_route {
+                    Some(Route::Api(ref b)) => (b.provider.clone(), b.model.clone()),
+                    Some(Route::Local {
+                        ref provider,
+                        ref path,
+                        ..
+                    }) => (
+                        provider.clone(),
+                        path.file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                    None => (
+                        rt.active_provider.clone(),
+                        match rt.active_service {
+                            TranscriptionService::Api => rt.api_model.clone(),
+                            TranscriptionService::Local => {
+                                config::find_local_model(&rt.active_provider)
+                                    .map(|m| m.file_name.to_string())
+                                    .or_else(|| {
+                                        config::find_vosk_model(&rt.active_provider)
+                                            .map(|m| m.name.to_string())
+                                    })
+                                    .or_else(|| {
+                                        rt.custom_model
+                                            .as_ref()
+                                            .and_then(|p| p.file_name())
+                                            .map(|n| n.to_string_lossy().into_owned())
+                                    })
+                                    .unwrap_or_default()
+                            }
+                        },
+                    ),
+                };
+                match (profile_route, rt.active_service) {
+                    (Some(Route::Api(b)), _) => {
+                        match api_request_options(
+                            &config_c,
+                            &b.provider,
+                            &b.path,
+                            &language_choice,
+                            whisper_params.translate,
+                            &rt.vocabulary,
+                        ) {
+                            Ok(options) => spawn_api_transcription(
+                                b.base_url,
+                                api_path_for(&b.path, whisper_params.translate),
+                                b.api_key.unwrap_or_default(),
+                                b.model,
+                                AudioUpload::Bytes(wav),
+                                options,
+                                tx,
+                            ),
+                            // Reported like a failed request, through the usual path
+                            Err(e) => {
+                                let _ = tx.send(SttMsg::Done(Err(e), std::time::Duration::ZERO));
+                            }
+                        }
+                    }
+                    (
+                        Some(Route::Local {
+                            path, multilingual, ..
+                        }),
+                        _,
+                    ) => {
+                        // One-shot model: load it for this dictation only
+                        let language = if multilingual {
+                            language_choice
+                        } else {
+                            "en".to_string()
+                        };
                         std::thread::spawn(move || {
-                            let rt = tokio::runtime::Runtime::new()
-                                .expect("failed to create tokio runtime");
-                            let result = rt
-                                .block_on(crate::api::transcribe(&base_url, &api_key, &model, wav));
-                            let _ = tx.send(result);
+                            let started = std::time::Instant::now();
+                            let seg_tx = tx.clone();
+                            let result = LocalEngine::load(&path).and_then(|w| {
+                                w.transcribe_with_segments(
+                                    &wav,
+                                    sample_rate,
+                                    &language,
+                                    &whisper_params,
+                                    move |seg| {
+                                        let _ = seg_tx.send(SttMsg::Segment(seg));
+                                    },
+                                )
+                            });
+                            let _ = tx.send(SttMsg::Done(result, started.elapsed()));
                         });
                     }
-                    TranscriptionService::Local => {
-                        let Some(whisper) = rt.local_whisper.clone() else {
-                            let _ = tx.send(Err("Local model not loaded".into()));
-                            return;
-                        };
+                    (None, TranscriptionService::Api) => {
+                        match api_request_options(
+                            &config_c,
+                            &rt.active_provider,
+                            &rt.api_path,
+                            &language_choice,
+                            whisper_params.translate,
+                            &rt.vocabulary,
+                        ) {
+                            Ok(options) => spawn_api_transcription(
+                                rt.api_base_url.clone(),
+                                api_path_for(&rt.api_path, whisper_params.translate),
+                                rt.api_key.clone().unwrap_or_default(),
+                                rt.api_model.clone(),
+                                AudioUpload::Bytes(wav),
+                                options,
+                                tx,
+                            ),
+                            Err(e) => {
+                                let _ = tx.send(SttMsg::Done(Err(e), std::time::Duration::ZERO));
+                            }
+                        }
+                    }
+                    (None, TranscriptionService::Local) if live_session.is_some() => {
+                        // The live decoder has typed most of it already; finish the tail
+                        let session = live_session.expect("checked above");
                         std::thread::spawn(move || {
-                            let result = whisper.transcribe(&wav, sample_rate);
-                            let _ = tx.send(result);
+                            let started = std::time::Instant::now();
+                            let result = session.finish();
+                            let _ = tx.send(SttMsg::Done(result, started.elapsed()));
+                        });
+                    }
+                    (None, TranscriptionService::Local) if rt.local_engine.is_none() => {
+                        // Reported through the usual path, so the audio can be retried
+                        let _ = tx.send(SttMsg::Done(
+                            Err("Local model not loaded".into()),
+                            std::time::Duration::ZERO,
+                        ));
+                    }
+                    (None, TranscriptionService::Local) => {
+                        let whisper = rt.local_engine.clone().expect("checked above");
+                        let language = match config::find_local_model(&rt.active_provider) {
+                            Some(m) => config::effective_language(m, &language_choice),
+                            // Custom models may be multilingual; honour the Language menu
+                            None => language_choice.as_str(),
+                        }
+                        .to_string();
+                        std::thread::spawn(move || {
+                            let started = std::time::Instant::now();
+                            let seg_tx = tx.clone();
+                            let result = whisper.transcribe_with_segments(
+                                &wav,
+                                sample_rate,
+                                &language,
+                                &whisper_params,
+                                move |seg| {
+                                    let _ = seg_tx.send(SttMsg::Segment(seg));
+                                },
+                            );
+                            let _ = tx.send(SttMsg::Done(result, started.elapsed()));
                         });
                     }
                 }
@@ -558,67 +1760,341 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
 
                 let btn2 = btn.clone();
                 let st2 = st.clone();
+                let win_c2 = win_c.clone();
                 let state_c2 = Rc::clone(&state_c);
-                let notify = config_c.sound_notification;
+                let runtime_c2 = Rc::clone(&runtime_c);
+                let config_c2 = Arc::clone(&config_c);
+                let sounds = runtime_c.borrow().sounds.clone();
+                // Live dictation has typed the text already; it stays as spoken
+                let rx = match post_process_job(&db_c, &config_c).filter(|_| !live) {
+                    Some(job) => post_process_relay(rx, job),
+                    None => rx,
+                };
+                let mut partial = String::new();
+                let mut raw_text: Option<String> = None;
                 glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-                    match rx.try_recv() {
-                        Ok(Ok(text)) => {
-                            if let Ok(db) = db_inner.lock()
-                                && let Err(e) = db.insert(&text)
-                            {
-                                eprintln!("DB insert error: {e}");
+                    // Drain all pending messages; segments can arrive faster than the ticks
+                    loop {
+                        match rx.try_recv() {
+                            Ok(SttMsg::Segment(seg)) => {
+                                // Local models stream segments while decoding
+                                partial.push_str(&seg);
+                                show_status(&st2, &status_preview(&partial));
+                            }
+                            Ok(SttMsg::PostProcessing) => {
+                                show_status(&st2, "Post-processing...");
                             }
-                            match crate::input::copy_to_clipboard(&text) {
-                                Ok(_) => {
-                                    if notify {
-                                        play_notification();
+                            Ok(SttMsg::Raw(raw)) => {
+                                raw_text = Some(raw);
+                            }
+                            Ok(SttMsg::Done(Ok(transcript), latency)) => {
+                                // Vosk models don't report a language; it is fixed per model
+                                let language = transcript.language.clone().or_else(|| {
+                                    config::find_vosk_model(&usage_provider)
+                                        .map(|m| m.language.to_string())
+                                });
+                                // Personal dictionary: accepted learned replacements
+                                let (rules, commands) = match db_inner.lock() {
+                                    Ok(d) => (
+                                        d.accepted_replacements(language.as_deref())
+                                            .unwrap_or_default(),
+                                        crate::dictation_commands::stored(&d),
+                                    ),
+                                    Err(_) => (Vec::new(), None),
+                                };
+                                let mut text = crate::corrections::apply(&transcript.text, &rules);
+                                // Typed live, the text already went out as spoken
+                                if let Some(commands) = commands.filter(|_| !live) {
+                                    text = crate::dictation_commands::apply(&text, &commands);
+                                }
+                                let segments: Vec<_> = transcript
+                                    .segments
+                                    .iter()
+                                    .map(|seg| crate::local_stt::Segment {
+                                        text: crate::corrections::apply(&seg.text, &rules),
+                                        ..seg.clone()
+                                    })
+                                    .collect();
+                                let confidence = crate::confidence::score(&transcript.segments);
+                                let (low_confidence, review_low) = db_inner
+                                    .lock()
+                                    .map(|d| {
+                                        (
+                                            crate::confidence::is_low(
+                                                confidence,
+                                                crate::confidence::threshold(&d),
+                                            ),
+                                            crate::confidence::review_low(&d),
+                                        )
+                                    })
+                                    .unwrap_or_default();
+                                // The profile's template shapes what is copied; History keeps the text
+                                let copied = match &template {
+                                    Some(template) => crate::template::render(
+                                        template,
+                                        &crate::template::Vars {
+                                            text: &text,
+                                            provider: &usage_provider,
+                                            language: language.as_deref(),
+                                            time: chrono::Local::now().naive_local(),
+                                        },
+                                    ),
+                                    None => text.clone(),
+                                };
+                                if dry_run {
+                                    let stored = history_mode.stored_text(&text);
+                                    let (sync_dir, autosave_dir, hooks) = db_inner
+                                        .lock()
+                                        .ok()
+                                        .map(|d| {
+                                            (
+                                                d.get_setting(crate::sync::SYNC_DIR_SETTING)
+                                                    .ok()
+                                                    .flatten(),
+                                                crate::autosave::stored(&d).map(|(dir, _)| {
+                                                    dir.to_string_lossy().into_owned()
+                                                }),
+                                                crate::hooks::stored(&d),
+                                            )
+                                        })
+                                        .unwrap_or_default();
+                                    let event = crate::hooks::Event::new(
+                                        chrono::Local::now(),
+                                        &text,
+                                        &usage_provider,
+                                    );
+                                    let plan = crate::dryrun::plan(&crate::dryrun::Outputs {
+                                        text: &copied,
+                                        stored: stored.as_deref(),
+                                        sync_dir: sync_dir.as_deref(),
+                                        autosave_dir: autosave_dir.as_deref(),
+                                        typed: would_type,
+                                        paste: auto_paste,
+                                        announce,
+                                        keep_audio: kept_wav.is_some(),
+                                        hooks: Some((&hooks, &event)),
+                                    });
+                                    // The transcription did run, so it counts as usage
+                                    if let Ok(db) = db_inner.lock()
+                                        && let Err(e) = db.insert_usage(
+                                            None,
+                                            &usage_provider,
+                                            &usage_model,
+                                            audio_secs,
+                                            latency.as_millis() as u64,
+                                            text.split_whitespace().count(),
+                                            level_db,
+                                        )
+                                    {
+                                        tracing::error!("DB usage insert error: {e}");
                                     }
+                                    sounds.play(SoundEvent::Success);
                                     btn2.remove_css_class("processing");
-                                    btn2.add_css_class("done");
-
-                                    show_status(&st2, "Copied!");
+                                    show_status(&st2, "Dry run");
+                                    show_dry_run_window(&win_c2, &plan);
                                     let st3 = st2.clone();
-                                    let btn3 = btn2.clone();
                                     glib::timeout_add_local_once(
                                         std::time::Duration::from_secs(2),
-                                        move || {
-                                            hide_status(&st3);
-                                            btn3.remove_css_class("done");
-                                        },
+                                        move || hide_status(&st3),
                                     );
+                                    *state_c2.borrow_mut() = State::Idle;
+                                    return glib::ControlFlow::Break;
                                 }
-                                Err(e) => {
-                                    eprintln!("Clipboard error: {e}");
+                                let stored_id = if let Ok(db) = db_inner.lock() {
+                                    // History insert policy (global or per profile)
+                                    let id = crate::transcription::store(
+                                        &db,
+                                        &text,
+                                        language.as_deref(),
+                                        history_mode,
+                                    )
+                                    .unwrap_or_else(|e| {
+                                        tracing::error!("DB insert error: {e}");
+                                        None
+                                    });
+                                    if let Some(id) = id
+                                        && history_mode == crate::db::HistoryMode::Full
+                                        && !transcript.words.is_empty()
+                                        && let Err(e) = db.insert_words(id, &transcript.words)
+                                    {
+                                        tracing::error!("DB words insert error: {e}");
+                                    }
+                                    if let (Some(id), Some(raw)) = (id, raw_text.take())
+                                        && history_mode == crate::db::HistoryMode::Full
+                                        && let Err(e) = db.set_raw_text(id, &raw)
+                                    {
+                                        tracing::error!("DB raw text update error: {e}");
+                                    }
+                                    if let Some(id) = id
+                                        && history_mode == crate::db::HistoryMode::Full
+                                        && !segments.is_empty()
+                                        && let Err(e) = db.insert_segments(id, &segments)
+                                    {
+                                        tracing::error!("DB segments insert error: {e}");
+                                    }
+                                    if let (Some(id), Some(confidence)) = (id, confidence)
+                                        && let Err(e) = db.set_confidence(id, confidence)
+                                    {
+                                        tracing::error!("DB confidence update error: {e}");
+                                    }
+                                    if let (Some(id), Some(wav)) = (id, kept_wav.take()) {
+                                        let path = config::recording_path(&config_c2.audio_dir, id);
+                                        let saved = std::fs::create_dir_all(&config_c2.audio_dir)
+                                            .and_then(|_| std::fs::write(&path, wav))
+                                            .map_err(|e| e.to_string())
+                                            .and_then(|_| {
+                                                db.set_audio_path(id, &path)
+                                                    .map_err(|e| e.to_string())
+                                            });
+                                        if let Err(e) = saved {
+                                            tracing::warn!("Saving recording failed: {e}");
+                                        }
+                                    }
+                                    if let Err(e) = db.insert_usage(
+                                        id,
+                                        &usage_provider,
+                                        &usage_model,
+                                        audio_secs,
+                                        latency.as_millis() as u64,
+                                        text.split_whitespace().count(),
+                                        level_db,
+                                    ) {
+                                        tracing::error!("DB usage insert error: {e}");
+                                    }
+                                    id
+                                } else {
+                                    None
+                                };
+                                // Held for review: the window copies (and pastes) when confirmed
+                                if (review || (low_confidence && review_low)) && !live {
+                                    sounds.play(SoundEvent::Success);
                                     btn2.remove_css_class("processing");
-
-                                    show_status(&st2, "Error!");
-                                    let st3 = st2.clone();
-                                    glib::timeout_add_local_once(
-                                        std::time::Duration::from_secs(3),
-                                        move || hide_status(&st3),
+                                    hide_status(&st2);
+                                    show_review_window(
+                                        &win_c2,
+                                        &db_inner,
+                                        &runtime_c2,
+                                        &st2,
+                                        ReviewedTranscript {
+                                            copied,
+                                            // A fix goes back to History only where it holds the text as copied
+                                            history_id: stored_id.filter(|_| {
+                                                template.is_none()
+                                                    && history_mode == crate::db::HistoryMode::Full
+                                            }),
+                                            original: text,
+                                            language,
+                                            provider: usage_provider.clone(),
+                                            low_confidence,
+                                        },
                                     );
+                                    *state_c2.borrow_mut() = State::Idle;
+                                    return glib::ControlFlow::Break;
+                                }
+                                // Live dictation typed the text; leave the clipboard alone
+                                let delivered = if live {
+                                    Ok(())
+                                } else {
+                                    crate::input::copy_to_clipboard(&copied)
+                                };
+                                match delivered {
+                                    Ok(_) => {
+                                        log_transcript(
+                                            &runtime_c2.borrow().session_log,
+                                            &db_inner,
+                                            &text,
+                                            &usage_provider,
+                                        );
+                                        sounds.play(SoundEvent::Success);
+                                        btn2.remove_css_class("processing");
+                                        btn2.add_css_class("done");
+
+                                        show_status(
+                                            &st2,
+                                            &crate::confidence::status(
+                                                if live { "Typed" } else { "Copied" },
+                                                low_confidence,
+                                            ),
+                                        );
+                                        if auto_paste && !live {
+                                            paste_into_focused_app(
+                                                copied.clone(),
+                                                std::time::Duration::ZERO,
+                                            );
+                                        }
+                                        if announce
+                                            && let Err(e) = crate::announce::announce(
+                                                &crate::announce::message(&text, live),
+                                            )
+                                        {
+                                            tracing::warn!("Announcement failed: {e}");
+                                        }
+                                        if notify {
+                                            crate::notify::send(&text, live);
+                                        }
+                                        let tip = coaching_tip(&runtime_c2, &db_inner);
+                                        let st3 = st2.clone();
+                                        let btn3 = btn2.clone();
+                                        glib::timeout_add_local_once(
+                                            std::time::Duration::from_secs(2),
+                                            move || {
+                                                btn3.remove_css_class("done");
+                                                let Some(tip) = tip else {
+                                                    hide_status(&st3);
+                                                    return;
+                                                };
+                                                show_status(&st3, tip);
+                                                glib::timeout_add_local_once(
+                                                    std::time::Duration::from_secs(4),
+                                                    move || hide_status(&st3),
+                                                );
+                                            },
+                                        );
+                                    }
+                                    Err(e) => {
+                                        tracing::error!("Clipboard error: {e}");
+                                        sounds.play(SoundEvent::Error);
+                                        btn2.remove_css_class("processing");
+
+                                        show_status(&st2, "Copy failed");
+                                        let st3 = st2.clone();
+                                        glib::timeout_add_local_once(
+                                            std::time::Duration::from_secs(3),
+                                            move || hide_status(&st3),
+                                        );
+                                    }
                                 }
+                                *state_c2.borrow_mut() = State::Idle;
+                                return glib::ControlFlow::Break;
+                            }
+                            Ok(SttMsg::Done(Err(e), _)) => {
+                                tracing::error!("Transcription error: {e}");
+                                sounds.play(SoundEvent::Error);
+                                // The routed backend may have gone down; re-probe now
+                                if auto_mode {
+                                    start_auto_probe(&runtime_c2, &db_inner, &config_c2);
+                                }
+                                btn2.remove_css_class("processing");
+                                *state_c2.borrow_mut() = State::Idle;
+                                show_failure(
+                                    &btn2,
+                                    &st2,
+                                    &runtime_c2,
+                                    &state_c2,
+                                    &db_inner,
+                                    &e,
+                                    retry_audio.take(),
+                                );
+                                return glib::ControlFlow::Break;
+                            }
+                            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                                return glib::ControlFlow::Continue;
+                            }
+                            Err(_) => {
+                                *state_c2.borrow_mut() = State::Idle;
+                                btn2.remove_css_class("processing");
+                                return glib::ControlFlow::Break;
                             }
-                            *state_c2.borrow_mut() = State::Idle;
-                            glib::ControlFlow::Break
-                        }
-                        Ok(Err(e)) => {
-                            eprintln!("Transcription error: {e}");
-                            btn2.remove_css_class("processing");
-                            show_status(&st2, "Error!");
-                            let st3 = st2.clone();
-                            glib::timeout_add_local_once(
-                                std::time::Duration::from_secs(3),
-                                move || hide_status(&st3),
-                            );
-                            *state_c2.borrow_mut() = State::Idle;
-                            glib::ControlFlow::Break
-                        }
-                        Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-                        Err(_) => {
-                            *state_c2.borrow_mut() = State::Idle;
-                            btn2.remove_css_class("processing");
-                            glib::ControlFlow::Break
                         }
                     }
                 });
@@ -642,25 +2118,222 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         &initial_provider.to_variant(),
     );
 
+    // Providers blocked by organization policy point at a disabled action,
+    // so GTK renders them greyed out.
+    let policy_locked_action =
+        gtk4::gio::SimpleAction::new("policy-locked", Some(&String::static_variant_type()));
+    policy_locked_action.set_enabled(false);
+    app.add_action(&policy_locked_action);
+    let mode_target = |id: &str, url: Option<&str>| {
+        if config.policy.allows(id, url) {
+            format!("app.transcription-mode::{id}")
+        } else {
+            format!("app.policy-locked::{id}")
+        }
+    };
+
     let stt_api_section = gtk4::gio::Menu::new();
+    stt_api_section.append(
+        Some("Auto (fastest)"),
+        Some(&mode_target(crate::auto::AUTO_PROVIDER, None)),
+    );
     for preset in config::API_PRESETS {
-        stt_api_section.append(
-            Some(preset.label),
-            Some(&format!("app.transcription-mode::{}", preset.id)),
-        );
+        stt_api_section.append(Some(preset.label), Some(&mode_target(preset.id, None)));
     }
+    // Custom endpoints are allowed if a local one would be; the actual URL is
+    // checked when the dialog is saved.
     stt_api_section.append(
         Some("Custom API..."),
-        Some("app.transcription-mode::custom"),
+        Some(&mode_target("custom", Some("http://localhost"))),
     );
 
     let stt_local_section = gtk4::gio::Menu::new();
+    let stt_multi_section = gtk4::gio::Menu::new();
     for lm in config::LOCAL_MODEL_PRESETS {
-        stt_local_section.append(
+        let section = if lm.multilingual {
+            &stt_multi_section
+        } else {
+            &stt_local_section
+        };
+        section.append(
             Some(&format!("{} ({})", lm.label, lm.size_label)),
-            Some(&format!("app.transcription-mode::{}", lm.id)),
+            Some(&mode_target(lm.id, None)),
+        );
+    }
+
+    // Speaker labels need the tinydiarize model ("Small (speaker turns)")
+    stt_local_section.append(Some("Label speakers"), Some("app.label-speakers"));
+
+    // Vosk: small single-language models for near-instant transcription
+    let stt_vosk_section = gtk4::gio::Menu::new();
+    for vm in config::VOSK_MODEL_PRESETS {
+        stt_vosk_section.append(
+            Some(&format!("{} ({})", vm.label, vm.size_label)),
+            Some(&mode_target(vm.id, None)),
+        );
+    }
+
+    stt_multi_section.append(
+        Some("Custom model..."),
+        Some(&mode_target(config::CUSTOM_LOCAL_MODEL, None)),
+    );
+
+    // Spoken language for multilingual models
+    let language_action = gtk4::gio::SimpleAction::new_stateful(
+        "stt-language",
+        Some(&String::static_variant_type()),
+        &runtime.borrow().stt_language.to_variant(),
+    );
+    let language_menu = gtk4::gio::Menu::new();
+    for (code, label) in config::STT_LANGUAGES {
+        language_menu.append(Some(label), Some(&format!("app.stt-language::{code}")));
+    }
+    stt_multi_section.append_submenu(Some("Language"), &language_menu);
+
+    // What finished dictations leave in History
+    let history_mode_action = gtk4::gio::SimpleAction::new_stateful(
+        "history-mode",
+        Some(&String::static_variant_type()),
+        &runtime.borrow().history_mode.as_str().to_variant(),
+    );
+    let history_mode_menu = gtk4::gio::Menu::new();
+    history_mode_menu.append(Some("Full text"), Some("app.history-mode::full"));
+    history_mode_menu.append(Some("Preview only"), Some("app.history-mode::preview"));
+    history_mode_menu.append(Some("Nothing"), Some("app.history-mode::off"));
+
+    // Post-processing: a chat model rewrites transcripts before delivery
+    let postprocess_initial = db
+        .lock()
+        .ok()
+        .and_then(|d| {
+            d.get_setting(crate::postprocess::MODE_SETTING)
+                .ok()
+                .flatten()
+        })
+        .unwrap_or_else(|| crate::postprocess::OFF.to_string());
+    let postprocess_action = gtk4::gio::SimpleAction::new_stateful(
+        "postprocess-mode",
+        Some(&String::static_variant_type()),
+        &postprocess_initial.to_variant(),
+    );
+    let postprocess_menu = gtk4::gio::Menu::new();
+    postprocess_menu.append(Some("Off"), Some("app.postprocess-mode::off"));
+    for mode in crate::postprocess::MODES {
+        postprocess_menu.append(
+            Some(mode.label),
+            Some(&format!("app.postprocess-mode::{}", mode.id)),
+        );
+    }
+    postprocess_menu.append(Some("Custom Prompt"), Some("app.postprocess-mode::custom"));
+    let postprocess_setup = gtk4::gio::Menu::new();
+    postprocess_setup.append(Some("Set Up..."), Some("app.postprocess-setup"));
+    postprocess_menu.append_section(None, &postprocess_setup);
+
+    // Retention windows: how long History text and recordings are kept
+    let retention = db
+        .lock()
+        .map(|d| crate::retention::Retention::load(&d))
+        .unwrap_or_default();
+    let retention_state =
+        |days: Option<u32>| days.map_or_else(|| "forever".to_string(), |d| d.to_string());
+    let text_retention_action = gtk4::gio::SimpleAction::new_stateful(
+        "text-retention",
+        Some(&String::static_variant_type()),
+        &retention_state(retention.text_days).to_variant(),
+    );
+    let audio_retention_action = gtk4::gio::SimpleAction::new_stateful(
+        "audio-retention",
+        Some(&String::static_variant_type()),
+        &retention_state(retention.audio_days).to_variant(),
+    );
+    let auto_split_menu = gtk4::gio::Menu::new();
+    auto_split_menu.append(Some("Off"), Some("app.auto-split::off"));
+    for minutes in crate::split::CHOICES {
+        auto_split_menu.append(
+            Some(&format!("Every {minutes} minutes")),
+            Some(&format!("app.auto-split::{minutes}")),
+        );
+    }
+    let text_retention_menu = gtk4::gio::Menu::new();
+    text_retention_menu.append(Some("Forever"), Some("app.text-retention::forever"));
+    for days in crate::retention::TEXT_CHOICES {
+        text_retention_menu.append(
+            Some(&format!("{days} days")),
+            Some(&format!("app.text-retention::{days}")),
+        );
+    }
+    let audio_retention_menu = gtk4::gio::Menu::new();
+    audio_retention_menu.append(Some("Forever"), Some("app.audio-retention::forever"));
+    for days in crate::retention::AUDIO_CHOICES {
+        audio_retention_menu.append(
+            Some(&if *days == 1 {
+                "1 day".to_string()
+            } else {
+                format!("{days} days")
+            }),
+            Some(&format!("app.audio-retention::{days}")),
+        );
+    }
+
+    // Sounds: a sound (or none) and a volume per event
+    let sounds_menu = gtk4::gio::Menu::new();
+    let mut sound_actions = Vec::new();
+    for event in SoundEvent::ALL {
+        let name = event.id().replace('_', "-");
+        let sound_action = gtk4::gio::SimpleAction::new_stateful(
+            &format!("sound-{name}"),
+            Some(&String::static_variant_type()),
+            &initial_sounds.sound(event).as_setting().to_variant(),
+        );
+        let volume_action = gtk4::gio::SimpleAction::new_stateful(
+            &format!("sound-{name}-volume"),
+            Some(&String::static_variant_type()),
+            &initial_sounds.volume(event).to_string().to_variant(),
+        );
+        let choices = gtk4::gio::Menu::new();
+        for sound in crate::sounds::Sound::BUILT_IN {
+            choices.append(
+                Some(sound.label()),
+                Some(&format!("app.sound-{name}::{}", sound.as_setting())),
+            );
+        }
+        let volumes = gtk4::gio::Menu::new();
+        for volume in crate::sounds::VOLUME_CHOICES {
+            volumes.append(
+                Some(&format!("{volume}%")),
+                Some(&format!("app.sound-{name}-volume::{volume}")),
+            );
+        }
+        let event_menu = gtk4::gio::Menu::new();
+        event_menu.append_section(None, &choices);
+        event_menu.append_section(Some("Volume"), &volumes);
+        sounds_menu.append_submenu(Some(event.label()), &event_menu);
+        sound_actions.push((event, sound_action, volume_action));
+    }
+
+    // Quiet hours and the Do Not Disturb override
+    let quiet_hours_action = gtk4::gio::SimpleAction::new_stateful(
+        "quiet-hours",
+        Some(&String::static_variant_type()),
+        &crate::quiet::format_schedule(initial_quiet).to_variant(),
+    );
+    let quiet_menu = gtk4::gio::Menu::new();
+    quiet_menu.append(Some("Off"), Some("app.quiet-hours::off"));
+    for window in crate::quiet::SCHEDULE_CHOICES {
+        quiet_menu.append(
+            Some(&window.replace('-', "\u{2013}")),
+            Some(&format!("app.quiet-hours::{window}")),
         );
     }
+    let dnd_action = gtk4::gio::SimpleAction::new_stateful(
+        "dnd",
+        Some(&String::static_variant_type()),
+        &initial_dnd.as_str().to_variant(),
+    );
+    let dnd_menu = gtk4::gio::Menu::new();
+    dnd_menu.append(Some("Follow Quiet Hours"), Some("app.dnd::auto"));
+    dnd_menu.append(Some("On"), Some("app.dnd::on"));
+    dnd_menu.append(Some("Off"), Some("app.dnd::off"));
 
     // TTS section — voice selection
     let tts_initial = if initial_tts_provider == TtsProvider::Piper {
@@ -690,13 +2363,124 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     tts_manage.append(Some("Delete TTS"), Some("app.tts-delete"));
 
     let actions_section = gtk4::gio::Menu::new();
+    actions_section.append(Some("Local only"), Some("app.local-only"));
+    actions_section.append(
+        Some(&format!(
+            "Wake Word (\u{201c}{}\u{201d})",
+            crate::wakeword::WAKE_PHRASE
+        )),
+        Some("app.wake-word"),
+    );
+    actions_section.append(Some("Type as I Speak"), Some("app.live-dictation"));
+    actions_section.append(Some("Dictation Commands"), Some("app.dictation-commands"));
+    actions_section.append(
+        Some("Review Before Copying"),
+        Some("app.review-transcripts"),
+    );
+    actions_section.append(Some("Review Low Confidence"), Some("app.confidence-review"));
+    actions_section.append(Some("Pause Media While Recording"), Some("app.pause-media"));
+    actions_section.append(
+        Some("Silence Notifications While Recording"),
+        Some("app.silence-notifications"),
+    );
+    actions_section.append(Some("Dry Run"), Some("app.dry-run"));
+    actions_section.append(
+        Some("Announce to Screen Reader"),
+        Some("app.announce-transcripts"),
+    );
+    actions_section.append(Some("Notify with Preview"), Some("app.notify-transcripts"));
+    actions_section.append(Some("Live Captions"), Some("app.live-captions"));
+    actions_section.append(Some("Remote Control..."), Some("app.remote-control"));
+    actions_section.append(
+        Some("Recording Dot on Every Monitor"),
+        Some("app.monitor-dots"),
+    );
+    actions_section.append(Some("Keep Above Other Windows"), Some("app.keep-above"));
+    actions_section.append(Some("Show on All Workspaces"), Some("app.all-workspaces"));
+    if cfg!(target_os = "linux") {
+        actions_section.append(Some("Tray Icon"), Some("app.tray-icon"));
+        actions_section.append(Some("Show Floating Button"), Some("app.show-button"));
+        actions_section.append(Some("Start at Login"), Some("app.autostart"));
+    }
+    // Docking needs X11 or a layer-shell window; elsewhere the compositor
+    // places it
+    let placeable = cfg!(target_os = "linux")
+        && (crate::stacking::is_layer_window(&window) || !crate::stacking::on_wayland());
+    let monitor_menu = gtk4::gio::Menu::new();
+    if placeable {
+        let corner_menu = gtk4::gio::Menu::new();
+        for corner in crate::stacking::Corner::ALL {
+            corner_menu.append(
+                Some(corner.label()),
+                Some(&format!("app.screen-corner::{}", corner.as_str())),
+            );
+        }
+        actions_section.append_submenu(Some("Screen Corner"), &corner_menu);
+        fill_monitor_menu(&monitor_menu);
+        actions_section.append_submenu(Some("Move to Monitor"), &monitor_menu);
+    }
+    let theme_menu = gtk4::gio::Menu::new();
+    for choice in crate::appearance::Theme::ALL {
+        theme_menu.append(
+            Some(choice.label()),
+            Some(&format!("app.theme::{}", choice.as_str())),
+        );
+    }
+    actions_section.append_submenu(Some("Theme"), &theme_menu);
+    let size_menu = gtk4::gio::Menu::new();
+    for size in crate::appearance::WidgetSize::PRESETS {
+        size_menu.append(
+            Some(&size.label()),
+            Some(&format!("app.widget-size::{}", size.as_str())),
+        );
+    }
+    actions_section.append_submenu(Some("Size"), &size_menu);
+    actions_section.append_submenu(Some("Sounds"), &sounds_menu);
+    actions_section.append_submenu(Some("Quiet Hours"), &quiet_menu);
+    actions_section.append_submenu(Some("Do Not Disturb"), &dnd_menu);
     actions_section.append(Some("Read Clipboard"), Some("app.read-clipboard"));
+    actions_section.append(Some("Transcribe Files..."), Some("app.transcribe-files"));
+    actions_section.append(Some("Benchmark..."), Some("app.benchmark"));
+    actions_section.append_submenu(Some("Post-Processing"), &postprocess_menu);
+    actions_section.append(Some("Vocabulary..."), Some("app.vocabulary"));
+    actions_section.append(Some("Save Transcripts to Folder..."), Some("app.autosave"));
+    actions_section.append(Some("Hooks..."), Some("app.hooks"));
     actions_section.append(Some("History"), Some("app.show-history"));
+    actions_section.append(Some("Session Log"), Some("app.show-session-log"));
+    actions_section.append_submenu(Some("Store in History"), &history_mode_menu);
+    actions_section.append_submenu(Some("Keep Text"), &text_retention_menu);
+    actions_section.append(Some("Keep Recordings"), Some("app.store-audio"));
+    actions_section.append_submenu(Some("Keep Audio"), &audio_retention_menu);
+    actions_section.append_submenu(Some("Split Long Recordings"), &auto_split_menu);
+    actions_section.append(Some("Start Meeting..."), Some("app.start-meeting"));
+    actions_section.append(Some("Lock History..."), Some("app.history-lock"));
+    actions_section.append(Some("Encrypt History..."), Some("app.history-encryption"));
+    actions_section.append(Some("Usage"), Some("app.show-usage"));
+    actions_section.append(Some("Stats"), Some("app.show-stats"));
+    actions_section.append(Some("Dictation Tips"), Some("app.coaching-hints"));
+    actions_section.append(Some("Manage Models..."), Some("app.manage-models"));
+    actions_section.append(Some("Show Logs"), Some("app.show-logs"));
+    actions_section.append(Some("Preferences..."), Some("app.preferences"));
+    actions_section.append(Some("Export Settings..."), Some("app.export-settings"));
+    actions_section.append(Some("Import Settings..."), Some("app.import-settings"));
+    actions_section.append(Some("Cancel Download"), Some("app.cancel-download"));
     actions_section.append(Some("Quit"), Some("app.quit"));
 
     let menu = gtk4::gio::Menu::new();
+    if config.policy.is_managed() {
+        let policy_section = gtk4::gio::Menu::new();
+        policy_section.append(
+            Some("Managed by your organization"),
+            Some("app.policy-locked::info"),
+        );
+        menu.append_section(None, &policy_section);
+    }
     menu.append_section(Some("STT — API"), &stt_api_section);
-    menu.append_section(Some("STT — Local"), &stt_local_section);
+    menu.append_section(Some("STT — Local (English)"), &stt_local_section);
+    menu.append_section(Some("STT — Local (Multilingual)"), &stt_multi_section);
+    if crate::vosk_stt::AVAILABLE {
+        menu.append_section(Some("STT — Local (Vosk, fast)"), &stt_vosk_section);
+    }
     menu.append_section(Some("TTS — Voices"), &tts_section);
     menu.append_section(None, &tts_manage);
     menu.append_section(None, &actions_section);
@@ -715,6 +2499,69 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     });
     button.add_controller(gesture);
 
+    // Drop a shared .whispercrabs-provider file on the button to import it,
+    // or an audio or video file to transcribe it like a recording
+    let drop_target =
+        gtk4::DropTarget::new(gtk4::gio::File::static_type(), gtk4::gdk::DragAction::COPY);
+    let runtime_drop = Rc::clone(&runtime);
+    let state_drop = Rc::clone(&state);
+    let db_drop = Arc::clone(&db);
+    let config_drop = Arc::clone(&config);
+    let mode_drop = mode_action.clone();
+    let status_drop = status.clone();
+    let win_drop = window.clone();
+    let btn_drop = button.clone();
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Some(path) = value.get::<gtk4::gio::File>().ok().and_then(|f| f.path()) else {
+            return false;
+        };
+        let media = crate::decode::is_audio_file(&path) || crate::audio::is_video_file(&path);
+        if !media
+            && path
+                .extension()
+                .is_none_or(|e| e != crate::provider_file::EXTENSION)
+        {
+            return false;
+        }
+        if *state_drop.borrow() != State::Idle || runtime_drop.borrow().downloading {
+            return false;
+        }
+        if media {
+            return transcribe_dropped_file(
+                &path,
+                &btn_drop,
+                &status_drop,
+                &runtime_drop,
+                &state_drop,
+                &db_drop,
+            );
+        }
+        match crate::provider_file::load(&path) {
+            Ok(file) => {
+                show_custom_api_dialog(
+                    &win_drop,
+                    &runtime_drop,
+                    &db_drop,
+                    &mode_drop,
+                    &status_drop,
+                    &config_drop,
+                    Some(file),
+                );
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Provider import failed: {e}");
+                show_status(&status_drop, "Invalid provider file");
+                let st = status_drop.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st);
+                });
+                false
+            }
+        }
+    });
+    button.add_controller(drop_target);
+
     // Action: transcription mode switch (provider-based)
     let runtime_mode = Rc::clone(&runtime);
     let state_mode = Rc::clone(&state);
@@ -739,18 +2586,60 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
         }
 
         // No-op if already on this provider
-        if chosen == runtime_mode.borrow().active_provider {
+        {
+            let rt = runtime_mode.borrow();
+            let current = if rt.auto_mode {
+                crate::auto::AUTO_PROVIDER
+            } else {
+                rt.active_provider.as_str()
+            };
+            // Re-picking "Custom model..." opens the file chooser again
+            if chosen == current && chosen != config::CUSTOM_LOCAL_MODEL {
+                return;
+            }
+        }
+
+        // Organization policy (custom URLs are checked when saved)
+        if chosen != "custom" && !config_mode.policy.allows(&chosen, None) {
+            show_status(&status_mode, "Blocked by policy");
+            let st = status_mode.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                hide_status(&st);
+            });
+            action.set_state(&runtime_mode.borrow().active_provider.to_variant());
             return;
         }
 
-        if let Some(local_preset) = config::find_local_model(&chosen) {
+        // A manual choice replaces whatever a profile rule overrode
+        if !runtime_mode.borrow().rule_switching
+            && let Ok(d) = db_mode.lock()
+        {
+            let _ = d.delete_setting("profile_rule_base");
+        }
+
+        runtime_mode.borrow_mut().auto_mode = chosen == crate::auto::AUTO_PROVIDER;
+
+        if chosen == crate::auto::AUTO_PROVIDER {
+            switch_to_auto(&runtime_mode, &config_mode, &db_mode, action, &status_mode);
+        } else if chosen == config::CUSTOM_LOCAL_MODEL {
+            show_custom_model_dialog(&win_mode, &runtime_mode, &db_mode, action, &status_mode);
+        } else if let Some(local_preset) = config::find_local_model(&chosen) {
+            switch_to_local(
+                &runtime_mode,
+                &config_mode,
+                &db_mode,
+                action,
+                &status_mode,
+                local_preset.id,
+            );
+        } else if let Some(vosk_preset) = config::find_vosk_model(&chosen) {
             switch_to_local(
                 &runtime_mode,
                 &config_mode,
                 &db_mode,
                 action,
                 &status_mode,
-                local_preset,
+                vosk_preset.id,
             );
         } else if chosen == "custom" {
             show_custom_api_dialog(
@@ -760,6 +2649,7 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
                 action,
                 &status_mode,
                 &config_mode,
+                None,
             );
         } else if let Some(preset) = config::find_preset(&chosen) {
             switch_to_preset(
@@ -775,72 +2665,669 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     });
     app.add_action(&mode_action);
 
-    // Action: show history
-    let history_action = gtk4::gio::SimpleAction::new("show-history", None);
-    let db_hist = Arc::clone(&db);
-    let win_ref = window.clone();
-    history_action.connect_activate(move |_, _| {
-        show_history_dialog(&win_ref, &db_hist);
-    });
-    app.add_action(&history_action);
+    if let Some((dir, local_path)) = startup_models_unavailable {
+        let (runtime_m, config_m, db_m) =
+            (Rc::clone(&runtime), Arc::clone(&config), Arc::clone(&db));
+        let (action_m, status_m) = (mode_action.clone(), status.clone());
+        let provider = initial_provider.clone();
+        glib::idle_add_local_once(move || {
+            show_models_unavailable_dialog(
+                &runtime_m, &config_m, &db_m, &action_m, &status_m, &provider, &dir, local_path,
+            );
+        });
+    }
 
-    // Action: quit
-    let quit_action = gtk4::gio::SimpleAction::new("quit", None);
-    quit_action.connect_activate(move |_, _| {
-        std::process::exit(0);
+    // Auto mode: re-probe backends periodically so routing follows outages
+    if runtime.borrow().auto_mode {
+        start_auto_probe(&runtime, &db, &config);
+    }
+    let runtime_auto = Rc::clone(&runtime);
+    let db_auto = Arc::clone(&db);
+    let config_auto = Arc::clone(&config);
+    glib::timeout_add_seconds_local(crate::auto::PROBE_INTERVAL_SECS, move || {
+        if runtime_auto.borrow().auto_mode {
+            start_auto_probe(&runtime_auto, &db_auto, &config_auto);
+        }
+        glib::ControlFlow::Continue
     });
-    app.add_action(&quit_action);
 
-    // --- Save position on close ---
-    let db_close = Arc::clone(&db);
-    window.connect_close_request(move |win| {
-        save_window_position(win, &db_close);
-        glib::Propagation::Proceed
+    // Action: "Local only" privacy switch — blocks every network code path
+    let local_only_initial = db.lock().map_or(config.policy.local_only, |d| {
+        crate::transcription::local_only(&d, &config)
     });
-
-    // --- Position: saved or bottom-right ---
-    let db_pos = Arc::clone(&db);
-    window.connect_realize(move |win| {
-        if let Some(surface) = win.surface()
-            && let Some(toplevel) = surface.downcast_ref::<gdk::Toplevel>()
-        {
-            toplevel.set_decorated(false);
+    crate::policy::set_local_only(local_only_initial);
+    local_badge.set_visible(local_only_initial);
+    let local_only_action =
+        gtk4::gio::SimpleAction::new_stateful("local-only", None, &local_only_initial.to_variant());
+    // Admin-enforced local-only can't be switched off
+    local_only_action.set_enabled(!config.policy.local_only);
+    let db_local = Arc::clone(&db);
+    let badge = local_badge.clone();
+    let status_local = status.clone();
+    local_only_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        crate::policy::set_local_only(enabled);
+        action.set_state(&enabled.to_variant());
+        badge.set_visible(enabled);
+        if let Ok(d) = db_local.lock() {
+            let _ = d.set_setting("local_only", if enabled { "true" } else { "false" });
         }
-        let w = win.clone();
-        let db_p = Arc::clone(&db_pos);
-        glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
-            position_window(&w, &db_p);
+        show_status(
+            &status_local,
+            if enabled {
+                "Local only: network off"
+            } else {
+                "Network access on"
+            },
+        );
+        let st = status_local.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
         });
     });
+    app.add_action(&local_only_action);
 
-    // --- Esc key: stop recording ---
-    let esc_btn = button.clone();
-    let esc_state = Rc::clone(&state);
-    let esc_shortcut = gtk4::Shortcut::new(
-        gtk4::ShortcutTrigger::parse_string("Escape"),
-        Some(gtk4::CallbackAction::new(move |_, _| {
-            if *esc_state.borrow() == State::Recording {
-                esc_btn.emit_clicked();
-            }
-            glib::Propagation::Stop
-        })),
-    );
-    let esc_controller = gtk4::ShortcutController::new();
-    esc_controller.set_scope(gtk4::ShortcutScope::Global);
-    esc_controller.add_shortcut(esc_shortcut);
-    window.add_controller(esc_controller);
+    // Action: spoken language for multilingual local models
+    let runtime_lang = Rc::clone(&runtime);
+    let db_lang = Arc::clone(&db);
+    language_action.connect_activate(move |action, param| {
+        let Some(code) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        if !config::is_known_language(&code) {
+            return;
+        }
+        runtime_lang.borrow_mut().stt_language = code.clone();
+        if let Ok(d) = db_lang.lock() {
+            let _ = d.set_setting("stt_language", &code);
+        }
+        action.set_state(&code.to_variant());
+    });
+    app.add_action(&language_action);
 
-    // --- D-Bus action: "record" — triggered by GNOME shortcut ---
-    let record_action = gtk4::gio::SimpleAction::new("record", None);
-    let btn_rec = button.clone();
-    let state_rec = Rc::clone(&state);
-    let win_rec = window.clone();
+    // Action: "Label speakers" toggle for tinydiarize models
+    let label_speakers_action = gtk4::gio::SimpleAction::new_stateful(
+        "label-speakers",
+        None,
+        &initial_label_speakers.to_variant(),
+    );
+    let runtime_speakers = Rc::clone(&runtime);
+    let db_speakers = Arc::clone(&db);
+    label_speakers_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_speakers.borrow_mut().label_speakers = enabled;
+        if let Ok(d) = db_speakers.lock() {
+            let _ = d.set_setting("label_speakers", if enabled { "true" } else { "false" });
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&label_speakers_action);
+
+    // Action: history insert policy ("full", "preview" or "off")
+    let runtime_hmode = Rc::clone(&runtime);
+    let db_hmode = Arc::clone(&db);
+    history_mode_action.connect_activate(move |action, param| {
+        let Some(mode) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|m| crate::db::HistoryMode::parse(&m))
+        else {
+            return;
+        };
+        runtime_hmode.borrow_mut().history_mode = mode;
+        if let Ok(d) = db_hmode.lock() {
+            let _ = d.set_setting("history_mode", mode.as_str());
+        }
+        action.set_state(&mode.as_str().to_variant());
+    });
+    app.add_action(&history_mode_action);
+
+    // Action: post-processing mode ("off", a built-in mode or "custom"), persisted
+    let db_pmode = Arc::clone(&db);
+    let config_pmode = Arc::clone(&config);
+    let (win_pmode, status_pmode) = (window.clone(), status.clone());
+    postprocess_action.connect_activate(move |action, param| {
+        let Some(mode) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        if mode != crate::postprocess::OFF
+            && mode != crate::postprocess::CUSTOM
+            && crate::postprocess::find_mode(&mode).is_none()
+        {
+            return;
+        }
+        let Ok(d) = db_pmode.lock() else { return };
+        let _ = d.set_setting(crate::postprocess::MODE_SETTING, &mode);
+        action.set_state(&mode.to_variant());
+        // Missing a provider, model or prompt: say so and offer the setup
+        if let Err(e) = crate::postprocess::job(&d, &config_pmode) {
+            drop(d);
+            flash_status(&status_pmode, &e);
+            show_postprocess_dialog(&win_pmode, &db_pmode, &config_pmode);
+        }
+    });
+    app.add_action(&postprocess_action);
+    let postprocess_setup_action = gtk4::gio::SimpleAction::new("postprocess-setup", None);
+    let (db_psetup, config_psetup, win_psetup) =
+        (Arc::clone(&db), Arc::clone(&config), window.clone());
+    postprocess_setup_action.connect_activate(move |_, _| {
+        show_postprocess_dialog(&win_psetup, &db_psetup, &config_psetup);
+    });
+    app.add_action(&postprocess_setup_action);
+
+    // Action: edit the custom vocabulary
+    let vocabulary_action = gtk4::gio::SimpleAction::new("vocabulary", None);
+    let (db_vocab, runtime_vocab, win_vocab) =
+        (Arc::clone(&db), Rc::clone(&runtime), window.clone());
+    vocabulary_action.connect_activate(move |_, _| {
+        show_vocabulary_dialog(&win_vocab, &db_vocab, &runtime_vocab);
+    });
+    app.add_action(&vocabulary_action);
+
+    // Action: choose the folder every transcript is also written to
+    let autosave_action = gtk4::gio::SimpleAction::new("autosave", None);
+    let (db_autosave, win_autosave) = (Arc::clone(&db), window.clone());
+    autosave_action.connect_activate(move |_, _| {
+        show_autosave_dialog(win_autosave.upcast_ref(), &db_autosave);
+    });
+    app.add_action(&autosave_action);
+
+    // Action: edit the command and webhook run after each transcription
+    let hooks_action = gtk4::gio::SimpleAction::new("hooks", None);
+    let (db_hooks, win_hooks) = (Arc::clone(&db), window.clone());
+    hooks_action.connect_activate(move |_, _| {
+        show_hooks_dialog(&win_hooks, &db_hooks);
+    });
+    app.add_action(&hooks_action);
+
+    // --- D-Bus action: "set-hooks" — JSON object with `command` and `url` ---
+    let set_hooks_action =
+        gtk4::gio::SimpleAction::new("set-hooks", Some(&String::static_variant_type()));
+    let db_set_hooks = Arc::clone(&db);
+    set_hooks_action.connect_activate(move |_, param| {
+        let Some(json_str) = param.and_then(|p| p.get::<String>()) else {
+            tracing::warn!("set-hooks: expected string parameter");
+            return;
+        };
+        if json_str.len() > 4096 {
+            tracing::warn!("set-hooks: JSON too large");
+            return;
+        }
+        tracing::info!("[dbus] 'set-hooks' action activated");
+        let saved = crate::hooks::parse(&json_str).and_then(|hooks| {
+            let d = db_set_hooks.lock().map_err(|e| e.to_string())?;
+            crate::hooks::save(&d, &hooks).map_err(|e| e.to_string())
+        });
+        if let Err(e) = saved {
+            tracing::warn!("set-hooks: {e}");
+        }
+    });
+    app.add_action(&set_hooks_action);
+
+    // --- D-Bus action: "set-vocabulary" — custom terms, one per line or comma-separated ---
+    let set_vocabulary_action =
+        gtk4::gio::SimpleAction::new("set-vocabulary", Some(&String::static_variant_type()));
+    let (db_set_vocab, runtime_set_vocab) = (Arc::clone(&db), Rc::clone(&runtime));
+    set_vocabulary_action.connect_activate(move |_, param| {
+        let Some(text) = param.and_then(|p| p.get::<String>()) else {
+            tracing::warn!("set-vocabulary: expected string parameter");
+            return;
+        };
+        tracing::info!("[dbus] 'set-vocabulary' action activated");
+        let terms = crate::vocabulary::parse(&text);
+        if let Ok(d) = db_set_vocab.lock()
+            && let Err(e) = crate::vocabulary::save(&d, &terms)
+        {
+            tracing::warn!("set-vocabulary: {e}");
+            return;
+        }
+        runtime_set_vocab.borrow_mut().vocabulary = terms;
+    });
+    app.add_action(&set_vocabulary_action);
+
+    // Action: split long recordings into parts every N minutes, persisted
+    let auto_split_action = gtk4::gio::SimpleAction::new_stateful(
+        "auto-split",
+        Some(&String::static_variant_type()),
+        &crate::split::state(runtime.borrow().auto_split).to_variant(),
+    );
+    let (runtime_split, db_split) = (Rc::clone(&runtime), Arc::clone(&db));
+    auto_split_action.connect_activate(move |action, param| {
+        let Some(value) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        let minutes = crate::split::parse_minutes(&value);
+        if minutes.is_none() && value != "off" {
+            return;
+        }
+        runtime_split.borrow_mut().auto_split = minutes;
+        if let Ok(d) = db_split.lock() {
+            let _ = match minutes {
+                Some(m) => d.set_setting(crate::split::SPLIT_SETTING, &m.to_string()),
+                None => d.delete_setting(crate::split::SPLIT_SETTING),
+            };
+        }
+        action.set_state(&crate::split::state(minutes).to_variant());
+    });
+    app.add_action(&auto_split_action);
+
+    // Action: open the meeting window, recording until it is stopped
+    let meeting_action = gtk4::gio::SimpleAction::new("start-meeting", None);
+    let (runtime_meeting, config_meeting, db_meeting, win_meeting) = (
+        Rc::clone(&runtime),
+        Arc::clone(&config),
+        Arc::clone(&db),
+        window.clone(),
+    );
+    meeting_action.connect_activate(move |_, _| {
+        show_meeting_window(&win_meeting, &runtime_meeting, &config_meeting, &db_meeting);
+    });
+    app.add_action(&meeting_action);
+
+    // Action: length of a meeting segment in seconds, persisted
+    let initial_meeting_secs = db
+        .lock()
+        .map(|d| crate::meeting::segment_secs(&d))
+        .unwrap_or(crate::meeting::DEFAULT_SEGMENT_SECS);
+    let meeting_segment_action = gtk4::gio::SimpleAction::new_stateful(
+        "meeting-segment",
+        Some(&String::static_variant_type()),
+        &initial_meeting_secs.to_string().to_variant(),
+    );
+    let db_meeting_secs = Arc::clone(&db);
+    meeting_segment_action.connect_activate(move |action, param| {
+        let Some(secs) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|v| crate::meeting::parse_secs(&v))
+        else {
+            return;
+        };
+        if let Ok(d) = db_meeting_secs.lock() {
+            let _ = d.set_setting(crate::meeting::SEGMENT_SETTING, &secs.to_string());
+        }
+        action.set_state(&secs.to_string().to_variant());
+    });
+    app.add_action(&meeting_segment_action);
+
+    // Actions: sound and volume per event, persisted and previewed on change
+    for (event, sound_action, volume_action) in sound_actions {
+        let key = format!("sound_{}", event.id());
+        let (runtime_snd, db_snd, key_snd) = (Rc::clone(&runtime), Arc::clone(&db), key.clone());
+        sound_action.connect_activate(move |action, param| {
+            let Some(sound) = param
+                .and_then(|p| p.get::<String>())
+                .and_then(|v| crate::sounds::Sound::parse(&v))
+            else {
+                return;
+            };
+            let value = sound.as_setting();
+            if let Ok(d) = db_snd.lock() {
+                let _ = d.set_setting(&key_snd, &value);
+            }
+            action.set_state(&value.to_variant());
+            let mut rt = runtime_snd.borrow_mut();
+            rt.sounds.set_sound(event, sound);
+            rt.sounds.play(event);
+        });
+        app.add_action(&sound_action);
+
+        let (runtime_vol, db_vol) = (Rc::clone(&runtime), Arc::clone(&db));
+        volume_action.connect_activate(move |action, param| {
+            let Some(volume) = param
+                .and_then(|p| p.get::<String>())
+                .and_then(|v| crate::sounds::parse_volume(&v))
+            else {
+                return;
+            };
+            if let Ok(d) = db_vol.lock() {
+                let _ = d.set_setting(&format!("{key}_volume"), &volume.to_string());
+            }
+            action.set_state(&volume.to_string().to_variant());
+            let mut rt = runtime_vol.borrow_mut();
+            rt.sounds.set_volume(event, volume);
+            rt.sounds.play(event);
+        });
+        app.add_action(&volume_action);
+    }
+
+    // Actions: quiet hours schedule and Do Not Disturb, persisted
+    let db_quiet = Arc::clone(&db);
+    quiet_hours_action.connect_activate(move |action, param| {
+        let Some(value) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        let schedule = crate::quiet::parse_schedule(&value);
+        if value != "off" && schedule.is_none() {
+            return;
+        }
+        crate::quiet::set_schedule(schedule);
+        if let Ok(d) = db_quiet.lock() {
+            let _ = d.set_setting("quiet_hours", &crate::quiet::format_schedule(schedule));
+        }
+        action.set_state(&value.to_variant());
+    });
+    app.add_action(&quiet_hours_action);
+
+    let db_dnd = Arc::clone(&db);
+    dnd_action.connect_activate(move |action, param| {
+        let Some(dnd) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|v| crate::quiet::Dnd::parse(&v))
+        else {
+            return;
+        };
+        crate::quiet::set_dnd(dnd);
+        if let Ok(d) = db_dnd.lock() {
+            let _ = d.set_setting("dnd", dnd.as_str());
+        }
+        action.set_state(&dnd.as_str().to_variant());
+    });
+    app.add_action(&dnd_action);
+
+    // Actions: retention windows, enforced right away and then hourly
+    for (action, key) in [
+        (&text_retention_action, "text_retention_days"),
+        (&audio_retention_action, "audio_retention_days"),
+    ] {
+        let db_ret = Arc::clone(&db);
+        let audio_dir = config.audio_dir.clone();
+        action.connect_activate(move |action, param| {
+            let Some(value) = param.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            let days = crate::retention::parse_days(&value);
+            if value != "forever" && days.is_none() {
+                return;
+            }
+            if let Ok(d) = db_ret.lock() {
+                let _ = match days {
+                    Some(days) => d.set_setting(key, &days.to_string()),
+                    None => d.delete_setting(key),
+                };
+            }
+            action.set_state(&value.to_variant());
+            run_maintenance(&db_ret, &audio_dir);
+        });
+        app.add_action(action);
+    }
+    run_maintenance(&db, &config.audio_dir);
+    let (db_maint, audio_maint) = (Arc::clone(&db), config.audio_dir.clone());
+    glib::timeout_add_local(crate::retention::MAINTENANCE_INTERVAL, move || {
+        run_maintenance(&db_maint, &audio_maint);
+        glib::ControlFlow::Continue
+    });
+
+    // Action: show history
+    let history_action = gtk4::gio::SimpleAction::new("show-history", None);
+    let db_hist = Arc::clone(&db);
+    let win_ref = window.clone();
+    let audio_dir = config.audio_dir.clone();
+    let (runtime_hist, config_hist, db_retr) =
+        (Rc::clone(&runtime), Arc::clone(&config), Arc::clone(&db));
+    let retranscribe = Retranscribe {
+        run: {
+            let (runtime, config, db) = (
+                Rc::clone(&runtime_hist),
+                Arc::clone(&config_hist),
+                Arc::clone(&db_retr),
+            );
+            Rc::new(move |audio: &std::path::Path, provider: Option<&str>| {
+                spawn_stored_transcription(&runtime, &config, &db, audio, provider)
+            })
+        },
+        choices: Rc::new(move || rerun_choices(&runtime_hist, &db_retr, &config_hist)),
+    };
+    history_action.connect_activate(move |_, _| {
+        let lock = db_hist.lock().ok().and_then(|d| {
+            d.get_setting(crate::lock::HISTORY_LOCK_SETTING)
+                .ok()
+                .flatten()
+        });
+        match lock {
+            Some(hash) => {
+                let (win, db, audio_dir, retranscribe) = (
+                    win_ref.clone(),
+                    Arc::clone(&db_hist),
+                    audio_dir.clone(),
+                    retranscribe.clone(),
+                );
+                show_unlock_dialog(&win_ref, hash, move || {
+                    show_history_dialog(&win, &db, &audio_dir, &retranscribe);
+                });
+            }
+            None => show_history_dialog(&win_ref, &db_hist, &audio_dir, &retranscribe),
+        }
+    });
+    app.add_action(&history_action);
+
+    // Action: this session's transcripts; behind the History lock like History
+    let session_log_action = gtk4::gio::SimpleAction::new("show-session-log", None);
+    let (db_log, win_log, runtime_log) = (Arc::clone(&db), window.clone(), Rc::clone(&runtime));
+    session_log_action.connect_activate(move |_, _| {
+        let log = runtime_log.borrow().session_log.clone();
+        let lock = db_log.lock().ok().and_then(|d| {
+            d.get_setting(crate::lock::HISTORY_LOCK_SETTING)
+                .ok()
+                .flatten()
+        });
+        match lock {
+            Some(hash) => {
+                let win = win_log.clone();
+                show_unlock_dialog(&win_log, hash, move || {
+                    show_session_log_window(&win, &log);
+                });
+            }
+            None => show_session_log_window(&win_log, &log),
+        }
+    });
+    app.add_action(&session_log_action);
+
+    // Action: the log file, for looking into failures without a terminal
+    let logs_action = gtk4::gio::SimpleAction::new("show-logs", None);
+    let win_logs = window.clone();
+    logs_action.connect_activate(move |_, _| show_logs_window(&win_logs));
+    app.add_action(&logs_action);
+
+    // Action: abort the running model download (kept for resume)
+    let cancel_download_action = gtk4::gio::SimpleAction::new("cancel-download", None);
+    cancel_download_action.set_enabled(false);
+    let runtime_cancel = Rc::clone(&runtime);
+    cancel_download_action.connect_activate(move |_, _| {
+        let rt = runtime_cancel.borrow();
+        if rt.downloading {
+            rt.download_cancel
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+    app.add_action(&cancel_download_action);
+
+    // Action: set, change or remove the History lock
+    let history_lock_action = gtk4::gio::SimpleAction::new("history-lock", None);
+    let db_hlock = Arc::clone(&db);
+    let win_hlock = window.clone();
+    history_lock_action.connect_activate(move |_, _| {
+        show_history_lock_dialog(&win_hlock, &db_hlock);
+    });
+    app.add_action(&history_lock_action);
+
+    // Action: encrypt History at rest, change its key or decrypt it
+    let history_encryption_action = gtk4::gio::SimpleAction::new("history-encryption", None);
+    let db_enc = Arc::clone(&db);
+    let win_enc = window.clone();
+    history_encryption_action.connect_activate(move |_, _| {
+        show_history_encryption_dialog(&win_enc, &db_enc);
+    });
+    app.add_action(&history_encryption_action);
+
+    // Action: show usage / cost summary
+    let usage_action = gtk4::gio::SimpleAction::new("show-usage", None);
+    let db_usage = Arc::clone(&db);
+    let win_usage = window.clone();
+    usage_action.connect_activate(move |_, _| {
+        show_usage_dialog(&win_usage, &db_usage);
+    });
+    app.add_action(&usage_action);
+
+    // Action: dictation statistics
+    let stats_action = gtk4::gio::SimpleAction::new("show-stats", None);
+    let db_stats = Arc::clone(&db);
+    let win_stats = window.clone();
+    stats_action.connect_activate(move |_, _| {
+        show_stats_dialog(&win_stats, &db_stats);
+    });
+    app.add_action(&stats_action);
+
+    // Action: downloaded model manager
+    let models_action = gtk4::gio::SimpleAction::new("manage-models", None);
+    let runtime_models = Rc::clone(&runtime);
+    let config_models = Arc::clone(&config);
+    let win_models = window.clone();
+    models_action.connect_activate(move |_, _| {
+        show_models_dialog(&win_models, &runtime_models, &config_models);
+    });
+    app.add_action(&models_action);
+
+    // Action: quit
+    let quit_action = gtk4::gio::SimpleAction::new("quit", None);
+    quit_action.connect_activate(move |_, _| {
+        std::process::exit(0);
+    });
+    app.add_action(&quit_action);
+
+    // --- Save position on close ---
+    let db_close = Arc::clone(&db);
+    window.connect_close_request(move |win| {
+        if !crate::stacking::is_layer_window(win) {
+            save_window_position(win, &db_close);
+        }
+        glib::Propagation::Proceed
+    });
+
+    // --- Position: saved or bottom-right ---
+    let db_pos = Arc::clone(&db);
+    window.connect_realize(move |win| {
+        if let Some(surface) = win.surface()
+            && let Some(toplevel) = surface.downcast_ref::<gdk::Toplevel>()
+        {
+            toplevel.set_decorated(false);
+        }
+        let w = win.clone();
+        let db_p = Arc::clone(&db_pos);
+        // A layer-shell window is placed by its anchors
+        if crate::stacking::is_layer_window(win) {
+            return;
+        }
+        glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
+            position_window(&w, &db_p);
+            if crate::stacking::on_wayland() {
+                return;
+            }
+            for (hint, on) in [
+                (crate::stacking::Hint::Above, keep_above),
+                (crate::stacking::Hint::Sticky, all_workspaces),
+            ] {
+                if on && let Err(e) = crate::stacking::set_hint("WhisperCrabs", hint, true) {
+                    tracing::warn!("Window hint failed: {e}");
+                }
+            }
+        });
+    });
+
+    // --- In-app keys: Esc stops recording, the rest as set in Preferences ---
+    let keys_controller: Rc<RefCell<Option<gtk4::ShortcutController>>> =
+        Rc::new(RefCell::new(None));
+    install_keybindings(&window, &db, &keys_controller);
+    let reload_keys_action = gtk4::gio::SimpleAction::new("reload-keybindings", None);
+    let (window_keys, db_keys) = (window.clone(), Arc::clone(&db));
+    reload_keys_action.connect_activate(move |_, _| {
+        install_keybindings(&window_keys, &db_keys, &keys_controller);
+    });
+    app.add_action(&reload_keys_action);
+
+    // Action: start or stop recording, like clicking the button
+    let toggle_action = gtk4::gio::SimpleAction::new("toggle-recording", None);
+    let (btn_toggle, state_toggle) = (button.clone(), Rc::clone(&state));
+    toggle_action.connect_activate(move |_, _| {
+        if matches!(*state_toggle.borrow(), State::Idle | State::Recording) {
+            btn_toggle.emit_clicked();
+        }
+    });
+    app.add_action(&toggle_action);
+
+    // Action: stop recording without transcribing it
+    let discard_action = gtk4::gio::SimpleAction::new("discard-recording", None);
+    let (btn_discard, state_discard, runtime_discard) =
+        (button.clone(), Rc::clone(&state), Rc::clone(&runtime));
+    discard_action.connect_activate(move |_, _| {
+        if *state_discard.borrow() == State::Recording {
+            runtime_discard.borrow_mut().discard_recording = true;
+            btn_discard.emit_clicked();
+        }
+    });
+    app.add_action(&discard_action);
+
+    // Action: switch to the next usable transcription mode
+    let cycle_action = gtk4::gio::SimpleAction::new("cycle-mode", None);
+    let (runtime_cycle, db_cycle, config_cycle, status_cycle, mode_cycle) = (
+        Rc::clone(&runtime),
+        Arc::clone(&db),
+        Arc::clone(&config),
+        status.clone(),
+        mode_action.clone(),
+    );
+    cycle_action.connect_activate(move |_, _| {
+        let choices: Vec<String> = rerun_choices(&runtime_cycle, &db_cycle, &config_cycle)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let current = {
+            let rt = runtime_cycle.borrow();
+            if rt.auto_mode {
+                crate::auto::AUTO_PROVIDER.to_string()
+            } else {
+                rt.active_provider.clone()
+            }
+        };
+        let Some(next) = crate::keybindings::next_choice(&choices, &current) else {
+            return;
+        };
+        if next == current {
+            return;
+        }
+        mode_cycle.activate(Some(&next.to_variant()));
+        show_status(&status_cycle, &provider_label(next));
+        let st = status_cycle.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&cycle_action);
+
+    // --- D-Bus state: "status" — the status text ("" when idle), for bars and scripts ---
+    let status_action =
+        gtk4::gio::SimpleAction::new_stateful("status", None, &String::new().to_variant());
+    status_action.set_enabled(false);
+    app.add_action(&status_action);
+
+    // --- D-Bus action: "record" — triggered by GNOME shortcut ---
+    let record_action = gtk4::gio::SimpleAction::new("record", None);
+    let btn_rec = button.clone();
+    let state_rec = Rc::clone(&state);
+    let win_rec = window.clone();
+    let no_window_rec = config.no_window;
     record_action.connect_activate(move |_, _| {
-        eprintln!("[dbus] 'record' action activated");
-        win_rec.present();
+        tracing::info!("[dbus] 'record' action activated");
+        if !no_window_rec {
+            win_rec.present();
+        }
         // GNOME Wayland: force-activate via Shell D-Bus (falls back silently on other DEs)
         #[cfg(target_os = "linux")]
-        {
+        if !no_window_rec {
             let _ = std::process::Command::new("gdbus")
                 .args([
                     "call", "--session",
@@ -864,1082 +3351,9365 @@ pub fn build_ui(app: &gtk4::Application, config: Arc<Config>) {
     let btn_stop = button.clone();
     let state_stop = Rc::clone(&state);
     stop_action.connect_activate(move |_, _| {
-        eprintln!("[dbus] 'stop' action activated");
+        tracing::info!("[dbus] 'stop' action activated");
         if *state_stop.borrow() == State::Recording {
             btn_stop.emit_clicked();
         }
     });
     app.add_action(&stop_action);
 
-    // --- D-Bus action: "set-api-config" — programmatic custom API setup ---
-    let api_config_action =
-        gtk4::gio::SimpleAction::new("set-api-config", Some(&String::static_variant_type()));
-    let runtime_api_cfg = Rc::clone(&runtime);
-    let db_api_cfg = Arc::clone(&db);
-    let config_api_cfg = Arc::clone(&config);
-    let mode_action_ref = mode_action.clone();
-    api_config_action.connect_activate(move |_, param| {
-        let Some(param) = param else { return };
-        let Some(json_str) = param.get::<String>() else {
-            eprintln!("set-api-config: expected string parameter");
+    // --- D-Bus action: "record-profile" — one-shot dictation with a named profile ---
+    let record_profile_action =
+        gtk4::gio::SimpleAction::new("record-profile", Some(&String::static_variant_type()));
+    let btn_prof = button.clone();
+    let state_prof = Rc::clone(&state);
+    let runtime_prof = Rc::clone(&runtime);
+    let db_prof = Arc::clone(&db);
+    let win_prof = window.clone();
+    let no_window_prof = config.no_window;
+    record_profile_action.connect_activate(move |_, param| {
+        let Some(name) = param.and_then(|p| p.get::<String>()) else {
             return;
         };
-
-        // Cap incoming JSON size to prevent abuse
-        if json_str.len() > 4096 {
-            eprintln!("set-api-config: JSON too large");
-            return;
-        }
-
-        eprintln!("[dbus] 'set-api-config' action activated");
-
-        #[derive(serde::Deserialize)]
-        struct ApiConfigInput {
-            base_url: String,
-            model: String,
-            api_key: Option<String>,
+        tracing::info!("[dbus] 'record-profile' action activated ({name})");
+        // The same shortcut stops the recording it started
+        match *state_prof.borrow() {
+            State::Recording => {
+                btn_prof.emit_clicked();
+                return;
+            }
+            State::Idle => {}
+            _ => return,
         }
-
-        let input: ApiConfigInput = match serde_json::from_str(&json_str) {
-            Ok(v) => v,
+        let profiles = db_prof
+            .lock()
+            .ok()
+            .and_then(|d| d.get_setting("record_profiles").ok().flatten())
+            .map(|json| crate::profiles::parse_profiles(&json))
+            .unwrap_or_else(|| Ok(Vec::new()));
+        let profile = match profiles {
+            Ok(list) => crate::profiles::find(&list, &name).cloned(),
             Err(e) => {
-                eprintln!("set-api-config: invalid JSON: {e}");
+                tracing::warn!("record-profile: {e}");
                 return;
             }
         };
-
-        // Validate URL scheme
-        if !input.base_url.starts_with("http://") && !input.base_url.starts_with("https://") {
-            eprintln!("set-api-config: base_url must use http:// or https://");
+        let Some(profile) = profile else {
+            tracing::warn!("record-profile: no profile named '{name}'");
             return;
+        };
+        if !no_window_prof {
+            win_prof.present();
         }
-
-        let base_url = input.base_url;
-        let model = input.model;
-        let api_key = input.api_key;
-
-        // Persist to DB
-        if let Ok(d) = db_api_cfg.lock() {
-            let _ = d.set_setting("api_custom_url", &base_url);
-            if let Some(ref k) = api_key {
-                let _ = d.set_setting("api_custom_key", k);
-            }
-            let _ = d.set_setting("api_custom_model", &model);
-            let _ = d.set_setting("transcription_mode", "custom");
-        }
-
-        // Update RuntimeState
-        {
-            let mut rt = runtime_api_cfg.borrow_mut();
-            rt.active_service = TranscriptionService::Api;
-            rt.active_provider = "custom".to_string();
-            rt.api_base_url = base_url;
-            rt.api_key = api_key;
-            rt.api_model = model;
-            rt.local_whisper = None;
+        runtime_prof.borrow_mut().pending_profile = Some(profile);
+        btn_prof.emit_clicked();
+        // Recording didn't start (guard or error): don't leak into the next click
+        if *state_prof.borrow() != State::Recording {
+            let mut rt = runtime_prof.borrow_mut();
+            rt.pending_profile = None;
+            rt.profile_route = None;
         }
-
-        // Delete model file to free disk space
-        delete_all_local_models(&config_api_cfg.models_dir);
-
-        mode_action_ref.set_state(&"custom".to_variant());
     });
-    app.add_action(&api_config_action);
+    app.add_action(&record_profile_action);
 
-    // --- TTS mode action (voice selection) ---
-    let runtime_tts = Rc::clone(&runtime);
-    let db_tts = Arc::clone(&db);
-    let config_tts = Arc::clone(&config);
-    let status_tts = status.clone();
-    let read_cb_action_ref = read_clipboard_action.clone();
-    let window_tts = window.clone();
-    tts_mode_action.connect_activate(move |action, param| {
-        let Some(param) = param else {
-            dbg_log!("[TTS] tts-mode activated with no param");
-            return;
-        };
-        let Some(chosen) = param.get::<String>() else {
-            dbg_log!("[TTS] tts-mode param not a string");
+    // --- D-Bus action: "set-record-profiles" — JSON array of named profiles ---
+    let profiles_action =
+        gtk4::gio::SimpleAction::new("set-record-profiles", Some(&String::static_variant_type()));
+    let db_profiles = Arc::clone(&db);
+    profiles_action.connect_activate(move |_, param| {
+        let Some(param) = param else { return };
+        let Some(json_str) = param.get::<String>() else {
+            tracing::warn!("set-record-profiles: expected string parameter");
             return;
         };
-        dbg_log!("[TTS] tts-mode chosen: {chosen}");
-
-        if runtime_tts.borrow().tts_downloading {
-            dbg_log!("[TTS] already downloading, ignoring");
+        if json_str.len() > 4096 {
+            tracing::warn!("set-record-profiles: JSON too large");
             return;
         }
+        tracing::info!("[dbus] 'set-record-profiles' action activated");
+        match crate::profiles::parse_profiles(&json_str) {
+            Ok(profiles) => {
+                if let Ok(d) = db_profiles.lock() {
+                    if profiles.is_empty() {
+                        let _ = d.delete_setting("record_profiles");
+                    } else {
+                        let _ = d.set_setting("record_profiles", &json_str);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("set-record-profiles: {e}"),
+        }
+    });
+    app.add_action(&profiles_action);
+
+    // --- Hardware triggers: headset buttons / PTT pedals (evdev) ---
+    if config.hid_trigger {
+        let source = crate::hid::HidSource::new(&config.hid_keys, config.hid_mode);
+        match start_activation_source(source, &state) {
+            // The device readers run for the whole session
+            Ok(source) => std::mem::forget(source),
+            Err(e) => tracing::warn!("HID trigger disabled: {e}"),
+        }
+    }
 
-        if chosen == "none" {
-            {
-                let mut rt = runtime_tts.borrow_mut();
-                rt.tts_provider = TtsProvider::None;
-                rt.tts_engine = None;
+    // --- D-Bus control interface (org.whispercrabs.Control) ---
+    let (runtime_bus, state_bus, action_bus) =
+        (Rc::clone(&runtime), Rc::clone(&state), mode_action.clone());
+    let handler: crate::dbus::Handler = Rc::new(move |request| match request {
+        crate::dbus::Request::LastTranscript => {
+            let log = runtime_bus.borrow().session_log.clone();
+            let text = log
+                .item(log.n_items().saturating_sub(1))
+                .and_downcast::<glib::BoxedAnyObject>()
+                .map(|e| e.borrow::<crate::session_log::Entry>().text.clone())
+                .unwrap_or_default();
+            Ok(Some((text,).to_variant()))
+        }
+        crate::dbus::Request::State => Ok(Some((state_name(*state_bus.borrow()),).to_variant())),
+        crate::dbus::Request::SetProvider(provider) => {
+            if *state_bus.borrow() != State::Idle {
+                return Err("Busy, try again when idle".into());
             }
-            if let Ok(d) = db_tts.lock() {
-                let _ = d.set_setting("tts_provider", "none");
+            action_bus.activate(Some(&provider.to_variant()));
+            let current = action_bus
+                .state()
+                .and_then(|s| s.get::<String>())
+                .unwrap_or_default();
+            if current == provider {
+                Ok(None)
+            } else {
+                Err(format!("Can't switch to \"{provider}\""))
             }
-            read_cb_action_ref.set_enabled(false);
-            action.set_state(&"none".to_variant());
-            return;
         }
+        crate::dbus::Request::Activate(_) => Ok(None),
+    });
+    match start_activation_source(crate::dbus::ControlSource::new(handler), &state) {
+        // Exported for the whole session
+        Ok(source) => std::mem::forget(source),
+        Err(e) => tracing::warn!("D-Bus interface disabled: {e}"),
+    }
 
-        // Any other value is a voice ID
-        let Some(voice) = config::find_piper_voice(&chosen) else {
-            dbg_log!("[TTS] unknown voice: {chosen}");
+    // Action: real-time dictation (local models), persisted across restarts
+    let live_action = gtk4::gio::SimpleAction::new_stateful(
+        "live-dictation",
+        None,
+        &initial_live_dictation.to_variant(),
+    );
+    let runtime_live = Rc::clone(&runtime);
+    let db_live = Arc::clone(&db);
+    let status_live = status.clone();
+    live_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if enabled && !crate::input::typing_available() {
+            show_status(
+                &status_live,
+                if cfg!(target_os = "linux") {
+                    "Install wtype or xdotool"
+                } else {
+                    "Live typing needs Linux"
+                },
+            );
+            let st = status_live.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
             return;
-        };
-
-        let piper_dir = config_tts.models_dir.join("piper");
-        let has_venv = config::piper_venv_exists(&piper_dir);
-        let has_voice = config::piper_voice_exists(&piper_dir, voice.id);
+        }
+        runtime_live.borrow_mut().live_dictation = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_live.lock() {
+            let _ = d.set_setting("live_dictation", if enabled { "true" } else { "false" });
+        }
+    });
+    app.add_action(&live_action);
 
-        if has_venv && has_voice {
-            // Delete old voice files before loading new one
-            let old_voice = runtime_tts.borrow().tts_voice.clone();
-            if old_voice != voice.id {
-                cleanup_old_voice(&piper_dir, &old_voice);
-            }
-            // Already downloaded — just load
-            dbg_log!("[TTS] loading voice {}...", voice.id);
-            show_status(&status_tts, "Loading TTS...");
-            match PiperTts::new(&piper_dir, voice.id) {
-                Ok(engine) => {
-                    let mut rt = runtime_tts.borrow_mut();
-                    rt.tts_provider = TtsProvider::Piper;
-                    rt.tts_voice = voice.id.to_string();
-                    rt.tts_engine = Some(Arc::new(engine));
-                }
-                Err(e) => {
-                    eprintln!("Failed to load Piper: {e}");
-                    show_status(&status_tts, "TTS load failed");
-                }
-            }
-            hide_status(&status_tts);
-            if let Ok(d) = db_tts.lock() {
-                let _ = d.set_setting("tts_provider", "piper");
-                let _ = d.set_setting("tts_voice", voice.id);
-            }
-            read_cb_action_ref.set_enabled(true);
-            action.set_state(&chosen.to_variant());
-        } else {
-            // Need to download (venv and/or voice model)
-            dbg_log!("[TTS] downloading voice {}...", voice.id);
-            download_tts_models(
-                &runtime_tts,
-                &config_tts.models_dir,
-                &db_tts,
-                action,
-                &read_cb_action_ref,
-                &status_tts,
-                &window_tts,
-                voice,
-                has_venv,
+    // Action: spoken dictation commands ("new line", "comma"), persisted
+    let commands_action = gtk4::gio::SimpleAction::new_stateful(
+        "dictation-commands",
+        None,
+        &initial_dictation_commands.to_variant(),
+    );
+    let db_commands = Arc::clone(&db);
+    commands_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if let Ok(d) = db_commands.lock() {
+            let _ = d.set_setting(
+                crate::dictation_commands::ENABLED_SETTING,
+                if enabled { "true" } else { "false" },
             );
         }
+        action.set_state(&enabled.to_variant());
     });
-    app.add_action(&tts_mode_action);
+    app.add_action(&commands_action);
 
-    // --- TTS Reset action ---
-    let runtime_reset = Rc::clone(&runtime);
-    let config_reset = Arc::clone(&config);
-    let db_reset = Arc::clone(&db);
-    let status_reset = status.clone();
-    let tts_action_reset = tts_mode_action.clone();
-    let read_cb_reset = read_clipboard_action.clone();
-    let reset_action = gtk4::gio::SimpleAction::new("tts-reset", None);
-    reset_action.connect_activate(move |_, _| {
-        dbg_log!("[TTS] reset requested");
-        let piper_dir = config_reset.models_dir.join("piper");
-        if piper_dir.exists() {
-            let _ = std::fs::remove_dir_all(&piper_dir);
-            dbg_log!("[TTS] deleted {}", piper_dir.display());
-        }
-        {
-            let mut rt = runtime_reset.borrow_mut();
-            rt.tts_provider = TtsProvider::None;
-            rt.tts_engine = None;
+    // --- D-Bus action: "set-dictation-commands" — JSON array of extra commands ---
+    let command_table_action = gtk4::gio::SimpleAction::new(
+        "set-dictation-commands",
+        Some(&String::static_variant_type()),
+    );
+    let db_command_table = Arc::clone(&db);
+    command_table_action.connect_activate(move |_, param| {
+        let Some(param) = param else { return };
+        let Some(json_str) = param.get::<String>() else {
+            tracing::warn!("set-dictation-commands: expected string parameter");
+            return;
+        };
+        if json_str.len() > 4096 {
+            tracing::warn!("set-dictation-commands: JSON too large");
+            return;
         }
-        if let Ok(d) = db_reset.lock() {
-            let _ = d.set_setting("tts_provider", "none");
+        tracing::info!("[dbus] 'set-dictation-commands' action activated");
+        match crate::dictation_commands::parse_table(&json_str) {
+            Ok(commands) => {
+                if let Ok(d) = db_command_table.lock() {
+                    let key = crate::dictation_commands::TABLE_SETTING;
+                    if commands.is_empty() {
+                        let _ = d.delete_setting(key);
+                    } else {
+                        let _ = d.set_setting(key, &json_str);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("set-dictation-commands: {e}"),
         }
-        read_cb_reset.set_enabled(false);
-        tts_action_reset.set_state(&"none".to_variant());
-        show_status(&status_reset, "TTS reset");
-        let st = status_reset.clone();
-        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || hide_status(&st));
     });
-    app.add_action(&reset_action);
+    app.add_action(&command_table_action);
 
-    // --- TTS Delete action ---
-    let runtime_del = Rc::clone(&runtime);
-    let config_del = Arc::clone(&config);
-    let db_del = Arc::clone(&db);
-    let status_del = status.clone();
-    let tts_action_del = tts_mode_action.clone();
-    let read_cb_del = read_clipboard_action.clone();
-    let delete_action = gtk4::gio::SimpleAction::new("tts-delete", None);
-    delete_action.connect_activate(move |_, _| {
-        dbg_log!("[TTS] delete requested");
-        let piper_dir = config_del.models_dir.join("piper");
-        if piper_dir.exists() {
-            let _ = std::fs::remove_dir_all(&piper_dir);
-            dbg_log!("[TTS] deleted {}", piper_dir.display());
-        }
-        {
-            let mut rt = runtime_del.borrow_mut();
-            rt.tts_provider = TtsProvider::None;
-            rt.tts_engine = None;
+    // Action: speak each transcript to the screen reader, persisted across restarts
+    let announce_action = gtk4::gio::SimpleAction::new_stateful(
+        "announce-transcripts",
+        None,
+        &initial_announce.to_variant(),
+    );
+    let runtime_announce = Rc::clone(&runtime);
+    let db_announce = Arc::clone(&db);
+    let status_announce = status.clone();
+    announce_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if enabled && !crate::announce::available() {
+            show_status(
+                &status_announce,
+                if cfg!(target_os = "linux") {
+                    "Install speech-dispatcher"
+                } else {
+                    "Announcing needs Linux"
+                },
+            );
+            let st = status_announce.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
         }
-        if let Ok(d) = db_del.lock() {
-            let _ = d.set_setting("tts_provider", "none");
+        runtime_announce.borrow_mut().announce = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_announce.lock() {
+            let _ = d.set_setting(
+                "announce_transcripts",
+                if enabled { "true" } else { "false" },
+            );
         }
-        read_cb_del.set_enabled(false);
-        tts_action_del.set_state(&"none".to_variant());
-        show_status(&status_del, "TTS deleted");
-        let st = status_del.clone();
-        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || hide_status(&st));
     });
-    app.add_action(&delete_action);
+    app.add_action(&announce_action);
 
-    // --- Read Clipboard action (TTS playback) ---
-    let runtime_rc = Rc::clone(&runtime);
-    let state_tts_rc = Rc::clone(&state);
-    let btn_tts = button.clone();
-    read_clipboard_action.connect_activate(move |_, _| {
-        dbg_log!("[TTS] read-clipboard activated");
-        let rt = runtime_rc.borrow();
-        if rt.tts_provider == TtsProvider::None {
-            dbg_log!("[TTS] provider is None, skipping");
-            return;
+    // Action: a desktop notification for each transcript, persisted across restarts
+    let notify_action = gtk4::gio::SimpleAction::new_stateful(
+        "notify-transcripts",
+        None,
+        &initial_notify.to_variant(),
+    );
+    let runtime_notify = Rc::clone(&runtime);
+    let db_notify = Arc::clone(&db);
+    notify_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_notify.borrow_mut().notify = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_notify.lock() {
+            let _ = d.set_setting(
+                crate::notify::SETTING,
+                if enabled { "true" } else { "false" },
+            );
         }
-        let Some(ref engine) = rt.tts_engine else {
-            dbg_log!("[TTS] no engine loaded");
+    });
+    app.add_action(&notify_action);
+
+    // Action: put a transcript back on the clipboard ("Copy Again" in its notification)
+    let copy_action =
+        gtk4::gio::SimpleAction::new("copy-transcript", Some(&String::static_variant_type()));
+    let status_copy = status.clone();
+    copy_action.connect_activate(move |_, param| {
+        let Some(text) = param.and_then(|p| p.get::<String>()) else {
             return;
         };
-
-        let text = match arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
-            Ok(t) if !t.trim().is_empty() => {
-                dbg_log!("[TTS] clipboard text len={}", t.len());
-                t
-            }
-            Ok(_) => {
-                dbg_log!("[TTS] clipboard empty or whitespace");
-                return;
-            }
+        let note = match crate::input::copy_to_clipboard(&text) {
+            Ok(()) => "Copied!",
             Err(e) => {
-                dbg_log!("[TTS] clipboard error: {e}");
-                return;
+                tracing::warn!("Copy failed: {e}");
+                "Copy failed"
             }
         };
+        show_status(&status_copy, note);
+        let st = status_copy.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    });
+    app.add_action(&copy_action);
+
+    // Action: paste each transcript into the focused app, persisted across restarts
+    let auto_paste_action =
+        gtk4::gio::SimpleAction::new_stateful("auto-paste", None, &initial_auto_paste.to_variant());
+    let runtime_paste = Rc::clone(&runtime);
+    let db_paste = Arc::clone(&db);
+    let status_paste = status.clone();
+    auto_paste_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if enabled && !crate::input::typing_available() {
+            show_status(
+                &status_paste,
+                if cfg!(target_os = "linux") {
+                    "Install wtype or xdotool"
+                } else {
+                    "Auto-paste needs Linux"
+                },
+            );
+            let st = status_paste.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        runtime_paste.borrow_mut().auto_paste = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_paste.lock() {
+            let _ = d.set_setting("auto_paste", if enabled { "true" } else { "false" });
+        }
+    });
+    app.add_action(&auto_paste_action);
 
-        let engine = Arc::clone(engine);
-        let preview: String = text.chars().take(80).collect();
-        dbg_log!("[TTS] synthesizing: {:?}", preview);
-
-        // Yellow button while synthesizing
-        *state_tts_rc.borrow_mut() = State::Synthesizing;
-        btn_tts.add_css_class("synthesizing");
+    // Action: gentle hints about speaking rate and loudness, persisted
+    let coaching_action = gtk4::gio::SimpleAction::new_stateful(
+        "coaching-hints",
+        None,
+        &initial_coaching.to_variant(),
+    );
+    let runtime_coach = Rc::clone(&runtime);
+    let db_coach = Arc::clone(&db);
+    coaching_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_coach.borrow_mut().coaching = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_coach.lock() {
+            let _ = d.set_setting("coaching_hints", if enabled { "true" } else { "false" });
+        }
+    });
+    app.add_action(&coaching_action);
+
+    // Action: dry run, for the session only so it can't be forgotten on
+    let dry_run_action =
+        gtk4::gio::SimpleAction::new_stateful("dry-run", None, &false.to_variant());
+    let runtime_dry = Rc::clone(&runtime);
+    let badge_dry = dry_run_badge.clone();
+    dry_run_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_dry.borrow_mut().dry_run = enabled;
+        badge_dry.set_visible(enabled);
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&dry_run_action);
 
-        let stop_flag = Arc::clone(&rt.tts_stop);
-        stop_flag.store(false, std::sync::atomic::Ordering::Relaxed);
-        drop(rt);
+    // Action: input device by name ("" for the system default), persisted
+    let audio_device_action = gtk4::gio::SimpleAction::new_stateful(
+        "audio-device",
+        Some(&String::static_variant_type()),
+        &initial_audio_device.to_variant(),
+    );
+    let recorder_device = Rc::clone(&recorder);
+    let db_device = Arc::clone(&db);
+    audio_device_action.connect_activate(move |action, param| {
+        let Some(name) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        recorder_device
+            .borrow_mut()
+            .set_device((!name.is_empty()).then(|| name.clone()));
+        if let Ok(d) = db_device.lock() {
+            let _ = if name.is_empty() {
+                d.delete_setting("audio_device")
+            } else {
+                d.set_setting("audio_device", &name)
+            };
+        }
+        action.set_state(&name.to_variant());
+    });
+    app.add_action(&audio_device_action);
+
+    // Action: the Preferences window
+    let preferences_action = gtk4::gio::SimpleAction::new("preferences", None);
+    let (app_pref, win_pref, runtime_pref, config_pref, db_pref) = (
+        app.clone(),
+        window.clone(),
+        Rc::clone(&runtime),
+        Arc::clone(&config),
+        Arc::clone(&db),
+    );
+    let (mode_pref, status_pref) = (mode_action.clone(), status.clone());
+    preferences_action.connect_activate(move |_, _| {
+        show_preferences_window(
+            &app_pref,
+            &win_pref,
+            &runtime_pref,
+            &config_pref,
+            &db_pref,
+            &mode_pref,
+            &status_pref,
+        );
+    });
+    app.add_action(&preferences_action);
 
-        let sr = engine.sample_rate();
-        let (tx, rx) = std::sync::mpsc::channel::<Result<Vec<i16>, String>>();
+    // Actions: save the settings to a file, and apply such a file
+    let export_settings_action = gtk4::gio::SimpleAction::new("export-settings", None);
+    let (win_export, db_export) = (window.clone(), Arc::clone(&db));
+    export_settings_action.connect_activate(move |_, _| {
+        show_export_settings_dialog(&win_export, &db_export);
+    });
+    app.add_action(&export_settings_action);
+    let import_settings_action = gtk4::gio::SimpleAction::new("import-settings", None);
+    let (win_import, db_import) = (window.clone(), Arc::clone(&db));
+    import_settings_action.connect_activate(move |_, _| {
+        import_settings_file(&win_import, &db_import);
+    });
+    app.add_action(&import_settings_action);
 
-        std::thread::spawn(move || {
-            let result = engine.synthesize(&text);
-            let _ = tx.send(result);
-        });
+    // Action: keep recordings with their History entries, persisted across restarts
+    let store_audio_action = gtk4::gio::SimpleAction::new_stateful(
+        "store-audio",
+        None,
+        &initial_store_audio.to_variant(),
+    );
+    let runtime_store = Rc::clone(&runtime);
+    let db_store = Arc::clone(&db);
+    store_audio_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_store.borrow_mut().store_audio = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_store.lock() {
+            let _ = d.set_setting("store_audio", if enabled { "true" } else { "false" });
+        }
+    });
+    app.add_action(&store_audio_action);
 
-        let btn2 = btn_tts.clone();
-        let state2 = Rc::clone(&state_tts_rc);
-        let stop2 = stop_flag;
+    // Action: pin a light or dark look, or follow the desktop; persisted
+    let theme_action = gtk4::gio::SimpleAction::new_stateful(
+        "theme",
+        Some(&String::static_variant_type()),
+        &theme.get().as_str().to_variant(),
+    );
+    let (window_theme, db_theme) = (window.clone(), Arc::clone(&db));
+    theme_action.connect_activate(move |action, param| {
+        let Some(choice) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|v| crate::appearance::Theme::parse(&v))
+        else {
+            return;
+        };
+        theme.set(choice);
+        crate::appearance::apply(&window_theme, choice.scheme(desktop_scheme.get()));
+        if let Ok(d) = db_theme.lock() {
+            let _ = d.set_setting(crate::appearance::THEME_SETTING, choice.as_str());
+        }
+        action.set_state(&choice.as_str().to_variant());
+    });
+    app.add_action(&theme_action);
 
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            match rx.try_recv() {
-                Ok(Ok(samples)) => {
-                    dbg_log!(
-                        "[TTS] got {} samples, playing at {}Hz...",
-                        samples.len(),
-                        sr
-                    );
-                    btn2.remove_css_class("synthesizing");
+    // Action: size of the record button; persisted
+    let size_action = gtk4::gio::SimpleAction::new_stateful(
+        "widget-size",
+        Some(&String::static_variant_type()),
+        &widget_size.get().as_str().to_variant(),
+    );
+    let (window_size, button_size, icon_size, db_size) = (
+        window.clone(),
+        button.clone(),
+        icon.clone(),
+        Arc::clone(&db),
+    );
+    size_action.connect_activate(move |action, param| {
+        let Some(size) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|v| crate::appearance::WidgetSize::parse(&v))
+        else {
+            return;
+        };
+        widget_size.set(size);
+        crate::appearance::apply_size(&size_provider, &button_size, &icon_size, size);
+        let (width, height) = size.window_size();
+        window_size.set_default_size(width, height);
+        if let Ok(d) = db_size.lock() {
+            let _ = d.set_setting(crate::appearance::SIZE_SETTING, &size.as_str());
+        }
+        action.set_state(&size.as_str().to_variant());
+    });
+    app.add_action(&size_action);
+    // Action: recording indicator on every monitor, persisted across restarts
+    let dots_action = gtk4::gio::SimpleAction::new_stateful(
+        "monitor-dots",
+        None,
+        &initial_monitor_dots.to_variant(),
+    );
+    let runtime_dots = Rc::clone(&runtime);
+    let db_dots = Arc::clone(&db);
+    dots_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_dots.borrow_mut().monitor_dots = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_dots.lock() {
+            let _ = d.set_setting("monitor_dots", if enabled { "true" } else { "false" });
+        }
+    });
+    app.add_action(&dots_action);
 
-                    // Green button while speaking
-                    *state2.borrow_mut() = State::Speaking;
-                    btn2.add_css_class("speaking");
+    // Action: hold transcripts in an editable window before copying, persisted
+    let review_action = gtk4::gio::SimpleAction::new_stateful(
+        "review-transcripts",
+        None,
+        &initial_review.to_variant(),
+    );
+    let (runtime_review, db_review) = (Rc::clone(&runtime), Arc::clone(&db));
+    review_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_review.borrow_mut().review = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_review.lock() {
+            let _ = d.set_setting(
+                crate::review::SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&review_action);
 
-                    let btn3 = btn2.clone();
-                    let state3 = Rc::clone(&state2);
-                    let stop3 = Arc::clone(&stop2);
+    // Action: hold low-confidence transcripts for review even with reviewing off
+    let confidence_review_action = gtk4::gio::SimpleAction::new_stateful(
+        "confidence-review",
+        None,
+        &db.lock()
+            .ok()
+            .is_some_and(|d| crate::confidence::review_low(&d))
+            .to_variant(),
+    );
+    let db_conf = Arc::clone(&db);
+    confidence_review_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_conf.lock() {
+            let _ = d.set_setting(
+                crate::confidence::REVIEW_SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&confidence_review_action);
 
-                    play_tts_audio(samples, sr, stop3, move || {
-                        btn3.remove_css_class("speaking");
-                        *state3.borrow_mut() = State::Idle;
-                    });
+    // Action: pause playing media players while recording, persisted
+    let pause_media_action = gtk4::gio::SimpleAction::new_stateful(
+        "pause-media",
+        None,
+        &initial_pause_media.to_variant(),
+    );
+    let (runtime_pause, db_pause) = (Rc::clone(&runtime), Arc::clone(&db));
+    pause_media_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_pause.borrow_mut().pause_media = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_pause.lock() {
+            let _ = d.set_setting(
+                crate::media::ENABLED_SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&pause_media_action);
 
-                    glib::ControlFlow::Break
+    // Action: desktop do-not-disturb while recording, persisted
+    let silence_action = gtk4::gio::SimpleAction::new_stateful(
+        "silence-notifications",
+        None,
+        &initial_silence.to_variant(),
+    );
+    let (runtime_silence, db_silence) = (Rc::clone(&runtime), Arc::clone(&db));
+    silence_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        runtime_silence.borrow_mut().silence_notifications = enabled;
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_silence.lock() {
+            let _ = d.set_setting(
+                crate::silence::ENABLED_SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&silence_action);
+
+    // --- D-Bus action: "set-pause-media-players" — player ids to pause, empty for all ---
+    let pause_players_action = gtk4::gio::SimpleAction::new(
+        "set-pause-media-players",
+        Some(&String::static_variant_type()),
+    );
+    let db_players = Arc::clone(&db);
+    pause_players_action.connect_activate(move |_, param| {
+        let Some(text) = param.and_then(|p| p.get::<String>()) else {
+            tracing::warn!("set-pause-media-players: expected string parameter");
+            return;
+        };
+        tracing::info!("[dbus] 'set-pause-media-players' action activated");
+        save_pause_media_players(&db_players, &text);
+    });
+    app.add_action(&pause_players_action);
+
+    // Actions: keep the window above others / on every workspace, persisted
+    for (name, key, hint, initial) in [
+        (
+            "keep-above",
+            crate::stacking::KEEP_ABOVE_SETTING,
+            crate::stacking::Hint::Above,
+            keep_above,
+        ),
+        (
+            "all-workspaces",
+            crate::stacking::ALL_WORKSPACES_SETTING,
+            crate::stacking::Hint::Sticky,
+            all_workspaces,
+        ),
+    ] {
+        let hint_action = gtk4::gio::SimpleAction::new_stateful(name, None, &initial.to_variant());
+        let (db_hint, status_hint, window_hint) = (Arc::clone(&db), status.clone(), window.clone());
+        hint_action.connect_activate(move |action, _| {
+            let enabled = !action
+                .state()
+                .and_then(|v| v.get::<bool>())
+                .unwrap_or(false);
+            let note = if crate::stacking::is_layer_window(&window_hint) {
+                match hint {
+                    crate::stacking::Hint::Above => {
+                        crate::stacking::set_keep_above(&window_hint, enabled);
+                        None
+                    }
+                    crate::stacking::Hint::Sticky => Some("Always on every workspace on Wayland"),
                 }
-                Ok(Err(e)) => {
-                    dbg_log!("[TTS] synthesis error: {e}");
-                    btn2.remove_css_class("synthesizing");
-                    *state2.borrow_mut() = State::Idle;
-                    glib::ControlFlow::Break
+            } else if crate::stacking::on_wayland() {
+                Some("Needs X11 or layer-shell")
+            } else if let Err(e) = crate::stacking::set_hint("WhisperCrabs", hint, enabled) {
+                tracing::warn!("Window hint failed: {e}");
+                show_status(&status_hint, "Install wmctrl");
+                let st = status_hint.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st);
+                });
+                return;
+            } else {
+                None
+            };
+            action.set_state(&enabled.to_variant());
+            if let Ok(d) = db_hint.lock() {
+                let _ = d.set_setting(key, if enabled { "true" } else { "false" });
+            }
+            if let Some(note) = note {
+                show_status(&status_hint, note);
+                let st = status_hint.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st);
+                });
+            }
+        });
+        app.add_action(&hint_action);
+    }
+
+    // Action: screen corner the window docks to, persisted. A layer-shell
+    // window is anchored there; on X11 it moves SCREEN_MARGIN from it.
+    let docked = if crate::stacking::is_layer_window(&window) {
+        Some(corner)
+    } else {
+        db.lock().ok().and_then(|d| {
+            d.get_setting(crate::stacking::POSITION_CORNER_SETTING)
+                .ok()
+                .flatten()
+                .and_then(|v| crate::stacking::Corner::parse(&v))
+        })
+    };
+    let corner_action = gtk4::gio::SimpleAction::new_stateful(
+        "screen-corner",
+        Some(&String::static_variant_type()),
+        &docked.map_or("", |c| c.as_str()).to_variant(),
+    );
+    let (window_corner, db_corner, margin) =
+        (window.clone(), Arc::clone(&db), config.screen_margin);
+    corner_action.connect_activate(move |action, param| {
+        let Some(corner) = param
+            .and_then(|p| p.get::<String>())
+            .and_then(|v| crate::stacking::Corner::parse(&v))
+        else {
+            return;
+        };
+        if crate::stacking::is_layer_window(&window_corner) {
+            crate::stacking::place(&window_corner, corner, margin);
+            if let Ok(d) = db_corner.lock() {
+                let _ = d.set_setting(crate::stacking::CORNER_SETTING, corner.as_str());
+            }
+        } else {
+            let placement = crate::stacking::Placement {
+                corner,
+                offset: margin,
+            };
+            if let Ok(d) = db_corner.lock() {
+                let _ = d.set_setting(
+                    crate::stacking::POSITION_CORNER_SETTING,
+                    placement.corner.as_str(),
+                );
+                let _ = d.set_setting(
+                    crate::stacking::POSITION_OFFSET_SETTING,
+                    &placement.offset_str(),
+                );
+            }
+            position_window(&window_corner, &db_corner);
+        }
+        action.set_state(&corner.as_str().to_variant());
+    });
+    app.add_action(&corner_action);
+
+    // Action: monitor the window docks to, persisted by connector name
+    let monitor_action = gtk4::gio::SimpleAction::new_stateful(
+        "window-monitor",
+        Some(&String::static_variant_type()),
+        &chosen_monitor.unwrap_or_default().to_variant(),
+    );
+    let (window_monitor, db_monitor) = (window.clone(), Arc::clone(&db));
+    monitor_action.connect_activate(move |action, param| {
+        let Some(id) = param.and_then(|p| p.get::<String>()) else {
+            return;
+        };
+        if crate::stacking::is_layer_window(&window_monitor) {
+            crate::stacking::set_monitor(&window_monitor, monitor_by_id(&id).as_ref());
+        } else {
+            // Keep the corner and offset the window has now
+            save_window_position(&window_monitor, &db_monitor);
+        }
+        if let Ok(d) = db_monitor.lock() {
+            let _ = d.set_setting(crate::stacking::POSITION_MONITOR_SETTING, &id);
+        }
+        if !crate::stacking::is_layer_window(&window_monitor) {
+            position_window(&window_monitor, &db_monitor);
+        }
+        action.set_state(&id.to_variant());
+    });
+    app.add_action(&monitor_action);
+
+    // Monitors plugged in or out: refresh the list, and go back to the
+    // chosen monitor once it's there again (or off one that's gone)
+    if placeable && let Some(display) = gdk::Display::default() {
+        let (window_plug, db_plug) = (window.clone(), Arc::clone(&db));
+        display.monitors().connect_items_changed(move |_, _, _, _| {
+            fill_monitor_menu(&monitor_menu);
+            let (w, d) = (window_plug.clone(), Arc::clone(&db_plug));
+            // Monitor geometry settles after the change is announced
+            glib::timeout_add_local_once(std::time::Duration::from_millis(500), move || {
+                if crate::stacking::is_layer_window(&w) {
+                    let id = d.lock().ok().and_then(|d| {
+                        d.get_setting(crate::stacking::POSITION_MONITOR_SETTING)
+                            .ok()
+                            .flatten()
+                    });
+                    let monitor = id.as_deref().and_then(monitor_by_id);
+                    crate::stacking::set_monitor(&w, monitor.as_ref());
+                } else {
+                    position_window(&w, &d);
                 }
-                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-                Err(_) => {
-                    btn2.remove_css_class("synthesizing");
-                    *state2.borrow_mut() = State::Idle;
-                    glib::ControlFlow::Break
+            });
+        });
+    }
+
+    // --- Wake word: optional "hey whisper" listener (off by default) ---
+    let wake_listener: Rc<RefCell<Option<Rc<crate::wakeword::WakeWordSource>>>> =
+        Rc::new(RefCell::new(None));
+    let wake_initial = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("wake_word").ok().flatten())
+        .is_some_and(|v| v == "true");
+    let wake_action = gtk4::gio::SimpleAction::new_stateful("wake-word", None, &false.to_variant());
+    let db_wake = Arc::clone(&db);
+    let config_wake = Arc::clone(&config);
+    let badge_wake = wake_badge.clone();
+    let status_wake = status.clone();
+    let state_wake = Rc::clone(&state);
+    wake_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        let listener = if enabled {
+            let source = crate::wakeword::WakeWordSource::new(&config_wake.wake_word_model);
+            match start_activation_source(source, &state_wake) {
+                Ok(listener) => Some(listener),
+                Err(e) => {
+                    tracing::warn!("Wake word disabled: {e}");
+                    show_status(
+                        &status_wake,
+                        if config_wake.wake_word_model.is_file() {
+                            "Wake word failed"
+                        } else {
+                            "No wake word model"
+                        },
+                    );
+                    let st = status_wake.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                        hide_status(&st);
+                    });
+                    return;
                 }
             }
+        } else {
+            None
+        };
+        // Replacing the old listener drops it, which closes its audio stream
+        *wake_listener.borrow_mut() = listener;
+        action.set_state(&enabled.to_variant());
+        badge_wake.set_visible(enabled);
+        if let Ok(d) = db_wake.lock() {
+            let _ = d.set_setting("wake_word", if enabled { "true" } else { "false" });
+        }
+        show_status(
+            &status_wake,
+            if enabled {
+                "Wake word on"
+            } else {
+                "Wake word off"
+            },
+        );
+        let st = status_wake.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
         });
     });
-    app.add_action(&read_clipboard_action);
+    app.add_action(&wake_action);
+    if wake_initial {
+        wake_action.activate(None);
+    }
+
+    // --- Live captions: rolling transcription of system audio (off until toggled) ---
+    let captions: Rc<RefCell<Option<(crate::captions::CaptionSession, gtk4::Window)>>> =
+        Rc::new(RefCell::new(None));
+    let captions_action =
+        gtk4::gio::SimpleAction::new_stateful("live-captions", None, &false.to_variant());
+    let runtime_captions = Rc::clone(&runtime);
+    let config_captions = Arc::clone(&config);
+    let status_captions = status.clone();
+    let app_captions = app.clone();
+    captions_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        if !enabled {
+            // Dropping the session stops the capture
+            if let Some((_, win)) = captions.borrow_mut().take() {
+                win.destroy();
+            }
+            action.set_state(&false.to_variant());
+            return;
+        }
+        match start_live_captions(&runtime_captions, &config_captions) {
+            Ok((session, rx)) => {
+                let win = show_captions_window(&app_captions, rx);
+                let action_c = action.clone();
+                win.connect_close_request(move |_| {
+                    action_c.activate(None);
+                    glib::Propagation::Stop
+                });
+                *captions.borrow_mut() = Some((session, win));
+                action.set_state(&true.to_variant());
+            }
+            Err(e) => {
+                tracing::warn!("Live captions failed: {e}");
+                show_status(&status_captions, &e);
+                let st = status_captions.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st);
+                });
+            }
+        }
+    });
+    app.add_action(&captions_action);
+
+    // --- Remote control: phone record button over the LAN (off until paired) ---
+    let remote: Rc<RefCell<Option<Rc<crate::remote::RemoteSource>>>> = Rc::new(RefCell::new(None));
+    let remote_initial = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("remote_control").ok().flatten())
+        .is_some_and(|v| v == "true");
+    if remote_initial {
+        match start_remote_control(config.remote_port, false, &state) {
+            Ok(server) => *remote.borrow_mut() = Some(server),
+            Err(e) => tracing::warn!("Remote control disabled: {e}"),
+        }
+    }
+    let remote_action = gtk4::gio::SimpleAction::new("remote-control", None);
+    let db_remote = Arc::clone(&db);
+    let config_remote = Arc::clone(&config);
+    let state_remote = Rc::clone(&state);
+    let win_remote = window.clone();
+    remote_action.connect_activate(move |_, _| {
+        show_remote_dialog(
+            &win_remote,
+            &db_remote,
+            config_remote.remote_port,
+            &remote,
+            &state_remote,
+        );
+    });
+    app.add_action(&remote_action);
+
+    // --- Tray icon: record, stop and History from the panel (off by default) ---
+    let tray: Rc<RefCell<Option<Rc<crate::tray::TraySource>>>> = Rc::new(RefCell::new(None));
+    let (tray_initial, hide_button) = db
+        .lock()
+        .map(|d| {
+            let on = |key| {
+                d.get_setting(key)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|v| v == "true")
+            };
+            (
+                on(crate::tray::TRAY_SETTING),
+                on(crate::tray::HIDE_BUTTON_SETTING),
+            )
+        })
+        .unwrap_or_default();
+    if tray_initial {
+        match start_tray(!hide_button, &state) {
+            Ok(source) => *tray.borrow_mut() = Some(source),
+            Err(e) => tracing::warn!("Tray icon disabled: {e}"),
+        }
+    }
+    // The button is only hidden while the tray can bring it back, or for
+    // this session when started with --hidden
+    let button_visible = !(hide_button && tray.borrow().is_some()) && !config.hidden;
+    let button_action =
+        gtk4::gio::SimpleAction::new_stateful("show-button", None, &button_visible.to_variant());
+    let (window_button, db_button, status_button, tray_button) = (
+        window.clone(),
+        Arc::clone(&db),
+        status.clone(),
+        Rc::clone(&tray),
+    );
+    button_action.connect_activate(move |action, _| {
+        let visible = !action.state().and_then(|v| v.get::<bool>()).unwrap_or(true);
+        if !visible && tray_button.borrow().is_none() {
+            show_status(&status_button, "Turn on the tray icon first");
+            let st = status_button.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        action.set_state(&visible.to_variant());
+        if visible {
+            window_button.present();
+        } else {
+            window_button.set_visible(false);
+        }
+        if let Some(source) = tray_button.borrow().as_ref() {
+            source.set_button_visible(visible);
+        }
+        if let Ok(d) = db_button.lock() {
+            let _ = d.set_setting(
+                crate::tray::HIDE_BUTTON_SETTING,
+                if visible { "false" } else { "true" },
+            );
+        }
+    });
+    app.add_action(&button_action);
+
+    let tray_action = gtk4::gio::SimpleAction::new_stateful(
+        "tray-icon",
+        None,
+        &tray.borrow().is_some().to_variant(),
+    );
+    let (db_tray, status_tray, state_tray, button_tray) = (
+        Arc::clone(&db),
+        status.clone(),
+        Rc::clone(&state),
+        button_action.clone(),
+    );
+    tray_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        let button_visible = button_tray
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(true);
+        if enabled {
+            match start_tray(button_visible, &state_tray) {
+                Ok(source) => *tray.borrow_mut() = Some(source),
+                Err(e) => {
+                    tracing::warn!("Tray icon failed: {e}");
+                    show_status(&status_tray, "Tray icon unavailable");
+                    let st = status_tray.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                        hide_status(&st);
+                    });
+                    return;
+                }
+            }
+        } else {
+            *tray.borrow_mut() = None;
+            // Without the tray, the button is the only way back in
+            if !button_visible {
+                button_tray.activate(None);
+            }
+        }
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_tray.lock() {
+            let _ = d.set_setting(
+                crate::tray::TRAY_SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&tray_action);
+
+    // --- Global shortcuts registered through the desktop portal (off by default) ---
+    let portal_shortcuts: Rc<RefCell<Option<Rc<crate::global_shortcuts::PortalShortcutsSource>>>> =
+        Rc::new(RefCell::new(None));
+    let portal_initial = db
+        .lock()
+        .ok()
+        .and_then(|d| {
+            d.get_setting(crate::global_shortcuts::SETTING)
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|v| v == "true");
+    if portal_initial {
+        match start_portal_shortcuts(&db, &state) {
+            Ok(source) => *portal_shortcuts.borrow_mut() = Some(source),
+            Err(e) => tracing::warn!("Portal shortcuts disabled: {e}"),
+        }
+    }
+    let portal_action = gtk4::gio::SimpleAction::new_stateful(
+        "portal-shortcuts",
+        None,
+        &portal_shortcuts.borrow().is_some().to_variant(),
+    );
+    let (db_portal, state_portal, status_portal, slot_portal) = (
+        Arc::clone(&db),
+        Rc::clone(&state),
+        status.clone(),
+        Rc::clone(&portal_shortcuts),
+    );
+    portal_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        // Close a running session before binding again
+        *slot_portal.borrow_mut() = None;
+        if enabled {
+            match start_portal_shortcuts(&db_portal, &state_portal) {
+                Ok(source) => *slot_portal.borrow_mut() = Some(source),
+                Err(e) => {
+                    tracing::warn!("Portal shortcuts failed: {e}");
+                    show_status(&status_portal, "Shortcuts unavailable");
+                    let st = status_portal.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                        hide_status(&st);
+                    });
+                    return;
+                }
+            }
+        }
+        action.set_state(&enabled.to_variant());
+        if let Ok(d) = db_portal.lock() {
+            let _ = d.set_setting(
+                crate::global_shortcuts::SETTING,
+                if enabled { "true" } else { "false" },
+            );
+        }
+    });
+    app.add_action(&portal_action);
+
+    // Action: bind the portal shortcuts again with the keys set in Preferences
+    let rebind_action = gtk4::gio::SimpleAction::new("rebind-shortcuts", None);
+    let (db_rebind, state_rebind) = (Arc::clone(&db), Rc::clone(&state));
+    rebind_action.connect_activate(move |_, _| {
+        if portal_shortcuts.borrow().is_none() {
+            return;
+        }
+        *portal_shortcuts.borrow_mut() = None;
+        match start_portal_shortcuts(&db_rebind, &state_rebind) {
+            Ok(source) => *portal_shortcuts.borrow_mut() = Some(source),
+            Err(e) => tracing::warn!("Portal shortcuts failed: {e}"),
+        }
+    });
+    app.add_action(&rebind_action);
+
+    // Action: XDG autostart entry; the file itself is the setting
+    let autostart_action = gtk4::gio::SimpleAction::new_stateful(
+        "autostart",
+        None,
+        &crate::autostart::is_enabled().to_variant(),
+    );
+    let status_autostart = status.clone();
+    autostart_action.connect_activate(move |action, _| {
+        let enabled = !action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false);
+        let result = if enabled {
+            crate::autostart::enable()
+        } else {
+            crate::autostart::disable()
+        };
+        if let Err(e) = result {
+            tracing::warn!("Autostart change failed: {e}");
+            show_status(&status_autostart, "Autostart failed");
+            let st = status_autostart.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st);
+            });
+            return;
+        }
+        action.set_state(&enabled.to_variant());
+    });
+    app.add_action(&autostart_action);
+
+    // --- D-Bus action: "set-api-config" — programmatic custom API setup ---
+    let api_config_action =
+        gtk4::gio::SimpleAction::new("set-api-config", Some(&String::static_variant_type()));
+    let runtime_api_cfg = Rc::clone(&runtime);
+    let db_api_cfg = Arc::clone(&db);
+    let config_api_cfg = Arc::clone(&config);
+    let mode_action_ref = mode_action.clone();
+    api_config_action.connect_activate(move |_, param| {
+        let Some(param) = param else { return };
+        let Some(json_str) = param.get::<String>() else {
+            tracing::warn!("set-api-config: expected string parameter");
+            return;
+        };
+
+        // Cap incoming JSON size to prevent abuse
+        if json_str.len() > 4096 {
+            tracing::warn!("set-api-config: JSON too large");
+            return;
+        }
+
+        tracing::info!("[dbus] 'set-api-config' action activated");
+
+        #[derive(serde::Deserialize)]
+        struct ApiConfigInput {
+            base_url: String,
+            model: String,
+            api_key: Option<String>,
+            path: Option<String>,
+        }
+
+        let input: ApiConfigInput = match serde_json::from_str(&json_str) {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!("set-api-config: invalid JSON: {e}");
+                return;
+            }
+        };
+
+        // Validate URL scheme
+        if !input.base_url.starts_with("http://") && !input.base_url.starts_with("https://") {
+            tracing::warn!("set-api-config: base_url must use http:// or https://");
+            return;
+        }
+
+        if !config_api_cfg
+            .policy
+            .allows("custom", Some(&input.base_url))
+        {
+            tracing::warn!("set-api-config: endpoint blocked by organization policy");
+            return;
+        }
+
+        let base_url = input.base_url;
+        let model = input.model;
+        let api_key = input.api_key;
+        let path = input
+            .path
+            .unwrap_or_else(|| config::DEFAULT_TRANSCRIPTION_PATH.to_string());
+
+        // Persist to DB
+        if let Ok(d) = db_api_cfg.lock() {
+            let _ = d.set_setting("api_custom_url", &base_url);
+            if let Some(ref k) = api_key {
+                let _ = d.set_setting("api_custom_key", k);
+            }
+            let _ = d.set_setting("api_custom_model", &model);
+            let _ = d.set_setting("api_custom_path", &path);
+            let _ = d.set_setting("transcription_mode", "custom");
+        }
+
+        // Update RuntimeState
+        {
+            let mut rt = runtime_api_cfg.borrow_mut();
+            rt.active_service = TranscriptionService::Api;
+            rt.active_provider = "custom".to_string();
+            rt.api_base_url = base_url;
+            rt.api_key = api_key;
+            rt.api_model = model;
+            rt.api_path = path;
+            rt.local_engine = None;
+        }
+
+        mode_action_ref.set_state(&"custom".to_variant());
+    });
+    app.add_action(&api_config_action);
+
+    // Rules are evaluated without waiting for nmcli; keep the network read
+    // ahead so a changed Wi-Fi is seen within a few seconds
+    let db_ssid = Arc::clone(&db);
+    let has_rules = move || {
+        db_ssid
+            .lock()
+            .is_ok_and(|d| d.get_setting("profile_rules").ok().flatten().is_some())
+    };
+    if has_rules() {
+        crate::rules::refresh_ssid();
+    }
+    glib::timeout_add_seconds_local(crate::rules::SSID_REFRESH_SECS, move || {
+        if has_rules() {
+            crate::rules::refresh_ssid();
+        }
+        glib::ControlFlow::Continue
+    });
+
+    // --- D-Bus action: "set-profile-rules" — JSON array of auto-switch rules ---
+    let rules_action =
+        gtk4::gio::SimpleAction::new("set-profile-rules", Some(&String::static_variant_type()));
+    let db_rules = Arc::clone(&db);
+    rules_action.connect_activate(move |_, param| {
+        let Some(param) = param else { return };
+        let Some(json_str) = param.get::<String>() else {
+            tracing::warn!("set-profile-rules: expected string parameter");
+            return;
+        };
+        if json_str.len() > 4096 {
+            tracing::warn!("set-profile-rules: JSON too large");
+            return;
+        }
+        tracing::info!("[dbus] 'set-profile-rules' action activated");
+        match crate::rules::parse_rules(&json_str) {
+            Ok(rules) => {
+                if let Ok(d) = db_rules.lock() {
+                    if rules.is_empty() {
+                        let _ = d.delete_setting("profile_rules");
+                    } else {
+                        let _ = d.set_setting("profile_rules", &json_str);
+                        // Read the network now so the first dictation sees it
+                        crate::rules::refresh_ssid();
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("set-profile-rules: {e}"),
+        }
+    });
+    // Rules can't switch away from an admin-pinned provider
+    rules_action.set_enabled(!config.policy.is_locked());
+    app.add_action(&rules_action);
+
+    // --- TTS mode action (voice selection) ---
+    let runtime_tts = Rc::clone(&runtime);
+    let db_tts = Arc::clone(&db);
+    let config_tts = Arc::clone(&config);
+    let status_tts = status.clone();
+    let read_cb_action_ref = read_clipboard_action.clone();
+    let window_tts = window.clone();
+    tts_mode_action.connect_activate(move |action, param| {
+        let Some(param) = param else {
+            dbg_log!("[TTS] tts-mode activated with no param");
+            return;
+        };
+        let Some(chosen) = param.get::<String>() else {
+            dbg_log!("[TTS] tts-mode param not a string");
+            return;
+        };
+        dbg_log!("[TTS] tts-mode chosen: {chosen}");
+
+        if runtime_tts.borrow().tts_downloading {
+            dbg_log!("[TTS] already downloading, ignoring");
+            return;
+        }
+
+        if chosen == "none" {
+            {
+                let mut rt = runtime_tts.borrow_mut();
+                rt.tts_provider = TtsProvider::None;
+                rt.tts_engine = None;
+            }
+            if let Ok(d) = db_tts.lock() {
+                let _ = d.set_setting("tts_provider", "none");
+            }
+            read_cb_action_ref.set_enabled(false);
+            action.set_state(&"none".to_variant());
+            return;
+        }
+
+        // Any other value is a voice ID
+        let Some(voice) = config::find_piper_voice(&chosen) else {
+            dbg_log!("[TTS] unknown voice: {chosen}");
+            return;
+        };
+
+        let piper_dir = config_tts.local_models_dir.join("piper");
+        let has_venv = config::piper_venv_exists(&piper_dir);
+        let has_voice = config::piper_voice_exists(&piper_dir, voice.id);
+
+        if has_venv && has_voice {
+            // Delete old voice files before loading new one
+            let old_voice = runtime_tts.borrow().tts_voice.clone();
+            if old_voice != voice.id {
+                cleanup_old_voice(&piper_dir, &old_voice);
+            }
+            // Already downloaded — just load
+            dbg_log!("[TTS] loading voice {}...", voice.id);
+            show_status(&status_tts, "Loading TTS...");
+            match PiperTts::new(&piper_dir, voice.id) {
+                Ok(engine) => {
+                    let mut rt = runtime_tts.borrow_mut();
+                    rt.tts_provider = TtsProvider::Piper;
+                    rt.tts_voice = voice.id.to_string();
+                    rt.tts_engine = Some(Arc::new(engine));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load Piper: {e}");
+                    show_status(&status_tts, "TTS load failed");
+                }
+            }
+            hide_status(&status_tts);
+            if let Ok(d) = db_tts.lock() {
+                let _ = d.set_setting("tts_provider", "piper");
+                let _ = d.set_setting("tts_voice", voice.id);
+            }
+            read_cb_action_ref.set_enabled(true);
+            action.set_state(&chosen.to_variant());
+        } else {
+            // Need to download (venv and/or voice model)
+            dbg_log!("[TTS] downloading voice {}...", voice.id);
+            download_tts_models(
+                &runtime_tts,
+                &config_tts.local_models_dir,
+                &db_tts,
+                action,
+                &read_cb_action_ref,
+                &status_tts,
+                &window_tts,
+                voice,
+                has_venv,
+            );
+        }
+    });
+    app.add_action(&tts_mode_action);
+
+    // --- TTS Reset action ---
+    let runtime_reset = Rc::clone(&runtime);
+    let config_reset = Arc::clone(&config);
+    let db_reset = Arc::clone(&db);
+    let status_reset = status.clone();
+    let tts_action_reset = tts_mode_action.clone();
+    let read_cb_reset = read_clipboard_action.clone();
+    let reset_action = gtk4::gio::SimpleAction::new("tts-reset", None);
+    reset_action.connect_activate(move |_, _| {
+        dbg_log!("[TTS] reset requested");
+        let piper_dir = config_reset.local_models_dir.join("piper");
+        if piper_dir.exists() {
+            let _ = std::fs::remove_dir_all(&piper_dir);
+            dbg_log!("[TTS] deleted {}", piper_dir.display());
+        }
+        {
+            let mut rt = runtime_reset.borrow_mut();
+            rt.tts_provider = TtsProvider::None;
+            rt.tts_engine = None;
+        }
+        if let Ok(d) = db_reset.lock() {
+            let _ = d.set_setting("tts_provider", "none");
+        }
+        read_cb_reset.set_enabled(false);
+        tts_action_reset.set_state(&"none".to_variant());
+        show_status(&status_reset, "TTS reset");
+        let st = status_reset.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || hide_status(&st));
+    });
+    app.add_action(&reset_action);
+
+    // --- TTS Delete action ---
+    let runtime_del = Rc::clone(&runtime);
+    let config_del = Arc::clone(&config);
+    let db_del = Arc::clone(&db);
+    let status_del = status.clone();
+    let tts_action_del = tts_mode_action.clone();
+    let read_cb_del = read_clipboard_action.clone();
+    let delete_action = gtk4::gio::SimpleAction::new("tts-delete", None);
+    delete_action.connect_activate(move |_, _| {
+        dbg_log!("[TTS] delete requested");
+        let piper_dir = config_del.local_models_dir.join("piper");
+        if piper_dir.exists() {
+            let _ = std::fs::remove_dir_all(&piper_dir);
+            dbg_log!("[TTS] deleted {}", piper_dir.display());
+        }
+        {
+            let mut rt = runtime_del.borrow_mut();
+            rt.tts_provider = TtsProvider::None;
+            rt.tts_engine = None;
+        }
+        if let Ok(d) = db_del.lock() {
+            let _ = d.set_setting("tts_provider", "none");
+        }
+        read_cb_del.set_enabled(false);
+        tts_action_del.set_state(&"none".to_variant());
+        show_status(&status_del, "TTS deleted");
+        let st = status_del.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || hide_status(&st));
+    });
+    app.add_action(&delete_action);
+
+    // --- Read Clipboard action (TTS playback) ---
+    let runtime_rc = Rc::clone(&runtime);
+    let state_tts_rc = Rc::clone(&state);
+    let btn_tts = button.clone();
+    read_clipboard_action.connect_activate(move |_, _| {
+        dbg_log!("[TTS] read-clipboard activated");
+        let rt = runtime_rc.borrow();
+        if rt.tts_provider == TtsProvider::None {
+            dbg_log!("[TTS] provider is None, skipping");
+            return;
+        }
+        let Some(ref engine) = rt.tts_engine else {
+            dbg_log!("[TTS] no engine loaded");
+            return;
+        };
+
+        let text = match arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
+            Ok(t) if !t.trim().is_empty() => {
+                dbg_log!("[TTS] clipboard text len={}", t.len());
+                t
+            }
+            Ok(_) => {
+                dbg_log!("[TTS] clipboard empty or whitespace");
+                return;
+            }
+            Err(e) => {
+                dbg_log!("[TTS] clipboard error: {e}");
+                return;
+            }
+        };
+
+        let engine = Arc::clone(engine);
+        let preview: String = text.chars().take(80).collect();
+        dbg_log!("[TTS] synthesizing: {:?}", preview);
+
+        // Yellow button while synthesizing
+        *state_tts_rc.borrow_mut() = State::Synthesizing;
+        btn_tts.add_css_class("synthesizing");
+
+        let stop_flag = Arc::clone(&rt.tts_stop);
+        stop_flag.store(false, std::sync::atomic::Ordering::Relaxed);
+        drop(rt);
+
+        let sr = engine.sample_rate();
+        let (tx, rx) = std::sync::mpsc::channel::<Result<Vec<i16>, String>>();
+
+        std::thread::spawn(move || {
+            let result = engine.synthesize(&text);
+            let _ = tx.send(result);
+        });
+
+        let btn2 = btn_tts.clone();
+        let state2 = Rc::clone(&state_tts_rc);
+        let stop2 = stop_flag;
+
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok(Ok(samples)) => {
+                    dbg_log!(
+                        "[TTS] got {} samples, playing at {}Hz...",
+                        samples.len(),
+                        sr
+                    );
+                    btn2.remove_css_class("synthesizing");
+
+                    // Green button while speaking
+                    *state2.borrow_mut() = State::Speaking;
+                    btn2.add_css_class("speaking");
+
+                    let btn3 = btn2.clone();
+                    let state3 = Rc::clone(&state2);
+                    let stop3 = Arc::clone(&stop2);
+
+                    play_tts_audio(samples, sr, stop3, move || {
+                        btn3.remove_css_class("speaking");
+                        *state3.borrow_mut() = State::Idle;
+                    });
+
+                    glib::ControlFlow::Break
+                }
+                Ok(Err(e)) => {
+                    dbg_log!("[TTS] synthesis error: {e}");
+                    btn2.remove_css_class("synthesizing");
+                    *state2.borrow_mut() = State::Idle;
+                    glib::ControlFlow::Break
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(_) => {
+                    btn2.remove_css_class("synthesizing");
+                    *state2.borrow_mut() = State::Idle;
+                    glib::ControlFlow::Break
+                }
+            }
+        });
+    });
+    app.add_action(&read_clipboard_action);
+
+    // --- Transcribe Files action: batch-transcribe audio files ---
+    let batch_action = gtk4::gio::SimpleAction::new("transcribe-files", None);
+    let (runtime_batch, db_batch, config_batch) =
+        (Rc::clone(&runtime), Arc::clone(&db), Arc::clone(&config));
+    let win_batch = window.clone();
+    batch_action.connect_activate(move |_, _| {
+        choose_batch_files(&win_batch, &runtime_batch, &db_batch, &config_batch);
+    });
+    app.add_action(&batch_action);
+
+    // Action: compare every model and provider on one clip
+    let benchmark_action = gtk4::gio::SimpleAction::new("benchmark", None);
+    let (runtime_bench, db_bench, config_bench, win_bench) = (
+        Rc::clone(&runtime),
+        Arc::clone(&db),
+        Arc::clone(&config),
+        window.clone(),
+    );
+    benchmark_action.connect_activate(move |_, _| {
+        show_benchmark_window(&win_bench, &runtime_bench, &db_bench, &config_bench);
+    });
+    app.add_action(&benchmark_action);
+
+    // --- D-Bus action: "speak" — reads clipboard and speaks ---
+    let speak_action = gtk4::gio::SimpleAction::new("speak", None);
+    let runtime_speak = Rc::clone(&runtime);
+    let state_speak = Rc::clone(&state);
+    let btn_speak = button.clone();
+    speak_action.connect_activate(move |_, _| {
+        tracing::info!("[dbus] 'speak' action activated");
+        let rt = runtime_speak.borrow();
+        if rt.tts_provider == TtsProvider::None {
+            return;
+        }
+        let Some(ref engine) = rt.tts_engine else {
+            return;
+        };
+        let text = match arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
+            Ok(t) if !t.trim().is_empty() => t,
+            _ => return,
+        };
+        let engine = Arc::clone(engine);
+        let stop_flag = Arc::clone(&rt.tts_stop);
+        stop_flag.store(false, std::sync::atomic::Ordering::Relaxed);
+        drop(rt);
+
+        let sr = engine.sample_rate();
+        *state_speak.borrow_mut() = State::Synthesizing;
+        btn_speak.add_css_class("synthesizing");
+
+        let (tx, rx) = std::sync::mpsc::channel::<Result<Vec<i16>, String>>();
+        std::thread::spawn(move || {
+            let _ = tx.send(engine.synthesize(&text));
+        });
+
+        let btn2 = btn_speak.clone();
+        let state2 = Rc::clone(&state_speak);
+        let stop2 = stop_flag;
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            match rx.try_recv() {
+                Ok(Ok(samples)) => {
+                    btn2.remove_css_class("synthesizing");
+                    *state2.borrow_mut() = State::Speaking;
+                    btn2.add_css_class("speaking");
+                    let btn3 = btn2.clone();
+                    let state3 = Rc::clone(&state2);
+                    play_tts_audio(samples, sr, Arc::clone(&stop2), move || {
+                        btn3.remove_css_class("speaking");
+                        *state3.borrow_mut() = State::Idle;
+                    });
+                    glib::ControlFlow::Break
+                }
+                Ok(Err(_)) => {
+                    btn2.remove_css_class("synthesizing");
+                    *state2.borrow_mut() = State::Idle;
+                    glib::ControlFlow::Break
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(_) => {
+                    btn2.remove_css_class("synthesizing");
+                    *state2.borrow_mut() = State::Idle;
+                    glib::ControlFlow::Break
+                }
+            }
+        });
+    });
+    app.add_action(&speak_action);
+
+    if config.no_window {
+        // Nothing keeps the application alive without a window
+        std::mem::forget(app.hold());
+        tracing::info!("Running without a window; control it with D-Bus actions");
+    } else if button_visible {
+        window.present();
+    } else {
+        tracing::info!("Floating button hidden; show it from the tray or the show-button action");
+    }
+}
+
+/// Apply the History retention windows on a background thread.
+fn run_maintenance(db: &Arc<Mutex<Db>>, audio_dir: &std::path::Path) {
+    let (db, audio_dir) = (Arc::clone(db), audio_dir.to_path_buf());
+    std::thread::spawn(move || {
+        let Ok(d) = db.lock() else {
+            return;
+        };
+        let retention = crate::retention::Retention::load(&d);
+        match crate::retention::run(&d, &audio_dir, retention) {
+            Ok((0, 0)) => {}
+            Ok((entries, recordings)) => {
+                dbg_log!("[RETENTION] removed {entries} entries, {recordings} recordings");
+            }
+            Err(e) => tracing::warn!("Retention: {e}"),
+        }
+    });
+}
+
+/// Evaluate profile rules against the current network / time and return the
+/// provider to switch to, if it differs from the active one. When no rule
+/// matches, returns the manually chosen provider that a rule had overridden.
+fn profile_rule_target(runtime: &Rc<RefCell<RuntimeState>>, db: &Arc<Mutex<Db>>) -> Option<String> {
+    let (json, base) = {
+        let d = db.lock().ok()?;
+        (
+            d.get_setting("profile_rules").ok().flatten()?,
+            d.get_setting("profile_rule_base").ok().flatten(),
+        )
+    };
+    let rules = match crate::rules::parse_rules(&json) {
+        Ok(r) if !r.is_empty() => r,
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!("Ignoring profile rules: {e}");
+            return None;
+        }
+    };
+    let active = {
+        let rt = runtime.borrow();
+        if rt.auto_mode {
+            crate::auto::AUTO_PROVIDER.to_string()
+        } else {
+            rt.active_provider.clone()
+        }
+    };
+
+    let target = crate::rules::evaluate(&rules, &crate::rules::Context::current());
+    let d = db.lock().ok()?;
+    match target {
+        Some(target) if target != active => {
+            if base.is_none() {
+                let _ = d.set_setting("profile_rule_base", &active);
+            }
+            Some(target.to_string())
+        }
+        Some(_) => None,
+        None => {
+            let base = base?;
+            let _ = d.delete_setting("profile_rule_base");
+            (base != active).then_some(base)
+        }
+    }
+}
+
+/// Resolve the provider override of a record profile. Returns `Ok(None)` when
+/// the profile keeps the active provider, or an error to show when it can't
+/// be used right now.
+fn resolve_profile_route(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+    profile: &crate::profiles::Profile,
+) -> Result<Option<Route>, String> {
+    match profile.provider {
+        Some(ref provider) => resolve_provider_route(runtime, db, config, provider),
+        None => Ok(None),
+    }
+}
+
+/// Route a one-off transcription to `provider` (a profile override or a
+/// History re-run) without switching the active provider. Returns `Ok(None)`
+/// when `provider` is the active one, or an error to show when it can't be
+/// used right now.
+fn resolve_provider_route(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+    provider: &str,
+) -> Result<Option<Route>, String> {
+    {
+        let rt = runtime.borrow();
+        if !rt.auto_mode && provider == rt.active_provider {
+            return Ok(None);
+        }
+    }
+
+    if config::is_local_provider(provider) {
+        let custom = runtime.borrow().custom_model.clone();
+        return crate::transcription::local_route(config, provider, custom.as_deref()).map(Some);
+    }
+
+    let Some(backend) = auto_backends(db, config)
+        .into_iter()
+        .find(|b| b.provider == provider)
+    else {
+        return Err("Provider not set up".into());
+    };
+    if crate::policy::ensure_network_allowed(&backend.base_url).is_err() {
+        return Err("Local only: provider needs network".into());
+    }
+    if !crate::policy::is_local_url(&backend.base_url) {
+        let host = crate::policy::url_host(&backend.base_url);
+        if !cloud_consented(db, &host) {
+            return Err("Use this provider once to allow uploads".into());
+        }
+    }
+    Ok(Some(Route::Api(backend)))
+}
+
+/// Whether the user allowed uploads to `host` (see
+/// [`show_cloud_consent_dialog`]).
+fn cloud_consented(db: &Arc<Mutex<Db>>, host: &str) -> bool {
+    db.lock()
+        .is_ok_and(|d| crate::transcription::cloud_consented(&d, host))
+}
+
+/// Choose the endpoint, chat model and custom prompt of post-processing.
+fn show_postprocess_dialog(
+    window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Post-Processing")
+        .default_width(440)
+        .transient_for(window)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let setting = |key| {
+        db.lock()
+            .ok()
+            .and_then(|d| d.get_setting(key).ok().flatten())
+            .unwrap_or_default()
+    };
+    let providers: Vec<String> = auto_backends(db, config)
+        .into_iter()
+        .map(|b| b.provider)
+        .collect();
+
+    let info = gtk4::Label::new(Some(
+        "Transcripts are rewritten by a chat model of one of your API providers before they are copied.",
+    ));
+    info.set_wrap(true);
+    info.set_halign(gtk4::Align::Start);
+    vbox.append(&info);
+
+    let provider_heading = gtk4::Label::new(Some("Provider"));
+    provider_heading.set_halign(gtk4::Align::Start);
+    vbox.append(&provider_heading);
+    let labels: Vec<String> = providers.iter().map(|p| provider_label(p)).collect();
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let provider = gtk4::DropDown::from_strings(&labels);
+    let chosen = setting(crate::postprocess::PROVIDER_SETTING);
+    if let Some(i) = providers.iter().position(|p| *p == chosen) {
+        provider.set_selected(i as u32);
+    }
+    if providers.is_empty() {
+        provider.set_sensitive(false);
+        provider_heading.set_label("Provider (set up an API provider first)");
+    }
+    vbox.append(&provider);
+
+    let model_label = gtk4::Label::new(Some("Chat model"));
+    model_label.set_halign(gtk4::Align::Start);
+    vbox.append(&model_label);
+    let model = gtk4::Entry::new();
+    model.set_text(&setting(crate::postprocess::MODEL_SETTING));
+    let providers_p = providers.clone();
+    let placeholder = move |i: u32| {
+        providers_p
+            .get(i as usize)
+            .and_then(|p| crate::postprocess::default_model(p))
+            .unwrap_or("Model name")
+            .to_string()
+    };
+    model.set_placeholder_text(Some(&placeholder(provider.selected())));
+    let model_p = model.clone();
+    provider.connect_selected_notify(move |dd| {
+        model_p.set_placeholder_text(Some(&placeholder(dd.selected())));
+    });
+    vbox.append(&model);
+
+    let prompt_label = gtk4::Label::new(Some(&format!(
+        "Custom prompt (the Custom Prompt mode, up to {} bytes)",
+        crate::postprocess::MAX_PROMPT_BYTES
+    )));
+    prompt_label.set_halign(gtk4::Align::Start);
+    vbox.append(&prompt_label);
+    let prompt = gtk4::TextView::new();
+    prompt.set_wrap_mode(gtk4::WrapMode::WordChar);
+    prompt
+        .buffer()
+        .set_text(&setting(crate::postprocess::PROMPT_SETTING));
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_min_content_height(100);
+    scroll.set_child(Some(&prompt));
+    vbox.append(&scroll);
+
+    let failure = gtk4::Label::new(None);
+    failure.add_css_class("error");
+    failure.set_wrap(true);
+    failure.set_halign(gtk4::Align::Start);
+    failure.set_visible(false);
+    vbox.append(&failure);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    vbox.append(&btn_box);
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_save, dialog_save) = (Arc::clone(db), dialog.clone());
+    save_btn.connect_clicked(move |_| {
+        let buffer = prompt.buffer();
+        let prompt_text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        if let Err(e) = crate::postprocess::check_prompt(prompt_text.trim()) {
+            failure.set_label(&e);
+            failure.set_visible(true);
+            return;
+        }
+        if let Ok(d) = db_save.lock() {
+            if let Some(p) = providers.get(provider.selected() as usize) {
+                let _ = d.set_setting(crate::postprocess::PROVIDER_SETTING, p);
+            }
+            let _ = d.set_setting(crate::postprocess::MODEL_SETTING, model.text().trim());
+            let _ = d.set_setting(crate::postprocess::PROMPT_SETTING, prompt_text.trim());
+        }
+        dialog_save.close();
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Edit the custom vocabulary, one term per line.
+fn show_vocabulary_dialog(
+    window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    runtime: &Rc<RefCell<RuntimeState>>,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Vocabulary")
+        .default_width(360)
+        .default_height(380)
+        .transient_for(window)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "Names, jargon and acronyms to recognize, one per line. They steer local whisper models and API providers that take a prompt.",
+    ));
+    info.set_wrap(true);
+    info.set_halign(gtk4::Align::Start);
+    vbox.append(&info);
+
+    let terms = gtk4::TextView::new();
+    terms.set_wrap_mode(gtk4::WrapMode::WordChar);
+    terms
+        .buffer()
+        .set_text(&runtime.borrow().vocabulary.join("\n"));
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_child(Some(&terms));
+    vbox.append(&scroll);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    vbox.append(&btn_box);
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_save, runtime_save, dialog_save) = (Arc::clone(db), Rc::clone(runtime), dialog.clone());
+    save_btn.connect_clicked(move |_| {
+        let buffer = terms.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        let parsed = crate::vocabulary::parse(&text);
+        if let Ok(d) = db_save.lock()
+            && let Err(e) = crate::vocabulary::save(&d, &parsed)
+        {
+            tracing::error!("Vocabulary save error: {e}");
+            return;
+        }
+        runtime_save.borrow_mut().vocabulary = parsed;
+        dialog_save.close();
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Edit the shell command and webhook run after each transcription.
+fn show_hooks_dialog(window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let current = db
+        .lock()
+        .map(|d| crate::hooks::stored(&d))
+        .unwrap_or_default();
+
+    let dialog = gtk4::Window::builder()
+        .title("Hooks")
+        .default_width(420)
+        .transient_for(window)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "Run after every transcription, in the background. Leave a field empty to turn it off.",
+    ));
+    info.set_wrap(true);
+    info.set_halign(gtk4::Align::Start);
+    vbox.append(&info);
+
+    let command_label = gtk4::Label::new(Some("Shell command (gets the text on stdin)"));
+    command_label.set_halign(gtk4::Align::Start);
+    vbox.append(&command_label);
+    let command = gtk4::Entry::new();
+    command.set_placeholder_text(Some("todo.sh add \"$(cat)\""));
+    command.set_text(current.command.as_deref().unwrap_or(""));
+    vbox.append(&command);
+
+    let url_label = gtk4::Label::new(Some(
+        "Webhook URL (gets text, timestamp and provider as JSON)",
+    ));
+    url_label.set_halign(gtk4::Align::Start);
+    vbox.append(&url_label);
+    let url = gtk4::Entry::new();
+    url.set_placeholder_text(Some("https://example.com/webhook"));
+    url.set_text(current.url.as_deref().unwrap_or(""));
+    vbox.append(&url);
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    vbox.append(&btn_box);
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_save, dialog_save) = (Arc::clone(db), dialog.clone());
+    save_btn.connect_clicked(move |_| {
+        let hooks = crate::hooks::Hooks {
+            command: Some(command.text().to_string()),
+            url: Some(url.text().to_string()),
+        };
+        let saved = hooks.validated().and_then(|hooks| {
+            let d = db_save.lock().map_err(|e| e.to_string())?;
+            crate::hooks::save(&d, &hooks).map_err(|e| e.to_string())
+        });
+        match saved {
+            Ok(()) => dialog_save.close(),
+            Err(e) => {
+                error.set_text(&e);
+                error.set_visible(true);
+            }
+        }
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// The post-processing to run on the next transcript, if a mode is on; a
+/// mode that can't run is skipped with a warning.
+fn post_process_job(db: &Arc<Mutex<Db>>, config: &Config) -> Option<crate::postprocess::Job> {
+    let d = db.lock().ok()?;
+    crate::postprocess::job(&d, config).unwrap_or_else(|e| {
+        tracing::warn!("Post-processing skipped: {e}");
+        None
+    })
+}
+
+/// Pass the messages of a transcription worker on, sending a finished
+/// transcript through `job` first: [`SttMsg::PostProcessing`] while it
+/// runs, then [`SttMsg::Raw`] with the original text ahead of the
+/// rewritten one. A failed rewrite delivers the original unchanged.
+fn post_process_relay(
+    rx: std::sync::mpsc::Receiver<SttMsg>,
+    job: crate::postprocess::Job,
+) -> std::sync::mpsc::Receiver<SttMsg> {
+    let (tx, relayed) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for message in rx {
+            let message = match message {
+                SttMsg::Done(Ok(mut transcript), latency) if !transcript.text.trim().is_empty() => {
+                    let _ = tx.send(SttMsg::PostProcessing);
+                    if let Some(raw) = crate::postprocess::rewrite(&job, &mut transcript) {
+                        let _ = tx.send(SttMsg::Raw(raw));
+                    }
+                    SttMsg::Done(Ok(transcript), latency)
+                }
+                message => message,
+            };
+            if tx.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    relayed
+}
+
+/// Why the active provider can't take audio that wasn't just recorded (a
+/// dropped file, a retry), if it can't.
+fn audio_unwelcome(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+) -> Option<&'static str> {
+    let rt = runtime.borrow();
+    // Same one-time consent as a dictation; the button asks for it
+    if rt.active_service == TranscriptionService::Api
+        && !crate::policy::is_local_url(&rt.api_base_url)
+        && !cloud_consented(db, crate::policy::url_host(&rt.api_base_url))
+    {
+        return Some("Dictate once to allow uploads first");
+    }
+    if rt.active_service == TranscriptionService::Local && rt.local_engine.is_none() {
+        return Some("Local model not loaded");
+    }
+    None
+}
+
+/// Report a failed dictation: its kind under the button and, while the
+/// button is shown, a popover with the provider's message, what to do about
+/// it and **Retry with same audio** when `audio` (the recording and its
+/// sample rate) can be transcribed again.
+fn show_failure(
+    button: &gtk4::Button,
+    status: &gtk4::Label,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    state: &Rc<RefCell<State>>,
+    db: &Arc<Mutex<Db>>,
+    error: &str,
+    audio: Option<(Vec<u8>, u32)>,
+) {
+    let kind = crate::errors::classify(error);
+    let audio = audio.filter(|_| kind.retryable());
+    let retryable = audio.is_some();
+    runtime.borrow_mut().failed_audio = audio;
+
+    if !button.is_mapped() {
+        // Tray only: the status goes out as a notification
+        flash_status(status, &crate::errors::summary(error));
+        return;
+    }
+    show_status(status, kind.status());
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(8);
+    vbox.set_margin_bottom(8);
+    vbox.set_margin_start(8);
+    vbox.set_margin_end(8);
+    let heading = gtk4::Label::new(Some(kind.title()));
+    heading.add_css_class("heading");
+    heading.set_halign(gtk4::Align::Start);
+    vbox.append(&heading);
+    let detail = gtk4::Label::new(Some(&crate::errors::detail(error)));
+    detail.set_wrap(true);
+    detail.set_max_width_chars(40);
+    detail.set_halign(gtk4::Align::Start);
+    detail.set_xalign(0.0);
+    detail.set_selectable(true);
+    vbox.append(&detail);
+    let hint = gtk4::Label::new(Some(kind.hint()));
+    hint.add_css_class("dim-label");
+    hint.set_wrap(true);
+    hint.set_max_width_chars(40);
+    hint.set_halign(gtk4::Align::Start);
+    hint.set_xalign(0.0);
+    vbox.append(&hint);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let logs_btn = gtk4::Button::with_label("Show Logs");
+    logs_btn.set_action_name(Some("app.show-logs"));
+    buttons.append(&logs_btn);
+    let retry_btn = gtk4::Button::with_label("Retry with same audio");
+    retry_btn.add_css_class("suggested-action");
+    retry_btn.set_visible(retryable);
+    buttons.append(&retry_btn);
+    vbox.append(&buttons);
+
+    let popover = gtk4::Popover::new();
+    popover.set_child(Some(&vbox));
+    popover.set_parent(button);
+    let st = status.clone();
+    popover.connect_closed(move |p| {
+        hide_status(&st);
+        p.unparent();
+    });
+    let popover_c = popover.clone();
+    logs_btn.connect_clicked(move |_| popover_c.popdown());
+
+    let (popover_c, button_c, status_c) = (popover.clone(), button.clone(), status.clone());
+    let (runtime_c, state_c, db_c) = (Rc::clone(runtime), Rc::clone(state), Arc::clone(db));
+    retry_btn.connect_clicked(move |_| {
+        popover_c.popdown();
+        if *state_c.borrow() != State::Idle {
+            return;
+        }
+        if let Some(reason) = audio_unwelcome(&runtime_c, &db_c) {
+            flash_status(&status_c, reason);
+            return;
+        }
+        let Some(audio) = runtime_c.borrow_mut().failed_audio.take() else {
+            return;
+        };
+        // Stop a "recording" that is the kept audio; the click handler
+        // transcribes it like a dropped file
+        runtime_c.borrow_mut().dropped_audio = Some(audio);
+        *state_c.borrow_mut() = State::Recording;
+        button_c.emit_clicked();
+    });
+    popover.popup();
+}
+
+/// Decode a file dropped on the button and transcribe it as if it had just
+/// been recorded: the usual delivery, clipboard and History included. Each
+/// call fails with `false` when the active provider can't take it now.
+fn transcribe_dropped_file(
+    path: &std::path::Path,
+    button: &gtk4::Button,
+    status: &gtk4::Label,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    state: &Rc<RefCell<State>>,
+    db: &Arc<Mutex<Db>>,
+) -> bool {
+    if let Some(reason) = audio_unwelcome(runtime, db) {
+        flash_status(status, reason);
+        return false;
+    }
+
+    *state.borrow_mut() = State::Processing;
+    button.add_css_class("processing");
+    button.remove_css_class("done");
+    show_status(status, "Reading file...");
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = tx.send(crate::audio::decode_file(&path));
+    });
+    let (button, status, runtime, state) = (
+        button.clone(),
+        status.clone(),
+        Rc::clone(runtime),
+        Rc::clone(state),
+    );
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let decoded = match rx.try_recv() {
+            Ok(decoded) => decoded,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+            Err(_) => Err("File reader stopped".into()),
+        };
+        button.remove_css_class("processing");
+        match decoded {
+            Ok(audio) => {
+                // Stop a "recording" that is the file; the click handler
+                // takes it from here
+                runtime.borrow_mut().dropped_audio = Some(audio);
+                *state.borrow_mut() = State::Recording;
+                button.emit_clicked();
+            }
+            Err(e) => {
+                tracing::warn!("Dropped file unreadable: {e}");
+                *state.borrow_mut() = State::Idle;
+                runtime.borrow().sounds.play(SoundEvent::Error);
+                flash_status(&status, &format!("Err: {e}"));
+            }
+        }
+        glib::ControlFlow::Break
+    });
+    true
+}
+
+/// Where a batch goes: the active provider (the fastest backend in auto
+/// mode), subject to the same upload consent as a dictation.
+fn batch_route(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Config,
+) -> Result<Route, String> {
+    let rt = runtime.borrow();
+    let route = match rt.auto_route.clone() {
+        Some(backend) if rt.auto_mode => Route::Api(backend),
+        _ => {
+            let d = db.lock().map_err(|e| e.to_string())?;
+            crate::transcription::route(&d, config, &rt.active_provider)?
+        }
+    };
+    if let Route::Api(ref backend) = route
+        && !crate::policy::is_local_url(&backend.base_url)
+    {
+        let host = crate::policy::url_host(&backend.base_url);
+        if !cloud_consented(db, &host) {
+            return Err(format!(
+                "Uploads to {host} not allowed yet; dictate once first"
+            ));
+        }
+    }
+    Ok(route)
+}
+
+/// Run `on_response` once `chooser` is answered. Native choosers aren't
+/// owned by a parent widget, so the handler holds on to this one until then.
+fn connect_chooser_response(
+    chooser: &gtk4::FileChooserNative,
+    on_response: impl Fn(&gtk4::FileChooserNative, gtk4::ResponseType) + 'static,
+) {
+    let keep_alive = RefCell::new(Some(chooser.clone()));
+    chooser.connect_response(move |chooser, response| {
+        keep_alive.borrow_mut().take();
+        on_response(chooser, response);
+    });
+}
+
+/// Ask for audio files and transcribe them in a progress window.
+fn choose_batch_files(
+    window: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Transcribe Files"),
+        Some(window),
+        gtk4::FileChooserAction::Open,
+        Some("Transcribe"),
+        Some("Cancel"),
+    );
+    chooser.set_select_multiple(true);
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Audio (WAV, MP3, Ogg, M4A, FLAC)"));
+    for extension in crate::decode::EXTENSIONS {
+        filter.add_suffix(extension);
+    }
+    chooser.add_filter(&filter);
+
+    let (window, runtime, db, config) = (
+        window.clone(),
+        Rc::clone(runtime),
+        Arc::clone(db),
+        Arc::clone(config),
+    );
+    connect_chooser_response(&chooser, move |chooser, response| {
+        if response != gtk4::ResponseType::Accept {
+            return;
+        }
+        let inputs: Vec<std::path::PathBuf> = chooser
+            .files()
+            .iter::<gtk4::gio::File>()
+            .filter_map(|f| f.ok().and_then(|f| f.path()))
+            .collect();
+        let job = crate::batch::collect(&inputs).and_then(|files| {
+            Ok(crate::batch::Job {
+                files,
+                route: batch_route(&runtime, &db, &config)?,
+                language: runtime.borrow().stt_language.clone(),
+                translate: false,
+                vocabulary: runtime.borrow().vocabulary.clone(),
+            })
+        });
+        // The loaded model serves the batch instead of a second copy
+        let engines = {
+            let rt = runtime.borrow();
+            match (&job, &rt.local_engine) {
+                (
+                    Ok(crate::batch::Job {
+                        route: Route::Local { provider, path, .. },
+                        ..
+                    }),
+                    Some(engine),
+                ) if *provider == rt.active_provider => {
+                    crate::transcription::EngineCache::with_engine(path, Arc::clone(engine))
+                }
+                _ => crate::transcription::EngineCache::default(),
+            }
+        };
+        show_batch_dialog(&window, &db, &config, job, engines);
+    });
+    chooser.show();
+}
+
+/// Run a clip (the last kept recording, or a chosen file) through every
+/// downloaded model and configured provider, then show their latency,
+/// speed and word differences against the selected provider's transcript.
+fn show_benchmark_window(
+    window: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Benchmark")
+        .default_width(560)
+        .default_height(480)
+        .transient_for(window)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let clip: Rc<RefCell<Option<std::path::PathBuf>>> = Rc::new(RefCell::new(
+        db.lock()
+            .ok()
+            .and_then(|d| crate::benchmark::last_recording(&d))
+            .map(|(_, path)| path),
+    ));
+    let source_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    let source = gtk4::Label::new(None);
+    source.set_hexpand(true);
+    source.set_xalign(0.0);
+    source.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+    let show_source = {
+        let source = source.clone();
+        move |clip: &Option<std::path::PathBuf>| match clip {
+            Some(path) => source.set_label(&format!("Clip: {}", path.display())),
+            None => source.set_label("No kept recording; choose an audio file"),
+        }
+    };
+    show_source(&clip.borrow());
+    source_row.append(&source);
+    let choose_btn = gtk4::Button::with_label("Choose File...");
+    source_row.append(&choose_btn);
+    let run_btn = gtk4::Button::with_label("Run");
+    run_btn.add_css_class("suggested-action");
+    run_btn.set_sensitive(clip.borrow().is_some());
+    source_row.append(&run_btn);
+    vbox.append(&source_row);
+
+    let status = gtk4::Label::new(Some(
+        "Each downloaded model and configured provider transcribes the clip in turn.",
+    ));
+    status.set_halign(gtk4::Align::Start);
+    status.set_wrap(true);
+    status.add_css_class("dim-label");
+    vbox.append(&status);
+
+    let progress = gtk4::ProgressBar::new();
+    vbox.append(&progress);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let results = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    scroll.set_child(Some(&results));
+    vbox.append(&scroll);
+
+    let close_btn = gtk4::Button::with_label("Close");
+    close_btn.set_halign(gtk4::Align::End);
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+    vbox.append(&close_btn);
+
+    let (clip_choose, run_choose, dialog_choose) =
+        (Rc::clone(&clip), run_btn.clone(), dialog.clone());
+    choose_btn.connect_clicked(move |_| {
+        let chooser = gtk4::FileChooserNative::new(
+            Some("Benchmark Clip"),
+            Some(&dialog_choose),
+            gtk4::FileChooserAction::Open,
+            Some("Choose"),
+            Some("Cancel"),
+        );
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some("Audio (WAV, MP3, Ogg, M4A, FLAC)"));
+        for extension in crate::decode::EXTENSIONS {
+            filter.add_suffix(extension);
+        }
+        chooser.add_filter(&filter);
+        let (clip, run_btn, show_source) = (
+            Rc::clone(&clip_choose),
+            run_choose.clone(),
+            show_source.clone(),
+        );
+        connect_chooser_response(&chooser, move |chooser, response| {
+            if response != gtk4::ResponseType::Accept {
+                return;
+            }
+            if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                *clip.borrow_mut() = Some(path);
+                show_source(&clip.borrow());
+                run_btn.set_sensitive(true);
+            }
+        });
+        chooser.show();
+    });
+
+    let (runtime, db, config) = (Rc::clone(runtime), Arc::clone(db), Arc::clone(config));
+    run_btn.connect_clicked(move |run_btn| {
+        let Some(path) = clip.borrow().clone() else {
+            return;
+        };
+        while let Some(child) = results.first_child() {
+            results.remove(&child);
+        }
+        progress.set_fraction(0.0);
+        let (wav, sample_rate) = match crate::benchmark::decode(&path) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                status.set_label(&e);
+                return;
+            }
+        };
+        let (candidates, preferred) = match db.lock() {
+            Ok(d) => (
+                crate::benchmark::candidates(&d, &config),
+                crate::transcription::stored_provider(&d, &config),
+            ),
+            Err(_) => return,
+        };
+        if candidates.is_empty() {
+            status.set_label("No downloaded models or configured providers");
+            return;
+        }
+        run_btn.set_sensitive(false);
+        let audio_secs = crate::audio::wav_duration_secs(&wav).unwrap_or(0.0);
+        let names: Vec<String> = candidates.iter().map(|c| c.provider.clone()).collect();
+        let total = names.len();
+        let (language, vocabulary) = {
+            let rt = runtime.borrow();
+            (rt.stt_language.clone(), rt.vocabulary.clone())
+        };
+        let rx = crate::benchmark::spawn(
+            Arc::clone(&config),
+            candidates,
+            wav,
+            sample_rate,
+            language,
+            vocabulary,
+        );
+        let mut outcomes = Vec::new();
+        let (status, progress, results, run_btn) = (
+            status.clone(),
+            progress.clone(),
+            results.clone(),
+            run_btn.clone(),
+        );
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            loop {
+                let message = match rx.try_recv() {
+                    Ok(message) => message,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        crate::benchmark::Progress::Finished
+                    }
+                };
+                match message {
+                    crate::benchmark::Progress::Started(i) => {
+                        status.set_label(&format!(
+                            "Transcribing with {} ({} of {total})...",
+                            names[i],
+                            i + 1
+                        ));
+                    }
+                    crate::benchmark::Progress::Done(i, outcome) => {
+                        progress.set_fraction((i + 1) as f64 / total as f64);
+                        outcomes.push(outcome);
+                    }
+                    crate::benchmark::Progress::Finished => {
+                        let reference = crate::benchmark::reference(&outcomes, &preferred);
+                        status.set_label(&match reference {
+                            Some(r) => {
+                                format!("{audio_secs:.1} s clip, compared with {}", r.provider)
+                            }
+                            None => "Every provider failed".to_string(),
+                        });
+                        for outcome in &outcomes {
+                            results.append(&benchmark_result(outcome, audio_secs, reference));
+                        }
+                        run_btn.set_sensitive(true);
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+        });
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// One provider's benchmark result: its numbers, then its transcript with
+/// words missing from the reference struck out and extra ones underlined.
+fn benchmark_result(
+    outcome: &crate::benchmark::Outcome,
+    audio_secs: f64,
+    reference: Option<&crate::benchmark::Outcome>,
+) -> gtk4::Box {
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    let heading = gtk4::Label::new(Some(&crate::benchmark::summary_line(
+        outcome, audio_secs, reference,
+    )));
+    heading.set_xalign(0.0);
+    heading.add_css_class("monospace");
+    row.append(&heading);
+    if let Ok(text) = &outcome.text {
+        let compared = reference
+            .filter(|r| r.provider != outcome.provider)
+            .and_then(|r| r.text.as_ref().ok());
+        let markup = match compared {
+            Some(r) => crate::benchmark::word_diff(r, text)
+                .iter()
+                .map(|change| match change {
+                    crate::benchmark::Change::Same(w) => glib::markup_escape_text(w).to_string(),
+                    crate::benchmark::Change::Removed(w) => format!(
+                        "<span foreground=\"#c01c28\" strikethrough=\"true\">{}</span>",
+                        glib::markup_escape_text(w)
+                    ),
+                    crate::benchmark::Change::Added(w) => format!(
+                        "<span foreground=\"#26a269\" underline=\"single\">{}</span>",
+                        glib::markup_escape_text(w)
+                    ),
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => glib::markup_escape_text(text).to_string(),
+        };
+        let body = gtk4::Label::new(None);
+        body.set_markup(&markup);
+        body.set_xalign(0.0);
+        body.set_wrap(true);
+        body.set_selectable(true);
+        row.append(&body);
+    }
+    row
+}
+
+/// Run `job` with a progress bar and each file's result.
+fn show_batch_dialog(
+    window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+    job: Result<crate::batch::Job, String>,
+    engines: crate::transcription::EngineCache,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Transcribe Files")
+        .default_width(480)
+        .default_height(360)
+        .transient_for(window)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let status = gtk4::Label::new(None);
+    status.set_halign(gtk4::Align::Start);
+    status.set_wrap(true);
+    vbox.append(&status);
+
+    let progress = gtk4::ProgressBar::new();
+    vbox.append(&progress);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let results = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+    scroll.set_child(Some(&results));
+    vbox.append(&scroll);
+
+    let close_btn = gtk4::Button::with_label("Cancel");
+    close_btn.set_halign(gtk4::Align::End);
+    vbox.append(&close_btn);
+
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+    let cancel_close = Arc::clone(&cancel);
+    dialog.connect_close_request(move |_| {
+        cancel_close.store(true, std::sync::atomic::Ordering::Relaxed);
+        glib::Propagation::Proceed
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+
+    let job = match job {
+        Ok(job) => job,
+        Err(e) => {
+            status.set_label(&e);
+            close_btn.set_label("Close");
+            return;
+        }
+    };
+    let names: Vec<String> = job
+        .files
+        .iter()
+        .map(|f| {
+            f.file_name().map_or_else(
+                || f.display().to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    let total = names.len();
+    let rx = crate::batch::spawn(
+        job,
+        Arc::clone(config),
+        Arc::clone(db),
+        Arc::new(engines),
+        cancel,
+    );
+    let failed = Rc::new(Cell::new(0usize));
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        loop {
+            let message = match rx.try_recv() {
+                Ok(message) => message,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    crate::batch::Progress::Finished
+                }
+            };
+            match message {
+                crate::batch::Progress::Started(i) => {
+                    status.set_label(&format!("Transcribing {} of {total}: {}", i + 1, names[i]));
+                }
+                crate::batch::Progress::Done(i, result) => {
+                    progress.set_fraction((i + 1) as f64 / total as f64);
+                    let line = match result {
+                        Ok(outcome) if outcome.subtitles => {
+                            format!("{}: .txt and .srt written", names[i])
+                        }
+                        Ok(_) => format!("{}: .txt written", names[i]),
+                        Err(e) => {
+                            failed.set(failed.get() + 1);
+                            format!("{}: {e}", names[i])
+                        }
+                    };
+                    let label = gtk4::Label::new(Some(&line));
+                    label.set_halign(gtk4::Align::Start);
+                    label.set_wrap(true);
+                    label.set_selectable(true);
+                    results.append(&label);
+                }
+                crate::batch::Progress::Finished => {
+                    status.set_label(&match failed.get() {
+                        0 => format!("Done: {total} files transcribed"),
+                        n => format!("Done: {n} of {total} files failed"),
+                    });
+                    close_btn.set_label("Close");
+                    return glib::ControlFlow::Break;
+                }
+            }
+        }
+    });
+}
+
+/// A part of a split recording on its way into History.
+struct SplitPart {
+    number: u32,
+    /// The part's WAV in the parts folder, removed once it is in History.
+    path: std::path::PathBuf,
+    audio_secs: f64,
+    level_db: Option<f64>,
+    history_mode: crate::db::HistoryMode,
+    /// Keep the WAV as the entry's recording instead of removing it.
+    keep_audio: bool,
+}
+
+/// Split the recording that just began every few minutes (the `auto-split`
+/// setting), transcribing each part into History while it goes on. Not
+/// during live dictation, which reads the whole buffer, or a dry run, which
+/// must not write History. Parts use `profile`'s provider and History
+/// choice, like the rest of the dictation.
+fn start_auto_split(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    recorder: &Rc<RefCell<Recorder>>,
+    state: &Rc<RefCell<State>>,
+    status: &gtk4::Label,
+    profile: Option<&crate::profiles::Profile>,
+) {
+    let (minutes, session, history_mode, store_audio) = {
+        let mut rt = runtime.borrow_mut();
+        // A timer from an earlier recording sees the new session and stops
+        rt.split_session += 1;
+        let Some(minutes) = rt.auto_split else {
+            return;
+        };
+        if rt.live_session.is_some() || rt.dry_run {
+            return;
+        }
+        (
+            minutes,
+            rt.split_session,
+            profile.and_then(|p| p.history).unwrap_or(rt.history_mode),
+            rt.store_audio,
+        )
+    };
+    let provider = profile.and_then(|p| p.provider.clone());
+    let started = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let (runtime, config, db, recorder, state, status) = (
+        Rc::clone(runtime),
+        Arc::clone(config),
+        Arc::clone(db),
+        Rc::clone(recorder),
+        Rc::clone(state),
+        status.clone(),
+    );
+    let mut number = 0;
+    glib::timeout_add_local(crate::split::interval(minutes), move || {
+        if runtime.borrow().split_session != session || *state.borrow() != State::Recording {
+            return glib::ControlFlow::Break;
+        }
+        number += 1;
+        let path = crate::split::part_path(&config.audio_dir, &started, number);
+        let wav = match recorder.borrow().take_segment() {
+            Ok(wav) => wav,
+            Err(e) => {
+                tracing::warn!("Auto-split part {number}: {e}");
+                return glib::ControlFlow::Continue;
+            }
+        };
+        let part = SplitPart {
+            number,
+            audio_secs: crate::audio::wav_duration_secs(&wav).unwrap_or(0.0),
+            level_db: crate::audio::wav_level_db(&wav),
+            history_mode,
+            keep_audio: store_audio && history_mode == crate::db::HistoryMode::Full,
+            path,
+        };
+        let started_run = std::fs::create_dir_all(crate::split::parts_dir(&config.audio_dir))
+            .and_then(|_| std::fs::write(&part.path, &wav))
+            .map_err(|e| format!("Saving {}: {e}", part.path.display()))
+            .and_then(|_| {
+                spawn_stored_transcription(&runtime, &config, &db, &part.path, provider.as_deref())
+            });
+        match started_run {
+            Ok(run) => {
+                show_status(&status, &crate::split::part_status(number));
+                let log = runtime.borrow().session_log.clone();
+                finish_split_part(run, part, &config, &db, &status, &log);
+            }
+            Err(e) => tracing::warn!("Auto-split part {number} not transcribed: {e}"),
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Wait for the transcription of `part` and add it to History. A part that
+/// fails keeps its WAV in the parts folder.
+fn finish_split_part(
+    run: StoredRun,
+    part: SplitPart,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    status: &gtk4::Label,
+    log: &gtk4::gio::ListStore,
+) {
+    let (config, db, status, log) = (
+        Arc::clone(config),
+        Arc::clone(db),
+        status.clone(),
+        log.clone(),
+    );
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let (result, latency) = match run.rx.try_recv() {
+            Ok(SttMsg::Done(result, latency)) => (result, latency),
+            Ok(_) | Err(std::sync::mpsc::TryRecvError::Empty) => {
+                return glib::ControlFlow::Continue;
+            }
+            Err(_) => (
+                Err("Transcription stopped".into()),
+                std::time::Duration::ZERO,
+            ),
+        };
+        let saved = result.and_then(|transcript| {
+            let d = db.lock().map_err(|e| e.to_string())?;
+            let language = transcript.language.as_deref();
+            let rules = d.accepted_replacements(language).unwrap_or_default();
+            let mut text = crate::corrections::apply(&transcript.text, &rules);
+            if let Some(commands) = crate::dictation_commands::stored(&d) {
+                text = crate::dictation_commands::apply(&text, &commands);
+            }
+            let id = crate::transcription::store(&d, &text, language, part.history_mode)?;
+            if let Some(id) = id
+                && part.history_mode == crate::db::HistoryMode::Full
+                && !transcript.segments.is_empty()
+            {
+                let segments: Vec<_> = transcript
+                    .segments
+                    .iter()
+                    .map(|seg| crate::local_stt::Segment {
+                        text: crate::corrections::apply(&seg.text, &rules),
+                        ..seg.clone()
+                    })
+                    .collect();
+                if let Err(e) = d.insert_segments(id, &segments) {
+                    tracing::error!("DB segments insert error: {e}");
+                }
+            }
+            match id.filter(|_| part.keep_audio) {
+                Some(id) => {
+                    let kept = config::recording_path(&config.audio_dir, id);
+                    let moved = std::fs::rename(&part.path, &kept)
+                        .map_err(|e| e.to_string())
+                        .and_then(|_| d.set_audio_path(id, &kept).map_err(|e| e.to_string()));
+                    if let Err(e) = moved {
+                        tracing::warn!("Keeping part recording failed: {e}");
+                    }
+                }
+                None => {
+                    let _ = std::fs::remove_file(&part.path);
+                }
+            }
+            if let Err(e) = d.insert_usage(
+                id,
+                &run.provider,
+                &run.model,
+                part.audio_secs,
+                latency.as_millis() as u64,
+                text.split_whitespace().count(),
+                part.level_db,
+            ) {
+                tracing::error!("DB usage insert error: {e}");
+            }
+            Ok(text)
+        });
+        match saved {
+            Ok(text) => log_transcript(&log, &db, &text, &run.provider),
+            Err(e) => {
+                tracing::warn!(
+                    "Auto-split part {} failed, audio kept at {}: {e}",
+                    part.number,
+                    part.path.display()
+                );
+                show_status(&status, &format!("Part {} failed", part.number));
+            }
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+/// A meeting running in its window (see [`crate::meeting`]).
+struct MeetingRun {
+    meeting: RefCell<crate::meeting::Meeting>,
+    /// The meeting's own recorder, `None` once stopped.
+    recorder: RefCell<Option<Recorder>>,
+    /// `YYYYMMDD-HHMMSS` of the start, naming the segment files.
+    stamp: String,
+    history_mode: crate::db::HistoryMode,
+    /// Provider of the latest segment, for the session log and hooks.
+    provider: RefCell<String>,
+    saved: Cell<bool>,
+    buffer: gtk4::TextBuffer,
+    status: gtk4::Label,
+}
+
+/// Meeting mode: record with a recorder of its own until **Stop**,
+/// transcribing a segment every few seconds into the window's live
+/// transcript, then save the whole meeting as one History entry. Opening
+/// it again while a meeting runs brings its window back.
+fn show_meeting_window(
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+) {
+    if let Some(window) = runtime.borrow().meeting_window.clone() {
+        window.present();
+        return;
+    }
+    let (device, segment_secs, history_mode) = match db.lock() {
+        Ok(d) => (
+            d.get_setting("audio_device")
+                .ok()
+                .flatten()
+                .filter(|d| !d.is_empty()),
+            crate::meeting::segment_secs(&d),
+            crate::transcription::stored_history_mode(&d),
+        ),
+        Err(_) => return,
+    };
+
+    let dialog = gtk4::Window::builder()
+        .title("Meeting")
+        .default_width(520)
+        .default_height(420)
+        .transient_for(parent)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let status = gtk4::Label::new(None);
+    status.set_xalign(0.0);
+    status.add_css_class("dim-label");
+    vbox.append(&status);
+
+    let view = gtk4::TextView::new();
+    view.set_editable(false);
+    view.set_cursor_visible(false);
+    view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_child(Some(&view));
+    // Keep the newest segment in view
+    scroll
+        .vadjustment()
+        .connect_upper_notify(|adj| adj.set_value(adj.upper() - adj.page_size()));
+    vbox.append(&scroll);
+
+    let mut recorder = Recorder::new();
+    recorder.set_device(device);
+    let started = recorder.start();
+    let run = Rc::new(MeetingRun {
+        meeting: RefCell::new(crate::meeting::Meeting::new(chrono::Local::now())),
+        recorder: RefCell::new(started.is_ok().then_some(recorder)),
+        stamp: chrono::Local::now().format("%Y%m%d-%H%M%S").to_string(),
+        history_mode,
+        provider: RefCell::new(String::new()),
+        saved: Cell::new(false),
+        buffer: view.buffer(),
+        status: status.clone(),
+    });
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let copy_btn = gtk4::Button::with_label("Copy");
+    let run_copy = Rc::clone(&run);
+    copy_btn.connect_clicked(move |_| {
+        let document = run_copy.meeting.borrow().document();
+        if let Err(e) = crate::input::copy_to_clipboard(&document) {
+            tracing::warn!("Copy failed: {e}");
+        }
+    });
+    buttons.append(&copy_btn);
+    let stop_btn = gtk4::Button::with_label("Stop");
+    stop_btn.add_css_class("destructive-action");
+    buttons.append(&stop_btn);
+    vbox.append(&buttons);
+
+    if let Err(e) = started {
+        status.set_label(&format!("Err: {e}"));
+        stop_btn.set_sensitive(false);
+        dialog.set_child(Some(&vbox));
+        dialog.present();
+        return;
+    }
+    meeting_refresh(&run);
+
+    let stop = {
+        let (run, runtime, config, db, stop_btn) = (
+            Rc::clone(&run),
+            Rc::clone(runtime),
+            Arc::clone(config),
+            Arc::clone(db),
+            stop_btn.clone(),
+        );
+        move || {
+            let Some(mut recorder) = run.recorder.borrow_mut().take() else {
+                return;
+            };
+            stop_btn.set_sensitive(false);
+            match recorder.stop() {
+                Ok(wav) => meeting_hand_off(&run, wav, &runtime, &config, &db),
+                Err(e) => dbg_log!("[MEETING] last segment: {e}"),
+            }
+            meeting_refresh(&run);
+            if run.meeting.borrow().pending() == 0 {
+                meeting_save(&run, &runtime, &db);
+            }
+        }
+    };
+    let stop = Rc::new(stop);
+    let stop_click = Rc::clone(&stop);
+    stop_btn.connect_clicked(move |_| stop_click());
+    let (stop_close, runtime_close) = (Rc::clone(&stop), Rc::clone(runtime));
+    dialog.connect_close_request(move |_| {
+        // Closing ends the meeting; its last segments are still saved
+        stop_close();
+        runtime_close.borrow_mut().meeting_window = None;
+        glib::Propagation::Proceed
+    });
+
+    let (run_segment, runtime_segment, config_segment, db_segment) = (
+        Rc::clone(&run),
+        Rc::clone(runtime),
+        Arc::clone(config),
+        Arc::clone(db),
+    );
+    glib::timeout_add_local(
+        std::time::Duration::from_secs(segment_secs as u64),
+        move || {
+            let taken = match run_segment.recorder.borrow().as_ref() {
+                Some(recorder) => recorder.take_segment(),
+                None => return glib::ControlFlow::Break,
+            };
+            match taken {
+                Ok(wav) => meeting_hand_off(
+                    &run_segment,
+                    wav,
+                    &runtime_segment,
+                    &config_segment,
+                    &db_segment,
+                ),
+                Err(e) => dbg_log!("[MEETING] segment: {e}"),
+            }
+            glib::ControlFlow::Continue
+        },
+    );
+    let run_tick = Rc::clone(&run);
+    glib::timeout_add_seconds_local(1, move || {
+        if run_tick.recorder.borrow().is_none() {
+            return glib::ControlFlow::Break;
+        }
+        meeting_refresh(&run_tick);
+        glib::ControlFlow::Continue
+    });
+
+    runtime.borrow_mut().meeting_window = Some(dialog.clone());
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Show the meeting's transcript and status in its window.
+fn meeting_refresh(run: &MeetingRun) {
+    let meeting = run.meeting.borrow();
+    let document = meeting.document();
+    if run
+        .buffer
+        .text(&run.buffer.start_iter(), &run.buffer.end_iter(), false)
+        != document
+    {
+        run.buffer.set_text(&document);
+    }
+    if !run.saved.get() {
+        run.status
+            .set_label(&meeting.status(run.recorder.borrow().is_some()));
+    }
+}
+
+/// Transcribe a segment of the meeting, `wav`, from a file in the parts
+/// folder; it fills its place in the transcript when done, and saves the
+/// meeting when it was the last one after **Stop**.
+fn meeting_hand_off(
+    run: &Rc<MeetingRun>,
+    wav: Vec<u8>,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+) {
+    let audio_secs = crate::audio::wav_duration_secs(&wav).unwrap_or(0.0);
+    let level_db = crate::audio::wav_level_db(&wav);
+    let index = run.meeting.borrow_mut().push((audio_secs * 1000.0) as i64);
+    let path = crate::split::part_path(
+        &config.audio_dir,
+        &format!("{}-meeting", run.stamp),
+        index as u32 + 1,
+    );
+    let started_run = std::fs::create_dir_all(crate::split::parts_dir(&config.audio_dir))
+        .and_then(|_| std::fs::write(&path, &wav))
+        .map_err(|e| format!("Saving {}: {e}", path.display()))
+        .and_then(|_| spawn_stored_transcription(runtime, config, db, &path, None));
+    let stt = match started_run {
+        Ok(stt) => stt,
+        Err(e) => {
+            tracing::warn!("Meeting segment {} not transcribed: {e}", index + 1);
+            run.meeting.borrow_mut().fail(index, &e);
+            meeting_refresh(run);
+            return;
+        }
+    };
+    meeting_refresh(run);
+    let (run, runtime, db) = (Rc::clone(run), Rc::clone(runtime), Arc::clone(db));
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let (result, latency) = match stt.rx.try_recv() {
+            Ok(SttMsg::Done(result, latency)) => (result, latency),
+            Ok(_) | Err(std::sync::mpsc::TryRecvError::Empty) => {
+                return glib::ControlFlow::Continue;
+            }
+            Err(_) => (
+                Err("Transcription stopped".into()),
+                std::time::Duration::ZERO,
+            ),
+        };
+        match result {
+            Ok(transcript) => {
+                let language = transcript.language.as_deref();
+                let rules = db
+                    .lock()
+                    .map(|d| d.accepted_replacements(language).unwrap_or_default())
+                    .unwrap_or_default();
+                let text = crate::corrections::apply(&transcript.text, &rules);
+                let segments: Vec<_> = transcript
+                    .segments
+                    .iter()
+                    .map(|seg| crate::local_stt::Segment {
+                        text: crate::corrections::apply(&seg.text, &rules),
+                        ..seg.clone()
+                    })
+                    .collect();
+                run.meeting.borrow_mut().finish(
+                    index,
+                    &text,
+                    &segments,
+                    transcript.language.clone(),
+                );
+                let _ = std::fs::remove_file(&path);
+                if let Ok(d) = db.lock()
+                    && let Err(e) = d.insert_usage(
+                        None,
+                        &stt.provider,
+                        &stt.model,
+                        audio_secs,
+                        latency.as_millis() as u64,
+                        text.split_whitespace().count(),
+                        level_db,
+                    )
+                {
+                    tracing::error!("DB usage insert error: {e}");
+                }
+                *run.provider.borrow_mut() = stt.provider.clone();
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Meeting segment {} failed, audio kept at {}: {e}",
+                    index + 1,
+                    path.display()
+                );
+                run.meeting.borrow_mut().fail(index, &e);
+            }
+        }
+        meeting_refresh(&run);
+        if run.recorder.borrow().is_none() && run.meeting.borrow().pending() == 0 {
+            meeting_save(&run, &runtime, &db);
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+/// Save the stopped meeting as one History entry and deliver it to the
+/// session log and hooks, once.
+fn meeting_save(run: &MeetingRun, runtime: &Rc<RefCell<RuntimeState>>, db: &Arc<Mutex<Db>>) {
+    if run.saved.replace(true) {
+        return;
+    }
+    let meeting = run.meeting.borrow();
+    let text = meeting.text();
+    if text.is_empty() {
+        run.status.set_label("Nothing was transcribed");
+        return;
+    }
+    let stored = db.lock().map_err(|e| e.to_string()).and_then(|d| {
+        let id = crate::transcription::store(
+            &d,
+            &text,
+            meeting.language().as_deref(),
+            run.history_mode,
+        )?;
+        if let Some(id) = id
+            && run.history_mode == crate::db::HistoryMode::Full
+            && let Err(e) = d.insert_segments(id, &meeting.segments())
+        {
+            tracing::error!("DB segments insert error: {e}");
+        }
+        Ok(id)
+    });
+    run.status.set_label(&match stored {
+        Ok(Some(_)) => "Meeting saved to History".to_string(),
+        Ok(None) => "Meeting finished (not stored in History)".to_string(),
+        Err(e) => format!("Saving the meeting failed: {e}"),
+    });
+    let log = runtime.borrow().session_log.clone();
+    log_transcript(&log, db, &text, &run.provider.borrow());
+}
+
+/// Start a live dictation for the recording that just began, if it is
+/// enabled and the plain local model path applies (profiles and API
+/// providers transcribe once at the end). Returns whether one started.
+fn start_live_dictation(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+    recorder: &Recorder,
+) -> bool {
+    let mut rt = runtime.borrow_mut();
+    if !rt.live_dictation
+        || rt.dry_run
+        || rt.pending_profile.is_some()
+        || rt.active_service != TranscriptionService::Local
+    {
+        return false;
+    }
+    let Some(engine) = rt.local_engine.clone() else {
+        return false;
+    };
+    let language = match config::find_local_model(&rt.active_provider) {
+        Some(m) => config::effective_language(m, &rt.stt_language),
+        None => rt.stt_language.as_str(),
+    }
+    .to_string();
+    let rules = db
+        .lock()
+        .ok()
+        .and_then(|d| d.accepted_replacements(None).ok())
+        .unwrap_or_default();
+    let mut params = config.whisper.clone();
+    params.diarize = false;
+    params.initial_prompt = crate::vocabulary::prompt(None, &rt.vocabulary);
+    rt.live_session = Some(crate::dictation::LiveSession::start(
+        engine,
+        recorder.live_audio(),
+        language,
+        params,
+        rules,
+    ));
+    true
+}
+
+/// Start capturing system audio for live captions with the loaded local model.
+fn start_live_captions(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+) -> Result<
+    (
+        crate::captions::CaptionSession,
+        std::sync::mpsc::Receiver<String>,
+    ),
+    String,
+> {
+    let rt = runtime.borrow();
+    let engine = rt
+        .local_engine
+        .clone()
+        .ok_or("Load a local model for captions")?;
+    let language = match config::find_local_model(&rt.active_provider) {
+        Some(m) => config::effective_language(m, &rt.stt_language),
+        None => rt.stt_language.as_str(),
+    }
+    .to_string();
+    let mut params = config.whisper.clone();
+    params.diarize = false;
+    crate::captions::CaptionSession::start(engine, language, params)
+}
+
+/// Open the caption window and show each caption from `rx` as it arrives.
+fn show_captions_window(
+    app: &gtk4::Application,
+    rx: std::sync::mpsc::Receiver<String>,
+) -> gtk4::Window {
+    const TITLE: &str = "WhisperCrabs Live Captions";
+
+    let label = gtk4::Label::new(Some("Listening..."));
+    label.add_css_class("caption-text");
+    label.set_wrap(true);
+    label.set_xalign(0.0);
+    label.set_valign(gtk4::Align::End);
+    let win = gtk4::Window::builder()
+        .application(app)
+        .title(TITLE)
+        .default_width(720)
+        .default_height(160)
+        .resizable(true)
+        .css_classes(vec!["live-captions"])
+        .child(&label)
+        .build();
+    win.present();
+
+    // GTK 4 has no keep-above; ask the window manager on X11 (Wayland ignores it)
+    #[cfg(target_os = "linux")]
+    glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
+        let _ = std::process::Command::new("wmctrl")
+            .args(["-r", TITLE, "-b", "add,above"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    });
+
+    let mut lines = crate::captions::CaptionLines::default();
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        loop {
+            match rx.try_recv() {
+                Ok(text) => {
+                    lines.push(&text);
+                    label.set_text(&lines.text());
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    return glib::ControlFlow::Continue;
+                }
+                Err(_) => return glib::ControlFlow::Break,
+            }
+        }
+    });
+    win
+}
+
+/// The pairing token from the keyring; a new one when `renew` or none is stored yet.
+fn remote_token(renew: bool) -> Result<String, String> {
+    if !renew && let Some(token) = crate::secrets::get(crate::secrets::REMOTE_TOKEN) {
+        return Ok(token);
+    }
+    let token = crate::db::generate_key()?;
+    crate::secrets::set(crate::secrets::REMOTE_TOKEN, &token)?;
+    Ok(token)
+}
+
+/// Name of `state` as activation sources see it.
+fn state_name(state: State) -> &'static str {
+    match state {
+        State::Idle => "idle",
+        State::Recording => "recording",
+        State::Processing => "processing",
+        State::Synthesizing | State::Speaking => "speaking",
+    }
+}
+
+/// Start an activation source and run its activations as the `record` and
+/// `stop` actions until it stops. It runs while the returned handle is kept.
+fn start_activation_source<S: crate::activation::ActivationSource + 'static>(
+    mut source: S,
+    state: &Rc<RefCell<State>>,
+) -> Result<Rc<S>, String> {
+    let rx = source.start()?;
+    let name = source.name();
+    dbg_log!("[ACTIVATION] {name} started");
+    let source = Rc::new(source);
+    let (weak, state) = (Rc::downgrade(&source), Rc::clone(state));
+    glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
+        if let Some(source) = weak.upgrade() {
+            source.state_changed(state_name(*state.borrow()));
+        }
+        loop {
+            let activation = match rx.try_recv() {
+                Ok(activation) => activation,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    return glib::ControlFlow::Continue;
+                }
+                Err(_) => {
+                    dbg_log!("[ACTIVATION] {name} stopped");
+                    return glib::ControlFlow::Break;
+                }
+            };
+            // Busy transcribing or speaking: triggers wait for idle
+            let recording = match *state.borrow() {
+                State::Idle => false,
+                State::Recording => true,
+                _ => continue,
+            };
+            let Some(start) = activation.resolve(recording) else {
+                continue;
+            };
+            let action = if start { "record" } else { "stop" };
+            dbg_log!("[ACTIVATION] {name}: {activation:?} → '{action}'");
+            if let Some(app) = gtk4::gio::Application::default() {
+                app.activate_action(action, None);
+            }
+        }
+    });
+    Ok(source)
+}
+
+/// Register the global shortcuts with the desktop portal, suggesting the
+/// keys chosen in Preferences.
+fn start_portal_shortcuts(
+    db: &Arc<Mutex<Db>>,
+    state: &Rc<RefCell<State>>,
+) -> Result<Rc<crate::global_shortcuts::PortalShortcutsSource>, String> {
+    let source = {
+        let d = db.lock().map_err(|e| e.to_string())?;
+        crate::global_shortcuts::PortalShortcutsSource::new(|id| {
+            d.get_setting(&crate::global_shortcuts::trigger_setting(id))
+                .ok()
+                .flatten()
+        })
+    };
+    start_activation_source(source, state)
+}
+
+/// Replace the window's in-app keys with those in settings.
+fn install_keybindings(
+    window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    slot: &Rc<RefCell<Option<gtk4::ShortcutController>>>,
+) {
+    if let Some(old) = slot.borrow_mut().take() {
+        window.remove_controller(&old);
+    }
+    let controller = gtk4::ShortcutController::new();
+    controller.set_scope(gtk4::ShortcutScope::Global);
+    let Ok(d) = db.lock() else {
+        return;
+    };
+    for binding in crate::keybindings::BINDINGS {
+        let stored = d
+            .get_setting(&crate::keybindings::setting(binding.id))
+            .ok()
+            .flatten();
+        let Some(keys) = crate::keybindings::effective(binding, stored.as_deref()) else {
+            continue;
+        };
+        let Some(trigger) = gtk4::ShortcutTrigger::parse_string(keys) else {
+            tracing::warn!("Ignoring key \"{keys}\" for {}", binding.label);
+            continue;
+        };
+        controller.add_shortcut(gtk4::Shortcut::new(
+            Some(trigger),
+            Some(gtk4::NamedAction::new(binding.action)),
+        ));
+    }
+    window.add_controller(controller.clone());
+    *slot.borrow_mut() = Some(controller);
+}
+
+/// Start the tray icon and run the app actions picked in its menu.
+fn start_tray(
+    button_visible: bool,
+    state: &Rc<RefCell<State>>,
+) -> Result<Rc<crate::tray::TraySource>, String> {
+    let source = start_activation_source(crate::tray::TraySource::new(button_visible), state)?;
+    if let Some(rx) = source.take_actions() {
+        glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
+            loop {
+                match rx.try_recv() {
+                    Ok(action) => {
+                        if let Some(app) = gtk4::gio::Application::default() {
+                            app.activate_action(action, None);
+                        }
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue;
+                    }
+                    Err(_) => return glib::ControlFlow::Break,
+                }
+            }
+        });
+    }
+    Ok(source)
+}
+
+/// Start the phone remote, with a new pairing token when `renew_token`.
+fn start_remote_control(
+    port: u16,
+    renew_token: bool,
+    state: &Rc<RefCell<State>>,
+) -> Result<Rc<crate::remote::RemoteSource>, String> {
+    let token = remote_token(renew_token)?;
+    start_activation_source(crate::remote::RemoteSource::new(port, token), state)
+}
+
+/// Turn the phone remote on or off and show the QR code to pair a phone with.
+fn show_remote_dialog(
+    parent: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    port: u16,
+    remote: &Rc<RefCell<Option<Rc<crate::remote::RemoteSource>>>>,
+    state: &Rc<RefCell<State>>,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Remote Control")
+        .default_width(360)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let toggle_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+    let toggle_label = gtk4::Label::new(Some("Use a phone on this network as a record button"));
+    toggle_label.set_wrap(true);
+    toggle_label.set_xalign(0.0);
+    toggle_label.set_hexpand(true);
+    let switch = gtk4::Switch::new();
+    switch.set_valign(gtk4::Align::Center);
+    switch.set_active(remote.borrow().is_some());
+    toggle_row.append(&toggle_label);
+    toggle_row.append(&switch);
+    vbox.append(&toggle_row);
+
+    // Modules of the pairing QR code, drawn black on white with a quiet zone
+    let qr: Rc<RefCell<(usize, Vec<bool>)>> = Rc::new(RefCell::new((0, Vec::new())));
+    let qr_area = gtk4::DrawingArea::new();
+    qr_area.set_content_width(240);
+    qr_area.set_content_height(240);
+    qr_area.set_halign(gtk4::Align::Center);
+    let qr_draw = Rc::clone(&qr);
+    qr_area.set_draw_func(move |_, cr, width, height| {
+        const QUIET_ZONE: usize = 4;
+        let (size, modules) = &*qr_draw.borrow();
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        let _ = cr.paint();
+        if *size == 0 {
+            return;
+        }
+        let cell = width.min(height) as f64 / (size + 2 * QUIET_ZONE) as f64;
+        cr.set_source_rgb(0.0, 0.0, 0.0);
+        for (i, _) in modules.iter().enumerate().filter(|(_, dark)| **dark) {
+            let (x, y) = (i % size + QUIET_ZONE, i / size + QUIET_ZONE);
+            cr.rectangle(x as f64 * cell, y as f64 * cell, cell, cell);
+        }
+        let _ = cr.fill();
+    });
+    vbox.append(&qr_area);
+
+    let url_label = gtk4::Label::new(None);
+    url_label.set_selectable(true);
+    url_label.set_wrap(true);
+    url_label.set_wrap_mode(gtk4::pango::WrapMode::Char);
+    vbox.append(&url_label);
+
+    let hint = gtk4::Label::new(Some(
+        "Scan the code with the phone's camera, or open the address in its browser.",
+    ));
+    hint.set_wrap(true);
+    hint.add_css_class("dim-label");
+    vbox.append(&hint);
+
+    let error = gtk4::Label::new(None);
+    error.set_wrap(true);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let renew_btn = gtk4::Button::with_label("New Token");
+    renew_btn.set_tooltip_text(Some("Unpair every phone paired so far"));
+    let close_btn = gtk4::Button::with_label("Close");
+    btn_box.append(&renew_btn);
+    btn_box.append(&close_btn);
+    vbox.append(&btn_box);
+    dialog.set_child(Some(&vbox));
+
+    // Show the pairing code while the server runs
+    let refresh = {
+        let (remote, qr, qr_area, url_label, hint, renew_btn) = (
+            Rc::clone(remote),
+            Rc::clone(&qr),
+            qr_area.clone(),
+            url_label.clone(),
+            hint.clone(),
+            renew_btn.clone(),
+        );
+        Rc::new(move || {
+            let running = remote.borrow().is_some();
+            let url = crate::remote::lan_address()
+                .zip(crate::secrets::get(crate::secrets::REMOTE_TOKEN))
+                .map(|(ip, token)| crate::remote::pairing_url(ip, port, &token));
+            *qr.borrow_mut() = match url.as_deref().filter(|_| running) {
+                Some(url) => crate::remote::qr_modules(url).unwrap_or_else(|e| {
+                    tracing::warn!("{e}");
+                    (0, Vec::new())
+                }),
+                None => (0, Vec::new()),
+            };
+            qr_area.queue_draw();
+            url_label.set_label(match (running, &url) {
+                (true, Some(url)) => url.as_str(),
+                (true, None) => "No network connection found",
+                (false, _) => "",
+            });
+            for widget in [
+                qr_area.upcast_ref::<gtk4::Widget>(),
+                url_label.upcast_ref(),
+                hint.upcast_ref(),
+            ] {
+                widget.set_visible(running);
+            }
+            renew_btn.set_sensitive(running);
+        })
+    };
+    refresh();
+
+    // (Re)start the server, or stop it
+    let apply = {
+        let (remote, state, db, error, refresh) = (
+            Rc::clone(remote),
+            Rc::clone(state),
+            Arc::clone(db),
+            error.clone(),
+            Rc::clone(&refresh),
+        );
+        Rc::new(move |enabled: bool, renew: bool| -> bool {
+            // Drop the old server first so the port is free again
+            *remote.borrow_mut() = None;
+            let result = if enabled {
+                start_remote_control(port, renew, &state).map(Some)
+            } else {
+                Ok(None)
+            };
+            let ok = match result {
+                Ok(server) => {
+                    *remote.borrow_mut() = server;
+                    error.set_visible(false);
+                    true
+                }
+                Err(e) => {
+                    tracing::warn!("Remote control failed: {e}");
+                    error.set_label(&e);
+                    error.set_visible(true);
+                    false
+                }
+            };
+            if let Ok(d) = db.lock() {
+                let on = remote.borrow().is_some();
+                let _ = d.set_setting("remote_control", if on { "true" } else { "false" });
+            }
+            refresh();
+            ok
+        })
+    };
+
+    let apply_switch = Rc::clone(&apply);
+    switch.connect_active_notify(move |switch| {
+        if !apply_switch(switch.is_active(), false) {
+            switch.set_active(false);
+        }
+    });
+    renew_btn.connect_clicked(move |_| {
+        apply(true, true);
+    });
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+
+    dialog.present();
+}
+
+/// Send a WAV clip to an API backend on a worker thread; the result and the
+/// request latency arrive on `tx`.
+fn spawn_api_transcription(
+    base_url: String,
+    path: String,
+    api_key: String,
+    model: String,
+    audio: AudioUpload,
+    options: crate::api::RequestOptions,
+    tx: std::sync::mpsc::Sender<SttMsg>,
+) {
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        // Long in-memory takes are spooled to disk here, off the UI thread
+        let audio = match audio {
+            AudioUpload::Bytes(wav) => AudioUpload::from_recording(wav),
+            file => file,
+        };
+        let result = crate::transcription::transcribe_api(
+            &base_url, &path, &api_key, &model, audio, &options,
+        );
+        let _ = tx.send(SttMsg::Done(result, started.elapsed()));
+    });
+}
+
+/// A transcription of a stored recording started from History.
+struct StoredRun {
+    rx: std::sync::mpsc::Receiver<SttMsg>,
+    /// Provider id and model doing the work, for the revision and usage records.
+    provider: String,
+    model: String,
+}
+
+/// Transcribes stored recordings for History. `run` uses the given provider
+/// id, or the active provider for `None`; `choices` lists the providers
+/// offered under **Re-run with…** as (id, label).
+#[derive(Clone)]
+struct Retranscribe {
+    run: Rc<dyn Fn(&std::path::Path, Option<&str>) -> Result<StoredRun, String>>,
+    choices: Rc<dyn Fn() -> Vec<(String, String)>>,
+}
+
+/// Transcribe the stored recording at `audio` on a worker thread, with
+/// `provider` or the active provider, applying the same guards as a new
+/// dictation. API uploads stream the file instead of reading it into memory.
+fn spawn_stored_transcription(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    audio: &std::path::Path,
+    provider: Option<&str>,
+) -> Result<StoredRun, String> {
+    let route = match provider {
+        Some(provider) => resolve_provider_route(runtime, db, config, provider)?,
+        None => None,
+    };
+    let rt = runtime.borrow();
+    let (tx, rx) = std::sync::mpsc::channel::<SttMsg>();
+    match route {
+        Some(Route::Api(b)) => {
+            let options = api_request_options(
+                config,
+                &b.provider,
+                &b.path,
+                &rt.stt_language,
+                false,
+                &rt.vocabulary,
+            )?;
+            let run = StoredRun {
+                rx,
+                provider: b.provider,
+                model: b.model.clone(),
+            };
+            spawn_api_transcription(
+                b.base_url,
+                api_path_for(&b.path, false),
+                b.api_key.unwrap_or_default(),
+                b.model,
+                AudioUpload::File(audio.to_path_buf()),
+                options,
+                tx,
+            );
+            return Ok(run);
+        }
+        Some(Route::Local {
+            provider,
+            path,
+            multilingual,
+        }) => {
+            // One-shot model: load it for this transcription only
+            let wav = std::fs::read(audio).map_err(|e| format!("Can't read recording: {e}"))?;
+            let sample_rate = hound::WavReader::new(std::io::Cursor::new(&wav))
+                .map(|r| r.spec().sample_rate)
+                .map_err(|e| format!("Stored recording unreadable: {e}"))?;
+            let language = if multilingual {
+                rt.stt_language.clone()
+            } else {
+                "en".to_string()
+            };
+            let mut params = config.whisper.clone();
+            params.diarize = rt.label_speakers;
+            params.initial_prompt = crate::vocabulary::prompt(None, &rt.vocabulary);
+            let model = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            std::thread::spawn(move || {
+                let started = std::time::Instant::now();
+                let result = LocalEngine::load(&path).and_then(|w| {
+                    w.transcribe_with_segments(&wav, sample_rate, &language, &params, |_| {})
+                });
+                let _ = tx.send(SttMsg::Done(result, started.elapsed()));
+            });
+            return Ok(StoredRun {
+                rx,
+                provider,
+                model,
+            });
+        }
+        None => {}
+    }
+    let model = match rt.active_service {
+        TranscriptionService::Api => {
+            if !config
+                .policy
+                .allows(&rt.active_provider, Some(&rt.api_base_url))
+            {
+                return Err("Blocked by policy".into());
+            }
+            crate::policy::ensure_network_allowed(&rt.api_base_url)?;
+            if !crate::policy::is_local_url(&rt.api_base_url) {
+                let host = crate::policy::url_host(&rt.api_base_url);
+                if !cloud_consented(db, &host) {
+                    return Err(format!("Uploads to {host} not allowed yet"));
+                }
+            }
+            let options = api_request_options(
+                config,
+                &rt.active_provider,
+                &rt.api_path,
+                &rt.stt_language,
+                false,
+                &rt.vocabulary,
+            )?;
+            spawn_api_transcription(
+                rt.api_base_url.clone(),
+                api_path_for(&rt.api_path, false),
+                rt.api_key.clone().unwrap_or_default(),
+                rt.api_model.clone(),
+                AudioUpload::File(audio.to_path_buf()),
+                options,
+                tx,
+            );
+            rt.api_model.clone()
+        }
+        TranscriptionService::Local => {
+            let engine = rt.local_engine.clone().ok_or("No local model loaded")?;
+            let wav = std::fs::read(audio).map_err(|e| format!("Can't read recording: {e}"))?;
+            let sample_rate = hound::WavReader::new(std::io::Cursor::new(&wav))
+                .map(|r| r.spec().sample_rate)
+                .map_err(|e| format!("Stored recording unreadable: {e}"))?;
+            let language = match config::find_local_model(&rt.active_provider) {
+                Some(m) => config::effective_language(m, &rt.stt_language),
+                None => rt.stt_language.as_str(),
+            }
+            .to_string();
+            let mut params = config.whisper.clone();
+            params.diarize = rt.label_speakers;
+            params.initial_prompt = crate::vocabulary::prompt(None, &rt.vocabulary);
+            std::thread::spawn(move || {
+                let started = std::time::Instant::now();
+                let result =
+                    engine.transcribe_with_segments(&wav, sample_rate, &language, &params, |_| {});
+                let _ = tx.send(SttMsg::Done(result, started.elapsed()));
+            });
+            config::find_local_model(&rt.active_provider)
+                .map(|m| m.file_name.to_string())
+                .unwrap_or_default()
+        }
+    };
+    Ok(StoredRun {
+        rx,
+        provider: rt.active_provider.clone(),
+        model,
+    })
+}
+
+/// Providers a stored recording can be re-run with, as (id, label): API
+/// backends that are set up, then downloaded local models, filtered by
+/// organization policy.
+fn rerun_choices(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Config,
+) -> Vec<(String, String)> {
+    let mut choices: Vec<(String, String)> = auto_backends(db, config)
+        .into_iter()
+        .map(|b| {
+            let label = provider_label(&b.provider);
+            (b.provider, label)
+        })
+        .collect();
+    for dir in [&config.models_dir, &config.local_models_dir] {
+        for (m, _) in config::downloaded_models(dir) {
+            choices.push((m.id.to_string(), provider_label(m.id)));
+        }
+        for (m, _) in config::downloaded_vosk_models(dir) {
+            choices.push((m.id.to_string(), provider_label(m.id)));
+        }
+    }
+    if runtime
+        .borrow()
+        .custom_model
+        .as_ref()
+        .is_some_and(|p| p.exists())
+    {
+        choices.push((
+            config::CUSTOM_LOCAL_MODEL.to_string(),
+            provider_label(config::CUSTOM_LOCAL_MODEL),
+        ));
+    }
+    let mut seen = std::collections::HashSet::new();
+    choices.retain(|(id, _)| {
+        seen.insert(id.clone())
+            && (!config::is_local_provider(id) || config.policy.allows(id, None))
+    });
+    choices
+}
+
+/// Human-readable name of a provider id, e.g. `Groq` or `Small (Multilingual)`.
+fn provider_label(id: &str) -> String {
+    if let Some(preset) = config::find_preset(id) {
+        return preset.label.to_string();
+    }
+    if let Some(m) = config::find_local_model(id) {
+        let kind = if m.multilingual {
+            "Multilingual"
+        } else {
+            "English"
+        };
+        return format!("{} ({kind})", m.label);
+    }
+    if let Some(m) = config::find_vosk_model(id) {
+        return format!("{} (Vosk)", m.label);
+    }
+    match id {
+        "custom" => "Custom API".to_string(),
+        config::CUSTOM_LOCAL_MODEL => "Custom model".to_string(),
+        _ => id.to_string(),
+    }
+}
+
+/// The model to send to `preset`: the one chosen in Preferences, else its default.
+fn preset_model(db: &Arc<Mutex<Db>>, preset: &config::ApiPreset) -> String {
+    let stored = db.lock().ok().and_then(|d| {
+        d.get_setting(&crate::preferences::model_setting(preset.id))
+            .ok()
+            .flatten()
+    });
+    crate::preferences::effective_model(stored.as_deref(), preset.default_model)
+}
+
+/// API backends auto mode may route to: presets with a usable key plus the
+/// saved custom endpoint, filtered by organization policy.
+fn auto_backends(db: &Arc<Mutex<Db>>, config: &Config) -> Vec<crate::auto::Backend> {
+    db.lock()
+        .map(|d| crate::transcription::api_backends(&d, config))
+        .unwrap_or_default()
+}
+
+/// Probe all auto-mode backends in the background and update the route.
+/// Does nothing if a probe round is already running.
+fn start_auto_probe(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    config: &Arc<Config>,
+) {
+    {
+        let mut rt = runtime.borrow_mut();
+        if rt.auto_probing {
+            return;
+        }
+        rt.auto_probing = true;
+    }
+    let backends = auto_backends(db, config);
+    let to_probe = backends.clone();
+    let (tx, rx) = std::sync::mpsc::channel::<Vec<crate::auto::ProbeResult>>();
+    std::thread::spawn(move || {
+        let _ = tx.send(crate::auto::probe_all(&to_probe));
+    });
+
+    let runtime = Rc::clone(runtime);
+    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
+        match rx.try_recv() {
+            Ok(results) => {
+                let mut rt = runtime.borrow_mut();
+                rt.auto_probing = false;
+                let current = rt.auto_route.as_ref().map(|b| b.provider.clone());
+                let chosen = crate::auto::pick(&results, current.as_deref());
+                dbg_log!("[AUTO] fastest healthy backend: {chosen:?}");
+                rt.auto_route =
+                    chosen.and_then(|p| backends.iter().find(|b| b.provider == p).cloned());
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(_) => {
+                runtime.borrow_mut().auto_probing = false;
+                glib::ControlFlow::Break
+            }
+        }
+    });
+}
+
+fn switch_to_auto(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+) {
+    {
+        let mut rt = runtime.borrow_mut();
+        rt.auto_mode = true;
+        rt.active_service = TranscriptionService::Api;
+        rt.active_provider = crate::auto::AUTO_PROVIDER.to_string();
+        rt.local_engine = None;
+    }
+
+    if let Ok(d) = db.lock() {
+        let _ = d.set_setting("transcription_mode", crate::auto::AUTO_PROVIDER);
+    }
+
+    action.set_state(&crate::auto::AUTO_PROVIDER.to_variant());
+    start_auto_probe(runtime, db, config);
+
+    show_status(status, "Auto mode (fastest provider)");
+    let st = status.clone();
+    glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+        hide_status(&st);
+    });
+}
+
+fn switch_to_preset(
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    preset: &config::ApiPreset,
+) {
+    // Resolve API key: DB per-provider key → env var for the env's endpoint
+    let resolved_key = if preset.needs_key {
+        db.lock()
+            .ok()
+            .and_then(|d| {
+                d.get_setting(&format!("api_key_{}", preset.id))
+                    .ok()
+                    .flatten()
+            })
+            .or_else(|| config.env_key_for(preset.base_url))
+    } else {
+        None
+    };
+
+    // If provider needs a key and we don't have one, show a dialog to collect it
+    if preset.needs_key && resolved_key.is_none() {
+        show_api_key_dialog(parent, runtime, config, db, action, status, preset);
+        return;
+    }
+
+    apply_preset(runtime, config, db, action, status, preset, resolved_key);
+}
+
+fn apply_preset(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    preset: &config::ApiPreset,
+    api_key: Option<String>,
+) {
+    {
+        let mut rt = runtime.borrow_mut();
+        rt.active_service = TranscriptionService::Api;
+        rt.active_provider = preset.id.to_string();
+        rt.api_base_url = preset.base_url.to_string();
+        rt.api_model = preset_model(db, preset);
+        rt.api_path = config::DEFAULT_TRANSCRIPTION_PATH.to_string();
+        rt.api_key = api_key;
+        rt.local_engine = None;
+    }
+
+    // Persist to DB
+    if let Ok(d) = db.lock() {
+        let _ = d.set_setting("transcription_mode", preset.id);
+    }
+
+    action.set_state(&preset.id.to_variant());
+
+    show_status(status, &format!("{} mode", preset.label));
+    let st = status.clone();
+    glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+        hide_status(&st);
+    });
+}
+
+/// One-time consent dialog shown before audio is first uploaded to a cloud host.
+/// "Always allow" is remembered per host; "Local only" turns on the privacy switch.
+fn show_cloud_consent_dialog(
+    parent: &gtk4::ApplicationWindow,
+    button: &gtk4::Button,
+    app: &gtk4::Application,
+    db: &Arc<Mutex<Db>>,
+    provider_label: &str,
+    host: &str,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Send audio to the cloud?")
+        .default_width(420)
+        .default_height(160)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let label = gtk4::Label::new(Some(&format!(
+        "{provider_label} transcribes on a remote server. Your recorded audio \
+         (a WAV clip of what you say) will be uploaded to {host}.\n\n\
+         The transcript is stored only on this machine."
+    )));
+    label.set_wrap(true);
+    label.set_halign(gtk4::Align::Start);
+    vbox.append(&label);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let local_btn = gtk4::Button::with_label("Local only");
+    let allow_btn = gtk4::Button::with_label("Always allow");
+    allow_btn.add_css_class("suggested-action");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&local_btn);
+    btn_box.append(&allow_btn);
+    vbox.append(&btn_box);
+
+    dialog.set_child(Some(&vbox));
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_local = dialog.clone();
+    let app_local = app.clone();
+    local_btn.connect_clicked(move |_| {
+        if !crate::policy::local_only() {
+            app_local.activate_action("local-only", None);
+        }
+        dialog_local.close();
+    });
+
+    let dialog_allow = dialog.clone();
+    let db_allow = Arc::clone(db);
+    let btn_allow = button.clone();
+    let key = format!("cloud_consent_{host}");
+    allow_btn.connect_clicked(move |_| {
+        if let Ok(d) = db_allow.lock() {
+            let _ = d.set_setting(&key, "allow");
+        }
+        dialog_allow.close();
+        // Consent given — start the recording the user asked for
+        btn_allow.emit_clicked();
+    });
+
+    dialog.present();
+}
+
+fn show_api_key_dialog(
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    preset: &config::ApiPreset,
+) {
+    let previous_provider = runtime.borrow().active_provider.clone();
+
+    let dialog = gtk4::Window::builder()
+        .title(format!("{} API Key", preset.label))
+        .default_width(380)
+        .default_height(140)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let grid = gtk4::Grid::builder()
+        .row_spacing(8)
+        .column_spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+
+    let label = gtk4::Label::new(Some(&format!("Enter your {} API key:", preset.label)));
+    label.set_halign(gtk4::Align::Start);
+    grid.attach(&label, 0, 0, 2, 1);
+
+    let key_entry = gtk4::Entry::new();
+    key_entry.set_hexpand(true);
+    key_entry.set_placeholder_text(Some("API key"));
+    key_entry.set_input_purpose(gtk4::InputPurpose::Password);
+    key_entry.set_visibility(false);
+    grid.attach(&key_entry, 0, 1, 2, 1);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    grid.attach(&btn_box, 0, 2, 2, 1);
+
+    dialog.set_child(Some(&grid));
+
+    // Cancel → revert radio to previous provider
+    let action_cancel = action.clone();
+    let prev = previous_provider.clone();
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        action_cancel.set_state(&prev.to_variant());
+        dialog_cancel.close();
+    });
+
+    // Save → persist key to DB, then switch
+    let runtime_save = Rc::clone(runtime);
+    let config_save = Arc::clone(config);
+    let db_save = Arc::clone(db);
+    let action_save = action.clone();
+    let status_save = status.clone();
+    let dialog_save = dialog.clone();
+    let preset_id = preset.id;
+    let preset_label = preset.label;
+    let preset_base_url = preset.base_url;
+    let preset_default_model = preset.default_model;
+    let preset_usd_per_minute = preset.usd_per_minute;
+    save_btn.connect_clicked(move |_| {
+        let key_text = key_entry.text().to_string();
+        if key_text.is_empty() {
+            return;
+        }
+
+        // Persist key to DB
+        if let Ok(d) = db_save.lock() {
+            let _ = d.set_setting(&format!("api_key_{}", preset_id), &key_text);
+        }
+
+        let static_preset = config::ApiPreset {
+            id: preset_id,
+            label: preset_label,
+            base_url: preset_base_url,
+            default_model: preset_default_model,
+            needs_key: true,
+            usd_per_minute: preset_usd_per_minute,
+        };
+
+        apply_preset(
+            &runtime_save,
+            &config_save,
+            &db_save,
+            &action_save,
+            &status_save,
+            &static_preset,
+            Some(key_text),
+        );
+
+        dialog_save.close();
+    });
+
+    dialog.present();
+}
+
+fn show_custom_api_dialog(
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    config: &Arc<Config>,
+    import: Option<crate::provider_file::ProviderFile>,
+) {
+    let previous_provider = runtime.borrow().active_provider.clone();
+
+    let title = match import {
+        Some(ref f) => format!("Import Provider: {}", f.name),
+        None => "Custom API Configuration".to_string(),
+    };
+    let dialog = gtk4::Window::builder()
+        .title(title)
+        .default_width(400)
+        .default_height(260)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let grid = gtk4::Grid::builder()
+        .row_spacing(8)
+        .column_spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+
+    // Base URL
+    let url_label = gtk4::Label::new(Some("Base URL"));
+    url_label.set_halign(gtk4::Align::End);
+    let url_entry = gtk4::Entry::new();
+    url_entry.set_hexpand(true);
+    url_entry.set_placeholder_text(Some("https://api.example.com/v1"));
+    grid.attach(&url_label, 0, 0, 1, 1);
+    grid.attach(&url_entry, 1, 0, 2, 1);
+
+    // API Key
+    let key_label = gtk4::Label::new(Some("API Key"));
+    key_label.set_halign(gtk4::Align::End);
+    let key_entry = gtk4::Entry::new();
+    key_entry.set_hexpand(true);
+    key_entry.set_placeholder_text(Some("(optional)"));
+    key_entry.set_input_purpose(gtk4::InputPurpose::Password);
+    key_entry.set_visibility(false);
+    grid.attach(&key_label, 0, 1, 1, 1);
+    grid.attach(&key_entry, 1, 1, 2, 1);
+
+    // Model
+    let model_label = gtk4::Label::new(Some("Model"));
+    model_label.set_halign(gtk4::Align::End);
+    let model_entry = gtk4::Entry::new();
+    model_entry.set_hexpand(true);
+    model_entry.set_placeholder_text(Some("whisper-1"));
+    grid.attach(&model_label, 0, 2, 1, 1);
+    grid.attach(&model_entry, 1, 2, 2, 1);
+
+    // Endpoint path (whisper.cpp server uses /inference, most others the default)
+    let path_label = gtk4::Label::new(Some("Path"));
+    path_label.set_halign(gtk4::Align::End);
+    let path_entry = gtk4::Entry::new();
+    path_entry.set_hexpand(true);
+    path_entry.set_placeholder_text(Some(config::DEFAULT_TRANSCRIPTION_PATH));
+    grid.attach(&path_label, 0, 3, 1, 1);
+    grid.attach(&path_entry, 1, 3, 2, 1);
+
+    // Pre-populate from an imported provider file (no key), or from DB
+    if let Some(ref f) = import {
+        url_entry.set_text(&f.base_url);
+        model_entry.set_text(&f.model);
+        path_entry.set_text(f.path.as_deref().unwrap_or_default());
+        key_entry.grab_focus();
+    } else if let Ok(d) = db.lock() {
+        if let Ok(Some(url)) = d.get_setting("api_custom_url") {
+            url_entry.set_text(&url);
+        }
+        if let Ok(Some(key)) = d.get_setting("api_custom_key") {
+            key_entry.set_text(&key);
+        }
+        if let Ok(Some(model)) = d.get_setting("api_custom_model") {
+            model_entry.set_text(&model);
+        }
+        if let Ok(Some(path)) = d.get_setting("api_custom_path") {
+            path_entry.set_text(&path);
+        }
+    }
+
+    // Buttons
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let export_btn = gtk4::Button::with_label("Export...");
+    export_btn.set_tooltip_text(Some("Save this endpoint (without the key) to share it"));
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    btn_box.append(&export_btn);
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    grid.attach(&btn_box, 0, 4, 3, 1);
+
+    dialog.set_child(Some(&grid));
+
+    // Cancel → revert radio to previous provider
+    let action_cancel = action.clone();
+    let prev = previous_provider.clone();
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        action_cancel.set_state(&prev.to_variant());
+        dialog_cancel.close();
+    });
+
+    // Export → shareable provider file, never including the key
+    let (url_export, model_export, path_export) =
+        (url_entry.clone(), model_entry.clone(), path_entry.clone());
+    let dialog_export = dialog.clone();
+    let import_name = import.as_ref().map(|f| f.name.clone());
+    export_btn.connect_clicked(move |_| {
+        let url = url_export.text().trim().to_string();
+        let model = model_export.text().trim().to_string();
+        if url.is_empty() || model.is_empty() {
+            return;
+        }
+        let path = path_export.text().trim().to_string();
+        let file = crate::provider_file::ProviderFile {
+            version: crate::provider_file::VERSION,
+            name: import_name
+                .clone()
+                .unwrap_or_else(|| crate::policy::url_host(&url).to_string()),
+            base_url: url,
+            model,
+            path: (!path.is_empty() && path != config::DEFAULT_TRANSCRIPTION_PATH).then_some(path),
+        };
+        export_provider_file(&dialog_export, file);
+    });
+
+    // Save → persist + switch
+    let runtime_save = Rc::clone(runtime);
+    let db_save = Arc::clone(db);
+    let config_save = Arc::clone(config);
+    let action_save = action.clone();
+    let status_save = status.clone();
+    let dialog_save = dialog.clone();
+    save_btn.connect_clicked(move |_| {
+        let url = url_entry.text().to_string();
+        let key_text = key_entry.text().to_string();
+        let model = model_entry.text().to_string();
+        let path = match path_entry.text().trim() {
+            "" => config::DEFAULT_TRANSCRIPTION_PATH.to_string(),
+            p => p.to_string(),
+        };
+
+        if url.is_empty() || model.is_empty() {
+            return; // require at least URL and model
+        }
+
+        if !config_save.policy.allows("custom", Some(&url)) {
+            show_status(&status_save, "Blocked by policy");
+            return;
+        }
+
+        let api_key = if key_text.is_empty() {
+            None
+        } else {
+            Some(key_text.clone())
+        };
+
+        // Persist to DB
+        if let Ok(d) = db_save.lock() {
+            let _ = d.set_setting("api_custom_url", &url);
+            if let Some(ref k) = api_key {
+                let _ = d.set_setting("api_custom_key", k);
+            }
+            let _ = d.set_setting("api_custom_model", &model);
+            let _ = d.set_setting("api_custom_path", &path);
+            let _ = d.set_setting("transcription_mode", "custom");
+        }
+
+        // Update RuntimeState
+        {
+            let mut rt = runtime_save.borrow_mut();
+            rt.active_service = TranscriptionService::Api;
+            rt.active_provider = "custom".to_string();
+            rt.api_base_url = url;
+            rt.api_key = api_key;
+            rt.api_model = model;
+            rt.api_path = path;
+            rt.local_engine = None;
+        }
+
+        action_save.set_state(&"custom".to_variant());
+
+        show_status(&status_save, "Custom API mode");
+        let st = status_save.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+
+        dialog_save.close();
+    });
+
+    dialog.present();
+}
+
+/// Ask where to save a provider file and write it there.
+fn export_provider_file(parent: &gtk4::Window, file: crate::provider_file::ProviderFile) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Export Provider"),
+        Some(parent),
+        gtk4::FileChooserAction::Save,
+        Some("Export"),
+        Some("Cancel"),
+    );
+    chooser.set_current_name(&file.file_name());
+
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let Some(path) = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path())
+        else {
+            return;
+        };
+        if let Err(e) = std::fs::write(&path, file.to_json()) {
+            tracing::warn!("Provider export failed: {e}");
+        }
+    });
+    chooser.show();
+}
+
+fn switch_to_local(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    id: &str,
+) {
+    let model_path =
+        match config::locate_model(&config.models_dir, &config.local_models_dir, id, None) {
+            Some(config::ModelLocation::Found(path) | config::ModelLocation::Missing(path)) => path,
+            Some(config::ModelLocation::Unavailable { dir, local_path }) => {
+                action.set_state(&runtime.borrow().active_provider.to_variant());
+                show_models_unavailable_dialog(
+                    runtime, config, db, action, status, id, &dir, local_path,
+                );
+                return;
+            }
+            None => return,
+        };
+    activate_local_model(runtime, config, db, action, status, id, model_path);
+}
+
+/// Select local model `id` stored at `model_path`, downloading it first if needed.
+fn activate_local_model(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    id: &str,
+    model_path: std::path::PathBuf,
+) {
+    // Whisper models are single files; Vosk models are archives unpacked to a directory
+    let (url, archive) = if let Some(m) = config::find_local_model(id) {
+        (config::preset_model_url(&config.model_base_url, m), false)
+    } else if let Some(m) = config::find_vosk_model(id) {
+        (config::vosk_model_url(m), true)
+    } else {
+        return;
+    };
+
+    // Local-only mode: a missing model can't be downloaded, keep the current provider
+    if !model_path.exists() && crate::policy::ensure_network_allowed(&url).is_err() {
+        action.set_state(&runtime.borrow().active_provider.to_variant());
+        show_status(status, "Local only: model download blocked");
+        let st = status.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+            hide_status(&st);
+        });
+        return;
+    }
+
+    // Restored if the download is cancelled or fails
+    let previous = {
+        let rt = runtime.borrow();
+        PreviousProvider {
+            service: rt.active_service,
+            provider: rt.active_provider.clone(),
+            engine: rt.local_engine.clone(),
+        }
+    };
+
+    // Set active service immediately so the menu reflects the choice
+    {
+        let mut rt = runtime.borrow_mut();
+        rt.active_service = TranscriptionService::Local;
+        rt.active_provider = id.to_string();
+        rt.local_engine = None;
+    }
+    action.set_state(&id.to_variant());
+
+    // Persist to DB
+    if let Ok(d) = db.lock() {
+        let _ = d.set_setting("transcription_mode", id);
+    }
+
+    if model_path.exists() {
+        load_local_model(runtime, &model_path, action, status);
+    } else {
+        download_and_load_model(
+            runtime,
+            db,
+            &model_path,
+            &url,
+            archive,
+            action,
+            status,
+            previous,
+        );
+    }
+}
+
+/// Offered when the models directory (e.g. an external drive) can't be reached:
+/// retry once it is mounted, download a copy to this computer, or use an API.
+#[allow(clippy::too_many_arguments)]
+fn show_models_unavailable_dialog(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    id: &str,
+    dir: &std::path::Path,
+    local_path: Option<std::path::PathBuf>,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Models Not Available")
+        .default_width(420)
+        .modal(true)
+        .build();
+    if let Some(parent) = status.root().and_downcast::<gtk4::Window>() {
+        dialog.set_transient_for(Some(&parent));
+    }
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let label = gtk4::Label::new(Some(&format!(
+        "The models folder {} can't be reached. If it is on a removable or \
+         network drive, connect the drive and try again.",
+        dir.display()
+    )));
+    label.set_wrap(true);
+    label.set_halign(gtk4::Align::Start);
+    vbox.append(&label);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let api_btn = gtk4::Button::with_label("Use API");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&api_btn);
+    vbox.append(&btn_box);
+    dialog.set_child(Some(&vbox));
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_api = dialog.clone();
+    api_btn.connect_clicked(move |_| {
+        dialog_api.close();
+        if let Some(app) = gtk4::gio::Application::default() {
+            app.activate_action(
+                "transcription-mode",
+                Some(&config::API_PRESETS[0].id.to_variant()),
+            );
+        }
+    });
+
+    // Custom model files can't be re-downloaded
+    if let Some(local_path) = local_path {
+        let retry_btn = gtk4::Button::with_label("Try Again");
+        let download_btn = gtk4::Button::with_label("Download to This Computer");
+        download_btn.add_css_class("suggested-action");
+        btn_box.append(&retry_btn);
+        btn_box.append(&download_btn);
+
+        let dialog_retry = dialog.clone();
+        let (runtime_r, config_r, db_r) = (Rc::clone(runtime), Arc::clone(config), Arc::clone(db));
+        let (action_r, status_r) = (action.clone(), status.clone());
+        let id_r = id.to_string();
+        retry_btn.connect_clicked(move |_| {
+            dialog_retry.close();
+            switch_to_local(&runtime_r, &config_r, &db_r, &action_r, &status_r, &id_r);
+        });
+
+        let dialog_dl = dialog.clone();
+        let (runtime_d, config_d, db_d) = (Rc::clone(runtime), Arc::clone(config), Arc::clone(db));
+        let (action_d, status_d) = (action.clone(), status.clone());
+        let id_d = id.to_string();
+        download_btn.connect_clicked(move |_| {
+            dialog_dl.close();
+            activate_local_model(
+                &runtime_d,
+                &config_d,
+                &db_d,
+                &action_d,
+                &status_d,
+                &id_d,
+                local_path.clone(),
+            );
+        });
+    } else {
+        api_btn.add_css_class("suggested-action");
+    }
+
+    dialog.present();
+}
+
+/// Let the user pick their own ggml/gguf model file (e.g. a fine-tune) and
+/// load it directly from where it lives, outside the managed models dir.
+fn show_custom_model_dialog(
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+) {
+    let previous_provider = runtime.borrow().active_provider.clone();
+
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Choose Whisper Model"),
+        Some(parent),
+        gtk4::FileChooserAction::Open,
+        Some("Load"),
+        Some("Cancel"),
+    );
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Whisper models (*.bin, *.gguf)"));
+    filter.add_pattern("*.bin");
+    filter.add_pattern("*.gguf");
+    chooser.add_filter(&filter);
+    if let Some(dir) = runtime
+        .borrow()
+        .custom_model
+        .as_ref()
+        .and_then(|p| p.parent())
+    {
+        let _ = chooser.set_current_folder(Some(&gtk4::gio::File::for_path(dir)));
+    }
+
+    let runtime_c = Rc::clone(runtime);
+    let db_c = Arc::clone(db);
+    let action_c = action.clone();
+    let st = status.clone();
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let path = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path());
+        let Some(path) = path else {
+            action_c.set_state(&previous_provider.to_variant());
+            return;
+        };
+        if let Err(e) = config::validate_custom_model(&path) {
+            tracing::warn!("Custom model rejected: {e}");
+            action_c.set_state(&previous_provider.to_variant());
+            show_status(&st, "Not a whisper model file");
+            let st2 = st.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                hide_status(&st2);
+            });
+            return;
+        }
+
+        {
+            let mut rt = runtime_c.borrow_mut();
+            rt.active_service = TranscriptionService::Local;
+            rt.active_provider = config::CUSTOM_LOCAL_MODEL.to_string();
+            rt.local_engine = None;
+            rt.custom_model = Some(path.clone());
+        }
+        action_c.set_state(&config::CUSTOM_LOCAL_MODEL.to_variant());
+
+        if let Ok(d) = db_c.lock() {
+            let _ = d.set_setting("local_custom_model", &path.to_string_lossy());
+            let _ = d.set_setting("transcription_mode", config::CUSTOM_LOCAL_MODEL);
+        }
+
+        load_local_model(&runtime_c, &path, &action_c, &st);
+    });
+    chooser.show();
+}
+
+fn load_local_model(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    model_path: &std::path::Path,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+) {
+    show_status(status, "Loading model...");
+
+    let model_path = model_path.to_path_buf();
+    let (tx, rx) = std::sync::mpsc::channel::<Result<Arc<LocalEngine>, String>>();
+
+    std::thread::spawn(move || {
+        let result = LocalEngine::load(&model_path).map(Arc::new);
+        let _ = tx.send(result);
+    });
+
+    let runtime_c = Rc::clone(runtime);
+    let action_c = action.clone();
+    let st = status.clone();
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        match rx.try_recv() {
+            Ok(Ok(engine)) => {
+                runtime_c.borrow_mut().local_engine = Some(engine);
+                show_status(&st, "Local mode ready");
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                tracing::error!("Failed to load local model: {e}");
+                // Revert to default API provider
+                {
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.active_service = TranscriptionService::Api;
+                    rt.active_provider = "groq".to_string();
+                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
+                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+                    rt.api_path = config::DEFAULT_TRANSCRIPTION_PATH.to_string();
+                }
+                action_c.set_state(&"groq".to_variant());
+                show_status(&st, "Model load failed");
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+            Err(_) => {
+                {
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.active_service = TranscriptionService::Api;
+                    rt.active_provider = "groq".to_string();
+                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
+                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+                    rt.api_path = config::DEFAULT_TRANSCRIPTION_PATH.to_string();
+                }
+                action_c.set_state(&"groq".to_variant());
+                show_status(&st, "Model load failed");
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+        }
+    });
+}
+
+/// Download progress messages sent from the background thread
+enum DownloadMsg {
+    Progress(u64, Option<u64>), // downloaded, total
+    StepLabel(String),          // update the label text
+    Done,
+    Cancelled,
+    Error(String),
+}
+
+/// Provider selection restored when a model download is cancelled or fails.
+struct PreviousProvider {
+    service: TranscriptionService,
+    provider: String,
+    engine: Option<Arc<LocalEngine>>,
+}
+
+/// Enable the "Cancel Download" menu entry only while a download runs.
+fn set_cancel_download_enabled(enabled: bool) {
+    if let Some(app) = gtk4::gio::Application::default()
+        && let Some(action) = app
+            .lookup_action("cancel-download")
+            .and_downcast::<gtk4::gio::SimpleAction>()
+    {
+        action.set_enabled(enabled);
+    }
+}
+
+/// Download a model into its `.part` file, resuming from whatever an earlier
+/// attempt left there, then load it. `archive` models (Vosk) are unpacked
+/// into the `model_path` directory. The download can be aborted through
+/// `RuntimeState::download_cancel`; the partial file is kept for next time.
+#[allow(clippy::too_many_arguments)]
+fn download_and_load_model(
+    runtime: &Rc<RefCell<RuntimeState>>,
+    db: &Arc<Mutex<Db>>,
+    model_path: &std::path::Path,
+    url: &str,
+    archive: bool,
+    action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+    previous: PreviousProvider,
+) {
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    {
+        let mut rt = runtime.borrow_mut();
+        rt.downloading = true;
+        rt.download_cancel = Arc::clone(&cancel);
+    }
+    set_cancel_download_enabled(true);
+
+    show_status(status, "Downloading model...");
+
+    let url = url.to_string();
+    let model_path = model_path.to_path_buf();
+    let loaded_model_path = model_path.clone();
+    let part_path = config::model_part_path(&model_path);
+
+    let (tx, rx) = std::sync::mpsc::channel::<DownloadMsg>();
+
+    std::thread::spawn(move || {
+        // Ok(false) when cancelled
+        let result = (|| -> Result<bool, String> {
+            crate::policy::ensure_network_allowed(&url)?;
+
+            if let Some(dir) = part_path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+
+            // Resume from an earlier partial download if the server allows it
+            let offset = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+            let mut req = reqwest::blocking::Client::new().get(&url);
+            // Gated repos need the user's Hugging Face token
+            if config::is_huggingface_url(&url)
+                && let Some(token) = crate::secrets::hf_token()
+            {
+                req = req.bearer_auth(token);
+            }
+            if offset > 0 {
+                dbg_log!("[DOWNLOAD] resuming at {offset} bytes");
+                req = req.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+            }
+            let resp = req
+                .send()
+                .map_err(|e| format!("Download request failed: {e}"))?;
+
+            if resp.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                // The partial file is unusable; start over next time
+                let _ = std::fs::remove_file(&part_path);
+            }
+            if matches!(
+                resp.status(),
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+            ) {
+                return Err(format!(
+                    "Download failed: HTTP {} (gated repo? set a Hugging Face token)",
+                    resp.status()
+                ));
+            }
+            if !resp.status().is_success() {
+                return Err(format!("Download failed: HTTP {}", resp.status()));
+            }
+
+            let resumed = offset > 0
+                && resp.status() == reqwest::StatusCode::PARTIAL_CONTENT
+                && resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(config::content_range_start)
+                    == Some(offset);
+            let start = if resumed { offset } else { 0 };
+            let total = resp.content_length().map(|len| len + start);
+            let mut downloaded: u64 = start;
+
+            let mut file = {
+                let mut options = std::fs::OpenOptions::new();
+                options.create(true);
+                if resumed {
+                    options.append(true);
+                } else {
+                    options.write(true).truncate(true);
+                }
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::OpenOptionsExt;
+                    options.mode(0o600);
+                }
+                options
+                    .open(&part_path)
+                    .map_err(|e| format!("Failed to create file: {e}"))?
+            };
+
+            use std::io::{Read, Write};
+            let mut reader = resp;
+            let mut buf = [0u8; 65536];
+            loop {
+                if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Ok(false);
+                }
+                let n = reader
+                    .read(&mut buf)
+                    .map_err(|e| format!("Download read error: {e}"))?;
+                if n == 0 {
+                    break;
+                }
+                file.write_all(&buf[..n])
+                    .map_err(|e| format!("File write error: {e}"))?;
+                downloaded += n as u64;
+                let _ = tx.send(DownloadMsg::Progress(downloaded, total));
+            }
+
+            if archive {
+                // Unpack archive → model directory; a broken archive restarts next time
+                let installed = crate::vosk_stt::install_archive(&part_path, &model_path);
+                let _ = std::fs::remove_file(&part_path);
+                installed?;
+            } else {
+                // Rename .part → final path
+                std::fs::rename(&part_path, &model_path)
+                    .map_err(|e| format!("Failed to rename model file: {e}"))?;
+            }
+
+            Ok(true)
+        })();
+
+        // Partial files are kept so the next attempt can resume
+        let _ = tx.send(match result {
+            Ok(true) => DownloadMsg::Done,
+            Ok(false) => DownloadMsg::Cancelled,
+            Err(e) => DownloadMsg::Error(e),
+        });
+    });
+
+    let runtime_c = Rc::clone(runtime);
+    let db_c = Arc::clone(db);
+    let action_c = action.clone();
+    let st = status.clone();
+    let mut previous = Some(previous);
+    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
+        // Drain all pending messages, keep the last one
+        let mut last_msg = None;
+        while let Ok(msg) = rx.try_recv() {
+            last_msg = Some(msg);
+        }
+
+        match last_msg {
+            Some(DownloadMsg::Progress(downloaded, total)) => {
+                let dl_mb = downloaded as f64 / (1024.0 * 1024.0);
+                if let Some(t) = total {
+                    let total_mb = t as f64 / (1024.0 * 1024.0);
+                    show_status(&st, &format!("Downloading: {dl_mb:.0} / {total_mb:.0} MB"));
+                } else {
+                    show_status(&st, &format!("Downloading: {dl_mb:.0} MB"));
+                }
+                glib::ControlFlow::Continue
+            }
+            Some(DownloadMsg::Done) => {
+                runtime_c.borrow_mut().downloading = false;
+                set_cancel_download_enabled(false);
+                show_status(&st, "Loading model...");
+                // Now load the model
+                load_local_model(&runtime_c, &loaded_model_path, &action_c, &st);
+                glib::ControlFlow::Break
+            }
+            Some(DownloadMsg::StepLabel(_)) => glib::ControlFlow::Continue,
+            Some(msg @ (DownloadMsg::Cancelled | DownloadMsg::Error(_))) => {
+                let label = match msg {
+                    DownloadMsg::Error(e) => {
+                        tracing::warn!("Model download failed: {e}");
+                        "Download failed"
+                    }
+                    _ => "Download cancelled",
+                };
+                // Back to whatever was selected before the download
+                if let Some(prev) = previous.take() {
+                    if let Ok(d) = db_c.lock() {
+                        let _ = d.set_setting("transcription_mode", &prev.provider);
+                    }
+                    action_c.set_state(&prev.provider.to_variant());
+                    let mut rt = runtime_c.borrow_mut();
+                    rt.downloading = false;
+                    rt.active_service = prev.service;
+                    rt.active_provider = prev.provider;
+                    rt.local_engine = prev.engine;
+                }
+                set_cancel_download_enabled(false);
+                show_status(&st, label);
+                let st2 = st.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
+                    hide_status(&st2);
+                });
+                glib::ControlFlow::Break
+            }
+            None => glib::ControlFlow::Continue,
+        }
+    });
+}
+
+/// Remember where the window is as its nearest corner and the distance from
+/// it (X11 only; on Wayland a client can't learn its position, so the last
+/// one saved is kept).
+fn save_window_position(win: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    #[cfg(not(target_os = "linux"))]
+    let _ = (&win, &db);
+
+    #[cfg(target_os = "linux")]
+    {
+        if crate::stacking::on_wayland() {
+            return;
+        }
+        let title = win.title().map(|t| t.to_string()).unwrap_or_default();
+        let geometry = match crate::x11::window_geometry(&title) {
+            Ok(geometry) => geometry,
+            Err(e) => {
+                dbg_log!("[POSITION] not saved: {e}");
+                return;
+            }
+        };
+        let (x, y, w, h) = geometry;
+        let Some((index, monitor)) = monitors().into_iter().enumerate().find(|(_, m)| {
+            let (mx, my, mw, mh) = monitor_rect(m);
+            (mx..mx + mw).contains(&(x + w / 2)) && (my..my + mh).contains(&(y + h / 2))
+        }) else {
+            return;
+        };
+        let placement = crate::stacking::Placement::from_geometry(geometry, monitor_rect(&monitor));
+        if let Ok(db) = db.lock() {
+            let _ = db.set_setting(
+                crate::stacking::POSITION_CORNER_SETTING,
+                placement.corner.as_str(),
+            );
+            let _ = db.set_setting(
+                crate::stacking::POSITION_OFFSET_SETTING,
+                &placement.offset_str(),
+            );
+            let _ = db.set_setting(
+                crate::stacking::POSITION_MONITOR_SETTING,
+                &crate::stacking::monitor_id(index, monitor.connector().as_deref()),
+            );
+            // Superseded by the corner and offset
+            let _ = db.delete_setting("window_x");
+            let _ = db.delete_setting("window_y");
+        }
+    }
+}
+
+/// The connected monitors, in the display's order.
+fn monitors() -> Vec<gdk::Monitor> {
+    let Some(display) = gdk::Display::default() else {
+        return Vec::new();
+    };
+    let list = display.monitors();
+    (0..list.n_items())
+        .filter_map(|i| list.item(i).and_downcast::<gdk::Monitor>())
+        .collect()
+}
+
+/// The connected monitor remembered as `id` (see
+/// [`crate::stacking::monitor_id`]).
+fn monitor_by_id(id: &str) -> Option<gdk::Monitor> {
+    monitors()
+        .into_iter()
+        .enumerate()
+        .find(|(i, m)| crate::stacking::monitor_id(*i, m.connector().as_deref()) == id)
+        .map(|(_, m)| m)
+}
+
+/// Fill the "Move to Monitor" submenu with the connected monitors.
+fn fill_monitor_menu(menu: &gtk4::gio::Menu) {
+    menu.remove_all();
+    for (i, monitor) in monitors().iter().enumerate() {
+        let connector = monitor.connector();
+        let model = monitor.model();
+        menu.append(
+            Some(&crate::stacking::monitor_label(
+                i,
+                connector.as_deref(),
+                model.as_deref(),
+            )),
+            Some(&format!(
+                "app.window-monitor::{}",
+                crate::stacking::monitor_id(i, connector.as_deref())
+            )),
+        );
+    }
+}
+
+/// Geometry of `monitor` in device pixels, as X11 reports window positions.
+#[cfg(target_os = "linux")]
+fn monitor_rect(monitor: &gdk::Monitor) -> crate::stacking::Rect {
+    let geom = monitor.geometry();
+    let scale = monitor.scale_factor();
+    (
+        geom.x() * scale,
+        geom.y() * scale,
+        geom.width() * scale,
+        geom.height() * scale,
+    )
+}
+
+/// Small pulsing dots mirroring the recording state on every monitor other
+/// than the one showing the main window. Dropping them closes the dots.
+struct RecordingDots {
+    windows: Vec<gtk4::Window>,
+}
+
+/// Size of a recording dot window and its distance from the monitor corner.
+const RECORDING_DOT_SIZE: i32 = 26;
+const RECORDING_DOT_MARGIN: i32 = 24;
+
+impl RecordingDots {
+    fn show(app: &gtk4::Application, main: &gtk4::ApplicationWindow) -> Self {
+        let Some(display) = gdk::Display::default() else {
+            return Self {
+                windows: Vec::new(),
+            };
+        };
+        // Without a visible main window every monitor gets a dot
+        let main_monitor = main
+            .is_visible()
+            .then(|| main.surface())
+            .flatten()
+            .and_then(|surface| display.monitor_at_surface(&surface));
+        let monitors = display.monitors();
+        let mut windows = Vec::new();
+        for i in 0..monitors.n_items() {
+            let Some(monitor) = monitors.item(i).and_downcast::<gdk::Monitor>() else {
+                continue;
+            };
+            if main_monitor.as_ref() == Some(&monitor) {
+                continue;
+            }
+            // A unique title lets xdotool find and move each dot
+            let title = format!("WhisperCrabs Recording {i}");
+            let mark = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+            mark.add_css_class("recording-dot-mark");
+            mark.set_halign(gtk4::Align::Center);
+            mark.set_valign(gtk4::Align::Center);
+            let dot = gtk4::Window::builder()
+                .application(app)
+                .title(title.as_str())
+                .default_width(RECORDING_DOT_SIZE)
+                .default_height(RECORDING_DOT_SIZE)
+                .decorated(false)
+                .resizable(false)
+                .focusable(false)
+                .css_classes(vec!["recording-dot"])
+                .child(&mark)
+                .build();
+            dot.set_can_target(false);
+            dot.present();
+
+            // Top-right corner of the monitor (X11; Wayland compositors place windows themselves)
+            let geom = monitor.geometry();
+            let (x, y) = (
+                geom.x() + geom.width() - RECORDING_DOT_SIZE - RECORDING_DOT_MARGIN,
+                geom.y() + RECORDING_DOT_MARGIN,
+            );
+            #[cfg(target_os = "linux")]
+            glib::timeout_add_local_once(std::time::Duration::from_millis(200), move || {
+                let _ = std::process::Command::new("xdotool")
+                    .args([
+                        "search",
+                        "--name",
+                        &title,
+                        "windowmove",
+                        &x.to_string(),
+                        &y.to_string(),
+                    ])
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status();
+            });
+            #[cfg(not(target_os = "linux"))]
+            let _ = (x, y);
+            windows.push(dot);
+        }
+        dbg_log!("[DOTS] showing {} recording dots", windows.len());
+        Self { windows }
+    }
+}
+
+impl Drop for RecordingDots {
+    fn drop(&mut self) {
+        for dot in self.windows.drain(..) {
+            dot.destroy();
+        }
+    }
+}
+
+/// Move the window to its saved placement, or near the bottom-right corner
+/// of the first monitor. Compositors place windows themselves on Wayland.
+fn position_window(window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    #[cfg(not(target_os = "linux"))]
+    let _ = (&window, &db);
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::stacking::{
+            POSITION_CORNER_SETTING, POSITION_MONITOR_SETTING, POSITION_OFFSET_SETTING, Placement,
+        };
+
+        if crate::stacking::on_wayland() {
+            return;
+        }
+        let title = window.title().map(|t| t.to_string()).unwrap_or_default();
+        let (saved, monitor, legacy) = db
+            .lock()
+            .map(|db| {
+                let get = |key| db.get_setting(key).ok().flatten();
+                let saved = get(POSITION_CORNER_SETTING)
+                    .zip(get(POSITION_OFFSET_SETTING))
+                    .and_then(|(corner, offset)| Placement::parse(&corner, &offset));
+                // Absolute position saved by older versions
+                let legacy = get("window_x")
+                    .and_then(|x| x.parse::<i32>().ok())
+                    .zip(get("window_y").and_then(|y| y.parse::<i32>().ok()));
+                (saved, get(POSITION_MONITOR_SETTING), legacy)
+            })
+            .unwrap_or_default();
+
+        let position = match (saved, legacy) {
+            (None, Some(pos)) => Some(pos),
+            _ => {
+                // The saved monitor, or the first one if it's gone
+                let monitor = monitor
+                    .as_deref()
+                    .and_then(monitor_by_id)
+                    .or_else(|| monitors().into_iter().next());
+                monitor.zip(crate::x11::window_geometry(&title).ok()).map(
+                    |(monitor, (_, _, w, h))| {
+                        saved
+                            .unwrap_or_default()
+                            .position((w, h), monitor_rect(&monitor))
+                    },
+                )
+            }
+        };
+        if let Some((x, y)) = position
+            && let Err(e) = crate::x11::move_window(&title, x, y)
+        {
+            dbg_log!("[POSITION] not restored: {e}");
+        }
+    }
+}
+
+/// Ask for the History PIN/passphrase; `on_unlock` runs once it matches.
+fn show_unlock_dialog<F: Fn() + 'static>(
+    parent: &gtk4::ApplicationWindow,
+    hash: String,
+    on_unlock: F,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("History Locked")
+        .default_width(320)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let label = gtk4::Label::new(Some("Enter your PIN or passphrase:"));
+    label.set_halign(gtk4::Align::Start);
+    vbox.append(&label);
+
+    let entry = gtk4::PasswordEntry::new();
+    entry.set_hexpand(true);
+    vbox.append(&entry);
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let unlock_btn = gtk4::Button::with_label("Unlock");
+    unlock_btn.add_css_class("suggested-action");
+    btn_box.append(&cancel_btn);
+    btn_box.append(&unlock_btn);
+    vbox.append(&btn_box);
+    dialog.set_child(Some(&vbox));
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let dialog_unlock = dialog.clone();
+    let entry_unlock = entry.clone();
+    let try_unlock = Rc::new(move |btn: &gtk4::Button| {
+        if !btn.is_sensitive() {
+            return;
+        }
+        if crate::lock::verify_secret(&entry_unlock.text(), &hash) {
+            dialog_unlock.close();
+            on_unlock();
+            return;
+        }
+        entry_unlock.set_text("");
+        error.set_label("Wrong PIN or passphrase");
+        error.set_visible(true);
+        // Slow down guessing
+        btn.set_sensitive(false);
+        let btn = btn.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(1), move || {
+            btn.set_sensitive(true);
+        });
+    });
+    let try_click = Rc::clone(&try_unlock);
+    unlock_btn.connect_clicked(move |b| try_click(b));
+    let unlock_enter = unlock_btn.clone();
+    entry.connect_activate(move |_| try_unlock(&unlock_enter));
+
+    dialog.present();
+}
+
+/// Encrypt History with a key kept in the keyring or a passphrase asked at
+/// startup, switch between them, or go back to a plain database.
+fn show_history_encryption_dialog(parent: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let encrypted = db.lock().is_ok_and(|d| d.is_encrypted());
+    let keyring_key = crate::secrets::get(crate::secrets::HISTORY_KEY).is_some();
+
+    let dialog = gtk4::Window::builder()
+        .title("Encrypt History")
+        .default_width(400)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let info = gtk4::Label::new(Some(match (encrypted, keyring_key) {
+        (false, _) => "History is stored unencrypted. Encrypt it with:",
+        (true, true) => "History is encrypted with a key in the system keyring. Switch to:",
+        (true, false) => "History is encrypted with a passphrase. Switch to:",
+    }));
+    info.set_halign(gtk4::Align::Start);
+    info.set_wrap(true);
+    vbox.append(&info);
+
+    let keyring_choice =
+        gtk4::CheckButton::with_label("A key in the system keyring (unlocks automatically)");
+    let passphrase_choice =
+        gtk4::CheckButton::with_label("A passphrase (asked for at every start)");
+    passphrase_choice.set_group(Some(&keyring_choice));
+    keyring_choice.set_active(true);
+    vbox.append(&keyring_choice);
+    vbox.append(&passphrase_choice);
+
+    let passphrase = gtk4::PasswordEntry::new();
+    passphrase.set_placeholder_text(Some("Passphrase"));
+    let confirm = gtk4::PasswordEntry::new();
+    confirm.set_placeholder_text(Some("Confirm passphrase"));
+    for entry in [&passphrase, &confirm] {
+        entry.set_sensitive(false);
+        vbox.append(entry);
+    }
+    let (passphrase_s, confirm_s) = (passphrase.clone(), confirm.clone());
+    passphrase_choice.connect_toggled(move |b| {
+        passphrase_s.set_sensitive(b.is_active());
+        confirm_s.set_sensitive(b.is_active());
+    });
+
+    let note = gtk4::Label::new(Some(
+        "A forgotten passphrase can't be recovered; History would have to be started over.",
+    ));
+    note.set_wrap(true);
+    note.set_opacity(0.6);
+    note.set_halign(gtk4::Align::Start);
+    vbox.append(&note);
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.set_wrap(true);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let remove_btn = gtk4::Button::with_label("Remove Encryption");
+    remove_btn.add_css_class("destructive-action");
+    remove_btn.set_visible(encrypted);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label(if encrypted { "Change Key" } else { "Encrypt" });
+    save_btn.add_css_class("suggested-action");
+    btn_box.append(&remove_btn);
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    vbox.append(&btn_box);
+
+    if !crate::db::encryption_available() {
+        for widget in [
+            keyring_choice.upcast_ref::<gtk4::Widget>(),
+            passphrase_choice.upcast_ref(),
+            save_btn.upcast_ref(),
+            remove_btn.upcast_ref(),
+        ] {
+            widget.set_sensitive(false);
+        }
+        error.set_label("This build can't encrypt History (build with --features encryption).");
+        error.set_visible(true);
+    }
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_remove, dialog_remove, error_remove) = (Arc::clone(db), dialog.clone(), error.clone());
+    remove_btn.connect_clicked(move |_| {
+        let removed = db_remove
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut d| d.set_encryption(None));
+        match removed {
+            Ok(()) => {
+                let _ = crate::secrets::delete(crate::secrets::HISTORY_KEY);
+                dialog_remove.close();
+            }
+            Err(e) => {
+                error_remove.set_label(&e);
+                error_remove.set_visible(true);
+            }
+        }
+    });
+
+    let (db_save, dialog_save) = (Arc::clone(db), dialog.clone());
+    save_btn.connect_clicked(move |_| {
+        let saved = if keyring_choice.is_active() {
+            // The new key goes into the keyring first; the old one comes back on failure
+            let previous = crate::secrets::get(crate::secrets::HISTORY_KEY);
+            crate::db::generate_key()
+                .and_then(|key| crate::secrets::set(crate::secrets::HISTORY_KEY, &key).map(|_| key))
+                .and_then(|key| {
+                    let rekeyed = db_save
+                        .lock()
+                        .map_err(|e| e.to_string())
+                        .and_then(|mut d| d.set_encryption(Some(&key)));
+                    if rekeyed.is_err() {
+                        let _ = match &previous {
+                            Some(old) => crate::secrets::set(crate::secrets::HISTORY_KEY, old),
+                            None => crate::secrets::delete(crate::secrets::HISTORY_KEY),
+                        };
+                    }
+                    rekeyed
+                })
+        } else {
+            let secret = passphrase.text();
+            crate::db::validate_passphrase(&secret, &confirm.text()).and_then(|_| {
+                db_save
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut d| d.set_encryption(Some(&secret)))
+                    .map(|_| {
+                        let _ = crate::secrets::delete(crate::secrets::HISTORY_KEY);
+                    })
+            })
+        };
+        match saved {
+            Ok(()) => dialog_save.close(),
+            Err(e) => {
+                error.set_label(&e);
+                error.set_visible(true);
+            }
+        }
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Set, change or remove the PIN/passphrase that guards History.
+fn show_history_lock_dialog(parent: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let current_hash = db.lock().ok().and_then(|d| {
+        d.get_setting(crate::lock::HISTORY_LOCK_SETTING)
+            .ok()
+            .flatten()
+    });
+
+    let dialog = gtk4::Window::builder()
+        .title("Lock History")
+        .default_width(360)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let grid = gtk4::Grid::builder()
+        .row_spacing(8)
+        .column_spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+
+    let info = gtk4::Label::new(Some(if current_hash.is_some() {
+        "History is locked. Change or remove the PIN/passphrase:"
+    } else {
+        "Require a PIN or passphrase to open History:"
+    }));
+    info.set_halign(gtk4::Align::Start);
+    info.set_wrap(true);
+    grid.attach(&info, 0, 0, 2, 1);
+
+    let current_entry = gtk4::PasswordEntry::new();
+    current_entry.set_hexpand(true);
+    let new_entry = gtk4::PasswordEntry::new();
+    new_entry.set_hexpand(true);
+    let confirm_entry = gtk4::PasswordEntry::new();
+    confirm_entry.set_hexpand(true);
+
+    let mut row = 1;
+    if current_hash.is_some() {
+        let l = gtk4::Label::new(Some("Current:"));
+        l.set_halign(gtk4::Align::End);
+        grid.attach(&l, 0, row, 1, 1);
+        grid.attach(&current_entry, 1, row, 1, 1);
+        row += 1;
+    }
+    for (text, entry) in [("New:", &new_entry), ("Confirm:", &confirm_entry)] {
+        let l = gtk4::Label::new(Some(text));
+        l.set_halign(gtk4::Align::End);
+        grid.attach(&l, 0, row, 1, 1);
+        grid.attach(entry, 1, row, 1, 1);
+        row += 1;
+    }
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.add_css_class("error");
+    error.set_visible(false);
+    grid.attach(&error, 0, row, 2, 1);
+    row += 1;
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let remove_btn = gtk4::Button::with_label("Remove Lock");
+    remove_btn.add_css_class("destructive-action");
+    remove_btn.set_visible(current_hash.is_some());
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    btn_box.append(&remove_btn);
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    grid.attach(&btn_box, 0, row, 2, 1);
+    dialog.set_child(Some(&grid));
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    // The current secret is required before changing or removing the lock
+    let current_ok = {
+        let (hash, entry) = (current_hash.clone(), current_entry.clone());
+        Rc::new(move || {
+            hash.as_deref()
+                .is_none_or(|h| crate::lock::verify_secret(&entry.text(), h))
+        })
+    };
+
+    let (db_remove, dialog_remove, error_remove) = (Arc::clone(db), dialog.clone(), error.clone());
+    let current_ok_remove = Rc::clone(&current_ok);
+    remove_btn.connect_clicked(move |_| {
+        if !current_ok_remove() {
+            error_remove.set_label("Wrong current PIN or passphrase");
+            error_remove.set_visible(true);
+            return;
+        }
+        if let Ok(d) = db_remove.lock() {
+            let _ = d.delete_setting(crate::lock::HISTORY_LOCK_SETTING);
+        }
+        dialog_remove.close();
+    });
+
+    let (db_save, dialog_save) = (Arc::clone(db), dialog.clone());
+    save_btn.connect_clicked(move |_| {
+        if !current_ok() {
+            error.set_label("Wrong current PIN or passphrase");
+            error.set_visible(true);
+            return;
+        }
+        let result = crate::lock::validate_secret(&new_entry.text(), &confirm_entry.text())
+            .and_then(|()| crate::lock::hash_secret(&new_entry.text()));
+        match result {
+            Ok(hash) => {
+                if let Ok(d) = db_save.lock() {
+                    let _ = d.set_setting(crate::lock::HISTORY_LOCK_SETTING, &hash);
+                }
+                dialog_save.close();
+            }
+            Err(e) => {
+                error.set_label(&e);
+                error.set_visible(true);
+            }
+        }
+    });
+
+    dialog.present();
+}
+
+fn show_history_dialog(
+    _window: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    audio_dir: &std::path::Path,
+    retranscribe: &Retranscribe,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("WhisperCrabs History")
+        .default_width(420)
+        .default_height(420)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let header = gtk4::Label::new(Some("Recent Transcriptions"));
+    header.add_css_class("heading");
+    vbox.append(&header);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+
+    // Learned replacement suggestions, refreshed after each edit
+    let replacements_box = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+
+    let learn = gtk4::CheckButton::with_label("Learn corrections from edits");
+    learn.set_active(
+        db.lock()
+            .ok()
+            .and_then(|d| d.get_setting("learn_corrections").ok().flatten())
+            .is_none_or(|v| v != "false"),
+    );
+    let db_learn = Arc::clone(db);
+    learn.connect_toggled(move |b| {
+        if let Ok(d) = db_learn.lock() {
+            let _ = d.set_setting(
+                "learn_corrections",
+                if b.is_active() { "true" } else { "false" },
+            );
+        }
+    });
+
+    // Entries, a page at a time, with the next page loaded at the bottom
+    let list = Rc::new(HistoryList::new());
+    let factory = history_row_factory(
+        &list,
+        db,
+        audio_dir,
+        &learn,
+        &replacements_box,
+        retranscribe,
+    );
+    let list_view = gtk4::ListView::new(
+        Some(gtk4::NoSelection::new(Some(list.store.clone()))),
+        Some(factory),
+    );
+    list_view.set_show_separators(true);
+    let (list_more, db_more) = (Rc::clone(&list), Arc::clone(db));
+    scroll.connect_edge_reached(move |_, edge| {
+        if edge == gtk4::PositionType::Bottom {
+            load_history_page(&list_more, &db_more);
+        }
+    });
+
+    // Refill the list for the current language and tag filters
+    let refill: Rc<dyn Fn()> = {
+        let (list_f, db_f, retranscribe_f) =
+            (Rc::clone(&list), Arc::clone(db), retranscribe.clone());
+        Rc::new(move || fill_history_list(&list_f, &db_f, &retranscribe_f))
+    };
+
+    // Language and tag filters, offered once History has tagged entries
+    let (languages, tags) = db
+        .lock()
+        .ok()
+        .map(|d| {
+            (
+                d.languages().unwrap_or_default(),
+                d.all_tags().unwrap_or_default(),
+            )
+        })
+        .unwrap_or_default();
+    let filters = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    filters.set_halign(gtk4::Align::End);
+    if !languages.is_empty() {
+        let mut labels = vec!["All languages"];
+        labels.extend(languages.iter().map(|l| config::language_label(l)));
+        let dropdown = gtk4::DropDown::from_strings(&labels);
+        let (list_l, refill_l) = (Rc::clone(&list), Rc::clone(&refill));
+        dropdown.connect_selected_notify(move |dd| {
+            list_l.filter.borrow_mut().language = (dd.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| languages.get(i))
+                .cloned();
+            refill_l();
+        });
+        filters.append(&dropdown);
+    }
+    if !tags.is_empty() {
+        let mut labels = vec!["All tags".to_string()];
+        labels.extend(tags.iter().map(|t| format!("#{t}")));
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let dropdown = gtk4::DropDown::from_strings(&labels);
+        let (list_t, refill_t) = (Rc::clone(&list), Rc::clone(&refill));
+        dropdown.connect_selected_notify(move |dd| {
+            list_t.filter.borrow_mut().tag = (dd.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| tags.get(i))
+                .cloned();
+            refill_t();
+        });
+        filters.append(&dropdown);
+    }
+    if filters.first_child().is_some() {
+        vbox.append(&filters);
+    }
+    refill();
+
+    // Ctrl+1…9: copy the Nth listed entry, noted under the header
+    let copied = gtk4::Label::new(None);
+    copied.set_opacity(0.6);
+    copied.set_visible(false);
+    vbox.append(&copied);
+    let shortcuts = gtk4::ShortcutController::new();
+    for n in 1..=HISTORY_SHORTCUTS {
+        let (list_k, db_k, dialog_k, copied_k) = (
+            Rc::clone(&list),
+            Arc::clone(db),
+            dialog.clone(),
+            copied.clone(),
+        );
+        shortcuts.add_shortcut(gtk4::Shortcut::new(
+            gtk4::ShortcutTrigger::parse_string(&format!("<Control>{n}")),
+            Some(gtk4::CallbackAction::new(move |_, _| {
+                let Some(entry) = list_k
+                    .store
+                    .item(n - 1)
+                    .and_downcast::<glib::BoxedAnyObject>()
+                else {
+                    return glib::Propagation::Proceed;
+                };
+                let text = entry.borrow::<Transcription>().text.clone();
+                if copy_history_entry(Some(&dialog_k), &db_k, &text) {
+                    copied_k.set_label(&format!("Copied entry {n}"));
+                    copied_k.set_visible(true);
+                    let copied = copied_k.clone();
+                    glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                        copied.set_visible(false);
+                    });
+                }
+                glib::Propagation::Stop
+            })),
+        ));
+    }
+    dialog.add_controller(shortcuts);
+
+    // Pinned snippets lead the list
+    vbox.append(&list.pinned_box);
+    vbox.append(&list.empty);
+    scroll.set_child(Some(&list_view));
+    vbox.append(&scroll);
+
+    // Deleting: the checked rows, or everything after a confirmation
+    let delete_bar = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    delete_bar.set_halign(gtk4::Align::End);
+    let delete_selected_btn = gtk4::Button::with_label("Delete Selected");
+    let clear_btn = gtk4::Button::with_label("Clear All History\u{2026}");
+    clear_btn.add_css_class("destructive-action");
+    delete_bar.append(&delete_selected_btn);
+    delete_bar.append(&clear_btn);
+    vbox.append(&delete_bar);
+
+    let (db_sel, audio_sel, list_sel) = (Arc::clone(db), audio_dir.to_path_buf(), Rc::clone(&list));
+    delete_selected_btn.connect_clicked(move |_| {
+        let ids: Vec<i64> = list_sel.selected.borrow().iter().copied().collect();
+        if !ids.is_empty() {
+            delete_history_entries(&db_sel, &audio_sel, &list_sel, &ids);
+        }
+    });
+
+    let (db_clear, audio_clear, list_clear) =
+        (Arc::clone(db), audio_dir.to_path_buf(), Rc::clone(&list));
+    let dialog_clear = dialog.clone();
+    clear_btn.connect_clicked(move |_| {
+        let confirm = gtk4::MessageDialog::builder()
+            .transient_for(&dialog_clear)
+            .modal(true)
+            .message_type(gtk4::MessageType::Warning)
+            .buttons(gtk4::ButtonsType::None)
+            .text("Clear all history?")
+            .secondary_text(
+                "Every transcription, its word timings and its stored audio are deleted. \
+                 This can't be undone.",
+            )
+            .build();
+        confirm.add_button("Cancel", gtk4::ResponseType::Cancel);
+        confirm.add_button("Clear All", gtk4::ResponseType::Accept);
+        if let Some(btn) = confirm.widget_for_response(gtk4::ResponseType::Accept) {
+            btn.add_css_class("destructive-action");
+        }
+        let (db_c, audio_c, list_c) = (
+            Arc::clone(&db_clear),
+            audio_clear.clone(),
+            Rc::clone(&list_clear),
+        );
+        confirm.connect_response(move |d, response| {
+            d.close();
+            if response != gtk4::ResponseType::Accept {
+                return;
+            }
+            match db_c.lock().map(|d| d.clear()) {
+                Ok(Ok(())) => {
+                    // Retained recordings belong to history entries only
+                    if let Ok(files) = std::fs::read_dir(&audio_c) {
+                        for file in files.flatten() {
+                            if file.path().extension().is_some_and(|e| e == "wav") {
+                                let _ = std::fs::remove_file(file.path());
+                            }
+                        }
+                    }
+                    list_c.selected.borrow_mut().clear();
+                    list_c.complete.set(true);
+                    list_c.store.remove_all();
+                    list_c.update_empty();
+                    fill_pinned(&list_c, &db_c);
+                }
+                Ok(Err(e)) => tracing::error!("History clear error: {e}"),
+                Err(e) => tracing::error!("History clear error: {e}"),
+            }
+        });
+        confirm.present();
+    });
+
+    let replacements_header = gtk4::Label::new(Some("Replacements"));
+    replacements_header.add_css_class("heading");
+    replacements_header.set_halign(gtk4::Align::Start);
+    vbox.append(&replacements_header);
+    refresh_replacements(&replacements_box, db);
+    vbox.append(&replacements_box);
+    vbox.append(&learn);
+
+    let paste = gtk4::CheckButton::with_label("Paste after copying an entry");
+    paste.set_active(
+        db.lock()
+            .ok()
+            .and_then(|d| d.get_setting("history_paste").ok().flatten())
+            .is_some_and(|v| v == "true"),
+    );
+    paste.set_sensitive(crate::input::typing_available());
+    let db_paste = Arc::clone(db);
+    paste.connect_toggled(move |b| {
+        if let Ok(d) = db_paste.lock() {
+            let _ = d.set_setting(
+                "history_paste",
+                if b.is_active() { "true" } else { "false" },
+            );
+        }
+    });
+    vbox.append(&paste);
+
+    let transfer = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    transfer.set_halign(gtk4::Align::End);
+    let import_btn = gtk4::Button::with_label("Import\u{2026}");
+    import_btn.set_tooltip_text(Some("Merge a History export from another machine"));
+    let (db_import, dialog_import, refill_import) =
+        (Arc::clone(db), dialog.clone(), Rc::clone(&refill));
+    import_btn.connect_clicked(move |_| {
+        import_history_file(&dialog_import, &db_import, &refill_import);
+    });
+    let sync_btn = gtk4::Button::with_label("Sync Folder\u{2026}");
+    let (db_sync, dialog_sync, refill_sync) = (Arc::clone(db), dialog.clone(), Rc::clone(&refill));
+    sync_btn.connect_clicked(move |_| {
+        show_sync_dialog(&dialog_sync, &db_sync, &refill_sync);
+    });
+    let export_btn = gtk4::Button::with_label("Export\u{2026}");
+    let (db_export, dialog_export) = (Arc::clone(db), dialog.clone());
+    export_btn.connect_clicked(move |_| {
+        show_export_dialog(&dialog_export, &db_export);
+    });
+    transfer.append(&import_btn);
+    transfer.append(&sync_btn);
+    transfer.append(&export_btn);
+    vbox.append(&transfer);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Pick a format and an optional date range, then save History to a file.
+fn show_export_dialog(parent: &gtk4::Window, db: &Arc<Mutex<Db>>) {
+    use crate::export::ExportFormat;
+
+    let dialog = gtk4::Window::builder()
+        .title("Export History")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(300)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let labels: Vec<&str> = ExportFormat::ALL.iter().map(|f| f.label()).collect();
+    let format = gtk4::DropDown::from_strings(&labels);
+    vbox.append(&gtk4::Label::new(Some("Format")));
+    vbox.append(&format);
+
+    let since = gtk4::Entry::new();
+    since.set_placeholder_text(Some("From (YYYY-MM-DD, optional)"));
+    let until = gtk4::Entry::new();
+    until.set_placeholder_text(Some("To (YYYY-MM-DD, optional)"));
+    vbox.append(&since);
+    vbox.append(&until);
+
+    let error = gtk4::Label::new(None);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let export_btn = gtk4::Button::with_label("Export");
+    export_btn.add_css_class("suggested-action");
+    buttons.append(&cancel_btn);
+    buttons.append(&export_btn);
+    vbox.append(&buttons);
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_export, dialog_export) = (Arc::clone(db), dialog.clone());
+    export_btn.connect_clicked(move |_| {
+        let range = crate::export::parse_date(&since.text())
+            .and_then(|from| Ok((from, crate::export::parse_date(&until.text())?)));
+        let (from, to) = match range {
+            Ok(range) => range,
+            Err(e) => {
+                error.set_label(&e);
+                error.set_visible(true);
+                return;
+            }
+        };
+        let entries = match db_export.lock().map_err(|e| e.to_string()).and_then(|d| {
+            d.entries_between(from.as_deref(), to.as_deref())
+                .map_err(|e| e.to_string())
+        }) {
+            Ok(entries) if entries.is_empty() => {
+                error.set_label("No transcriptions in this range");
+                error.set_visible(true);
+                return;
+            }
+            Ok(entries) => entries,
+            Err(e) => {
+                error.set_label(&format!("Export failed: {e}"));
+                error.set_visible(true);
+                return;
+            }
+        };
+        let fmt = ExportFormat::ALL[(format.selected() as usize).min(ExportFormat::ALL.len() - 1)];
+        save_export(
+            &dialog_export,
+            "Export History",
+            format!("whispercrabs-history.{}", fmt.extension()),
+            crate::export::render(&entries, fmt),
+        );
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Ask where to save an export and write it there; closes `dialog` once saved.
+fn save_export(dialog: &gtk4::Window, title: &'static str, file_name: String, contents: String) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some(title),
+        Some(dialog),
+        gtk4::FileChooserAction::Save,
+        Some("Export"),
+        Some("Cancel"),
+    );
+    chooser.set_current_name(&file_name);
+
+    let dialog = dialog.clone();
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let Some(path) = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path())
+        else {
+            return;
+        };
+        match std::fs::write(&path, &contents) {
+            Ok(()) => dialog.close(),
+            Err(e) => tracing::warn!("{title} failed: {e}"),
+        }
+    });
+    chooser.show();
+}
+
+/// Ask for a History export (JSON) and merge it into History.
+fn import_history_file(parent: &gtk4::Window, db: &Arc<Mutex<Db>>, refill: &Rc<dyn Fn()>) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Import History"),
+        Some(parent),
+        gtk4::FileChooserAction::Open,
+        Some("Import"),
+        Some("Cancel"),
+    );
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("History export (JSON)"));
+    filter.add_pattern("*.json");
+    chooser.add_filter(&filter);
+
+    let (parent, db, refill) = (parent.clone(), Arc::clone(db), Rc::clone(refill));
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let Some(path) = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path())
+        else {
+            return;
+        };
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| crate::sync::parse_entries(&json))
+            .and_then(|entries| {
+                let d = db.lock().map_err(|e| e.to_string())?;
+                d.import(&entries).map_err(|e| e.to_string())
+            });
+        refill();
+        show_import_result(&parent, "Import History", result);
+    });
+    chooser.show();
+}
+
+/// Tell how an import or sync went.
+fn show_import_result(
+    parent: &gtk4::Window,
+    title: &str,
+    result: Result<crate::db::ImportSummary, String>,
+) {
+    let (message_type, message) = match result {
+        Ok(s) if s.skipped == 0 => (
+            gtk4::MessageType::Info,
+            format!("Added {} entries.", s.added),
+        ),
+        Ok(s) => (
+            gtk4::MessageType::Info,
+            format!(
+                "Added {} entries; {} were already in History.",
+                s.added, s.skipped
+            ),
+        ),
+        Err(e) => {
+            tracing::warn!("{title} failed: {e}");
+            (gtk4::MessageType::Error, e)
+        }
+    };
+    let dialog = gtk4::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .message_type(message_type)
+        .buttons(gtk4::ButtonsType::Ok)
+        .text(title)
+        .secondary_text(message)
+        .build();
+    dialog.connect_response(|d, _| d.close());
+    dialog.present();
+}
+
+/// Save the settings to a file, API keys optionally sealed with a passphrase.
+fn show_export_settings_dialog(parent: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let dialog = gtk4::Window::builder()
+        .title("Export Settings")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(320)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "Saves every setting except window position, microphone and the History lock.",
+    ));
+    info.set_wrap(true);
+    info.set_halign(gtk4::Align::Start);
+    vbox.append(&info);
+
+    let include_keys = gtk4::CheckButton::with_label("Include API keys (encrypted)");
+    vbox.append(&include_keys);
+    let passphrase = gtk4::PasswordEntry::new();
+    passphrase.set_placeholder_text(Some("Passphrase"));
+    let confirm = gtk4::PasswordEntry::new();
+    confirm.set_placeholder_text(Some("Confirm passphrase"));
+    for entry in [&passphrase, &confirm] {
+        entry.set_sensitive(false);
+        vbox.append(entry);
+    }
+    let (passphrase_s, confirm_s) = (passphrase.clone(), confirm.clone());
+    include_keys.connect_toggled(move |b| {
+        passphrase_s.set_sensitive(b.is_active());
+        confirm_s.set_sensitive(b.is_active());
+    });
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.set_wrap(true);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let export_btn = gtk4::Button::with_label("Export");
+    export_btn.add_css_class("suggested-action");
+    buttons.append(&cancel_btn);
+    buttons.append(&export_btn);
+    vbox.append(&buttons);
+
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+
+    let (db_export, dialog_export) = (Arc::clone(db), dialog.clone());
+    export_btn.connect_clicked(move |_| {
+        let secret = passphrase.text();
+        let exported = (if include_keys.is_active() {
+            crate::settings_file::validate_passphrase(&secret, &confirm.text())
+                .map(|_| Some(secret.as_str()))
+        } else {
+            Ok(None)
+        })
+        .and_then(|secret| {
+            let settings = db_export
+                .lock()
+                .map_err(|e| e.to_string())?
+                .all_settings()
+                .map_err(|e| e.to_string())?;
+            crate::settings_file::export(&settings, secret)
+        });
+        match exported {
+            Ok(json) => save_export(
+                &dialog_export,
+                "Export Settings",
+                "whispercrabs-settings.json".into(),
+                json,
+            ),
+            Err(e) => {
+                error.set_label(&e);
+                error.set_visible(true);
+            }
+        }
+    });
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Ask for a settings export and apply it, asking for the passphrase when
+/// it carries API keys.
+fn import_settings_file(parent: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Import Settings"),
+        Some(parent),
+        gtk4::FileChooserAction::Open,
+        Some("Import"),
+        Some("Cancel"),
+    );
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Settings export (JSON)"));
+    filter.add_pattern("*.json");
+    chooser.add_filter(&filter);
+
+    let (parent, db) = (parent.clone(), Arc::clone(db));
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let Some(path) = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path())
+        else {
+            return;
+        };
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| crate::settings_file::parse(&json));
+        match parsed {
+            Ok(file) if file.has_secrets() => ask_settings_passphrase(&parent, &db, file),
+            Ok(file) => {
+                let result = apply_settings_file(&db, file, None);
+                show_settings_import_result(parent.upcast_ref(), result);
+            }
+            Err(e) => show_settings_import_result(parent.upcast_ref(), Err(e)),
+        }
+    });
+    chooser.show();
+}
+
+/// Ask for the passphrase sealing the API keys of `file`, or import
+/// without them.
+fn ask_settings_passphrase(
+    parent: &gtk4::ApplicationWindow,
+    db: &Arc<Mutex<Db>>,
+    file: crate::settings_file::SettingsFile,
+) {
+    let dialog = gtk4::Window::builder()
+        .title("Import Settings")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(300)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "This export includes API keys. Enter the passphrase it was saved with:",
+    ));
+    info.set_wrap(true);
+    info.set_halign(gtk4::Align::Start);
+    vbox.append(&info);
+    let passphrase = gtk4::PasswordEntry::new();
+    passphrase.set_placeholder_text(Some("Passphrase"));
+    vbox.append(&passphrase);
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let skip_btn = gtk4::Button::with_label("Skip API Keys");
+    let import_btn = gtk4::Button::with_label("Import");
+    import_btn.add_css_class("suggested-action");
+    buttons.append(&skip_btn);
+    buttons.append(&import_btn);
+    vbox.append(&buttons);
+
+    let (db_skip, dialog_skip, parent_skip, file_skip) =
+        (Arc::clone(db), dialog.clone(), parent.clone(), file.clone());
+    skip_btn.connect_clicked(move |_| {
+        dialog_skip.close();
+        let result = apply_settings_file(&db_skip, file_skip.clone(), None);
+        show_settings_import_result(parent_skip.upcast_ref(), result);
+    });
+
+    let (db_import, dialog_import, parent_import, entry_import) = (
+        Arc::clone(db),
+        dialog.clone(),
+        parent.clone(),
+        passphrase.clone(),
+    );
+    let import = move || {
+        let secret = entry_import.text();
+        match apply_settings_file(&db_import, file.clone(), Some(&secret)) {
+            Err(e) if e == "Wrong passphrase" => {
+                error.set_label(&e);
+                error.set_visible(true);
+                entry_import.set_text("");
+            }
+            result => {
+                dialog_import.close();
+                show_settings_import_result(parent_import.upcast_ref(), result);
+            }
+        }
+    };
+    let import = Rc::new(import);
+    let import_btn_c = Rc::clone(&import);
+    import_btn.connect_clicked(move |_| import_btn_c());
+    passphrase.connect_activate(move |_| import());
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Store the settings of `file` (with its API keys, given their passphrase).
+fn apply_settings_file(
+    db: &Arc<Mutex<Db>>,
+    file: crate::settings_file::SettingsFile,
+    passphrase: Option<&str>,
+) -> Result<crate::db::ImportSummary, String> {
+    let settings = file.into_settings(passphrase)?;
+    db.lock()
+        .map_err(|e| e.to_string())?
+        .import_settings(&settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Tell how a settings import went. Most settings are read at startup, so
+/// the app asks for a restart.
+fn show_settings_import_result(
+    parent: &gtk4::Window,
+    result: Result<crate::db::ImportSummary, String>,
+) {
+    let (message_type, message) = match result {
+        Ok(s) if s.skipped == 0 => (
+            gtk4::MessageType::Info,
+            format!(
+                "Imported {} settings. Restart WhisperCrabs to apply them.",
+                s.added
+            ),
+        ),
+        Ok(s) => (
+            gtk4::MessageType::Info,
+            format!(
+                "Imported {} settings; {} unknown to this version were skipped. \
+                 Restart WhisperCrabs to apply them.",
+                s.added, s.skipped
+            ),
+        ),
+        Err(e) => {
+            tracing::warn!("Import Settings failed: {e}");
+            (gtk4::MessageType::Error, e)
+        }
+    };
+    let dialog = gtk4::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .message_type(message_type)
+        .buttons(gtk4::ButtonsType::Ok)
+        .text("Import Settings")
+        .secondary_text(message)
+        .build();
+    dialog.connect_response(|d, _| d.close());
+    dialog.present();
+}
+
+/// Import the entries other machines wrote to the sync folder.
+fn sync_from_folder(db: &Arc<Mutex<Db>>) -> Result<crate::db::ImportSummary, String> {
+    let d = db.lock().map_err(|e| e.to_string())?;
+    let dir = d
+        .get_setting(crate::sync::SYNC_DIR_SETTING)
+        .map_err(|e| e.to_string())?
+        .ok_or("No sync folder set")?;
+    let entries = crate::sync::read_dir(std::path::Path::new(&dir))?;
+    d.import(&entries).map_err(|e| e.to_string())
+}
+
+/// Choose, sync with or stop using the History sync folder.
+fn show_sync_dialog(parent: &gtk4::Window, db: &Arc<Mutex<Db>>, refill: &Rc<dyn Fn()>) {
+    let current = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::sync::SYNC_DIR_SETTING).ok().flatten());
+
+    let dialog = gtk4::Window::builder()
+        .title("History Sync Folder")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(380)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "New entries are also saved as files in this folder. Point a synced folder \
+         (Syncthing, Nextcloud, ...) at it on each machine to share History.",
+    ));
+    info.set_wrap(true);
+    info.set_xalign(0.0);
+    vbox.append(&info);
+
+    let folder = gtk4::Label::new(Some(current.as_deref().unwrap_or("Not syncing")));
+    folder.set_wrap(true);
+    folder.set_wrap_mode(gtk4::pango::WrapMode::Char);
+    folder.set_selectable(true);
+    folder.set_xalign(0.0);
+    folder.add_css_class("dim-label");
+    vbox.append(&folder);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let stop_btn = gtk4::Button::with_label("Stop Syncing");
+    stop_btn.set_sensitive(current.is_some());
+    let sync_btn = gtk4::Button::with_label("Sync Now");
+    sync_btn.set_sensitive(current.is_some());
+    let choose_btn = gtk4::Button::with_label("Choose Folder\u{2026}");
+    choose_btn.add_css_class("suggested-action");
+    buttons.append(&stop_btn);
+    buttons.append(&sync_btn);
+    buttons.append(&choose_btn);
+    vbox.append(&buttons);
+    dialog.set_child(Some(&vbox));
+
+    let (db_stop, dialog_stop) = (Arc::clone(db), dialog.clone());
+    stop_btn.connect_clicked(move |_| {
+        if let Ok(d) = db_stop.lock() {
+            let _ = d.delete_setting(crate::sync::SYNC_DIR_SETTING);
+        }
+        dialog_stop.close();
+    });
+
+    let (db_now, dialog_now, refill_now) = (Arc::clone(db), dialog.clone(), Rc::clone(refill));
+    sync_btn.connect_clicked(move |_| {
+        let result = sync_from_folder(&db_now);
+        refill_now();
+        show_import_result(&dialog_now, "Sync History", result);
+    });
+
+    let (db_choose, dialog_choose, refill_choose) =
+        (Arc::clone(db), dialog.clone(), Rc::clone(refill));
+    choose_btn.connect_clicked(move |_| {
+        let chooser = gtk4::FileChooserNative::new(
+            Some("History Sync Folder"),
+            Some(&dialog_choose),
+            gtk4::FileChooserAction::SelectFolder,
+            Some("Select"),
+            Some("Cancel"),
+        );
+        let (db, dialog, refill) = (
+            Arc::clone(&db_choose),
+            dialog_choose.clone(),
+            Rc::clone(&refill_choose),
+        );
+        connect_chooser_response(&chooser, move |chooser, response| {
+            let Some(path) = (response == gtk4::ResponseType::Accept)
+                .then(|| chooser.file())
+                .flatten()
+                .and_then(|f| f.path())
+            else {
+                return;
+            };
+            if let Ok(d) = db.lock() {
+                let _ = d.set_setting(crate::sync::SYNC_DIR_SETTING, &path.to_string_lossy());
+            }
+            // Pick up what the other machines already wrote there
+            let result = sync_from_folder(&db);
+            refill();
+            if let Some(parent) = dialog.transient_for() {
+                show_import_result(&parent, "Sync History", result);
+            }
+            dialog.close();
+        });
+        chooser.show();
+    });
+
+    dialog.present();
+}
+
+/// Choose or stop using the folder transcripts are auto-saved to.
+fn show_autosave_dialog(parent: &gtk4::Window, db: &Arc<Mutex<Db>>) {
+    let current = db.lock().ok().and_then(|d| crate::autosave::stored(&d));
+
+    let dialog = gtk4::Window::builder()
+        .title("Save Transcripts to Folder")
+        .transient_for(parent)
+        .modal(true)
+        .default_width(380)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let info = gtk4::Label::new(Some(
+        "Every transcript is also written to this folder as Markdown. Choose a folder \
+         in an Obsidian or Logseq vault to keep a voice journal.",
+    ));
+    info.set_wrap(true);
+    info.set_xalign(0.0);
+    vbox.append(&info);
+
+    let folder = gtk4::Label::new(Some(
+        current
+            .as_ref()
+            .map(|(dir, _)| dir.to_string_lossy())
+            .as_deref()
+            .unwrap_or("Not saving"),
+    ));
+    folder.set_wrap(true);
+    folder.set_wrap_mode(gtk4::pango::WrapMode::Char);
+    folder.set_selectable(true);
+    folder.set_xalign(0.0);
+    folder.add_css_class("dim-label");
+    vbox.append(&folder);
+
+    let labels: Vec<&str> = crate::autosave::Layout::ALL
+        .iter()
+        .map(|l| l.label())
+        .collect();
+    let layout = gtk4::DropDown::from_strings(&labels);
+    let chosen = current.as_ref().map(|(_, l)| *l).unwrap_or_default();
+    if let Some(i) = crate::autosave::Layout::ALL
+        .iter()
+        .position(|l| *l == chosen)
+    {
+        layout.set_selected(i as u32);
+    }
+    let db_layout = Arc::clone(db);
+    layout.connect_selected_notify(move |dd| {
+        let Some(layout) = crate::autosave::Layout::ALL.get(dd.selected() as usize) else {
+            return;
+        };
+        if let Ok(d) = db_layout.lock() {
+            let _ = d.set_setting(crate::autosave::LAYOUT_SETTING, layout.as_str());
+        }
+    });
+    vbox.append(&layout);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let stop_btn = gtk4::Button::with_label("Stop Saving");
+    stop_btn.set_sensitive(current.is_some());
+    let choose_btn = gtk4::Button::with_label("Choose Folder\u{2026}");
+    choose_btn.add_css_class("suggested-action");
+    buttons.append(&stop_btn);
+    buttons.append(&choose_btn);
+    vbox.append(&buttons);
+    dialog.set_child(Some(&vbox));
+
+    let (db_stop, dialog_stop) = (Arc::clone(db), dialog.clone());
+    stop_btn.connect_clicked(move |_| {
+        if let Ok(d) = db_stop.lock() {
+            let _ = d.delete_setting(crate::autosave::DIR_SETTING);
+        }
+        dialog_stop.close();
+    });
+
+    let (db_choose, dialog_choose) = (Arc::clone(db), dialog.clone());
+    choose_btn.connect_clicked(move |_| {
+        let chooser = gtk4::FileChooserNative::new(
+            Some("Save Transcripts to Folder"),
+            Some(&dialog_choose),
+            gtk4::FileChooserAction::SelectFolder,
+            Some("Select"),
+            Some("Cancel"),
+        );
+        let (db, dialog) = (Arc::clone(&db_choose), dialog_choose.clone());
+        connect_chooser_response(&chooser, move |chooser, response| {
+            let Some(path) = (response == gtk4::ResponseType::Accept)
+                .then(|| chooser.file())
+                .flatten()
+                .and_then(|f| f.path())
+            else {
+                return;
+            };
+            if let Ok(d) = db.lock() {
+                let _ = d.set_setting(crate::autosave::DIR_SETTING, &path.to_string_lossy());
+            }
+            dialog.close();
+        });
+        chooser.show();
+    });
+
+    dialog.present();
+}
+
+/// (Re)load History from its first page for the current filter.
+fn fill_history_list(list: &Rc<HistoryList>, db: &Arc<Mutex<Db>>, retranscribe: &Retranscribe) {
+    // Looked up before locking the database, which it reads too
+    *list.rerun_choices.borrow_mut() = (retranscribe.choices)();
+    list.selected.borrow_mut().clear();
+    list.complete.set(false);
+    list.store.remove_all();
+    load_history_page(list, db);
+    fill_pinned(list, db);
+}
+
+/// Append the next page of History entries, unless the last one is listed.
+fn load_history_page(list: &HistoryList, db: &Arc<Mutex<Db>>) {
+    if !list.complete.get() {
+        let page = {
+            let filter = list.filter.borrow();
+            db.lock().map_err(|e| e.to_string()).and_then(|d| {
+                d.page_filtered(
+                    list.store.n_items() as usize,
+                    HISTORY_PAGE_SIZE,
+                    filter.language.as_deref(),
+                    filter.tag.as_deref(),
+                )
+                .map_err(|e| e.to_string())
+            })
+        };
+        match page {
+            Ok(entries) => {
+                list.complete.set(entries.len() < HISTORY_PAGE_SIZE);
+                let items: Vec<glib::BoxedAnyObject> =
+                    entries.into_iter().map(glib::BoxedAnyObject::new).collect();
+                list.store.extend_from_slice(&items);
+            }
+            Err(e) => {
+                tracing::error!("History load error: {e}");
+                list.complete.set(true);
+            }
+        }
+    }
+    list.update_empty();
+}
+
+/// Builds the row of each History entry as it scrolls into view.
+fn history_row_factory(
+    list: &Rc<HistoryList>,
+    db: &Arc<Mutex<Db>>,
+    audio_dir: &std::path::Path,
+    learn: &gtk4::CheckButton,
+    replacements_box: &gtk4::Box,
+    retranscribe: &Retranscribe,
+) -> gtk4::SignalListItemFactory {
+    let factory = gtk4::SignalListItemFactory::new();
+    let (list_b, db_b, audio_b) = (Rc::clone(list), Arc::clone(db), audio_dir.to_path_buf());
+    let (learn_b, replacements_b, retranscribe_b) = (
+        learn.clone(),
+        replacements_box.clone(),
+        retranscribe.clone(),
+    );
+    factory.connect_bind(move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk4::ListItem>() else {
+            return;
+        };
+        let Some(entry) = item.item().and_downcast::<glib::BoxedAnyObject>() else {
+            return;
+        };
+        // The first entries get a Ctrl+1…9 quick-copy number
+        let shortcut = (item.position() < HISTORY_SHORTCUTS).then(|| item.position() + 1);
+        let row = history_row(
+            &entry,
+            shortcut,
+            &list_b,
+            &db_b,
+            &audio_b,
+            &learn_b,
+            &replacements_b,
+            &retranscribe_b,
+        );
+        item.set_child(Some(&row));
+    });
+    let list_u = Rc::clone(list);
+    factory.connect_unbind(move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk4::ListItem>() else {
+            return;
+        };
+        if let Some(entry) = item.item().and_downcast::<glib::BoxedAnyObject>() {
+            let id = entry.borrow::<Transcription>().id;
+            list_u.rows.borrow_mut().retain(|r| r.id != id);
+        }
+        item.set_child(None::<&gtk4::Widget>);
+    });
+    factory
+}
+
+/// The row of one History entry (`entry_obj` wraps its [`Transcription`]):
+/// the text with edit, copy, pin, tag, playback and re-transcription controls,
+/// numbered when `shortcut` (Ctrl+N) copies it.
+#[allow(clippy::too_many_arguments)]
+fn history_row(
+    entry_obj: &glib::BoxedAnyObject,
+    shortcut: Option<u32>,
+    list: &Rc<HistoryList>,
+    db: &Arc<Mutex<Db>>,
+    audio_dir: &std::path::Path,
+    learn: &gtk4::CheckButton,
+    replacements_box: &gtk4::Box,
+    retranscribe: &Retranscribe,
+) -> gtk4::Box {
+    let entry = entry_obj.borrow::<Transcription>().clone();
+    let rerun_choices = list.rerun_choices.borrow().clone();
+    let (words, segments, revisions, pinned, tags, note, raw_text) = match db.lock() {
+        Ok(d) => (
+            d.words(entry.id).unwrap_or_default(),
+            d.segments(entry.id).unwrap_or_default(),
+            d.revisions(entry.id).unwrap_or_default(),
+            d.is_pinned(entry.id).unwrap_or(false),
+            d.tags(entry.id).unwrap_or_default(),
+            d.note(entry.id).ok().flatten(),
+            d.raw_text(entry.id).ok().flatten(),
+        ),
+        Err(_) => Default::default(),
+    };
+
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    let top = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+    // Spoken language next to the timestamp, e.g. "2026-03-01 10:12 · German"
+    let stamp = match entry.language.as_deref() {
+        Some(code) => format!(
+            "{} \u{00b7} {}",
+            entry.created_at,
+            config::language_label(code)
+        ),
+        None => entry.created_at.clone(),
+    };
+    let check = gtk4::CheckButton::new();
+    check.set_tooltip_text(Some("Select for deletion"));
+    check.set_active(list.selected.borrow().contains(&entry.id));
+    let (list_check, id_check) = (Rc::clone(list), entry.id);
+    check.connect_toggled(move |b| {
+        let mut selected = list_check.selected.borrow_mut();
+        if b.is_active() {
+            selected.insert(id_check);
+        } else {
+            selected.remove(&id_check);
+        }
+    });
+    let time = gtk4::Label::new(Some(&stamp));
+    time.set_halign(gtk4::Align::Start);
+    time.set_hexpand(true);
+    time.set_opacity(0.6);
+    let edit_btn = gtk4::Button::with_label("Edit");
+    edit_btn.add_css_class("flat");
+    top.append(&check);
+    if let Some(n) = shortcut {
+        let number = gtk4::Label::new(Some(&n.to_string()));
+        number.add_css_class("dim-label");
+        number.set_tooltip_text(Some(&format!("Ctrl+{n} copies this entry")));
+        top.append(&number);
+    }
+    top.append(&time);
+
+    let text = gtk4::Label::new(Some(&entry.text));
+    text.set_halign(gtk4::Align::Start);
+    text.set_wrap(true);
+    text.set_selectable(true);
+
+    // The stored recording, if audio is kept for this entry
+    let audio = entry
+        .audio_path
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| config::recording_path(audio_dir, entry.id));
+
+    // Word timings: click a word to hear that part of the recording
+    let words_box = (!words.is_empty()).then(|| {
+        let words_btn = gtk4::ToggleButton::with_label("Words");
+        words_btn.add_css_class("flat");
+        top.append(&words_btn);
+        word_buttons(&words, audio.clone(), &words_btn)
+    });
+    // Timed segments: copy or play one part, or save them as subtitles
+    let segments_box = (!segments.is_empty()).then(|| {
+        let segments_btn = gtk4::ToggleButton::with_label("Segments");
+        segments_btn.add_css_class("flat");
+        top.append(&segments_btn);
+        segment_rows(&segments, audio.clone(), entry.id, &segments_btn)
+    });
+    // Stored recording: play it, or transcribe it again with the active provider
+    let retranscribe_btn = audio.exists().then(|| {
+        let play_btn = gtk4::Button::from_icon_name("media-playback-start-symbolic");
+        play_btn.add_css_class("flat");
+        play_btn.set_tooltip_text(Some("Play recording"));
+        let audio_p = audio.clone();
+        play_btn.connect_clicked(move |_| play_recording(audio_p.clone()));
+        top.append(&play_btn);
+        let retranscribe_btn = gtk4::Button::with_label("Re-transcribe");
+        retranscribe_btn.add_css_class("flat");
+        top.append(&retranscribe_btn);
+        retranscribe_btn
+    });
+    // ...or with another provider, kept as a linked revision
+    let rerun_btn = (audio.exists() && !rerun_choices.is_empty()).then(|| {
+        let rerun_btn = gtk4::MenuButton::new();
+        rerun_btn.set_label("Re-run with\u{2026}");
+        rerun_btn.add_css_class("flat");
+        top.append(&rerun_btn);
+        rerun_btn
+    });
+    let revisions_box = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    revisions_box.set_margin_start(12);
+    if let Some(raw) = raw_text {
+        revisions_box.append(&revision_row("Before post-processing", &raw));
+    }
+    for revision in revisions {
+        let label = revision
+            .provider
+            .as_deref()
+            .map(provider_label)
+            .unwrap_or_else(|| "Revision".to_string());
+        revisions_box.append(&revision_row(&label, &revision.text));
+    }
+    let pin_btn = gtk4::ToggleButton::new();
+    pin_btn.set_icon_name("view-pin-symbolic");
+    pin_btn.add_css_class("flat");
+    pin_btn.set_tooltip_text(Some("Pin to the top"));
+    pin_btn.set_active(pinned);
+    top.append(&pin_btn);
+    let (db_pin, list_pin) = (Arc::clone(db), Rc::clone(list));
+    let id_pin = entry.id;
+    pin_btn.connect_toggled(move |b| {
+        if let Ok(d) = db_pin.lock()
+            && let Err(e) = d.set_pinned(id_pin, b.is_active())
+        {
+            tracing::warn!("Pinning failed: {e}");
+        }
+        fill_pinned(&list_pin, &db_pin);
+    });
+
+    // Tags and a note, edited inline
+    let tags_btn = gtk4::Button::with_label("Tags");
+    tags_btn.add_css_class("flat");
+    tags_btn.set_tooltip_text(Some("Tags and note"));
+    top.append(&tags_btn);
+    let (tags_label, note_label, tags_editor) =
+        entry_tags_editor(db, entry.id, &tags, note.as_deref());
+    let tags_editor_t = tags_editor.clone();
+    tags_btn.connect_clicked(move |_| {
+        tags_editor_t.set_visible(!tags_editor_t.is_visible());
+    });
+    top.append(&edit_btn);
+    let copy_btn = gtk4::Button::from_icon_name("edit-copy-symbolic");
+    copy_btn.add_css_class("flat");
+    copy_btn.set_tooltip_text(Some("Copy"));
+    top.append(&copy_btn);
+    let delete_btn = gtk4::Button::from_icon_name("user-trash-symbolic");
+    delete_btn.add_css_class("flat");
+    delete_btn.set_tooltip_text(Some("Delete"));
+    top.append(&delete_btn);
+    let (db_del, audio_del, list_del) = (Arc::clone(db), audio_dir.to_path_buf(), Rc::clone(list));
+    let id_del = entry.id;
+    delete_btn.connect_clicked(move |_| {
+        delete_history_entries(&db_del, &audio_del, &list_del, &[id_del]);
+    });
+
+    // Inline editor, shown in place of the label while editing
+    let editor = gtk4::TextView::new();
+    editor.set_wrap_mode(gtk4::WrapMode::WordChar);
+    editor.buffer().set_text(&entry.text);
+    editor.set_visible(false);
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.set_halign(gtk4::Align::End);
+    save_btn.set_visible(false);
+
+    row.append(&top);
+    row.append(&text);
+    if let Some(ref words_box) = words_box {
+        row.append(words_box);
+    }
+    if let Some(ref segments_box) = segments_box {
+        row.append(segments_box);
+    }
+    row.append(&tags_label);
+    row.append(&note_label);
+    row.append(&tags_editor);
+    row.append(&revisions_box);
+    row.append(&editor);
+    row.append(&save_btn);
+
+    let (text_e, editor_e, save_e) = (text.clone(), editor.clone(), save_btn.clone());
+    edit_btn.connect_clicked(move |b| {
+        b.set_visible(false);
+        text_e.set_visible(false);
+        editor_e.set_visible(true);
+        save_e.set_visible(true);
+        editor_e.grab_focus();
+    });
 
-    // --- D-Bus action: "speak" — reads clipboard and speaks ---
-    let speak_action = gtk4::gio::SimpleAction::new("speak", None);
-    let runtime_speak = Rc::clone(&runtime);
-    let state_speak = Rc::clone(&state);
-    let btn_speak = button.clone();
-    speak_action.connect_activate(move |_, _| {
-        eprintln!("[dbus] 'speak' action activated");
-        let rt = runtime_speak.borrow();
-        if rt.tts_provider == TtsProvider::None {
-            return;
-        }
-        let Some(ref engine) = rt.tts_engine else {
-            return;
-        };
-        let text = match arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
-            Ok(t) if !t.trim().is_empty() => t,
-            _ => return,
-        };
-        let engine = Arc::clone(engine);
-        let stop_flag = Arc::clone(&rt.tts_stop);
-        stop_flag.store(false, std::sync::atomic::Ordering::Relaxed);
-        drop(rt);
+    // The listed entry, kept current so the row is rebuilt with any edit
+    let current = entry_obj.clone();
 
-        let sr = engine.sample_rate();
-        *state_speak.borrow_mut() = State::Synthesizing;
-        btn_speak.add_css_class("synthesizing");
+    // Copy again (the edited text, if changed), optionally pasting it right away
+    let (current_c, db_c) = (current.clone(), Arc::clone(db));
+    copy_btn.connect_clicked(move |b| {
+        copy_history_text(b, &db_c, &current_c.borrow::<Transcription>().text);
+    });
 
-        let (tx, rx) = std::sync::mpsc::channel::<Result<Vec<i16>, String>>();
-        std::thread::spawn(move || {
-            let _ = tx.send(engine.synthesize(&text));
-        });
+    if let Some(rerun_btn) = rerun_btn {
+        let popover = gtk4::Popover::new();
+        let choices_box = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+        for (provider, label) in &rerun_choices {
+            let choice = gtk4::Button::with_label(label);
+            choice.add_css_class("flat");
+            let (popover_c, rerun_c, revisions_c) =
+                (popover.clone(), rerun_btn.clone(), revisions_box.clone());
+            let (db_c, retranscribe_c) = (Arc::clone(db), retranscribe.clone());
+            let (audio_c, id_c) = (audio.clone(), entry.id);
+            let (provider, label) = (provider.clone(), label.clone());
+            choice.connect_clicked(move |_| {
+                popover_c.popdown();
+                rerun_history_entry(
+                    &retranscribe_c,
+                    &db_c,
+                    &audio_c,
+                    id_c,
+                    &provider,
+                    &label,
+                    &rerun_c,
+                    &revisions_c,
+                );
+            });
+            choices_box.append(&choice);
+        }
+        popover.set_child(Some(&choices_box));
+        rerun_btn.set_popover(Some(&popover));
+    }
 
-        let btn2 = btn_speak.clone();
-        let state2 = Rc::clone(&state_speak);
-        let stop2 = stop_flag;
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            match rx.try_recv() {
-                Ok(Ok(samples)) => {
-                    btn2.remove_css_class("synthesizing");
-                    *state2.borrow_mut() = State::Speaking;
-                    btn2.add_css_class("speaking");
-                    let btn3 = btn2.clone();
-                    let state3 = Rc::clone(&state2);
-                    play_tts_audio(samples, sr, Arc::clone(&stop2), move || {
-                        btn3.remove_css_class("speaking");
-                        *state3.borrow_mut() = State::Idle;
-                    });
-                    glib::ControlFlow::Break
+    if let Some(retranscribe_btn) = retranscribe_btn {
+        let (db_r, retranscribe) = (Arc::clone(db), retranscribe.clone());
+        let (text_r, editor_r, current_r) = (text.clone(), editor.clone(), current.clone());
+        let (audio_r, id_r) = (audio.clone(), entry.id);
+        retranscribe_btn.connect_clicked(move |b| {
+            let rx = match (retranscribe.run)(&audio_r, None) {
+                Ok(run) => run.rx,
+                Err(e) => {
+                    b.set_tooltip_text(Some(&e));
+                    b.set_label("Failed");
+                    return;
                 }
-                Ok(Err(_)) => {
-                    btn2.remove_css_class("synthesizing");
-                    *state2.borrow_mut() = State::Idle;
-                    glib::ControlFlow::Break
+            };
+            b.set_sensitive(false);
+            b.set_label("Transcribing\u{2026}");
+            let (b, db_r) = (b.clone(), Arc::clone(&db_r));
+            let (text_r, editor_r, current_r) =
+                (text_r.clone(), editor_r.clone(), current_r.clone());
+            glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+                let result = match rx.try_recv() {
+                    Ok(SttMsg::Done(result, _)) => result,
+                    Ok(_) | Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue;
+                    }
+                    Err(_) => Err("Transcription stopped".into()),
+                };
+                b.set_sensitive(true);
+                b.set_label("Re-transcribe");
+                let saved = result.and_then(|t| {
+                    let d = db_r.lock().map_err(|e| e.to_string())?;
+                    let rules = d
+                        .accepted_replacements(t.language.as_deref())
+                        .unwrap_or_default();
+                    let mut text = crate::corrections::apply(&t.text, &rules);
+                    if let Some(commands) = crate::dictation_commands::stored(&d) {
+                        text = crate::dictation_commands::apply(&text, &commands);
+                    }
+                    d.replace_transcript(id_r, &text, t.language.as_deref(), &t.words, &t.segments)
+                        .map_err(|e| e.to_string())?;
+                    Ok(text)
+                });
+                match saved {
+                    Ok(new_text) => {
+                        b.set_tooltip_text(None);
+                        text_r.set_label(&new_text);
+                        editor_r.buffer().set_text(&new_text);
+                        current_r.borrow_mut::<Transcription>().text = new_text;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Re-transcription failed: {e}");
+                        b.set_tooltip_text(Some(&e));
+                        b.set_label("Failed");
+                    }
                 }
-                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-                Err(_) => {
-                    btn2.remove_css_class("synthesizing");
-                    *state2.borrow_mut() = State::Idle;
-                    glib::ControlFlow::Break
+                glib::ControlFlow::Break
+            });
+        });
+    }
+
+    let id = entry.id;
+    let db_save = Arc::clone(db);
+    let entry_language = entry.language.clone();
+    let learn_s = learn.clone();
+    let replacements_s = replacements_box.clone();
+    save_btn.connect_clicked(move |b| {
+        let buffer = editor.buffer();
+        let edited = buffer
+            .text(&buffer.start_iter(), &buffer.end_iter(), false)
+            .trim()
+            .to_string();
+        let original = current.borrow::<Transcription>().text.clone();
+        if !edited.is_empty() && edited != original {
+            if let Ok(d) = db_save.lock() {
+                if let Err(e) = d.update_text(id, &edited) {
+                    tracing::error!("History update error: {e}");
+                }
+                if learn_s.is_active() {
+                    for (from, to) in crate::corrections::suggestions(&original, &edited) {
+                        let _ = d.suggest_replacement(&from, &to, entry_language.as_deref());
+                    }
                 }
             }
-        });
+            text.set_label(&edited);
+            current.borrow_mut::<Transcription>().text = edited;
+            refresh_replacements(&replacements_s, &db_save);
+        }
+        b.set_visible(false);
+        editor.set_visible(false);
+        text.set_visible(true);
+        edit_btn.set_visible(true);
     });
-    app.add_action(&speak_action);
 
-    window.present();
+    list.rows.borrow_mut().push(HistoryRow {
+        id: entry.id,
+        pin: pin_btn,
+    });
+    row
 }
 
-fn delete_all_local_models(models_dir: &std::path::Path) {
-    for lm in config::LOCAL_MODEL_PRESETS {
-        let path = models_dir.join(lm.file_name);
-        if path.exists()
-            && let Err(e) = std::fs::remove_file(&path)
-        {
-            eprintln!("Failed to delete model file {}: {e}", lm.file_name);
-        }
+/// The pinned section at the top of History: each pinned entry with a copy
+/// and an unpin button. Hidden while nothing is pinned.
+fn fill_pinned(list: &Rc<HistoryList>, db: &Arc<Mutex<Db>>) {
+    let pinned_box = &list.pinned_box;
+    while let Some(child) = pinned_box.first_child() {
+        pinned_box.remove(&child);
+    }
+    let pinned = db
+        .lock()
+        .ok()
+        .and_then(|d| d.pinned().ok())
+        .unwrap_or_default();
+    pinned_box.set_visible(!pinned.is_empty());
+    if pinned.is_empty() {
+        return;
+    }
+    let header = gtk4::Label::new(Some("Pinned"));
+    header.add_css_class("heading");
+    header.set_halign(gtk4::Align::Start);
+    pinned_box.append(&header);
+    for entry in pinned {
+        let line = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+        let text = gtk4::Label::new(Some(&entry.text));
+        text.set_halign(gtk4::Align::Start);
+        text.set_hexpand(true);
+        text.set_wrap(true);
+        let copy_btn = gtk4::Button::from_icon_name("edit-copy-symbolic");
+        copy_btn.add_css_class("flat");
+        copy_btn.set_tooltip_text(Some("Copy"));
+        let db_c = Arc::clone(db);
+        copy_btn.connect_clicked(move |b| copy_history_text(b, &db_c, &entry.text));
+        let unpin_btn = gtk4::Button::from_icon_name("window-close-symbolic");
+        unpin_btn.add_css_class("flat");
+        unpin_btn.set_tooltip_text(Some("Unpin"));
+        let (db_u, list_u) = (Arc::clone(db), Rc::clone(list));
+        let id = entry.id;
+        unpin_btn.connect_clicked(move |_| {
+            // A listed entry updates its own pin toggle, which saves and refreshes
+            let listed = list_u
+                .rows
+                .borrow()
+                .iter()
+                .find(|r| r.id == id)
+                .map(|r| r.pin.clone());
+            match listed {
+                Some(pin) => pin.set_active(false),
+                None => {
+                    if let Ok(d) = db_u.lock()
+                        && let Err(e) = d.set_pinned(id, false)
+                    {
+                        tracing::warn!("Unpinning failed: {e}");
+                    }
+                    fill_pinned(&list_u, &db_u);
+                }
+            }
+        });
+        line.append(&text);
+        line.append(&copy_btn);
+        line.append(&unpin_btn);
+        pinned_box.append(&line);
     }
+    pinned_box.append(&gtk4::Separator::new(gtk4::Orientation::Horizontal));
 }
 
-fn switch_to_preset(
-    parent: &gtk4::ApplicationWindow,
-    runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
-    db: &Arc<Mutex<Db>>,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
-    preset: &config::ApiPreset,
-) {
-    // Resolve API key: DB per-provider key → env var fallback
-    let resolved_key = if preset.needs_key {
-        db.lock()
-            .ok()
-            .and_then(|d| {
-                d.get_setting(&format!("api_key_{}", preset.id))
-                    .ok()
-                    .flatten()
-            })
-            .or_else(|| config.api_key.clone())
-    } else {
-        None
-    };
-
-    // If provider needs a key and we don't have one, show a dialog to collect it
-    if preset.needs_key && resolved_key.is_none() {
-        show_api_key_dialog(parent, runtime, config, db, action, status, preset);
+/// Copy a History text from `button`, pasting it into the previously
+/// focused app right away when "Paste after copying an entry" is on.
+fn copy_history_text(button: &gtk4::Button, db: &Arc<Mutex<Db>>, text: &str) {
+    let window = button.root().and_downcast::<gtk4::Window>();
+    if !copy_history_entry(window.as_ref(), db, text) {
         return;
     }
-
-    apply_preset(runtime, config, db, action, status, preset, resolved_key);
+    button.set_icon_name("object-select-symbolic");
+    let button = button.clone();
+    glib::timeout_add_local_once(std::time::Duration::from_secs(1), move || {
+        button.set_icon_name("edit-copy-symbolic");
+    });
 }
 
-fn apply_preset(
-    runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
-    db: &Arc<Mutex<Db>>,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
-    preset: &config::ApiPreset,
-    api_key: Option<String>,
-) {
-    {
-        let mut rt = runtime.borrow_mut();
-        rt.active_service = TranscriptionService::Api;
-        rt.active_provider = preset.id.to_string();
-        rt.api_base_url = preset.base_url.to_string();
-        rt.api_model = preset.default_model.to_string();
-        rt.api_key = api_key;
-        rt.local_whisper = None;
+/// Copy a History text. With "Paste after copying an entry" on, `window`
+/// closes to hand focus back and the text is pasted there. Returns whether
+/// the text was copied and the window stays open.
+fn copy_history_entry(window: Option<&gtk4::Window>, db: &Arc<Mutex<Db>>, text: &str) -> bool {
+    if let Err(e) = crate::input::copy_to_clipboard(text) {
+        tracing::warn!("Copy failed: {e}");
+        return false;
     }
-
-    // Delete all local model files to free disk space
-    delete_all_local_models(&config.models_dir);
-
-    // Persist to DB
-    if let Ok(d) = db.lock() {
-        let _ = d.set_setting("transcription_mode", preset.id);
+    let paste = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting("history_paste").ok().flatten())
+        .is_some_and(|v| v == "true");
+    if paste && let Some(window) = window {
+        // Hand focus back to the application the text goes into
+        window.close();
+        paste_into_focused_app(text.to_string(), std::time::Duration::from_millis(300));
+        return false;
     }
+    true
+}
 
-    action.set_state(&preset.id.to_variant());
+/// Paste `text`, just copied, into the focused application once `settle`
+/// has let focus move there, with that application's paste timing (see
+/// [`crate::paste`]).
+fn paste_into_focused_app(text: String, settle: std::time::Duration) {
+    glib::timeout_add_local_once(settle, move || {
+        let class = crate::input::focused_app_class();
+        let timing = crate::paste::timing_for(class.as_deref());
+        dbg_log!(
+            "[paste] {}: {timing:?}",
+            class.as_deref().unwrap_or("unknown app")
+        );
+        press_paste(text, timing, true);
+    });
+}
 
-    show_status(status, &format!("{} mode", preset.label));
-    let st = status.clone();
-    glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
-        hide_status(&st);
+/// Press Ctrl+V after the timing's delay. With verification on, a clipboard
+/// that no longer holds `text` gets it again (once) before the key press,
+/// and one that changed during the paste is reported.
+fn press_paste(text: String, timing: crate::paste::PasteTiming, may_copy: bool) {
+    let delay = std::time::Duration::from_millis(timing.delay_ms);
+    glib::timeout_add_local_once(delay, move || {
+        if timing.verify && !crate::input::clipboard_holds(&text) {
+            if !may_copy {
+                tracing::warn!("Paste skipped: the clipboard keeps being replaced");
+                return;
+            }
+            dbg_log!("[paste] clipboard replaced, copying again");
+            match crate::input::copy_to_clipboard(&text) {
+                Ok(()) => press_paste(text, timing, false),
+                Err(e) => tracing::warn!("Paste skipped: {e}"),
+            }
+            return;
+        }
+        if let Err(e) = crate::input::paste(timing.key_delay_ms) {
+            tracing::warn!("Paste failed: {e}");
+        } else if timing.verify && !crate::input::clipboard_holds(&text) {
+            tracing::warn!("Clipboard changed while pasting; the pasted text may differ");
+        }
     });
 }
 
-fn show_api_key_dialog(
-    parent: &gtk4::ApplicationWindow,
-    runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
+/// Tags and note of History entry `id`: labels showing them (hidden while
+/// empty) and a hidden inline editor that saves both.
+fn entry_tags_editor(
     db: &Arc<Mutex<Db>>,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
-    preset: &config::ApiPreset,
-) {
-    let previous_provider = runtime.borrow().active_provider.clone();
+    id: i64,
+    tags: &[String],
+    note: Option<&str>,
+) -> (gtk4::Label, gtk4::Label, gtk4::Box) {
+    let tags_text = |tags: &[String]| {
+        tags.iter()
+            .map(|t| format!("#{t}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let tags_label = gtk4::Label::new(Some(&tags_text(tags)));
+    tags_label.set_halign(gtk4::Align::Start);
+    tags_label.set_opacity(0.6);
+    tags_label.set_visible(!tags.is_empty());
+    let note_label = gtk4::Label::new(note);
+    note_label.set_halign(gtk4::Align::Start);
+    note_label.set_wrap(true);
+    note_label.set_opacity(0.8);
+    note_label.set_visible(note.is_some());
+
+    let editor = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+    editor.set_visible(false);
+    let tags_entry = gtk4::Entry::new();
+    tags_entry.set_placeholder_text(Some("Tags, e.g. work, ideas"));
+    tags_entry.set_text(&tags.join(", "));
+    let note_entry = gtk4::Entry::new();
+    note_entry.set_placeholder_text(Some("Note"));
+    note_entry.set_text(note.unwrap_or_default());
+    let save_btn = gtk4::Button::with_label("Save");
+    save_btn.set_halign(gtk4::Align::End);
+    editor.append(&tags_entry);
+    editor.append(&note_entry);
+    editor.append(&save_btn);
+
+    let (db_s, editor_s) = (Arc::clone(db), editor.clone());
+    let (tags_label_s, note_label_s) = (tags_label.clone(), note_label.clone());
+    let (tags_entry_s, note_entry_s) = (tags_entry.clone(), note_entry.clone());
+    let save = move || {
+        let tags = crate::db::parse_tags(&tags_entry_s.text());
+        let note = note_entry_s.text().trim().to_string();
+        let saved = db_s.lock().map_err(|e| e.to_string()).and_then(|d| {
+            d.set_tags(id, &tags)
+                .and_then(|_| d.set_note(id, Some(&note)))
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = saved {
+            tracing::warn!("Saving tags failed: {e}");
+            return;
+        }
+        tags_entry_s.set_text(&tags.join(", "));
+        tags_label_s.set_label(&tags_text(&tags));
+        tags_label_s.set_visible(!tags.is_empty());
+        note_label_s.set_label(&note);
+        note_label_s.set_visible(!note.is_empty());
+        editor_s.set_visible(false);
+    };
+    let save = Rc::new(save);
+    let save_c = Rc::clone(&save);
+    save_btn.connect_clicked(move |_| save_c());
+    let save_t = Rc::clone(&save);
+    tags_entry.connect_activate(move |_| save_t());
+    note_entry.connect_activate(move |_| save());
+    (tags_label, note_label, editor)
+}
 
-    let dialog = gtk4::Window::builder()
-        .title(format!("{} API Key", preset.label))
-        .default_width(380)
-        .default_height(140)
-        .transient_for(parent)
-        .modal(true)
-        .build();
+/// What History lists: entries in one language and/or with one tag.
+#[derive(Default)]
+struct HistoryFilter {
+    language: Option<String>,
+    tag: Option<String>,
+}
 
-    let grid = gtk4::Grid::builder()
-        .row_spacing(8)
-        .column_spacing(12)
-        .margin_top(16)
-        .margin_bottom(16)
-        .margin_start(16)
-        .margin_end(16)
-        .build();
+/// History entries fetched per page as the list scrolls.
+const HISTORY_PAGE_SIZE: usize = 50;
+
+/// Listed entries that Ctrl+1…9 copy.
+const HISTORY_SHORTCUTS: u32 = 9;
+
+/// The History list: entries loaded into `store` a page at a time, the rows
+/// on screen and the entries checked for deletion.
+struct HistoryList {
+    store: gtk4::gio::ListStore,
+    filter: RefCell<HistoryFilter>,
+    /// Set once the last page is loaded.
+    complete: Cell<bool>,
+    rerun_choices: RefCell<Vec<(String, String)>>,
+    selected: RefCell<HashSet<i64>>,
+    rows: RefCell<Vec<HistoryRow>>,
+    pinned_box: gtk4::Box,
+    empty: gtk4::Label,
+}
 
-    let label = gtk4::Label::new(Some(&format!("Enter your {} API key:", preset.label)));
-    label.set_halign(gtk4::Align::Start);
-    grid.attach(&label, 0, 0, 2, 1);
+impl HistoryList {
+    fn new() -> Self {
+        let empty = gtk4::Label::new(None);
+        empty.set_visible(false);
+        Self {
+            store: gtk4::gio::ListStore::new::<glib::BoxedAnyObject>(),
+            filter: RefCell::new(HistoryFilter::default()),
+            complete: Cell::new(false),
+            rerun_choices: RefCell::new(Vec::new()),
+            selected: RefCell::new(HashSet::new()),
+            rows: RefCell::new(Vec::new()),
+            pinned_box: gtk4::Box::new(gtk4::Orientation::Vertical, 2),
+            empty,
+        }
+    }
 
-    let key_entry = gtk4::Entry::new();
-    key_entry.set_hexpand(true);
-    key_entry.set_placeholder_text(Some("API key"));
-    key_entry.set_input_purpose(gtk4::InputPurpose::Password);
-    key_entry.set_visibility(false);
-    grid.attach(&key_entry, 0, 1, 2, 1);
+    /// Show why the list is empty, if it is.
+    fn update_empty(&self) {
+        let filter = self.filter.borrow();
+        self.empty.set_label(if filter.tag.is_some() {
+            "No transcriptions with this tag."
+        } else if filter.language.is_some() {
+            "No transcriptions in this language."
+        } else {
+            "No transcriptions yet."
+        });
+        self.empty.set_visible(self.store.n_items() == 0);
+    }
+}
 
-    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
-    btn_box.set_halign(gtk4::Align::End);
-    let cancel_btn = gtk4::Button::with_label("Cancel");
-    let save_btn = gtk4::Button::with_label("Save");
-    btn_box.append(&cancel_btn);
-    btn_box.append(&save_btn);
-    grid.attach(&btn_box, 0, 2, 2, 1);
+/// A History row on screen: its entry and pin toggle, which the pinned
+/// section switches off when unpinning.
+struct HistoryRow {
+    id: i64,
+    pin: gtk4::ToggleButton,
+}
 
-    dialog.set_child(Some(&grid));
+/// Delete History entries with their retained recordings and drop them
+/// from the list.
+fn delete_history_entries(
+    db: &Arc<Mutex<Db>>,
+    audio_dir: &std::path::Path,
+    list: &Rc<HistoryList>,
+    ids: &[i64],
+) {
+    match db.lock().map(|d| d.delete_many(ids)) {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            tracing::error!("History delete error: {e}");
+            return;
+        }
+        Err(e) => {
+            tracing::error!("History delete error: {e}");
+            return;
+        }
+    }
+    for id in ids {
+        let _ = std::fs::remove_file(config::recording_path(audio_dir, *id));
+    }
+    list.selected.borrow_mut().retain(|id| !ids.contains(id));
+    let mut first_removed = u32::MAX;
+    for position in (0..list.store.n_items()).rev() {
+        let deleted = list
+            .store
+            .item(position)
+            .and_downcast::<glib::BoxedAnyObject>()
+            .is_some_and(|e| ids.contains(&e.borrow::<Transcription>().id));
+        if deleted {
+            list.store.remove(position);
+            first_removed = position;
+        }
+    }
+    // Rebuild the numbered rows that moved up, so Ctrl+N matches its label
+    let renumbered = first_removed..list.store.n_items().min(HISTORY_SHORTCUTS);
+    if !renumbered.is_empty() {
+        let count = renumbered.end - renumbered.start;
+        list.store.items_changed(renumbered.start, count, count);
+    }
+    fill_pinned(list, db);
+    // Nothing left on screen: bring in the next page, if any
+    if list.store.n_items() == 0 {
+        load_history_page(list, db);
+    }
+}
 
-    // Cancel → revert radio to previous provider
-    let action_cancel = action.clone();
-    let prev = previous_provider.clone();
-    let dialog_cancel = dialog.clone();
-    cancel_btn.connect_clicked(move |_| {
-        action_cancel.set_state(&prev.to_variant());
-        dialog_cancel.close();
+/// Clickable words of a history entry, shown while `toggle` is active.
+/// Each word plays its slice of the retained recording at `audio`.
+fn word_buttons(
+    words: &[crate::local_stt::Word],
+    audio: std::path::PathBuf,
+    toggle: &gtk4::ToggleButton,
+) -> gtk4::Box {
+    let container = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    container.set_visible(false);
+    let flow = gtk4::FlowBox::new();
+    flow.set_selection_mode(gtk4::SelectionMode::None);
+    flow.set_column_spacing(0);
+    flow.set_row_spacing(0);
+    flow.set_max_children_per_line(64);
+    let note = gtk4::Label::new(Some("No audio stored for this entry."));
+    note.set_halign(gtk4::Align::Start);
+    note.set_opacity(0.6);
+    note.set_visible(false);
+    for word in words {
+        let btn = gtk4::Button::with_label(&word.text);
+        btn.add_css_class("flat");
+        btn.set_tooltip_text(Some(&format!(
+            "{:.1}s \u{2013} {:.1}s",
+            word.start_ms as f64 / 1000.0,
+            word.end_ms as f64 / 1000.0
+        )));
+        let (audio, note) = (audio.clone(), note.clone());
+        let (start_ms, end_ms) = (word.start_ms, word.end_ms);
+        btn.connect_clicked(move |_| {
+            if audio.exists() {
+                play_audio_snippet(audio.clone(), start_ms, end_ms);
+            } else {
+                note.set_visible(true);
+            }
+        });
+        flow.insert(&btn, -1);
+    }
+    container.append(&flow);
+    container.append(&note);
+    let c = container.clone();
+    toggle.connect_toggled(move |b| c.set_visible(b.is_active()));
+    container
+}
+
+/// Transcribe the recording of History entry `id` at `audio` with
+/// `provider` and store the result as a revision of the entry, listed in
+/// `revisions_box`. `button` shows the progress.
+#[allow(clippy::too_many_arguments)]
+fn rerun_history_entry(
+    retranscribe: &Retranscribe,
+    db: &Arc<Mutex<Db>>,
+    audio: &std::path::Path,
+    id: i64,
+    provider: &str,
+    label: &str,
+    button: &gtk4::MenuButton,
+    revisions_box: &gtk4::Box,
+) {
+    let run = match (retranscribe.run)(audio, Some(provider)) {
+        Ok(run) => run,
+        Err(e) => {
+            button.set_tooltip_text(Some(&e));
+            button.set_label("Failed");
+            return;
+        }
+    };
+    let audio_secs = hound::WavReader::open(audio)
+        .map(|r| r.duration() as f64 / r.spec().sample_rate.max(1) as f64)
+        .unwrap_or(0.0);
+    button.set_sensitive(false);
+    button.set_label(&format!("{label}\u{2026}"));
+    let (button, revisions_box) = (button.clone(), revisions_box.clone());
+    let (db, label) = (Arc::clone(db), label.to_string());
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        let (result, latency) = match run.rx.try_recv() {
+            Ok(SttMsg::Done(result, latency)) => (result, latency),
+            Ok(_) | Err(std::sync::mpsc::TryRecvError::Empty) => {
+                return glib::ControlFlow::Continue;
+            }
+            Err(_) => (
+                Err("Transcription stopped".into()),
+                std::time::Duration::ZERO,
+            ),
+        };
+        button.set_sensitive(true);
+        button.set_label("Re-run with\u{2026}");
+        let saved = result.and_then(|mut t| {
+            let d = db.lock().map_err(|e| e.to_string())?;
+            let rules = d
+                .accepted_replacements(t.language.as_deref())
+                .unwrap_or_default();
+            t.text = crate::corrections::apply(&t.text, &rules);
+            if let Some(commands) = crate::dictation_commands::stored(&d) {
+                t.text = crate::dictation_commands::apply(&t.text, &commands);
+            }
+            let revision = d
+                .insert_revision(id, &run.provider, &t)
+                .map_err(|e| e.to_string())?;
+            if let Err(e) = d.insert_usage(
+                Some(revision),
+                &run.provider,
+                &run.model,
+                audio_secs,
+                latency.as_millis() as u64,
+                t.text.split_whitespace().count(),
+                None,
+            ) {
+                tracing::error!("DB usage insert error: {e}");
+            }
+            Ok(t.text)
+        });
+        match saved {
+            Ok(text) => {
+                button.set_tooltip_text(None);
+                revisions_box.append(&revision_row(&label, &text));
+            }
+            Err(e) => {
+                tracing::warn!("Re-run failed: {e}");
+                button.set_tooltip_text(Some(&e));
+                button.set_label("Failed");
+            }
+        }
+        glib::ControlFlow::Break
     });
+}
 
-    // Save → persist key to DB, then switch
-    let runtime_save = Rc::clone(runtime);
-    let config_save = Arc::clone(config);
-    let db_save = Arc::clone(db);
-    let action_save = action.clone();
-    let status_save = status.clone();
-    let dialog_save = dialog.clone();
-    let preset_id = preset.id;
-    let preset_label = preset.label;
-    let preset_base_url = preset.base_url;
-    let preset_default_model = preset.default_model;
-    save_btn.connect_clicked(move |_| {
-        let key_text = key_entry.text().to_string();
-        if key_text.is_empty() {
-            return;
+/// A revision listed under its History entry: the provider that made it,
+/// its text and a copy button.
+fn revision_row(label: &str, text: &str) -> gtk4::Box {
+    let container = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    let header = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+    let title = gtk4::Label::new(Some(&format!("\u{21b3} {label}")));
+    title.set_halign(gtk4::Align::Start);
+    title.set_hexpand(true);
+    title.set_opacity(0.6);
+    let copy_btn = gtk4::Button::from_icon_name("edit-copy-symbolic");
+    copy_btn.add_css_class("flat");
+    copy_btn.set_tooltip_text(Some("Copy"));
+    let copy_text = text.to_string();
+    copy_btn.connect_clicked(move |_| {
+        if let Err(e) = crate::input::copy_to_clipboard(&copy_text) {
+            tracing::warn!("Copy failed: {e}");
         }
+    });
+    header.append(&title);
+    header.append(&copy_btn);
+    let body = gtk4::Label::new(Some(text));
+    body.set_halign(gtk4::Align::Start);
+    body.set_wrap(true);
+    body.set_selectable(true);
+    container.append(&header);
+    container.append(&body);
+    container
+}
 
-        // Persist key to DB
-        if let Ok(d) = db_save.lock() {
-            let _ = d.set_setting(&format!("api_key_{}", preset_id), &key_text);
+/// Timed segments of a history entry, shown while `toggle` is active. Each
+/// row copies its text or plays its slice of the recording at `audio`.
+fn segment_rows(
+    segments: &[crate::local_stt::Segment],
+    audio: std::path::PathBuf,
+    id: i64,
+    toggle: &gtk4::ToggleButton,
+) -> gtk4::Box {
+    let container = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    container.set_visible(false);
+    let note = gtk4::Label::new(Some("No audio stored for this entry."));
+    note.set_halign(gtk4::Align::Start);
+    note.set_opacity(0.6);
+    note.set_visible(false);
+    for seg in segments {
+        let line = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+        let span = gtk4::Label::new(Some(&format!(
+            "{:.1}s \u{2013} {:.1}s",
+            seg.start_ms as f64 / 1000.0,
+            seg.end_ms as f64 / 1000.0
+        )));
+        span.set_opacity(0.6);
+        let text = gtk4::Label::new(Some(&seg.text));
+        text.set_halign(gtk4::Align::Start);
+        text.set_hexpand(true);
+        text.set_wrap(true);
+        if let Some(confidence) = seg.confidence {
+            text.set_tooltip_text(Some(&format!("Confidence {:.0}%", confidence * 100.0)));
+        }
+        let play_btn = gtk4::Button::from_icon_name("media-playback-start-symbolic");
+        play_btn.add_css_class("flat");
+        play_btn.set_tooltip_text(Some("Play"));
+        let (audio, note_p) = (audio.clone(), note.clone());
+        let (start_ms, end_ms) = (seg.start_ms, seg.end_ms);
+        play_btn.connect_clicked(move |_| {
+            if audio.exists() {
+                play_audio_snippet(audio.clone(), start_ms, end_ms);
+            } else {
+                note_p.set_visible(true);
+            }
+        });
+        let copy_btn = gtk4::Button::from_icon_name("edit-copy-symbolic");
+        copy_btn.add_css_class("flat");
+        copy_btn.set_tooltip_text(Some("Copy"));
+        let seg_text = seg.text.clone();
+        copy_btn.connect_clicked(move |_| {
+            if let Err(e) = crate::input::copy_to_clipboard(&seg_text) {
+                tracing::warn!("Copy failed: {e}");
+            }
+        });
+        line.append(&span);
+        line.append(&text);
+        line.append(&play_btn);
+        line.append(&copy_btn);
+        container.append(&line);
+    }
+    let srt_btn = gtk4::Button::with_label("Save Subtitles\u{2026}");
+    srt_btn.add_css_class("flat");
+    srt_btn.set_halign(gtk4::Align::End);
+    let srt = crate::subtitles::to_srt(segments);
+    srt_btn.connect_clicked(move |b| {
+        if let Some(parent) = b.root().and_downcast::<gtk4::Window>() {
+            export_subtitles(&parent, id, srt.clone());
         }
+    });
+    container.append(&note);
+    container.append(&srt_btn);
+    let c = container.clone();
+    toggle.connect_toggled(move |b| c.set_visible(b.is_active()));
+    container
+}
 
-        let static_preset = config::ApiPreset {
-            id: preset_id,
-            label: preset_label,
-            base_url: preset_base_url,
-            default_model: preset_default_model,
-            needs_key: true,
+/// Ask where to save the subtitles of history entry `id` and write them there.
+fn export_subtitles(parent: &gtk4::Window, id: i64, srt: String) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Save Subtitles"),
+        Some(parent),
+        gtk4::FileChooserAction::Save,
+        Some("Save"),
+        Some("Cancel"),
+    );
+    chooser.set_current_name(&format!("transcription-{id}.srt"));
+
+    connect_chooser_response(&chooser, move |chooser, response| {
+        let Some(path) = (response == gtk4::ResponseType::Accept)
+            .then(|| chooser.file())
+            .flatten()
+            .and_then(|f| f.path())
+        else {
+            return;
         };
+        if let Err(e) = std::fs::write(&path, &srt) {
+            tracing::warn!("Subtitle export failed: {e}");
+        }
+    });
+    chooser.show();
+}
 
-        apply_preset(
-            &runtime_save,
-            &config_save,
-            &db_save,
-            &action_save,
-            &status_save,
-            &static_preset,
-            Some(key_text),
-        );
-
-        dialog_save.close();
+/// Play a whole stored recording on a background thread.
+fn play_recording(path: std::path::PathBuf) {
+    std::thread::spawn(move || {
+        use rodio::{Decoder, OutputStream, Sink};
+        if let Ok(file) = std::fs::File::open(&path)
+            && let Ok((_stream, handle)) = OutputStream::try_default()
+            && let Ok(sink) = Sink::try_new(&handle)
+            && let Ok(source) = Decoder::new(std::io::BufReader::new(file))
+        {
+            sink.append(source);
+            sink.sleep_until_end();
+        }
     });
+}
 
-    dialog.present();
+/// Lead-in and tail added around a word, since whisper's token timings are approximate.
+const SNIPPET_PADDING_MS: i64 = 150;
+
+/// Play `start_ms..end_ms` of a WAV file on a background thread.
+fn play_audio_snippet(path: std::path::PathBuf, start_ms: i64, end_ms: i64) {
+    std::thread::spawn(move || {
+        use rodio::{Decoder, OutputStream, Sink, Source};
+        let start = (start_ms - SNIPPET_PADDING_MS).max(0);
+        let len = (end_ms + SNIPPET_PADDING_MS - start).max(0);
+        if let Ok(file) = std::fs::File::open(&path)
+            && let Ok((_stream, handle)) = OutputStream::try_default()
+            && let Ok(sink) = Sink::try_new(&handle)
+            && let Ok(source) = Decoder::new(std::io::BufReader::new(file))
+        {
+            sink.append(
+                source
+                    .skip_duration(std::time::Duration::from_millis(start as u64))
+                    .take_duration(std::time::Duration::from_millis(len as u64)),
+            );
+            sink.sleep_until_end();
+        }
+    });
 }
 
-fn show_custom_api_dialog(
-    parent: &gtk4::ApplicationWindow,
-    runtime: &Rc<RefCell<RuntimeState>>,
-    db: &Arc<Mutex<Db>>,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
-    config: &Arc<Config>,
-) {
-    let previous_provider = runtime.borrow().active_provider.clone();
+/// Rebuild the list of learned replacements: pending suggestions get a
+/// one-click Accept, and every rule can be removed.
+fn refresh_replacements(container: &gtk4::Box, db: &Arc<Mutex<Db>>) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+    let rules = db
+        .lock()
+        .ok()
+        .and_then(|d| d.replacements().ok())
+        .unwrap_or_default();
+    if rules.is_empty() {
+        let empty = gtk4::Label::new(Some("Edit a transcript to suggest replacements."));
+        empty.set_halign(gtk4::Align::Start);
+        empty.set_opacity(0.6);
+        container.append(&empty);
+        return;
+    }
+    for rule in rules {
+        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        let scope = rule
+            .language
+            .as_deref()
+            .map(|l| format!(" ({})", config::language_label(l)))
+            .unwrap_or_default();
+        let label = gtk4::Label::new(Some(&format!(
+            "{} \u{2192} {}{scope}",
+            rule.original, rule.replacement
+        )));
+        label.set_halign(gtk4::Align::Start);
+        label.set_hexpand(true);
+        label.set_wrap(true);
+        if rule.accepted {
+            label.set_opacity(0.7);
+        }
+        row.append(&label);
+
+        if !rule.accepted {
+            let accept = gtk4::Button::with_label("Accept");
+            accept.add_css_class("suggested-action");
+            let (c, d) = (container.clone(), Arc::clone(db));
+            accept.connect_clicked(move |_| {
+                if let Ok(db) = d.lock() {
+                    let _ = db.accept_replacement(rule.id);
+                }
+                refresh_replacements(&c, &d);
+            });
+            row.append(&accept);
+        }
 
+        let remove = gtk4::Button::with_label(if rule.accepted { "Remove" } else { "Dismiss" });
+        remove.add_css_class("flat");
+        let (c, d) = (container.clone(), Arc::clone(db));
+        remove.connect_clicked(move |_| {
+            if let Ok(db) = d.lock() {
+                let _ = db.delete_replacement(rule.id);
+            }
+            refresh_replacements(&c, &d);
+        });
+        row.append(&remove);
+        container.append(&row);
+    }
+}
+
+fn show_usage_dialog(window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
     let dialog = gtk4::Window::builder()
-        .title("Custom API Configuration")
-        .default_width(400)
-        .default_height(220)
-        .transient_for(parent)
-        .modal(true)
+        .title("WhisperCrabs Usage")
+        .default_width(520)
+        .default_height(320)
+        .transient_for(window)
         .build();
 
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let header = gtk4::Label::new(Some("Usage per provider per month"));
+    header.add_css_class("heading");
+    vbox.append(&header);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+
     let grid = gtk4::Grid::builder()
-        .row_spacing(8)
-        .column_spacing(12)
-        .margin_top(16)
-        .margin_bottom(16)
-        .margin_start(16)
-        .margin_end(16)
+        .row_spacing(6)
+        .column_spacing(16)
         .build();
 
-    // Base URL
-    let url_label = gtk4::Label::new(Some("Base URL"));
-    url_label.set_halign(gtk4::Align::End);
-    let url_entry = gtk4::Entry::new();
-    url_entry.set_hexpand(true);
-    url_entry.set_placeholder_text(Some("https://api.example.com/v1"));
-    grid.attach(&url_label, 0, 0, 1, 1);
-    grid.attach(&url_entry, 1, 0, 2, 1);
+    let headings = [
+        "Month",
+        "Provider",
+        "Count",
+        "Minutes",
+        "Avg latency",
+        "Est. cost",
+    ];
+    for (col, title) in headings.iter().enumerate() {
+        let label = gtk4::Label::new(Some(title));
+        label.set_halign(gtk4::Align::Start);
+        label.add_css_class("dim-label");
+        grid.attach(&label, col as i32, 0, 1, 1);
+    }
 
-    // API Key
-    let key_label = gtk4::Label::new(Some("API Key"));
-    key_label.set_halign(gtk4::Align::End);
-    let key_entry = gtk4::Entry::new();
-    key_entry.set_hexpand(true);
-    key_entry.set_placeholder_text(Some("(optional)"));
-    key_entry.set_input_purpose(gtk4::InputPurpose::Password);
-    key_entry.set_visibility(false);
-    grid.attach(&key_label, 0, 1, 1, 1);
-    grid.attach(&key_entry, 1, 1, 2, 1);
+    let summaries = db
+        .lock()
+        .ok()
+        .and_then(|d| d.usage_by_month().ok())
+        .unwrap_or_default();
 
-    // Model
-    let model_label = gtk4::Label::new(Some("Model"));
-    model_label.set_halign(gtk4::Align::End);
-    let model_entry = gtk4::Entry::new();
-    model_entry.set_hexpand(true);
-    model_entry.set_placeholder_text(Some("whisper-1"));
-    grid.attach(&model_label, 0, 2, 1, 1);
-    grid.attach(&model_entry, 1, 2, 2, 1);
+    if summaries.is_empty() {
+        let empty = gtk4::Label::new(Some("No usage recorded yet."));
+        empty.set_halign(gtk4::Align::Start);
+        grid.attach(&empty, 0, 1, headings.len() as i32, 1);
+    }
 
-    // Pre-populate from DB
-    if let Ok(d) = db.lock() {
-        if let Ok(Some(url)) = d.get_setting("api_custom_url") {
-            url_entry.set_text(&url);
-        }
-        if let Ok(Some(key)) = d.get_setting("api_custom_key") {
-            key_entry.set_text(&key);
-        }
-        if let Ok(Some(model)) = d.get_setting("api_custom_model") {
-            model_entry.set_text(&model);
+    for (i, u) in summaries.iter().enumerate() {
+        let minutes = u.audio_secs / 60.0;
+        let cost = config::estimated_cost(&u.provider, minutes);
+        let cells = [
+            u.month.clone(),
+            u.provider.clone(),
+            u.transcriptions.to_string(),
+            format!("{minutes:.1}"),
+            format!("{:.1} s", u.avg_latency_ms / 1000.0),
+            format!("${cost:.2}"),
+        ];
+        for (col, text) in cells.iter().enumerate() {
+            let label = gtk4::Label::new(Some(text));
+            label.set_halign(gtk4::Align::Start);
+            grid.attach(&label, col as i32, i as i32 + 1, 1, 1);
         }
     }
 
-    // Buttons
-    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
-    btn_box.set_halign(gtk4::Align::End);
-    let cancel_btn = gtk4::Button::with_label("Cancel");
-    let save_btn = gtk4::Button::with_label("Save");
-    btn_box.append(&cancel_btn);
-    btn_box.append(&save_btn);
-    grid.attach(&btn_box, 0, 3, 3, 1);
+    scroll.set_child(Some(&grid));
+    vbox.append(&scroll);
 
-    dialog.set_child(Some(&grid));
+    let note = gtk4::Label::new(Some(
+        "Costs are estimates from published per-minute prices. Local models are free.",
+    ));
+    note.set_wrap(true);
+    note.set_opacity(0.6);
+    vbox.append(&note);
 
-    // Cancel → revert radio to previous provider
-    let action_cancel = action.clone();
-    let prev = previous_provider.clone();
-    let dialog_cancel = dialog.clone();
-    cancel_btn.connect_clicked(move |_| {
-        action_cancel.set_state(&prev.to_variant());
-        dialog_cancel.close();
-    });
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
 
-    // Save → persist + switch
-    let runtime_save = Rc::clone(runtime);
-    let db_save = Arc::clone(db);
-    let config_save = Arc::clone(config);
-    let action_save = action.clone();
-    let status_save = status.clone();
-    let dialog_save = dialog.clone();
-    save_btn.connect_clicked(move |_| {
-        let url = url_entry.text().to_string();
-        let key_text = key_entry.text().to_string();
-        let model = model_entry.text().to_string();
+/// Words dictated, audio minutes, average latency and the provider breakdown
+/// per day, week or month, with all-time totals for local models and APIs.
+fn show_stats_dialog(window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+    use crate::db::StatsPeriod;
 
-        if url.is_empty() || model.is_empty() {
-            return; // require at least URL and model
+    let dialog = gtk4::Window::builder()
+        .title("WhisperCrabs Stats")
+        .default_width(560)
+        .default_height(380)
+        .transient_for(window)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    let header = gtk4::Label::new(Some("Dictation statistics"));
+    header.add_css_class("heading");
+    vbox.append(&header);
+
+    // All-time totals, then local models against API providers
+    let all = db
+        .lock()
+        .ok()
+        .and_then(|d| d.stats(StatsPeriod::Month).ok())
+        .unwrap_or_default();
+    let is_local = |provider: &str| {
+        config::find_local_model(provider).is_some() || config::find_vosk_model(provider).is_some()
+    };
+    let groups = [
+        ("All time", all.iter().collect::<Vec<_>>()),
+        (
+            "Local models",
+            all.iter().filter(|s| is_local(&s.provider)).collect(),
+        ),
+        (
+            "API providers",
+            all.iter().filter(|s| !is_local(&s.provider)).collect(),
+        ),
+    ];
+    for (label, stats) in groups {
+        if let Some(summary) = stats_summary(&stats) {
+            let line = gtk4::Label::new(Some(&format!("{label}: {summary}")));
+            line.set_halign(gtk4::Align::Start);
+            vbox.append(&line);
         }
+    }
 
-        let api_key = if key_text.is_empty() {
-            None
-        } else {
-            Some(key_text.clone())
-        };
+    // Speaking rate and loudness of the latest dictations, with a hint if due
+    let recent = db
+        .lock()
+        .ok()
+        .and_then(|d| d.speech_samples(crate::coaching::RECENT).ok())
+        .unwrap_or_default();
+    if let Some(summary) = recent_speech_summary(&recent) {
+        let line = gtk4::Label::new(Some(&summary));
+        line.set_halign(gtk4::Align::Start);
+        vbox.append(&line);
+    }
+    if let Some(hint) = crate::coaching::hint(&recent) {
+        let line = gtk4::Label::new(Some(hint.message()));
+        line.set_halign(gtk4::Align::Start);
+        line.add_css_class("dim-label");
+        vbox.append(&line);
+    }
 
-        // Persist to DB
-        if let Ok(d) = db_save.lock() {
-            let _ = d.set_setting("api_custom_url", &url);
-            if let Some(ref k) = api_key {
-                let _ = d.set_setting("api_custom_key", k);
+    let labels: Vec<&str> = StatsPeriod::ALL.iter().map(|p| p.label()).collect();
+    let period = gtk4::DropDown::from_strings(&labels);
+    period.set_halign(gtk4::Align::End);
+    period.set_selected(1);
+    vbox.append(&period);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let grid = gtk4::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(16)
+        .build();
+    scroll.set_child(Some(&grid));
+    vbox.append(&scroll);
+
+    let fill = {
+        let (grid, db) = (grid.clone(), Arc::clone(db));
+        move |period: StatsPeriod| {
+            while let Some(child) = grid.first_child() {
+                grid.remove(&child);
+            }
+            let headings = [
+                "Period",
+                "Provider",
+                "Sessions",
+                "Words",
+                "WPM",
+                "Minutes",
+                "Avg latency",
+            ];
+            for (col, title) in headings.iter().enumerate() {
+                let label = gtk4::Label::new(Some(title));
+                label.set_halign(gtk4::Align::Start);
+                label.add_css_class("dim-label");
+                grid.attach(&label, col as i32, 0, 1, 1);
+            }
+            let stats = db
+                .lock()
+                .ok()
+                .and_then(|d| d.stats(period).ok())
+                .unwrap_or_default();
+            if stats.is_empty() {
+                let empty = gtk4::Label::new(Some("No dictations recorded yet."));
+                empty.set_halign(gtk4::Align::Start);
+                grid.attach(&empty, 0, 1, headings.len() as i32, 1);
+            }
+            for (i, s) in stats.iter().enumerate() {
+                // The period is named once, on its first provider
+                let first = i == 0 || stats[i - 1].period != s.period;
+                let cells = [
+                    if first {
+                        s.period.clone()
+                    } else {
+                        String::new()
+                    },
+                    provider_label(&s.provider),
+                    s.sessions.to_string(),
+                    s.words.to_string(),
+                    s.words_per_minute()
+                        .map_or_else(|| "\u{2014}".to_string(), |wpm| format!("{wpm:.0}")),
+                    format!("{:.1}", s.audio_secs / 60.0),
+                    format!("{:.1} s", s.avg_latency_ms / 1000.0),
+                ];
+                for (col, text) in cells.iter().enumerate() {
+                    let label = gtk4::Label::new(Some(text));
+                    label.set_halign(gtk4::Align::Start);
+                    grid.attach(&label, col as i32, i as i32 + 1, 1, 1);
+                }
             }
-            let _ = d.set_setting("api_custom_model", &model);
-            let _ = d.set_setting("transcription_mode", "custom");
         }
+    };
+    fill(StatsPeriod::ALL[period.selected() as usize]);
+    period.connect_selected_notify(move |dd| {
+        fill(StatsPeriod::ALL[(dd.selected() as usize).min(StatsPeriod::ALL.len() - 1)]);
+    });
 
-        // Update RuntimeState
-        {
-            let mut rt = runtime_save.borrow_mut();
-            rt.active_service = TranscriptionService::Api;
-            rt.active_provider = "custom".to_string();
-            rt.api_base_url = url;
-            rt.api_key = api_key;
-            rt.api_model = model;
-            rt.local_whisper = None;
-        }
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// The coaching hint to show after a dictation, if tips are on, the recent
+/// dictations call for one and none was shown in the last
+/// [`crate::coaching::HINT_INTERVAL`].
+fn coaching_tip(runtime: &Rc<RefCell<RuntimeState>>, db: &Arc<Mutex<Db>>) -> Option<&'static str> {
+    let mut rt = runtime.borrow_mut();
+    if !rt.coaching
+        || rt
+            .last_tip
+            .is_some_and(|t| t.elapsed() < crate::coaching::HINT_INTERVAL)
+    {
+        return None;
+    }
+    let recent = db
+        .lock()
+        .ok()?
+        .speech_samples(crate::coaching::RECENT)
+        .ok()?;
+    let hint = crate::coaching::hint(&recent)?;
+    rt.last_tip = Some(std::time::Instant::now());
+    Some(hint.message())
+}
+
+/// "1234 words · 56.7 min · 89 sessions · avg 1.2 s" over `stats`, `None`
+/// when there were no sessions.
+fn stats_summary(stats: &[&crate::db::DictationStats]) -> Option<String> {
+    let sessions: i64 = stats.iter().map(|s| s.sessions).sum();
+    if sessions == 0 {
+        return None;
+    }
+    let words: i64 = stats.iter().map(|s| s.words).sum();
+    let minutes = stats.iter().map(|s| s.audio_secs).sum::<f64>() / 60.0;
+    let latency_ms = stats
+        .iter()
+        .map(|s| s.avg_latency_ms * s.sessions as f64)
+        .sum::<f64>()
+        / sessions as f64;
+    let speech_secs = stats.iter().map(|s| s.speech_secs).sum::<f64>();
+    let rate = crate::coaching::words_per_minute(words, speech_secs)
+        .map(|wpm| format!(" \u{00b7} {wpm:.0} wpm"))
+        .unwrap_or_default();
+    Some(format!(
+        "{words} words \u{00b7} {minutes:.1} min \u{00b7} {sessions} sessions \u{00b7} avg {:.1} s{rate}",
+        latency_ms / 1000.0
+    ))
+}
+
+/// "Last 10 dictations: 152 wpm · -28 dBFS" over `recent`, `None` when
+/// neither could be measured.
+fn recent_speech_summary(recent: &[crate::coaching::SpeechSample]) -> Option<String> {
+    let average = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    let wpm = average(
+        recent
+            .iter()
+            .filter_map(crate::coaching::SpeechSample::words_per_minute)
+            .collect(),
+    );
+    let level = average(recent.iter().filter_map(|s| s.level_db).collect());
+    let parts: Vec<String> = [
+        wpm.map(|w| format!("{w:.0} wpm")),
+        level.map(|l| format!("{l:.0} dBFS")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| {
+        format!(
+            "Last {} dictations: {}",
+            recent.len(),
+            parts.join(" \u{00b7} ")
+        )
+    })
+}
 
-        // Delete model file to free disk space
-        delete_all_local_models(&config_save.models_dir);
+/// What a dry-run dictation would have done, one action per row.
+fn show_dry_run_window(parent: &gtk4::ApplicationWindow, plan: &[crate::dryrun::Planned]) {
+    let dialog = gtk4::Window::builder()
+        .title("Dry Run")
+        .default_width(420)
+        .transient_for(parent)
+        .build();
 
-        action_save.set_state(&"custom".to_variant());
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
 
-        show_status(&status_save, "Custom API mode");
-        let st = status_save.clone();
-        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
-            hide_status(&st);
-        });
+    let header = gtk4::Label::new(Some("Nothing was delivered. This dictation would:"));
+    header.set_xalign(0.0);
+    header.add_css_class("heading");
+    vbox.append(&header);
 
-        dialog_save.close();
-    });
+    for step in plan {
+        let action = gtk4::Label::new(Some(step.action));
+        action.set_xalign(0.0);
+        action.add_css_class("dim-label");
+        vbox.append(&action);
+        let detail = gtk4::Label::new(Some(&step.detail));
+        detail.set_xalign(0.0);
+        detail.set_wrap(true);
+        detail.set_selectable(true);
+        detail.set_margin_start(12);
+        vbox.append(&detail);
+    }
 
+    let close_btn = gtk4::Button::with_label("Close");
+    close_btn.set_halign(gtk4::Align::End);
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+    vbox.append(&close_btn);
+
+    dialog.set_child(Some(&vbox));
     dialog.present();
 }
 
-fn switch_to_local(
-    runtime: &Rc<RefCell<RuntimeState>>,
-    config: &Arc<Config>,
+/// A transcript held in the review window.
+struct ReviewedTranscript {
+    /// The text to copy, after the profile's template.
+    copied: String,
+    /// The History entry a fix is saved to, if it holds the text as copied.
+    history_id: Option<i64>,
+    /// The transcript as stored in History.
+    original: String,
+    language: Option<String>,
+    provider: String,
+    /// The speech model was unsure of it (see [`crate::confidence`]).
+    low_confidence: bool,
+}
+
+/// Let a finished transcript be edited before it is copied (and pasted) or
+/// discarded, see [`crate::review`].
+fn show_review_window(
+    parent: &gtk4::ApplicationWindow,
     db: &Arc<Mutex<Db>>,
-    action: &gtk4::gio::SimpleAction,
+    runtime: &Rc<RefCell<RuntimeState>>,
     status: &gtk4::Label,
-    local_preset: &config::LocalModelPreset,
+    reviewed: ReviewedTranscript,
 ) {
-    // Delete any previously loaded model files from other presets
-    {
-        let rt = runtime.borrow();
-        if rt.active_service == TranscriptionService::Local
-            && let Some(old_model) = config::find_local_model(&rt.active_provider)
-            && old_model.id != local_preset.id
+    let dialog = gtk4::Window::builder()
+        .title("Review Transcript")
+        .default_width(420)
+        .default_height(240)
+        .transient_for(parent)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+
+    if reviewed.low_confidence {
+        let hint = gtk4::Label::new(Some(
+            "Low confidence \u{2014} check the text before copying.",
+        ));
+        hint.set_xalign(0.0);
+        hint.add_css_class("dim-label");
+        vbox.append(&hint);
+    }
+    let editor = gtk4::TextView::new();
+    editor.set_wrap_mode(gtk4::WrapMode::WordChar);
+    editor.buffer().set_text(&reviewed.copied);
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_child(Some(&editor));
+    vbox.append(&scroll);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let discard_btn = gtk4::Button::with_label("Discard");
+    let copy_btn = gtk4::Button::with_label("Copy");
+    let paste_btn = gtk4::Button::with_label("Copy & Paste");
+    paste_btn.add_css_class("suggested-action");
+    btn_box.append(&discard_btn);
+    btn_box.append(&copy_btn);
+    btn_box.append(&paste_btn);
+    vbox.append(&btn_box);
+
+    let reviewed = Rc::new(reviewed);
+    let (db, runtime, status) = (Arc::clone(db), Rc::clone(runtime), status.clone());
+    let dialog_done = dialog.clone();
+    let finish = Rc::new(move |choice: crate::review::Choice| {
+        let buffer = editor.buffer();
+        let edited = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        // Closing first hands focus back to the app the text is pasted into
+        dialog_done.close();
+        let text = match crate::review::final_text(&edited) {
+            Some(text) if choice != crate::review::Choice::Discard => text,
+            _ => {
+                show_status(&status, "Discarded");
+                let st = status.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+                    hide_status(&st)
+                });
+                return;
+            }
+        };
+        if let (Some(id), Some(fixed)) = (
+            reviewed.history_id,
+            crate::review::correction(&reviewed.original, &text),
+        ) && let Ok(d) = db.lock()
         {
-            let old_path = config.models_dir.join(old_model.file_name);
-            if old_path.exists()
-                && let Err(e) = std::fs::remove_file(&old_path)
-            {
-                eprintln!("Failed to delete old model file: {e}");
+            if let Err(e) = d.update_text(id, &fixed) {
+                tracing::error!("History update error: {e}");
+            }
+            let learn = d
+                .get_setting("learn_corrections")
+                .ok()
+                .flatten()
+                .is_none_or(|v| v != "false");
+            if learn {
+                for (from, to) in crate::corrections::suggestions(&reviewed.original, &fixed) {
+                    let _ = d.suggest_replacement(&from, &to, reviewed.language.as_deref());
+                }
             }
         }
-    }
+        if let Err(e) = crate::input::copy_to_clipboard(&text) {
+            tracing::error!("Clipboard error: {e}");
+            show_status(&status, "Copy failed");
+        } else {
+            log_transcript(
+                &runtime.borrow().session_log,
+                &db,
+                &text,
+                &reviewed.provider,
+            );
+            show_status(&status, "Copied!");
+            if choice == crate::review::Choice::CopyAndPaste {
+                paste_into_focused_app(text, std::time::Duration::from_millis(300));
+            }
+        }
+        let st = status.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || hide_status(&st));
+    });
 
-    // Set active service immediately so the menu reflects the choice
-    {
-        let mut rt = runtime.borrow_mut();
-        rt.active_service = TranscriptionService::Local;
-        rt.active_provider = local_preset.id.to_string();
-        rt.local_whisper = None;
+    for (button, choice) in [
+        (&discard_btn, crate::review::Choice::Discard),
+        (&copy_btn, crate::review::Choice::Copy),
+        (&paste_btn, crate::review::Choice::CopyAndPaste),
+    ] {
+        let finish = Rc::clone(&finish);
+        button.connect_clicked(move |_| finish(choice));
     }
-    action.set_state(&local_preset.id.to_variant());
 
-    // Persist to DB
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+    paste_btn.grab_focus();
+}
+
+/// Add a delivered transcript to the session log, dropping the oldest
+/// entries beyond [`crate::session_log::MAX_ENTRIES`], announce it on
+/// D-Bus and run the hooks.
+fn log_transcript(log: &gtk4::gio::ListStore, db: &Arc<Mutex<Db>>, text: &str, provider: &str) {
+    crate::dbus::transcription_done(text, provider);
+    let now = chrono::Local::now();
     if let Ok(d) = db.lock() {
-        let _ = d.set_setting("transcription_mode", local_preset.id);
+        crate::hooks::fire(
+            crate::hooks::stored(&d),
+            crate::hooks::Event::new(now, text, provider),
+        );
     }
-
-    let model_path = config.models_dir.join(local_preset.file_name);
-    if model_path.exists() {
-        load_whisper_model(runtime, &model_path, action, status);
-    } else {
-        let url = config::model_url(local_preset.file_name);
-        download_and_load_model(runtime, &model_path, &url, action, status);
+    let entry = crate::session_log::Entry::new(now, text, provider);
+    log.append(&glib::BoxedAnyObject::new(entry));
+    let excess = crate::session_log::excess(log.n_items());
+    if excess > 0 {
+        log.splice(0, excess, &[] as &[glib::Object]);
     }
 }
 
-fn load_whisper_model(
-    runtime: &Rc<RefCell<RuntimeState>>,
-    model_path: &std::path::Path,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
-) {
-    show_status(status, "Loading model...");
+/// Lines of the log file shown in **Show Logs**.
+const LOG_WINDOW_LINES: usize = 2000;
 
-    let model_path = model_path.to_path_buf();
-    let (tx, rx) = std::sync::mpsc::channel::<Result<Arc<LocalWhisper>, String>>();
+/// The end of the log file (see [`crate::log`]), with **Refresh** to read
+/// it again and **Copy All** for a bug report.
+fn show_logs_window(parent: &gtk4::ApplicationWindow) {
+    let dialog = gtk4::Window::builder()
+        .title("Logs")
+        .default_width(720)
+        .default_height(480)
+        .transient_for(parent)
+        .build();
 
-    std::thread::spawn(move || {
-        let result = LocalWhisper::new(&model_path).map(Arc::new);
-        let _ = tx.send(result);
-    });
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let path = crate::log::log_path();
+    let location = gtk4::Label::new(Some(&path.display().to_string()));
+    location.set_xalign(0.0);
+    location.set_selectable(true);
+    location.add_css_class("dim-label");
+    vbox.append(&location);
+
+    let view = gtk4::TextView::new();
+    view.set_editable(false);
+    view.set_monospace(true);
+    view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_child(Some(&view));
+    vbox.append(&scroll);
 
-    let runtime_c = Rc::clone(runtime);
-    let action_c = action.clone();
-    let st = status.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-        match rx.try_recv() {
-            Ok(Ok(whisper)) => {
-                runtime_c.borrow_mut().local_whisper = Some(whisper);
-                show_status(&st, "Local mode ready");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
-            }
-            Ok(Err(e)) => {
-                eprintln!("Failed to load whisper model: {e}");
-                // Revert to default API provider
-                {
-                    let mut rt = runtime_c.borrow_mut();
-                    rt.active_service = TranscriptionService::Api;
-                    rt.active_provider = "groq".to_string();
-                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
-                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
-                }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Model load failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
-            Err(_) => {
-                {
-                    let mut rt = runtime_c.borrow_mut();
-                    rt.active_service = TranscriptionService::Api;
-                    rt.active_provider = "groq".to_string();
-                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
-                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+    let load = {
+        let (view, scroll) = (view.clone(), scroll.clone());
+        move || {
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) if !text.trim().is_empty() => {
+                    crate::log::tail(&text, LOG_WINDOW_LINES).to_string()
                 }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Model load failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
-            }
+                Ok(_) => "Nothing logged yet.".to_string(),
+                Err(e) => format!("Can't read {}: {e}", path.display()),
+            };
+            view.buffer().set_text(&text);
+            // Newest lines at the bottom, in view
+            let adjustment = scroll.vadjustment();
+            glib::idle_add_local_once(move || {
+                adjustment.set_value(adjustment.upper() - adjustment.page_size());
+            });
+        }
+    };
+    load();
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let refresh_btn = gtk4::Button::with_label("Refresh");
+    refresh_btn.connect_clicked(move |_| load());
+    buttons.append(&refresh_btn);
+    let copy_btn = gtk4::Button::with_label("Copy All");
+    let view_copy = view.clone();
+    copy_btn.connect_clicked(move |_| {
+        let buffer = view_copy.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        if let Err(e) = crate::input::copy_to_clipboard(&text) {
+            tracing::warn!("Copy failed: {e}");
         }
     });
+    buttons.append(&copy_btn);
+    let close_btn = gtk4::Button::with_label("Close");
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+    buttons.append(&close_btn);
+    vbox.append(&buttons);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
 }
 
-/// Download progress messages sent from the background thread
-enum DownloadMsg {
-    Progress(u64, Option<u64>), // downloaded, total
-    StepLabel(String),          // update the label text
-    Done,
-    Error(String),
+/// This session's transcripts, oldest first, each with a copy button. The
+/// list follows `log`, so new transcripts appear while the window is open.
+fn show_session_log_window(parent: &gtk4::ApplicationWindow, log: &gtk4::gio::ListStore) {
+    let dialog = gtk4::Window::builder()
+        .title("Session Log")
+        .default_width(420)
+        .default_height(360)
+        .transient_for(parent)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let empty = gtk4::Label::new(Some("Nothing dictated since WhisperCrabs started."));
+    empty.add_css_class("dim-label");
+    empty.set_vexpand(true);
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.bind_model(Some(log), |item| {
+        let entry = item
+            .downcast_ref::<glib::BoxedAnyObject>()
+            .expect("session log holds entries")
+            .borrow::<crate::session_log::Entry>()
+            .clone();
+        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+        row.set_margin_top(4);
+        row.set_margin_bottom(4);
+        let texts = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+        texts.set_hexpand(true);
+        let heading = gtk4::Label::new(Some(&entry.heading()));
+        heading.set_xalign(0.0);
+        heading.add_css_class("dim-label");
+        texts.append(&heading);
+        let text = gtk4::Label::new(Some(&entry.text));
+        text.set_xalign(0.0);
+        text.set_wrap(true);
+        text.set_selectable(true);
+        texts.append(&text);
+        row.append(&texts);
+        let copy_btn = gtk4::Button::from_icon_name("edit-copy-symbolic");
+        copy_btn.set_valign(gtk4::Align::Start);
+        copy_btn.set_tooltip_text(Some("Copy"));
+        copy_btn.connect_clicked(move |button| {
+            if let Err(e) = crate::input::copy_to_clipboard(&entry.text) {
+                tracing::warn!("Copy failed: {e}");
+                return;
+            }
+            button.set_icon_name("object-select-symbolic");
+            let button = button.clone();
+            glib::timeout_add_local_once(std::time::Duration::from_secs(1), move || {
+                button.set_icon_name("edit-copy-symbolic");
+            });
+        });
+        row.append(&copy_btn);
+        row.upcast()
+    });
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_child(Some(&list));
+
+    let show_list = {
+        let (scroll, empty) = (scroll.clone(), empty.clone());
+        move |log: &gtk4::gio::ListStore| {
+            let any = log.n_items() > 0;
+            scroll.set_visible(any);
+            empty.set_visible(!any);
+        }
+    };
+    show_list(log);
+    vbox.append(&empty);
+    vbox.append(&scroll);
+
+    // Keep the newest transcript in view
+    let adjustment = scroll.vadjustment();
+    adjustment.connect_upper_notify(|adj| adj.set_value(adj.upper() - adj.page_size()));
+    let handler = RefCell::new(Some(
+        log.connect_items_changed(move |log, _, _, _| show_list(log)),
+    ));
+    let log_c = log.clone();
+    dialog.connect_destroy(move |_| {
+        if let Some(handler) = handler.borrow_mut().take() {
+            log_c.disconnect(handler);
+        }
+    });
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    buttons.set_halign(gtk4::Align::End);
+    let copy_all_btn = gtk4::Button::with_label("Copy All");
+    let log_copy = log.clone();
+    copy_all_btn.connect_clicked(move |_| {
+        let entries: Vec<crate::session_log::Entry> = (0..log_copy.n_items())
+            .filter_map(|i| log_copy.item(i).and_downcast::<glib::BoxedAnyObject>())
+            .map(|e| e.borrow::<crate::session_log::Entry>().clone())
+            .collect();
+        if let Err(e) = crate::input::copy_to_clipboard(&crate::session_log::joined(&entries)) {
+            tracing::warn!("Copy failed: {e}");
+        }
+    });
+    buttons.append(&copy_all_btn);
+    let clear_btn = gtk4::Button::with_label("Clear");
+    let log_clear = log.clone();
+    clear_btn.connect_clicked(move |_| log_clear.remove_all());
+    buttons.append(&clear_btn);
+    let close_btn = gtk4::Button::with_label("Close");
+    let dialog_close = dialog.clone();
+    close_btn.connect_clicked(move |_| dialog_close.close());
+    buttons.append(&close_btn);
+    vbox.append(&buttons);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
 }
 
-fn download_and_load_model(
-    runtime: &Rc<RefCell<RuntimeState>>,
-    model_path: &std::path::Path,
-    url: &str,
-    action: &gtk4::gio::SimpleAction,
-    status: &gtk4::Label,
+/// Run `sync` now and on every state change of `action` until `window` closes.
+fn follow_action_state(
+    window: &gtk4::Window,
+    action: &gtk4::gio::Action,
+    sync: impl Fn(&gtk4::gio::Action) + 'static,
 ) {
-    runtime.borrow_mut().downloading = true;
+    sync(action);
+    let handler = RefCell::new(Some(action.connect_state_notify(sync)));
+    let action = action.clone();
+    window.connect_destroy(move |_| {
+        if let Some(handler) = handler.borrow_mut().take() {
+            action.disconnect(handler);
+        }
+    });
+}
 
-    show_status(status, "Downloading model...");
+/// A drop-down for the string-stateful action `app.<name>`: it shows the
+/// action's state and activates the action with the chosen value, so a
+/// choice is applied and persisted exactly as from the menu.
+fn action_dropdown(
+    app: &gtk4::Application,
+    window: &gtk4::Window,
+    name: &str,
+    choices: Vec<(String, String)>,
+) -> gtk4::DropDown {
+    let labels: Vec<&str> = choices.iter().map(|(_, label)| label.as_str()).collect();
+    let dropdown = gtk4::DropDown::from_strings(&labels);
+    dropdown.set_hexpand(true);
+    let Some(action) = app.lookup_action(name) else {
+        dropdown.set_sensitive(false);
+        return dropdown;
+    };
+    let choices = Rc::new(choices);
+    let state_of = |action: &gtk4::gio::Action| {
+        action
+            .state()
+            .and_then(|v| v.get::<String>())
+            .unwrap_or_default()
+    };
+    let sync = {
+        let (dropdown, choices) = (dropdown.clone(), Rc::clone(&choices));
+        Rc::new(move |action: &gtk4::gio::Action| {
+            dropdown.set_selected(
+                crate::preferences::choice_index(&choices, &state_of(action))
+                    .unwrap_or(gtk4::INVALID_LIST_POSITION),
+            );
+        })
+    };
+    let sync_c = Rc::clone(&sync);
+    follow_action_state(window, &action, move |a| sync_c(a));
+    dropdown.connect_selected_notify(move |dropdown| {
+        let Some((value, _)) = choices.get(dropdown.selected() as usize) else {
+            return;
+        };
+        if state_of(&action) != *value {
+            action.activate(Some(&value.to_variant()));
+            // A refused or pending choice (a dialog still open) shows the state as is
+            sync(&action);
+        }
+    });
+    dropdown
+}
 
-    let url = url.to_string();
-    let model_path = model_path.to_path_buf();
-    let loaded_model_path = model_path.clone();
-    let part_path = model_path.with_extension("bin.part");
+/// A switch for the boolean-stateful action `app.<name>`; flipping it
+/// activates the action, which may refuse (e.g. a missing tool).
+fn action_switch(app: &gtk4::Application, window: &gtk4::Window, name: &str) -> gtk4::Switch {
+    let switch = gtk4::Switch::new();
+    switch.set_valign(gtk4::Align::Center);
+    switch.set_halign(gtk4::Align::End);
+    let Some(action) = app.lookup_action(name) else {
+        switch.set_sensitive(false);
+        return switch;
+    };
+    let state_of = |action: &gtk4::gio::Action| {
+        action
+            .state()
+            .and_then(|v| v.get::<bool>())
+            .unwrap_or(false)
+    };
+    let switch_c = switch.clone();
+    follow_action_state(window, &action, move |a| switch_c.set_active(state_of(a)));
+    switch.connect_active_notify(move |switch| {
+        if switch.is_active() != state_of(&action) {
+            action.activate(None);
+            switch.set_active(state_of(&action));
+        }
+    });
+    switch
+}
 
-    let (tx, rx) = std::sync::mpsc::channel::<DownloadMsg>();
+/// A labelled row of a Preferences page.
+/// Save the allowlist of players paused while recording; empty clears it.
+fn save_pause_media_players(db: &Arc<Mutex<Db>>, text: &str) {
+    let players = crate::media::parse_players(text);
+    if let Ok(d) = db.lock() {
+        let _ = if players.is_empty() {
+            d.delete_setting(crate::media::PLAYERS_SETTING)
+        } else {
+            d.set_setting(crate::media::PLAYERS_SETTING, &players.join(", "))
+        };
+    }
+}
 
-    std::thread::spawn(move || {
-        let result = (|| -> Result<(), String> {
-            let resp = reqwest::blocking::Client::new()
-                .get(&url)
-                .send()
-                .map_err(|e| format!("Download request failed: {e}"))?;
+fn preferences_row(grid: &gtk4::Grid, row: i32, label: &str, widget: &impl IsA<gtk4::Widget>) {
+    let label = gtk4::Label::new(Some(label));
+    label.set_xalign(0.0);
+    grid.attach(&label, 0, row, 1, 1);
+    grid.attach(widget, 1, row, 1, 1);
+}
 
-            if !resp.status().is_success() {
-                return Err(format!("Download failed: HTTP {}", resp.status()));
-            }
+fn preferences_page(notebook: &gtk4::Notebook, title: &str) -> gtk4::Grid {
+    let grid = gtk4::Grid::builder()
+        .row_spacing(8)
+        .column_spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    notebook.append_page(&grid, Some(&gtk4::Label::new(Some(title))));
+    grid
+}
 
-            let total = resp.content_length();
-            let mut downloaded: u64 = 0;
+/// Preferences: the settings of the right-click menu and a few that were
+/// only reachable through environment variables, in one window. Every
+/// control drives an app action, so changes apply and persist right away.
+fn show_preferences_window(
+    app: &gtk4::Application,
+    parent: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+    db: &Arc<Mutex<Db>>,
+    mode_action: &gtk4::gio::SimpleAction,
+    status: &gtk4::Label,
+) {
+    let window = gtk4::Window::builder()
+        .title("Preferences")
+        .default_width(480)
+        .transient_for(parent)
+        .build();
+    let notebook = gtk4::Notebook::new();
+    let w = &window;
+    let strings = |items: &[(&str, &str)]| -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(v, l)| (v.to_string(), l.to_string()))
+            .collect()
+    };
 
-            let mut file = {
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::OpenOptionsExt;
-                    std::fs::OpenOptions::new()
-                        .write(true)
-                        .create(true)
-                        .truncate(true)
-                        .mode(0o600)
-                        .open(&part_path)
-                        .map_err(|e| format!("Failed to create file: {e}"))?
-                }
-                #[cfg(not(unix))]
-                {
-                    std::fs::File::create(&part_path)
-                        .map_err(|e| format!("Failed to create file: {e}"))?
+    // --- Transcription: provider, key, model and language ---
+    let page = preferences_page(&notebook, "Transcription");
+    let mut providers = vec![(
+        crate::auto::AUTO_PROVIDER.to_string(),
+        "Auto (fastest)".to_string(),
+    )];
+    providers.extend(
+        config::API_PRESETS
+            .iter()
+            .map(|p| (p.id.to_string(), p.label.to_string())),
+    );
+    providers.push(("custom".to_string(), "Custom API...".to_string()));
+    providers.extend(
+        config::LOCAL_MODEL_PRESETS
+            .iter()
+            .map(|m| (m.id.to_string(), format!("{} ({})", m.label, m.size_label))),
+    );
+    if crate::vosk_stt::AVAILABLE {
+        providers.extend(
+            config::VOSK_MODEL_PRESETS
+                .iter()
+                .map(|m| (m.id.to_string(), format!("{} ({})", m.label, m.size_label))),
+        );
+    }
+    providers.push((
+        config::CUSTOM_LOCAL_MODEL.to_string(),
+        "Custom model...".to_string(),
+    ));
+    providers.retain(|(id, _)| {
+        let url = (id == "custom").then_some("http://localhost");
+        config.policy.allows(id, url)
+    });
+    preferences_row(
+        &page,
+        0,
+        "Provider",
+        &action_dropdown(app, w, "transcription-mode", providers),
+    );
+
+    let key_btn = gtk4::Button::with_label("Change API Key...");
+    key_btn.set_halign(gtk4::Align::Start);
+    preferences_row(&page, 1, "API key", &key_btn);
+
+    let model_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    let model_entry = gtk4::Entry::new();
+    model_entry.set_hexpand(true);
+    let model_btn = gtk4::Button::with_label("Apply");
+    model_row.append(&model_entry);
+    model_row.append(&model_btn);
+    preferences_row(&page, 2, "API model", &model_row);
+
+    let languages = config::STT_LANGUAGES
+        .iter()
+        .map(|(code, label)| (code.to_string(), label.to_string()))
+        .collect();
+    let language = action_dropdown(app, w, "stt-language", languages);
+    preferences_row(&page, 3, "Language", &language);
+
+    let supports = gtk4::Label::new(None);
+    supports.set_halign(gtk4::Align::Start);
+    supports.set_wrap(true);
+    supports.set_opacity(0.6);
+    preferences_row(&page, 4, "Supports", &supports);
+
+    // The key, model and language rows follow the active provider
+    if let Some(mode) = app.lookup_action("transcription-mode") {
+        let (db, key_btn, model_entry, model_btn) = (
+            Arc::clone(db),
+            key_btn.clone(),
+            model_entry.clone(),
+            model_btn.clone(),
+        );
+        follow_action_state(w, &mode, move |mode| {
+            let provider = mode
+                .state()
+                .and_then(|v| v.get::<String>())
+                .unwrap_or_default();
+            let preset = config::find_preset(&provider);
+            key_btn.set_sensitive(preset.is_some_and(|p| p.needs_key));
+            let (stored, default) = match (preset, provider.as_str()) {
+                (Some(p), _) => (
+                    db.lock().ok().and_then(|d| {
+                        d.get_setting(&crate::preferences::model_setting(p.id))
+                            .ok()
+                            .flatten()
+                    }),
+                    p.default_model,
+                ),
+                (None, "custom") => (
+                    db.lock()
+                        .ok()
+                        .and_then(|d| d.get_setting("api_custom_model").ok().flatten()),
+                    "",
+                ),
+                _ => (None, ""),
+            };
+            let editable = preset.is_some() || provider == "custom";
+            model_entry.set_text(stored.as_deref().unwrap_or_default());
+            model_entry.set_placeholder_text(Some(if editable {
+                default
+            } else {
+                "Not used by this provider"
+            }));
+            model_entry.set_sensitive(editable);
+            model_btn.set_sensitive(editable);
+
+            // API providers: only what the capability registry allows
+            let caps = editable.then(|| {
+                let path = (provider == "custom")
+                    .then(|| {
+                        db.lock()
+                            .ok()
+                            .and_then(|d| d.get_setting("api_custom_path").ok().flatten())
+                    })
+                    .flatten()
+                    .unwrap_or_default();
+                crate::capabilities::for_provider(&provider, &path)
+            });
+            let pick_language = match caps {
+                Some(caps) => caps.language,
+                None => config::find_local_model(&provider)
+                    .map_or(config::find_vosk_model(&provider).is_none(), |m| {
+                        m.multilingual
+                    }),
+            };
+            language.set_sensitive(pick_language);
+            language.set_tooltip_text((!pick_language).then_some(
+                "This provider doesn't take a language; it is detected or fixed by the model",
+            ));
+            supports.set_label(&match caps {
+                Some(caps) => caps.summary(),
+                None if provider == crate::auto::AUTO_PROVIDER => {
+                    "Whatever the fastest provider supports".to_string()
                 }
-            };
+                None => "Runs on this machine, with no upload limit".to_string(),
+            });
+        });
+    }
 
-            use std::io::{Read, Write};
-            let mut reader = resp;
-            let mut buf = [0u8; 65536];
-            loop {
-                let n = reader
-                    .read(&mut buf)
-                    .map_err(|e| format!("Download read error: {e}"))?;
-                if n == 0 {
-                    break;
+    let (parent_key, runtime_key, config_key, db_key, mode_key, status_key) = (
+        parent.clone(),
+        Rc::clone(runtime),
+        Arc::clone(config),
+        Arc::clone(db),
+        mode_action.clone(),
+        status.clone(),
+    );
+    key_btn.connect_clicked(move |_| {
+        let provider = runtime_key.borrow().active_provider.clone();
+        if let Some(preset) = config::find_preset(&provider) {
+            show_api_key_dialog(
+                &parent_key,
+                &runtime_key,
+                &config_key,
+                &db_key,
+                &mode_key,
+                &status_key,
+                preset,
+            );
+        }
+    });
+
+    let (runtime_model, db_model, entry_model, status_model) = (
+        Rc::clone(runtime),
+        Arc::clone(db),
+        model_entry.clone(),
+        status.clone(),
+    );
+    let apply_model = move || {
+        let provider = runtime_model.borrow().active_provider.clone();
+        let text = entry_model.text().trim().to_string();
+        let model = match config::find_preset(&provider) {
+            Some(preset) => {
+                if let Ok(d) = db_model.lock() {
+                    let key = crate::preferences::model_setting(preset.id);
+                    let _ = if text.is_empty() {
+                        d.delete_setting(&key)
+                    } else {
+                        d.set_setting(&key, &text)
+                    };
                 }
-                file.write_all(&buf[..n])
-                    .map_err(|e| format!("File write error: {e}"))?;
-                downloaded += n as u64;
-                let _ = tx.send(DownloadMsg::Progress(downloaded, total));
+                crate::preferences::effective_model(Some(&text), preset.default_model)
+            }
+            None if provider == "custom" && !text.is_empty() => {
+                if let Ok(d) = db_model.lock() {
+                    let _ = d.set_setting("api_custom_model", &text);
+                }
+                text
             }
+            None => return,
+        };
+        show_status(&status_model, &format!("Model: {model}"));
+        runtime_model.borrow_mut().api_model = model;
+        let st = status_model.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_secs(2), move || {
+            hide_status(&st);
+        });
+    };
+    let apply_model = Rc::new(apply_model);
+    let apply_c = Rc::clone(&apply_model);
+    model_btn.connect_clicked(move |_| apply_c());
+    model_entry.connect_activate(move |_| apply_model());
+
+    // --- Audio: input device and what happens to the text ---
+    let page = preferences_page(&notebook, "Audio");
+    let mut devices = vec![(String::new(), "System default".to_string())];
+    devices.extend(
+        crate::audio::input_devices()
+            .into_iter()
+            .map(|name| (name.clone(), name)),
+    );
+    // A saved device that is unplugged right now stays selectable
+    if let Some(saved) = app
+        .lookup_action("audio-device")
+        .and_then(|a| a.state())
+        .and_then(|v| v.get::<String>())
+        && !devices.iter().any(|(name, _)| *name == saved)
+    {
+        devices.push((saved.clone(), format!("{saved} (not connected)")));
+    }
+    preferences_row(
+        &page,
+        0,
+        "Microphone",
+        &action_dropdown(app, w, "audio-device", devices),
+    );
+    preferences_row(
+        &page,
+        1,
+        "Paste after copying",
+        &action_switch(app, w, "auto-paste"),
+    );
+    preferences_row(
+        &page,
+        2,
+        "Review before copying",
+        &action_switch(app, w, "review-transcripts"),
+    );
+    preferences_row(
+        &page,
+        3,
+        "Review low confidence",
+        &action_switch(app, w, "confidence-review"),
+    );
+    preferences_row(
+        &page,
+        4,
+        "Type as I speak",
+        &action_switch(app, w, "live-dictation"),
+    );
+    preferences_row(
+        &page,
+        5,
+        "Dictation commands",
+        &action_switch(app, w, "dictation-commands"),
+    );
+    preferences_row(
+        &page,
+        6,
+        "Announce to screen reader",
+        &action_switch(app, w, "announce-transcripts"),
+    );
+    preferences_row(
+        &page,
+        7,
+        "Notify with preview",
+        &action_switch(app, w, "notify-transcripts"),
+    );
+    preferences_row(
+        &page,
+        8,
+        "Dictation tips",
+        &action_switch(app, w, "coaching-hints"),
+    );
+    preferences_row(
+        &page,
+        9,
+        "Dry run (preview only)",
+        &action_switch(app, w, "dry-run"),
+    );
+    let mut splits = vec![("off".to_string(), "Off".to_string())];
+    splits.extend(
+        crate::split::CHOICES
+            .iter()
+            .map(|m| (m.to_string(), format!("Every {m} minutes"))),
+    );
+    preferences_row(
+        &page,
+        10,
+        "Pause media while recording",
+        &action_switch(app, w, "pause-media"),
+    );
+    let players = gtk4::Entry::new();
+    players.set_hexpand(true);
+    players.set_placeholder_text(Some("All players, or e.g. spotify, vlc"));
+    if let Some(saved) = db
+        .lock()
+        .ok()
+        .and_then(|d| d.get_setting(crate::media::PLAYERS_SETTING).ok().flatten())
+    {
+        players.set_text(&saved);
+    }
+    let db_players = Arc::clone(db);
+    players.connect_changed(move |entry| save_pause_media_players(&db_players, &entry.text()));
+    preferences_row(&page, 11, "Players to pause", &players);
+    preferences_row(
+        &page,
+        12,
+        "Silence notifications while recording",
+        &action_switch(app, w, "silence-notifications"),
+    );
+    preferences_row(
+        &page,
+        13,
+        "Split long recordings",
+        &action_dropdown(app, w, "auto-split", splits),
+    );
+    let meeting_segments = crate::meeting::CHOICES
+        .iter()
+        .map(|s| (s.to_string(), format!("Every {s} seconds")))
+        .collect();
+    preferences_row(
+        &page,
+        14,
+        "Meeting segments",
+        &action_dropdown(app, w, "meeting-segment", meeting_segments),
+    );
 
-            // Rename .part → final path
-            std::fs::rename(&part_path, &model_path)
-                .map_err(|e| format!("Failed to rename model file: {e}"))?;
+    // --- Sounds: a sound and volume per event, quiet hours ---
+    let page = preferences_page(&notebook, "Sounds");
+    let sounds: Vec<(String, String)> = crate::sounds::Sound::BUILT_IN
+        .iter()
+        .map(|s| (s.as_setting(), s.label().to_string()))
+        .collect();
+    let volumes: Vec<(String, String)> = crate::sounds::VOLUME_CHOICES
+        .iter()
+        .map(|v| (v.to_string(), format!("{v}%")))
+        .collect();
+    let mut row = 0;
+    for event in SoundEvent::ALL {
+        let name = event.id().replace('_', "-");
+        let mut choices = sounds.clone();
+        // Keep a custom WAV file selectable
+        if let Some(current) = app
+            .lookup_action(&format!("sound-{name}"))
+            .and_then(|a| a.state())
+            .and_then(|v| v.get::<String>())
+            && !choices.iter().any(|(v, _)| *v == current)
+        {
+            choices.push((current, "Custom File".to_string()));
+        }
+        let controls = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+        controls.append(&action_dropdown(app, w, &format!("sound-{name}"), choices));
+        let volume = action_dropdown(app, w, &format!("sound-{name}-volume"), volumes.clone());
+        volume.set_hexpand(false);
+        controls.append(&volume);
+        preferences_row(&page, row, event.label(), &controls);
+        row += 1;
+    }
+    let mut quiet = vec![("off".to_string(), "Off".to_string())];
+    quiet.extend(
+        crate::quiet::SCHEDULE_CHOICES
+            .iter()
+            .map(|s| (s.to_string(), s.replace('-', "\u{2013}"))),
+    );
+    preferences_row(
+        &page,
+        row,
+        "Quiet hours",
+        &action_dropdown(app, w, "quiet-hours", quiet),
+    );
+    preferences_row(
+        &page,
+        row + 1,
+        "Do not disturb",
+        &action_dropdown(
+            app,
+            w,
+            "dnd",
+            strings(&[("auto", "Follow Quiet Hours"), ("on", "On"), ("off", "Off")]),
+        ),
+    );
 
-            Ok(())
-        })();
+    // --- History: what is kept and for how long ---
+    let page = preferences_page(&notebook, "History");
+    preferences_row(
+        &page,
+        0,
+        "Store in History",
+        &action_dropdown(
+            app,
+            w,
+            "history-mode",
+            strings(&[
+                ("full", "Full text"),
+                ("preview", "Preview only"),
+                ("off", "Nothing"),
+            ]),
+        ),
+    );
+    let days = |choices: &[u32]| {
+        let mut out = vec![("forever".to_string(), "Forever".to_string())];
+        out.extend(choices.iter().map(|d| {
+            let label = if *d == 1 {
+                "1 day".to_string()
+            } else {
+                format!("{d} days")
+            };
+            (d.to_string(), label)
+        }));
+        out
+    };
+    preferences_row(
+        &page,
+        1,
+        "Keep text",
+        &action_dropdown(
+            app,
+            w,
+            "text-retention",
+            days(crate::retention::TEXT_CHOICES),
+        ),
+    );
+    preferences_row(
+        &page,
+        2,
+        "Keep recordings",
+        &action_switch(app, w, "store-audio"),
+    );
+    preferences_row(
+        &page,
+        3,
+        "Keep audio",
+        &action_dropdown(
+            app,
+            w,
+            "audio-retention",
+            days(crate::retention::AUDIO_CHOICES),
+        ),
+    );
 
-        match result {
-            Ok(()) => {
-                let _ = tx.send(DownloadMsg::Done);
-            }
-            Err(e) => {
-                // Clean up partial file
-                let _ = std::fs::remove_file(&part_path);
-                let _ = tx.send(DownloadMsg::Error(e));
+    // --- Shortcuts: global hotkeys belong to the desktop; give it the commands ---
+    let page = preferences_page(&notebook, "Shortcuts");
+    let hint = gtk4::Label::new(Some(
+        "Add a custom shortcut in your desktop's keyboard settings and paste the command for it.",
+    ));
+    hint.set_wrap(true);
+    hint.set_xalign(0.0);
+    hint.add_css_class("dim-label");
+    page.attach(&hint, 0, 0, 2, 1);
+    for (i, (label, action)) in crate::preferences::SHORTCUT_ACTIONS.iter().enumerate() {
+        let command = crate::preferences::shortcut_command(action);
+        let copy = gtk4::Button::with_label("Copy Command");
+        copy.set_halign(gtk4::Align::Start);
+        copy.set_tooltip_text(Some(&command));
+        copy.connect_clicked(move |btn| match crate::input::copy_to_clipboard(&command) {
+            Ok(()) => btn.set_label("Copied"),
+            Err(e) => tracing::warn!("Copy failed: {e}"),
+        });
+        preferences_row(&page, i as i32 + 1, label, &copy);
+    }
+    if config.hid_trigger {
+        let hid = gtk4::Label::new(Some(&format!(
+            "Hardware buttons are on in {} mode; change them with HID_KEYS and HID_MODE.",
+            match config.hid_mode {
+                config::HidMode::Toggle => "toggle",
+                config::HidMode::PushToTalk => "push-to-talk",
             }
-        }
-    });
+        )));
+        hid.set_wrap(true);
+        hid.set_xalign(0.0);
+        hid.add_css_class("dim-label");
+        page.attach(
+            &hid,
+            0,
+            crate::preferences::SHORTCUT_ACTIONS.len() as i32 + 1,
+            2,
+            1,
+        );
+    }
+    if cfg!(target_os = "linux") {
+        portal_shortcut_rows(
+            app,
+            w,
+            &page,
+            crate::preferences::SHORTCUT_ACTIONS.len() as i32 + 2,
+            db,
+        );
+    }
 
-    let runtime_c = Rc::clone(runtime);
-    let action_c = action.clone();
-    let st = status.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
-        // Drain all pending messages, keep the last one
-        let mut last_msg = None;
-        while let Ok(msg) = rx.try_recv() {
-            last_msg = Some(msg);
+    // --- Keys: shortcuts inside the window, remappable ---
+    let page = preferences_page(&notebook, "Keys");
+    let hint = gtk4::Label::new(Some(
+        "Keys that work while WhisperCrabs has focus, e.g. Ctrl+H or <Control>h. \
+         Leave a field empty for the default, or type none for no key.",
+    ));
+    hint.set_wrap(true);
+    hint.set_xalign(0.0);
+    hint.add_css_class("dim-label");
+    page.attach(&hint, 0, 0, 2, 1);
+    let mut entries = Vec::new();
+    for (i, binding) in crate::keybindings::BINDINGS.iter().enumerate() {
+        let entry = gtk4::Entry::new();
+        entry.set_hexpand(true);
+        entry.set_placeholder_text(Some(binding.default));
+        if let Some(keys) = db.lock().ok().and_then(|d| {
+            d.get_setting(&crate::keybindings::setting(binding.id))
+                .ok()
+                .flatten()
+        }) {
+            entry.set_text(&keys);
         }
-
-        match last_msg {
-            Some(DownloadMsg::Progress(downloaded, total)) => {
-                let dl_mb = downloaded as f64 / (1024.0 * 1024.0);
-                if let Some(t) = total {
-                    let total_mb = t as f64 / (1024.0 * 1024.0);
-                    show_status(&st, &format!("Downloading: {dl_mb:.0} / {total_mb:.0} MB"));
-                } else {
-                    show_status(&st, &format!("Downloading: {dl_mb:.0} MB"));
-                }
-                glib::ControlFlow::Continue
-            }
-            Some(DownloadMsg::Done) => {
-                runtime_c.borrow_mut().downloading = false;
-                show_status(&st, "Loading model...");
-                // Now load the model
-                load_whisper_model(&runtime_c, &loaded_model_path, &action_c, &st);
-                glib::ControlFlow::Break
+        preferences_row(&page, i as i32 + 1, binding.label, &entry);
+        entries.push((binding, entry));
+    }
+    let keys_note = gtk4::Label::new(None);
+    keys_note.set_xalign(0.0);
+    keys_note.add_css_class("dim-label");
+    let apply = gtk4::Button::with_label("Apply");
+    apply.set_halign(gtk4::Align::Start);
+    let (db_keys, note_keys) = (Arc::clone(db), keys_note.clone());
+    apply.connect_clicked(move |_| {
+        let Ok(d) = db_keys.lock() else {
+            return;
+        };
+        for (binding, entry) in &entries {
+            let key = crate::keybindings::setting(binding.id);
+            let text = entry.text();
+            if text.trim().is_empty() {
+                let _ = d.delete_setting(&key);
+                continue;
             }
-            Some(DownloadMsg::StepLabel(_)) => glib::ControlFlow::Continue,
-            Some(DownloadMsg::Error(e)) => {
-                eprintln!("Model download failed: {e}");
-                {
-                    let mut rt = runtime_c.borrow_mut();
-                    rt.downloading = false;
-                    rt.active_service = TranscriptionService::Api;
-                    rt.active_provider = "groq".to_string();
-                    rt.api_base_url = config::API_PRESETS[0].base_url.to_string();
-                    rt.api_model = config::API_PRESETS[0].default_model.to_string();
+            match crate::keybindings::parse(&text)
+                .filter(|k| k == crate::keybindings::NONE || gtk4::accelerator_parse(k).is_some())
+            {
+                Some(keys) => {
+                    entry.set_text(&keys);
+                    let _ = d.set_setting(&key, &keys);
+                }
+                None => {
+                    note_keys.set_text(&format!("Not a key combination: {text}"));
+                    return;
                 }
-                action_c.set_state(&"groq".to_variant());
-                show_status(&st, "Download failed");
-                let st2 = st.clone();
-                glib::timeout_add_local_once(std::time::Duration::from_secs(3), move || {
-                    hide_status(&st2);
-                });
-                glib::ControlFlow::Break
             }
-            None => glib::ControlFlow::Continue,
+        }
+        drop(d);
+        note_keys.set_text("Saved");
+        if let Some(app) = gtk4::gio::Application::default() {
+            app.activate_action("reload-keybindings", None);
         }
     });
+    let row = crate::keybindings::BINDINGS.len() as i32 + 1;
+    page.attach(&apply, 1, row, 1, 1);
+    page.attach(&keys_note, 0, row + 1, 2, 1);
+
+    window.set_child(Some(&notebook));
+    window.present();
 }
 
-fn save_window_position(win: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
-    #[cfg(not(target_os = "linux"))]
-    let _ = (&win, &db);
+/// Shortcuts tab: register the global shortcuts through the desktop portal,
+/// the keys to suggest for each, and what the desktop actually bound.
+fn portal_shortcut_rows(
+    app: &gtk4::Application,
+    w: &gtk4::Window,
+    page: &gtk4::Grid,
+    first_row: i32,
+    db: &Arc<Mutex<Db>>,
+) {
+    use crate::global_shortcuts::{SHORTCUTS, Status};
 
-    #[cfg(target_os = "linux")]
-    {
-        let title = win.title().map(|t| t.to_string()).unwrap_or_default();
-        if let Ok(output) = std::process::Command::new("xdotool")
-            .args(["search", "--name", &title, "getwindowgeometry"])
-            .output()
-        {
-            let text = String::from_utf8_lossy(&output.stdout);
-            for line in text.lines() {
-                if let Some(pos) = line.strip_prefix("  Position: ")
-                    && let Some((xs, ys)) = pos.split_once(',')
-                {
-                    let x = xs.trim();
-                    let y = ys.split_whitespace().next().unwrap_or("0");
-                    if let Ok(db) = db.lock() {
-                        let _ = db.set_setting("window_x", x);
-                        let _ = db.set_setting("window_y", y);
-                    }
-                }
-            }
+    let hint = gtk4::Label::new(Some(
+        "Or let WhisperCrabs register them with the desktop (GlobalShortcuts portal). \
+         Type the keys to suggest, e.g. Ctrl+Alt+Space; your desktop confirms them.",
+    ));
+    hint.set_wrap(true);
+    hint.set_xalign(0.0);
+    hint.add_css_class("dim-label");
+    page.attach(&hint, 0, first_row, 2, 1);
+    preferences_row(
+        page,
+        first_row + 1,
+        "Register shortcuts",
+        &action_switch(app, w, "portal-shortcuts"),
+    );
+    let mut entries = Vec::new();
+    for (i, shortcut) in SHORTCUTS.iter().enumerate() {
+        let entry = gtk4::Entry::new();
+        entry.set_hexpand(true);
+        entry.set_placeholder_text(Some(shortcut.default_trigger.unwrap_or("Not set")));
+        if let Some(keys) = db.lock().ok().and_then(|d| {
+            d.get_setting(&crate::global_shortcuts::trigger_setting(shortcut.id))
+                .ok()
+                .flatten()
+        }) {
+            entry.set_text(&keys);
         }
+        preferences_row(page, first_row + 2 + i as i32, shortcut.description, &entry);
+        entries.push((shortcut.id, entry));
     }
-}
-
-fn position_window(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
-    let saved = db.lock().ok().and_then(|db| {
-        let x = db.get_setting("window_x").ok()??.parse::<i32>().ok()?;
-        let y = db.get_setting("window_y").ok()??.parse::<i32>().ok()?;
-        Some((x, y))
-    });
-
-    #[allow(unused_variables)]
-    let (x, y) = match saved {
-        Some(pos) => pos,
-        None => {
-            if let Some(display) = gdk::Display::default() {
-                let monitors = display.monitors();
-                if let Some(monitor) = monitors
-                    .item(0)
-                    .and_then(|m| m.downcast::<gdk::Monitor>().ok())
-                {
-                    let geom = monitor.geometry();
-                    (
-                        geom.x() + geom.width() - 100,
-                        geom.y() + geom.height() - 140,
-                    )
-                } else {
-                    (100, 100)
+    let bound = gtk4::Label::new(None);
+    bound.set_wrap(true);
+    bound.set_xalign(0.0);
+    bound.add_css_class("dim-label");
+    let apply = gtk4::Button::with_label("Apply");
+    apply.set_halign(gtk4::Align::Start);
+    let (db_apply, bound_apply) = (Arc::clone(db), bound.clone());
+    apply.connect_clicked(move |_| {
+        let Ok(d) = db_apply.lock() else {
+            return;
+        };
+        for (id, entry) in &entries {
+            let text = entry.text();
+            let key = crate::global_shortcuts::trigger_setting(id);
+            if text.trim().is_empty() {
+                let _ = d.delete_setting(&key);
+                continue;
+            }
+            match crate::global_shortcuts::normalize_trigger(&text) {
+                Some(keys) => {
+                    entry.set_text(&keys);
+                    let _ = d.set_setting(&key, &keys);
+                }
+                None => {
+                    bound_apply.set_text(&format!("Not a key combination: {text}"));
+                    return;
                 }
-            } else {
-                (100, 100)
             }
         }
-    };
-
-    #[cfg(target_os = "linux")]
-    {
-        let title = "WhisperCrabs";
-        let _ = std::process::Command::new("xdotool")
-            .args([
-                "search",
-                "--name",
-                title,
-                "windowmove",
-                &x.to_string(),
-                &y.to_string(),
-            ])
-            .status();
-    }
+        drop(d);
+        if let Some(app) = gtk4::gio::Application::default() {
+            app.activate_action("rebind-shortcuts", None);
+        }
+    });
+    let row = first_row + 2 + SHORTCUTS.len() as i32;
+    page.attach(&apply, 1, row, 1, 1);
+    page.attach(&bound, 0, row + 1, 2, 1);
+
+    // Follow the portal session while the window is open
+    let weak = bound.downgrade();
+    let mut last = None;
+    glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+        let Some(bound) = weak.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
+        let status = crate::global_shortcuts::status();
+        if last.as_ref() != Some(&status) {
+            bound.set_text(&match &status {
+                Status::Off => String::new(),
+                Status::Binding => "Waiting for the desktop to confirm…".to_string(),
+                Status::Bound(keys) => keys
+                    .iter()
+                    .map(|(id, keys)| {
+                        let keys = if keys.is_empty() { "no keys" } else { keys };
+                        format!("{id}: {keys}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" \u{00b7} "),
+                Status::Failed(e) => format!("Not available: {e}"),
+            });
+            last = Some(status);
+        }
+        glib::ControlFlow::Continue
+    });
 }
 
-fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
+/// List downloaded whisper models with their size and a Delete button.
+/// The model currently in use can't be deleted.
+fn show_models_dialog(
+    window: &gtk4::ApplicationWindow,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+) {
     let dialog = gtk4::Window::builder()
-        .title("WhisperCrabs History")
-        .default_width(400)
-        .default_height(300)
+        .title("Manage Models")
+        .default_width(420)
+        .default_height(320)
+        .transient_for(window)
         .build();
 
     let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
@@ -1948,48 +12718,221 @@ fn show_history_dialog(_window: &gtk4::ApplicationWindow, db: &Arc<Mutex<Db>>) {
     vbox.set_margin_start(12);
     vbox.set_margin_end(12);
 
-    let header = gtk4::Label::new(Some("Recent Transcriptions"));
+    let header = gtk4::Label::new(Some("Downloaded models"));
     header.add_css_class("heading");
     vbox.append(&header);
 
     let scroll = gtk4::ScrolledWindow::new();
     scroll.set_vexpand(true);
+    let list = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    scroll.set_child(Some(&list));
+    vbox.append(&scroll);
 
-    let list_box = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+    let total = gtk4::Label::new(None);
+    total.set_halign(gtk4::Align::Start);
+    total.set_opacity(0.6);
+    vbox.append(&total);
 
-    if let Ok(db) = db.lock()
-        && let Ok(entries) = db.recent(20)
-    {
-        if entries.is_empty() {
-            let empty = gtk4::Label::new(Some("No transcriptions yet."));
-            list_box.append(&empty);
-        } else {
-            for entry in entries {
-                let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
-                let time = gtk4::Label::new(Some(&entry.created_at));
-                time.set_halign(gtk4::Align::Start);
-                time.set_opacity(0.6);
+    refresh_models_list(&list, &total, runtime, config);
+
+    // Token for gated/private repos (WHISPER_MODEL_BASE_URL)
+    let token_btn = gtk4::Button::with_label("Hugging Face Token...");
+    token_btn.set_halign(gtk4::Align::Start);
+    let dialog_token = dialog.clone();
+    token_btn.connect_clicked(move |_| show_hf_token_dialog(&dialog_token));
+    vbox.append(&token_btn);
+
+    dialog.set_child(Some(&vbox));
+    dialog.present();
+}
+
+/// Set or remove the Hugging Face token (kept in the OS keyring).
+fn show_hf_token_dialog(parent: &gtk4::Window) {
+    let dialog = gtk4::Window::builder()
+        .title("Hugging Face Token")
+        .default_width(380)
+        .transient_for(parent)
+        .modal(true)
+        .build();
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+    vbox.set_margin_top(16);
+    vbox.set_margin_bottom(16);
+    vbox.set_margin_start(16);
+    vbox.set_margin_end(16);
+
+    let has_token = crate::secrets::get(crate::secrets::HF_TOKEN).is_some();
+    let label = gtk4::Label::new(Some(if has_token {
+        "A token is stored in your keyring. Enter a new one to replace it:"
+    } else {
+        "Token for gated or private model repos (sent to huggingface.co only):"
+    }));
+    label.set_halign(gtk4::Align::Start);
+    label.set_wrap(true);
+    vbox.append(&label);
+
+    let entry = gtk4::PasswordEntry::new();
+    entry.set_hexpand(true);
+    vbox.append(&entry);
+
+    let error = gtk4::Label::new(None);
+    error.set_halign(gtk4::Align::Start);
+    error.add_css_class("error");
+    error.set_visible(false);
+    vbox.append(&error);
+
+    let btn_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    btn_box.set_halign(gtk4::Align::End);
+    let remove_btn = gtk4::Button::with_label("Remove");
+    remove_btn.set_visible(has_token);
+    let cancel_btn = gtk4::Button::with_label("Cancel");
+    let save_btn = gtk4::Button::with_label("Save");
+    btn_box.append(&remove_btn);
+    btn_box.append(&cancel_btn);
+    btn_box.append(&save_btn);
+    vbox.append(&btn_box);
+    dialog.set_child(Some(&vbox));
 
-                let text = gtk4::Label::new(Some(&entry.text));
-                text.set_halign(gtk4::Align::Start);
-                text.set_wrap(true);
-                text.set_selectable(true);
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
 
-                row.append(&time);
-                row.append(&text);
+    let (dialog_remove, error_remove) = (dialog.clone(), error.clone());
+    remove_btn.connect_clicked(
+        move |_| match crate::secrets::delete(crate::secrets::HF_TOKEN) {
+            Ok(()) => dialog_remove.close(),
+            Err(e) => {
+                error_remove.set_label(&e);
+                error_remove.set_visible(true);
+            }
+        },
+    );
 
-                let sep = gtk4::Separator::new(gtk4::Orientation::Horizontal);
-                list_box.append(&row);
-                list_box.append(&sep);
+    let dialog_save = dialog.clone();
+    save_btn.connect_clicked(move |_| {
+        let token = entry.text().trim().to_string();
+        if token.is_empty() {
+            return;
+        }
+        match crate::secrets::set(crate::secrets::HF_TOKEN, &token) {
+            Ok(()) => dialog_save.close(),
+            Err(e) => {
+                error.set_label(&e);
+                error.set_visible(true);
             }
         }
+    });
+
+    dialog.present();
+}
+
+fn refresh_models_list(
+    list: &gtk4::Box,
+    total: &gtk4::Label,
+    runtime: &Rc<RefCell<RuntimeState>>,
+    config: &Arc<Config>,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
     }
 
-    scroll.set_child(Some(&list_box));
-    vbox.append(&scroll);
+    // Models on an external drive, plus any fallback copies on this machine
+    let mut dirs = vec![config.models_dir.as_path()];
+    if config.local_models_dir != config.models_dir {
+        dirs.push(config.local_models_dir.as_path());
+    }
 
-    dialog.set_child(Some(&vbox));
-    dialog.present();
+    // (provider id, label, size, path) for whisper files and Vosk directories
+    let mut models: Vec<(&str, String, u64, std::path::PathBuf)> = Vec::new();
+    for dir in &dirs {
+        models.extend(config::downloaded_models(dir).into_iter().map(|(m, size)| {
+            let kind = if m.multilingual {
+                "Multilingual"
+            } else {
+                "English"
+            };
+            (
+                m.id,
+                format!("{} ({kind})", m.label),
+                size,
+                dir.join(m.file_name),
+            )
+        }));
+        models.extend(
+            config::downloaded_vosk_models(dir)
+                .into_iter()
+                .map(|(m, size)| {
+                    (
+                        m.id,
+                        format!("{} (Vosk)", m.label),
+                        size,
+                        config::vosk_model_dir(dir, m),
+                    )
+                }),
+        );
+    }
+    let bytes: u64 = models.iter().map(|(_, _, size, _)| size).sum();
+    let location = if !config::models_dir_available(&config.models_dir) {
+        format!("\u{2014} {} is not available", config.models_dir.display())
+    } else if dirs.len() > 1 {
+        format!(
+            "in {} and {}",
+            config.models_dir.display(),
+            config.local_models_dir.display()
+        )
+    } else {
+        format!("in {}", config.models_dir.display())
+    };
+    total.set_label(&format!(
+        "{} model(s), {} {location}",
+        models.len(),
+        config::format_size(bytes),
+    ));
+
+    if models.is_empty() {
+        let empty = gtk4::Label::new(Some("No models downloaded."));
+        empty.set_halign(gtk4::Align::Start);
+        list.append(&empty);
+        return;
+    }
+
+    let active = {
+        let rt = runtime.borrow();
+        (rt.active_service == TranscriptionService::Local).then(|| rt.active_provider.clone())
+    };
+    for (id, label, size, path) in models {
+        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+        let label = gtk4::Label::new(Some(&format!(
+            "{label} \u{2014} {}",
+            config::format_size(size)
+        )));
+        label.set_halign(gtk4::Align::Start);
+        label.set_hexpand(true);
+        label.set_tooltip_text(path.file_name().map(|n| n.to_string_lossy()).as_deref());
+        row.append(&label);
+
+        let delete = gtk4::Button::with_label("Delete");
+        if active.as_deref() == Some(id) {
+            delete.set_label("In use");
+            delete.set_sensitive(false);
+        } else {
+            delete.add_css_class("destructive-action");
+        }
+        let (list_c, total_c) = (list.clone(), total.clone());
+        let (runtime_c, config_c) = (Rc::clone(runtime), Arc::clone(config));
+        delete.connect_clicked(move |_| {
+            let result = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to delete model {}: {e}", path.display());
+            }
+            refresh_models_list(&list_c, &total_c, &runtime_c, &config_c);
+        });
+        row.append(&delete);
+        list.append(&row);
+    }
 }
 
 // ── TTS helpers ─────────────────────────────────────────────────────────────
@@ -2115,6 +13058,12 @@ fn download_tts_models(
     let config_url = voice.config_url();
 
     std::thread::spawn(move || {
+        // Local-only mode: pip and voice downloads both need the network
+        if let Err(e) = crate::policy::ensure_network_allowed(&onnx_url) {
+            let _ = tx.send(DownloadMsg::Error(e));
+            return;
+        }
+
         // Step 1: Set up venv (if needed)
         if !skip_venv {
             let run_cmd = |cmd: &str, args: &[&str]| -> Result<(), String> {
@@ -2237,6 +13186,9 @@ fn download_tts_models(
                 DownloadMsg::Error(e) => {
                     terminal = Some(Err(e));
                 }
+                DownloadMsg::Cancelled => {
+                    terminal = Some(Err("cancelled".into()));
+                }
             }
         }
 
@@ -2284,7 +13236,7 @@ fn download_tts_models(
                         hide_status(&st);
                     }
                     Err(e) => {
-                        eprintln!("TTS load failed: {e}");
+                        tracing::warn!("TTS load failed: {e}");
                         runtime_c.borrow_mut().tts_downloading = false;
                         show_status(&st, "TTS load failed");
                         let st2 = st.clone();
@@ -2298,7 +13250,7 @@ fn download_tts_models(
             }
             Some(Err(e)) => {
                 dialog_ref.close();
-                eprintln!("TTS download failed: {e}");
+                tracing::warn!("TTS download failed: {e}");
                 runtime_c.borrow_mut().tts_downloading = false;
                 show_status(&st, "TTS download failed");
                 let st2 = st.clone();
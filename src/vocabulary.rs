@@ -0,0 +1,84 @@
+//! Custom vocabulary — names, jargon and acronyms the models should know.
+//!
+//! The list is edited under **Vocabulary...** in the right-click menu, one
+//! term per line, and stored in the `vocabulary` setting. Each dictation
+//! hands it to the backend in the form it takes: the `prompt` of API
+//! providers whose capabilities include one (after `API_PROMPT`), the
+//! initial prompt of local whisper models, and keyword boosts for providers
+//! that take `keywords` (see [`crate::capabilities`]). Vosk models have a
+//! fixed vocabulary and ignore it.
+
+use crate::db::Db;
+
+pub const SETTING: &str = "vocabulary";
+
+/// Most terms kept; whisper reads only the last 224 prompt tokens anyway.
+pub const MAX_TERMS: usize = 100;
+
+/// Longest prompt built from the list, in bytes.
+pub const MAX_PROMPT_LEN: usize = 800;
+
+/// The terms of `text`: one per line (commas separate too), trimmed. Repeats
+/// that differ only in case and terms with control characters are dropped.
+pub fn parse(text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in text.split(['\n', ',']).map(str::trim) {
+        if term.is_empty()
+            || term.contains(char::is_control)
+            || terms.iter().any(|t| t.eq_ignore_ascii_case(term))
+        {
+            continue;
+        }
+        terms.push(term.to_string());
+        if terms.len() == MAX_TERMS {
+            break;
+        }
+    }
+    terms
+}
+
+/// The saved terms.
+pub fn stored(d: &Db) -> Vec<String> {
+    d.get_setting(SETTING)
+        .ok()
+        .flatten()
+        .map(|text| parse(&text))
+        .unwrap_or_default()
+}
+
+/// Save `terms`, one per line; an empty list clears the setting.
+pub fn save(d: &Db, terms: &[String]) -> rusqlite::Result<()> {
+    if terms.is_empty() {
+        d.delete_setting(SETTING)
+    } else {
+        d.set_setting(SETTING, &terms.join("\n"))
+    }
+}
+
+/// The prompt steering a model towards `terms`, after `base` (the
+/// configured `API_PROMPT`) when there is one. Whisper continues a prompt
+/// like earlier speech, so the terms read as a comma-separated sentence.
+/// Terms that don't fit in [`MAX_PROMPT_LEN`] are left out.
+pub fn prompt(base: Option<&str>, terms: &[String]) -> Option<String> {
+    let base = base.map(str::trim).filter(|b| !b.is_empty());
+    let mut list = String::new();
+    let budget = MAX_PROMPT_LEN.saturating_sub(base.map_or(0, |b| b.len() + 1));
+    for term in terms {
+        if list.len() + term.len() + 3 > budget {
+            break;
+        }
+        if !list.is_empty() {
+            list.push_str(", ");
+        }
+        list.push_str(term);
+    }
+    if !list.is_empty() {
+        list.push('.');
+    }
+    match (base, list.is_empty()) {
+        (Some(base), false) => Some(format!("{base} {list}")),
+        (Some(base), true) => Some(base.to_string()),
+        (None, false) => Some(list),
+        (None, true) => None,
+    }
+}
@@ -0,0 +1,166 @@
+//! Vosk — a lightweight local engine for near-instant offline transcription.
+//!
+//! Vosk models are ~40–50 MB directories (unpacked from a zip archive) and
+//! decode much faster than whisper, at lower accuracy — a good fit for quick
+//! commands and short notes. Each model recognizes a single language.
+//!
+//! Vosk links against the native `libvosk` library, so it is only compiled
+//! with the `vosk` cargo feature; without it the engine reports itself as
+//! unavailable and the Vosk menu section is hidden.
+
+use crate::local_stt::Transcript;
+use std::path::Path;
+
+#[cfg(feature = "vosk")]
+mod imp {
+    use crate::local_stt::{Segment, Transcript, Word};
+    use std::io::Cursor;
+    use std::path::Path;
+    use vosk::{CompleteResult, DecodingState, Model, Recognizer};
+
+    /// Samples fed to the recognizer per call (~0.25 s at 16 kHz).
+    const CHUNK_SAMPLES: usize = 4000;
+
+    pub struct VoskEngine {
+        model: Model,
+    }
+
+    impl VoskEngine {
+        pub fn new(model_dir: &Path) -> Result<Self, String> {
+            let path = model_dir.to_str().ok_or("Model path is not valid UTF-8")?;
+            let model = Model::new(path).ok_or("Failed to load Vosk model")?;
+            Ok(Self { model })
+        }
+
+        pub fn transcribe(
+            &self,
+            wav_data: &[u8],
+            sample_rate: u32,
+            on_segment: &mut dyn FnMut(String),
+        ) -> Result<Transcript, String> {
+            let mut reader = hound::WavReader::new(Cursor::new(wav_data))
+                .map_err(|e| format!("WAV parse error: {e}"))?;
+            let samples: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap_or(0)).collect();
+            if samples.is_empty() {
+                return Err("No audio samples in WAV".into());
+            }
+
+            let mut rec = Recognizer::new(&self.model, sample_rate as f32)
+                .ok_or("Failed to create Vosk recognizer")?;
+            rec.set_words(true);
+
+            let mut transcript = Transcript::default();
+            for chunk in samples.chunks(CHUNK_SAMPLES) {
+                if let Ok(DecodingState::Finalized) = rec.accept_waveform(chunk) {
+                    let text = push_result(&mut transcript, rec.result());
+                    if !text.is_empty() {
+                        on_segment(format!(" {text}"));
+                    }
+                }
+            }
+            push_result(&mut transcript, rec.final_result());
+            transcript.text = transcript.text.trim().to_string();
+            Ok(transcript)
+        }
+    }
+
+    /// Append one utterance to the transcript; returns its text.
+    fn push_result(transcript: &mut Transcript, result: CompleteResult) -> String {
+        let Some(single) = result.single() else {
+            return String::new();
+        };
+        transcript.text.push(' ');
+        transcript.text.push_str(single.text);
+        transcript.words.extend(single.result.iter().map(|w| Word {
+            text: w.word.to_string(),
+            start_ms: (w.start * 1000.0) as i64,
+            end_ms: (w.end * 1000.0) as i64,
+        }));
+        // Each utterance is a segment spanning its words
+        if let (Some(first), Some(last)) = (single.result.first(), single.result.last()) {
+            let confs: Vec<f32> = single.result.iter().map(|w| w.conf).collect();
+            transcript.segments.push(Segment {
+                start_ms: (first.start * 1000.0) as i64,
+                end_ms: (last.end * 1000.0) as i64,
+                text: single.text.to_string(),
+                confidence: crate::local_stt::mean_confidence(&confs),
+            });
+        }
+        single.text.to_string()
+    }
+
+    /// Unpack a downloaded model archive so that `model_dir` holds the model.
+    pub fn install_archive(archive: &Path, model_dir: &Path) -> Result<(), String> {
+        let parent = model_dir.parent().ok_or("Invalid model directory")?;
+        let staging = parent.join(".unpacking");
+        let _ = std::fs::remove_dir_all(&staging);
+        std::fs::create_dir_all(&staging).map_err(|e| format!("Create dir: {e}"))?;
+
+        let file = std::fs::File::open(archive).map_err(|e| format!("Open archive: {e}"))?;
+        let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid archive: {e}"))?;
+        // `extract` rejects entries that would escape the staging directory
+        zip.extract(&staging)
+            .map_err(|e| format!("Unpack archive: {e}"))?;
+
+        // Archives hold a single top-level directory named after the model
+        let name = model_dir.file_name().ok_or("Invalid model directory")?;
+        let unpacked = if staging.join(name).is_dir() {
+            staging.join(name)
+        } else {
+            staging.clone()
+        };
+        let _ = std::fs::remove_dir_all(model_dir);
+        std::fs::rename(&unpacked, model_dir).map_err(|e| format!("Install model: {e}"))?;
+        let _ = std::fs::remove_dir_all(&staging);
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "vosk"))]
+mod imp {
+    use crate::local_stt::Transcript;
+    use std::path::Path;
+
+    const UNAVAILABLE: &str = "This build has no Vosk support (enable the `vosk` feature)";
+
+    pub struct VoskEngine;
+
+    impl VoskEngine {
+        pub fn new(_model_dir: &Path) -> Result<Self, String> {
+            Err(UNAVAILABLE.into())
+        }
+
+        pub fn transcribe(
+            &self,
+            _wav_data: &[u8],
+            _sample_rate: u32,
+            _on_segment: &mut dyn FnMut(String),
+        ) -> Result<Transcript, String> {
+            Err(UNAVAILABLE.into())
+        }
+    }
+
+    pub fn install_archive(_archive: &Path, _model_dir: &Path) -> Result<(), String> {
+        Err(UNAVAILABLE.into())
+    }
+}
+
+pub use imp::VoskEngine;
+
+/// Whether this build includes the Vosk engine.
+pub const AVAILABLE: bool = cfg!(feature = "vosk");
+
+/// Unpack a downloaded Vosk model archive into `model_dir`.
+pub fn install_archive(archive: &Path, model_dir: &Path) -> Result<(), String> {
+    imp::install_archive(archive, model_dir)
+}
+
+/// Transcribe a WAV clip; `on_segment` receives each finalized utterance.
+pub fn transcribe(
+    engine: &VoskEngine,
+    wav_data: &[u8],
+    sample_rate: u32,
+    mut on_segment: impl FnMut(String),
+) -> Result<Transcript, String> {
+    engine.transcribe(wav_data, sample_rate, &mut on_segment)
+}
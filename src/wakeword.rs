@@ -0,0 +1,188 @@
+//! Wake-word activation — say "hey whisper" to start a recording.
+//!
+//! An optional background listener keeps a low-rate input stream open and
+//! runs it through a [rustpotter](https://github.com/GiviMAD/rustpotter)
+//! detector. A detection starts a recording exactly like the `record` D-Bus
+//! action. The listener is off by default; while it runs the main window
+//! shows a `WAKE WORD` badge.
+//!
+//! rustpotter matches against a wake-word file (`.rpw`) recorded or trained
+//! with the rustpotter CLI; the default location is
+//! `~/.local/share/whispercrabs/wakeword/hey-whisper.rpw` (override with
+//! `WAKE_WORD_MODEL`).
+
+use crate::activation::{Activation, ActivationSource};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rustpotter::{Rustpotter, RustpotterConfig, SampleFormat};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Phrase shown in the UI; the detector itself matches whatever the model file holds.
+pub const WAKE_PHRASE: &str = "hey whisper";
+
+/// File name of the default wake-word model in the data directory.
+pub const DEFAULT_MODEL_FILE: &str = "hey-whisper.rpw";
+
+/// Detections closer together than this are one utterance.
+pub const COOLDOWN: Duration = Duration::from_secs(2);
+
+/// Whether a detection at `now` should trigger, given the previous trigger.
+pub fn should_trigger(last: Option<Instant>, now: Instant) -> bool {
+    last.is_none_or(|t| now.duration_since(t) >= COOLDOWN)
+}
+
+/// A running wake-word listener. Dropping it closes the audio stream.
+pub struct WakeWordListener {
+    stop: Arc<AtomicBool>,
+}
+
+impl WakeWordListener {
+    /// Open the default input device and start listening for the wake word
+    /// in `model`. Each detection is delivered on the returned channel as
+    /// [`Activation::Start`]; it disconnects once the listener stops.
+    pub fn start(model: &Path) -> Result<(Self, mpsc::Receiver<Activation>), String> {
+        if !model.is_file() {
+            return Err(format!("Wake word model not found: {}", model.display()));
+        }
+        let model = model
+            .to_str()
+            .ok_or("Wake word model path is not valid UTF-8")?
+            .to_string();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+
+        // cpal streams aren't Send on every platform: build and own it on the listener thread
+        let stop_c = Arc::clone(&stop);
+        std::thread::spawn(move || {
+            let stream = match open_stream(&model, tx) {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    stream
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            while !stop_c.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            drop(stream);
+            dbg_log!("[WAKE] listener stopped");
+        });
+
+        ready_rx
+            .recv()
+            .map_err(|_| "Wake word listener failed to start".to_string())??;
+        dbg_log!("[WAKE] listening for \"{WAKE_PHRASE}\"");
+        Ok((Self { stop }, rx))
+    }
+}
+
+impl Drop for WakeWordListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The wake word as an activation source: a detection starts a recording.
+pub struct WakeWordSource {
+    model: PathBuf,
+    listener: Option<WakeWordListener>,
+}
+
+impl WakeWordSource {
+    pub fn new(model: &Path) -> Self {
+        Self {
+            model: model.to_path_buf(),
+            listener: None,
+        }
+    }
+}
+
+impl ActivationSource for WakeWordSource {
+    fn name(&self) -> &'static str {
+        "wake word"
+    }
+
+    fn start(&mut self) -> Result<mpsc::Receiver<Activation>, String> {
+        let (listener, rx) = WakeWordListener::start(&self.model)?;
+        // Replacing a running listener drops it, which closes its audio stream
+        self.listener = Some(listener);
+        Ok(rx)
+    }
+}
+
+fn open_stream(model: &str, tx: mpsc::Sender<Activation>) -> Result<cpal::Stream, String> {
+    let host = cpal::default_host();
+    let device = host
+        .default_input_device()
+        .ok_or("No input device available")?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("No input config: {e}"))?;
+
+    let mut rp_config = RustpotterConfig::default();
+    rp_config.fmt.sample_rate = config.sample_rate().0 as usize;
+    rp_config.fmt.channels = config.channels();
+    rp_config.fmt.sample_format = SampleFormat::F32;
+    let mut detector = Rustpotter::new(&rp_config)?;
+    detector.add_wakeword_from_file("wake", model)?;
+    let frame = detector.get_samples_per_frame();
+
+    let mut buffer: Vec<f32> = Vec::with_capacity(frame * 2);
+    let mut last: Option<Instant> = None;
+    let mut feed = move |samples: &mut dyn Iterator<Item = f32>| {
+        buffer.extend(samples);
+        while buffer.len() >= frame {
+            let chunk: Vec<f32> = buffer.drain(..frame).collect();
+            if let Some(detection) = detector.process_samples(chunk) {
+                let now = Instant::now();
+                if should_trigger(last, now) {
+                    dbg_log!("[WAKE] detected (score {:.2})", detection.score);
+                    last = Some(now);
+                    let _ = tx.send(Activation::Start);
+                }
+            }
+        }
+    };
+
+    let err_fn = |err| tracing::error!("Wake word stream error: {err}");
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config.into(),
+            move |data: &[f32], _: &_| feed(&mut data.iter().copied()),
+            err_fn,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[i16], _: &_| {
+                feed(&mut data.iter().map(|&s| s as f32 / i16::MAX as f32));
+            },
+            err_fn,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[u16], _: &_| {
+                feed(
+                    &mut data
+                        .iter()
+                        .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0),
+                );
+            },
+            err_fn,
+            None,
+        ),
+        fmt => return Err(format!("Unsupported sample format: {fmt:?}")),
+    }
+    .map_err(|e| format!("Failed to build stream: {e}"))?;
+
+    stream.play().map_err(|e| format!("Failed to play: {e}"))?;
+    Ok(stream)
+}
@@ -0,0 +1,99 @@
+//! Reading and setting window positions on X11, without external tools.
+//!
+//! GTK 4 neither reports nor sets where a toplevel is. These calls speak
+//! the X protocol directly (x11rb, no C library): a window is found by
+//! title among this process's top-level windows (`_NET_CLIENT_LIST`,
+//! `_NET_WM_PID`), its position read with `TranslateCoordinates` and set
+//! with `ConfigureWindow`. They fail on Wayland, where GTK windows aren't X
+//! windows; callers skip them there.
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConfigureWindowAux, ConnectionExt, Window};
+use x11rb::rust_connection::RustConnection;
+
+use crate::stacking::Rect;
+
+/// Where this process's window titled `title` is on the screen, in device
+/// pixels.
+pub fn window_geometry(title: &str) -> Result<Rect, String> {
+    let (conn, root) = connect()?;
+    let window = find_window(&conn, root, title)?;
+    let size = conn
+        .get_geometry(window)
+        .map_err(err)?
+        .reply()
+        .map_err(err)?;
+    let origin = conn
+        .translate_coordinates(window, root, 0, 0)
+        .map_err(err)?
+        .reply()
+        .map_err(err)?;
+    Ok((
+        origin.dst_x.into(),
+        origin.dst_y.into(),
+        size.width.into(),
+        size.height.into(),
+    ))
+}
+
+/// Move this process's window titled `title` to `(x, y)`.
+pub fn move_window(title: &str, x: i32, y: i32) -> Result<(), String> {
+    let (conn, root) = connect()?;
+    let window = find_window(&conn, root, title)?;
+    conn.configure_window(window, &ConfigureWindowAux::new().x(x).y(y))
+        .map_err(err)?;
+    conn.flush().map_err(err)?;
+    Ok(())
+}
+
+fn connect() -> Result<(RustConnection, Window), String> {
+    let (conn, screen) = x11rb::connect(None).map_err(err)?;
+    let root = conn.setup().roots[screen].root;
+    Ok((conn, root))
+}
+
+fn find_window(conn: &RustConnection, root: Window, title: &str) -> Result<Window, String> {
+    let atom = |name: &str| -> Result<u32, String> {
+        Ok(conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(err)?
+            .reply()
+            .map_err(err)?
+            .atom)
+    };
+    let (client_list, wm_pid, wm_name, utf8) = (
+        atom("_NET_CLIENT_LIST")?,
+        atom("_NET_WM_PID")?,
+        atom("_NET_WM_NAME")?,
+        atom("UTF8_STRING")?,
+    );
+    let windows: Vec<Window> = conn
+        .get_property(false, root, client_list, AtomEnum::WINDOW, 0, u32::MAX)
+        .map_err(err)?
+        .reply()
+        .map_err(err)?
+        .value32()
+        .map(|values| values.collect())
+        .unwrap_or_default();
+    let pid = std::process::id();
+    for window in windows {
+        let property = |name, kind| {
+            conn.get_property(false, window, name, kind, 0, 1024)
+                .ok()?
+                .reply()
+                .ok()
+        };
+        let ours = property(wm_pid, AtomEnum::CARDINAL.into())
+            .and_then(|p| p.value32()?.next())
+            .is_some_and(|p| p == pid);
+        let named = property(wm_name, utf8).is_some_and(|p| p.value == title.as_bytes());
+        if ours && named {
+            return Ok(window);
+        }
+    }
+    Err(format!("no window titled \"{title}\""))
+}
+
+fn err(e: impl std::fmt::Display) -> String {
+    format!("X11: {e}")
+}